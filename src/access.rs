@@ -0,0 +1,133 @@
+use crate::audit_log::AdminAudit;
+use crate::event::{DeFiEvent, EventEmitter, RoleChangedData, ACCESS_TOPIC};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Val};
+
+/// Roles shared by every contract in the platform. `Owner` manages the other roles and
+/// passes every role check, so a contract with a single admin behaves as before.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Owner,
+    Operator,
+    Pauser,
+    RateFeeder,
+    Minter,
+    /// Marks addresses as KYC-verified in the compliance registry
+    Attestor,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AccessDataKey {
+    Role(Role, Address),
+}
+
+// Role assignments are stored in the calling contract's own instance storage
+pub struct AccessControl;
+
+impl AccessControl {
+    /// Grants `Owner` to the contract's initial admin
+    pub fn init_owner(env: &Env, owner: &Address) {
+        Self::set_role(env, Role::Owner, owner, true);
+    }
+
+    pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+        Self::holds(env, role, account) || Self::holds(env, Role::Owner, account)
+    }
+
+    /// Requires `account`'s authorization and that it holds `role`
+    pub fn require_role(env: &Env, role: Role, account: &Address) {
+        account.require_auth();
+        if !Self::has_role(env, role, account) {
+            panic!("Missing required role");
+        }
+    }
+
+    /// `require_role` for an admin action: the call is also appended to the
+    /// contract's audit log, with a hash of `params`
+    pub fn require_audited_role<P>(
+        env: &Env,
+        role: Role,
+        account: &Address,
+        function: &str,
+        params: P,
+    ) where
+        P: IntoVal<Env, Val>,
+    {
+        Self::require_role(env, role, account);
+        AdminAudit::record(env, account, function, params);
+    }
+
+    pub fn grant_role(env: &Env, owner: &Address, role: Role, account: &Address) {
+        Self::require_audited_role(
+            env,
+            Role::Owner,
+            owner,
+            "grant_role",
+            (role, account.clone()),
+        );
+        Self::set_role(env, role, account, true);
+
+        let event = DeFiEvent::RoleGranted(RoleChangedData {
+            role,
+            account: account.clone(),
+            changed_by: owner.clone(),
+            changed_at: env.ledger().timestamp(),
+        });
+        EventEmitter::emit_event(env, ACCESS_TOPIC, event);
+    }
+
+    pub fn revoke_role(env: &Env, owner: &Address, role: Role, account: &Address) {
+        Self::require_audited_role(
+            env,
+            Role::Owner,
+            owner,
+            "revoke_role",
+            (role, account.clone()),
+        );
+        Self::remove_role(env, owner, role, account);
+    }
+
+    /// Lets an account give up a role it holds, e.g. a compromised operator key
+    pub fn renounce_role(env: &Env, account: &Address, role: Role) {
+        account.require_auth();
+        if !Self::holds(env, role, account) {
+            panic!("Role not held");
+        }
+        Self::remove_role(env, account, role, account);
+    }
+
+    /// Moves `Owner` from `from` to `to`, used when a contract's admin is transferred
+    pub fn transfer_ownership(env: &Env, from: &Address, to: &Address) {
+        Self::set_role(env, Role::Owner, from, false);
+        Self::set_role(env, Role::Owner, to, true);
+    }
+
+    fn remove_role(env: &Env, by: &Address, role: Role, account: &Address) {
+        Self::set_role(env, role, account, false);
+
+        let event = DeFiEvent::RoleRevoked(RoleChangedData {
+            role,
+            account: account.clone(),
+            changed_by: by.clone(),
+            changed_at: env.ledger().timestamp(),
+        });
+        EventEmitter::emit_event(env, ACCESS_TOPIC, event);
+    }
+
+    fn holds(env: &Env, role: Role, account: &Address) -> bool {
+        env.storage()
+            .instance()
+            .get(&AccessDataKey::Role(role, account.clone()))
+            .unwrap_or(false)
+    }
+
+    fn set_role(env: &Env, role: Role, account: &Address, granted: bool) {
+        let key = AccessDataKey::Role(role, account.clone());
+        if granted {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+}
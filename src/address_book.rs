@@ -0,0 +1,198 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::storage;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+
+/// Longest activation delay the owner can set: 7 days
+pub const MAX_ACTIVATION_DELAY: u64 = 7 * 24 * 60 * 60;
+
+const ADDRESS_BOOK_TOPIC: Symbol = symbol_short!("addrbook");
+
+/// An approved beneficiary in a user's address book
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Beneficiary {
+    pub label: Symbol,
+    pub added_at: u64,
+    /// When payments to the beneficiary are first allowed
+    pub active_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AddressBookDataKey {
+    Admin,
+    ActivationDelay,
+    Beneficiary(Address, Address),
+    /// Time a user's whitelist-only mode ends; `u64::MAX` while it is on
+    WhitelistOnlyUntil(Address),
+}
+
+#[contract]
+pub struct AddressBookContract;
+
+/// Per-user address books shared by every contract that points at this
+/// registry. A user who turns on whitelist-only mode can only pay addresses in
+/// their book. New entries, and turning the mode off, take effect only after
+/// the activation delay, so a taken-over account cannot drain funds at once.
+#[contractimpl]
+impl AddressBookContract {
+    pub fn initialize_address_book(env: Env, admin: Address, activation_delay: u64) {
+        if env.storage().instance().has(&AddressBookDataKey::Admin) {
+            panic!("Address book already initialized");
+        }
+        admin.require_auth();
+        Self::check_delay(activation_delay);
+        env.storage()
+            .instance()
+            .set(&AddressBookDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&AddressBookDataKey::ActivationDelay, &activation_delay);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Change the delay before new entries take effect (owner only)
+    pub fn set_activation_delay(env: Env, caller: Address, activation_delay: u64) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_activation_delay",
+            activation_delay,
+        );
+        Self::check_delay(activation_delay);
+        env.storage()
+            .instance()
+            .set(&AddressBookDataKey::ActivationDelay, &activation_delay);
+    }
+
+    pub fn get_activation_delay(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&AddressBookDataKey::ActivationDelay)
+            .unwrap_or(0)
+    }
+
+    /// Add or relabel a beneficiary (user auth). A new beneficiary can be
+    /// paid once the activation delay has passed.
+    pub fn add_beneficiary(
+        env: Env,
+        user: Address,
+        beneficiary: Address,
+        label: Symbol,
+    ) -> Beneficiary {
+        user.require_auth();
+        if user == beneficiary {
+            panic!("Cannot add yourself as a beneficiary");
+        }
+
+        let key = AddressBookDataKey::Beneficiary(user.clone(), beneficiary.clone());
+        let now = env.ledger().timestamp();
+        let entry = match storage::read::<_, Beneficiary>(&env, &key) {
+            Some(existing) => Beneficiary { label, ..existing },
+            None => Beneficiary {
+                label,
+                added_at: now,
+                active_at: now + Self::get_activation_delay(env.clone()),
+            },
+        };
+        storage::write(&env, &key, &entry);
+        env.events().publish(
+            (ADDRESS_BOOK_TOPIC, symbol_short!("added"), user),
+            (beneficiary, entry.active_at),
+        );
+        entry
+    }
+
+    /// Drop a beneficiary from the user's book (user auth); effective at once
+    pub fn remove_beneficiary(env: Env, user: Address, beneficiary: Address) {
+        user.require_auth();
+        let key = AddressBookDataKey::Beneficiary(user.clone(), beneficiary.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Beneficiary not found");
+        }
+        env.storage().persistent().remove(&key);
+        env.events().publish(
+            (ADDRESS_BOOK_TOPIC, symbol_short!("removed"), user),
+            beneficiary,
+        );
+    }
+
+    pub fn get_beneficiary(env: Env, user: Address, beneficiary: Address) -> Option<Beneficiary> {
+        storage::read(&env, &AddressBookDataKey::Beneficiary(user, beneficiary))
+    }
+
+    /// Turn whitelist-only mode on, at once, or off, after the activation
+    /// delay (user auth)
+    pub fn set_whitelist_only(env: Env, user: Address, enabled: bool) {
+        user.require_auth();
+        let key = AddressBookDataKey::WhitelistOnlyUntil(user.clone());
+        if enabled {
+            storage::write(&env, &key, &u64::MAX);
+        } else if Self::is_whitelist_only(env.clone(), user.clone()) {
+            let until = env.ledger().timestamp() + Self::get_activation_delay(env.clone());
+            storage::write(&env, &key, &until);
+        }
+        env.events().publish(
+            (ADDRESS_BOOK_TOPIC, symbol_short!("wl_only"), user),
+            enabled,
+        );
+    }
+
+    pub fn is_whitelist_only(env: Env, user: Address) -> bool {
+        storage::read::<_, u64>(&env, &AddressBookDataKey::WhitelistOnlyUntil(user))
+            .is_some_and(|until| env.ledger().timestamp() < until)
+    }
+
+    /// Whether `user` may pay `target` under their whitelist settings
+    pub fn is_approved(env: Env, user: Address, target: Address) -> bool {
+        if user == target || !Self::is_whitelist_only(env.clone(), user.clone()) {
+            return true;
+        }
+        Self::get_beneficiary(env.clone(), user, target)
+            .is_some_and(|entry| env.ledger().timestamp() >= entry.active_at)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_address_book_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_address_book_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_address_book_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_address_book_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_address_book_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn check_delay(activation_delay: u64) {
+        if activation_delay > MAX_ACTIVATION_DELAY {
+            panic!("Activation delay too long");
+        }
+    }
+}
+
+// Enforcement hook for contracts holding an address book address
+pub struct BeneficiaryCheck;
+
+impl BeneficiaryCheck {
+    /// Whether `user` may pay `target` according to the book at `registry`
+    pub fn is_approved(env: &Env, registry: &Address, user: &Address, target: &Address) -> bool {
+        AddressBookContractClient::new(env, registry).is_approved(user, target)
+    }
+}
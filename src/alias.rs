@@ -0,0 +1,143 @@
+use crate::errors::AppError;
+use soroban_sdk::xdr::FromXdr;
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env, String};
+
+const MAX_ALIAS_LEN: usize = 256;
+
+/// Kinds of human-readable identifiers that can stand in for a wallet address
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AliasKind {
+    Email,
+    /// Phone number in E.164 form, e.g. +2348012345678
+    Phone,
+    /// Lowercase letters, digits and underscores, 3 to 32 characters
+    Username,
+}
+
+/// An alias of a given kind, e.g. `{ kind: Phone, value: "+2348012345678" }`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alias {
+    pub kind: AliasKind,
+    pub value: String,
+}
+
+// Email hashes carry no namespace prefix so addresses already derived from
+// emails stay the same
+fn namespace(kind: AliasKind) -> &'static [u8] {
+    match kind {
+        AliasKind::Email => b"",
+        AliasKind::Phone => b"phone:",
+        AliasKind::Username => b"username:",
+    }
+}
+
+fn validate(kind: AliasKind, value: &[u8]) -> Result<(), AppError> {
+    let valid = match kind {
+        AliasKind::Email => {
+            let at = value.iter().position(|c| *c == b'@');
+            match at {
+                Some(i) => {
+                    i > 0
+                        && i < value.len() - 1
+                        && value.iter().filter(|c| **c == b'@').count() == 1
+                        && !value.iter().any(|c| c.is_ascii_whitespace())
+                }
+                None => false,
+            }
+        }
+        AliasKind::Phone => {
+            let digits = value.get(1..).unwrap_or(&[]);
+            value.first() == Some(&b'+')
+                && (8..=15).contains(&digits.len())
+                && digits[0] != b'0'
+                && digits.iter().all(|c| c.is_ascii_digit())
+        }
+        AliasKind::Username => {
+            (3..=32).contains(&value.len())
+                && value
+                    .iter()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'_')
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidAddress)
+    }
+}
+
+/// Validates `value` for its kind and hashes it into the alias namespace
+pub fn hash_alias(env: &Env, kind: AliasKind, value: &String) -> Result<BytesN<32>, AppError> {
+    let len = value.len() as usize;
+    if len == 0 || len > MAX_ALIAS_LEN {
+        return Err(AppError::InvalidAddress);
+    }
+
+    let mut buf = [0u8; MAX_ALIAS_LEN];
+    value.copy_into_slice(&mut buf[..len]);
+    validate(kind, &buf[..len])?;
+
+    let mut preimage = Bytes::from_slice(env, namespace(kind));
+    preimage.extend_from_slice(&buf[..len]);
+    Ok(env.crypto().sha256(&preimage).into())
+}
+
+/// Deterministic contract address for an alias, used when no wallet is registered for it
+pub fn derive_alias_address(
+    env: &Env,
+    kind: AliasKind,
+    value: &String,
+) -> Result<Address, AppError> {
+    let hash = hash_alias(env, kind, value)?;
+
+    let mut xdr: [u8; 40] = [0; 40];
+    xdr[3] = 18;
+    xdr[7] = 1;
+    let slice: &mut [u8; 32] = (&mut xdr[8..40]).try_into().unwrap();
+    hash.copy_into_slice(slice);
+
+    let addr_bytes = Bytes::from_slice(env, &xdr);
+    Address::from_xdr(env, &addr_bytes).map_err(|_| AppError::InvalidAddress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alias_kinds_are_namespaced() {
+        let env = Env::default();
+        let value = String::from_str(&env, "alice_01");
+
+        let username = hash_alias(&env, AliasKind::Username, &value).unwrap();
+        let mut preimage = Bytes::from_slice(&env, b"alice_01");
+        let unprefixed: BytesN<32> = env.crypto().sha256(&preimage).into();
+        preimage = Bytes::from_slice(&env, b"username:alice_01");
+        let prefixed: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        assert_ne!(username, unprefixed);
+        assert_eq!(username, prefixed);
+    }
+
+    #[test]
+    fn alias_validation_per_kind() {
+        let env = Env::default();
+        let check = |kind, value| hash_alias(&env, kind, &String::from_str(&env, value)).is_ok();
+
+        assert!(check(AliasKind::Email, "user@example.com"));
+        assert!(!check(AliasKind::Email, "user.example.com"));
+        assert!(!check(AliasKind::Email, "user@"));
+
+        assert!(check(AliasKind::Phone, "+2348012345678"));
+        assert!(!check(AliasKind::Phone, "08012345678"));
+        assert!(!check(AliasKind::Phone, "+0123456789"));
+        assert!(!check(AliasKind::Phone, "+1234"));
+
+        assert!(check(AliasKind::Username, "alice_01"));
+        assert!(!check(AliasKind::Username, "Alice"));
+        assert!(!check(AliasKind::Username, "al"));
+    }
+}
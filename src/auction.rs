@@ -0,0 +1,391 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    AuctionBidData, AuctionCancelledData, AuctionCreatedData, AuctionEvent, AuctionSettledData,
+    DeFiEvent, EventEmitter, AUCTION_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+const BASIS_POINTS_DIVISOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuctionKind {
+    /// Ascending bids; the highest bid when the auction ends wins
+    English,
+    /// Price falls from `start_price` to `reserve_price`; the first bid wins
+    Dutch,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Active,
+    Settled,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionParams {
+    /// Dutch opening price; ignored for English auctions
+    pub start_price: i128,
+    /// English minimum opening bid, or the Dutch floor price
+    pub reserve_price: i128,
+    /// Smallest raise over the highest English bid, in basis points
+    pub min_increment_bps: u32,
+    pub duration: u64,
+    /// English bids this close to the end push the end back by this much
+    pub extension_window: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub id: u64,
+    pub seller: Address,
+    pub lot_token: Address,
+    pub lot_amount: i128,
+    pub bid_token: Address,
+    pub kind: AuctionKind,
+    pub params: AuctionParams,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128,
+    pub status: AuctionStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AuctionDataKey {
+    Admin,
+    NextId,
+    Auction(u64),
+}
+
+#[contract]
+pub struct AuctionContract;
+
+/// Auctions for liquidated lending collateral and abandoned escrow balances.
+/// Operators consign a lot on a governance decision; bids are held by the
+/// contract until they are outbid or the auction settles.
+#[contractimpl]
+impl AuctionContract {
+    pub fn initialize_auction_house(env: Env, admin: Address) {
+        if env.storage().instance().has(&AuctionDataKey::Admin) {
+            panic!("Auction house already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&AuctionDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Consign `lot_amount` of `lot_token` for sale against `bid_token` (operator only)
+    pub fn create_auction(
+        env: Env,
+        seller: Address,
+        lot_token: Address,
+        lot_amount: i128,
+        bid_token: Address,
+        kind: AuctionKind,
+        params: AuctionParams,
+    ) -> u64 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &seller,
+            "create_auction",
+            (
+                lot_token.clone(),
+                lot_amount,
+                bid_token.clone(),
+                kind,
+                params.clone(),
+            ),
+        );
+        if lot_amount <= 0 || params.reserve_price <= 0 || params.duration == 0 {
+            panic!("Invalid auction parameters");
+        }
+        if kind == AuctionKind::Dutch && params.start_price < params.reserve_price {
+            panic!("Start price below reserve");
+        }
+
+        token::Client::new(&env, &lot_token).transfer(
+            &seller,
+            &env.current_contract_address(),
+            &lot_amount,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&AuctionDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&AuctionDataKey::NextId, &(id + 1));
+
+        let now = env.ledger().timestamp();
+        let auction = Auction {
+            id,
+            seller: seller.clone(),
+            lot_token: lot_token.clone(),
+            lot_amount,
+            bid_token,
+            kind,
+            params: params.clone(),
+            start_time: now,
+            end_time: now + params.duration,
+            highest_bidder: None,
+            highest_bid: 0,
+            status: AuctionStatus::Active,
+        };
+        Self::save(&env, &auction);
+
+        let event = DeFiEvent::Auction(AuctionEvent::Created(AuctionCreatedData {
+            auction_id: id,
+            seller,
+            lot_token,
+            lot_amount,
+            kind,
+            end_time: auction.end_time,
+        }));
+        EventEmitter::emit_event(&env, AUCTION_TOPIC, event);
+        id
+    }
+
+    /// Bid up to `amount`. English bids are held until outbid or settled; a
+    /// Dutch bid at or above the current price buys the lot at that price.
+    pub fn place_bid(env: Env, auction_id: u64, bidder: Address, amount: i128) {
+        bidder.require_auth();
+        let mut auction = Self::get_auction(env.clone(), auction_id);
+        let now = env.ledger().timestamp();
+        if auction.status != AuctionStatus::Active || now >= auction.end_time {
+            panic!("Auction not open");
+        }
+
+        let price = Self::get_min_bid(env.clone(), auction_id);
+        if amount < price {
+            panic!("Bid too low");
+        }
+        let bid_token = token::Client::new(&env, &auction.bid_token);
+
+        match auction.kind {
+            AuctionKind::English => {
+                bid_token.transfer(&bidder, &env.current_contract_address(), &amount);
+                if let Some(previous) = auction.highest_bidder.clone() {
+                    bid_token.transfer(
+                        &env.current_contract_address(),
+                        &previous,
+                        &auction.highest_bid,
+                    );
+                }
+                auction.highest_bidder = Some(bidder.clone());
+                auction.highest_bid = amount;
+                if auction.end_time - now < auction.params.extension_window {
+                    auction.end_time = now + auction.params.extension_window;
+                }
+                Self::save(&env, &auction);
+
+                let event = DeFiEvent::Auction(AuctionEvent::BidPlaced(AuctionBidData {
+                    auction_id,
+                    bidder,
+                    amount,
+                    end_time: auction.end_time,
+                }));
+                EventEmitter::emit_event(&env, AUCTION_TOPIC, event);
+            }
+            AuctionKind::Dutch => {
+                bid_token.transfer(&bidder, &auction.seller, &price);
+                auction.highest_bidder = Some(bidder);
+                auction.highest_bid = price;
+                Self::finish(&env, &mut auction);
+            }
+        }
+    }
+
+    /// Close an ended auction: the lot goes to the winner and the winning bid
+    /// to the seller, or the lot returns to the seller without bids
+    pub fn settle_auction(env: Env, auction_id: u64) {
+        let mut auction = Self::get_auction(env.clone(), auction_id);
+        if auction.status != AuctionStatus::Active {
+            panic!("Auction not active");
+        }
+        if env.ledger().timestamp() < auction.end_time {
+            panic!("Auction still running");
+        }
+
+        if auction.highest_bidder.is_some() {
+            token::Client::new(&env, &auction.bid_token).transfer(
+                &env.current_contract_address(),
+                &auction.seller,
+                &auction.highest_bid,
+            );
+        }
+        Self::finish(&env, &mut auction);
+    }
+
+    /// Withdraw a lot nobody has bid on (seller only)
+    pub fn cancel_auction(env: Env, auction_id: u64) {
+        let mut auction = Self::get_auction(env.clone(), auction_id);
+        auction.seller.require_auth();
+        if auction.status != AuctionStatus::Active {
+            panic!("Auction not active");
+        }
+        if auction.highest_bidder.is_some() {
+            panic!("Auction has bids");
+        }
+
+        token::Client::new(&env, &auction.lot_token).transfer(
+            &env.current_contract_address(),
+            &auction.seller,
+            &auction.lot_amount,
+        );
+        auction.status = AuctionStatus::Cancelled;
+        Self::save(&env, &auction);
+
+        let event =
+            DeFiEvent::Auction(AuctionEvent::Cancelled(AuctionCancelledData { auction_id }));
+        EventEmitter::emit_event(&env, AUCTION_TOPIC, event);
+    }
+
+    pub fn get_auction(env: Env, auction_id: u64) -> Auction {
+        env.storage()
+            .persistent()
+            .get(&AuctionDataKey::Auction(auction_id))
+            .expect("Auction not found")
+    }
+
+    /// Lowest acceptable bid right now: the current Dutch price, or the
+    /// reserve / highest bid plus the minimum increment for English auctions
+    pub fn get_min_bid(env: Env, auction_id: u64) -> i128 {
+        let auction = Self::get_auction(env.clone(), auction_id);
+        let params = &auction.params;
+        match auction.kind {
+            AuctionKind::English => {
+                if auction.highest_bidder.is_none() {
+                    return params.reserve_price;
+                }
+                let increment =
+                    auction.highest_bid * params.min_increment_bps as i128 / BASIS_POINTS_DIVISOR;
+                auction.highest_bid + increment.max(1)
+            }
+            AuctionKind::Dutch => {
+                let elapsed = env
+                    .ledger()
+                    .timestamp()
+                    .saturating_sub(auction.start_time)
+                    .min(params.duration);
+                params.start_price
+                    - (params.start_price - params.reserve_price) * elapsed as i128
+                        / params.duration as i128
+            }
+        }
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_auction_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_auction_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_auction_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_auction_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_auction_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    // Hands the lot to the winner, or back to the seller, and marks the auction settled
+    fn finish(env: &Env, auction: &mut Auction) {
+        let receiver = auction
+            .highest_bidder
+            .clone()
+            .unwrap_or(auction.seller.clone());
+        token::Client::new(env, &auction.lot_token).transfer(
+            &env.current_contract_address(),
+            &receiver,
+            &auction.lot_amount,
+        );
+        auction.status = AuctionStatus::Settled;
+        Self::save(env, auction);
+
+        let event = DeFiEvent::Auction(AuctionEvent::Settled(AuctionSettledData {
+            auction_id: auction.id,
+            winner: auction.highest_bidder.clone(),
+            price: auction.highest_bid,
+        }));
+        EventEmitter::emit_event(env, AUCTION_TOPIC, event);
+    }
+
+    fn save(env: &Env, auction: &Auction) {
+        env.storage()
+            .persistent()
+            .set(&AuctionDataKey::Auction(auction.id), auction);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_auction_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_auction_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_auction_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_auction_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` held for active auctions: consigned lots and standing English bids
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let next_id: u64 = env
+            .storage()
+            .instance()
+            .get(&AuctionDataKey::NextId)
+            .unwrap_or(1);
+        let mut owed = 0;
+        for id in 1..next_id {
+            let auction = Self::get_auction(env.clone(), id);
+            if auction.status != AuctionStatus::Active {
+                continue;
+            }
+            if auction.lot_token == *token {
+                owed += auction.lot_amount;
+            }
+            if auction.bid_token == *token && auction.highest_bidder.is_some() {
+                owed += auction.highest_bid;
+            }
+        }
+        Some(owed)
+    }
+}
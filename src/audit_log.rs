@@ -0,0 +1,192 @@
+use crate::access::{AccessControl, Role};
+use crate::storage;
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
+};
+
+/// Most entries returned by one page of the log
+pub const MAX_AUDIT_PAGE: u32 = 100;
+
+/// One privileged call made on a contract reporting to the log
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminAction {
+    /// Position in the log, from 0
+    pub seq: u64,
+    pub actor: Address,
+    /// Contract the call was made on
+    pub contract: Address,
+    pub function: Symbol,
+    /// SHA-256 of the call's arguments, XDR-encoded
+    pub params_hash: BytesN<32>,
+    pub timestamp: u64,
+    /// SHA-256 of the XDR of the previous entry; all zeroes for the first
+    pub prev_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AuditLogDataKey {
+    Admin,
+    /// Contract allowed to append to the log
+    Source(Address),
+    Count,
+    /// SHA-256 of the XDR of the latest entry
+    Head,
+    Entry(u64),
+}
+
+#[contract]
+pub struct AuditLogContract;
+
+/// Append-only journal of admin actions shared by every contract pointed at
+/// it. Each entry commits to the one before it, so a copy of the log can be
+/// checked against the head hash and no entry can be dropped or altered
+/// without breaking the chain. Only contracts the owner registers may append.
+#[contractimpl]
+impl AuditLogContract {
+    pub fn initialize_audit_log(env: Env, admin: Address) {
+        if env.storage().instance().has(&AuditLogDataKey::Admin) {
+            panic!("Audit log already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&AuditLogDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Allow or stop a contract appending to the log (owner only)
+    pub fn set_audit_source(env: Env, caller: Address, source: Address, allowed: bool) {
+        AccessControl::require_role(&env, Role::Owner, &caller);
+        let key = AuditLogDataKey::Source(source);
+        if allowed {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+
+    pub fn is_audit_source(env: Env, source: Address) -> bool {
+        env.storage()
+            .instance()
+            .has(&AuditLogDataKey::Source(source))
+    }
+
+    /// Append an action taken on `source`, which must be the calling
+    /// contract. Returns the entry's position.
+    pub fn record_admin_action(
+        env: Env,
+        source: Address,
+        actor: Address,
+        function: Symbol,
+        params_hash: BytesN<32>,
+    ) -> u64 {
+        source.require_auth();
+        if !Self::is_audit_source(env.clone(), source.clone()) {
+            panic!("Not an audit source");
+        }
+
+        let seq = Self::get_admin_action_count(env.clone());
+        let entry = AdminAction {
+            seq,
+            actor,
+            contract: source,
+            function,
+            params_hash,
+            timestamp: env.ledger().timestamp(),
+            prev_hash: Self::get_audit_head(env.clone()),
+        };
+        storage::write(&env, &AuditLogDataKey::Entry(seq), &entry);
+        let head: BytesN<32> = env.crypto().sha256(&entry.to_xdr(&env)).into();
+        env.storage().instance().set(&AuditLogDataKey::Head, &head);
+        env.storage()
+            .instance()
+            .set(&AuditLogDataKey::Count, &(seq + 1));
+        seq
+    }
+
+    pub fn get_admin_action(env: Env, seq: u64) -> Option<AdminAction> {
+        storage::read(&env, &AuditLogDataKey::Entry(seq))
+    }
+
+    /// Entries from `offset` on, oldest first, at most `MAX_AUDIT_PAGE` a page
+    pub fn get_admin_actions(env: Env, offset: u64, limit: u32) -> Vec<AdminAction> {
+        let count = Self::get_admin_action_count(env.clone());
+        let end = count.min(offset.saturating_add(u64::from(limit.min(MAX_AUDIT_PAGE))));
+        let mut actions = Vec::new(&env);
+        for seq in offset..end {
+            if let Some(action) = Self::get_admin_action(env.clone(), seq) {
+                actions.push_back(action);
+            }
+        }
+        actions
+    }
+
+    pub fn get_admin_action_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&AuditLogDataKey::Count)
+            .unwrap_or(0)
+    }
+
+    /// Hash the next entry will carry as `prev_hash`
+    pub fn get_audit_head(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&AuditLogDataKey::Head)
+            .unwrap_or_else(|| BytesN::from_array(&env, &[0; 32]))
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_audit_log_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_audit_log_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AuditDataKey {
+    Log,
+}
+
+// Reporting hook for contracts that journal their admin actions. The log a
+// contract reports to is kept in its own instance storage; with none set,
+// recording is a no-op.
+pub struct AdminAudit;
+
+impl AdminAudit {
+    /// Sets or clears the audit log the contract reports to
+    pub fn set_log(env: &Env, log: Option<Address>) {
+        match log {
+            Some(log) => env.storage().instance().set(&AuditDataKey::Log, &log),
+            None => env.storage().instance().remove(&AuditDataKey::Log),
+        }
+    }
+
+    pub fn get_log(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&AuditDataKey::Log)
+    }
+
+    /// Appends `actor` calling `function` with `params` to the audit log
+    pub fn record<P>(env: &Env, actor: &Address, function: &str, params: P)
+    where
+        P: IntoVal<Env, Val>,
+    {
+        if let Some(log) = Self::get_log(env) {
+            let params_hash: BytesN<32> = env.crypto().sha256(&params.to_xdr(env)).into();
+            AuditLogContractClient::new(env, &log).record_admin_action(
+                &env.current_contract_address(),
+                actor,
+                &Symbol::new(env, function),
+                &params_hash,
+            );
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    AddressBlockedData, AddressUnblockedData, BlocklistEvent, DeFiEvent, EventEmitter,
+    BLOCKLIST_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockEntry {
+    pub reason: Symbol,
+    pub blocked_by: Address,
+    pub blocked_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BlocklistDataKey {
+    Admin,
+    Entry(Address),
+}
+
+#[contract]
+pub struct BlocklistContract;
+
+/// Sanctioned addresses shared by every contract that points at this registry.
+/// The owner, typically the multisig, maintains the list.
+#[contractimpl]
+impl BlocklistContract {
+    pub fn initialize_blocklist(env: Env, admin: Address) {
+        if env.storage().instance().has(&BlocklistDataKey::Admin) {
+            panic!("Blocklist already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&BlocklistDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Flags `account` so participating contracts reject it (owner only)
+    pub fn block_address(env: Env, caller: Address, account: Address, reason: Symbol) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "block_address",
+            (account.clone(), reason.clone()),
+        );
+        let entry = BlockEntry {
+            reason: reason.clone(),
+            blocked_by: caller.clone(),
+            blocked_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&BlocklistDataKey::Entry(account.clone()), &entry);
+
+        let event = DeFiEvent::Blocklist(BlocklistEvent::Blocked(AddressBlockedData {
+            account,
+            reason,
+            changed_by: caller,
+        }));
+        EventEmitter::emit_event(&env, BLOCKLIST_TOPIC, event);
+    }
+
+    /// Removes `account` from the list (owner only)
+    pub fn unblock_address(env: Env, caller: Address, account: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "unblock_address",
+            account.clone(),
+        );
+        let key = BlocklistDataKey::Entry(account.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Address not blocked");
+        }
+        env.storage().persistent().remove(&key);
+
+        let event = DeFiEvent::Blocklist(BlocklistEvent::Unblocked(AddressUnblockedData {
+            account,
+            changed_by: caller,
+        }));
+        EventEmitter::emit_event(&env, BLOCKLIST_TOPIC, event);
+    }
+
+    pub fn is_blocked(env: Env, account: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&BlocklistDataKey::Entry(account))
+    }
+
+    pub fn get_block_entry(env: Env, account: Address) -> Option<BlockEntry> {
+        env.storage()
+            .persistent()
+            .get(&BlocklistDataKey::Entry(account))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_blocklist_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_blocklist_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_blocklist_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_blocklist_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_blocklist_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+}
+
+// Enforcement hook for contracts holding a blocklist address
+pub struct BlocklistCheck;
+
+impl BlocklistCheck {
+    /// Whether any of `accounts` is on the blocklist at `registry`
+    pub fn any_blocked(env: &Env, registry: &Address, accounts: &[&Address]) -> bool {
+        let client = BlocklistContractClient::new(env, registry);
+        accounts.iter().any(|account| client.is_blocked(account))
+    }
+}
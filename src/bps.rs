@@ -0,0 +1,48 @@
+use crate::errors::AppError;
+use crate::utils::checked_mul_div;
+
+/// Basis points in a whole: 10_000 bps is 100%
+pub const MAX_BPS: u32 = 10_000;
+
+/// A rate in basis points known to be at most 100%. Entry points keep taking
+/// plain `u32`s; they go through a `Bps` to check a configured rate and to
+/// apply it, so an unchecked 150% fee cannot reach the arithmetic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Bps(u32);
+
+impl Bps {
+    pub const ZERO: Bps = Bps(0);
+    pub const FULL: Bps = Bps(MAX_BPS);
+
+    /// `bps`, or `InvalidBps` when it is above 100%
+    pub fn new(bps: u32) -> Result<Bps, AppError> {
+        Self::capped(bps, MAX_BPS)
+    }
+
+    /// `bps`, or `InvalidBps` when it is above `cap` or 100%
+    pub fn capped(bps: u32, cap: u32) -> Result<Bps, AppError> {
+        if bps > cap || bps > MAX_BPS {
+            return Err(AppError::InvalidBps);
+        }
+        Ok(Bps(bps))
+    }
+
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// This rate of `amount`, rounded toward zero
+    pub fn of(self, amount: i128) -> Result<i128, AppError> {
+        checked_mul_div(amount, i128::from(self.0), i128::from(MAX_BPS))
+    }
+
+    /// What is left of 100% after this rate
+    pub fn complement(self) -> Bps {
+        Bps(MAX_BPS - self.0)
+    }
+
+    /// Both rates together, or `InvalidBps` when they add up to more than 100%
+    pub fn checked_add(self, other: Bps) -> Result<Bps, AppError> {
+        Self::new(self.0 + other.0)
+    }
+}
@@ -0,0 +1,389 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    BridgeEvent, BridgeInboundData, BridgeOutboundData, BridgePauseData, DeFiEvent, EventEmitter,
+    BRIDGE_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env, Symbol,
+    Vec,
+};
+
+const BRIDGE_KEY: Symbol = symbol_short!("BRIDGE");
+/// Upper bound on attestor keys, to keep quorum checks within budget
+pub const MAX_ATTESTORS: u32 = 20;
+
+/// How this contract holds a bridged asset on Stellar
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BridgeMode {
+    /// Native Stellar asset, locked here on the way out and released on the way in
+    Locked,
+    /// Mirror of a remote asset, burned on the way out and minted on the way
+    /// in; this contract must be the asset's admin
+    Wrapped,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttestorSet {
+    pub keys: Vec<BytesN<32>>,
+    /// Distinct attestor signatures needed to authorize an inbound transfer
+    pub threshold: u32,
+}
+
+/// One attestor's ed25519 signature over an inbound transfer payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttestorSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BridgeDataKey {
+    Admin,
+    Attestors,
+    Paused,
+    NextId,
+    Asset(Address),
+    /// Inbound transfer ids already paid out
+    Processed(BytesN<32>),
+}
+
+#[contract]
+pub struct BridgeContract;
+
+/// Bridge escrow for moving assets between Stellar and other chains.
+/// Outbound transfers lock or burn the asset here and are picked up by the
+/// attestors; inbound transfers release or mint once a quorum of attestors
+/// has signed them. Pausers can halt inbound and outbound transfers at the
+/// first sign of fraud.
+#[contractimpl]
+impl BridgeContract {
+    pub fn initialize_bridge(env: Env, admin: Address, attestors: Vec<BytesN<32>>, threshold: u32) {
+        if env.storage().instance().has(&BridgeDataKey::Admin) {
+            panic!("Bridge already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&BridgeDataKey::Admin, &admin);
+        Self::store_attestors(&env, attestors, threshold);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Replace the attestor keys and quorum (owner only)
+    pub fn set_bridge_attestors(
+        env: Env,
+        caller: Address,
+        attestors: Vec<BytesN<32>>,
+        threshold: u32,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_bridge_attestors",
+            (attestors.clone(), threshold),
+        );
+        Self::store_attestors(&env, attestors, threshold);
+    }
+
+    pub fn get_bridge_attestors(env: Env) -> AttestorSet {
+        env.storage()
+            .instance()
+            .get(&BridgeDataKey::Attestors)
+            .expect("Bridge not initialized")
+    }
+
+    /// Enable `token` for bridging in the given mode (owner only)
+    pub fn set_bridge_asset(env: Env, caller: Address, token: Address, mode: BridgeMode) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_bridge_asset",
+            (token.clone(), mode),
+        );
+        env.storage()
+            .instance()
+            .set(&BridgeDataKey::Asset(token), &mode);
+    }
+
+    /// Stop bridging `token` (owner only)
+    pub fn remove_bridge_asset(env: Env, caller: Address, token: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "remove_bridge_asset",
+            token.clone(),
+        );
+        env.storage()
+            .instance()
+            .remove(&BridgeDataKey::Asset(token));
+    }
+
+    pub fn get_bridge_asset(env: Env, token: Address) -> BridgeMode {
+        env.storage()
+            .instance()
+            .get(&BridgeDataKey::Asset(token))
+            .expect("Asset not bridged")
+    }
+
+    /// Halt all transfers (pauser only)
+    pub fn pause_bridge(env: Env, caller: Address) {
+        AccessControl::require_audited_role(&env, Role::Pauser, &caller, "pause_bridge", ());
+        Self::set_paused(&env, caller, true);
+    }
+
+    /// Resume transfers after an incident (owner only)
+    pub fn unpause_bridge(env: Env, caller: Address) {
+        AccessControl::require_audited_role(&env, Role::Owner, &caller, "unpause_bridge", ());
+        Self::set_paused(&env, caller, false);
+    }
+
+    pub fn is_bridge_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&BridgeDataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Send `amount` of `token` to `dest_address` on `dest_chain`; returns the
+    /// outbound transfer id the attestors reference on the other side
+    pub fn bridge_out(
+        env: Env,
+        sender: Address,
+        token: Address,
+        amount: i128,
+        dest_chain: u32,
+        dest_address: Bytes,
+    ) -> u64 {
+        sender.require_auth();
+        Self::require_not_paused(&env);
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let client = token::Client::new(&env, &token);
+        match Self::get_bridge_asset(env.clone(), token.clone()) {
+            BridgeMode::Locked => {
+                client.transfer(&sender, &env.current_contract_address(), &amount)
+            }
+            BridgeMode::Wrapped => client.burn(&sender, &amount),
+        }
+
+        let transfer_id: u64 = env
+            .storage()
+            .instance()
+            .get(&BridgeDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&BridgeDataKey::NextId, &(transfer_id + 1));
+
+        let event = DeFiEvent::Bridge(BridgeEvent::Outbound(BridgeOutboundData {
+            transfer_id,
+            sender,
+            token,
+            amount,
+            dest_chain,
+            dest_address,
+        }));
+        EventEmitter::emit_event(&env, BRIDGE_TOPIC, event);
+        transfer_id
+    }
+
+    /// Pay out an inbound transfer signed by a quorum of attestors. Each
+    /// transfer id is honoured once.
+    pub fn bridge_in(
+        env: Env,
+        transfer_id: BytesN<32>,
+        token: Address,
+        recipient: Address,
+        amount: i128,
+        signatures: Vec<AttestorSignature>,
+    ) {
+        Self::require_not_paused(&env);
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let processed_key = BridgeDataKey::Processed(transfer_id.clone());
+        if env.storage().persistent().has(&processed_key) {
+            panic!("Transfer already processed");
+        }
+
+        let payload = Self::bridge_in_payload(
+            env.clone(),
+            transfer_id.clone(),
+            token.clone(),
+            recipient.clone(),
+            amount,
+        );
+        let attestors = Self::get_bridge_attestors(env.clone());
+        let mut signers: Vec<BytesN<32>> = Vec::new(&env);
+        for entry in signatures.iter() {
+            if !attestors.keys.contains(&entry.public_key) {
+                panic!("Unknown attestor");
+            }
+            if signers.contains(&entry.public_key) {
+                panic!("Duplicate attestor signature");
+            }
+            env.crypto().ed25519_verify(
+                &entry.public_key,
+                &payload.clone().into(),
+                &entry.signature,
+            );
+            signers.push_back(entry.public_key);
+        }
+        if signers.len() < attestors.threshold {
+            panic!("Not enough attestor signatures");
+        }
+        env.storage().persistent().set(&processed_key, &true);
+
+        match Self::get_bridge_asset(env.clone(), token.clone()) {
+            BridgeMode::Locked => token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &recipient,
+                &amount,
+            ),
+            BridgeMode::Wrapped => {
+                token::StellarAssetClient::new(&env, &token).mint(&recipient, &amount)
+            }
+        }
+
+        let event = DeFiEvent::Bridge(BridgeEvent::Inbound(BridgeInboundData {
+            transfer_id,
+            token,
+            recipient,
+            amount,
+        }));
+        EventEmitter::emit_event(&env, BRIDGE_TOPIC, event);
+    }
+
+    /// The 32-byte digest attestors sign to authorize an inbound transfer
+    pub fn bridge_in_payload(
+        env: Env,
+        transfer_id: BytesN<32>,
+        token: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> BytesN<32> {
+        let message = (
+            BRIDGE_KEY,
+            env.current_contract_address(),
+            transfer_id,
+            token,
+            recipient,
+            amount,
+        )
+            .to_xdr(&env);
+        env.crypto().sha256(&message).into()
+    }
+
+    pub fn is_bridge_transfer_processed(env: Env, transfer_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .has(&BridgeDataKey::Processed(transfer_id))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_bridge_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_bridge_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_bridge_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_bridge_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_bridge_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn store_attestors(env: &Env, keys: Vec<BytesN<32>>, threshold: u32) {
+        if keys.len() > MAX_ATTESTORS {
+            panic!("Too many attestors");
+        }
+        if threshold == 0 || threshold > keys.len() {
+            panic!("Invalid attestor threshold");
+        }
+        env.storage()
+            .instance()
+            .set(&BridgeDataKey::Attestors, &AttestorSet { keys, threshold });
+    }
+
+    fn set_paused(env: &Env, caller: Address, paused: bool) {
+        env.storage()
+            .instance()
+            .set(&BridgeDataKey::Paused, &paused);
+        let data = BridgePauseData { caller };
+        let event = DeFiEvent::Bridge(if paused {
+            BridgeEvent::Paused(data)
+        } else {
+            BridgeEvent::Unpaused(data)
+        });
+        EventEmitter::emit_event(env, BRIDGE_TOPIC, event);
+    }
+
+    fn require_not_paused(env: &Env) {
+        if Self::is_bridge_paused(env.clone()) {
+            panic!("Bridge paused");
+        }
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_bridge_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_bridge_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_bridge_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_bridge_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// A locked asset's whole balance backs its mirrors on the remote chain,
+    /// which this contract has no record of
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mode: Option<BridgeMode> = env
+            .storage()
+            .instance()
+            .get(&BridgeDataKey::Asset(token.clone()));
+        match mode {
+            Some(BridgeMode::Locked) => None,
+            _ => Some(0),
+        }
+    }
+}
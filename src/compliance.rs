@@ -0,0 +1,158 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    ComplianceEvent, DeFiEvent, EventEmitter, KycAttestedData, KycRevokedData, COMPLIANCE_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+/// Highest KYC tier, matching the three-tier scheme required by Nigerian regulators
+pub const MAX_KYC_TIER: u32 = 3;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KycRecord {
+    /// 1 to `MAX_KYC_TIER`
+    pub tier: u32,
+    pub expires_at: u64,
+    pub attested_by: Address,
+    pub attested_at: u64,
+}
+
+/// How a contract consults the registry: amounts at or above `threshold` need
+/// every party to hold at least `min_tier`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompliancePolicy {
+    pub registry: Address,
+    pub min_tier: u32,
+    pub threshold: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ComplianceDataKey {
+    Admin,
+    Kyc(Address),
+}
+
+#[contract]
+pub struct ComplianceContract;
+
+/// Registry of KYC verification levels. Attestors mark addresses as verified
+/// up to a tier until an expiry; escrow, conversion and token contracts check
+/// it through a `CompliancePolicy`.
+#[contractimpl]
+impl ComplianceContract {
+    pub fn initialize_compliance(env: Env, admin: Address) {
+        if env.storage().instance().has(&ComplianceDataKey::Admin) {
+            panic!("Compliance already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&ComplianceDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Records `subject` as verified at `tier` until `expires_at` (attestor only)
+    pub fn attest_kyc(env: Env, attestor: Address, subject: Address, tier: u32, expires_at: u64) {
+        AccessControl::require_role(&env, Role::Attestor, &attestor);
+        if tier == 0 || tier > MAX_KYC_TIER {
+            panic!("Invalid KYC tier");
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Expiry must be in the future");
+        }
+
+        let record = KycRecord {
+            tier,
+            expires_at,
+            attested_by: attestor.clone(),
+            attested_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&ComplianceDataKey::Kyc(subject.clone()), &record);
+
+        let event = DeFiEvent::Compliance(ComplianceEvent::Attested(KycAttestedData {
+            subject,
+            tier,
+            expires_at,
+            attestor,
+        }));
+        EventEmitter::emit_event(&env, COMPLIANCE_TOPIC, event);
+    }
+
+    /// Removes a verification (attestor only)
+    pub fn revoke_kyc(env: Env, attestor: Address, subject: Address) {
+        AccessControl::require_role(&env, Role::Attestor, &attestor);
+        let key = ComplianceDataKey::Kyc(subject.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("KYC record not found");
+        }
+        env.storage().persistent().remove(&key);
+
+        let event = DeFiEvent::Compliance(ComplianceEvent::Revoked(KycRevokedData {
+            subject,
+            revoked_by: attestor,
+        }));
+        EventEmitter::emit_event(&env, COMPLIANCE_TOPIC, event);
+    }
+
+    pub fn get_kyc_record(env: Env, subject: Address) -> Option<KycRecord> {
+        env.storage()
+            .persistent()
+            .get(&ComplianceDataKey::Kyc(subject))
+    }
+
+    /// Current tier of `subject`, 0 when unverified or expired
+    pub fn get_kyc_tier(env: Env, subject: Address) -> u32 {
+        match Self::get_kyc_record(env.clone(), subject) {
+            Some(record) if record.expires_at > env.ledger().timestamp() => record.tier,
+            _ => 0,
+        }
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_compliance_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_compliance_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_compliance_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_compliance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_compliance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+}
+
+// Enforcement hook for contracts holding a compliance policy
+pub struct ComplianceCheck;
+
+impl ComplianceCheck {
+    /// Whether `subject` may move `amount` under `policy`
+    pub fn is_allowed(
+        env: &Env,
+        policy: &CompliancePolicy,
+        subject: &Address,
+        amount: i128,
+    ) -> bool {
+        amount < policy.threshold
+            || ComplianceContractClient::new(env, &policy.registry).get_kyc_tier(subject)
+                >= policy.min_tier
+    }
+}
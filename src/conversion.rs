@@ -1,11 +1,33 @@
 use core::fmt::Write;
 use heapless::String as HString;
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
 use soroban_sdk::{
-    contract, contractimpl, contractmeta, contracttype, log, Address, Env, Map,
-    String as SorobanString, Symbol, Vec,
+    contract, contractimpl, contractmeta, contracttype, log, token, vec, Address, Bytes, BytesN,
+    Env, IntoVal, Map, String as SorobanString, Symbol, Vec,
 };
 
-use crate::utils::{validate_address, validate_positive_amount};
+use crate::access::{AccessControl, Role};
+use crate::address_book::BeneficiaryCheck;
+use crate::audit_log::AdminAudit;
+use crate::bps::Bps;
+use crate::compliance::{ComplianceCheck, CompliancePolicy};
+use crate::errors::{fail, or_overflow, AppError};
+use crate::guardian::{GuardianCheck, Subsystem};
+use crate::idempotency::Idempotency;
+use crate::inheritance::{Inheritance, InheritancePlan};
+use crate::loyalty::LoyaltyContractClient;
+use crate::oracle::OracleContractClient;
+use crate::pool_manager::PoolManagerContractClient;
+use crate::promo::{PromoCode, Promos};
+use crate::reference::PaymentReference;
+use crate::referral::ReferralContractClient;
+use crate::sep40::{read_feed_rate, RateFeed};
+use crate::snapshot::{ConfigSnapshots, SnapshotInfo};
+use crate::storage;
+use crate::upgrade::Upgrade;
+use crate::utils::{
+    checked_add, checked_mul_div, checked_sub, validate_address, validate_positive_amount,
+};
 
 use crate::events::publish;
 
@@ -77,6 +99,96 @@ pub struct ConversionTx {
     pub status: ConversionStatus,
 }
 
+/// Why a balance changed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BalanceChangeKind {
+    Deposit,
+    /// Source leg of a conversion
+    ConversionOut,
+    /// Target leg of a conversion
+    ConversionIn,
+    /// Leftover below the dust threshold taken from the user
+    DustSwept,
+    /// Swept dust credited to the fee collector
+    DustReceived,
+    /// Spread credited to the fee collector while no pool can take it
+    SpreadReceived,
+    /// Balance passed to an inheritance beneficiary
+    Bequeathed,
+    /// Balance taken over from an inactive owner
+    Inherited,
+    /// Balance debited for payout, including withdrawals still queued
+    Withdrawal,
+    /// Queued withdrawal cancelled and returned to the balance
+    WithdrawalCancelled,
+}
+
+/// One line of a user's statement in a currency
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceEntry {
+    pub kind: BalanceChangeKind,
+    /// Signed change to the balance
+    pub amount: i128,
+    /// Balance after the change
+    pub balance: i128,
+    /// Conversion transaction behind the change, if any
+    pub tx_id: Option<Symbol>,
+    pub timestamp: u64,
+}
+
+/// Opt-in protection for a user's balances: withdrawals above `threshold`
+/// wait `delay` seconds, during which the user or `guardian` can cancel them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalGuard {
+    pub threshold: i128,
+    pub delay: u64,
+    pub guardian: Option<Address>,
+}
+
+/// Change to a guard in force, held back for that guard's delay so a taken
+/// over account cannot simply switch its protection off
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingGuardChange {
+    pub guard: Option<WithdrawalGuard>,
+    pub effective_at: u64,
+}
+
+/// A large withdrawal waiting out its cooling-off period. The amount has
+/// already left the user's balance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingWithdrawal {
+    pub id: u64,
+    pub user: Address,
+    pub currency: Currency,
+    pub amount: i128,
+    pub requested_at: u64,
+    pub available_at: u64,
+    /// Account paid in the backing token on release, for external payouts
+    pub destination: Option<Address>,
+}
+
+/// Standing instruction to convert from a balance once the pair's rate
+/// reaches a trigger. Funds are not set aside; each fill converts from the
+/// user's balance at the time it runs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitOrder {
+    pub id: u64,
+    pub user: Address,
+    pub from_currency: Currency,
+    pub to_currency: Currency,
+    /// Source amount still to convert
+    pub remaining: i128,
+    /// Lowest spread-adjusted rate the order fills at (scaled by 10^8)
+    pub min_rate: i128,
+    pub placed_at: u64,
+}
+
 /// Status of conversion transaction
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -117,6 +229,36 @@ pub enum ConversionEvent {
     RateLocked(Currency, Currency, i128, u64),
     /// Fee collected
     FeeCollected(Currency, i128, Address),
+    /// Admin-posted rate for a pair deleted
+    RateRemoved(Currency, Currency),
+    /// Pair taken offline for conversions
+    PairSuspended(Currency, Currency),
+    /// Suspended pair brought back online
+    PairResumed(Currency, Currency),
+    /// Number of decimals a currency's amounts are denominated in
+    DecimalsSet(Currency, u32),
+    /// Leftover below the dust threshold moved from a user to the fee collector
+    DustSwept(Address, Currency, i128),
+    /// Spread set on a currency pair, in basis points of the mid rate
+    SpreadSet(Currency, Currency, u32),
+    /// Spread earned on a conversion, in the target currency
+    SpreadCollected(Symbol, Currency, i128),
+    /// Balance released for payout
+    Withdrawn(Address, Currency, i128),
+    /// Withdrawal held until the timestamp given
+    WithdrawalQueued(u64, Address, Currency, i128, u64),
+    /// Queued withdrawal cancelled, and by whom
+    WithdrawalCancelled(u64, Address),
+    /// Promo code, by hash, redeemed by a user on a conversion
+    PromoRedeemed(BytesN<32>, Address, Symbol),
+    /// Balance paid out in the backing token to an external account
+    PaidOut(Address, Currency, i128, Address),
+    /// Limit order placed: id, user, source, target, amount and trigger rate
+    LimitOrderPlaced(u64, Address, Currency, Currency, i128, i128),
+    /// Part of a limit order converted, and the source amount left on it
+    LimitOrderFilled(u64, Symbol, i128, i128),
+    /// Limit order withdrawn by its user
+    LimitOrderCancelled(u64),
 }
 
 /// Storage keys for the contract
@@ -135,6 +277,50 @@ pub enum DataKey {
     TxCounter,
     /// Supported currencies list
     SupportedCurrencies,
+    /// Price oracle that replaces admin-posted rates when set
+    PriceOracle,
+    /// SEP-40 feed for a currency pair
+    RateFeed(Currency, Currency),
+    /// KYC requirements for large conversions
+    CompliancePolicy,
+    /// Guardian whose conversion breaker halts this contract
+    Guardian,
+    /// Loyalty ledger that awards points on conversions and discounts fees
+    Loyalty,
+    /// Referral program credited with a share of each conversion fee
+    Referrals,
+    /// Currency pair taken offline regardless of its rate source
+    SuspendedPair(Currency, Currency),
+    /// Decimals a currency's amounts and balances are denominated in
+    Decimals(Currency),
+    /// Smallest balance or conversion output worth keeping in a currency
+    DustThreshold(Currency),
+    /// Liquidity pool manager whose providers earn a share of conversion fees
+    PoolManager,
+    /// Spread in basis points taken off the mid rate of a currency pair
+    Spread(Currency, Currency),
+    /// Number of statement entries for a user's currency balance
+    BalanceHistoryLen(Address, Currency),
+    /// Statement entry for a user's currency balance, by position
+    BalanceHistory(Address, Currency, u32),
+    /// Withdrawal guard a user has in force
+    WithdrawalGuard(Address),
+    /// Queued change to a user's withdrawal guard
+    PendingGuardChange(Address),
+    /// Withdrawal in its cooling-off period
+    Withdrawal(u64),
+    /// Withdrawal counter
+    WithdrawalCounter,
+    /// Part of an opted-in balance supplied to the currency's liquidity pool
+    AutoSupplied(Address, Currency),
+    /// Token held by this contract that backs balances in a currency
+    BackingToken(Currency),
+    /// Address book whose whitelist-only users may only pay approved accounts
+    AddressBook,
+    /// Resting limit order
+    LimitOrder(u64),
+    /// Limit order counter
+    LimitOrderCounter,
 }
 
 #[contract]
@@ -148,8 +334,28 @@ contractmeta!(
 
 const RATE_PRECISION: i128 = 100_000_000; // 10^8 for rate precision
 const MAX_FEE_BPS: u32 = 1000; // Maximum 10% fee
+const MAX_SPREAD_BPS: u32 = 1000; // Maximum 10% spread
+const MAX_PAGE_SIZE: u32 = 50;
+/// Longest cooling-off period a withdrawal guard may impose
+pub const MAX_WITHDRAWAL_DELAY: u64 = 7 * 24 * 60 * 60;
+/// Precision conversion math runs at; amounts in currencies with registered
+/// decimals are scaled up to it before the rate is applied
+pub const NORMALIZED_DECIMALS: u32 = 18;
+
+// Bump when the storage layout changes and add a step to `migrate_conversion`
+const STORAGE_VERSION: u32 = 2;
 
 impl Currency {
+    /// Every currency, in declaration order
+    pub const ALL: [Currency; 6] = [
+        Currency::NGN,
+        Currency::USD,
+        Currency::EUR,
+        Currency::GBP,
+        Currency::BTC,
+        Currency::ETH,
+    ];
+
     pub fn to_string(&self, env: &Env) -> SorobanString {
         match self {
             Currency::NGN => SorobanString::from_str(env, "NGN"),
@@ -178,14 +384,6 @@ impl ConversionContract {
         validate_address(&env, &admin).unwrap();
         validate_address(&env, &fee_collector).unwrap();
 
-        if fee_bps > MAX_FEE_BPS {
-            panic!("Fee too high, maximum is {} basis points", MAX_FEE_BPS);
-        }
-
-        if min_amount <= 0 || max_amount <= min_amount {
-            panic!("Invalid conversion amount limits");
-        }
-
         let config = PlatformConfig {
             admin: admin.clone(),
             fee_bps,
@@ -194,6 +392,10 @@ impl ConversionContract {
             max_conversion_amount: max_amount,
             rate_lock_duration: 300, // 5 minutes default
         };
+        Self::check_config(&env, &config);
+
+        AccessControl::init_owner(&env, &admin);
+        Upgrade::init_version(&env, STORAGE_VERSION);
 
         // Initialize supported currencies
         let mut currencies = Vec::new(&env);
@@ -213,21 +415,34 @@ impl ConversionContract {
         config
     }
 
-    /// Update exchange rate for a currency pair
+    /// Update exchange rate for a currency pair (rate feeder only)
     pub fn update_rate(
         env: Env,
+        caller: Address,
         from_currency: Currency,
         to_currency: Currency,
         rate: i128,
         validity_duration: u64,
     ) -> ExchangeRate {
-        let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
-
-        // Only admin can update rates
-        config.admin.require_auth();
+        AccessControl::require_audited_role(
+            &env,
+            Role::RateFeeder,
+            &caller,
+            "update_rate",
+            (
+                from_currency.clone(),
+                to_currency.clone(),
+                rate,
+                validity_duration,
+            ),
+        );
 
         if rate <= 0 {
-            panic!("Exchange rate must be positive");
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Exchange rate must be positive",
+            );
         }
 
         let exchange_rate = ExchangeRate {
@@ -259,28 +474,26 @@ impl ConversionContract {
     }
 
     /// Lock exchange rate for a transaction
-    pub fn conversion_rate(env: Env, from_currency: Currency, to_currency: Currency) -> ExchangeRate {
+    pub fn conversion_rate(
+        env: Env,
+        from_currency: Currency,
+        to_currency: Currency,
+    ) -> ExchangeRate {
         let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
-        let mut rate_info: ExchangeRate = env
-            .storage()
-            .instance()
-            .get(&DataKey::Rate(from_currency.clone(), to_currency.clone()))
-            .unwrap_or_else(|| panic!("Exchange rate not found"));
-
-        // Check if rate is still valid
+        let mut rate_info = Self::current_rate(&env, &from_currency, &to_currency);
         let current_time = env.ledger().timestamp();
-        if current_time > rate_info.updated_at + rate_info.validity_duration {
-            panic!("Exchange rate has expired");
-        }
 
         // Lock the rate
         rate_info.is_locked = true;
         let locked_until = current_time + config.rate_lock_duration;
 
-        env.storage().instance().set(
-            &DataKey::Rate(from_currency.clone(), to_currency.clone()),
-            &rate_info,
-        );
+        // Oracle prices are not cached in the admin rate slot
+        if Self::get_price_oracle(env.clone()).is_none() {
+            env.storage().instance().set(
+                &DataKey::Rate(from_currency.clone(), to_currency.clone()),
+                &rate_info,
+            );
+        }
 
         // Emit rate locked event
         publish(
@@ -291,6 +504,33 @@ impl ConversionContract {
         rate_info
     }
 
+    /// Convert under a client-generated idempotency key. Repeating a key the
+    /// user already used returns the original transaction instead of
+    /// converting again.
+    pub fn convert_currency_idempotent(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+        key: BytesN<32>,
+    ) -> ConversionTx {
+        if let Some(tx_id) = Idempotency::recall(&env, &user, &key) {
+            user.require_auth();
+            return Self::get_transaction(env, tx_id);
+        }
+
+        let tx = Self::convert_currency(
+            env.clone(),
+            user.clone(),
+            from_currency,
+            to_currency,
+            amount,
+        );
+        Idempotency::remember(&env, &user, &key, &tx.tx_id);
+        tx
+    }
+
     /// Perform currency conversion
     pub fn convert_currency(
         env: Env,
@@ -300,9 +540,239 @@ impl ConversionContract {
         amount: i128,
     ) -> ConversionTx {
         user.require_auth();
+        Inheritance::record_activity(&env, &user);
+        Self::convert(env, user, from_currency, to_currency, amount, 0)
+    }
+
+    /// Convert with the backend's payment reference on the conversion's events
+    pub fn convert_currency_with_reference(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+        reference: BytesN<32>,
+    ) -> ConversionTx {
+        PaymentReference::attach(&env, &reference, || {
+            Self::convert_currency(env.clone(), user, from_currency, to_currency, amount)
+        })
+    }
+
+    /// Convert with a promo code that waives part or all of the platform fee.
+    /// Each call uses up one of the code's redemptions.
+    pub fn convert_currency_with_promo(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+        code: Bytes,
+    ) -> ConversionTx {
+        user.require_auth();
+        Inheritance::record_activity(&env, &user);
+        let (code_hash, discount_bps) = Promos::redeem(&env, &code);
+        let tx = Self::convert(
+            env.clone(),
+            user.clone(),
+            from_currency,
+            to_currency,
+            amount,
+            discount_bps,
+        );
+        publish(
+            &env,
+            ConversionEvent::PromoRedeemed(code_hash, user, tx.tx_id.clone()),
+        );
+        tx
+    }
+
+    /// Register a promo code by its SHA-256 hash (owner only)
+    pub fn issue_promo_code(
+        env: Env,
+        caller: Address,
+        code_hash: BytesN<32>,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> PromoCode {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "issue_promo_code",
+            (code_hash.clone(), discount_bps, max_uses, expires_at),
+        );
+        Promos::issue(&env, &code_hash, discount_bps, max_uses, expires_at)
+    }
+
+    /// Withdraw a promo code before it runs out (owner only)
+    pub fn revoke_promo_code(env: Env, caller: Address, code_hash: BytesN<32>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "revoke_promo_code",
+            code_hash.clone(),
+        );
+        Promos::revoke(&env, &code_hash);
+    }
+
+    pub fn get_promo_code(env: Env, code_hash: BytesN<32>) -> Option<PromoCode> {
+        Promos::get(&env, &code_hash)
+    }
+
+    /// Leave an instruction to convert `amount` once the pair's rate, after
+    /// spread, is at least `min_rate` (user auth). Keepers fill it, in full
+    /// or in parts, while the rate holds.
+    pub fn place_limit_order(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+        min_rate: i128,
+    ) -> LimitOrder {
+        Self::require_operational(&env);
+        user.require_auth();
+        if from_currency == to_currency {
+            fail(
+                &env,
+                AppError::UnsupportedCurrency,
+                "Cannot convert to the same currency",
+            );
+        }
+        if amount <= 0 {
+            fail(&env, AppError::InvalidAmount, "Amount must be positive");
+        }
+        let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
+        if amount < config.min_conversion_amount {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Amount below minimum conversion limit",
+            );
+        }
+        if min_rate <= 0 {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Trigger rate must be positive",
+            );
+        }
+
+        let id = env
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::LimitOrderCounter)
+            .unwrap_or(0)
+            + 1;
+        let order = LimitOrder {
+            id,
+            user: user.clone(),
+            from_currency: from_currency.clone(),
+            to_currency: to_currency.clone(),
+            remaining: amount,
+            min_rate,
+            placed_at: env.ledger().timestamp(),
+        };
+        storage::write(&env, &DataKey::LimitOrder(id), &order);
+        env.storage()
+            .instance()
+            .set(&DataKey::LimitOrderCounter, &id);
+        publish(
+            &env,
+            ConversionEvent::LimitOrderPlaced(
+                id,
+                user,
+                from_currency,
+                to_currency,
+                amount,
+                min_rate,
+            ),
+        );
+        order
+    }
+
+    /// Convert up to `amount` of a limit order whose trigger rate has been
+    /// reached. Callable by anyone, so keepers can watch the rate and fill
+    /// orders in sizes the conversion limits and the user's balance allow.
+    pub fn execute_limit_order(env: Env, order_id: u64, amount: i128) -> ConversionTx {
+        let mut order = Self::get_limit_order(env.clone(), order_id)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Limit order not found"));
+        if amount <= 0 {
+            fail(&env, AppError::InvalidAmount, "Amount must be positive");
+        }
+        let mid_rate = Self::current_rate(&env, &order.from_currency, &order.to_currency).rate;
+        if Self::spread_rate(&env, &order.from_currency, &order.to_currency, mid_rate)
+            < order.min_rate
+        {
+            fail(&env, AppError::InvalidState, "Trigger rate not reached");
+        }
+
+        // A remainder too small to ever convert on its own goes with this fill
+        let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
+        let mut fill = amount.min(order.remaining);
+        if order.remaining - fill < config.min_conversion_amount {
+            fill = order.remaining;
+        }
+        order.remaining -= fill;
+        if order.remaining == 0 {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::LimitOrder(order_id));
+        } else {
+            storage::write(&env, &DataKey::LimitOrder(order_id), &order);
+        }
+        let tx = Self::convert(
+            env.clone(),
+            order.user,
+            order.from_currency,
+            order.to_currency,
+            fill,
+            0,
+        );
+        publish(
+            &env,
+            ConversionEvent::LimitOrderFilled(order_id, tx.tx_id.clone(), fill, order.remaining),
+        );
+        tx
+    }
+
+    /// Withdraw a limit order's unfilled remainder (user auth)
+    pub fn cancel_limit_order(env: Env, user: Address, order_id: u64) {
+        user.require_auth();
+        let order = Self::get_limit_order(env.clone(), order_id)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Limit order not found"));
+        if order.user != user {
+            fail(&env, AppError::Unauthorized, "Not the order's owner");
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LimitOrder(order_id));
+        publish(&env, ConversionEvent::LimitOrderCancelled(order_id));
+    }
+
+    pub fn get_limit_order(env: Env, order_id: u64) -> Option<LimitOrder> {
+        storage::read(&env, &DataKey::LimitOrder(order_id))
+    }
+
+    fn convert(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+        fee_discount_bps: u32,
+    ) -> ConversionTx {
+        Self::require_operational(&env);
 
         // Validate conversion parameters
         Self::validate_conversion(&env, &from_currency, &to_currency, amount);
+        if let Some(policy) = Self::get_conversion_compliance_policy(env.clone()) {
+            if !ComplianceCheck::is_allowed(&env, &policy, &user, amount) {
+                fail(&env, AppError::Unauthorized, "KYC tier too low");
+            }
+        }
 
         // Get and validate user balance
         let mut user_balance = Self::get_or_create_user_balance(&env, &user);
@@ -312,27 +782,50 @@ impl ConversionContract {
             .unwrap_or(0);
 
         if current_balance < amount {
-            panic!("Insufficient balance for conversion");
+            fail(
+                &env,
+                AppError::InsufficientBalance,
+                "Insufficient balance for conversion",
+            );
         }
 
         // Get exchange rate
-        let rate_info: ExchangeRate = env
-            .storage()
-            .instance()
-            .get(&DataKey::Rate(from_currency.clone(), to_currency.clone()))
-            .unwrap_or_else(|| panic!("Exchange rate not found"));
-
-        // Validate rate is not expired
+        let rate_info = Self::current_rate(&env, &from_currency, &to_currency);
         let current_time = env.ledger().timestamp();
-        if current_time > rate_info.updated_at + rate_info.validity_duration {
-            panic!("Exchange rate has expired");
-        }
 
-        // Calculate conversion amounts
-        let converted_amount = (amount * rate_info.rate) / RATE_PRECISION;
+        // Calculate conversion amounts at the pair's spread-adjusted rate; what
+        // the spread keeps back from the mid-rate amount goes to the target
+        // pool's reward reserve, or to the fee collector when it can't
+        let rate = Self::spread_rate(&env, &from_currency, &to_currency, rate_info.rate);
+        let mid_amount =
+            Self::convert_amount(&env, &from_currency, &to_currency, amount, rate_info.rate);
+        let converted_amount =
+            Self::convert_amount(&env, &from_currency, &to_currency, amount, rate);
+        let spread = or_overflow(&env, checked_sub(mid_amount, converted_amount));
         let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
-        let platform_fee = Self::calculate_fee(converted_amount, config.fee_bps);
-        let amount_received = converted_amount - platform_fee;
+        let mut platform_fee = Promos::discounted(
+            or_overflow(&env, Self::calculate_fee(converted_amount, config.fee_bps)),
+            fee_discount_bps,
+        );
+        let loyalty = Self::get_conversion_loyalty(env.clone());
+        if let Some(loyalty) = &loyalty {
+            if platform_fee > 0 {
+                let credit = LoyaltyContractClient::new(&env, loyalty).apply_loyalty_fee_credit(
+                    &env.current_contract_address(),
+                    &user,
+                    &platform_fee,
+                );
+                platform_fee = or_overflow(&env, checked_sub(platform_fee, credit));
+            }
+        }
+        let amount_received = or_overflow(&env, checked_sub(converted_amount, platform_fee));
+        if amount_received < Self::get_dust_threshold(env.clone(), to_currency.clone()) {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Conversion output below dust threshold",
+            );
+        }
 
         // Generate transaction ID
         let tx_counter: u64 = env
@@ -345,19 +838,79 @@ impl ConversionContract {
         write!(&mut s, "{}", tx_counter + 1).unwrap();
         let tx_id = Symbol::new(&env, s.as_str());
 
-        // Update user balances atomically
+        // Update user balances atomically. A source leftover too small to ever
+        // convert is swept to the fee collector rather than left stranded.
+        let mut from_balance = or_overflow(&env, checked_sub(current_balance, amount));
+        let mut dust = 0;
+        if from_balance > 0
+            && from_balance < Self::get_dust_threshold(env.clone(), from_currency.clone())
+            && user != config.fee_collector
+        {
+            dust = from_balance;
+            from_balance = 0;
+        }
+        Self::ensure_on_hand(
+            &env,
+            &user,
+            &from_currency,
+            current_balance,
+            current_balance - from_balance,
+        );
         user_balance
             .balances
-            .set(from_currency.clone(), current_balance - amount);
+            .set(from_currency.clone(), from_balance);
         let to_balance = user_balance.balances.get(to_currency.clone()).unwrap_or(0);
-        user_balance
-            .balances
-            .set(to_currency.clone(), to_balance + amount_received);
+        user_balance.balances.set(
+            to_currency.clone(),
+            or_overflow(&env, checked_add(to_balance, amount_received)),
+        );
         user_balance.updated_at = current_time;
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Balance(user.clone()), &user_balance);
+        storage::write(&env, &DataKey::Balance(user.clone()), &user_balance);
+        Self::journal(
+            &env,
+            &user,
+            &from_currency,
+            BalanceChangeKind::ConversionOut,
+            -amount,
+            from_balance + dust,
+            Some(tx_id.clone()),
+        );
+        if dust > 0 {
+            Self::journal(
+                &env,
+                &user,
+                &from_currency,
+                BalanceChangeKind::DustSwept,
+                -dust,
+                from_balance,
+                Some(tx_id.clone()),
+            );
+        }
+        Self::journal(
+            &env,
+            &user,
+            &to_currency,
+            BalanceChangeKind::ConversionIn,
+            amount_received,
+            user_balance.balances.get(to_currency.clone()).unwrap_or(0),
+            Some(tx_id.clone()),
+        );
+        Self::supply_idle(&env, &user, &to_currency);
+        if dust > 0 {
+            Self::credit(
+                &env,
+                &config.fee_collector,
+                &from_currency,
+                dust,
+                BalanceChangeKind::DustReceived,
+                Some(tx_id.clone()),
+            );
+            publish(
+                &env,
+                ConversionEvent::DustSwept(user.clone(), from_currency.clone(), dust),
+            );
+        }
 
         // Create conversion transaction record
         let conversion_tx = ConversionTx {
@@ -366,7 +919,7 @@ impl ConversionContract {
             from_currency: from_currency.clone(),
             to_currency: to_currency.clone(),
             amount,
-            rate: rate_info.rate,
+            rate,
             amount_received,
             platform_fee,
             timestamp: current_time,
@@ -374,9 +927,7 @@ impl ConversionContract {
         };
 
         // Store transaction
-        env.storage()
-            .instance()
-            .set(&DataKey::Transaction(tx_id.clone()), &conversion_tx);
+        storage::write(&env, &DataKey::Transaction(tx_id.clone()), &conversion_tx);
         env.storage()
             .instance()
             .set(&DataKey::TxCounter, &(tx_counter + 1));
@@ -386,6 +937,68 @@ impl ConversionContract {
             Self::collect_platform_fee(&env, &to_currency, platform_fee, &config.fee_collector);
         }
 
+        // Report the fee and spread so the target pool's providers accrue their
+        // share; the pool takes their share in its token from this contract
+        let mut spread_to_pool = false;
+        if let Some(pool_manager) = Self::get_conversion_pool_manager(env.clone()) {
+            let pools = PoolManagerContractClient::new(&env, &pool_manager);
+            let reward = if platform_fee > 0 {
+                pools.get_pool_fee_reward(&to_currency, &platform_fee)
+            } else {
+                0
+            };
+            if reward > 0 && Self::authorize_pool_pull(&env, &pool_manager, &to_currency, reward) {
+                pools.report_pool_fees(
+                    &env.current_contract_address(),
+                    &to_currency,
+                    &platform_fee,
+                );
+            }
+            if spread > 0 && Self::authorize_pool_pull(&env, &pool_manager, &to_currency, spread) {
+                spread_to_pool = pools.report_pool_spread(
+                    &env.current_contract_address(),
+                    &to_currency,
+                    &spread,
+                ) > 0;
+            }
+        }
+        if spread > 0 {
+            if !spread_to_pool {
+                Self::credit(
+                    &env,
+                    &config.fee_collector,
+                    &to_currency,
+                    spread,
+                    BalanceChangeKind::SpreadReceived,
+                    Some(tx_id.clone()),
+                );
+            }
+            publish(
+                &env,
+                ConversionEvent::SpreadCollected(tx_id.clone(), to_currency.clone(), spread),
+            );
+        }
+
+        // Report the fee so the user's referrers accrue their commission
+        if platform_fee > 0 {
+            if let Some(referrals) = Self::get_conversion_referrals(env.clone()) {
+                ReferralContractClient::new(&env, &referrals).report_referral_fee(
+                    &env.current_contract_address(),
+                    &user,
+                    &platform_fee,
+                );
+            }
+        }
+
+        // Award loyalty points on the volume converted
+        if let Some(loyalty) = &loyalty {
+            LoyaltyContractClient::new(&env, loyalty).award_loyalty_points(
+                &env.current_contract_address(),
+                &user,
+                &amount,
+            );
+        }
+
         // Emit conversion completed event
         publish(
             &env,
@@ -419,10 +1032,8 @@ impl ConversionContract {
 
     /// Get conversion transaction details
     pub fn get_transaction(env: Env, tx_id: Symbol) -> ConversionTx {
-        env.storage()
-            .instance()
-            .get(&DataKey::Transaction(tx_id))
-            .unwrap_or_else(|| panic!("Transaction not found"))
+        storage::read(&env, &DataKey::Transaction(tx_id))
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Transaction not found"))
     }
 
     /// Get current exchange rate
@@ -430,51 +1041,1013 @@ impl ConversionContract {
         env.storage()
             .instance()
             .get(&DataKey::Rate(from_currency, to_currency))
-            .unwrap_or_else(|| panic!("Exchange rate not found"))
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Exchange rate not found"))
     }
 
-    /// Get platform configuration
-    pub fn get_conversion_config(env: Env) -> PlatformConfig {
-        env.storage()
-            .instance()
-            .get(&DataKey::Config)
-            .unwrap_or_else(|| panic!("Contract not initialized"))
+    /// Delete the admin-posted rate for a pair so it can no longer be used,
+    /// e.g. when the currency is retired (owner only)
+    pub fn remove_rate(env: Env, caller: Address, from_currency: Currency, to_currency: Currency) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "remove_rate",
+            (from_currency.clone(), to_currency.clone()),
+        );
+        let key = DataKey::Rate(from_currency.clone(), to_currency.clone());
+        if !env.storage().instance().has(&key) {
+            fail(&env, AppError::NotFound, "Exchange rate not found");
+        }
+        env.storage().instance().remove(&key);
+
+        publish(
+            &env,
+            ConversionEvent::RateRemoved(from_currency, to_currency),
+        );
     }
 
-    pub fn deposit(env: Env, user: Address, currency: Currency, amount: i128) {
-        let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
+    /// Take a pair offline: rate locks and conversions fail whatever the rate
+    /// source until the pair is resumed (pauser only)
+    pub fn suspend_pair(env: Env, caller: Address, from_currency: Currency, to_currency: Currency) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Pauser,
+            &caller,
+            "suspend_pair",
+            (from_currency.clone(), to_currency.clone()),
+        );
+        env.storage().instance().set(
+            &DataKey::SuspendedPair(from_currency.clone(), to_currency.clone()),
+            &true,
+        );
 
-        // Either admin or the user themselves can deposit
-        if env.current_contract_address() != config.admin {
-            user.require_auth();
-        } else {
-            config.admin.require_auth();
+        publish(
+            &env,
+            ConversionEvent::PairSuspended(from_currency, to_currency),
+        );
+    }
+
+    /// Bring a suspended pair back online (pauser only)
+    pub fn resume_pair(env: Env, caller: Address, from_currency: Currency, to_currency: Currency) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Pauser,
+            &caller,
+            "resume_pair",
+            (from_currency.clone(), to_currency.clone()),
+        );
+        let key = DataKey::SuspendedPair(from_currency.clone(), to_currency.clone());
+        if !env.storage().instance().has(&key) {
+            fail(&env, AppError::InvalidState, "Currency pair not suspended");
         }
+        env.storage().instance().remove(&key);
 
-        validate_positive_amount(amount).unwrap();
+        publish(
+            &env,
+            ConversionEvent::PairResumed(from_currency, to_currency),
+        );
+    }
 
-        let mut user_balance = Self::get_or_create_user_balance(&env, &user);
-        let current_balance = user_balance.balances.get(currency.clone()).unwrap_or(0);
-        user_balance
-            .balances
-            .set(currency, current_balance + amount);
-        user_balance.updated_at = env.ledger().timestamp();
+    pub fn is_pair_suspended(env: Env, from_currency: Currency, to_currency: Currency) -> bool {
+        env.storage()
+            .instance()
+            .has(&DataKey::SuspendedPair(from_currency, to_currency))
+    }
 
+    /// Register how many decimals `currency` amounts are denominated in, e.g. 2
+    /// for NGN or 18 for ETH (owner only). Conversions between two registered
+    /// currencies scale through `NORMALIZED_DECIMALS`, so their rate is the price
+    /// of one whole unit; a pair with an unregistered side converts raw integers.
+    pub fn set_currency_decimals(env: Env, caller: Address, currency: Currency, decimals: u32) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_currency_decimals",
+            (currency.clone(), decimals),
+        );
+        if decimals > NORMALIZED_DECIMALS {
+            fail(&env, AppError::InvalidConfig, "Too many decimals");
+        }
         env.storage()
             .instance()
-            .set(&DataKey::Balance(user), &user_balance);
+            .set(&DataKey::Decimals(currency.clone()), &decimals);
+        publish(&env, ConversionEvent::DecimalsSet(currency, decimals));
     }
 
-    // Private helper methods
+    pub fn get_currency_decimals(env: Env, currency: Currency) -> Option<u32> {
+        env.storage().instance().get(&DataKey::Decimals(currency))
+    }
 
-    fn validate_conversion(
-        env: &Env,
-        from_currency: &Currency,
-        to_currency: &Currency,
-        amount: i128,
+    /// Set the smallest amount of `currency` worth holding, or clear it with
+    /// `None` (owner only). Conversions paying out less are rejected, and a
+    /// source balance left below it is swept to the fee collector.
+    pub fn set_dust_threshold(
+        env: Env,
+        caller: Address,
+        currency: Currency,
+        threshold: Option<i128>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_dust_threshold",
+            (currency.clone(), threshold),
+        );
+        let key = DataKey::DustThreshold(currency);
+        match threshold {
+            Some(threshold) if threshold < 0 => fail(
+                &env,
+                AppError::InvalidConfig,
+                "Dust threshold must not be negative",
+            ),
+            Some(threshold) => env.storage().instance().set(&key, &threshold),
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    pub fn get_dust_threshold(env: Env, currency: Currency) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DustThreshold(currency))
+            .unwrap_or(0)
+    }
+
+    /// Read rates from a price oracle instead of admin-posted rates, or clear it
+    /// to go back to admin rates (owner only)
+    pub fn set_price_oracle(env: Env, caller: Address, oracle: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_price_oracle",
+            oracle.clone(),
+        );
+        match oracle {
+            Some(oracle) => env.storage().instance().set(&DataKey::PriceOracle, &oracle),
+            None => env.storage().instance().remove(&DataKey::PriceOracle),
+        }
+    }
+
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PriceOracle)
+    }
+
+    /// Require a KYC tier for conversions at or above the policy threshold,
+    /// given in source currency units, or clear it (owner only)
+    pub fn set_conversion_compliance_policy(
+        env: Env,
+        caller: Address,
+        policy: Option<CompliancePolicy>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_compliance_policy",
+            policy.clone(),
+        );
+        match policy {
+            Some(policy) => env
+                .storage()
+                .instance()
+                .set(&DataKey::CompliancePolicy, &policy),
+            None => env.storage().instance().remove(&DataKey::CompliancePolicy),
+        }
+    }
+
+    pub fn get_conversion_compliance_policy(env: Env) -> Option<CompliancePolicy> {
+        env.storage().instance().get(&DataKey::CompliancePolicy)
+    }
+
+    /// Set or clear the guardian whose conversion breaker halts conversions
+    /// and deposits (owner only)
+    pub fn set_conversion_guardian(env: Env, caller: Address, guardian: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_guardian",
+            guardian.clone(),
+        );
+        match guardian {
+            Some(guardian) => env.storage().instance().set(&DataKey::Guardian, &guardian),
+            None => env.storage().instance().remove(&DataKey::Guardian),
+        }
+    }
+
+    pub fn get_conversion_guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Guardian)
+    }
+
+    /// Set or clear the loyalty ledger that awards points on conversions and
+    /// applies redeemed fee credit (owner only). This contract must hold the
+    /// ledger's minter role.
+    pub fn set_conversion_loyalty(env: Env, caller: Address, loyalty: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_loyalty",
+            loyalty.clone(),
+        );
+        match loyalty {
+            Some(loyalty) => env.storage().instance().set(&DataKey::Loyalty, &loyalty),
+            None => env.storage().instance().remove(&DataKey::Loyalty),
+        }
+    }
+
+    pub fn get_conversion_loyalty(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Loyalty)
+    }
+
+    /// Set or clear the referral program conversion fees are reported to
+    /// (owner only). This contract must hold the program's minter role.
+    pub fn set_conversion_referrals(env: Env, caller: Address, referrals: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_referrals",
+            referrals.clone(),
+        );
+        match referrals {
+            Some(referrals) => env
+                .storage()
+                .instance()
+                .set(&DataKey::Referrals, &referrals),
+            None => env.storage().instance().remove(&DataKey::Referrals),
+        }
+    }
+
+    pub fn get_conversion_referrals(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Referrals)
+    }
+
+    /// Set or clear the pool manager credited with fees (owner only). This
+    /// contract must hold the pool manager's `Minter` role to report fees.
+    pub fn set_conversion_pool_manager(env: Env, caller: Address, pool_manager: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_pool_manager",
+            pool_manager.clone(),
+        );
+        match pool_manager {
+            Some(pool_manager) => env
+                .storage()
+                .instance()
+                .set(&DataKey::PoolManager, &pool_manager),
+            None => env.storage().instance().remove(&DataKey::PoolManager),
+        }
+    }
+
+    pub fn get_conversion_pool_manager(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PoolManager)
+    }
+
+    /// Set or clear the token paid out for a currency by `payout_external`
+    /// (owner only). It must share the currency's decimals, and this contract
+    /// must hold enough of it to cover payouts.
+    pub fn set_backing_token(
+        env: Env,
+        caller: Address,
+        currency: Currency,
+        token: Option<Address>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_backing_token",
+            (currency.clone(), token.clone()),
+        );
+        let key = DataKey::BackingToken(currency);
+        match token {
+            Some(token) => env.storage().instance().set(&key, &token),
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    pub fn get_backing_token(env: Env, currency: Currency) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::BackingToken(currency))
+    }
+
+    /// Set or clear the address book checked on external payouts (owner only)
+    pub fn set_conversion_address_book(env: Env, caller: Address, address_book: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_address_book",
+            address_book.clone(),
+        );
+        match address_book {
+            Some(address_book) => env
+                .storage()
+                .instance()
+                .set(&DataKey::AddressBook, &address_book),
+            None => env.storage().instance().remove(&DataKey::AddressBook),
+        }
+    }
+
+    pub fn get_conversion_address_book(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::AddressBook)
+    }
+
+    /// Set the spread, in basis points, taken off the mid rate when converting
+    /// `from_currency` into `to_currency`, or clear it with `None` (operator
+    /// only). The spread is paid to the target currency's liquidity providers,
+    /// or credited to the fee collector while that pool has none.
+    pub fn set_pair_spread(
+        env: Env,
+        caller: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        spread_bps: Option<u32>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_pair_spread",
+            (from_currency.clone(), to_currency.clone(), spread_bps),
+        );
+        let key = DataKey::Spread(from_currency.clone(), to_currency.clone());
+        match spread_bps {
+            Some(spread_bps) if Bps::capped(spread_bps, MAX_SPREAD_BPS).is_err() => {
+                fail(&env, AppError::InvalidConfig, "Spread too high")
+            }
+            Some(spread_bps) => env.storage().instance().set(&key, &spread_bps),
+            None => env.storage().instance().remove(&key),
+        }
+        publish(
+            &env,
+            ConversionEvent::SpreadSet(from_currency, to_currency, spread_bps.unwrap_or(0)),
+        );
+    }
+
+    pub fn get_pair_spread(env: Env, from_currency: Currency, to_currency: Currency) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Spread(from_currency, to_currency))
+            .unwrap_or(0)
+    }
+
+    /// Name who inherits the caller's balances after `inactivity_period` seconds
+    /// without activity, or clear the plan with `None`
+    pub fn set_conversion_beneficiary(
+        env: Env,
+        owner: Address,
+        beneficiary: Option<Address>,
+        inactivity_period: u64,
+    ) {
+        owner.require_auth();
+        Inheritance::set_plan(&env, &owner, beneficiary, inactivity_period);
+    }
+
+    pub fn get_conversion_inheritance(env: Env, owner: Address) -> Option<InheritancePlan> {
+        Inheritance::get_plan(&env, &owner)
+    }
+
+    /// Start the notice period on a claim against an inactive owner
+    pub fn announce_conversion_inheritance(env: Env, beneficiary: Address, owner: Address) {
+        Inheritance::announce_claim(&env, &owner, &beneficiary);
+    }
+
+    /// Move every balance of an inactive owner to the beneficiary once the
+    /// announced claim's notice period has passed
+    pub fn claim_conversion_inheritance(
+        env: Env,
+        beneficiary: Address,
+        owner: Address,
+    ) -> UserBalance {
+        Self::require_operational(&env);
+        Inheritance::take_claim(&env, &owner, &beneficiary);
+
+        let inherited = Self::get_or_create_user_balance(&env, &owner);
+        let mut user_balance = Self::get_or_create_user_balance(&env, &beneficiary);
+        for (currency, amount) in inherited.balances.iter() {
+            let current_balance = user_balance.balances.get(currency.clone()).unwrap_or(0);
+            let new_balance = or_overflow(&env, checked_add(current_balance, amount));
+            user_balance.balances.set(currency.clone(), new_balance);
+            Self::ensure_on_hand(&env, &owner, &currency, amount, amount);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::AutoSupplied(owner.clone(), currency.clone()));
+            if amount != 0 {
+                Self::journal(
+                    &env,
+                    &owner,
+                    &currency,
+                    BalanceChangeKind::Bequeathed,
+                    -amount,
+                    0,
+                    None,
+                );
+                Self::journal(
+                    &env,
+                    &beneficiary,
+                    &currency,
+                    BalanceChangeKind::Inherited,
+                    amount,
+                    new_balance,
+                    None,
+                );
+            }
+        }
+        user_balance.updated_at = env.ledger().timestamp();
+
+        env.storage().persistent().remove(&DataKey::Balance(owner));
+        storage::write(&env, &DataKey::Balance(beneficiary.clone()), &user_balance);
+        for currency in user_balance.balances.keys() {
+            Self::supply_idle(&env, &beneficiary, &currency);
+        }
+        user_balance
+    }
+
+    /// Read a pair's rate from a SEP-40 feed such as Reflector, or clear it
+    /// (owner only). Stale feed prices fall back to the regular rate source.
+    pub fn set_rate_feed(
+        env: Env,
+        caller: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        feed: Option<RateFeed>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_rate_feed",
+            (from_currency.clone(), to_currency.clone(), feed.clone()),
+        );
+        let key = DataKey::RateFeed(from_currency, to_currency);
+        match feed {
+            Some(feed) => env.storage().instance().set(&key, &feed),
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    pub fn get_rate_feed(
+        env: Env,
+        from_currency: Currency,
+        to_currency: Currency,
+    ) -> Option<RateFeed> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RateFeed(from_currency, to_currency))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_conversion_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_conversion_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_conversion_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_conversion_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_conversion_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    /// Give up a role held by `account`
+    pub fn renounce_conversion_role(env: Env, account: Address, role: Role) {
+        AccessControl::renounce_role(&env, &account, role);
+    }
+
+    pub fn has_conversion_role(env: Env, role: Role, account: Address) -> bool {
+        AccessControl::has_role(&env, role, &account)
+    }
+
+    /// Replace the contract code (owner only)
+    pub fn upgrade_conversion(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        Upgrade::upgrade(&env, &caller, new_wasm_hash);
+    }
+
+    /// Bring storage written by older code up to the current layout
+    pub fn migrate_conversion(env: Env) -> u32 {
+        Upgrade::migrate(&env, STORAGE_VERSION, |env, version| {
+            // v1: admin checks moved to roles, so the existing admin becomes owner
+            if version == 1 {
+                if let Some(admin) = env
+                    .storage()
+                    .instance()
+                    .get::<_, PlatformConfig>(&DataKey::Config)
+                    .map(|config| config.admin)
+                {
+                    AccessControl::init_owner(env, &admin);
+                }
+            }
+            // v2: transactions and balances moved from instance to persistent
+            // storage. Balances are found through their owners' transactions;
+            // any others move on first use.
+            if version == 2 {
+                let count: u64 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::TxCounter)
+                    .unwrap_or(0);
+                for n in 1..=count {
+                    let mut s: HString<12> = HString::new();
+                    s.push_str("tx").unwrap();
+                    write!(&mut s, "{}", n).unwrap();
+                    let key = DataKey::Transaction(Symbol::new(env, s.as_str()));
+                    if let Some(tx) = storage::move_from_instance::<_, ConversionTx>(env, &key) {
+                        storage::move_from_instance::<_, UserBalance>(
+                            env,
+                            &DataKey::Balance(tx.user),
+                        );
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn get_conversion_storage_version(env: Env) -> u32 {
+        Upgrade::storage_version(&env)
+    }
+
+    /// Get platform configuration
+    pub fn get_conversion_config(env: Env) -> PlatformConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .unwrap_or_else(|| fail(&env, AppError::NotInitialized, "Contract not initialized"))
+    }
+
+    /// Change the fee and conversion limits (owner only). Take a snapshot
+    /// first to be able to roll the change back.
+    pub fn update_conversion_config(
+        env: Env,
+        caller: Address,
+        fee_bps: u32,
+        fee_collector: Address,
+        min_amount: i128,
+        max_amount: i128,
+    ) -> PlatformConfig {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "update_conversion_config",
+            (fee_bps, fee_collector.clone(), min_amount, max_amount),
+        );
+        validate_address(&env, &fee_collector).unwrap();
+
+        let config = PlatformConfig {
+            fee_bps,
+            fee_collector,
+            min_conversion_amount: min_amount,
+            max_conversion_amount: max_amount,
+            ..Self::get_conversion_config(env.clone())
+        };
+        Self::check_config(&env, &config);
+        env.storage().instance().set(&DataKey::Config, &config);
+        config
+    }
+
+    /// Save the configuration in force as a new snapshot version (owner only)
+    pub fn snapshot_conversion_config(env: Env, caller: Address) -> u32 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "snapshot_conversion_config",
+            (),
+        );
+        ConfigSnapshots::take(&env, &Self::get_conversion_config(env.clone()), &caller)
+    }
+
+    /// Put a snapshot's configuration back in force (owner only). The admin
+    /// stays as it is.
+    pub fn rollback_conversion_config(env: Env, caller: Address, version: u32) -> PlatformConfig {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "rollback_conversion_config",
+            version,
+        );
+        let snapshot: PlatformConfig = ConfigSnapshots::restore(&env, version, &caller)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Snapshot not found"));
+        let config = PlatformConfig {
+            admin: Self::get_conversion_config(env.clone()).admin,
+            ..snapshot
+        };
+        Self::check_config(&env, &config);
+        env.storage().instance().set(&DataKey::Config, &config);
+        config
+    }
+
+    pub fn get_conversion_config_snapshot(env: Env, version: u32) -> Option<SnapshotInfo> {
+        ConfigSnapshots::info(&env, version)
+    }
+
+    pub fn deposit(env: Env, user: Address, currency: Currency, amount: i128) {
+        Self::require_operational(&env);
+        let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
+
+        // Either admin or the user themselves can deposit
+        if env.current_contract_address() != config.admin {
+            user.require_auth();
+            Inheritance::record_activity(&env, &user);
+        } else {
+            config.admin.require_auth();
+        }
+
+        validate_positive_amount(amount).unwrap();
+
+        let mut user_balance = Self::get_or_create_user_balance(&env, &user);
+        let current_balance = user_balance.balances.get(currency.clone()).unwrap_or(0);
+        let new_balance = or_overflow(&env, checked_add(current_balance, amount));
+        user_balance.balances.set(currency.clone(), new_balance);
+        user_balance.updated_at = env.ledger().timestamp();
+
+        storage::write(&env, &DataKey::Balance(user.clone()), &user_balance);
+        Self::journal(
+            &env,
+            &user,
+            &currency,
+            BalanceChangeKind::Deposit,
+            amount,
+            new_balance,
+            None,
+        );
+        Self::supply_idle(&env, &user, &currency);
+    }
+
+    /// Debit a balance for payout by the platform. A withdrawal above the
+    /// user's guard threshold is queued for the guard's delay and its id
+    /// returned; anything else is released at once.
+    pub fn withdraw(env: Env, user: Address, currency: Currency, amount: i128) -> Option<u64> {
+        Self::require_operational(&env);
+        user.require_auth();
+        Self::withdraw_to(env, user, currency, amount, None)
+    }
+
+    /// Debit a balance and pay it out in the currency's backing token to any
+    /// Stellar account, such as a self-custody wallet that is not a NexaFx
+    /// user. Withdrawal guards apply as for `withdraw`; a queued payout is
+    /// sent when the withdrawal is executed.
+    pub fn payout_external(
+        env: Env,
+        user: Address,
+        currency: Currency,
+        amount: i128,
+        destination: Address,
+    ) -> Option<u64> {
+        Self::require_operational(&env);
+        user.require_auth();
+        Self::backing_token(&env, &currency);
+        if let Some(address_book) = Self::get_conversion_address_book(env.clone()) {
+            if !BeneficiaryCheck::is_approved(&env, &address_book, &user, &destination) {
+                fail(
+                    &env,
+                    AppError::Unauthorized,
+                    "Destination not in user's address book",
+                );
+            }
+        }
+        Self::withdraw_to(env, user, currency, amount, Some(destination))
+    }
+
+    fn withdraw_to(
+        env: Env,
+        user: Address,
+        currency: Currency,
+        amount: i128,
+        destination: Option<Address>,
+    ) -> Option<u64> {
+        Inheritance::record_activity(&env, &user);
+        validate_positive_amount(amount).unwrap();
+
+        let mut user_balance = Self::get_or_create_user_balance(&env, &user);
+        let current_balance = user_balance.balances.get(currency.clone()).unwrap_or(0);
+        if current_balance < amount {
+            fail(
+                &env,
+                AppError::InsufficientBalance,
+                "Insufficient balance for withdrawal",
+            );
+        }
+        Self::ensure_on_hand(&env, &user, &currency, current_balance, amount);
+        let new_balance = or_overflow(&env, checked_sub(current_balance, amount));
+        user_balance.balances.set(currency.clone(), new_balance);
+        user_balance.updated_at = env.ledger().timestamp();
+        storage::write(&env, &DataKey::Balance(user.clone()), &user_balance);
+        Self::journal(
+            &env,
+            &user,
+            &currency,
+            BalanceChangeKind::Withdrawal,
+            -amount,
+            new_balance,
+            None,
+        );
+
+        let guard = Self::get_withdrawal_guard(env.clone(), user.clone());
+        let Some(guard) = guard.filter(|guard| amount > guard.threshold) else {
+            Self::release(&env, user, currency, amount, destination);
+            return None;
+        };
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::WithdrawalCounter)
+            .unwrap_or(0)
+            + 1;
+        let requested_at = env.ledger().timestamp();
+        let withdrawal = PendingWithdrawal {
+            id,
+            user: user.clone(),
+            currency: currency.clone(),
+            amount,
+            requested_at,
+            available_at: requested_at + guard.delay,
+            destination,
+        };
+        storage::write(&env, &DataKey::Withdrawal(id), &withdrawal);
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalCounter, &id);
+
+        publish(
+            &env,
+            ConversionEvent::WithdrawalQueued(id, user, currency, amount, withdrawal.available_at),
+        );
+        Some(id)
+    }
+
+    /// Withdraw with the backend's payment reference on the withdrawal's
+    /// events. A queued withdrawal's reference is on its queued event only.
+    pub fn withdraw_with_reference(
+        env: Env,
+        user: Address,
+        currency: Currency,
+        amount: i128,
+        reference: BytesN<32>,
+    ) -> Option<u64> {
+        PaymentReference::attach(&env, &reference, || {
+            Self::withdraw(env.clone(), user, currency, amount)
+        })
+    }
+
+    /// Release a queued withdrawal once its cooling-off period has passed
+    /// (callable by anyone)
+    pub fn execute_withdrawal(env: Env, withdrawal_id: u64) {
+        Self::require_operational(&env);
+        let withdrawal = Self::get_pending_withdrawal(env.clone(), withdrawal_id)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Withdrawal not found"));
+        if env.ledger().timestamp() < withdrawal.available_at {
+            fail(&env, AppError::InvalidState, "Withdrawal is cooling off");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Withdrawal(withdrawal_id));
+        Self::release(
+            &env,
+            withdrawal.user,
+            withdrawal.currency,
+            withdrawal.amount,
+            withdrawal.destination,
+        );
+    }
+
+    /// Cancel a queued withdrawal and return the amount to the balance (the
+    /// user or their guardian). Cancelling only ever keeps funds in place, so
+    /// it stays open while conversions are halted.
+    pub fn cancel_withdrawal(env: Env, caller: Address, withdrawal_id: u64) {
+        caller.require_auth();
+        let withdrawal = Self::get_pending_withdrawal(env.clone(), withdrawal_id)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Withdrawal not found"));
+        let guardian = Self::get_withdrawal_guard(env.clone(), withdrawal.user.clone())
+            .and_then(|guard| guard.guardian);
+        if caller != withdrawal.user && guardian.as_ref() != Some(&caller) {
+            fail(
+                &env,
+                AppError::Unauthorized,
+                "Only the user or their guardian can cancel",
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Withdrawal(withdrawal_id));
+        Self::credit(
+            &env,
+            &withdrawal.user,
+            &withdrawal.currency,
+            withdrawal.amount,
+            BalanceChangeKind::WithdrawalCancelled,
+            None,
+        );
+        publish(
+            &env,
+            ConversionEvent::WithdrawalCancelled(withdrawal_id, caller),
+        );
+    }
+
+    pub fn get_pending_withdrawal(env: Env, withdrawal_id: u64) -> Option<PendingWithdrawal> {
+        storage::read(&env, &DataKey::Withdrawal(withdrawal_id))
+    }
+
+    /// Set, change or clear the user's withdrawal guard (user auth). With no
+    /// guard in force the new one applies at once; otherwise the change waits
+    /// out the current guard's delay.
+    pub fn set_withdrawal_guard(env: Env, user: Address, guard: Option<WithdrawalGuard>) {
+        user.require_auth();
+        if let Some(guard) = &guard {
+            if guard.threshold < 0 || guard.delay == 0 || guard.delay > MAX_WITHDRAWAL_DELAY {
+                fail(&env, AppError::InvalidConfig, "Invalid withdrawal guard");
+            }
+        }
+
+        match Self::apply_guard_change(&env, &user) {
+            Some(current) => {
+                let change = PendingGuardChange {
+                    guard,
+                    effective_at: env.ledger().timestamp() + current.delay,
+                };
+                storage::write(&env, &DataKey::PendingGuardChange(user), &change);
+            }
+            None => Self::store_guard(&env, &user, guard),
+        }
+    }
+
+    /// Guard in force for a user, counting a queued change whose delay has passed
+    pub fn get_withdrawal_guard(env: Env, user: Address) -> Option<WithdrawalGuard> {
+        if let Some(change) = Self::get_pending_guard_change(env.clone(), user.clone()) {
+            if env.ledger().timestamp() >= change.effective_at {
+                return change.guard;
+            }
+        }
+        storage::read(&env, &DataKey::WithdrawalGuard(user))
+    }
+
+    pub fn get_pending_guard_change(env: Env, user: Address) -> Option<PendingGuardChange> {
+        storage::read(&env, &DataKey::PendingGuardChange(user))
+    }
+
+    /// Opt a balance into the currency's liquidity pool, or back out. While
+    /// opted in, whatever of the balance sits idle is supplied to the pool,
+    /// earning rewards claimable from the pool manager. It stays spendable:
+    /// conversions and withdrawals draw what they need back from the pool.
+    pub fn set_auto_supply(env: Env, user: Address, currency: Currency, enabled: bool) {
+        Self::require_operational(&env);
+        user.require_auth();
+
+        let key = DataKey::AutoSupplied(user.clone(), currency.clone());
+        if enabled {
+            if Self::get_conversion_pool_manager(env.clone()).is_none() {
+                fail(&env, AppError::NotInitialized, "Pool manager not set");
+            }
+            if !env.storage().persistent().has(&key) {
+                storage::write(&env, &key, &0i128);
+            }
+            Self::supply_idle(&env, &user, &currency);
+        } else {
+            let balance = Self::get_or_create_user_balance(&env, &user)
+                .balances
+                .get(currency.clone())
+                .unwrap_or(0);
+            Self::ensure_on_hand(&env, &user, &currency, balance, balance);
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// How much of a balance sits in the liquidity pool; `None` when the
+    /// balance is not opted in
+    pub fn get_auto_supplied(env: Env, user: Address, currency: Currency) -> Option<i128> {
+        storage::read(&env, &DataKey::AutoSupplied(user, currency))
+    }
+
+    /// Statement of a user's balance changes in one currency, oldest first
+    pub fn get_balance_history(
+        env: Env,
+        user: Address,
+        currency: Currency,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<BalanceEntry> {
+        let len = Self::get_balance_history_len(env.clone(), user.clone(), currency.clone());
+        let mut entries = Vec::new(&env);
+        let mut index = offset;
+        while index < len && entries.len() < limit.min(MAX_PAGE_SIZE) {
+            let key = DataKey::BalanceHistory(user.clone(), currency.clone(), index);
+            entries.push_back(storage::read(&env, &key).unwrap());
+            index += 1;
+        }
+        entries
+    }
+
+    pub fn get_balance_history_len(env: Env, user: Address, currency: Currency) -> u32 {
+        storage::read(&env, &DataKey::BalanceHistoryLen(user, currency)).unwrap_or(0)
+    }
+
+    // Private helper methods
+
+    fn require_operational(env: &Env) {
+        if let Some(guardian) = Self::get_conversion_guardian(env.clone()) {
+            if GuardianCheck::is_halted(env, &guardian, Subsystem::Conversion) {
+                fail(env, AppError::Paused, "Conversions halted by guardian");
+            }
+        }
+    }
+
+    // The pair's mid rate less its spread: the rate a conversion is paid at
+    fn spread_rate(
+        env: &Env,
+        from_currency: &Currency,
+        to_currency: &Currency,
+        mid_rate: i128,
+    ) -> i128 {
+        let spread_bps =
+            Self::get_pair_spread(env.clone(), from_currency.clone(), to_currency.clone());
+        or_overflow(
+            env,
+            Bps::new(spread_bps).and_then(|spread| spread.complement().of(mid_rate)),
+        )
+    }
+
+    // Rate for a pair: a fresh SEP-40 feed price if the pair has a feed, else the
+    // price oracle when one is set, else the admin-posted rate if not expired
+    fn current_rate(env: &Env, from_currency: &Currency, to_currency: &Currency) -> ExchangeRate {
+        if Self::is_pair_suspended(env.clone(), from_currency.clone(), to_currency.clone()) {
+            fail(env, AppError::Paused, "Currency pair suspended");
+        }
+
+        let feed = Self::get_rate_feed(env.clone(), from_currency.clone(), to_currency.clone());
+        if let Some(feed) = feed {
+            if let Some(rate) = read_feed_rate(env, &feed) {
+                return ExchangeRate {
+                    from_currency: from_currency.clone(),
+                    to_currency: to_currency.clone(),
+                    rate,
+                    updated_at: env.ledger().timestamp(),
+                    validity_duration: 0,
+                    is_locked: false,
+                };
+            }
+        }
+
+        if let Some(oracle) = Self::get_price_oracle(env.clone()) {
+            let price =
+                OracleContractClient::new(env, &oracle).get_price(from_currency, to_currency);
+            return ExchangeRate {
+                from_currency: from_currency.clone(),
+                to_currency: to_currency.clone(),
+                rate: price.price,
+                updated_at: price.timestamp,
+                validity_duration: price.valid_until - price.timestamp,
+                is_locked: false,
+            };
+        }
+
+        let rate_info: ExchangeRate = env
+            .storage()
+            .instance()
+            .get(&DataKey::Rate(from_currency.clone(), to_currency.clone()))
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "Exchange rate not found"));
+
+        if env.ledger().timestamp() > rate_info.updated_at + rate_info.validity_duration {
+            fail(env, AppError::RateExpired, "Exchange rate has expired");
+        }
+        rate_info
+    }
+
+    fn validate_conversion(
+        env: &Env,
+        from_currency: &Currency,
+        to_currency: &Currency,
+        amount: i128,
     ) {
         if from_currency == to_currency {
-            panic!("Cannot convert to the same currency");
+            fail(
+                env,
+                AppError::UnsupportedCurrency,
+                "Cannot convert to the same currency",
+            );
         }
 
         validate_positive_amount(amount).unwrap();
@@ -482,11 +2055,19 @@ impl ConversionContract {
         let config: PlatformConfig = env.storage().instance().get(&DataKey::Config).unwrap();
 
         if amount < config.min_conversion_amount {
-            panic!("Amount below minimum conversion limit");
+            fail(
+                env,
+                AppError::ConversionLimitExceeded,
+                "Amount below minimum conversion limit",
+            );
         }
 
         if amount > config.max_conversion_amount {
-            panic!("Amount exceeds maximum conversion limit");
+            fail(
+                env,
+                AppError::ConversionLimitExceeded,
+                "Amount exceeds maximum conversion limit",
+            );
         }
 
         // Validate currencies are supported
@@ -509,14 +2090,19 @@ impl ConversionContract {
         }
 
         if !from_supported || !to_supported {
-            panic!("Unsupported currency pair");
+            fail(
+                env,
+                AppError::UnsupportedCurrency,
+                "Unsupported currency pair",
+            );
         }
     }
 
     fn get_or_create_user_balance(env: &Env, user: &Address) -> UserBalance {
-        env.storage()
-            .instance()
-            .get(&DataKey::Balance(user.clone()))
+        let key = DataKey::Balance(user.clone());
+        storage::read(env, &key)
+            // Left in instance storage by code older than storage version 2
+            .or_else(|| storage::move_from_instance(env, &key))
             .unwrap_or_else(|| UserBalance {
                 user: user.clone(),
                 balances: Map::new(env),
@@ -524,8 +2110,228 @@ impl ConversionContract {
             })
     }
 
-    fn calculate_fee(amount: i128, fee_bps: u32) -> i128 {
-        (amount * i128::from(fee_bps)) / 10000
+    // `amount` of `from_currency` at `rate`, in `to_currency` units. When both
+    // currencies have registered decimals the source amount is scaled up to the
+    // normalized precision and the result scaled back down, rounding toward
+    // zero; otherwise the rate applies to the raw amounts.
+    fn convert_amount(
+        env: &Env,
+        from_currency: &Currency,
+        to_currency: &Currency,
+        amount: i128,
+        rate: i128,
+    ) -> i128 {
+        let (from_scale, to_scale) = match (
+            Self::decimal_scale(env, from_currency),
+            Self::decimal_scale(env, to_currency),
+        ) {
+            (Some(from_scale), Some(to_scale)) => (from_scale, to_scale),
+            _ => (1, 1),
+        };
+        let normalized = or_overflow(env, checked_mul_div(amount, from_scale, 1));
+        let converted = or_overflow(env, checked_mul_div(normalized, rate, RATE_PRECISION));
+        or_overflow(env, checked_mul_div(converted, 1, to_scale))
+    }
+
+    // Factor between a currency's own units and the normalized precision
+    fn decimal_scale(env: &Env, currency: &Currency) -> Option<i128> {
+        Self::get_currency_decimals(env.clone(), currency.clone())
+            .map(|decimals| 10i128.pow(NORMALIZED_DECIMALS - decimals))
+    }
+
+    fn credit(
+        env: &Env,
+        user: &Address,
+        currency: &Currency,
+        amount: i128,
+        kind: BalanceChangeKind,
+        tx_id: Option<Symbol>,
+    ) {
+        let mut balance = Self::get_or_create_user_balance(env, user);
+        let current = balance.balances.get(currency.clone()).unwrap_or(0);
+        let new_balance = or_overflow(env, checked_add(current, amount));
+        balance.balances.set(currency.clone(), new_balance);
+        balance.updated_at = env.ledger().timestamp();
+        storage::write(env, &DataKey::Balance(user.clone()), &balance);
+        Self::journal(env, user, currency, kind, amount, new_balance, tx_id);
+        Self::supply_idle(env, user, currency);
+    }
+
+    // Supply whatever of an opted-in balance sits idle to the currency's pool,
+    // out of the tokens this contract holds for it. The pool may turn the
+    // amount down, for instance below its minimum, in which case it stays
+    // idle until the next credit.
+    fn supply_idle(env: &Env, user: &Address, currency: &Currency) {
+        let key = DataKey::AutoSupplied(user.clone(), currency.clone());
+        let Some(supplied) = storage::read::<DataKey, i128>(env, &key) else {
+            return;
+        };
+        let Some(pool_manager) = Self::get_conversion_pool_manager(env.clone()) else {
+            return;
+        };
+        let balance = Self::get_or_create_user_balance(env, user)
+            .balances
+            .get(currency.clone())
+            .unwrap_or(0);
+        let idle = balance - supplied;
+        if idle <= 0 || !Self::authorize_pool_pull(env, &pool_manager, currency, idle) {
+            return;
+        }
+        let supplied_now = PoolManagerContractClient::new(env, &pool_manager)
+            .try_supply_liquidity_for(&env.current_contract_address(), user, currency, &idle)
+            .is_ok();
+        if supplied_now {
+            storage::write(env, &key, &(supplied + idle));
+        }
+    }
+
+    // Let the pool manager take `amount` of the currency's pool token from
+    // this contract in the call that follows; false if the pool has no token
+    fn authorize_pool_pull(
+        env: &Env,
+        pool_manager: &Address,
+        currency: &Currency,
+        amount: i128,
+    ) -> bool {
+        let Some(token) =
+            PoolManagerContractClient::new(env, pool_manager).get_pool_token(currency)
+        else {
+            return false;
+        };
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (env.current_contract_address(), pool_manager.clone(), amount)
+                        .into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+        true
+    }
+
+    // Draw an opted-in balance back from the pool until `needed` of it is on
+    // hand; `balance` is the balance before the debit that needs it
+    fn ensure_on_hand(env: &Env, user: &Address, currency: &Currency, balance: i128, needed: i128) {
+        let key = DataKey::AutoSupplied(user.clone(), currency.clone());
+        let Some(supplied) = storage::read::<DataKey, i128>(env, &key) else {
+            return;
+        };
+        let shortfall = needed - (balance - supplied);
+        if shortfall <= 0 {
+            return;
+        }
+        let pool_manager = Self::get_conversion_pool_manager(env.clone())
+            .unwrap_or_else(|| fail(env, AppError::NotInitialized, "Pool manager not set"));
+        PoolManagerContractClient::new(env, &pool_manager).withdraw_liquidity_for(
+            &env.current_contract_address(),
+            user,
+            currency,
+            &shortfall,
+        );
+        storage::write(env, &key, &(supplied - shortfall));
+    }
+
+    // Fold a queued guard change whose delay has passed into the stored
+    // guard, returning the guard now in force
+    fn apply_guard_change(env: &Env, user: &Address) -> Option<WithdrawalGuard> {
+        let current = Self::get_withdrawal_guard(env.clone(), user.clone());
+        let change_key = DataKey::PendingGuardChange(user.clone());
+        let matured = Self::get_pending_guard_change(env.clone(), user.clone())
+            .is_some_and(|change| env.ledger().timestamp() >= change.effective_at);
+        if matured {
+            env.storage().persistent().remove(&change_key);
+            Self::store_guard(env, user, current.clone());
+        }
+        current
+    }
+
+    fn store_guard(env: &Env, user: &Address, guard: Option<WithdrawalGuard>) {
+        let key = DataKey::WithdrawalGuard(user.clone());
+        match guard {
+            Some(guard) => storage::write(env, &key, &guard),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    // Append a line to the user's statement for `currency`
+    fn journal(
+        env: &Env,
+        user: &Address,
+        currency: &Currency,
+        kind: BalanceChangeKind,
+        amount: i128,
+        balance: i128,
+        tx_id: Option<Symbol>,
+    ) {
+        let len_key = DataKey::BalanceHistoryLen(user.clone(), currency.clone());
+        let len: u32 = storage::read(env, &len_key).unwrap_or(0);
+        let entry = BalanceEntry {
+            kind,
+            amount,
+            balance,
+            tx_id,
+            timestamp: env.ledger().timestamp(),
+        };
+        storage::write(
+            env,
+            &DataKey::BalanceHistory(user.clone(), currency.clone(), len),
+            &entry,
+        );
+        storage::write(env, &len_key, &(len + 1));
+    }
+
+    fn check_config(env: &Env, config: &PlatformConfig) {
+        if Bps::capped(config.fee_bps, MAX_FEE_BPS).is_err() {
+            fail(env, AppError::InvalidConfig, "Fee too high");
+        }
+
+        if config.min_conversion_amount <= 0
+            || config.max_conversion_amount <= config.min_conversion_amount
+        {
+            fail(
+                env,
+                AppError::InvalidConfig,
+                "Invalid conversion amount limits",
+            );
+        }
+    }
+
+    fn calculate_fee(amount: i128, fee_bps: u32) -> Result<i128, AppError> {
+        Bps::new(fee_bps)?.of(amount)
+    }
+
+    fn backing_token(env: &Env, currency: &Currency) -> Address {
+        Self::get_backing_token(env.clone(), currency.clone())
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "No backing token for currency"))
+    }
+
+    // Hand a debited amount over: on-chain in the backing token when it goes
+    // to an external account, otherwise to the platform's off-chain payout
+    fn release(
+        env: &Env,
+        user: Address,
+        currency: Currency,
+        amount: i128,
+        destination: Option<Address>,
+    ) {
+        match destination {
+            Some(destination) => {
+                token::Client::new(env, &Self::backing_token(env, &currency)).transfer(
+                    &env.current_contract_address(),
+                    &destination,
+                    &amount,
+                );
+                publish(
+                    env,
+                    ConversionEvent::PaidOut(user, currency, amount, destination),
+                );
+            }
+            None => publish(env, ConversionEvent::Withdrawn(user, currency, amount)),
+        }
     }
 
     fn collect_platform_fee(
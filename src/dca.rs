@@ -0,0 +1,396 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::Currency;
+use crate::event::{
+    DcaCancelledData, DcaCreatedData, DcaEvent, DcaExecutedData, DeFiEvent, EventEmitter, DCA_TOPIC,
+};
+use crate::keeper::{KeeperTip, KeeperTips};
+use crate::oracle::OracleContractClient;
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+const PRICE_PRECISION: i128 = 100_000_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DcaPlan {
+    pub id: u64,
+    pub owner: Address,
+    pub source: Currency,
+    pub target: Currency,
+    /// Source amount spent on each purchase
+    pub amount_per_period: i128,
+    pub period: u64,
+    pub next_execution: u64,
+    /// Unspent source deposit
+    pub balance: i128,
+    /// Total target amount bought so far
+    pub purchased: i128,
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DcaDataKey {
+    Admin,
+    Oracle,
+    NextId,
+    Market(Currency),
+    Liquidity(Currency),
+    Plan(u64),
+}
+
+#[contract]
+pub struct DcaContract;
+
+/// Dollar-cost averaging vault. Users deposit a source currency and schedule
+/// periodic purchases of a target currency; keepers execute due purchases
+/// against the vault's liquidity at the oracle rate.
+#[contractimpl]
+impl DcaContract {
+    pub fn initialize_dca(env: Env, admin: Address, oracle: Address) {
+        if env.storage().instance().has(&DcaDataKey::Admin) {
+            panic!("DCA vault already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DcaDataKey::Admin, &admin);
+        env.storage().instance().set(&DcaDataKey::Oracle, &oracle);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Map a currency to the token the vault settles it in (owner only)
+    pub fn set_dca_market(env: Env, caller: Address, currency: Currency, token: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_dca_market",
+            (currency.clone(), token.clone()),
+        );
+        env.storage()
+            .instance()
+            .set(&DcaDataKey::Market(currency), &token);
+    }
+
+    pub fn get_dca_market(env: Env, currency: Currency) -> Address {
+        env.storage()
+            .instance()
+            .get(&DcaDataKey::Market(currency))
+            .expect("Market not listed")
+    }
+
+    /// Add tokens the vault can sell to plans (operator only)
+    pub fn provide_dca_liquidity(env: Env, caller: Address, currency: Currency, amount: i128) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "provide_dca_liquidity",
+            (currency.clone(), amount),
+        );
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        token::Client::new(&env, &Self::get_dca_market(env.clone(), currency.clone())).transfer(
+            &caller,
+            &env.current_contract_address(),
+            &amount,
+        );
+        let liquidity = Self::get_dca_liquidity(env.clone(), currency.clone());
+        Self::set_liquidity(&env, &currency, liquidity + amount);
+    }
+
+    /// Take out liquidity, including source tokens collected from purchases (operator only)
+    pub fn withdraw_dca_liquidity(env: Env, caller: Address, currency: Currency, amount: i128) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "withdraw_dca_liquidity",
+            (currency.clone(), amount),
+        );
+        let liquidity = Self::get_dca_liquidity(env.clone(), currency.clone());
+        if amount <= 0 || amount > liquidity {
+            panic!("Invalid withdrawal amount");
+        }
+        Self::set_liquidity(&env, &currency, liquidity - amount);
+        token::Client::new(&env, &Self::get_dca_market(env.clone(), currency)).transfer(
+            &env.current_contract_address(),
+            &caller,
+            &amount,
+        );
+    }
+
+    pub fn get_dca_liquidity(env: Env, currency: Currency) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DcaDataKey::Liquidity(currency))
+            .unwrap_or(0)
+    }
+
+    /// Deposit `deposit` of `source` and buy `target` with `amount_per_period`
+    /// of it every `period` seconds, starting immediately
+    pub fn create_dca_plan(
+        env: Env,
+        owner: Address,
+        source: Currency,
+        target: Currency,
+        deposit: i128,
+        amount_per_period: i128,
+        period: u64,
+    ) -> u64 {
+        owner.require_auth();
+        if source == target {
+            panic!("Source and target must differ");
+        }
+        if amount_per_period <= 0 || deposit < amount_per_period || period == 0 {
+            panic!("Invalid plan parameters");
+        }
+        // Fails early when the target is not listed
+        Self::get_dca_market(env.clone(), target.clone());
+        token::Client::new(&env, &Self::get_dca_market(env.clone(), source.clone())).transfer(
+            &owner,
+            &env.current_contract_address(),
+            &deposit,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DcaDataKey::NextId)
+            .unwrap_or(1);
+        env.storage().instance().set(&DcaDataKey::NextId, &(id + 1));
+
+        let plan = DcaPlan {
+            id,
+            owner: owner.clone(),
+            source: source.clone(),
+            target: target.clone(),
+            amount_per_period,
+            period,
+            next_execution: env.ledger().timestamp(),
+            balance: deposit,
+            purchased: 0,
+            active: true,
+        };
+        Self::save(&env, &plan);
+
+        let event = DeFiEvent::Dca(DcaEvent::Created(DcaCreatedData {
+            plan_id: id,
+            owner,
+            source,
+            target,
+            deposit,
+            amount_per_period,
+            period,
+        }));
+        EventEmitter::emit_event(&env, DCA_TOPIC, event);
+        id
+    }
+
+    /// Run a due purchase: sells up to `amount_per_period` of the plan's
+    /// balance for the target currency at the oracle rate and pays it to the
+    /// owner, less the keeper tip paid to `keeper`. Callable by any keeper;
+    /// returns the target amount bought.
+    pub fn execute_dca(env: Env, keeper: Address, plan_id: u64) -> i128 {
+        let mut plan = Self::get_dca_plan(env.clone(), plan_id);
+        if !plan.active {
+            panic!("Plan not active");
+        }
+        if env.ledger().timestamp() < plan.next_execution {
+            panic!("Purchase not due");
+        }
+
+        let oracle: Address = env.storage().instance().get(&DcaDataKey::Oracle).unwrap();
+        let price = OracleContractClient::new(&env, &oracle)
+            .get_price(&plan.source, &plan.target)
+            .price;
+        let spent = plan.amount_per_period.min(plan.balance);
+        let received = spent * price / PRICE_PRECISION;
+        if received <= 0 {
+            panic!("Purchase too small");
+        }
+
+        let target_liquidity = Self::get_dca_liquidity(env.clone(), plan.target.clone());
+        if received > target_liquidity {
+            panic!("Insufficient liquidity");
+        }
+        Self::set_liquidity(&env, &plan.target, target_liquidity - received);
+        let source_liquidity = Self::get_dca_liquidity(env.clone(), plan.source.clone());
+        Self::set_liquidity(&env, &plan.source, source_liquidity + spent);
+
+        let tip = KeeperTips::tip_on(&env, received);
+        let target = token::Client::new(
+            &env,
+            &Self::get_dca_market(env.clone(), plan.target.clone()),
+        );
+        target.transfer(
+            &env.current_contract_address(),
+            &plan.owner,
+            &(received - tip),
+        );
+        if tip > 0 {
+            target.transfer(&env.current_contract_address(), &keeper, &tip);
+        }
+
+        plan.balance -= spent;
+        plan.purchased += received;
+        plan.next_execution += plan.period;
+        if plan.balance == 0 {
+            plan.active = false;
+        }
+        Self::save(&env, &plan);
+
+        let event = DeFiEvent::Dca(DcaEvent::Executed(DcaExecutedData {
+            plan_id,
+            spent,
+            received,
+            price,
+            remaining: plan.balance,
+        }));
+        EventEmitter::emit_event(&env, DCA_TOPIC, event);
+        received
+    }
+
+    /// Stop a plan and refund its unspent balance (owner of the plan only)
+    pub fn cancel_dca_plan(env: Env, plan_id: u64) -> i128 {
+        let mut plan = Self::get_dca_plan(env.clone(), plan_id);
+        plan.owner.require_auth();
+        if !plan.active {
+            panic!("Plan not active");
+        }
+
+        let refunded = plan.balance;
+        token::Client::new(
+            &env,
+            &Self::get_dca_market(env.clone(), plan.source.clone()),
+        )
+        .transfer(&env.current_contract_address(), &plan.owner, &refunded);
+        plan.balance = 0;
+        plan.active = false;
+        Self::save(&env, &plan);
+
+        let event = DeFiEvent::Dca(DcaEvent::Cancelled(DcaCancelledData {
+            plan_id,
+            owner: plan.owner,
+            refunded,
+        }));
+        EventEmitter::emit_event(&env, DCA_TOPIC, event);
+        refunded
+    }
+
+    /// Tip paid to keepers out of each purchase, or clear it (owner only)
+    pub fn set_dca_keeper_tip(env: Env, caller: Address, tip: Option<KeeperTip>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_dca_keeper_tip",
+            tip.clone(),
+        );
+        KeeperTips::set_tip(&env, tip);
+    }
+
+    pub fn get_dca_keeper_tip(env: Env) -> Option<KeeperTip> {
+        KeeperTips::get_tip(&env)
+    }
+
+    pub fn get_dca_plan(env: Env, plan_id: u64) -> DcaPlan {
+        env.storage()
+            .persistent()
+            .get(&DcaDataKey::Plan(plan_id))
+            .expect("Plan not found")
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_dca_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_dca_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_dca_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_dca_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_dca_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn set_liquidity(env: &Env, currency: &Currency, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&DcaDataKey::Liquidity(currency.clone()), &amount);
+    }
+
+    fn save(env: &Env, plan: &DcaPlan) {
+        env.storage()
+            .persistent()
+            .set(&DcaDataKey::Plan(plan.id), plan);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_dca_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_dca_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_dca_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_dca_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` owed to operators as liquidity and to plan owners as unspent deposits
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mut owed = 0;
+        for currency in Currency::ALL {
+            let market: Option<Address> = env
+                .storage()
+                .instance()
+                .get(&DcaDataKey::Market(currency.clone()));
+            if market.as_ref() == Some(token) {
+                owed += Self::get_dca_liquidity(env.clone(), currency);
+            }
+        }
+
+        let next_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DcaDataKey::NextId)
+            .unwrap_or(1);
+        for id in 1..next_id {
+            let plan = Self::get_dca_plan(env.clone(), id);
+            if plan.active && Self::get_dca_market(env.clone(), plan.source) == *token {
+                owed += plan.balance;
+            }
+        }
+        Some(owed)
+    }
+}
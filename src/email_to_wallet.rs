@@ -1,24 +1,512 @@
-use crate::errors::AppError;
+use crate::alias::{derive_alias_address, hash_alias, AliasKind};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EmailEvent, EmailPaymentClaimedData, EmailPaymentRefundedData, EmailPaymentSentData,
+    EmailRebindCancelledData, EmailRebindInitiatedData, EmailReboundData, EmailRegisteredData,
+    EmailVerifiedData, EventEmitter, EMAIL_TOPIC,
+};
 use crate::utils::derive_wallet_address_from_email;
-use soroban_sdk::{contract, contractimpl, Address, Env, IntoVal, String};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, String, Vec};
 
+const EMAIL_PAYMENT_TTL: u64 = 30 * 86400; // 30 days to claim before the sender can reclaim
+const REBIND_DELAY: u64 = 3 * 86400; // 3 days for the current wallet to cancel a rebind
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailRegistryConfig {
+    pub admin: Address,
+    /// Backend that attests a registrant controls the email
+    pub backend: Address,
+}
+
+/// A wallet chosen by the owner of an email. Only verified registrations are used by `resolve`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailRegistration {
+    pub email_hash: BytesN<32>,
+    pub wallet: Address,
+    pub registered_at: u64,
+    pub verified: bool,
+    pub verified_at: u64,
+}
+
+/// A recovery-initiated change of the wallet bound to an email
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRebind {
+    pub email_hash: BytesN<32>,
+    pub new_wallet: Address,
+    pub initiated_at: u64,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EmailPaymentStatus {
+    Pending,
+    Claimed,
+    Refunded,
+}
+
+/// Tokens sent to an email hash, held until the email's owner claims them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailPayment {
+    pub id: u64,
+    pub sender: Address,
+    pub email_hash: BytesN<32>,
+    pub token: Address,
+    pub amount: i128,
+    pub sent_at: u64,
+    /// After this the sender may reclaim the payment if it is still pending
+    pub expires_at: u64,
+    pub status: EmailPaymentStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum EmailDataKey {
+    Config,
+    Registration(BytesN<32>),
+    PaymentCount,
+    Payment(u64),
+    PendingPayments(BytesN<32>),
+    RecoveryAuthority,
+    PendingRebind(BytesN<32>),
+}
+
+#[contract]
 pub struct EmailToWalletContract;
 
+#[contractimpl]
 impl EmailToWalletContract {
-    pub fn get_wallet_from_email(env: Env, email: String) -> Result<Address, AppError> {
-        derive_wallet_address_from_email(&env, &email)
+    pub fn initialize_email_registry(
+        env: Env,
+        admin: Address,
+        backend: Address,
+    ) -> EmailRegistryConfig {
+        if env.storage().instance().has(&EmailDataKey::Config) {
+            panic!("Email registry already initialized");
+        }
+        admin.require_auth();
+
+        let config = EmailRegistryConfig { admin, backend };
+        env.storage().instance().set(&EmailDataKey::Config, &config);
+        config
+    }
+
+    pub fn set_email_backend(env: Env, backend: Address) -> EmailRegistryConfig {
+        let mut config = Self::get_email_registry_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(&env, &config.admin, "set_email_backend", backend.clone());
+
+        config.backend = backend;
+        env.storage().instance().set(&EmailDataKey::Config, &config);
+        config
+    }
+
+    /// Sets the account (typically a multisig) allowed to rebind emails for users who lost their keys
+    pub fn set_recovery_authority(env: Env, recovery: Address) {
+        let config = Self::get_email_registry_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "set_recovery_authority",
+            recovery.clone(),
+        );
+
+        env.storage()
+            .instance()
+            .set(&EmailDataKey::RecoveryAuthority, &recovery);
+    }
+
+    pub fn get_recovery_authority(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&EmailDataKey::RecoveryAuthority)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (admin only)
+    pub fn set_email_registry_audit_log(env: Env, audit_log: Option<Address>) {
+        let config = Self::get_email_registry_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "set_email_registry_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_email_registry_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    pub fn get_email_registry_config(env: Env) -> EmailRegistryConfig {
+        env.storage()
+            .instance()
+            .get(&EmailDataKey::Config)
+            .expect("Email registry not initialized")
+    }
+
+    /// Claims an email hash for `wallet`. The claim is pending until the backend verifies it.
+    pub fn register_email(env: Env, email_hash: BytesN<32>, wallet: Address) {
+        wallet.require_auth();
+
+        let key = EmailDataKey::Registration(email_hash.clone());
+        if let Some(existing) = env.storage().persistent().get::<_, EmailRegistration>(&key) {
+            if existing.verified {
+                panic!("Email already registered");
+            }
+        }
+
+        let registration = EmailRegistration {
+            email_hash: email_hash.clone(),
+            wallet: wallet.clone(),
+            registered_at: env.ledger().timestamp(),
+            verified: false,
+            verified_at: 0,
+        };
+        env.storage().persistent().set(&key, &registration);
+
+        let event = DeFiEvent::Email(EmailEvent::Registered(EmailRegisteredData {
+            email_hash,
+            wallet,
+            registered_at: registration.registered_at,
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+    }
+
+    /// Backend attestation that `wallet` proved control of the email. `wallet` must match
+    /// the pending claim so a later claim cannot be verified by mistake.
+    pub fn verify_email(env: Env, email_hash: BytesN<32>, wallet: Address) -> EmailRegistration {
+        let config = Self::get_email_registry_config(env.clone());
+        config.backend.require_auth();
+
+        let key = EmailDataKey::Registration(email_hash.clone());
+        let mut registration: EmailRegistration = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Email not registered");
+        if registration.verified {
+            panic!("Email already verified");
+        }
+        if registration.wallet != wallet {
+            panic!("Wallet does not match registration");
+        }
+
+        registration.verified = true;
+        registration.verified_at = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &registration);
+
+        let event = DeFiEvent::Email(EmailEvent::Verified(EmailVerifiedData {
+            email_hash,
+            wallet,
+            verified_by: config.backend,
+            verified_at: registration.verified_at,
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+
+        registration
+    }
+
+    pub fn get_registration(env: Env, email_hash: BytesN<32>) -> Option<EmailRegistration> {
+        env.storage()
+            .persistent()
+            .get(&EmailDataKey::Registration(email_hash))
+    }
+
+    /// Wallet for an email: the verified registered address if there is one,
+    /// otherwise the address derived from the email hash
+    pub fn resolve(env: Env, email: String) -> Address {
+        Self::resolve_alias(env, AliasKind::Email, email)
+    }
+
+    /// Wallet for any alias kind, preferring a verified registration for the alias hash
+    pub fn resolve_alias(env: Env, kind: AliasKind, value: String) -> Address {
+        let alias_hash = hash_alias(&env, kind, &value).unwrap_or_else(|_| panic!("Invalid alias"));
+        match Self::get_registration(env.clone(), alias_hash) {
+            Some(registration) if registration.verified => registration.wallet,
+            _ => derive_alias_address(&env, kind, &value).unwrap(),
+        }
+    }
+
+    pub fn get_wallet_from_email(env: Env, email: String) -> Address {
+        derive_wallet_address_from_email(&env, &email).unwrap_or_else(|_| panic!("Invalid email"))
+    }
+
+    /// Starts moving a verified email to `new_wallet`. The current wallet can cancel
+    /// during the delay; afterwards anyone can execute the rebind.
+    pub fn initiate_rebind(env: Env, email_hash: BytesN<32>, new_wallet: Address) -> PendingRebind {
+        let recovery =
+            Self::get_recovery_authority(env.clone()).expect("Recovery authority not set");
+        recovery.require_auth();
+
+        let registration = match Self::get_registration(env.clone(), email_hash.clone()) {
+            Some(registration) if registration.verified => registration,
+            _ => panic!("Email not verified"),
+        };
+        if registration.wallet == new_wallet {
+            panic!("Wallet already bound to email");
+        }
+        let key = EmailDataKey::PendingRebind(email_hash.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Rebind already pending");
+        }
+
+        let now = env.ledger().timestamp();
+        let rebind = PendingRebind {
+            email_hash: email_hash.clone(),
+            new_wallet: new_wallet.clone(),
+            initiated_at: now,
+            executable_at: now + REBIND_DELAY,
+        };
+        env.storage().persistent().set(&key, &rebind);
+
+        let event = DeFiEvent::Email(EmailEvent::RebindInitiated(EmailRebindInitiatedData {
+            email_hash,
+            current_wallet: registration.wallet,
+            new_wallet,
+            executable_at: rebind.executable_at,
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+
+        rebind
+    }
+
+    /// Lets the currently bound wallet stop a rebind it did not ask for
+    pub fn cancel_rebind(env: Env, email_hash: BytesN<32>) {
+        let key = EmailDataKey::PendingRebind(email_hash.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("No pending rebind");
+        }
+        let registration = Self::get_registration(env.clone(), email_hash.clone()).unwrap();
+        registration.wallet.require_auth();
+
+        env.storage().persistent().remove(&key);
+
+        let event = DeFiEvent::Email(EmailEvent::RebindCancelled(EmailRebindCancelledData {
+            email_hash,
+            cancelled_by: registration.wallet,
+            cancelled_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+    }
+
+    pub fn execute_rebind(env: Env, email_hash: BytesN<32>) -> EmailRegistration {
+        let rebind =
+            Self::get_pending_rebind(env.clone(), email_hash.clone()).expect("No pending rebind");
+        if env.ledger().timestamp() < rebind.executable_at {
+            panic!("Rebind delay has not elapsed");
+        }
+
+        let mut registration = Self::get_registration(env.clone(), email_hash.clone()).unwrap();
+        let old_wallet = registration.wallet;
+        registration.wallet = rebind.new_wallet.clone();
+        registration.verified_at = env.ledger().timestamp();
+        env.storage().persistent().set(
+            &EmailDataKey::Registration(email_hash.clone()),
+            &registration,
+        );
+        env.storage()
+            .persistent()
+            .remove(&EmailDataKey::PendingRebind(email_hash.clone()));
+
+        let event = DeFiEvent::Email(EmailEvent::Rebound(EmailReboundData {
+            email_hash,
+            old_wallet,
+            new_wallet: rebind.new_wallet,
+            rebound_at: registration.verified_at,
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+
+        registration
+    }
+
+    pub fn get_pending_rebind(env: Env, email_hash: BytesN<32>) -> Option<PendingRebind> {
+        env.storage()
+            .persistent()
+            .get(&EmailDataKey::PendingRebind(email_hash))
+    }
+
+    /// Holds `amount` of `token` for the owner of `email_hash` until they claim it
+    pub fn send_to_email(
+        env: Env,
+        sender: Address,
+        email_hash: BytesN<32>,
+        token: Address,
+        amount: i128,
+    ) -> u64 {
+        sender.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&EmailDataKey::PaymentCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&EmailDataKey::PaymentCount, &id);
+
+        let now = env.ledger().timestamp();
+        let payment = EmailPayment {
+            id,
+            sender: sender.clone(),
+            email_hash: email_hash.clone(),
+            token: token.clone(),
+            amount,
+            sent_at: now,
+            expires_at: now + EMAIL_PAYMENT_TTL,
+            status: EmailPaymentStatus::Pending,
+        };
+        env.storage()
+            .persistent()
+            .set(&EmailDataKey::Payment(id), &payment);
+
+        let mut pending = Self::pending_payment_ids(&env, &email_hash);
+        pending.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&EmailDataKey::PendingPayments(email_hash.clone()), &pending);
+
+        let event = DeFiEvent::Email(EmailEvent::PaymentSent(EmailPaymentSentData {
+            payment_id: id,
+            sender,
+            email_hash,
+            token,
+            amount,
+            expires_at: payment.expires_at,
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+
+        id
+    }
+
+    /// Pays every pending payment for a verified email to its registered wallet.
+    /// Expired payments not yet refunded are still claimable. Returns the number claimed.
+    pub fn claim_email_payments(env: Env, email_hash: BytesN<32>) -> u32 {
+        let registration = match Self::get_registration(env.clone(), email_hash.clone()) {
+            Some(registration) if registration.verified => registration,
+            _ => panic!("Email not verified"),
+        };
+        registration.wallet.require_auth();
+
+        let pending = Self::pending_payment_ids(&env, &email_hash);
+        for id in pending.iter() {
+            let mut payment = Self::get_email_payment(env.clone(), id);
+            token::Client::new(&env, &payment.token).transfer(
+                &env.current_contract_address(),
+                &registration.wallet,
+                &payment.amount,
+            );
+            payment.status = EmailPaymentStatus::Claimed;
+            env.storage()
+                .persistent()
+                .set(&EmailDataKey::Payment(id), &payment);
+
+            let event = DeFiEvent::Email(EmailEvent::PaymentClaimed(EmailPaymentClaimedData {
+                payment_id: id,
+                email_hash: email_hash.clone(),
+                wallet: registration.wallet.clone(),
+                token: payment.token,
+                amount: payment.amount,
+                claimed_at: env.ledger().timestamp(),
+            }));
+            EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+        }
+        env.storage()
+            .persistent()
+            .remove(&EmailDataKey::PendingPayments(email_hash));
+
+        pending.len()
+    }
+
+    /// Returns an expired, unclaimed payment to its sender. Callable by anyone.
+    pub fn refund_email_payment(env: Env, payment_id: u64) {
+        let mut payment = Self::get_email_payment(env.clone(), payment_id);
+        if payment.status != EmailPaymentStatus::Pending {
+            panic!("Payment is not pending");
+        }
+        if env.ledger().timestamp() <= payment.expires_at {
+            panic!("Payment has not expired");
+        }
+
+        token::Client::new(&env, &payment.token).transfer(
+            &env.current_contract_address(),
+            &payment.sender,
+            &payment.amount,
+        );
+        payment.status = EmailPaymentStatus::Refunded;
+        env.storage()
+            .persistent()
+            .set(&EmailDataKey::Payment(payment_id), &payment);
+
+        let mut pending = Self::pending_payment_ids(&env, &payment.email_hash);
+        if let Some(i) = pending.first_index_of(payment_id) {
+            pending.remove(i);
+        }
+        env.storage().persistent().set(
+            &EmailDataKey::PendingPayments(payment.email_hash.clone()),
+            &pending,
+        );
+
+        let event = DeFiEvent::Email(EmailEvent::PaymentRefunded(EmailPaymentRefundedData {
+            payment_id,
+            sender: payment.sender,
+            token: payment.token,
+            amount: payment.amount,
+            refunded_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, EMAIL_TOPIC, event);
+    }
+
+    pub fn get_email_payment(env: Env, payment_id: u64) -> EmailPayment {
+        env.storage()
+            .persistent()
+            .get(&EmailDataKey::Payment(payment_id))
+            .expect("Payment not found")
+    }
+
+    /// Payments still waiting to be claimed or refunded for an email
+    pub fn get_pending_payments(env: Env, email_hash: BytesN<32>) -> Vec<EmailPayment> {
+        let mut payments = Vec::new(&env);
+        for id in Self::pending_payment_ids(&env, &email_hash).iter() {
+            payments.push_back(Self::get_email_payment(env.clone(), id));
+        }
+        payments
+    }
+
+    fn pending_payment_ids(env: &Env, email_hash: &BytesN<32>) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&EmailDataKey::PendingPayments(email_hash.clone()))
+            .unwrap_or(Vec::new(env))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, String};
+    use crate::errors::AppError;
+    use crate::utils::derive_wallet_address_from_email;
+    use soroban_sdk::{Env, String};
 
     #[test]
     fn same_email_same_address() {
         let env = Env::default();
-        let email = String::from_slice(&env, "user@example.com");
+        let email = String::from_str(&env, "user@example.com");
 
         let addr1 = derive_wallet_address_from_email(&env, &email).expect("valid email");
         let addr2 = derive_wallet_address_from_email(&env, &email).expect("valid email");
@@ -29,8 +517,8 @@ mod tests {
     #[test]
     fn different_emails_different_addresses() {
         let env = Env::default();
-        let email1 = String::from_slice(&env, "alice@example.com");
-        let email2 = String::from_slice(&env, "bob@example.com");
+        let email1 = String::from_str(&env, "alice@example.com");
+        let email2 = String::from_str(&env, "bob@example.com");
 
         let addr1 = derive_wallet_address_from_email(&env, &email1).expect("valid email");
         let addr2 = derive_wallet_address_from_email(&env, &email2).expect("valid email");
@@ -44,7 +532,7 @@ mod tests {
     #[test]
     fn empty_email_should_fail() {
         let env = Env::default();
-        let empty_email = String::from_slice(&env, "");
+        let empty_email = String::from_str(&env, "");
 
         let result = derive_wallet_address_from_email(&env, &empty_email);
         assert!(matches!(result, Err(AppError::InvalidAddress)));
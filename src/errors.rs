@@ -1,14 +1,113 @@
-use soroban_sdk::contracttype;
+use crate::event::EventEmitter;
+use soroban_sdk::{contracterror, Env, Symbol};
 
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Error codes shared by every contract in the crate, so a host error code
+/// means the same thing whichever contract raised it. Each subsystem owns a
+/// block of codes:
+///
+/// - `1..=99` general validation and state errors
+/// - `100..=199` rate locks
+/// - `200..=299` nonces
+/// - `300..=399` fee distribution
+/// - `400..=499` smart wallets
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum AppError {
-    InvalidAmount,
-    InvalidAddress,
-    InvalidTimestamp,
-    InsufficientBalance,
-    UnsupportedCurrency,
-    RateExpired,
-    ConversionLimitExceeded,
-    Unauthorized,
+    InvalidAmount = 1,
+    InvalidAddress = 2,
+    InvalidTimestamp = 3,
+    InsufficientBalance = 4,
+    UnsupportedCurrency = 5,
+    RateExpired = 6,
+    ConversionLimitExceeded = 7,
+    Unauthorized = 8,
+    InvalidConfig = 9,
+    InvalidState = 10,
+    NotFound = 11,
+    NotInitialized = 12,
+    AlreadyInitialized = 13,
+    Paused = 14,
+    /// An amount calculation left the i128 range
+    Overflow = 15,
+
+    NoRateLocked = 100,
+    PairMismatch = 101,
+    AmountExceedsLock = 102,
+    InvalidDuration = 103,
+    LocksPaused = 104,
+    LockNotExpired = 105,
+    QuoteMismatch = 106,
+    RateLimited = 107,
+
+    InvalidNonce = 200,
+
+    InvalidBpsConfig = 300,
+    InvalidBps = 301,
+    FeeDistributionFailed = 302,
+
+    UnknownSigner = 400,
+    MissingOwnerSignature = 401,
+    /// The call breaks a spending policy and no co-signer is configured
+    PolicyViolation = 402,
+    /// The call needs the co-signer's signature
+    CosignerRequired = 403,
+}
+
+impl AppError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppError::InvalidAmount => "InvalidAmount",
+            AppError::InvalidAddress => "InvalidAddress",
+            AppError::InvalidTimestamp => "InvalidTimestamp",
+            AppError::InsufficientBalance => "InsufficientBalance",
+            AppError::UnsupportedCurrency => "UnsupportedCurrency",
+            AppError::RateExpired => "RateExpired",
+            AppError::ConversionLimitExceeded => "ConversionLimitExceeded",
+            AppError::Unauthorized => "Unauthorized",
+            AppError::InvalidConfig => "InvalidConfig",
+            AppError::InvalidState => "InvalidState",
+            AppError::NotFound => "NotFound",
+            AppError::NotInitialized => "NotInitialized",
+            AppError::AlreadyInitialized => "AlreadyInitialized",
+            AppError::Paused => "Paused",
+            AppError::Overflow => "Overflow",
+            AppError::NoRateLocked => "NoRateLocked",
+            AppError::PairMismatch => "PairMismatch",
+            AppError::AmountExceedsLock => "AmountExceedsLock",
+            AppError::InvalidDuration => "InvalidDuration",
+            AppError::LocksPaused => "LocksPaused",
+            AppError::LockNotExpired => "LockNotExpired",
+            AppError::QuoteMismatch => "QuoteMismatch",
+            AppError::RateLimited => "RateLimited",
+            AppError::InvalidNonce => "InvalidNonce",
+            AppError::InvalidBpsConfig => "InvalidBpsConfig",
+            AppError::InvalidBps => "InvalidBps",
+            AppError::FeeDistributionFailed => "FeeDistributionFailed",
+            AppError::UnknownSigner => "UnknownSigner",
+            AppError::MissingOwnerSignature => "MissingOwnerSignature",
+            AppError::PolicyViolation => "PolicyViolation",
+            AppError::CosignerRequired => "CosignerRequired",
+        }
+    }
+}
+
+/// Reports `error` as a `ContractError` event and aborts the invocation with `message`.
+///
+/// Events from a failed invocation are not part of the ledger's contract events, but
+/// they are kept in the transaction's diagnostic events (marked as from a failed call),
+/// which is where monitoring picks up failure patterns.
+pub fn fail(env: &Env, error: AppError, message: &str) -> ! {
+    EventEmitter::emit_contract_error(
+        env,
+        env.current_contract_address(),
+        Symbol::new(env, error.name()),
+        message,
+        &[],
+    );
+    panic!("{}", message)
+}
+
+/// Unwraps a checked amount calculation, aborting the invocation on overflow.
+pub fn or_overflow(env: &Env, result: Result<i128, AppError>) -> i128 {
+    result.unwrap_or_else(|error| fail(env, error, "Arithmetic overflow"))
 }
@@ -1,8 +1,34 @@
+use crate::access::{AccessControl, Role};
+use crate::address_book::BeneficiaryCheck;
+use crate::alias::Alias;
+use crate::audit_log::AdminAudit;
+use crate::blocklist::BlocklistCheck;
+use crate::compliance::{ComplianceCheck, CompliancePolicy};
+use crate::email_to_wallet::EmailToWalletContractClient;
+use crate::errors::{fail, or_overflow, AppError};
+use crate::event::{
+    DisputeNoteAddedData, EscrowContributionData, EscrowCustodyRedirectData, EscrowDeductionData,
+    EscrowDepositSettledData, EscrowDisputeEscalatedData, EscrowPromoRedeemedData,
+    EscrowReleaseDelegationData, EventEnvelope, EventJournal,
+};
+use crate::guardian::{GuardianCheck, Subsystem};
+use crate::idempotency::Idempotency;
+use crate::inheritance::{Inheritance, InheritancePlan};
+use crate::keeper::{KeeperTip, KeeperTips};
+use crate::promo::{PromoCode, Promos};
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::receipt::{PositionKind, ReceiptContractClient};
+use crate::reference::PaymentReference;
+use crate::reputation::{ReputationContractClient, TradeOutcome};
+use crate::rescue::Rescue;
+use crate::storage;
+use crate::upgrade::Upgrade;
+use crate::utils::{checked_add, checked_mul_div, checked_sub};
 use core::fmt::Write;
 use heapless::String as HString;
 use soroban_sdk::{
-    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Env,
-    Symbol, Vec,
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, Symbol, Vec,
 };
 
 /// Status of the escrow operation
@@ -23,6 +49,16 @@ pub enum EscrowStatus {
     DisputeResolvedForRecipient,
     /// Dispute was resolved in favor of sender
     DisputeResolvedForSender,
+    /// Funds owed to a frozen party were sent to the compliance custody address
+    RedirectedToCustody,
+    /// Crowdfunded escrow collecting contributions toward its amount
+    Funding,
+    /// Crowdfunded escrow missed its amount by the deadline; contributors
+    /// reclaim what they paid in
+    FundingFailed,
+    /// Deposit escrow split between the recipient's uncontested deductions
+    /// and the sender, with nothing left in dispute
+    DepositSettled,
 }
 
 /// Dispute information
@@ -39,6 +75,15 @@ pub struct DisputeInfo {
     pub reason: Symbol,
 }
 
+/// Hand-off of an expired dispute to the arbitration panel
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeEscalation {
+    /// Panel the dispute went to; only it can settle the dispute
+    pub panel: Address,
+    pub escalated_at: u64,
+}
+
 /// Configuration for the escrow
 #[contracttype]
 #[derive(Clone)]
@@ -80,6 +125,112 @@ pub struct EscrowInfo {
     pub has_dispute: bool,
 }
 
+/// Reputation requirements applied to escrow counterparties
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationPolicy {
+    /// Reputation contract that scores counterparties and records outcomes
+    pub registry: Address,
+    /// Lowest score either party may have to open an escrow
+    pub min_score: u32,
+    /// Parties scoring below this pay the higher dispute fee
+    pub low_score_threshold: u32,
+    /// Dispute fee charged to low-score parties, in basis points of the base fee
+    pub low_score_dispute_fee_bps: u32,
+}
+
+/// Operator a sender has approved to release one escrow on their behalf
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseDelegation {
+    pub operator: Address,
+    /// Last timestamp at which the operator may release
+    pub expires_at: u64,
+}
+
+/// Hash of one off-chain mediation message, anchored to a dispute
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeNote {
+    pub author: Address,
+    pub hash: BytesN<32>,
+    pub added_at: u64,
+}
+
+/// Contributions toward a crowdfunded escrow. The escrow's amount is the
+/// target; it turns active once contributions reach it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Crowdfund {
+    /// Time by which the target must be reached
+    pub deadline: u64,
+    pub raised: i128,
+    pub contributors: u32,
+    /// What contributors can reclaim, split pro-rata to their contributions;
+    /// zero unless the funding failed or the escrow was refunded
+    pub refundable: i128,
+}
+
+/// State of one deduction claimed against a deposit escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DeductionStatus {
+    /// Paid to the recipient at settlement unless contested first
+    Claimed,
+    /// Contested by the sender; goes to dispute resolution at settlement
+    Contested,
+}
+
+/// An itemized deduction the recipient of a deposit escrow claims from it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deduction {
+    pub amount: i128,
+    pub reason: Symbol,
+    pub claimed_at: u64,
+    pub status: DeductionStatus,
+}
+
+/// Upper bound on notes per dispute, to keep the log within one entry's budget
+pub const MAX_DISPUTE_NOTES: u32 = 100;
+
+/// Upper bound on deductions claimed against one deposit
+pub const MAX_DEDUCTIONS: u32 = 20;
+
+// Who a settlement pays
+enum Payout {
+    Recipient,
+    Sender,
+    /// An address standing in for the recipient: compliance custody or a
+    /// timeout fallback
+    Redirect(Address),
+}
+
+/// What `release` would do if called now, computed without changing state
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleasePreview {
+    pub escrow_id: Symbol,
+    /// Who would be paid: the holder of the escrow's receipt, or the recipient
+    pub payee: Address,
+    pub token: Address,
+    /// Amount transferred to the payee; releases carry no fee
+    pub amount: i128,
+    pub status: EscrowStatus,
+}
+
+/// Where an escrow's funds go when it times out without being released
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimeoutDestination {
+    /// Pay the recipient, as with a release
+    Recipient,
+    /// Return the funds to the sender, for deposit-style escrows
+    Sender,
+    /// Pay a third address named by the sender
+    Fallback(Address),
+}
+
 #[contract]
 pub struct EscrowContract;
 
@@ -95,15 +246,135 @@ pub trait EscrowContractTrait {
         dispute_period: u64,
     ) -> EscrowInfo;
 
+    #[allow(clippy::too_many_arguments)]
+    fn create_idempotent(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        key: BytesN<32>,
+    ) -> EscrowInfo;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_with_timeout_destination(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        destination: TimeoutDestination,
+    ) -> EscrowInfo;
+    fn get_timeout_destination(env: Env, escrow_id: Symbol) -> TimeoutDestination;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_with_reference(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        reference: BytesN<32>,
+    ) -> EscrowInfo;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_with_promo(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        code: Bytes,
+    ) -> EscrowInfo;
+    fn issue_escrow_promo_code(
+        env: Env,
+        caller: Address,
+        code_hash: BytesN<32>,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> PromoCode;
+    fn revoke_escrow_promo_code(env: Env, caller: Address, code_hash: BytesN<32>);
+    fn get_escrow_promo_code(env: Env, code_hash: BytesN<32>) -> Option<PromoCode>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_crowdfunded(
+        env: Env,
+        organizer: Address,
+        recipient: Address,
+        token: Address,
+        target: i128,
+        funding_deadline: u64,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo;
+    fn contribute_to_escrow(env: Env, funder: Address, escrow_id: Symbol, amount: i128) -> i128;
+    fn claim_contribution_refund(env: Env, funder: Address, escrow_id: Symbol) -> i128;
+    fn get_crowdfund(env: Env, escrow_id: Symbol) -> Option<Crowdfund>;
+    fn get_contribution(env: Env, escrow_id: Symbol, funder: Address) -> i128;
+    fn create_deposit(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo;
+    fn claim_deduction(env: Env, escrow_id: Symbol, amount: i128, reason: Symbol) -> u32;
+    fn contest_deduction(env: Env, escrow_id: Symbol, index: u32);
+    fn get_deductions(env: Env, escrow_id: Symbol) -> Vec<Deduction>;
+    fn settle_deposit(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo;
+
+    fn create_for_alias(
+        env: Env,
+        sender: Address,
+        alias: Alias,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo;
+
     fn release(env: Env, escrow_id: Symbol) -> EscrowInfo;
+    fn release_with_reference(env: Env, escrow_id: Symbol, reference: BytesN<32>) -> EscrowInfo;
+    fn simulate_release(env: Env, escrow_id: Symbol) -> ReleasePreview;
+    fn approve_release_operator(
+        env: Env,
+        escrow_id: Symbol,
+        operator: Address,
+        expires_at: u64,
+    ) -> ReleaseDelegation;
+    fn revoke_release_operator(env: Env, escrow_id: Symbol);
+    fn get_release_operator(env: Env, escrow_id: Symbol) -> Option<ReleaseDelegation>;
+    fn release_as_operator(env: Env, operator: Address, escrow_id: Symbol) -> EscrowInfo;
+    fn add_dispute_note(env: Env, author: Address, escrow_id: Symbol, hash: BytesN<32>) -> u32;
+    fn get_dispute_notes(env: Env, escrow_id: Symbol) -> Vec<DisputeNote>;
     fn refund(env: Env, escrow_id: Symbol) -> EscrowInfo;
-    fn check_timeout(env: Env, escrow_id: Symbol) -> EscrowInfo;
+    fn check_timeout(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo;
     fn get_escrow(env: Env, escrow_id: Symbol) -> EscrowInfo;
     fn get_all_escrows(env: Env) -> Vec<EscrowInfo>;
     fn initiate_dispute(env: Env, escrow_id: Symbol, reason: Symbol) -> EscrowInfo;
     fn resolve_dispute_for_recipient(env: Env, escrow_id: Symbol) -> EscrowInfo;
     fn resolve_dispute_for_sender(env: Env, escrow_id: Symbol) -> EscrowInfo;
-    fn check_dispute_timeout(env: Env, escrow_id: Symbol) -> EscrowInfo;
+    fn check_dispute_timeout(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo;
+    fn set_arbitration_panel(env: Env, caller: Address, panel: Option<Address>);
+    fn get_arbitration_panel(env: Env) -> Option<Address>;
+    fn get_dispute_escalation(env: Env, escrow_id: Symbol) -> Option<DisputeEscalation>;
+    fn resolve_escalated_dispute(
+        env: Env,
+        panel: Address,
+        escrow_id: Symbol,
+        resolve_for_recipient: bool,
+    ) -> EscrowInfo;
     fn get_dispute_info(env: Env, escrow_id: Symbol) -> Option<DisputeInfo>;
     fn can_dispute(env: Env, escrow_id: Symbol) -> bool;
     fn get_escrow_count(env: Env) -> u32;
@@ -112,14 +383,59 @@ pub trait EscrowContractTrait {
     fn get_escrows_by_participant(env: Env, participant: Address) -> Vec<EscrowInfo>;
     fn update_dispute_period(env: Env, escrow_id: Symbol, new_dispute_period: u64) -> EscrowInfo;
     fn initialize(env: Env, admin: Address);
-    fn set_dispute_fee(env: Env, fee: i128);
+    fn set_dispute_fee(env: Env, caller: Address, fee: i128);
+    fn set_escrow_alias_registry(env: Env, caller: Address, registry: Address);
+    fn set_reputation_policy(env: Env, caller: Address, policy: Option<ReputationPolicy>);
+    fn get_reputation_policy(env: Env) -> Option<ReputationPolicy>;
+    fn set_escrow_compliance_policy(env: Env, caller: Address, policy: Option<CompliancePolicy>);
+    fn get_escrow_compliance_policy(env: Env) -> Option<CompliancePolicy>;
+    fn set_escrow_blocklist(env: Env, caller: Address, blocklist: Option<Address>);
+    fn get_escrow_blocklist(env: Env) -> Option<Address>;
+    fn set_escrow_address_book(env: Env, caller: Address, address_book: Option<Address>);
+    fn get_escrow_address_book(env: Env) -> Option<Address>;
+    fn set_escrow_custody(env: Env, caller: Address, custody: Option<Address>);
+    fn get_escrow_custody(env: Env) -> Option<Address>;
+    fn redirect_to_custody(env: Env, caller: Address, escrow_id: Symbol) -> EscrowInfo;
+    fn set_escrow_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>);
+    fn get_escrow_rescue_timelock(env: Env) -> Option<Address>;
+    fn rescue_escrow_tokens(env: Env, token: Address, amount: i128, to: Address);
+    fn set_escrow_guardian(env: Env, caller: Address, guardian: Option<Address>);
+    fn get_escrow_guardian(env: Env) -> Option<Address>;
+    fn set_escrow_receipts(env: Env, caller: Address, receipts: Option<Address>);
+    fn get_escrow_receipts(env: Env) -> Option<Address>;
+    fn get_escrow_receipt(env: Env, escrow_id: Symbol) -> Option<(Address, u64)>;
+    fn set_dispute_rate_limit(env: Env, caller: Address, limit: Option<RateLimit>);
+    fn get_dispute_rate_limit(env: Env) -> Option<RateLimit>;
+    fn set_escrow_keeper_tip(env: Env, caller: Address, tip: Option<KeeperTip>);
+    fn get_escrow_keeper_tip(env: Env) -> Option<KeeperTip>;
+    fn set_escrow_beneficiary(
+        env: Env,
+        owner: Address,
+        beneficiary: Option<Address>,
+        inactivity_period: u64,
+    );
+    fn get_escrow_inheritance(env: Env, owner: Address) -> Option<InheritancePlan>;
+    fn announce_escrow_inheritance(env: Env, beneficiary: Address, owner: Address);
+    fn claim_escrow_inheritance(env: Env, beneficiary: Address, owner: Address) -> Vec<Symbol>;
     fn get_dispute_fee(env: Env) -> i128;
+    fn set_event_journal_capacity(env: Env, caller: Address, capacity: u32);
+    fn get_recent_events(env: Env, offset: u32, limit: u32) -> Vec<EventEnvelope>;
     fn get_admin(env: Env) -> Address;
     fn transfer_admin(env: Env, new_admin: Address);
-    fn set_paused(env: Env, paused: bool);
+    fn set_escrow_audit_log(env: Env, caller: Address, audit_log: Option<Address>);
+    fn get_escrow_audit_log(env: Env) -> Option<Address>;
+    fn grant_escrow_role(env: Env, owner: Address, role: Role, account: Address);
+    fn revoke_escrow_role(env: Env, owner: Address, role: Role, account: Address);
+    fn renounce_escrow_role(env: Env, account: Address, role: Role);
+    fn has_escrow_role(env: Env, role: Role, account: Address) -> bool;
+    fn upgrade_escrow(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
+    fn migrate_escrow(env: Env) -> u32;
+    fn get_escrow_storage_version(env: Env) -> u32;
+    fn set_paused(env: Env, caller: Address, paused: bool);
     fn is_paused(env: Env) -> bool;
     fn admin_resolve_dispute(
         env: Env,
+        caller: Address,
         escrow_id: Symbol,
         resolve_for_recipient: bool,
     ) -> EscrowInfo;
@@ -128,6 +444,29 @@ pub trait EscrowContractTrait {
 const ESCROW_COUNT_KEY: Symbol = symbol_short!("CNT");
 const DISPUTE_FEE_KEY: Symbol = symbol_short!("DFEE");
 const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+const ALIAS_REGISTRY_KEY: Symbol = symbol_short!("ALIASREG");
+const REPUTATION_POLICY_KEY: Symbol = symbol_short!("REPPOL");
+const COMPLIANCE_POLICY_KEY: Symbol = symbol_short!("KYCPOL");
+const BLOCKLIST_KEY: Symbol = symbol_short!("BLOCKLIST");
+const ADDRESS_BOOK_KEY: Symbol = symbol_short!("ADDRBOOK");
+const CUSTODY_KEY: Symbol = symbol_short!("CUSTODY");
+const GUARDIAN_KEY: Symbol = symbol_short!("GUARDIAN");
+const RECEIPTS_KEY: Symbol = symbol_short!("RECEIPTS");
+const RECEIPT_KEY: Symbol = symbol_short!("RECEIPT");
+const DELEGATE_KEY: Symbol = symbol_short!("DELEGATE");
+const NOTES_KEY: Symbol = symbol_short!("NOTES");
+const TIMEOUT_KEY: Symbol = symbol_short!("TIMEOUT");
+const PROMO_KEY: Symbol = symbol_short!("PROMO");
+const ARBITRATION_PANEL_KEY: Symbol = symbol_short!("ARBPANEL");
+const ESCALATION_KEY: Symbol = symbol_short!("ESCALATE");
+const PROCESSING_KEY: Symbol = symbol_short!("PROC");
+const CROWDFUND_KEY: Symbol = symbol_short!("CROWD");
+const CONTRIBUTION_KEY: Symbol = symbol_short!("CONTRIB");
+const DEPOSIT_KEY: Symbol = symbol_short!("DEPOSIT");
+const DISPUTE_FN: Symbol = symbol_short!("dispute");
+
+// Bump when the storage layout changes and add a step to `migrate_escrow`
+const STORAGE_VERSION: u32 = 2;
 
 #[contractimpl]
 impl EscrowContract {
@@ -141,511 +480,837 @@ impl EscrowContract {
         timeout_duration: u64,
         dispute_period: u64,
     ) -> EscrowInfo {
-        // Check if contract is paused
-        if env
-            .storage()
-            .instance()
-            .get(&symbol_short!("PAUSED"))
-            .unwrap_or(false)
-        {
-            panic!("Contract is paused");
-        }
-
-        // Validate inputs
-        if amount <= 0 {
-            panic!("Amount must be positive");
-        }
-        if timeout_duration == 0 {
-            panic!("Timeout duration must be non-zero");
-        }
-        if dispute_period == 0 {
-            panic!("Dispute period must be non-zero");
-        }
-        if timeout_duration < dispute_period {
-            panic!("Timeout duration must be greater than dispute period");
-        }
-        if sender == recipient {
-            panic!("Sender and recipient cannot be the same");
-        }
-
-        // Authenticate the sender
-        sender.require_auth();
-
-        // Verify token contract exists and sender has sufficient balance
-        // Note: In production, this would always check the balance
-        // For testing with mock addresses, we skip the balance check
-        let client = token::Client::new(&env, &token);
-        let sender_balance = client.balance(&sender);
-        if sender_balance < amount {
-            panic!("Insufficient token balance");
-        }
-
-        // Transfer tokens from sender to contract
-        let client = token::Client::new(&env, &token);
-        client.transfer(&sender, &env.current_contract_address(), &amount);
-
-        // Create a unique ID for this escrow
-        let count = env
-            .storage()
-            .instance()
-            .get(&ESCROW_COUNT_KEY)
-            .unwrap_or(0u32);
-        let mut s: HString<12> = HString::new();
-        s.push_str("escrow_").unwrap();
-        write!(&mut s, "{}", count).unwrap();
-        let id = Symbol::new(&env, s.as_str());
-        env.storage()
-            .instance()
-            .set(&ESCROW_COUNT_KEY, &(count + 1));
-
-        // Get current timestamp
-        let created_at = env.ledger().timestamp();
-
-        // Store escrow configuration
-        let escrow = EscrowConfig {
-            id: id.clone(),
-            sender: sender.clone(),
-            recipient: recipient.clone(),
-            token: token.clone(),
+        Self::open(
+            env,
+            sender,
+            recipient,
+            token,
             amount,
-            created_at,
             timeout_duration,
             dispute_period,
-            status: EscrowStatus::Active,
-            has_dispute: false,
-        };
+            None,
+        )
+    }
 
-        crate::event::EventEmitter::emit_escrow_created(
-            &env,
-            id.clone(),
+    /// Create an escrow under a client-generated idempotency key. Repeating a
+    /// key the sender already used returns that escrow instead of opening
+    /// another one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_idempotent(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        key: BytesN<32>,
+    ) -> EscrowInfo {
+        if let Some(escrow_id) = Idempotency::recall(&env, &sender, &key) {
+            sender.require_auth();
+            return Self::get_escrow(env, escrow_id);
+        }
+
+        let info = Self::create(
+            env.clone(),
             sender.clone(),
-            recipient.clone(),
-            token.clone(),
+            recipient,
+            token,
             amount,
             timeout_duration,
+            dispute_period,
         );
+        Idempotency::remember(&env, &sender, &key, &info.id);
+        info
+    }
 
-        // Save the escrow
-        env.storage().instance().set(&id, &escrow);
-
-        // Return escrow info
-        EscrowInfo {
-            id,
+    /// Create an escrow whose funds go to `destination` rather than the
+    /// recipient if it times out without a release
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_with_timeout_destination(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        destination: TimeoutDestination,
+    ) -> EscrowInfo {
+        let info = Self::create(
+            env.clone(),
             sender,
             recipient,
             token,
             amount,
-            created_at,
-            timeout_at: created_at + timeout_duration,
+            timeout_duration,
             dispute_period,
-            status: EscrowStatus::Active,
-            has_dispute: false,
+        );
+        if destination != TimeoutDestination::Recipient {
+            storage::write(&env, &(info.id.clone(), TIMEOUT_KEY), &destination);
         }
+        info
     }
 
-    /// Release funds to the recipient (can only be called by sender)
-    pub fn release(env: Env, escrow_id: Symbol) -> EscrowInfo {
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+    pub fn get_timeout_destination(env: Env, escrow_id: Symbol) -> TimeoutDestination {
+        storage::read(&env, &(escrow_id, TIMEOUT_KEY)).unwrap_or(TimeoutDestination::Recipient)
+    }
 
-        // Validate the escrow is active (not disputed)
-        if escrow.status != EscrowStatus::Active {
-            panic!("Escrow is not active or is disputed");
-        }
+    /// Create an escrow whose events carry the backend's payment reference
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_with_reference(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        reference: BytesN<32>,
+    ) -> EscrowInfo {
+        PaymentReference::attach(&env, &reference, || {
+            Self::create(
+                env.clone(),
+                sender,
+                recipient,
+                token,
+                amount,
+                timeout_duration,
+                dispute_period,
+            )
+        })
+    }
 
-        // Require sender authorization
-        escrow.sender.require_auth();
+    /// Create an escrow with a promo code that waives part or all of its
+    /// dispute fee. Each call uses up one of the code's redemptions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_with_promo(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        code: Bytes,
+    ) -> EscrowInfo {
+        let (code_hash, discount_bps) = Promos::redeem(&env, &code);
+        let info = Self::create(
+            env.clone(),
+            sender.clone(),
+            recipient,
+            token,
+            amount,
+            timeout_duration,
+            dispute_period,
+        );
+        storage::write(&env, &(info.id.clone(), PROMO_KEY), &discount_bps);
+        crate::event::EventEmitter::emit_escrow_promo_redeemed(
+            &env,
+            EscrowPromoRedeemedData {
+                escrow_id: info.id.clone(),
+                code_hash,
+                sender,
+                discount_bps,
+            },
+        );
+        info
+    }
 
-        // Transfer the tokens to the recipient
-        let client = token::Client::new(&env, &escrow.token);
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.recipient,
-            &escrow.amount,
+    /// Register a promo code by its SHA-256 hash (owner only)
+    pub fn issue_escrow_promo_code(
+        env: Env,
+        caller: Address,
+        code_hash: BytesN<32>,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> PromoCode {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "issue_escrow_promo_code",
+            (code_hash.clone(), discount_bps, max_uses, expires_at),
         );
+        Promos::issue(&env, &code_hash, discount_bps, max_uses, expires_at)
+    }
 
-        // Emit escrow release event
-        crate::event::EventEmitter::emit_escrow_released(
+    /// Withdraw a promo code before it runs out (owner only). Escrows already
+    /// created with it keep their discount.
+    pub fn revoke_escrow_promo_code(env: Env, caller: Address, code_hash: BytesN<32>) {
+        AccessControl::require_audited_role(
             &env,
-            escrow_id.clone(),
-            escrow.sender.clone(),
-            escrow.recipient.clone(),
-            escrow.token.clone(),
-            escrow.amount,
+            Role::Owner,
+            &caller,
+            "revoke_escrow_promo_code",
+            code_hash.clone(),
         );
+        Promos::revoke(&env, &code_hash);
+    }
 
-        // Update the escrow status
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::Released,
-            ..escrow.clone()
-        };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
-
-        // Return updated escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: escrow.dispute_period,
-            status: EscrowStatus::Released,
-            has_dispute: escrow.has_dispute,
-        }
+    pub fn get_escrow_promo_code(env: Env, code_hash: BytesN<32>) -> Option<PromoCode> {
+        Promos::get(&env, &code_hash)
     }
 
-    /// Refund the tokens back to the sender (can be called by both sender and recipient)
-    pub fn refund(env: Env, escrow_id: Symbol) -> EscrowInfo {
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+    /// Open an escrow that several funders pay into instead of the organizer.
+    /// It turns active once contributions reach `target`; if they fall short
+    /// by `funding_deadline`, each funder reclaims their contribution.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_crowdfunded(
+        env: Env,
+        organizer: Address,
+        recipient: Address,
+        token: Address,
+        target: i128,
+        funding_deadline: u64,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo {
+        Self::open(
+            env,
+            organizer,
+            recipient,
+            token,
+            target,
+            timeout_duration,
+            dispute_period,
+            Some(funding_deadline),
+        )
+    }
 
-        // Validate the escrow is active
-        if escrow.status != EscrowStatus::Active {
-            panic!("Escrow is not active");
+    /// Pay into a crowdfunded escrow (funder auth). Only what the escrow
+    /// still needs is taken; returns the amount accepted. The escrow's
+    /// timeout starts when it becomes fully funded.
+    pub fn contribute_to_escrow(
+        env: Env,
+        funder: Address,
+        escrow_id: Symbol,
+        amount: i128,
+    ) -> i128 {
+        Self::require_operational(&env);
+        if env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+        {
+            fail(&env, AppError::Paused, "Contract is paused");
+        }
+        if amount <= 0 {
+            fail(&env, AppError::InvalidAmount, "Amount must be positive");
         }
+        funder.require_auth();
+        Inheritance::record_activity(&env, &funder);
 
-        // For now, we'll just require the sender to authenticate for refund
-        // This is a simplification but ensures security
-        escrow.sender.require_auth();
+        let mut escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Funding {
+            fail(&env, AppError::InvalidState, "Escrow is not raising funds");
+        }
+        let mut crowdfund = Self::crowdfund(&env, &escrow_id);
+        let now = env.ledger().timestamp();
+        if now >= crowdfund.deadline {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Funding deadline has passed",
+            );
+        }
+        if Self::is_frozen(&env, &funder) {
+            fail(&env, AppError::Unauthorized, "Address is blocked");
+        }
 
-        // Transfer the tokens back to the sender
-        let client = token::Client::new(&env, &escrow.token);
-        client.transfer(
+        let accepted = amount.min(or_overflow(
+            &env,
+            checked_sub(escrow.amount, crowdfund.raised),
+        ));
+        token::Client::new(&env, &escrow.token).transfer(
+            &funder,
             &env.current_contract_address(),
-            &escrow.sender,
-            &escrow.amount,
+            &accepted,
         );
 
-        // Update the escrow status
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::Refunded,
-            ..escrow.clone()
-        };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
-
-        // Return updated escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: escrow.dispute_period,
-            status: EscrowStatus::Refunded,
-            has_dispute: escrow.has_dispute,
+        let key = (escrow_id.clone(), CONTRIBUTION_KEY, funder.clone());
+        let contributed: i128 = storage::read(&env, &key).unwrap_or(0);
+        if contributed == 0 {
+            crowdfund.contributors = crowdfund
+                .contributors
+                .checked_add(1)
+                .unwrap_or_else(|| fail(&env, AppError::Overflow, "Arithmetic overflow"));
         }
-    }
-
-    /// Check if the escrow has timed out and release funds if necessary
-    pub fn check_timeout(env: Env, escrow_id: Symbol) -> EscrowInfo {
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+        storage::write(
+            &env,
+            &key,
+            &or_overflow(&env, checked_add(contributed, accepted)),
+        );
+        crowdfund.raised = or_overflow(&env, checked_add(crowdfund.raised, accepted));
+        storage::write(&env, &(escrow_id.clone(), CROWDFUND_KEY), &crowdfund);
 
-        // Validate the escrow is active
-        if escrow.status != EscrowStatus::Active {
-            panic!("Escrow is not active");
+        if crowdfund.raised == escrow.amount {
+            escrow.status = EscrowStatus::Active;
+            escrow.created_at = now;
+            storage::write(&env, &escrow_id, &escrow);
         }
 
-        // Check if timeout has been reached
-        let current_time = env.ledger().timestamp();
-        let timeout_time = escrow.created_at + escrow.timeout_duration;
+        crate::event::EventEmitter::emit_escrow_contribution(
+            &env,
+            EscrowContributionData {
+                escrow_id,
+                funder,
+                amount: accepted,
+                raised: crowdfund.raised,
+            },
+            false,
+        );
+        accepted
+    }
 
-        if current_time < timeout_time {
-            panic!("Escrow has not timed out yet");
+    /// Take back a contribution (funder auth): all of it once the escrow has
+    /// missed its target, or a pro-rata share of the refund once an active
+    /// crowdfunded escrow is refunded. Returns the amount paid out.
+    pub fn claim_contribution_refund(env: Env, funder: Address, escrow_id: Symbol) -> i128 {
+        Self::require_operational(&env);
+        funder.require_auth();
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        let mut crowdfund = Self::crowdfund(&env, &escrow_id);
+        // The first claim after the deadline closes the funding round
+        if escrow.status == EscrowStatus::Funding {
+            if env.ledger().timestamp() < crowdfund.deadline {
+                fail(&env, AppError::InvalidState, "Funding is still open");
+            }
+            let failed = EscrowConfig {
+                status: EscrowStatus::FundingFailed,
+                ..escrow.clone()
+            };
+            storage::write(&env, &escrow_id, &failed);
+            crowdfund.refundable = crowdfund.raised;
+            storage::write(&env, &(escrow_id.clone(), CROWDFUND_KEY), &crowdfund);
+        }
+        if crowdfund.refundable == 0 {
+            fail(&env, AppError::InvalidState, "Nothing to refund");
         }
 
-        // Transfer the tokens to the recipient (auto-release)
-        let client = token::Client::new(&env, &escrow.token);
-        client.transfer(
+        let key = (escrow_id.clone(), CONTRIBUTION_KEY, funder.clone());
+        let contributed: i128 = storage::read(&env, &key).unwrap_or(0);
+        if contributed == 0 {
+            fail(&env, AppError::NotFound, "No contribution to refund");
+        }
+        env.storage().persistent().remove(&key);
+        let share = or_overflow(
+            &env,
+            checked_mul_div(contributed, crowdfund.refundable, crowdfund.raised),
+        );
+        token::Client::new(&env, &escrow.token).transfer(
             &env.current_contract_address(),
-            &escrow.recipient,
-            &escrow.amount,
+            &funder,
+            &share,
         );
 
-        // Update the escrow status
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::AutoReleased,
-            ..escrow.clone()
-        };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
-
-        // Return updated escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: timeout_time,
-            dispute_period: escrow.dispute_period,
-            status: EscrowStatus::AutoReleased,
-            has_dispute: escrow.has_dispute,
-        }
+        crate::event::EventEmitter::emit_escrow_contribution(
+            &env,
+            EscrowContributionData {
+                escrow_id,
+                funder,
+                amount: share,
+                raised: crowdfund.raised,
+            },
+            true,
+        );
+        share
     }
 
-    /// Get information about an escrow
-    pub fn get_escrow(env: Env, escrow_id: Symbol) -> EscrowInfo {
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
-
-        // Return escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: escrow.dispute_period,
-            status: escrow.status,
-            has_dispute: escrow.has_dispute,
-        }
+    pub fn get_crowdfund(env: Env, escrow_id: Symbol) -> Option<Crowdfund> {
+        storage::read(&env, &(escrow_id, CROWDFUND_KEY))
     }
 
-    /// Get all active escrows
-    pub fn get_all_escrows(env: Env) -> Vec<EscrowInfo> {
-        let count = env
-            .storage()
-            .instance()
-            .get(&ESCROW_COUNT_KEY)
-            .unwrap_or(0u32);
-        let mut escrows = Vec::new(&env);
-        for i in 0..count {
-            let mut s: HString<12> = HString::new();
-            s.push_str("escrow_").unwrap();
-            write!(&mut s, "{}", i).unwrap();
-            let id = Symbol::new(&env, s.as_str());
-            if env.storage().instance().has(&id) {
-                let escrow: EscrowConfig = env.storage().instance().get(&id).unwrap();
-                escrows.push_back(EscrowInfo {
-                    id: escrow.id,
-                    sender: escrow.sender,
-                    recipient: escrow.recipient,
-                    token: escrow.token,
-                    amount: escrow.amount,
-                    created_at: escrow.created_at,
-                    timeout_at: escrow.created_at + escrow.timeout_duration,
-                    dispute_period: escrow.dispute_period,
-                    status: escrow.status,
-                    has_dispute: escrow.has_dispute,
-                });
-            }
-        }
-        escrows
+    pub fn get_contribution(env: Env, escrow_id: Symbol, funder: Address) -> i128 {
+        storage::read(&env, &(escrow_id, CONTRIBUTION_KEY, funder)).unwrap_or(0)
     }
 
-    /// Initiate a dispute (can be called by sender or recipient)
-    pub fn initiate_dispute(env: Env, escrow_id: Symbol, reason: Symbol) -> EscrowInfo {
-        // Check if contract is paused
-        if env
-            .storage()
-            .instance()
-            .get(&symbol_short!("PAUSED"))
-            .unwrap_or(false)
-        {
-            panic!("Contract is paused");
-        }
-
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+    /// Open a security deposit. Before the timeout the recipient may claim
+    /// itemized deductions, each of which the sender can contest; the deposit
+    /// is then split by `settle_deposit` rather than released or refunded
+    /// whole.
+    pub fn create_deposit(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo {
+        let info = Self::create(
+            env.clone(),
+            sender,
+            recipient,
+            token,
+            amount,
+            timeout_duration,
+            dispute_period,
+        );
+        storage::write(
+            &env,
+            &(info.id.clone(), DEPOSIT_KEY),
+            &Vec::<Deduction>::new(&env),
+        );
+        info
+    }
 
-        // Check if dispute already exists first
-        if escrow.has_dispute {
-            panic!("Dispute already initiated");
+    /// Claim a deduction from a deposit (recipient auth) before it times out.
+    /// Deductions together may not exceed the deposit. Returns the
+    /// deduction's index.
+    pub fn claim_deduction(env: Env, escrow_id: Symbol, amount: i128, reason: Symbol) -> u32 {
+        Self::require_operational(&env);
+        if amount <= 0 {
+            fail(&env, AppError::InvalidAmount, "Amount must be positive");
         }
 
-        // Validate the escrow is active
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
         if escrow.status != EscrowStatus::Active {
-            panic!("Escrow is not active");
+            fail(&env, AppError::InvalidState, "Escrow is not active");
         }
+        escrow.recipient.require_auth();
+        Inheritance::record_activity(&env, &escrow.recipient);
 
-        // For now, we'll allow both sender and recipient to initiate disputes
-        // In a production system, you might want more sophisticated authorization
-        escrow.sender.require_auth();
-        let caller = escrow.sender.clone();
-
-        // Handle dispute fee if set
-        let dispute_fee = env
-            .storage()
-            .instance()
-            .get(&DISPUTE_FEE_KEY)
-            .unwrap_or(0i128);
-        if dispute_fee > 0 {
-            let client = token::Client::new(&env, &escrow.token);
-            let caller_balance = client.balance(&caller);
-            if caller_balance < dispute_fee {
-                panic!("Insufficient balance for dispute fee");
-            }
-            // Transfer dispute fee to contract (could be sent to admin or burned)
-            client.transfer(&caller, &env.current_contract_address(), &dispute_fee);
+        let now = env.ledger().timestamp();
+        if now >= escrow.created_at + escrow.timeout_duration {
+            fail(&env, AppError::InvalidTimestamp, "Deposit has timed out");
+        }
+        let mut deductions = Self::deductions(&env, &escrow_id);
+        if deductions.len() >= MAX_DEDUCTIONS {
+            fail(&env, AppError::InvalidState, "Too many deductions");
+        }
+        let mut claimed = amount;
+        for deduction in deductions.iter() {
+            claimed = or_overflow(&env, checked_add(claimed, deduction.amount));
+        }
+        if claimed > escrow.amount {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Deductions exceed the deposit",
+            );
         }
 
-        // Create dispute info and store separately
-        let dispute_info = DisputeInfo {
-            initiated_by: caller.clone(),
-            initiated_at: env.ledger().timestamp(),
-            dispute_period: escrow.dispute_period,
+        let index = deductions.len();
+        deductions.push_back(Deduction {
+            amount,
             reason: reason.clone(),
-        };
-
-        // Store dispute info separately using a simple key pattern
-        let dispute_key = symbol_short!("DISPUTE");
-        env.storage()
-            .instance()
-            .set(&(escrow_id.clone(), dispute_key), &dispute_info);
-
-        // Update escrow with dispute
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::Disputed,
-            has_dispute: true,
-            ..escrow.clone()
-        };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
+            claimed_at: now,
+            status: DeductionStatus::Claimed,
+        });
+        storage::write(&env, &(escrow_id.clone(), DEPOSIT_KEY), &deductions);
 
-        // Emit dispute initiated event
-        crate::event::EventEmitter::emit_dispute_initiated(
+        crate::event::EventEmitter::emit_escrow_deduction(
             &env,
-            escrow_id.clone(),
-            caller,
-            reason,
-            dispute_info.dispute_period,
+            EscrowDeductionData {
+                escrow_id,
+                index,
+                amount,
+                reason,
+            },
+            false,
         );
-
-        // Return updated escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: escrow.dispute_period,
-            status: EscrowStatus::Disputed,
-            has_dispute: true,
-        }
+        index
     }
 
-    /// Resolve dispute in favor of recipient (admin function or automated)
-    pub fn resolve_dispute_for_recipient(env: Env, escrow_id: Symbol) -> EscrowInfo {
-        // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+    /// Contest one deduction (sender auth) within the dispute period of its
+    /// claim. Only contested deductions go to dispute resolution.
+    pub fn contest_deduction(env: Env, escrow_id: Symbol, index: u32) {
+        Self::require_operational(&env);
 
-        // Validate the escrow is disputed
-        if escrow.status != EscrowStatus::Disputed {
-            panic!("Escrow is not disputed");
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+        escrow.sender.require_auth();
+        Inheritance::record_activity(&env, &escrow.sender);
+
+        let mut deductions = Self::deductions(&env, &escrow_id);
+        let mut deduction = deductions
+            .get(index)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Deduction not found"));
+        if deduction.status != DeductionStatus::Claimed {
+            fail(&env, AppError::InvalidState, "Deduction already contested");
+        }
+        if env.ledger().timestamp() >= deduction.claimed_at + escrow.dispute_period {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Contest window has closed",
+            );
+        }
+        // The first contest opens the deposit's dispute, so it pays the
+        // dispute fee `initiate_dispute` would charge
+        let first = !deductions
+            .iter()
+            .any(|claimed| claimed.status == DeductionStatus::Contested);
+        deduction.status = DeductionStatus::Contested;
+        deductions.set(index, deduction.clone());
+        storage::write(&env, &(escrow_id.clone(), DEPOSIT_KEY), &deductions);
+        if first {
+            let dispute_fee = Self::dispute_fee_for(&env, &escrow_id, &escrow.sender);
+            Self::collect_dispute_fee(&env, &escrow.token, &escrow.sender, dispute_fee);
         }
 
-        // Get dispute info
-        let dispute_key = symbol_short!("DISPUTE");
-        let dispute: DisputeInfo = env
-            .storage()
-            .instance()
-            .get(&(escrow_id.clone(), dispute_key))
-            .unwrap();
+        crate::event::EventEmitter::emit_escrow_deduction(
+            &env,
+            EscrowDeductionData {
+                escrow_id,
+                index,
+                amount: deduction.amount,
+                reason: deduction.reason,
+            },
+            true,
+        );
+    }
 
-        // Check if dispute period has expired (auto-resolution)
-        let current_time = env.ledger().timestamp();
-        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
+    /// Deductions claimed against a deposit, in the order they were claimed
+    pub fn get_deductions(env: Env, escrow_id: Symbol) -> Vec<Deduction> {
+        storage::read(&env, &(escrow_id, DEPOSIT_KEY)).unwrap_or_else(|| Vec::new(&env))
+    }
 
-        if current_time < dispute_expires_at {
-            // Manual resolution - require sender auth for now
-            escrow.sender.require_auth();
+    /// Split a deposit once it has timed out and no deduction can still be
+    /// contested. Uncontested deductions go to the recipient and the rest
+    /// back to the sender; contested deductions stay in escrow, which becomes
+    /// a dispute over just that amount, settled only by an operator or the
+    /// arbitration panel. Callable by anyone; `keeper` is paid the keeper tip
+    /// out of the sender's share.
+    pub fn settle_deposit(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+        let deductions = Self::deductions(&env, &escrow_id);
+        let now = env.ledger().timestamp();
+        if now < escrow.created_at + escrow.timeout_duration {
+            fail(&env, AppError::InvalidState, "Escrow has not timed out yet");
         }
 
-        // Transfer tokens to recipient
-        let client = token::Client::new(&env, &escrow.token);
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.recipient,
-            &escrow.amount,
+        let (mut deducted, mut contested) = (0i128, 0i128);
+        for deduction in deductions.iter() {
+            match deduction.status {
+                DeductionStatus::Claimed => {
+                    if now < deduction.claimed_at + escrow.dispute_period {
+                        fail(
+                            &env,
+                            AppError::InvalidState,
+                            "Deductions can still be contested",
+                        );
+                    }
+                    deducted = or_overflow(&env, checked_add(deducted, deduction.amount));
+                }
+                DeductionStatus::Contested => {
+                    contested = or_overflow(&env, checked_add(contested, deduction.amount));
+                }
+            }
+        }
+        let returned = or_overflow(
+            &env,
+            checked_sub(
+                escrow.amount,
+                or_overflow(&env, checked_add(deducted, contested)),
+            ),
         );
+        if deducted > 0 && Self::is_frozen(&env, &Self::current_payee(&env, &escrow)) {
+            fail(&env, AppError::Unauthorized, "Recipient is frozen");
+        }
 
-        // Update escrow status
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::DisputeResolvedForRecipient,
+        // Store the outcome before paying out; a contested remainder is left
+        // as the escrow's amount under an ordinary dispute
+        let status = if contested > 0 {
+            EscrowStatus::Disputed
+        } else {
+            EscrowStatus::DepositSettled
+        };
+        let settled = EscrowConfig {
+            amount: contested,
+            status: status.clone(),
+            has_dispute: contested > 0,
             ..escrow.clone()
         };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
+        storage::write(&env, &escrow_id, &settled);
+        if contested > 0 {
+            let dispute_info = DisputeInfo {
+                initiated_by: escrow.sender.clone(),
+                initiated_at: now,
+                dispute_period: escrow.dispute_period,
+                reason: symbol_short!("deduction"),
+            };
+            storage::write(
+                &env,
+                &(escrow_id.clone(), symbol_short!("DISPUTE")),
+                &dispute_info,
+            );
+        }
+
+        // The receipt stays with its holder while a dispute remains
+        let payee = if contested > 0 {
+            Self::current_payee(&env, &escrow)
+        } else {
+            Self::take_payee(&env, &escrow)
+        };
+        let tip = KeeperTips::tip_on(&env, returned);
+        let token = token::Client::new(&env, &escrow.token);
+        if deducted > 0 {
+            token.transfer(&env.current_contract_address(), &payee, &deducted);
+        }
+        let refunded = or_overflow(&env, checked_sub(returned, tip));
+        if refunded > 0 {
+            token.transfer(&env.current_contract_address(), &escrow.sender, &refunded);
+        }
+        if tip > 0 {
+            token.transfer(&env.current_contract_address(), &keeper, &tip);
+        }
 
-        // Emit dispute resolved event
-        crate::event::EventEmitter::emit_dispute_resolved(
+        if contested > 0 {
+            crate::event::EventEmitter::emit_dispute_initiated(
+                &env,
+                escrow_id.clone(),
+                escrow.sender.clone(),
+                symbol_short!("deduction"),
+                escrow.dispute_period,
+            );
+        } else {
+            Self::record_outcomes(
+                &env,
+                &escrow.sender,
+                &escrow.recipient,
+                TradeOutcome::Completed,
+            );
+        }
+        crate::event::EventEmitter::emit_escrow_deposit_settled(
             &env,
-            escrow_id.clone(),
-            escrow.recipient.clone(),
-            true, // resolved_for_recipient
+            EscrowDepositSettledData {
+                escrow_id: escrow_id.clone(),
+                deducted,
+                returned,
+                contested,
+            },
         );
+        Self::get_escrow(env, escrow_id)
+    }
 
-        // Return updated escrow info
-        EscrowInfo {
-            id: escrow.id,
-            sender: escrow.sender,
-            recipient: escrow.recipient,
-            token: escrow.token,
-            amount: escrow.amount,
-            created_at: escrow.created_at,
-            timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: escrow.dispute_period,
-            status: EscrowStatus::DisputeResolvedForRecipient,
-            has_dispute: true,
-        }
+    /// Create an escrow whose recipient is resolved from an alias through the email registry
+    pub fn create_for_alias(
+        env: Env,
+        sender: Address,
+        alias: Alias,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> EscrowInfo {
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&ALIAS_REGISTRY_KEY)
+            .expect("Alias registry not set");
+        let recipient = EmailToWalletContractClient::new(&env, &registry)
+            .resolve_alias(&alias.kind, &alias.value);
+        Self::create(
+            env,
+            sender,
+            recipient,
+            token,
+            amount,
+            timeout_duration,
+            dispute_period,
+        )
     }
 
-    /// Resolve dispute in favor of sender (admin function or automated)
-    pub fn resolve_dispute_for_sender(env: Env, escrow_id: Symbol) -> EscrowInfo {
+    /// Release funds to the recipient (can only be called by sender)
+    pub fn release(env: Env, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
         // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
 
-        // Validate the escrow is disputed
-        if escrow.status != EscrowStatus::Disputed {
-            panic!("Escrow is not disputed");
+        // Validate the escrow is active (not disputed)
+        if escrow.status != EscrowStatus::Active {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Escrow is not active or is disputed",
+            );
         }
 
-        // Get dispute info
-        let dispute_key = symbol_short!("DISPUTE");
-        let dispute: DisputeInfo = env
-            .storage()
-            .instance()
-            .get(&(escrow_id.clone(), dispute_key))
-            .unwrap();
+        Self::require_not_deposit(&env, &escrow_id);
 
-        // Check if dispute period has expired (auto-resolution)
-        let current_time = env.ledger().timestamp();
-        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
+        // Require sender authorization
+        escrow.sender.require_auth();
+        Inheritance::record_activity(&env, &escrow.sender);
 
-        if current_time < dispute_expires_at {
-            // Manual resolution - require sender auth for now
-            escrow.sender.require_auth();
+        let released_by = escrow.sender.clone();
+        Self::release_to_recipient(&env, escrow, released_by)
+    }
+
+    /// Release an escrow with the backend's payment reference on its events
+    pub fn release_with_reference(
+        env: Env,
+        escrow_id: Symbol,
+        reference: BytesN<32>,
+    ) -> EscrowInfo {
+        PaymentReference::attach(&env, &reference, || Self::release(env.clone(), escrow_id))
+    }
+
+    /// Preview `release` for the frontend. Fails with the same errors the
+    /// release itself would hit, apart from the sender's authorization.
+    pub fn simulate_release(env: Env, escrow_id: Symbol) -> ReleasePreview {
+        Self::require_operational(&env);
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Active {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Escrow is not active or is disputed",
+            );
+        }
+        Self::require_not_deposit(&env, &escrow_id);
+        Self::require_not_blocked(&env, &escrow.sender, &escrow.recipient);
+        let payee = Self::current_payee(&env, &escrow);
+        if Self::is_frozen(&env, &payee) {
+            fail(&env, AppError::Unauthorized, "Recipient is frozen");
         }
 
-        // Transfer tokens back to sender
-        let client = token::Client::new(&env, &escrow.token);
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.sender,
-            &escrow.amount,
-        );
+        ReleasePreview {
+            escrow_id,
+            payee,
+            token: escrow.token,
+            amount: escrow.amount,
+            status: EscrowStatus::Released,
+        }
+    }
 
-        // Update escrow status
-        let updated_escrow = EscrowConfig {
-            status: EscrowStatus::DisputeResolvedForSender,
-            ..escrow.clone()
+    /// Let `operator` release this escrow on the sender's behalf until
+    /// `expires_at`, replacing any earlier approval (sender only)
+    pub fn approve_release_operator(
+        env: Env,
+        escrow_id: Symbol,
+        operator: Address,
+        expires_at: u64,
+    ) -> ReleaseDelegation {
+        Self::require_operational(&env);
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+        Self::require_not_deposit(&env, &escrow_id);
+        escrow.sender.require_auth();
+        Inheritance::record_activity(&env, &escrow.sender);
+
+        if expires_at <= env.ledger().timestamp() {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Approval must expire in the future",
+            );
+        }
+
+        let delegation = ReleaseDelegation {
+            operator: operator.clone(),
+            expires_at,
         };
-        env.storage().instance().set(&escrow_id, &updated_escrow);
+        storage::write(&env, &(escrow_id.clone(), DELEGATE_KEY), &delegation);
 
-        // Emit dispute resolved event
-        crate::event::EventEmitter::emit_dispute_resolved(
+        crate::event::EventEmitter::emit_release_delegation_changed(
             &env,
-            escrow_id.clone(),
-            escrow.sender.clone(),
-            false, // resolved_for_recipient
+            EscrowReleaseDelegationData {
+                escrow_id,
+                sender: escrow.sender,
+                operator,
+                expires_at,
+            },
+            false,
+        );
+        delegation
+    }
+
+    /// Withdraw the release approval on an escrow (sender only)
+    pub fn revoke_release_operator(env: Env, escrow_id: Symbol) {
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        escrow.sender.require_auth();
+
+        let key = (escrow_id.clone(), DELEGATE_KEY);
+        let delegation: ReleaseDelegation = storage::read(&env, &key)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "No release operator approved"));
+        env.storage().persistent().remove(&key);
+
+        crate::event::EventEmitter::emit_release_delegation_changed(
+            &env,
+            EscrowReleaseDelegationData {
+                escrow_id,
+                sender: escrow.sender,
+                operator: delegation.operator,
+                expires_at: delegation.expires_at,
+            },
+            true,
         );
+    }
+
+    pub fn get_release_operator(env: Env, escrow_id: Symbol) -> Option<ReleaseDelegation> {
+        storage::read(&env, &(escrow_id, DELEGATE_KEY))
+    }
+
+    /// Release funds to the recipient as the operator the sender approved
+    /// for this escrow, before the approval expires
+    pub fn release_as_operator(env: Env, operator: Address, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+        operator.require_auth();
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.status != EscrowStatus::Active {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Escrow is not active or is disputed",
+            );
+        }
+
+        Self::require_not_deposit(&env, &escrow_id);
+
+        let delegation = Self::get_release_operator(env.clone(), escrow_id)
+            .filter(|delegation| delegation.operator == operator)
+            .unwrap_or_else(|| {
+                fail(
+                    &env,
+                    AppError::Unauthorized,
+                    "Operator not approved for this escrow",
+                )
+            });
+        if env.ledger().timestamp() > delegation.expires_at {
+            fail(&env, AppError::Unauthorized, "Release approval has expired");
+        }
+
+        Self::release_to_recipient(&env, escrow, operator)
+    }
+
+    /// Refund the tokens back to the sender (can be called by both sender and recipient)
+    pub fn refund(env: Env, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Validate the escrow is active
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+        Self::require_not_deposit(&env, &escrow_id);
+
+        // For now, we'll just require the sender to authenticate for refund
+        // This is a simplification but ensures security
+        escrow.sender.require_auth();
+        Inheritance::record_activity(&env, &escrow.sender);
+
+        // Mark the escrow settled, then pay the sender
+        Self::settle(&env, &escrow, EscrowStatus::Refunded, Payout::Sender, None);
 
         // Return updated escrow info
         EscrowInfo {
@@ -657,69 +1322,401 @@ impl EscrowContract {
             created_at: escrow.created_at,
             timeout_at: escrow.created_at + escrow.timeout_duration,
             dispute_period: escrow.dispute_period,
-            status: EscrowStatus::DisputeResolvedForSender,
-            has_dispute: true,
+            status: EscrowStatus::Refunded,
+            has_dispute: escrow.has_dispute,
         }
     }
 
-    /// Check if dispute has timed out and auto-resolve (default to recipient)
-    pub fn check_dispute_timeout(env: Env, escrow_id: Symbol) -> EscrowInfo {
+    /// Check if the escrow has timed out and pay it out to its timeout
+    /// destination if so. Callable by anyone; `keeper` is paid the keeper tip
+    /// out of the payout.
+    pub fn check_timeout(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
         // Get the escrow
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
 
-        // Validate the escrow is disputed
-        if escrow.status != EscrowStatus::Disputed {
-            panic!("Escrow is not disputed");
+        // Validate the escrow is active
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
         }
+        Self::require_not_deposit(&env, &escrow_id);
 
-        // Get dispute info
-        let dispute_key = symbol_short!("DISPUTE");
-        let dispute: DisputeInfo = env
-            .storage()
-            .instance()
-            .get(&(escrow_id.clone(), dispute_key))
-            .unwrap();
-
-        // Check if dispute period has expired
+        // Check if timeout has been reached
         let current_time = env.ledger().timestamp();
-        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
+        let timeout_time = escrow.created_at + escrow.timeout_duration;
 
-        if current_time < dispute_expires_at {
-            panic!("Dispute period has not expired yet");
+        if current_time < timeout_time {
+            fail(&env, AppError::InvalidState, "Escrow has not timed out yet");
         }
 
-        // Auto-resolve in favor of recipient (default behavior)
-        Self::resolve_dispute_for_recipient(env, escrow_id)
-    }
+        // Mark the escrow settled, then pay wherever the sender chose at creation
+        let (status, payout) = match Self::get_timeout_destination(env.clone(), escrow_id) {
+            TimeoutDestination::Recipient => (EscrowStatus::AutoReleased, Payout::Recipient),
+            TimeoutDestination::Sender => (EscrowStatus::Refunded, Payout::Sender),
+            TimeoutDestination::Fallback(fallback) => {
+                (EscrowStatus::AutoReleased, Payout::Redirect(fallback))
+            }
+        };
+        let paid_recipient = matches!(payout, Payout::Recipient);
+        Self::settle(&env, &escrow, status.clone(), payout, Some(&keeper));
+        if paid_recipient {
+            Self::record_outcomes(
+                &env,
+                &escrow.sender,
+                &escrow.recipient,
+                TradeOutcome::Completed,
+            );
+        }
 
-    /// Get dispute information for an escrow
-    pub fn get_dispute_info(env: Env, escrow_id: Symbol) -> Option<DisputeInfo> {
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
-        if escrow.has_dispute {
-            let dispute_key = symbol_short!("DISPUTE");
-            env.storage().instance().get(&(escrow_id, dispute_key))
-        } else {
-            None
+        // Return updated escrow info
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: timeout_time,
+            dispute_period: escrow.dispute_period,
+            status,
+            has_dispute: escrow.has_dispute,
         }
     }
 
-    /// Check if an escrow can be disputed (is active and no existing dispute)
-    pub fn can_dispute(env: Env, escrow_id: Symbol) -> bool {
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
-        escrow.status == EscrowStatus::Active && !escrow.has_dispute
+    /// Get information about an escrow
+    pub fn get_escrow(env: Env, escrow_id: Symbol) -> EscrowInfo {
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Return escrow info
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: escrow.dispute_period,
+            status: escrow.status,
+            has_dispute: escrow.has_dispute,
+        }
     }
 
-    /// Get escrow count for statistics
-    pub fn get_escrow_count(env: Env) -> u32 {
-        env.storage()
+    /// Get all active escrows
+    pub fn get_all_escrows(env: Env) -> Vec<EscrowInfo> {
+        let count = env
+            .storage()
             .instance()
             .get(&ESCROW_COUNT_KEY)
-            .unwrap_or(0u32)
+            .unwrap_or(0u32);
+        let mut escrows = Vec::new(&env);
+        for i in 0..count {
+            let mut s: HString<12> = HString::new();
+            s.push_str("escrow_").unwrap();
+            write!(&mut s, "{}", i).unwrap();
+            let id = Symbol::new(&env, s.as_str());
+            if env.storage().persistent().has(&id) {
+                let escrow: EscrowConfig = storage::read(&env, &id).unwrap();
+                escrows.push_back(EscrowInfo {
+                    id: escrow.id,
+                    sender: escrow.sender,
+                    recipient: escrow.recipient,
+                    token: escrow.token,
+                    amount: escrow.amount,
+                    created_at: escrow.created_at,
+                    timeout_at: escrow.created_at + escrow.timeout_duration,
+                    dispute_period: escrow.dispute_period,
+                    status: escrow.status,
+                    has_dispute: escrow.has_dispute,
+                });
+            }
+        }
+        escrows
+    }
+
+    /// Initiate a dispute (can be called by sender or recipient)
+    pub fn initiate_dispute(env: Env, escrow_id: Symbol, reason: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        // Check if contract is paused
+        if env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+        {
+            fail(&env, AppError::Paused, "Contract is paused");
+        }
+
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Check if dispute already exists first
+        if escrow.has_dispute {
+            fail(&env, AppError::InvalidState, "Dispute already initiated");
+        }
+
+        // Validate the escrow is active
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+        Self::require_not_deposit(&env, &escrow_id);
+
+        // For now, we'll allow both sender and recipient to initiate disputes
+        // In a production system, you might want more sophisticated authorization
+        escrow.sender.require_auth();
+        let caller = escrow.sender.clone();
+        Inheritance::record_activity(&env, &caller);
+        if !RateLimiter::try_consume(&env, DISPUTE_FN, &caller) {
+            fail(&env, AppError::RateLimited, "Rate limit exceeded");
+        }
+
+        let dispute_fee = Self::dispute_fee_for(&env, &escrow_id, &caller);
+
+        // Create dispute info and store separately
+        let dispute_info = DisputeInfo {
+            initiated_by: caller.clone(),
+            initiated_at: env.ledger().timestamp(),
+            dispute_period: escrow.dispute_period,
+            reason: reason.clone(),
+        };
+
+        // Store dispute info separately using a simple key pattern
+        let dispute_key = symbol_short!("DISPUTE");
+        storage::write(&env, &(escrow_id.clone(), dispute_key), &dispute_info);
+
+        // Update escrow with dispute
+        let updated_escrow = EscrowConfig {
+            status: EscrowStatus::Disputed,
+            has_dispute: true,
+            ..escrow.clone()
+        };
+        storage::write(&env, &escrow_id, &updated_escrow);
+
+        // Collected once the dispute is recorded, so a re-entering token sees it
+        Self::collect_dispute_fee(&env, &escrow.token, &caller, dispute_fee);
+
+        // Emit dispute initiated event
+        crate::event::EventEmitter::emit_dispute_initiated(
+            &env,
+            escrow_id.clone(),
+            caller,
+            reason,
+            dispute_info.dispute_period,
+        );
+
+        // Return updated escrow info
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: escrow.dispute_period,
+            status: EscrowStatus::Disputed,
+            has_dispute: true,
+        }
+    }
+
+    /// Resolve dispute in favor of recipient (admin function or automated)
+    pub fn resolve_dispute_for_recipient(env: Env, escrow_id: Symbol) -> EscrowInfo {
+        Self::resolve_for_recipient(&env, escrow_id, None)
+    }
+
+    /// Resolve dispute in favor of sender (admin function or automated)
+    pub fn resolve_dispute_for_sender(env: Env, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Validate the escrow is disputed
+        if escrow.status != EscrowStatus::Disputed {
+            fail(&env, AppError::InvalidState, "Escrow is not disputed");
+        }
+
+        // Get dispute info
+        let dispute_key = symbol_short!("DISPUTE");
+        let dispute: DisputeInfo = storage::read(&env, &(escrow_id.clone(), dispute_key)).unwrap();
+
+        // Check if dispute period has expired (auto-resolution)
+        let current_time = env.ledger().timestamp();
+        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
+
+        Self::require_not_deposit(&env, &escrow_id);
+        if current_time < dispute_expires_at {
+            // Manual resolution - require sender auth for now
+            escrow.sender.require_auth();
+        } else {
+            Self::require_not_arbitrated(&env, &escrow_id);
+        }
+
+        // Mark the escrow settled, then pay the sender
+        Self::settle(
+            &env,
+            &escrow,
+            EscrowStatus::DisputeResolvedForSender,
+            Payout::Sender,
+            None,
+        );
+        Self::record_dispute_outcome(&env, &escrow.sender, &escrow.recipient);
+
+        // Emit dispute resolved event
+        crate::event::EventEmitter::emit_dispute_resolved(
+            &env,
+            escrow_id.clone(),
+            escrow.sender.clone(),
+            false, // resolved_for_recipient
+        );
+
+        // Return updated escrow info
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: escrow.dispute_period,
+            status: EscrowStatus::DisputeResolvedForSender,
+            has_dispute: true,
+        }
+    }
+
+    /// Check if dispute has timed out and auto-resolve (default to recipient).
+    /// Callable by anyone; `keeper` is paid the keeper tip out of the payout.
+    pub fn check_dispute_timeout(env: Env, keeper: Address, escrow_id: Symbol) -> EscrowInfo {
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Validate the escrow is disputed
+        if escrow.status != EscrowStatus::Disputed {
+            fail(&env, AppError::InvalidState, "Escrow is not disputed");
+        }
+
+        // Get dispute info
+        let dispute_key = symbol_short!("DISPUTE");
+        let dispute: DisputeInfo = storage::read(&env, &(escrow_id.clone(), dispute_key)).unwrap();
+
+        // Check if dispute period has expired
+        let current_time = env.ledger().timestamp();
+        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
+
+        if current_time < dispute_expires_at {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Dispute period has not expired yet",
+            );
+        }
+
+        // Hand the dispute to the arbitration panel, if one is configured
+        if let Some(panel) = Self::get_arbitration_panel(env.clone()) {
+            Self::require_operational(&env);
+            if Self::get_dispute_escalation(env.clone(), escrow_id.clone()).is_some() {
+                fail(&env, AppError::InvalidState, "Dispute already escalated");
+            }
+            let escalation = DisputeEscalation {
+                panel: panel.clone(),
+                escalated_at: current_time,
+            };
+            storage::write(&env, &(escrow_id.clone(), ESCALATION_KEY), &escalation);
+            crate::event::EventEmitter::emit_escrow_dispute_escalated(
+                &env,
+                EscrowDisputeEscalatedData {
+                    escrow_id: escrow_id.clone(),
+                    panel,
+                    escalated_by: keeper,
+                    escalated_at: current_time,
+                },
+            );
+            return Self::get_escrow(env, escrow_id);
+        }
+
+        // Auto-resolve in favor of recipient (default behavior)
+        Self::resolve_for_recipient(&env, escrow_id, Some(&keeper))
+    }
+
+    /// Anchor the hash of a mediation message to an open dispute. Only the
+    /// sender, the recipient or an arbitrator (operator) may write, and notes
+    /// can never be edited or removed. Returns the note's index in the log.
+    pub fn add_dispute_note(env: Env, author: Address, escrow_id: Symbol, hash: BytesN<32>) -> u32 {
+        author.require_auth();
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Escrow not found"));
+        if escrow.status != EscrowStatus::Disputed {
+            fail(&env, AppError::InvalidState, "Escrow is not disputed");
+        }
+        if author != escrow.sender
+            && author != escrow.recipient
+            && !AccessControl::has_role(&env, Role::Operator, &author)
+        {
+            fail(&env, AppError::Unauthorized, "Not a party to the dispute");
+        }
+
+        let mut notes = Self::get_dispute_notes(env.clone(), escrow_id.clone());
+        if notes.len() >= MAX_DISPUTE_NOTES {
+            fail(&env, AppError::InvalidState, "Too many dispute notes");
+        }
+        let index = notes.len();
+        notes.push_back(DisputeNote {
+            author: author.clone(),
+            hash: hash.clone(),
+            added_at: env.ledger().timestamp(),
+        });
+        storage::write(&env, &(escrow_id.clone(), NOTES_KEY), &notes);
+
+        crate::event::EventEmitter::emit_dispute_note_added(
+            &env,
+            DisputeNoteAddedData {
+                escrow_id,
+                author,
+                hash,
+                index,
+            },
+        );
+        index
+    }
+
+    /// Mediation notes of an escrow's dispute, oldest first
+    pub fn get_dispute_notes(env: Env, escrow_id: Symbol) -> Vec<DisputeNote> {
+        storage::read(&env, &(escrow_id, NOTES_KEY)).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get dispute information for an escrow
+    pub fn get_dispute_info(env: Env, escrow_id: Symbol) -> Option<DisputeInfo> {
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if escrow.has_dispute {
+            let dispute_key = symbol_short!("DISPUTE");
+            storage::read(&env, &(escrow_id, dispute_key))
+        } else {
+            None
+        }
+    }
+
+    /// Check if an escrow can be disputed (is active and no existing dispute)
+    pub fn can_dispute(env: Env, escrow_id: Symbol) -> bool {
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        escrow.status == EscrowStatus::Active && !escrow.has_dispute
+    }
+
+    /// Get escrow count for statistics
+    pub fn get_escrow_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&ESCROW_COUNT_KEY)
+            .unwrap_or(0u32)
     }
 
     /// Check if escrow exists
     pub fn escrow_exists(env: Env, escrow_id: Symbol) -> bool {
-        env.storage().instance().has(&escrow_id)
+        env.storage().persistent().has(&escrow_id)
     }
 
     /// Get escrows by status for filtering
@@ -737,8 +1734,8 @@ impl EscrowContract {
             write!(&mut s, "{}", i).unwrap();
             let id = Symbol::new(&env, s.as_str());
 
-            if env.storage().instance().has(&id) {
-                let escrow: EscrowConfig = env.storage().instance().get(&id).unwrap();
+            if env.storage().persistent().has(&id) {
+                let escrow: EscrowConfig = storage::read(&env, &id).unwrap();
                 if escrow.status == status {
                     escrows.push_back(EscrowInfo {
                         id: escrow.id,
@@ -758,76 +1755,1064 @@ impl EscrowContract {
         escrows
     }
 
-    /// Get escrows by participant (sender or recipient)
-    pub fn get_escrows_by_participant(env: Env, participant: Address) -> Vec<EscrowInfo> {
-        let count = env
+    /// Get escrows by participant (sender or recipient)
+    pub fn get_escrows_by_participant(env: Env, participant: Address) -> Vec<EscrowInfo> {
+        let count = env
+            .storage()
+            .instance()
+            .get(&ESCROW_COUNT_KEY)
+            .unwrap_or(0u32);
+        let mut escrows = Vec::new(&env);
+
+        for i in 0..count {
+            let mut s: HString<12> = HString::new();
+            s.push_str("escrow_").unwrap();
+            write!(&mut s, "{}", i).unwrap();
+            let id = Symbol::new(&env, s.as_str());
+
+            if env.storage().persistent().has(&id) {
+                let escrow: EscrowConfig = storage::read(&env, &id).unwrap();
+                if escrow.sender == participant || escrow.recipient == participant {
+                    escrows.push_back(EscrowInfo {
+                        id: escrow.id,
+                        sender: escrow.sender,
+                        recipient: escrow.recipient,
+                        token: escrow.token,
+                        amount: escrow.amount,
+                        created_at: escrow.created_at,
+                        timeout_at: escrow.created_at + escrow.timeout_duration,
+                        dispute_period: escrow.dispute_period,
+                        status: escrow.status,
+                        has_dispute: escrow.has_dispute,
+                    });
+                }
+            }
+        }
+        escrows
+    }
+
+    /// Update dispute period for an active escrow (only by sender before dispute)
+    pub fn update_dispute_period(
+        env: Env,
+        escrow_id: Symbol,
+        new_dispute_period: u64,
+    ) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        // Only sender can update and only if escrow is active with no dispute
+        escrow.sender.require_auth();
+        Inheritance::record_activity(&env, &escrow.sender);
+
+        if escrow.status != EscrowStatus::Active {
+            fail(&env, AppError::InvalidState, "Escrow is not active");
+        }
+
+        if escrow.has_dispute {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Cannot update dispute period after dispute initiated",
+            );
+        }
+
+        if new_dispute_period == 0 {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Dispute period must be non-zero",
+            );
+        }
+
+        if escrow.timeout_duration < new_dispute_period {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Dispute period cannot exceed timeout duration",
+            );
+        }
+
+        let updated_escrow = EscrowConfig {
+            dispute_period: new_dispute_period,
+            ..escrow.clone()
+        };
+
+        storage::write(&env, &escrow_id, &updated_escrow);
+
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: new_dispute_period,
+            status: escrow.status,
+            has_dispute: escrow.has_dispute,
+        }
+    }
+
+    /// Initialize contract with admin (should be called once during deployment)
+    pub fn initialize(env: Env, admin: Address) {
+        // Check if already initialized
+        if env.storage().instance().has(&ADMIN_KEY) {
+            fail(
+                &env,
+                AppError::AlreadyInitialized,
+                "Contract already initialized",
+            );
+        }
+
+        admin.require_auth();
+        env.storage().instance().set(&ADMIN_KEY, &admin);
+        AccessControl::init_owner(&env, &admin);
+        Upgrade::init_version(&env, STORAGE_VERSION);
+
+        // Set default dispute fee to 0 (can be updated by admin)
+        env.storage().instance().set(&DISPUTE_FEE_KEY, &0i128);
+    }
+
+    /// Set dispute fee (operator only)
+    pub fn set_dispute_fee(env: Env, caller: Address, fee: i128) {
+        AccessControl::require_audited_role(&env, Role::Operator, &caller, "set_dispute_fee", fee);
+
+        if fee < 0 {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Dispute fee cannot be negative",
+            );
+        }
+
+        let old_fee = Self::get_dispute_fee(env.clone());
+        env.storage().instance().set(&DISPUTE_FEE_KEY, &fee);
+
+        crate::event::EventEmitter::emit_dispute_fee_updated(&env, caller, old_fee, fee);
+    }
+
+    /// Set the registry used to resolve alias recipients (operator only)
+    pub fn set_escrow_alias_registry(env: Env, caller: Address, registry: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_escrow_alias_registry",
+            registry.clone(),
+        );
+
+        env.storage().instance().set(&ALIAS_REGISTRY_KEY, &registry);
+    }
+
+    /// Set or clear the reputation requirements for counterparties (operator only).
+    /// The escrow contract needs the operator role on the registry to record outcomes.
+    pub fn set_reputation_policy(env: Env, caller: Address, policy: Option<ReputationPolicy>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_reputation_policy",
+            policy.clone(),
+        );
+
+        match policy {
+            Some(policy) => env
+                .storage()
+                .instance()
+                .set(&REPUTATION_POLICY_KEY, &policy),
+            None => env.storage().instance().remove(&REPUTATION_POLICY_KEY),
+        }
+    }
+
+    pub fn get_reputation_policy(env: Env) -> Option<ReputationPolicy> {
+        env.storage().instance().get(&REPUTATION_POLICY_KEY)
+    }
+
+    /// Set or clear the KYC requirements for escrow parties (operator only)
+    pub fn set_escrow_compliance_policy(
+        env: Env,
+        caller: Address,
+        policy: Option<CompliancePolicy>,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_escrow_compliance_policy",
+            policy.clone(),
+        );
+
+        match policy {
+            Some(policy) => env
+                .storage()
+                .instance()
+                .set(&COMPLIANCE_POLICY_KEY, &policy),
+            None => env.storage().instance().remove(&COMPLIANCE_POLICY_KEY),
+        }
+    }
+
+    pub fn get_escrow_compliance_policy(env: Env) -> Option<CompliancePolicy> {
+        env.storage().instance().get(&COMPLIANCE_POLICY_KEY)
+    }
+
+    /// Set or clear the shared blocklist checked on create and release (operator only)
+    pub fn set_escrow_blocklist(env: Env, caller: Address, blocklist: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_escrow_blocklist",
+            blocklist.clone(),
+        );
+
+        match blocklist {
+            Some(blocklist) => env.storage().instance().set(&BLOCKLIST_KEY, &blocklist),
+            None => env.storage().instance().remove(&BLOCKLIST_KEY),
+        }
+    }
+
+    pub fn get_escrow_blocklist(env: Env) -> Option<Address> {
+        env.storage().instance().get(&BLOCKLIST_KEY)
+    }
+
+    /// Set or clear the address book whose whitelist-only users may only
+    /// create escrows to approved recipients (operator only)
+    pub fn set_escrow_address_book(env: Env, caller: Address, address_book: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_escrow_address_book",
+            address_book.clone(),
+        );
+
+        match address_book {
+            Some(address_book) => env
+                .storage()
+                .instance()
+                .set(&ADDRESS_BOOK_KEY, &address_book),
+            None => env.storage().instance().remove(&ADDRESS_BOOK_KEY),
+        }
+    }
+
+    pub fn get_escrow_address_book(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ADDRESS_BOOK_KEY)
+    }
+
+    /// Set or clear the compliance custody address that takes escrows owed to
+    /// frozen recipients (owner only)
+    pub fn set_escrow_custody(env: Env, caller: Address, custody: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_custody",
+            custody.clone(),
+        );
+
+        match custody {
+            Some(custody) => env.storage().instance().set(&CUSTODY_KEY, &custody),
+            None => env.storage().instance().remove(&CUSTODY_KEY),
+        }
+    }
+
+    pub fn get_escrow_custody(env: Env) -> Option<Address> {
+        env.storage().instance().get(&CUSTODY_KEY)
+    }
+
+    /// Send an open escrow whose payee has been frozen on the blocklist to the
+    /// custody address instead (operator only). Any escrow receipt is burned.
+    pub fn redirect_to_custody(env: Env, caller: Address, escrow_id: Symbol) -> EscrowInfo {
+        Self::require_operational(&env);
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "redirect_to_custody",
+            escrow_id.clone(),
+        );
+
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+        if !matches!(escrow.status, EscrowStatus::Active | EscrowStatus::Disputed) {
+            fail(&env, AppError::InvalidState, "Escrow is not open");
+        }
+        let Some(custody) = Self::get_escrow_custody(env.clone()) else {
+            fail(&env, AppError::InvalidConfig, "Custody address not set");
+        };
+        let frozen_payee = Self::current_payee(&env, &escrow);
+        if !Self::is_frozen(&env, &frozen_payee) {
+            fail(&env, AppError::InvalidState, "Recipient is not frozen");
+        }
+
+        Self::settle(
+            &env,
+            &escrow,
+            EscrowStatus::RedirectedToCustody,
+            Payout::Redirect(custody.clone()),
+            None,
+        );
+
+        crate::event::EventEmitter::emit_escrow_redirected_to_custody(
+            &env,
+            EscrowCustodyRedirectData {
+                escrow_id: escrow.id.clone(),
+                frozen_payee,
+                custody,
+                token: escrow.token.clone(),
+                amount: escrow.amount,
+                redirected_by: caller,
+                redirected_at: env.ledger().timestamp(),
+            },
+        );
+
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: escrow.dispute_period,
+            status: EscrowStatus::RedirectedToCustody,
+            has_dispute: escrow.has_dispute,
+        }
+    }
+
+    /// Set or clear the guardian whose escrow breaker halts this contract (owner only)
+    pub fn set_escrow_guardian(env: Env, caller: Address, guardian: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_guardian",
+            guardian.clone(),
+        );
+
+        match guardian {
+            Some(guardian) => env.storage().instance().set(&GUARDIAN_KEY, &guardian),
+            None => env.storage().instance().remove(&GUARDIAN_KEY),
+        }
+    }
+
+    pub fn get_escrow_guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&GUARDIAN_KEY)
+    }
+
+    /// Set or clear the receipt contract new escrows are tokenized with (owner
+    /// only). A receipted escrow pays out to the receipt's holder instead of
+    /// the recipient; this contract must hold the receipt contract's minter role.
+    pub fn set_escrow_receipts(env: Env, caller: Address, receipts: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_receipts",
+            receipts.clone(),
+        );
+
+        match receipts {
+            Some(receipts) => env.storage().instance().set(&RECEIPTS_KEY, &receipts),
+            None => env.storage().instance().remove(&RECEIPTS_KEY),
+        }
+    }
+
+    pub fn get_escrow_receipts(env: Env) -> Option<Address> {
+        env.storage().instance().get(&RECEIPTS_KEY)
+    }
+
+    /// Receipt contract and id tokenizing an escrow, if any
+    pub fn get_escrow_receipt(env: Env, escrow_id: Symbol) -> Option<(Address, u64)> {
+        storage::read(&env, &(escrow_id, RECEIPT_KEY))
+    }
+
+    /// Limit how often each address can open disputes, or clear the limit (operator only)
+    pub fn set_dispute_rate_limit(env: Env, caller: Address, limit: Option<RateLimit>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_dispute_rate_limit",
+            limit.clone(),
+        );
+
+        RateLimiter::set_limit(&env, DISPUTE_FN, limit);
+    }
+
+    pub fn get_dispute_rate_limit(env: Env) -> Option<RateLimit> {
+        RateLimiter::get_limit(&env, DISPUTE_FN)
+    }
+
+    /// Tip paid to whoever closes a timed-out escrow or dispute, or clear it (owner only)
+    pub fn set_escrow_keeper_tip(env: Env, caller: Address, tip: Option<KeeperTip>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_keeper_tip",
+            tip.clone(),
+        );
+
+        KeeperTips::set_tip(&env, tip);
+    }
+
+    pub fn get_escrow_keeper_tip(env: Env) -> Option<KeeperTip> {
+        KeeperTips::get_tip(&env)
+    }
+
+    /// Name who takes over the caller's open escrows after `inactivity_period`
+    /// seconds without activity, or clear the plan with `None`
+    pub fn set_escrow_beneficiary(
+        env: Env,
+        owner: Address,
+        beneficiary: Option<Address>,
+        inactivity_period: u64,
+    ) {
+        owner.require_auth();
+        Inheritance::set_plan(&env, &owner, beneficiary, inactivity_period);
+    }
+
+    pub fn get_escrow_inheritance(env: Env, owner: Address) -> Option<InheritancePlan> {
+        Inheritance::get_plan(&env, &owner)
+    }
+
+    /// Start the notice period on a claim against an inactive owner
+    pub fn announce_escrow_inheritance(env: Env, beneficiary: Address, owner: Address) {
+        Inheritance::announce_claim(&env, &owner, &beneficiary);
+    }
+
+    /// Put the beneficiary in the owner's place on every active or disputed
+    /// escrow once the announced claim's notice period has passed
+    pub fn claim_escrow_inheritance(env: Env, beneficiary: Address, owner: Address) -> Vec<Symbol> {
+        Self::require_operational(&env);
+        Inheritance::take_claim(&env, &owner, &beneficiary);
+
+        let count = env
+            .storage()
+            .instance()
+            .get(&ESCROW_COUNT_KEY)
+            .unwrap_or(0u32);
+        let mut claimed = Vec::new(&env);
+        for i in 0..count {
+            let mut s: HString<12> = HString::new();
+            s.push_str("escrow_").unwrap();
+            write!(&mut s, "{}", i).unwrap();
+            let id = Symbol::new(&env, s.as_str());
+
+            let Some(mut escrow) = storage::read::<_, EscrowConfig>(&env, &id) else {
+                continue;
+            };
+            if escrow.status != EscrowStatus::Active && escrow.status != EscrowStatus::Disputed {
+                continue;
+            }
+            if escrow.sender == owner {
+                escrow.sender = beneficiary.clone();
+            } else if escrow.recipient == owner {
+                escrow.recipient = beneficiary.clone();
+            } else {
+                continue;
+            }
+            storage::write(&env, &id, &escrow);
+            claimed.push_back(id);
+        }
+        claimed
+    }
+
+    /// Keep the last `capacity` events in contract storage, 0 to disable (operator only)
+    pub fn set_event_journal_capacity(env: Env, caller: Address, capacity: u32) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_event_journal_capacity",
+            capacity,
+        );
+
+        EventJournal::set_capacity(&env, capacity);
+    }
+
+    /// Recent events from the journal, newest first
+    pub fn get_recent_events(env: Env, offset: u32, limit: u32) -> Vec<EventEnvelope> {
+        EventJournal::recent(&env, offset, limit)
+    }
+
+    /// Get current dispute fee
+    pub fn get_dispute_fee(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DISPUTE_FEE_KEY)
+            .unwrap_or(0i128)
+    }
+
+    /// Get contract admin
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&ADMIN_KEY).unwrap()
+    }
+
+    /// Transfer admin rights (admin only)
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY).unwrap();
+        admin.require_auth();
+        AdminAudit::record(&env, &admin, "transfer_admin", new_admin.clone());
+
+        env.storage().instance().set(&ADMIN_KEY, &new_admin);
+        AccessControl::transfer_ownership(&env, &admin, &new_admin);
+
+        crate::event::EventEmitter::emit_escrow_admin_transferred(&env, admin, new_admin);
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_escrow_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_escrow_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_escrow_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_escrow_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    /// Give up a role held by `account`
+    pub fn renounce_escrow_role(env: Env, account: Address, role: Role) {
+        AccessControl::renounce_role(&env, &account, role);
+    }
+
+    pub fn has_escrow_role(env: Env, role: Role, account: Address) -> bool {
+        AccessControl::has_role(&env, role, &account)
+    }
+
+    /// Replace the contract code (owner only)
+    pub fn upgrade_escrow(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        Upgrade::upgrade(&env, &caller, new_wasm_hash);
+    }
+
+    /// Bring storage written by older code up to the current layout
+    pub fn migrate_escrow(env: Env) -> u32 {
+        Upgrade::migrate(&env, STORAGE_VERSION, |env, version| {
+            // v1: admin checks moved to roles, so the existing admin becomes owner
+            if version == 1 {
+                if let Some(admin) = env.storage().instance().get::<_, Address>(&ADMIN_KEY) {
+                    AccessControl::init_owner(env, &admin);
+                }
+            }
+            // v2: escrow records, disputes and receipts moved from instance to
+            // persistent storage
+            if version == 2 {
+                let count = env
+                    .storage()
+                    .instance()
+                    .get(&ESCROW_COUNT_KEY)
+                    .unwrap_or(0u32);
+                for i in 0..count {
+                    let mut s: HString<12> = HString::new();
+                    s.push_str("escrow_").unwrap();
+                    write!(&mut s, "{}", i).unwrap();
+                    let id = Symbol::new(env, s.as_str());
+                    storage::move_from_instance::<_, EscrowConfig>(env, &id);
+                    storage::move_from_instance::<_, DisputeInfo>(
+                        env,
+                        &(id.clone(), symbol_short!("DISPUTE")),
+                    );
+                    storage::move_from_instance::<_, (Address, u64)>(env, &(id, RECEIPT_KEY));
+                }
+            }
+        })
+    }
+
+    pub fn get_escrow_storage_version(env: Env) -> u32 {
+        Upgrade::storage_version(&env)
+    }
+
+    /// Emergency pause/unpause functionality (pauser only)
+    pub fn set_paused(env: Env, caller: Address, paused: bool) {
+        AccessControl::require_audited_role(&env, Role::Pauser, &caller, "set_paused", paused);
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &paused);
+
+        crate::event::EventEmitter::emit_escrow_pause_changed(&env, caller, paused);
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+    }
+
+    /// Admin emergency resolution (operator only, for extreme cases)
+    pub fn admin_resolve_dispute(
+        env: Env,
+        caller: Address,
+        escrow_id: Symbol,
+        resolve_for_recipient: bool,
+    ) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "admin_resolve_dispute",
+            (escrow_id.clone(), resolve_for_recipient),
+        );
+        Self::arbitrate(env, escrow_id, resolve_for_recipient)
+    }
+
+    /// Set or clear the arbitration panel that disputes escalate to once their
+    /// dispute period passes unresolved (owner only). The panel is a contract,
+    /// such as a multisig or DAO, that applies its own resolution quorum.
+    pub fn set_arbitration_panel(env: Env, caller: Address, panel: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_arbitration_panel",
+            panel.clone(),
+        );
+
+        match panel {
+            Some(panel) => env.storage().instance().set(&ARBITRATION_PANEL_KEY, &panel),
+            None => env.storage().instance().remove(&ARBITRATION_PANEL_KEY),
+        }
+    }
+
+    pub fn get_arbitration_panel(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ARBITRATION_PANEL_KEY)
+    }
+
+    pub fn get_dispute_escalation(env: Env, escrow_id: Symbol) -> Option<DisputeEscalation> {
+        storage::read(&env, &(escrow_id, ESCALATION_KEY))
+    }
+
+    /// Settle a dispute escalated to arbitration (the panel it went to only)
+    pub fn resolve_escalated_dispute(
+        env: Env,
+        panel: Address,
+        escrow_id: Symbol,
+        resolve_for_recipient: bool,
+    ) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        let escalation = Self::get_dispute_escalation(env.clone(), escrow_id.clone())
+            .unwrap_or_else(|| fail(&env, AppError::InvalidState, "Dispute is not escalated"));
+        if panel != escalation.panel {
+            fail(&env, AppError::Unauthorized, "Not the arbitration panel");
+        }
+        panel.require_auth();
+
+        Self::arbitrate(env, escrow_id, resolve_for_recipient)
+    }
+
+    // Settle an open dispute on an arbitrator's ruling
+    fn arbitrate(env: Env, escrow_id: Symbol, resolve_for_recipient: bool) -> EscrowInfo {
+        let escrow: EscrowConfig = storage::read(&env, &escrow_id).unwrap();
+
+        if escrow.status != EscrowStatus::Disputed {
+            fail(&env, AppError::InvalidState, "Escrow is not disputed");
+        }
+
+        if resolve_for_recipient {
+            // Mark the escrow settled, then pay the recipient
+            Self::settle(
+                &env,
+                &escrow,
+                EscrowStatus::DisputeResolvedForRecipient,
+                Payout::Recipient,
+                None,
+            );
+            Self::record_dispute_outcome(&env, &escrow.recipient, &escrow.sender);
+
+            crate::event::EventEmitter::emit_dispute_resolved(
+                &env,
+                escrow_id.clone(),
+                escrow.recipient.clone(),
+                true,
+            );
+
+            EscrowInfo {
+                id: escrow.id,
+                sender: escrow.sender,
+                recipient: escrow.recipient,
+                token: escrow.token,
+                amount: escrow.amount,
+                created_at: escrow.created_at,
+                timeout_at: escrow.created_at + escrow.timeout_duration,
+                dispute_period: escrow.dispute_period,
+                status: EscrowStatus::DisputeResolvedForRecipient,
+                has_dispute: true,
+            }
+        } else {
+            // Mark the escrow settled, then pay the sender
+            Self::settle(
+                &env,
+                &escrow,
+                EscrowStatus::DisputeResolvedForSender,
+                Payout::Sender,
+                None,
+            );
+            Self::record_dispute_outcome(&env, &escrow.sender, &escrow.recipient);
+
+            crate::event::EventEmitter::emit_dispute_resolved(
+                &env,
+                escrow_id.clone(),
+                escrow.sender.clone(),
+                false,
+            );
+
+            EscrowInfo {
+                id: escrow.id,
+                sender: escrow.sender,
+                recipient: escrow.recipient,
+                token: escrow.token,
+                amount: escrow.amount,
+                created_at: escrow.created_at,
+                timeout_at: escrow.created_at + escrow.timeout_duration,
+                dispute_period: escrow.dispute_period,
+                status: EscrowStatus::DisputeResolvedForSender,
+                has_dispute: true,
+            }
+        }
+    }
+
+    // Open an escrow funded by the sender up front or, given a funding
+    // deadline, by contributors until it reaches `amount`
+    #[allow(clippy::too_many_arguments)]
+    fn open(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        timeout_duration: u64,
+        dispute_period: u64,
+        funding_deadline: Option<u64>,
+    ) -> EscrowInfo {
+        Self::require_operational(&env);
+
+        // Check if contract is paused
+        if env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+        {
+            fail(&env, AppError::Paused, "Contract is paused");
+        }
+
+        // Validate inputs
+        if amount <= 0 {
+            fail(&env, AppError::InvalidAmount, "Amount must be positive");
+        }
+        if timeout_duration == 0 {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Timeout duration must be non-zero",
+            );
+        }
+        if dispute_period == 0 {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Dispute period must be non-zero",
+            );
+        }
+        if timeout_duration < dispute_period {
+            fail(
+                &env,
+                AppError::InvalidTimestamp,
+                "Timeout duration must be greater than dispute period",
+            );
+        }
+        if sender == recipient {
+            fail(
+                &env,
+                AppError::InvalidAddress,
+                "Sender and recipient cannot be the same",
+            );
+        }
+
+        // Authenticate the sender
+        sender.require_auth();
+        Inheritance::record_activity(&env, &sender);
+
+        Self::require_not_blocked(&env, &sender, &recipient);
+        if let Some(address_book) = Self::get_escrow_address_book(env.clone()) {
+            if !BeneficiaryCheck::is_approved(&env, &address_book, &sender, &recipient) {
+                fail(
+                    &env,
+                    AppError::Unauthorized,
+                    "Recipient not in sender's address book",
+                );
+            }
+        }
+
+        // Both parties must hold the required KYC tier, if a policy is configured
+        if let Some(policy) = Self::get_escrow_compliance_policy(env.clone()) {
+            if !ComplianceCheck::is_allowed(&env, &policy, &sender, amount)
+                || !ComplianceCheck::is_allowed(&env, &policy, &recipient, amount)
+            {
+                fail(&env, AppError::Unauthorized, "KYC tier too low");
+            }
+        }
+
+        // Both parties must meet the reputation floor, if one is configured
+        if let Some(policy) = Self::get_reputation_policy(env.clone()) {
+            let registry = ReputationContractClient::new(&env, &policy.registry);
+            if registry.get_reputation_score(&sender) < policy.min_score
+                || registry.get_reputation_score(&recipient) < policy.min_score
+            {
+                fail(
+                    &env,
+                    AppError::Unauthorized,
+                    "Counterparty reputation too low",
+                );
+            }
+        }
+
+        // A crowdfunded escrow is paid in by its contributors instead
+        let status = match funding_deadline {
+            Some(deadline) => {
+                if deadline <= env.ledger().timestamp() {
+                    fail(
+                        &env,
+                        AppError::InvalidTimestamp,
+                        "Funding deadline must be in the future",
+                    );
+                }
+                EscrowStatus::Funding
+            }
+            None => {
+                // Verify token contract exists and sender has sufficient balance
+                // Note: In production, this would always check the balance
+                // For testing with mock addresses, we skip the balance check
+                let client = token::Client::new(&env, &token);
+                let sender_balance = client.balance(&sender);
+                if sender_balance < amount {
+                    fail(
+                        &env,
+                        AppError::InsufficientBalance,
+                        "Insufficient token balance",
+                    );
+                }
+
+                // Transfer tokens from sender to contract
+                let client = token::Client::new(&env, &token);
+                client.transfer(&sender, &env.current_contract_address(), &amount);
+                EscrowStatus::Active
+            }
+        };
+
+        // Create a unique ID for this escrow
+        let count = env
+            .storage()
+            .instance()
+            .get(&ESCROW_COUNT_KEY)
+            .unwrap_or(0u32);
+        let mut s: HString<12> = HString::new();
+        s.push_str("escrow_").unwrap();
+        write!(&mut s, "{}", count).unwrap();
+        let id = Symbol::new(&env, s.as_str());
+        env.storage()
+            .instance()
+            .set(&ESCROW_COUNT_KEY, &(count + 1));
+
+        // Get current timestamp
+        let created_at = env.ledger().timestamp();
+
+        // Store escrow configuration
+        let escrow = EscrowConfig {
+            id: id.clone(),
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token: token.clone(),
+            amount,
+            created_at,
+            timeout_duration,
+            dispute_period,
+            status: status.clone(),
+            has_dispute: false,
+        };
+
+        crate::event::EventEmitter::emit_escrow_created(
+            &env,
+            id.clone(),
+            sender.clone(),
+            recipient.clone(),
+            token.clone(),
+            amount,
+            timeout_duration,
+        );
+
+        // Save the escrow
+        storage::write(&env, &id, &escrow);
+        if let Some(deadline) = funding_deadline {
+            let crowdfund = Crowdfund {
+                deadline,
+                raised: 0,
+                contributors: 0,
+                refundable: 0,
+            };
+            storage::write(&env, &(id.clone(), CROWDFUND_KEY), &crowdfund);
+        }
+        if let Some(receipts) = Self::get_escrow_receipts(env.clone()) {
+            let receipt_id = ReceiptContractClient::new(&env, &receipts).mint_receipt(
+                &env.current_contract_address(),
+                &PositionKind::Escrow,
+                &recipient,
+            );
+            storage::write(&env, &(id.clone(), RECEIPT_KEY), &(receipts, receipt_id));
+        }
+
+        // Return escrow info
+        EscrowInfo {
+            id,
+            sender,
+            recipient,
+            token,
+            amount,
+            created_at,
+            timeout_at: created_at + timeout_duration,
+            dispute_period,
+            status,
+            has_dispute: false,
+        }
+    }
+
+    fn crowdfund(env: &Env, escrow_id: &Symbol) -> Crowdfund {
+        Self::get_crowdfund(env.clone(), escrow_id.clone())
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "Escrow is not crowdfunded"))
+    }
+
+    fn deductions(env: &Env, escrow_id: &Symbol) -> Vec<Deduction> {
+        storage::read(env, &(escrow_id.clone(), DEPOSIT_KEY))
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "Escrow is not a deposit"))
+    }
+
+    // Dispute fee charged to `caller`, scaled up for low-reputation callers
+    // and down by any promo the escrow was created with
+    fn dispute_fee_for(env: &Env, escrow_id: &Symbol, caller: &Address) -> i128 {
+        let mut dispute_fee = env
             .storage()
             .instance()
-            .get(&ESCROW_COUNT_KEY)
-            .unwrap_or(0u32);
-        let mut escrows = Vec::new(&env);
-
-        for i in 0..count {
-            let mut s: HString<12> = HString::new();
-            s.push_str("escrow_").unwrap();
-            write!(&mut s, "{}", i).unwrap();
-            let id = Symbol::new(&env, s.as_str());
-
-            if env.storage().instance().has(&id) {
-                let escrow: EscrowConfig = env.storage().instance().get(&id).unwrap();
-                if escrow.sender == participant || escrow.recipient == participant {
-                    escrows.push_back(EscrowInfo {
-                        id: escrow.id,
-                        sender: escrow.sender,
-                        recipient: escrow.recipient,
-                        token: escrow.token,
-                        amount: escrow.amount,
-                        created_at: escrow.created_at,
-                        timeout_at: escrow.created_at + escrow.timeout_duration,
-                        dispute_period: escrow.dispute_period,
-                        status: escrow.status,
-                        has_dispute: escrow.has_dispute,
-                    });
-                }
+            .get(&DISPUTE_FEE_KEY)
+            .unwrap_or(0i128);
+        if let Some(policy) = Self::get_reputation_policy(env.clone()) {
+            let score =
+                ReputationContractClient::new(env, &policy.registry).get_reputation_score(caller);
+            if score < policy.low_score_threshold {
+                dispute_fee = or_overflow(
+                    env,
+                    checked_mul_div(dispute_fee, policy.low_score_dispute_fee_bps as i128, 10000),
+                );
             }
         }
-        escrows
+        if let Some(discount_bps) = storage::read(env, &(escrow_id.clone(), PROMO_KEY)) {
+            dispute_fee = Promos::discounted(dispute_fee, discount_bps);
+        }
+        dispute_fee
     }
 
-    /// Update dispute period for an active escrow (only by sender before dispute)
-    pub fn update_dispute_period(
-        env: Env,
-        escrow_id: Symbol,
-        new_dispute_period: u64,
-    ) -> EscrowInfo {
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
-
-        // Only sender can update and only if escrow is active with no dispute
-        escrow.sender.require_auth();
-
-        if escrow.status != EscrowStatus::Active {
-            panic!("Escrow is not active");
+    // The fee stays with the contract (could be sent to admin or burned)
+    fn collect_dispute_fee(env: &Env, token: &Address, caller: &Address, dispute_fee: i128) {
+        if dispute_fee <= 0 {
+            return;
+        }
+        let client = token::Client::new(env, token);
+        if client.balance(caller) < dispute_fee {
+            fail(
+                env,
+                AppError::InsufficientBalance,
+                "Insufficient balance for dispute fee",
+            );
         }
+        client.transfer(caller, &env.current_contract_address(), &dispute_fee);
+    }
 
-        if escrow.has_dispute {
-            panic!("Cannot update dispute period after dispute initiated");
+    // A deposit is split through its deductions, never released, refunded,
+    // timed out or disputed whole, and a contested deduction is settled only
+    // by arbitration, never by either party
+    fn require_not_deposit(env: &Env, escrow_id: &Symbol) {
+        if env
+            .storage()
+            .persistent()
+            .has(&(escrow_id.clone(), DEPOSIT_KEY))
+        {
+            fail(
+                env,
+                AppError::InvalidState,
+                "Deposit escrows settle through their deductions",
+            );
         }
+    }
 
-        if new_dispute_period == 0 {
-            panic!("Dispute period must be non-zero");
+    // Escrows are read-only while the guardian's escrow breaker is tripped
+    fn require_operational(env: &Env) {
+        if let Some(guardian) = Self::get_escrow_guardian(env.clone()) {
+            if GuardianCheck::is_halted(env, &guardian, Subsystem::Escrow) {
+                fail(env, AppError::Paused, "Escrow halted by guardian");
+            }
         }
+    }
 
-        if escrow.timeout_duration < new_dispute_period {
-            panic!("Dispute period cannot exceed timeout duration");
+    fn require_not_blocked(env: &Env, sender: &Address, recipient: &Address) {
+        if let Some(blocklist) = Self::get_escrow_blocklist(env.clone()) {
+            if BlocklistCheck::any_blocked(env, &blocklist, &[sender, recipient]) {
+                fail(env, AppError::Unauthorized, "Address is blocked");
+            }
         }
+    }
 
-        let updated_escrow = EscrowConfig {
-            dispute_period: new_dispute_period,
-            ..escrow.clone()
-        };
+    // Pay an active escrow out to its recipient on behalf of `released_by`
+    fn release_to_recipient(env: &Env, escrow: EscrowConfig, released_by: Address) -> EscrowInfo {
+        Self::require_not_blocked(env, &escrow.sender, &escrow.recipient);
+
+        // Mark the escrow settled, then pay the recipient
+        Self::settle(
+            env,
+            &escrow,
+            EscrowStatus::Released,
+            Payout::Recipient,
+            None,
+        );
+
+        // Emit escrow release event
+        crate::event::EventEmitter::emit_escrow_released(
+            env,
+            escrow.id.clone(),
+            released_by,
+            escrow.recipient.clone(),
+            escrow.token.clone(),
+            escrow.amount,
+        );
 
-        env.storage().instance().set(&escrow_id, &updated_escrow);
+        Self::record_outcomes(
+            env,
+            &escrow.sender,
+            &escrow.recipient,
+            TradeOutcome::Completed,
+        );
 
+        // Return updated escrow info
         EscrowInfo {
             id: escrow.id,
             sender: escrow.sender,
@@ -836,160 +2821,274 @@ impl EscrowContract {
             amount: escrow.amount,
             created_at: escrow.created_at,
             timeout_at: escrow.created_at + escrow.timeout_duration,
-            dispute_period: new_dispute_period,
-            status: escrow.status,
+            dispute_period: escrow.dispute_period,
+            status: EscrowStatus::Released,
             has_dispute: escrow.has_dispute,
         }
     }
 
-    /// Initialize contract with admin (should be called once during deployment)
-    pub fn initialize(env: Env, admin: Address) {
-        // Check if already initialized
-        if env.storage().instance().has(&ADMIN_KEY) {
-            panic!("Contract already initialized");
+    // Whoever the recipient's share would be paid to right now, without
+    // touching the receipt
+    fn current_payee(env: &Env, escrow: &EscrowConfig) -> Address {
+        match Self::get_escrow_receipt(env.clone(), escrow.id.clone()) {
+            Some((receipts, receipt_id)) => {
+                ReceiptContractClient::new(env, &receipts).receipt_owner(&receipt_id)
+            }
+            None => escrow.recipient.clone(),
         }
-
-        admin.require_auth();
-        env.storage().instance().set(&ADMIN_KEY, &admin);
-
-        // Set default dispute fee to 0 (can be updated by admin)
-        env.storage().instance().set(&DISPUTE_FEE_KEY, &0i128);
     }
 
-    /// Set dispute fee (admin only)
-    pub fn set_dispute_fee(env: Env, fee: i128) {
-        let admin: Address = env.storage().instance().get(&ADMIN_KEY).unwrap();
-        admin.require_auth();
-
-        if fee < 0 {
-            panic!("Dispute fee cannot be negative");
-        }
-
-        env.storage().instance().set(&DISPUTE_FEE_KEY, &fee);
+    // Frozen means listed on the escrow's blocklist, which is how the
+    // compliance side freezes a holder
+    fn is_frozen(env: &Env, account: &Address) -> bool {
+        Self::get_escrow_blocklist(env.clone())
+            .is_some_and(|blocklist| BlocklistCheck::any_blocked(env, &blocklist, &[account]))
     }
 
-    /// Get current dispute fee
-    pub fn get_dispute_fee(env: Env) -> i128 {
+    // Whoever the recipient's share is paid to: the holder of the escrow's
+    // receipt, which is burned on payout, or the recipient itself
+    fn take_payee(env: &Env, escrow: &EscrowConfig) -> Address {
+        let Some((receipts, receipt_id)) = Self::get_escrow_receipt(env.clone(), escrow.id.clone())
+        else {
+            return escrow.recipient.clone();
+        };
+        let client = ReceiptContractClient::new(env, &receipts);
+        let holder = client.receipt_owner(&receipt_id);
+        client.burn_receipt(&env.current_contract_address(), &receipt_id);
         env.storage()
-            .instance()
-            .get(&DISPUTE_FEE_KEY)
-            .unwrap_or(0i128)
-    }
-
-    /// Get contract admin
-    pub fn get_admin(env: Env) -> Address {
-        env.storage().instance().get(&ADMIN_KEY).unwrap()
+            .persistent()
+            .remove(&(escrow.id.clone(), RECEIPT_KEY));
+        holder
     }
 
-    /// Transfer admin rights (admin only)
-    pub fn transfer_admin(env: Env, new_admin: Address) {
-        let admin: Address = env.storage().instance().get(&ADMIN_KEY).unwrap();
-        admin.require_auth();
-
-        env.storage().instance().set(&ADMIN_KEY, &new_admin);
+    // An expired dispute is settled by arbitration rather than by whoever
+    // calls, once a panel is configured or the dispute has gone to one
+    fn require_not_arbitrated(env: &Env, escrow_id: &Symbol) {
+        if Self::get_arbitration_panel(env.clone()).is_some()
+            || Self::get_dispute_escalation(env.clone(), escrow_id.clone()).is_some()
+        {
+            fail(env, AppError::InvalidState, "Dispute goes to arbitration");
+        }
     }
 
-    /// Emergency pause/unpause functionality (admin only)
-    pub fn set_paused(env: Env, paused: bool) {
-        let admin: Address = env.storage().instance().get(&ADMIN_KEY).unwrap();
-        admin.require_auth();
+    // Settle a disputed escrow to its recipient; an expired dispute can be
+    // closed by anyone, with `keeper` (if any) tipped from the payout
+    fn resolve_for_recipient(env: &Env, escrow_id: Symbol, keeper: Option<&Address>) -> EscrowInfo {
+        Self::require_operational(env);
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("PAUSED"), &paused);
-    }
+        // Get the escrow
+        let escrow: EscrowConfig = storage::read(env, &escrow_id).unwrap();
 
-    /// Check if contract is paused
-    pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .instance()
-            .get(&symbol_short!("PAUSED"))
-            .unwrap_or(false)
-    }
+        // Validate the escrow is disputed
+        if escrow.status != EscrowStatus::Disputed {
+            fail(env, AppError::InvalidState, "Escrow is not disputed");
+        }
 
-    /// Admin emergency resolution (admin only, for extreme cases)
-    pub fn admin_resolve_dispute(
-        env: Env,
-        escrow_id: Symbol,
-        resolve_for_recipient: bool,
-    ) -> EscrowInfo {
-        let admin: Address = env.storage().instance().get(&ADMIN_KEY).unwrap();
-        admin.require_auth();
+        // Get dispute info
+        let dispute_key = symbol_short!("DISPUTE");
+        let dispute: DisputeInfo = storage::read(env, &(escrow_id.clone(), dispute_key)).unwrap();
 
-        let escrow: EscrowConfig = env.storage().instance().get(&escrow_id).unwrap();
+        // Check if dispute period has expired (auto-resolution)
+        let current_time = env.ledger().timestamp();
+        let dispute_expires_at = dispute.initiated_at + dispute.dispute_period;
 
-        if escrow.status != EscrowStatus::Disputed {
-            panic!("Escrow is not disputed");
+        Self::require_not_deposit(env, &escrow_id);
+        if current_time < dispute_expires_at {
+            // Manual resolution - require sender auth for now
+            escrow.sender.require_auth();
+        } else {
+            Self::require_not_arbitrated(env, &escrow_id);
         }
 
-        if resolve_for_recipient {
-            // Transfer tokens to recipient
-            let client = token::Client::new(&env, &escrow.token);
-            client.transfer(
-                &env.current_contract_address(),
-                &escrow.recipient,
-                &escrow.amount,
-            );
+        // Mark the escrow settled, then pay the recipient
+        Self::settle(
+            env,
+            &escrow,
+            EscrowStatus::DisputeResolvedForRecipient,
+            Payout::Recipient,
+            keeper,
+        );
+        Self::record_dispute_outcome(env, &escrow.recipient, &escrow.sender);
 
-            let updated_escrow = EscrowConfig {
-                status: EscrowStatus::DisputeResolvedForRecipient,
-                ..escrow.clone()
-            };
-            env.storage().instance().set(&escrow_id, &updated_escrow);
+        // Emit dispute resolved event
+        crate::event::EventEmitter::emit_dispute_resolved(
+            env,
+            escrow_id.clone(),
+            escrow.recipient.clone(),
+            true, // resolved_for_recipient
+        );
 
-            crate::event::EventEmitter::emit_dispute_resolved(
-                &env,
-                escrow_id.clone(),
-                escrow.recipient.clone(),
-                true,
+        // Return updated escrow info
+        EscrowInfo {
+            id: escrow.id,
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            token: escrow.token,
+            amount: escrow.amount,
+            created_at: escrow.created_at,
+            timeout_at: escrow.created_at + escrow.timeout_duration,
+            dispute_period: escrow.dispute_period,
+            status: EscrowStatus::DisputeResolvedForRecipient,
+            has_dispute: true,
+        }
+    }
+
+    // Store the escrow's final status before any tokens leave the contract, so a
+    // token or receipt contract calling back in sees it settled, then pay out.
+    // The processing flag turns away a nested settlement of the same escrow,
+    // and the re-read catches any callback that moved the escrow mid-payout.
+    // A `keeper` closing the escrow through a timeout takes the keeper tip out
+    // of the payout. A frozen recipient is never paid. Refunding a
+    // crowdfunded escrow leaves the funds for its contributors to claim.
+    fn settle(
+        env: &Env,
+        escrow: &EscrowConfig,
+        status: EscrowStatus,
+        payout: Payout,
+        keeper: Option<&Address>,
+    ) {
+        let lock = (escrow.id.clone(), PROCESSING_KEY);
+        if env.storage().temporary().has(&lock) {
+            fail(
+                env,
+                AppError::InvalidState,
+                "Escrow is already being processed",
             );
+        }
+        env.storage().temporary().set(&lock, &true);
 
-            EscrowInfo {
-                id: escrow.id,
-                sender: escrow.sender,
-                recipient: escrow.recipient,
-                token: escrow.token,
-                amount: escrow.amount,
-                created_at: escrow.created_at,
-                timeout_at: escrow.created_at + escrow.timeout_duration,
-                dispute_period: escrow.dispute_period,
-                status: EscrowStatus::DisputeResolvedForRecipient,
-                has_dispute: true,
+        let settled = EscrowConfig {
+            status: status.clone(),
+            ..escrow.clone()
+        };
+        storage::write(env, &escrow.id, &settled);
+        // A release approval has nothing left to act on
+        env.storage()
+            .persistent()
+            .remove(&(escrow.id.clone(), DELEGATE_KEY));
+
+        let refunding = matches!(payout, Payout::Sender);
+        let payee = match payout {
+            Payout::Recipient => {
+                // Refuse up front rather than let the token transfer fail;
+                // an operator can redirect the escrow to custody instead
+                if Self::is_frozen(env, &Self::current_payee(env, escrow)) {
+                    fail(env, AppError::Unauthorized, "Recipient is frozen");
+                }
+                Self::take_payee(env, escrow)
             }
-        } else {
-            // Transfer tokens back to sender
-            let client = token::Client::new(&env, &escrow.token);
-            client.transfer(
+            Payout::Sender => escrow.sender.clone(),
+            Payout::Redirect(payee) => {
+                Self::take_payee(env, escrow);
+                payee
+            }
+        };
+        let tip = keeper.map_or(0, |_| KeeperTips::tip_on(env, escrow.amount));
+        let token = token::Client::new(env, &escrow.token);
+        match Self::get_crowdfund(env.clone(), escrow.id.clone()) {
+            Some(mut crowdfund) if refunding => {
+                crowdfund.refundable = escrow.amount - tip;
+                storage::write(env, &(escrow.id.clone(), CROWDFUND_KEY), &crowdfund);
+            }
+            _ => token.transfer(
                 &env.current_contract_address(),
-                &escrow.sender,
-                &escrow.amount,
-            );
+                &payee,
+                &(escrow.amount - tip),
+            ),
+        }
+        if let Some(keeper) = keeper.filter(|_| tip > 0) {
+            token.transfer(&env.current_contract_address(), keeper, &tip);
+        }
 
-            let updated_escrow = EscrowConfig {
-                status: EscrowStatus::DisputeResolvedForSender,
-                ..escrow.clone()
-            };
-            env.storage().instance().set(&escrow_id, &updated_escrow);
+        let stored: EscrowConfig = storage::read(env, &escrow.id).unwrap();
+        if stored.status != status {
+            fail(env, AppError::InvalidState, "Escrow changed during payout");
+        }
+        env.storage().temporary().remove(&lock);
+    }
 
-            crate::event::EventEmitter::emit_dispute_resolved(
-                &env,
-                escrow_id.clone(),
-                escrow.sender.clone(),
-                false,
-            );
+    // Report a settled escrow to the reputation registry, if one is configured
+    fn record_outcomes(env: &Env, sender: &Address, recipient: &Address, outcome: TradeOutcome) {
+        if let Some(policy) = Self::get_reputation_policy(env.clone()) {
+            let registry = ReputationContractClient::new(env, &policy.registry);
+            let recorder = env.current_contract_address();
+            registry.record_outcome(&recorder, sender, &outcome);
+            registry.record_outcome(&recorder, recipient, &outcome);
+        }
+    }
 
-            EscrowInfo {
-                id: escrow.id,
-                sender: escrow.sender,
-                recipient: escrow.recipient,
-                token: escrow.token,
-                amount: escrow.amount,
-                created_at: escrow.created_at,
-                timeout_at: escrow.created_at + escrow.timeout_duration,
-                dispute_period: escrow.dispute_period,
-                status: EscrowStatus::DisputeResolvedForSender,
-                has_dispute: true,
+    fn record_dispute_outcome(env: &Env, winner: &Address, loser: &Address) {
+        if let Some(policy) = Self::get_reputation_policy(env.clone()) {
+            let registry = ReputationContractClient::new(env, &policy.registry);
+            let recorder = env.current_contract_address();
+            registry.record_outcome(&recorder, winner, &TradeOutcome::Disputed);
+            registry.record_outcome(&recorder, loser, &TradeOutcome::Defaulted);
+        }
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_escrow_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_escrow_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_escrow_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` held for open escrows, contributions still being raised, and
+    /// crowdfund refunds. A refund pool counts in full until the escrow is
+    /// gone, since claims against it are not totalled. Escrow records are
+    /// never removed, so a missing one fails the rescue rather than being
+    /// skipped and leaving its funds uncounted.
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let count = env
+            .storage()
+            .instance()
+            .get(&ESCROW_COUNT_KEY)
+            .unwrap_or(0u32);
+        let mut owed = 0;
+        for i in 0..count {
+            let mut s: HString<12> = HString::new();
+            s.push_str("escrow_").unwrap();
+            write!(&mut s, "{}", i).unwrap();
+            let id = Symbol::new(env, s.as_str());
+            let escrow: EscrowConfig = storage::read(env, &id)
+                .unwrap_or_else(|| fail(env, AppError::NotFound, "Escrow not found"));
+            if escrow.token != *token {
+                continue;
             }
+            let held = match escrow.status {
+                EscrowStatus::Active | EscrowStatus::Disputed => escrow.amount,
+                _ => 0,
+            };
+            let crowdfund = Self::get_crowdfund(env.clone(), id).map_or(0, |crowdfund| {
+                if escrow.status == EscrowStatus::Funding {
+                    crowdfund.raised
+                } else {
+                    crowdfund.refundable
+                }
+            });
+            owed = or_overflow(env, checked_add(owed, held));
+            owed = or_overflow(env, checked_add(owed, crowdfund));
         }
+        Some(owed)
     }
 }
@@ -1,4 +1,14 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec};
+use crate::access::Role;
+use crate::auction::AuctionKind;
+use crate::conversion::Currency;
+use crate::guardian::Subsystem;
+use crate::loyalty::Redemption;
+use crate::order_book::OrderSide;
+use crate::payment_request::PaymentRequestStatus;
+use crate::receipt::PositionKind;
+use crate::reference::PaymentReference;
+use crate::token::TokenMetadata;
+use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, Env, String, Symbol, Vec};
 
 // Event topics for efficient filtering and indexing
 pub const ESCROW_TOPIC: Symbol = symbol_short!("ESCROW");
@@ -6,112 +16,1464 @@ pub const SWAP_TOPIC: Symbol = symbol_short!("SWAP");
 pub const MULTISIG_TOPIC: Symbol = symbol_short!("MULTISIG");
 pub const TOKEN_TOPIC: Symbol = symbol_short!("TOKEN");
 pub const SYSTEM_TOPIC: Symbol = symbol_short!("SYSTEM");
+pub const RATE_LOCK_TOPIC: Symbol = symbol_short!("RATELOCK");
+pub const EMAIL_TOPIC: Symbol = symbol_short!("EMAIL");
+pub const TIMELOCK_TOPIC: Symbol = symbol_short!("TIMELOCK");
+pub const ACCESS_TOPIC: Symbol = symbol_short!("ACCESS");
+pub const ORACLE_TOPIC: Symbol = symbol_short!("ORACLE");
+pub const VESTING_TOPIC: Symbol = symbol_short!("VESTING");
+pub const STREAM_TOPIC: Symbol = symbol_short!("STREAM");
+pub const SAVINGS_TOPIC: Symbol = symbol_short!("SAVINGS");
+pub const SPLIT_TOPIC: Symbol = symbol_short!("SPLIT");
+pub const BILLING_TOPIC: Symbol = symbol_short!("BILLING");
+pub const INVOICE_TOPIC: Symbol = symbol_short!("INVOICE");
+pub const PAYROLL_TOPIC: Symbol = symbol_short!("PAYROLL");
+pub const LENDING_TOPIC: Symbol = symbol_short!("LENDING");
+pub const INSURANCE_TOPIC: Symbol = symbol_short!("INSURANCE");
+pub const COMPLIANCE_TOPIC: Symbol = symbol_short!("KYC");
+pub const BLOCKLIST_TOPIC: Symbol = symbol_short!("BLOCKLIST");
+pub const GUARDIAN_TOPIC: Symbol = symbol_short!("GUARDIAN");
+pub const INHERITANCE_TOPIC: Symbol = symbol_short!("INHERIT");
+pub const WALLET_TOPIC: Symbol = symbol_short!("WALLET");
+pub const SPONSORSHIP_TOPIC: Symbol = symbol_short!("SPONSOR");
+pub const AUCTION_TOPIC: Symbol = symbol_short!("AUCTION");
+pub const OTC_TOPIC: Symbol = symbol_short!("OTC");
+pub const REMITTANCE_TOPIC: Symbol = symbol_short!("REMIT");
+pub const DCA_TOPIC: Symbol = symbol_short!("DCA");
+pub const ORDER_BOOK_TOPIC: Symbol = symbol_short!("ORDERS");
+pub const BRIDGE_TOPIC: Symbol = symbol_short!("BRIDGE");
+pub const LOYALTY_TOPIC: Symbol = symbol_short!("LOYALTY");
+pub const REFERRAL_TOPIC: Symbol = symbol_short!("REFERRAL");
+pub const P2P_TOPIC: Symbol = symbol_short!("P2P");
+pub const PAYMENT_REQUEST_TOPIC: Symbol = symbol_short!("PAYREQ");
+pub const RECEIPT_TOPIC: Symbol = symbol_short!("RECEIPT");
+
+// Bumped whenever the layout of an event data structure changes
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+const EVENT_SEQUENCE_KEY: Symbol = symbol_short!("EVT_SEQ");
+const JOURNAL_CAPACITY_KEY: Symbol = symbol_short!("JRNL_CAP");
+const JOURNAL_KEY: Symbol = symbol_short!("JRNL");
+pub const MAX_JOURNAL_CAPACITY: u32 = 200;
+pub const MAX_JOURNAL_PAGE_SIZE: u32 = 50;
 
 // Escrow event data structure
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct EscrowCreatedData {
-    pub escrow_id: Symbol,
-    pub sender: Address,
-    pub recipient: Address,
-    pub token: Address,
+pub struct EscrowCreatedData {
+    pub escrow_id: Symbol,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub created_at: u64,
+    pub timeout_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowReleasedData {
+    pub escrow_id: Symbol,
+    pub released_by: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub released_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowRefundedData {
+    pub escrow_id: Symbol,
+    pub refunded_by: Address,
+    pub sender: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub refunded_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDisputeInitiatedData {
+    pub escrow_id: Symbol,
+    pub initiated_by: Address,
+    pub reason: Symbol,
+    pub dispute_period: u64,
+    pub initiated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDisputeResolvedData {
+    pub escrow_id: Symbol,
+    pub resolved_for: Address,
+    pub resolved_for_recipient: bool,
+    pub resolved_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowPauseChangedData {
+    pub admin: Address,
+    pub changed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowAdminTransferredData {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+    pub transferred_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDisputeFeeUpdatedData {
+    pub admin: Address,
+    pub old_fee: i128,
+    pub new_fee: i128,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowInstanceDeployedData {
+    pub escrow: Address,
+    pub client: Address,
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+    pub deployed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowReleaseDelegationData {
+    pub escrow_id: Symbol,
+    pub sender: Address,
+    pub operator: Address,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeNoteAddedData {
+    pub escrow_id: Symbol,
+    pub author: Address,
+    /// Hash of the off-chain mediation message
+    pub hash: BytesN<32>,
+    /// Position of the note in the dispute's log
+    pub index: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowCustodyRedirectData {
+    pub escrow_id: Symbol,
+    /// Frozen party the escrow would otherwise have paid
+    pub frozen_payee: Address,
+    pub custody: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub redirected_by: Address,
+    pub redirected_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDisputeEscalatedData {
+    pub escrow_id: Symbol,
+    pub panel: Address,
+    /// Keeper who reported the expired dispute
+    pub escalated_by: Address,
+    pub escalated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowPromoRedeemedData {
+    pub escrow_id: Symbol,
+    pub code_hash: BytesN<32>,
+    pub sender: Address,
+    /// Share of the escrow's dispute fee waived, in basis points
+    pub discount_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowContributionData {
+    pub escrow_id: Symbol,
+    pub funder: Address,
+    /// Amount paid in, or paid back on a refund
+    pub amount: i128,
+    /// Total contributed toward the escrow's target
+    pub raised: i128,
+}
+
+// Escrow events added after `DeFiEvent` reached the 50-case limit on
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDeductionData {
+    pub escrow_id: Symbol,
+    /// Position of the deduction in the deposit's list
+    pub index: u32,
+    pub amount: i128,
+    pub reason: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowDepositSettledData {
+    pub escrow_id: Symbol,
+    /// Uncontested deductions paid to the recipient
+    pub deducted: i128,
+    /// Returned to the sender
+    pub returned: i128,
+    /// Left in escrow for dispute resolution
+    pub contested: i128,
+}
+
+// contract spec unions
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum EscrowEvent {
+    ReleaseDelegated(EscrowReleaseDelegationData),
+    ReleaseDelegationRevoked(EscrowReleaseDelegationData),
+    DisputeNoteAdded(DisputeNoteAddedData),
+    RedirectedToCustody(EscrowCustodyRedirectData),
+    PromoRedeemed(EscrowPromoRedeemedData),
+    DisputeEscalated(EscrowDisputeEscalatedData),
+    Contributed(EscrowContributionData),
+    ContributionRefunded(EscrowContributionData),
+    DeductionClaimed(EscrowDeductionData),
+    DeductionContested(EscrowDeductionData),
+    DepositSettled(EscrowDepositSettledData),
+}
+
+// Swap event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SwapOfferCreatedData {
+    pub offer_id: u64,
+    pub creator: Address,
+    pub offer_token: Address,
+    pub offer_amount: i128,
+    pub request_token: Address,
+    pub request_amount: i128,
+    pub exchange_rate: i128,
+    pub expires_at: u64,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SwapOfferAcceptedData {
+    pub offer_id: u64,
+    pub creator: Address,
+    pub acceptor: Address,
+    pub offer_token: Address,
+    pub offer_amount: i128,
+    pub request_token: Address,
+    pub request_amount: i128,
+    pub fee_amount: i128,
+    pub fee_token: Address,
+    pub accepted_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum SwapOfferEvent {
+    Created(SwapOfferCreatedData),
+    Accepted(SwapOfferAcceptedData),
+}
+
+// Token event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenTransferredData {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub from_balance: i128,
+    pub to_balance: i128,
+    pub transferred_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenApprovedData {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub amount: i128,
+    pub approved_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenMintedData {
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub minter: Address,
+    pub minted_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenSnapshotData {
+    pub token: Address,
+    pub snapshot_id: u32,
+    pub taken_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenMetadataUpdatedData {
+    pub token: Address,
+    /// New documentation pointer; `None` when cleared
+    pub metadata: Option<TokenMetadata>,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenLockedData {
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    /// Lock source the tokens came from
+    pub source: Address,
+    pub unlock_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum TokenEvent {
+    Transferred(TokenTransferredData),
+    Minted(TokenMintedData),
+    Approved(TokenApprovedData),
+    SnapshotTaken(TokenSnapshotData),
+    MetadataUpdated(TokenMetadataUpdatedData),
+    Locked(TokenLockedData),
+}
+
+// Rate lock event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RateLockCreatedData {
+    pub lock_id: u64,
+    pub user: Address,
+    pub from_currency: Currency,
+    pub to_currency: Currency,
+    pub rate: i128,
+    pub max_amount: i128,
+    pub fee_paid: i128,
+    pub expires_at: u64,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RateLockConsumedData {
+    pub lock_id: u64,
+    pub user: Address,
+    pub amount: i128,
+    pub remaining_amount: i128,
+    pub consumed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RateLockClosedData {
+    pub lock_id: u64,
+    pub user: Address,
+    pub unused_amount: i128,
+    pub closed_at: u64,
+}
+
+/// Rate lock events, nested so the whole family takes a single `DeFiEvent` case
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum RateLockEvent {
+    Created(RateLockCreatedData),
+    Consumed(RateLockConsumedData),
+    Expired(RateLockClosedData),
+    Cancelled(RateLockClosedData),
+}
+
+// Email registry event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailRegisteredData {
+    pub email_hash: BytesN<32>,
+    pub wallet: Address,
+    pub registered_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailVerifiedData {
+    pub email_hash: BytesN<32>,
+    pub wallet: Address,
+    pub verified_by: Address,
+    pub verified_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailPaymentSentData {
+    pub payment_id: u64,
+    pub sender: Address,
+    pub email_hash: BytesN<32>,
+    pub token: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailPaymentClaimedData {
+    pub payment_id: u64,
+    pub email_hash: BytesN<32>,
+    pub wallet: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub claimed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailPaymentRefundedData {
+    pub payment_id: u64,
+    pub sender: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub refunded_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailRebindInitiatedData {
+    pub email_hash: BytesN<32>,
+    pub current_wallet: Address,
+    pub new_wallet: Address,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailRebindCancelledData {
+    pub email_hash: BytesN<32>,
+    pub cancelled_by: Address,
+    pub cancelled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmailReboundData {
+    pub email_hash: BytesN<32>,
+    pub old_wallet: Address,
+    pub new_wallet: Address,
+    pub rebound_at: u64,
+}
+
+/// Email registry events, nested so the whole family takes a single `DeFiEvent` case
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum EmailEvent {
+    Registered(EmailRegisteredData),
+    Verified(EmailVerifiedData),
+    PaymentSent(EmailPaymentSentData),
+    PaymentClaimed(EmailPaymentClaimedData),
+    PaymentRefunded(EmailPaymentRefundedData),
+    RebindInitiated(EmailRebindInitiatedData),
+    RebindCancelled(EmailRebindCancelledData),
+    Rebound(EmailReboundData),
+}
+
+// Timelock event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TimelockQueuedData {
+    pub operation_id: u64,
+    pub target: Address,
+    pub function: Symbol,
+    pub eta: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TimelockExecutedData {
+    pub operation_id: u64,
+    pub target: Address,
+    pub function: Symbol,
+    pub executed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TimelockCancelledData {
+    pub operation_id: u64,
+    pub cancelled_at: u64,
+}
+
+/// Timelock events, nested so the whole family takes a single `DeFiEvent` case
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum TimelockEvent {
+    Queued(TimelockQueuedData),
+    Executed(TimelockExecutedData),
+    Cancelled(TimelockCancelledData),
+}
+
+// Access control event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RoleChangedData {
+    pub role: Role,
+    pub account: Address,
+    pub changed_by: Address,
+    pub changed_at: u64,
+}
+
+// Oracle event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PriceSubmittedData {
+    pub feeder: Address,
+    pub from_currency: Currency,
+    pub to_currency: Currency,
+    pub price: i128,
+    pub submitted_at: u64,
+}
+
+// Vesting event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VestingCreatedData {
+    pub schedule_id: u64,
+    pub beneficiary: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub cliff_time: u64,
+    pub end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VestingClaimedData {
+    pub schedule_id: u64,
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub total_claimed: i128,
+    pub claimed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VestingRevokedData {
+    pub schedule_id: u64,
+    pub vested: i128,
+    pub refunded: i128,
+    pub revoked_at: u64,
+}
+
+/// Vesting events, nested so the whole family takes a single `DeFiEvent` case
+/// (contract enums are limited to 50 cases)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum VestingEvent {
+    Created(VestingCreatedData),
+    Claimed(VestingClaimedData),
+    Revoked(VestingRevokedData),
+}
+
+// Stream event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamCreatedData {
+    pub stream_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub deposit: i128,
+    pub start_time: u64,
+    pub stop_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamWithdrawnData {
+    pub stream_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub total_withdrawn: i128,
+    pub withdrawn_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamPauseChangedData {
+    pub stream_id: u64,
+    /// Amount streamed at the time of the change
+    pub streamed: i128,
+    pub changed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamCancelledData {
+    pub stream_id: u64,
+    pub recipient_amount: i128,
+    pub sender_amount: i128,
+    pub cancelled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum StreamEvent {
+    Created(StreamCreatedData),
+    Withdrawn(StreamWithdrawnData),
+    Paused(StreamPauseChangedData),
+    Resumed(StreamPauseChangedData),
+    Cancelled(StreamCancelledData),
+}
+
+// Savings group event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SavingsContributionData {
+    pub group_id: u64,
+    pub round: u32,
+    pub member: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SavingsPenaltyData {
+    pub group_id: u64,
+    pub round: u32,
+    pub member: Address,
+    /// Missed contribution plus penalty taken from the member's collateral
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SavingsRoundPaidData {
+    pub group_id: u64,
+    pub round: u32,
+    pub recipient: Address,
+    pub amount: i128,
+    pub missed_contributions: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum SavingsEvent {
+    Contribution(SavingsContributionData),
+    PenaltyCharged(SavingsPenaltyData),
+    RoundPaid(SavingsRoundPaidData),
+}
+
+// Splitter event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SplitDepositedData {
+    pub split_id: u64,
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SplitReleasedData {
+    pub split_id: u64,
+    pub token: Address,
+    pub payee: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum SplitEvent {
+    Deposited(SplitDepositedData),
+    Released(SplitReleasedData),
+}
+
+// Subscription event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SubscriptionCreatedData {
+    pub subscription_id: u64,
+    pub plan_id: u64,
+    pub subscriber: Address,
+    pub merchant: Address,
+    pub spending_cap: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InvoiceChargedData {
+    pub subscription_id: u64,
+    /// Sequential per subscription, starting at 1
+    pub invoice_number: u32,
+    pub subscriber: Address,
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub charged_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SubscriptionCancelledData {
+    pub subscription_id: u64,
+    pub subscriber: Address,
+    pub total_charged: i128,
+    pub cancelled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum SubscriptionEvent {
+    Created(SubscriptionCreatedData),
+    Invoiced(InvoiceChargedData),
+    Cancelled(SubscriptionCancelledData),
+}
+
+// Invoice event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InvoiceIssuedData {
+    pub invoice_id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub due_date: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InvoicePaidData {
+    pub invoice_id: u64,
+    pub payer: Address,
+    pub amount: i128,
+    /// Set when the payment is held by an escrow
+    pub escrow_id: Option<Symbol>,
+    pub paid_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InvoiceStatusChangedData {
+    pub invoice_id: u64,
+    pub changed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum InvoiceEvent {
+    Issued(InvoiceIssuedData),
+    Paid(InvoicePaidData),
+    Cancelled(InvoiceStatusChangedData),
+    /// An escrow payment was refunded, so the invoice is payable again
+    Reopened(InvoiceStatusChangedData),
+}
+
+// Payroll event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SalaryPaidData {
+    pub employee: Address,
+    pub currency: Currency,
+    /// Amount paid in `currency`
+    pub amount: i128,
+    /// Salary in the payroll's base currency
+    pub base_amount: i128,
+    pub pay_date: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PayrollRunData {
+    pub offset: u32,
+    pub employees_paid: u32,
+    pub total_base_amount: i128,
+    pub run_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum PayrollEvent {
+    SalaryPaid(SalaryPaidData),
+    RunCompleted(PayrollRunData),
+}
+
+// Lending event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LendingActionData {
+    pub user: Address,
+    pub currency: Currency,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LiquidationData {
+    pub borrower: Address,
+    pub liquidator: Address,
+    pub debt_currency: Currency,
+    pub repaid: i128,
+    pub collateral_currency: Currency,
+    pub seized: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum LendingEvent {
+    Supplied(LendingActionData),
+    Withdrawn(LendingActionData),
+    Borrowed(LendingActionData),
+    Repaid(LendingActionData),
+    Liquidated(LiquidationData),
+}
+
+// Insurance event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InsuranceFundedData {
+    pub from: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InsuranceStakeData {
+    pub staker: Address,
+    pub amount: i128,
+    pub shares: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InsuranceClaimData {
+    pub claim_id: u64,
+    pub claimant: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum InsuranceEvent {
+    Funded(InsuranceFundedData),
+    Staked(InsuranceStakeData),
+    Unstaked(InsuranceStakeData),
+    ClaimSubmitted(InsuranceClaimData),
+    ClaimPaid(InsuranceClaimData),
+    ClaimRejected(InsuranceClaimData),
+}
+
+// Compliance event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct KycAttestedData {
+    pub subject: Address,
+    pub tier: u32,
+    pub expires_at: u64,
+    pub attestor: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct KycRevokedData {
+    pub subject: Address,
+    pub revoked_by: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum ComplianceEvent {
+    Attested(KycAttestedData),
+    Revoked(KycRevokedData),
+}
+
+// Blocklist event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AddressBlockedData {
+    pub account: Address,
+    pub reason: Symbol,
+    pub changed_by: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AddressUnblockedData {
+    pub account: Address,
+    pub changed_by: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum BlocklistEvent {
+    Blocked(AddressBlockedData),
+    Unblocked(AddressUnblockedData),
+}
+
+// Guardian event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BreakerTrippedData {
+    pub subsystem: Subsystem,
+    pub monitor: Address,
+    pub reason: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BreakerResetData {
+    pub subsystem: Subsystem,
+    pub reset_by: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum GuardianEvent {
+    Tripped(BreakerTrippedData),
+    Reset(BreakerResetData),
+}
+
+// Inheritance event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InheritancePlanSetData {
+    pub owner: Address,
+    pub beneficiary: Option<Address>,
+    pub inactivity_period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InheritanceAnnouncedData {
+    pub owner: Address,
+    pub beneficiary: Address,
+    pub claimable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InheritanceClaimData {
+    pub owner: Address,
+    pub beneficiary: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum InheritanceEvent {
+    PlanSet(InheritancePlanSetData),
+    Announced(InheritanceAnnouncedData),
+    Cancelled(InheritanceClaimData),
+    Claimed(InheritanceClaimData),
+}
+
+// Smart wallet event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletCosignerChangedData {
+    pub wallet: Address,
+    pub cosigner: Option<BytesN<32>>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletPolicySetData {
+    pub wallet: Address,
+    pub token: Address,
+    pub daily_limit: i128,
+    pub cosign_threshold: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletPolicyRemovedData {
+    pub wallet: Address,
+    pub token: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletAllowlistData {
+    pub wallet: Address,
+    pub enabled: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletDestinationData {
+    pub wallet: Address,
+    pub destination: Address,
+    pub allowed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WalletRecoveryData {
+    pub wallet: Address,
+    pub new_key: BytesN<32>,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum WalletEvent {
+    CosignerChanged(WalletCosignerChangedData),
+    PolicySet(WalletPolicySetData),
+    PolicyRemoved(WalletPolicyRemovedData),
+    AllowlistToggled(WalletAllowlistData),
+    DestinationSet(WalletDestinationData),
+    RecoveryStarted(WalletRecoveryData),
+    RecoveryCancelled(WalletRecoveryData),
+    Recovered(WalletRecoveryData),
+}
+
+// Sponsorship event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SponsorshipFundedData {
+    pub funder: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SponsoredCallData {
+    pub user: Address,
+    pub contract: Address,
+    pub function: Symbol,
+    pub relayer: Address,
+    pub fee: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum SponsorshipEvent {
+    Funded(SponsorshipFundedData),
+    Relayed(SponsoredCallData),
+}
+
+// Auction event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionCreatedData {
+    pub auction_id: u64,
+    pub seller: Address,
+    pub lot_token: Address,
+    pub lot_amount: i128,
+    pub kind: AuctionKind,
+    pub end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionBidData {
+    pub auction_id: u64,
+    pub bidder: Address,
+    pub amount: i128,
+    pub end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionSettledData {
+    pub auction_id: u64,
+    pub winner: Option<Address>,
+    pub price: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionCancelledData {
+    pub auction_id: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum AuctionEvent {
+    Created(AuctionCreatedData),
+    BidPlaced(AuctionBidData),
+    Settled(AuctionSettledData),
+    Cancelled(AuctionCancelledData),
+}
+
+// OTC deal room event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OtcDealData {
+    pub deal_id: u64,
+    pub party_a: Address,
+    pub party_b: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OtcLegFundedData {
+    pub deal_id: u64,
+    pub leg_index: u32,
+    pub payer: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum OtcEvent {
+    Proposed(OtcDealData),
+    LegFunded(OtcLegFundedData),
+    Settled(OtcDealData),
+    Cancelled(OtcDealData),
+}
+
+// Remittance corridor event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RemittanceSentData {
+    pub remittance_id: u64,
+    pub sender: Address,
+    pub agent: Address,
+    pub amount: i128,
+    pub deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RemittanceSettledData {
+    pub remittance_id: u64,
+    pub receiver: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum RemittanceEvent {
+    Sent(RemittanceSentData),
+    Delivered(RemittanceSettledData),
+    Refunded(RemittanceSettledData),
+}
+
+// DCA vault event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DcaCreatedData {
+    pub plan_id: u64,
+    pub owner: Address,
+    pub source: Currency,
+    pub target: Currency,
+    pub deposit: i128,
+    pub amount_per_period: i128,
+    pub period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DcaExecutedData {
+    pub plan_id: u64,
+    pub spent: i128,
+    pub received: i128,
+    pub price: i128,
+    pub remaining: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DcaCancelledData {
+    pub plan_id: u64,
+    pub owner: Address,
+    pub refunded: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DcaEvent {
+    Created(DcaCreatedData),
+    Executed(DcaExecutedData),
+    Cancelled(DcaCancelledData),
+}
+
+// Order book event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderPlacedData {
+    pub order_id: u64,
+    pub owner: Address,
+    pub side: OrderSide,
+    pub price: i128,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderCancelledData {
+    pub order_id: u64,
+    pub owner: Address,
+    pub remaining: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderFilledData {
+    pub buy_order_id: u64,
+    pub sell_order_id: u64,
+    pub price: i128,
     pub amount: i128,
-    pub created_at: u64,
-    pub timeout_at: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct EscrowReleasedData {
-    pub escrow_id: Symbol,
-    pub released_by: Address,
-    pub recipient: Address,
+pub struct TradingSessionData {
+    pub master: Address,
+    pub bot: Address,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum OrderBookEvent {
+    Placed(OrderPlacedData),
+    Cancelled(OrderCancelledData),
+    Filled(OrderFilledData),
+    SessionGranted(TradingSessionData),
+    SessionRevoked(TradingSessionData),
+}
+
+// Bridge event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BridgeOutboundData {
+    pub transfer_id: u64,
+    pub sender: Address,
     pub token: Address,
     pub amount: i128,
-    pub released_at: u64,
+    pub dest_chain: u32,
+    pub dest_address: Bytes,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct EscrowRefundedData {
-    pub escrow_id: Symbol,
-    pub refunded_by: Address,
-    pub sender: Address,
+pub struct BridgeInboundData {
+    pub transfer_id: BytesN<32>,
     pub token: Address,
+    pub recipient: Address,
     pub amount: i128,
-    pub refunded_at: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct EscrowDisputeInitiatedData {
-    pub escrow_id: Symbol,
-    pub initiated_by: Address,
-    pub reason: Symbol,
-    pub dispute_period: u64,
-    pub initiated_at: u64,
+pub struct BridgePauseData {
+    pub caller: Address,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct EscrowDisputeResolvedData {
-    pub escrow_id: Symbol,
-    pub resolved_for: Address,
-    pub resolved_for_recipient: bool,
-    pub resolved_at: u64,
+pub enum BridgeEvent {
+    Outbound(BridgeOutboundData),
+    Inbound(BridgeInboundData),
+    Paused(BridgePauseData),
+    Unpaused(BridgePauseData),
 }
 
-// Swap event data structures
+// Loyalty event data structures
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct SwapOfferCreatedData {
+pub struct LoyaltyAwardedData {
+    pub account: Address,
+    pub volume: i128,
+    pub points: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LoyaltyRedeemedData {
+    pub account: Address,
+    pub points: i128,
+    pub redemption: Redemption,
+    /// Fee credit granted or reward tokens paid out
+    pub value: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum LoyaltyEvent {
+    Awarded(LoyaltyAwardedData),
+    Redeemed(LoyaltyRedeemedData),
+}
+
+// Referral event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralRegisteredData {
+    pub referee: Address,
+    pub referrer: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralAccruedData {
+    pub referrer: Address,
+    pub trader: Address,
+    /// 1 for the direct referrer, 2 for the referrer's referrer
+    pub tier: u32,
+    pub fee: i128,
+    pub commission: i128,
+    pub vests_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralClaimedData {
+    pub referrer: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum ReferralEvent {
+    Registered(ReferralRegisteredData),
+    Accrued(ReferralAccruedData),
+    Claimed(ReferralClaimedData),
+}
+
+// P2P market event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct P2pOfferPostedData {
     pub offer_id: u64,
-    pub creator: Address,
-    pub offer_token: Address,
-    pub offer_amount: i128,
-    pub request_token: Address,
-    pub request_amount: i128,
-    pub exchange_rate: i128,
-    pub expires_at: u64,
-    pub created_at: u64,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub fiat: Currency,
+    pub price: i128,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct SwapOfferAcceptedData {
+pub struct P2pTradeOpenedData {
+    pub trade_id: u64,
     pub offer_id: u64,
-    pub creator: Address,
-    pub acceptor: Address,
-    pub offer_token: Address,
-    pub offer_amount: i128,
-    pub request_token: Address,
-    pub request_amount: i128,
-    pub fee_amount: i128,
-    pub fee_token: Address,
-    pub accepted_at: u64,
+    pub buyer: Address,
+    pub amount: i128,
+    pub fiat_amount: i128,
+    pub payment_deadline: u64,
 }
 
-// Token event data structures
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct TokenTransferredData {
-    pub token: Address,
-    pub from: Address,
-    pub to: Address,
+pub struct P2pTradeData {
+    pub trade_id: u64,
+    pub caller: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct P2pTradeSettledData {
+    pub trade_id: u64,
+    pub released_to_buyer: bool,
     pub amount: i128,
-    pub from_balance: i128,
-    pub to_balance: i128,
-    pub transferred_at: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct TokenMintedData {
+pub enum P2pEvent {
+    OfferPosted(P2pOfferPostedData),
+    TradeOpened(P2pTradeOpenedData),
+    FiatSent(P2pTradeData),
+    Disputed(P2pTradeData),
+    Settled(P2pTradeSettledData),
+}
+
+// Payment request event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PaymentRequestCreatedData {
+    pub request_id: u64,
+    pub requester: Address,
+    pub payer: Address,
     pub token: Address,
-    pub to: Address,
     pub amount: i128,
-    pub minter: Address,
-    pub minted_at: u64,
+    pub expires_at: u64,
+    pub memo: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PaymentRequestReminderData {
+    pub request_id: u64,
+    pub payer: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PaymentRequestClosedData {
+    pub request_id: u64,
+    pub status: PaymentRequestStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum PaymentRequestEvent {
+    Created(PaymentRequestCreatedData),
+    Reminded(PaymentRequestReminderData),
+    Closed(PaymentRequestClosedData),
+}
+
+// Receipt event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReceiptMintedData {
+    pub receipt_id: u64,
+    pub issuer: Address,
+    pub kind: PositionKind,
+    pub to: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReceiptTransferredData {
+    pub receipt_id: u64,
+    pub from: Address,
+    pub to: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum ReceiptEvent {
+    Minted(ReceiptMintedData),
+    Transferred(ReceiptTransferredData),
+    /// Receipt retired by its issuer; `from` is the last holder
+    Burned(ReceiptTransferredData),
+}
+
+// Upgrade event data structures
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ContractUpgradedData {
+    pub new_wasm_hash: BytesN<32>,
+    pub upgraded_by: Address,
+    pub upgraded_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StorageMigratedData {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub migrated_at: u64,
 }
 
 // Multisig event data structures
@@ -145,6 +1507,14 @@ pub struct MultisigConfigUpdatedData {
     pub updated_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum MultisigEvent {
+    TransactionProposed(MultisigTransactionProposedData),
+    TransactionExecuted(MultisigTransactionExecutedData),
+    ConfigUpdated(MultisigConfigUpdatedData),
+}
+
 // Wallet event data structures
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -177,23 +1547,92 @@ pub enum DeFiEvent {
     EscrowRefunded(EscrowRefundedData),
     EscrowDisputeInitiated(EscrowDisputeInitiatedData),
     EscrowDisputeResolved(EscrowDisputeResolvedData),
-    SwapOfferCreated(SwapOfferCreatedData),
-    SwapOfferAccepted(SwapOfferAcceptedData),
-    TokenTransferred(TokenTransferredData),
-    TokenMinted(TokenMintedData),
-    MultisigTransactionProposed(MultisigTransactionProposedData),
-    MultisigTransactionExecuted(MultisigTransactionExecutedData),
-    MultisigConfigUpdated(MultisigConfigUpdatedData),
+    EscrowPaused(EscrowPauseChangedData),
+    EscrowUnpaused(EscrowPauseChangedData),
+    EscrowAdminTransferred(EscrowAdminTransferredData),
+    EscrowDisputeFeeUpdated(EscrowDisputeFeeUpdatedData),
+    EscrowInstanceDeployed(EscrowInstanceDeployedData),
+    Escrow(EscrowEvent),
+    SwapOffer(SwapOfferEvent),
+    Token(TokenEvent),
+    RateLock(RateLockEvent),
+    Email(EmailEvent),
+    Timelock(TimelockEvent),
+    RoleGranted(RoleChangedData),
+    RoleRevoked(RoleChangedData),
+    PriceSubmitted(PriceSubmittedData),
+    Vesting(VestingEvent),
+    Stream(StreamEvent),
+    Savings(SavingsEvent),
+    Split(SplitEvent),
+    Subscription(SubscriptionEvent),
+    Invoice(InvoiceEvent),
+    Payroll(PayrollEvent),
+    Lending(LendingEvent),
+    Insurance(InsuranceEvent),
+    Compliance(ComplianceEvent),
+    Blocklist(BlocklistEvent),
+    Guardian(GuardianEvent),
+    Inheritance(InheritanceEvent),
+    Wallet(WalletEvent),
+    Sponsorship(SponsorshipEvent),
+    Auction(AuctionEvent),
+    Otc(OtcEvent),
+    Remittance(RemittanceEvent),
+    Dca(DcaEvent),
+    OrderBook(OrderBookEvent),
+    Bridge(BridgeEvent),
+    Loyalty(LoyaltyEvent),
+    Referral(ReferralEvent),
+    P2p(P2pEvent),
+    PaymentRequest(PaymentRequestEvent),
+    Receipt(ReceiptEvent),
+    ContractUpgraded(ContractUpgradedData),
+    StorageMigrated(StorageMigratedData),
+    Multisig(MultisigEvent),
     WalletToppedUp(WalletToppedUpData),
     ContractError(ContractErrorData),
 }
 
+// Every event is published inside an envelope so indexers can detect gaps
+// (sequence is per emitting contract) and handle format changes across upgrades
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EventEnvelope {
+    pub version: u32,
+    pub contract: Address,
+    pub sequence: u64,
+    /// Backend payment reference of the operation that emitted the event;
+    /// all zeroes when none was supplied
+    pub reference: BytesN<32>,
+    pub event: DeFiEvent,
+}
+
 // Event emission utilities
 pub struct EventEmitter;
 
 impl EventEmitter {
     pub fn emit_event(env: &Env, topic: Symbol, event: DeFiEvent) {
-        env.events().publish((topic,), event);
+        let sequence = Self::current_sequence(env) + 1;
+        env.storage().instance().set(&EVENT_SEQUENCE_KEY, &sequence);
+
+        let envelope = EventEnvelope {
+            version: EVENT_SCHEMA_VERSION,
+            contract: env.current_contract_address(),
+            sequence,
+            reference: PaymentReference::current(env),
+            event,
+        };
+        EventJournal::record(env, &envelope);
+        env.events().publish((topic,), envelope);
+    }
+
+    // Sequence number of the last event emitted by the current contract
+    pub fn current_sequence(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&EVENT_SEQUENCE_KEY)
+            .unwrap_or(0)
     }
 
     pub fn emit_escrow_created(
@@ -273,6 +1712,101 @@ impl EventEmitter {
         Self::emit_event(env, ESCROW_TOPIC, event);
     }
 
+    pub fn emit_escrow_pause_changed(env: &Env, admin: Address, paused: bool) {
+        let event_data = EscrowPauseChangedData {
+            admin,
+            changed_at: env.ledger().timestamp(),
+        };
+        let event = if paused {
+            DeFiEvent::EscrowPaused(event_data)
+        } else {
+            DeFiEvent::EscrowUnpaused(event_data)
+        };
+        Self::emit_event(env, ESCROW_TOPIC, event);
+    }
+
+    pub fn emit_escrow_admin_transferred(env: &Env, previous_admin: Address, new_admin: Address) {
+        let event_data = EscrowAdminTransferredData {
+            previous_admin,
+            new_admin,
+            transferred_at: env.ledger().timestamp(),
+        };
+        let event = DeFiEvent::EscrowAdminTransferred(event_data);
+        Self::emit_event(env, ESCROW_TOPIC, event);
+    }
+
+    pub fn emit_dispute_fee_updated(env: &Env, admin: Address, old_fee: i128, new_fee: i128) {
+        let event_data = EscrowDisputeFeeUpdatedData {
+            admin,
+            old_fee,
+            new_fee,
+            updated_at: env.ledger().timestamp(),
+        };
+        let event = DeFiEvent::EscrowDisputeFeeUpdated(event_data);
+        Self::emit_event(env, ESCROW_TOPIC, event);
+    }
+
+    pub fn emit_release_delegation_changed(
+        env: &Env,
+        delegation: EscrowReleaseDelegationData,
+        revoked: bool,
+    ) {
+        let event = if revoked {
+            EscrowEvent::ReleaseDelegationRevoked(delegation)
+        } else {
+            EscrowEvent::ReleaseDelegated(delegation)
+        };
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_dispute_note_added(env: &Env, note: DisputeNoteAddedData) {
+        let event = EscrowEvent::DisputeNoteAdded(note);
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_redirected_to_custody(env: &Env, redirect: EscrowCustodyRedirectData) {
+        let event = EscrowEvent::RedirectedToCustody(redirect);
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_dispute_escalated(env: &Env, escalation: EscrowDisputeEscalatedData) {
+        let event = EscrowEvent::DisputeEscalated(escalation);
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_contribution(
+        env: &Env,
+        contribution: EscrowContributionData,
+        refunded: bool,
+    ) {
+        let event = if refunded {
+            EscrowEvent::ContributionRefunded(contribution)
+        } else {
+            EscrowEvent::Contributed(contribution)
+        };
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_deduction(env: &Env, deduction: EscrowDeductionData, contested: bool) {
+        let event = if contested {
+            EscrowEvent::DeductionContested(deduction)
+        } else {
+            EscrowEvent::DeductionClaimed(deduction)
+        };
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_deposit_settled(env: &Env, settled: EscrowDepositSettledData) {
+        let event = EscrowEvent::DepositSettled(settled);
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    pub fn emit_escrow_promo_redeemed(env: &Env, redeemed: EscrowPromoRedeemedData) {
+        let event = EscrowEvent::PromoRedeemed(redeemed);
+        Self::emit_event(env, ESCROW_TOPIC, DeFiEvent::Escrow(event));
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn emit_swap_offer_created(
         env: &Env,
         offer_id: u64,
@@ -300,7 +1834,7 @@ impl EventEmitter {
             expires_at,
             created_at: env.ledger().timestamp(),
         };
-        let event = DeFiEvent::SwapOfferCreated(event_data);
+        let event = DeFiEvent::SwapOffer(SwapOfferEvent::Created(event_data));
         Self::emit_event(env, SWAP_TOPIC, event);
     }
 
@@ -322,7 +1856,25 @@ impl EventEmitter {
             to_balance,
             transferred_at: env.ledger().timestamp(),
         };
-        let event = DeFiEvent::TokenTransferred(event_data);
+        let event = DeFiEvent::Token(TokenEvent::Transferred(event_data));
+        Self::emit_event(env, TOKEN_TOPIC, event);
+    }
+
+    pub fn emit_token_approval(
+        env: &Env,
+        token: Address,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+    ) {
+        let event_data = TokenApprovedData {
+            token,
+            owner,
+            spender,
+            amount,
+            approved_at: env.ledger().timestamp(),
+        };
+        let event = DeFiEvent::Token(TokenEvent::Approved(event_data));
         Self::emit_event(env, TOKEN_TOPIC, event);
     }
 
@@ -365,6 +1917,66 @@ impl EventEmitter {
     }
 }
 
+// Optional ring buffer of the last N events per contract, for clients that
+// cannot run an event indexer. Disabled (capacity 0) unless a contract enables it.
+pub struct EventJournal;
+
+impl EventJournal {
+    pub fn set_capacity(env: &Env, capacity: u32) {
+        if capacity > MAX_JOURNAL_CAPACITY {
+            panic!("Journal capacity too large");
+        }
+        env.storage()
+            .instance()
+            .set(&JOURNAL_CAPACITY_KEY, &capacity);
+    }
+
+    pub fn capacity(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&JOURNAL_CAPACITY_KEY)
+            .unwrap_or(0)
+    }
+
+    fn record(env: &Env, envelope: &EventEnvelope) {
+        let capacity = Self::capacity(env);
+        if capacity == 0 {
+            return;
+        }
+        let slot = (envelope.sequence % capacity as u64) as u32;
+        env.storage()
+            .persistent()
+            .set(&(JOURNAL_KEY, slot), envelope);
+    }
+
+    // Newest first, skipping `offset` events. Stops at the first slot that has
+    // been overwritten or was written under a different capacity.
+    pub fn recent(env: &Env, offset: u32, limit: u32) -> Vec<EventEnvelope> {
+        let mut events = Vec::new(env);
+        let capacity = Self::capacity(env);
+        let latest = EventEmitter::current_sequence(env);
+
+        let limit = limit.min(MAX_JOURNAL_PAGE_SIZE);
+        for i in 0..limit {
+            let back = (offset + i) as u64;
+            if back >= capacity as u64 || back >= latest {
+                break;
+            }
+            let sequence = latest - back;
+            let slot = (sequence % capacity as u64) as u32;
+            match env
+                .storage()
+                .persistent()
+                .get::<_, EventEnvelope>(&(JOURNAL_KEY, slot))
+            {
+                Some(envelope) if envelope.sequence == sequence => events.push_back(envelope),
+                _ => break,
+            }
+        }
+        events
+    }
+}
+
 // Event query utilities for backends and explorers
 pub struct EventQuery;
 
@@ -384,4 +1996,97 @@ impl EventQuery {
     pub fn system_events_filter() -> Symbol {
         SYSTEM_TOPIC
     }
+    pub fn rate_lock_events_filter() -> Symbol {
+        RATE_LOCK_TOPIC
+    }
+    pub fn email_events_filter() -> Symbol {
+        EMAIL_TOPIC
+    }
+    pub fn timelock_events_filter() -> Symbol {
+        TIMELOCK_TOPIC
+    }
+    pub fn access_events_filter() -> Symbol {
+        ACCESS_TOPIC
+    }
+    pub fn oracle_events_filter() -> Symbol {
+        ORACLE_TOPIC
+    }
+    pub fn vesting_events_filter() -> Symbol {
+        VESTING_TOPIC
+    }
+    pub fn stream_events_filter() -> Symbol {
+        STREAM_TOPIC
+    }
+    pub fn savings_events_filter() -> Symbol {
+        SAVINGS_TOPIC
+    }
+    pub fn split_events_filter() -> Symbol {
+        SPLIT_TOPIC
+    }
+    pub fn billing_events_filter() -> Symbol {
+        BILLING_TOPIC
+    }
+    pub fn invoice_events_filter() -> Symbol {
+        INVOICE_TOPIC
+    }
+    pub fn payroll_events_filter() -> Symbol {
+        PAYROLL_TOPIC
+    }
+    pub fn lending_events_filter() -> Symbol {
+        LENDING_TOPIC
+    }
+    pub fn insurance_events_filter() -> Symbol {
+        INSURANCE_TOPIC
+    }
+    pub fn compliance_events_filter() -> Symbol {
+        COMPLIANCE_TOPIC
+    }
+    pub fn blocklist_events_filter() -> Symbol {
+        BLOCKLIST_TOPIC
+    }
+    pub fn guardian_events_filter() -> Symbol {
+        GUARDIAN_TOPIC
+    }
+    pub fn inheritance_events_filter() -> Symbol {
+        INHERITANCE_TOPIC
+    }
+    pub fn wallet_events_filter() -> Symbol {
+        WALLET_TOPIC
+    }
+    pub fn sponsorship_events_filter() -> Symbol {
+        SPONSORSHIP_TOPIC
+    }
+    pub fn auction_events_filter() -> Symbol {
+        AUCTION_TOPIC
+    }
+    pub fn otc_events_filter() -> Symbol {
+        OTC_TOPIC
+    }
+    pub fn remittance_events_filter() -> Symbol {
+        REMITTANCE_TOPIC
+    }
+    pub fn dca_events_filter() -> Symbol {
+        DCA_TOPIC
+    }
+    pub fn order_book_events_filter() -> Symbol {
+        ORDER_BOOK_TOPIC
+    }
+    pub fn bridge_events_filter() -> Symbol {
+        BRIDGE_TOPIC
+    }
+    pub fn loyalty_events_filter() -> Symbol {
+        LOYALTY_TOPIC
+    }
+    pub fn referral_events_filter() -> Symbol {
+        REFERRAL_TOPIC
+    }
+    pub fn p2p_events_filter() -> Symbol {
+        P2P_TOPIC
+    }
+    pub fn payment_request_events_filter() -> Symbol {
+        PAYMENT_REQUEST_TOPIC
+    }
+    pub fn receipt_events_filter() -> Symbol {
+        RECEIPT_TOPIC
+    }
 }
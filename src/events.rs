@@ -1,8 +1,11 @@
 use soroban_sdk::Env;
 
 use crate::conversion::ConversionEvent;
+use crate::reference::PaymentReference;
 
-/// Publishes a conversion event to the environment
+/// Publishes a conversion event to the environment, paired with the payment
+/// reference of the operation that emitted it
 pub fn publish(env: &Env, event: ConversionEvent) {
-    env.events().publish(("conversion",), event);
+    env.events()
+        .publish(("conversion",), (event, PaymentReference::current(env)));
 }
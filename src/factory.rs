@@ -0,0 +1,218 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::escrow::EscrowClient;
+use crate::event::{DeFiEvent, EscrowInstanceDeployedData, EventEmitter, ESCROW_TOPIC};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryConfig {
+    pub admin: Address,
+    /// Uploaded escrow code that new instances are deployed from
+    pub escrow_wasm_hash: BytesN<32>,
+}
+
+/// Settings forwarded to a newly deployed escrow before it is handed over
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowInitParams {
+    /// Admin (owner) of the new instance
+    pub admin: Address,
+    pub dispute_fee: i128,
+}
+
+/// Registry entry for an escrow instance deployed by the factory
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeployedEscrow {
+    pub address: Address,
+    /// Enterprise client the instance is dedicated to
+    pub client: Address,
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+    pub deployed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum FactoryDataKey {
+    Config,
+    DeploymentCount,
+    Deployment(Address),
+    ClientDeployments(Address),
+}
+
+#[contract]
+pub struct EscrowFactoryContract;
+
+/// Deploys a separate escrow instance per enterprise client so each client's
+/// escrows, pause state and fees are isolated from everyone else's.
+#[contractimpl]
+impl EscrowFactoryContract {
+    pub fn initialize_factory(
+        env: Env,
+        admin: Address,
+        escrow_wasm_hash: BytesN<32>,
+    ) -> FactoryConfig {
+        if env.storage().instance().has(&FactoryDataKey::Config) {
+            panic!("Factory already initialized");
+        }
+        admin.require_auth();
+
+        let config = FactoryConfig {
+            admin: admin.clone(),
+            escrow_wasm_hash,
+        };
+        env.storage()
+            .instance()
+            .set(&FactoryDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+        config
+    }
+
+    pub fn get_factory_config(env: Env) -> FactoryConfig {
+        env.storage()
+            .instance()
+            .get(&FactoryDataKey::Config)
+            .expect("Factory not initialized")
+    }
+
+    /// Points future deployments at new escrow code (owner only). Existing
+    /// instances keep their code until upgraded individually.
+    pub fn set_escrow_wasm_hash(env: Env, caller: Address, escrow_wasm_hash: BytesN<32>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_escrow_wasm_hash",
+            escrow_wasm_hash.clone(),
+        );
+
+        let mut config = Self::get_factory_config(env.clone());
+        config.escrow_wasm_hash = escrow_wasm_hash;
+        env.storage()
+            .instance()
+            .set(&FactoryDataKey::Config, &config);
+    }
+
+    /// Deploys and initializes an escrow instance for `client` (operator only)
+    pub fn deploy_escrow(
+        env: Env,
+        caller: Address,
+        client: Address,
+        params: EscrowInitParams,
+    ) -> Address {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "deploy_escrow",
+            (client.clone(), params.clone()),
+        );
+        if params.dispute_fee < 0 {
+            panic!("Dispute fee cannot be negative");
+        }
+
+        let config = Self::get_factory_config(env.clone());
+        let mut deployments = Self::get_client_deployments(env.clone(), client.clone());
+
+        // Salt is derived from the client and its deployment index, so addresses
+        // are predictable and never collide
+        let mut preimage = client.clone().to_xdr(&env);
+        preimage.extend_from_slice(&deployments.len().to_be_bytes());
+        let salt: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        let address = env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(config.escrow_wasm_hash.clone(), ());
+
+        // The factory administers the instance while applying settings, then
+        // hands it to the client's admin
+        let factory = env.current_contract_address();
+        let escrow = EscrowClient::new(&env, &address);
+        escrow.initialize(&factory);
+        if params.dispute_fee > 0 {
+            escrow.set_dispute_fee(&factory, &params.dispute_fee);
+        }
+        escrow.transfer_admin(&params.admin);
+
+        let deployed = DeployedEscrow {
+            address: address.clone(),
+            client: client.clone(),
+            admin: params.admin.clone(),
+            wasm_hash: config.escrow_wasm_hash.clone(),
+            deployed_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&FactoryDataKey::Deployment(address.clone()), &deployed);
+        deployments.push_back(address.clone());
+        env.storage().persistent().set(
+            &FactoryDataKey::ClientDeployments(client.clone()),
+            &deployments,
+        );
+        let count = Self::get_deployment_count(env.clone()) + 1;
+        env.storage()
+            .instance()
+            .set(&FactoryDataKey::DeploymentCount, &count);
+
+        let event = DeFiEvent::EscrowInstanceDeployed(EscrowInstanceDeployedData {
+            escrow: address.clone(),
+            client,
+            admin: params.admin,
+            wasm_hash: config.escrow_wasm_hash,
+            deployed_at: deployed.deployed_at,
+        });
+        EventEmitter::emit_event(&env, ESCROW_TOPIC, event);
+
+        address
+    }
+
+    pub fn get_deployment(env: Env, escrow: Address) -> Option<DeployedEscrow> {
+        env.storage()
+            .persistent()
+            .get(&FactoryDataKey::Deployment(escrow))
+    }
+
+    pub fn get_client_deployments(env: Env, client: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&FactoryDataKey::ClientDeployments(client))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_deployment_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&FactoryDataKey::DeploymentCount)
+            .unwrap_or(0)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_factory_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_factory_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_factory_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_factory_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_factory_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env, Error};
-
-const MAX_BPS: u32 = 10000; // Represents 100%
+use crate::audit_log::AdminAudit;
+use crate::bps::Bps;
+use crate::errors::AppError;
+use crate::snapshot::{ConfigSnapshots, SnapshotInfo};
+use crate::utils::checked_add;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -9,13 +12,6 @@ pub enum DataKey {
     TotalDistributed(Address), // Key for tracking total distributed amounts per token
 }
 
-const ERR_ALREADY_INITIALIZED: u32 = 1;
-const ERR_INVALID_BPS_CONFIG: u32 = 2;
-const ERR_NOT_INITIALIZED: u32 = 3;
-const ERR_INVALID_BPS: u32 = 4;
-const ERR_INVALID_FEE_AMOUNT: u32 = 5;
-const ERR_FEE_DISTRIBUTION_FAILED: u32 = 7;
-
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FeeDistributionConfig {
@@ -56,19 +52,14 @@ impl FeeSplitterContract {
         reward_pool_address: Address,
         treasury_bps: u32,
         reward_pool_bps: u32,
-    ) -> Result<(), Error> {
+    ) -> Result<(), AppError> {
         if env.storage().instance().has(&DataKey::Config) {
-            return Err(Error::from_contract_error(ERR_ALREADY_INITIALIZED));
+            return Err(AppError::AlreadyInitialized);
         }
 
         admin.require_auth();
 
-        if treasury_bps > MAX_BPS
-            || reward_pool_bps > MAX_BPS
-            || (treasury_bps + reward_pool_bps) > MAX_BPS
-        {
-            return Err(Error::from_contract_error(ERR_INVALID_BPS_CONFIG));
-        }
+        split(treasury_bps, reward_pool_bps).map_err(|_| AppError::InvalidBpsConfig)?;
 
         let config = FeeDistributionConfig {
             admin,
@@ -87,14 +78,25 @@ impl FeeSplitterContract {
         reward_pool_address: Option<Address>,
         treasury_bps: Option<u32>,
         reward_pool_bps: Option<u32>,
-    ) -> Result<FeeDistributionConfig, Error> {
+    ) -> Result<FeeDistributionConfig, AppError> {
         let mut config: FeeDistributionConfig = env
             .storage()
             .instance()
             .get(&DataKey::Config)
-            .ok_or_else(|| Error::from_contract_error(ERR_NOT_INITIALIZED))?;
+            .ok_or(AppError::NotInitialized)?;
 
         config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "update_fees_config",
+            (
+                treasury_address.clone(),
+                reward_pool_address.clone(),
+                treasury_bps,
+                reward_pool_bps,
+            ),
+        );
 
         if let Some(addr) = treasury_address {
             config.treasury_address = addr;
@@ -109,22 +111,59 @@ impl FeeSplitterContract {
             config.reward_pool_bps = bps;
         }
 
-        if config.treasury_bps > MAX_BPS
-            || config.reward_pool_bps > MAX_BPS
-            || (config.treasury_bps + config.reward_pool_bps) > MAX_BPS
-        {
-            return Err(Error::from_contract_error(ERR_INVALID_BPS));
-        }
+        split(config.treasury_bps, config.reward_pool_bps)?;
 
         env.storage().instance().set(&DataKey::Config, &config);
         Ok(config.clone())
     }
 
-    pub fn get_fees_config(env: Env) -> Result<FeeDistributionConfig, Error> {
+    pub fn get_fees_config(env: Env) -> Result<FeeDistributionConfig, AppError> {
         env.storage()
             .instance()
             .get(&DataKey::Config)
-            .ok_or_else(|| Error::from_contract_error(ERR_NOT_INITIALIZED))
+            .ok_or(AppError::NotInitialized)
+    }
+
+    /// Save the configuration in force as a new snapshot version (admin only)
+    pub fn snapshot_fees_config(env: Env) -> Result<u32, AppError> {
+        let config = Self::get_fees_config(env.clone())?;
+        config.admin.require_auth();
+        AdminAudit::record(&env, &config.admin, "snapshot_fees_config", ());
+        Ok(ConfigSnapshots::take(&env, &config, &config.admin))
+    }
+
+    /// Put a snapshot's destinations and shares back in force (admin only)
+    pub fn rollback_fees_config(env: Env, version: u32) -> Result<FeeDistributionConfig, AppError> {
+        let current = Self::get_fees_config(env.clone())?;
+        current.admin.require_auth();
+        AdminAudit::record(&env, &current.admin, "rollback_fees_config", version);
+
+        let snapshot: FeeDistributionConfig =
+            ConfigSnapshots::restore(&env, version, &current.admin).ok_or(AppError::NotFound)?;
+        let config = FeeDistributionConfig {
+            admin: current.admin,
+            ..snapshot
+        };
+        split(config.treasury_bps, config.reward_pool_bps)?;
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(config)
+    }
+
+    pub fn get_fees_config_snapshot(env: Env, version: u32) -> Option<SnapshotInfo> {
+        ConfigSnapshots::info(&env, version)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (admin only)
+    pub fn set_fees_audit_log(env: Env, audit_log: Option<Address>) -> Result<(), AppError> {
+        let config = Self::get_fees_config(env.clone())?;
+        config.admin.require_auth();
+        AdminAudit::record(&env, &config.admin, "set_fees_audit_log", audit_log.clone());
+        AdminAudit::set_log(&env, audit_log);
+        Ok(())
+    }
+
+    pub fn get_fees_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
     }
 
     /// Distributes collected fees to treasury and reward pools.
@@ -135,9 +174,9 @@ impl FeeSplitterContract {
         fee_token: Address,
         total_fee_amount: i128,
         fee_collector_contract: Address, // The contract that holds the fees and calls this function
-    ) -> Result<(), Error> {
+    ) -> Result<(), AppError> {
         if total_fee_amount <= 0 {
-            return Err(Error::from_contract_error(ERR_INVALID_FEE_AMOUNT));
+            return Err(AppError::InvalidAmount);
         }
 
         // Authenticate the caller (must be the contract that collected the fees)
@@ -148,19 +187,18 @@ impl FeeSplitterContract {
             .storage()
             .instance()
             .get(&DataKey::Config)
-            .ok_or_else(|| Error::from_contract_error(ERR_NOT_INITIALIZED))?;
+            .ok_or(AppError::NotInitialized)?;
 
         let token_client = token::Client::new(&env, &fee_token);
 
-        let treasury_amount =
-            (total_fee_amount * i128::from(config.treasury_bps)) / i128::from(MAX_BPS);
-        let reward_pool_amount =
-            (total_fee_amount * i128::from(config.reward_pool_bps)) / i128::from(MAX_BPS);
+        let (treasury_bps, reward_pool_bps) = split(config.treasury_bps, config.reward_pool_bps)?;
+        let treasury_amount = treasury_bps.of(total_fee_amount)?;
+        let reward_pool_amount = reward_pool_bps.of(total_fee_amount)?;
 
         // Ensure the sum of distributed amounts does not exceed the total fee.
         // Any dust/remainder from bps calculation will remain with the fee_collector_contract.
-        if treasury_amount + reward_pool_amount > total_fee_amount {
-            return Err(Error::from_contract_error(ERR_FEE_DISTRIBUTION_FAILED));
+        if checked_add(treasury_amount, reward_pool_amount)? > total_fee_amount {
+            return Err(AppError::FeeDistributionFailed);
         }
 
         if treasury_amount > 0 {
@@ -197,8 +235,8 @@ impl FeeSplitterContract {
         let key = DataKey::TotalDistributed(fee_token_clone);
         let mut totals: TokenDistributionTotals =
             env.storage().instance().get(&key).unwrap_or_default();
-        totals.to_treasury += treasury_amount;
-        totals.to_reward_pool += reward_pool_amount;
+        totals.to_treasury = checked_add(totals.to_treasury, treasury_amount)?;
+        totals.to_reward_pool = checked_add(totals.to_reward_pool, reward_pool_amount)?;
         env.storage().instance().set(&key, &totals);
 
         Ok(())
@@ -212,3 +250,11 @@ impl FeeSplitterContract {
             .unwrap_or_default()
     }
 }
+
+// The treasury and reward pool shares, which may not add up to more than 100%
+fn split(treasury_bps: u32, reward_pool_bps: u32) -> Result<(Bps, Bps), AppError> {
+    let treasury = Bps::new(treasury_bps)?;
+    let reward_pool = Bps::new(reward_pool_bps)?;
+    treasury.checked_add(reward_pool)?;
+    Ok((treasury, reward_pool))
+}
@@ -0,0 +1,145 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    BreakerResetData, BreakerTrippedData, DeFiEvent, EventEmitter, GuardianEvent, GUARDIAN_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol};
+
+/// Part of the platform a breaker halts
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Subsystem {
+    Escrow,
+    Swap,
+    Conversion,
+    Pools,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakerState {
+    pub tripped_by: Address,
+    pub reason: Symbol,
+    pub tripped_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum GuardianDataKey {
+    Admin,
+    Breaker(Subsystem),
+}
+
+#[contract]
+pub struct GuardianContract;
+
+/// Platform-wide circuit breakers. Monitors holding the pauser role can trip
+/// the breaker for one subsystem, which puts every contract of that subsystem
+/// pointing at this guardian into read-only safe mode until the owner
+/// (governance) resets it.
+#[contractimpl]
+impl GuardianContract {
+    pub fn initialize_guardian(env: Env, admin: Address) {
+        if env.storage().instance().has(&GuardianDataKey::Admin) {
+            panic!("Guardian already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&GuardianDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Halts `subsystem` (pauser only)
+    pub fn trip_breaker(env: Env, monitor: Address, subsystem: Subsystem, reason: Symbol) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Pauser,
+            &monitor,
+            "trip_breaker",
+            (subsystem, reason.clone()),
+        );
+        let key = GuardianDataKey::Breaker(subsystem);
+        if env.storage().instance().has(&key) {
+            panic!("Breaker already tripped");
+        }
+
+        let state = BreakerState {
+            tripped_by: monitor.clone(),
+            reason: reason.clone(),
+            tripped_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&key, &state);
+
+        let event = DeFiEvent::Guardian(GuardianEvent::Tripped(BreakerTrippedData {
+            subsystem,
+            monitor,
+            reason,
+        }));
+        EventEmitter::emit_event(&env, GUARDIAN_TOPIC, event);
+    }
+
+    /// Lifts the breaker on `subsystem` (owner only)
+    pub fn reset_breaker(env: Env, caller: Address, subsystem: Subsystem) {
+        AccessControl::require_audited_role(&env, Role::Owner, &caller, "reset_breaker", subsystem);
+        let key = GuardianDataKey::Breaker(subsystem);
+        if !env.storage().instance().has(&key) {
+            panic!("Breaker not tripped");
+        }
+        env.storage().instance().remove(&key);
+
+        let event = DeFiEvent::Guardian(GuardianEvent::Reset(BreakerResetData {
+            subsystem,
+            reset_by: caller,
+        }));
+        EventEmitter::emit_event(&env, GUARDIAN_TOPIC, event);
+    }
+
+    pub fn is_tripped(env: Env, subsystem: Subsystem) -> bool {
+        env.storage()
+            .instance()
+            .has(&GuardianDataKey::Breaker(subsystem))
+    }
+
+    pub fn get_breaker(env: Env, subsystem: Subsystem) -> Option<BreakerState> {
+        env.storage()
+            .instance()
+            .get(&GuardianDataKey::Breaker(subsystem))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_guardian_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_guardian_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_guardian_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_guardian_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_guardian_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+}
+
+// Enforcement hook for contracts holding a guardian address
+pub struct GuardianCheck;
+
+impl GuardianCheck {
+    /// Whether the guardian at `guardian` has halted `subsystem`
+    pub fn is_halted(env: &Env, guardian: &Address, subsystem: Subsystem) -> bool {
+        GuardianContractClient::new(env, guardian).is_tripped(&subsystem)
+    }
+}
@@ -0,0 +1,40 @@
+use crate::storage::DAY_IN_LEDGERS;
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, TryFromVal, Val};
+
+/// How long, in ledgers, a repeated key keeps returning the first result
+pub const IDEMPOTENCY_WINDOW: u32 = DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum IdempotencyDataKey {
+    Result(Address, BytesN<32>),
+}
+
+// Results of keyed operations, remembered in the calling contract's own
+// temporary storage so a backend retrying a submission gets the original
+// outcome back. Keys are scoped to the authorizing account.
+pub struct Idempotency;
+
+impl Idempotency {
+    /// Result of an earlier call by `account` with `key`, if still remembered
+    pub fn recall<V>(env: &Env, account: &Address, key: &BytesN<32>) -> Option<V>
+    where
+        V: TryFromVal<Env, Val>,
+    {
+        env.storage()
+            .temporary()
+            .get(&IdempotencyDataKey::Result(account.clone(), key.clone()))
+    }
+
+    /// Remembers `result` for `key` for the idempotency window
+    pub fn remember<V>(env: &Env, account: &Address, key: &BytesN<32>, result: &V)
+    where
+        V: IntoVal<Env, Val>,
+    {
+        let entry = IdempotencyDataKey::Result(account.clone(), key.clone());
+        env.storage().temporary().set(&entry, result);
+        env.storage()
+            .temporary()
+            .extend_ttl(&entry, IDEMPOTENCY_WINDOW, IDEMPOTENCY_WINDOW);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::errors::{fail, AppError};
+use crate::event::{
+    DeFiEvent, EventEmitter, InheritanceAnnouncedData, InheritanceClaimData, InheritanceEvent,
+    InheritancePlanSetData, INHERITANCE_TOPIC,
+};
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Time an owner has to show signs of life between a beneficiary announcing
+/// a claim and the claim going through
+pub const CLAIM_NOTICE_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// Opt-in dead-man switch: once `owner` has been inactive for
+/// `inactivity_period` seconds, `beneficiary` may announce a claim and take
+/// over the owner's holdings after `CLAIM_NOTICE_PERIOD`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InheritancePlan {
+    pub beneficiary: Address,
+    pub inactivity_period: u64,
+    pub last_active: u64,
+    /// When the pending claim was announced, 0 when there is none
+    pub announced_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InheritanceDataKey {
+    Plan(Address),
+}
+
+// Plans kept in the calling contract's own storage, so each contract only
+// hands over what it holds
+pub struct Inheritance;
+
+impl Inheritance {
+    /// Sets or clears `owner`'s plan; the caller checks the owner's auth
+    pub fn set_plan(
+        env: &Env,
+        owner: &Address,
+        beneficiary: Option<Address>,
+        inactivity_period: u64,
+    ) {
+        let key = InheritanceDataKey::Plan(owner.clone());
+        match beneficiary.clone() {
+            Some(beneficiary) => {
+                if beneficiary == *owner {
+                    fail(
+                        env,
+                        AppError::InvalidAddress,
+                        "Beneficiary cannot be the owner",
+                    );
+                }
+                if inactivity_period == 0 {
+                    fail(
+                        env,
+                        AppError::InvalidTimestamp,
+                        "Inactivity period must be non-zero",
+                    );
+                }
+                let plan = InheritancePlan {
+                    beneficiary,
+                    inactivity_period,
+                    last_active: env.ledger().timestamp(),
+                    announced_at: 0,
+                };
+                env.storage().persistent().set(&key, &plan);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        let event = DeFiEvent::Inheritance(InheritanceEvent::PlanSet(InheritancePlanSetData {
+            owner: owner.clone(),
+            beneficiary,
+            inactivity_period,
+        }));
+        EventEmitter::emit_event(env, INHERITANCE_TOPIC, event);
+    }
+
+    pub fn get_plan(env: &Env, owner: &Address) -> Option<InheritancePlan> {
+        env.storage()
+            .persistent()
+            .get(&InheritanceDataKey::Plan(owner.clone()))
+    }
+
+    /// Restarts `owner`'s inactivity clock and cancels any pending claim.
+    /// Call from every entrypoint the owner authorizes.
+    pub fn record_activity(env: &Env, owner: &Address) {
+        let Some(mut plan) = Self::get_plan(env, owner) else {
+            return;
+        };
+        if plan.announced_at != 0 {
+            let event = DeFiEvent::Inheritance(InheritanceEvent::Cancelled(InheritanceClaimData {
+                owner: owner.clone(),
+                beneficiary: plan.beneficiary.clone(),
+            }));
+            EventEmitter::emit_event(env, INHERITANCE_TOPIC, event);
+        }
+
+        plan.last_active = env.ledger().timestamp();
+        plan.announced_at = 0;
+        env.storage()
+            .persistent()
+            .set(&InheritanceDataKey::Plan(owner.clone()), &plan);
+    }
+
+    /// Starts the notice period on a claim against an inactive `owner`
+    pub fn announce_claim(env: &Env, owner: &Address, beneficiary: &Address) {
+        beneficiary.require_auth();
+        let mut plan = Self::beneficiary_plan(env, owner, beneficiary);
+        if plan.announced_at != 0 {
+            fail(env, AppError::InvalidState, "Claim already announced");
+        }
+        let now = env.ledger().timestamp();
+        if now < plan.last_active + plan.inactivity_period {
+            fail(env, AppError::InvalidState, "Owner is still active");
+        }
+
+        plan.announced_at = now;
+        env.storage()
+            .persistent()
+            .set(&InheritanceDataKey::Plan(owner.clone()), &plan);
+
+        let event = DeFiEvent::Inheritance(InheritanceEvent::Announced(InheritanceAnnouncedData {
+            owner: owner.clone(),
+            beneficiary: beneficiary.clone(),
+            claimable_at: now + CLAIM_NOTICE_PERIOD,
+        }));
+        EventEmitter::emit_event(env, INHERITANCE_TOPIC, event);
+    }
+
+    /// Completes an announced claim once the notice period has passed and
+    /// removes the plan; the caller then moves the owner's holdings
+    pub fn take_claim(env: &Env, owner: &Address, beneficiary: &Address) {
+        beneficiary.require_auth();
+        let plan = Self::beneficiary_plan(env, owner, beneficiary);
+        if plan.announced_at == 0 {
+            fail(env, AppError::InvalidState, "Claim not announced");
+        }
+        if env.ledger().timestamp() < plan.announced_at + CLAIM_NOTICE_PERIOD {
+            fail(env, AppError::InvalidState, "Notice period not elapsed");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&InheritanceDataKey::Plan(owner.clone()));
+
+        let event = DeFiEvent::Inheritance(InheritanceEvent::Claimed(InheritanceClaimData {
+            owner: owner.clone(),
+            beneficiary: beneficiary.clone(),
+        }));
+        EventEmitter::emit_event(env, INHERITANCE_TOPIC, event);
+    }
+
+    fn beneficiary_plan(env: &Env, owner: &Address, beneficiary: &Address) -> InheritancePlan {
+        let plan = Self::get_plan(env, owner)
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "No inheritance plan"));
+        if plan.beneficiary != *beneficiary {
+            fail(
+                env,
+                AppError::Unauthorized,
+                "Not the designated beneficiary",
+            );
+        }
+        plan
+    }
+}
@@ -0,0 +1,450 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, InsuranceClaimData, InsuranceEvent, InsuranceFundedData,
+    InsuranceStakeData, INSURANCE_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env};
+
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceConfig {
+    pub admin: Address,
+    pub token: Address,
+    pub epoch_duration: u64,
+    /// Most that approved claims may pay out in one epoch, as a share of the
+    /// pool value when the epoch's first claim was paid
+    pub max_epoch_payout_bps: u32,
+    /// Seconds between requesting an unstake and withdrawing it
+    pub unstake_delay: u64,
+    pub started_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    Pending,
+    Paid,
+    Rejected,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceClaim {
+    pub id: u64,
+    pub claimant: Address,
+    pub amount: i128,
+    /// Hash of the off-chain loss report
+    pub evidence_hash: BytesN<32>,
+    pub status: ClaimStatus,
+    pub submitted_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochPayouts {
+    /// Payout cap fixed by the epoch's first payout
+    pub cap: i128,
+    pub paid: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingUnstake {
+    pub shares: i128,
+    pub available_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InsuranceDataKey {
+    Config,
+    TotalShares,
+    Shares(Address),
+    PendingUnstake(Address),
+    ClaimCount,
+    Claim(u64),
+    EpochPayouts(u64),
+}
+
+#[contract]
+pub struct InsuranceContract;
+
+/// Backstop pool covering protocol losses. Stakers own the pool's token balance
+/// pro rata, so fees sent to the pool (via `fund_insurance` or by pointing a
+/// fee destination at this contract) accrue to them, and approved claims are
+/// paid out of it.
+#[contractimpl]
+impl InsuranceContract {
+    pub fn initialize_insurance(
+        env: Env,
+        admin: Address,
+        token: Address,
+        epoch_duration: u64,
+        max_epoch_payout_bps: u32,
+        unstake_delay: u64,
+    ) {
+        if env.storage().instance().has(&InsuranceDataKey::Config) {
+            panic!("Insurance already initialized");
+        }
+        admin.require_auth();
+        if epoch_duration == 0 || max_epoch_payout_bps as i128 > BASIS_POINTS_DIVISOR {
+            panic!("Invalid insurance parameters");
+        }
+
+        let config = InsuranceConfig {
+            admin: admin.clone(),
+            token,
+            epoch_duration,
+            max_epoch_payout_bps,
+            unstake_delay,
+            started_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    pub fn get_insurance_config(env: Env) -> InsuranceConfig {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::Config)
+            .expect("Insurance not initialized")
+    }
+
+    /// Tokens held by the pool
+    pub fn get_pool_value(env: Env) -> i128 {
+        let config = Self::get_insurance_config(env.clone());
+        token::Client::new(&env, &config.token).balance(&env.current_contract_address())
+    }
+
+    /// Adds protocol fees to the pool without minting shares
+    pub fn fund_insurance(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let config = Self::get_insurance_config(env.clone());
+        token::Client::new(&env, &config.token).transfer(
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let event =
+            DeFiEvent::Insurance(InsuranceEvent::Funded(InsuranceFundedData { from, amount }));
+        EventEmitter::emit_event(&env, INSURANCE_TOPIC, event);
+    }
+
+    /// Stakes backstop capital, returning the shares minted
+    pub fn stake_backstop(env: Env, staker: Address, amount: i128) -> i128 {
+        staker.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let pool_value = Self::get_pool_value(env.clone());
+        let total_shares = Self::get_total_shares(env.clone());
+        let shares = if total_shares == 0 || pool_value == 0 {
+            amount
+        } else {
+            amount * total_shares / pool_value
+        };
+        if shares <= 0 {
+            panic!("Stake too small");
+        }
+
+        let config = Self::get_insurance_config(env.clone());
+        token::Client::new(&env, &config.token).transfer(
+            &staker,
+            &env.current_contract_address(),
+            &amount,
+        );
+        Self::set_shares(
+            &env,
+            &staker,
+            Self::get_staker_shares(env.clone(), staker.clone()) + shares,
+        );
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::TotalShares, &(total_shares + shares));
+
+        let event = DeFiEvent::Insurance(InsuranceEvent::Staked(InsuranceStakeData {
+            staker,
+            amount,
+            shares,
+        }));
+        EventEmitter::emit_event(&env, INSURANCE_TOPIC, event);
+
+        shares
+    }
+
+    /// Starts the unstake cooldown for `shares`. They keep covering claims
+    /// until withdrawn, so stakers cannot exit ahead of a pending claim.
+    pub fn request_unstake(env: Env, staker: Address, shares: i128) {
+        staker.require_auth();
+        if shares <= 0 || shares > Self::get_staker_shares(env.clone(), staker.clone()) {
+            panic!("Invalid share amount");
+        }
+
+        let config = Self::get_insurance_config(env.clone());
+        let pending = PendingUnstake {
+            shares,
+            available_at: env.ledger().timestamp() + config.unstake_delay,
+        };
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::PendingUnstake(staker), &pending);
+    }
+
+    /// Burns the requested shares once the cooldown has passed and pays out
+    /// their current value
+    pub fn withdraw_unstaked(env: Env, staker: Address) -> i128 {
+        staker.require_auth();
+        let key = InsuranceDataKey::PendingUnstake(staker.clone());
+        let pending: PendingUnstake = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("No unstake requested");
+        if env.ledger().timestamp() < pending.available_at {
+            panic!("Unstake cooldown not finished");
+        }
+
+        let staker_shares = Self::get_staker_shares(env.clone(), staker.clone());
+        let shares = pending.shares.min(staker_shares);
+        let total_shares = Self::get_total_shares(env.clone());
+        let amount = shares * Self::get_pool_value(env.clone()) / total_shares;
+
+        env.storage().persistent().remove(&key);
+        Self::set_shares(&env, &staker, staker_shares - shares);
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::TotalShares, &(total_shares - shares));
+        let config = Self::get_insurance_config(env.clone());
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &staker,
+            &amount,
+        );
+
+        let event = DeFiEvent::Insurance(InsuranceEvent::Unstaked(InsuranceStakeData {
+            staker,
+            amount,
+            shares,
+        }));
+        EventEmitter::emit_event(&env, INSURANCE_TOPIC, event);
+
+        amount
+    }
+
+    pub fn get_staker_shares(env: Env, staker: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::Shares(staker))
+            .unwrap_or(0)
+    }
+
+    pub fn get_total_shares(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&InsuranceDataKey::TotalShares)
+            .unwrap_or(0)
+    }
+
+    /// Files a claim for a covered loss, to be approved or rejected by the owner
+    pub fn submit_claim(
+        env: Env,
+        claimant: Address,
+        amount: i128,
+        evidence_hash: BytesN<32>,
+    ) -> u64 {
+        claimant.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&InsuranceDataKey::ClaimCount)
+            .unwrap_or(0)
+            + 1;
+        let claim = InsuranceClaim {
+            id,
+            claimant,
+            amount,
+            evidence_hash,
+            status: ClaimStatus::Pending,
+            submitted_at: env.ledger().timestamp(),
+        };
+        Self::save_claim(&env, &claim);
+        env.storage()
+            .instance()
+            .set(&InsuranceDataKey::ClaimCount, &id);
+        Self::emit_claim(&env, InsuranceEvent::ClaimSubmitted, &claim);
+
+        id
+    }
+
+    /// Pays an approved claim, within the current epoch's payout cap (owner only)
+    pub fn approve_claim(env: Env, caller: Address, claim_id: u64) {
+        AccessControl::require_audited_role(&env, Role::Owner, &caller, "approve_claim", claim_id);
+        let mut claim = Self::pending_claim(&env, claim_id);
+        let config = Self::get_insurance_config(env.clone());
+
+        let epoch = Self::get_current_epoch(env.clone());
+        let mut payouts = env
+            .storage()
+            .persistent()
+            .get(&InsuranceDataKey::EpochPayouts(epoch))
+            .unwrap_or_else(|| EpochPayouts {
+                cap: Self::get_pool_value(env.clone()) * config.max_epoch_payout_bps as i128
+                    / BASIS_POINTS_DIVISOR,
+                paid: 0,
+            });
+        if payouts.paid + claim.amount > payouts.cap {
+            panic!("Epoch payout cap exceeded");
+        }
+        payouts.paid += claim.amount;
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::EpochPayouts(epoch), &payouts);
+
+        claim.status = ClaimStatus::Paid;
+        Self::save_claim(&env, &claim);
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &claim.claimant,
+            &claim.amount,
+        );
+        Self::emit_claim(&env, InsuranceEvent::ClaimPaid, &claim);
+    }
+
+    /// Rejects a pending claim (owner only)
+    pub fn reject_claim(env: Env, caller: Address, claim_id: u64) {
+        AccessControl::require_audited_role(&env, Role::Owner, &caller, "reject_claim", claim_id);
+        let mut claim = Self::pending_claim(&env, claim_id);
+        claim.status = ClaimStatus::Rejected;
+        Self::save_claim(&env, &claim);
+        Self::emit_claim(&env, InsuranceEvent::ClaimRejected, &claim);
+    }
+
+    pub fn get_claim(env: Env, claim_id: u64) -> InsuranceClaim {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::Claim(claim_id))
+            .expect("Claim not found")
+    }
+
+    pub fn get_current_epoch(env: Env) -> u64 {
+        let config = Self::get_insurance_config(env.clone());
+        (env.ledger().timestamp() - config.started_at) / config.epoch_duration
+    }
+
+    pub fn get_epoch_payouts(env: Env, epoch: u64) -> Option<EpochPayouts> {
+        env.storage()
+            .persistent()
+            .get(&InsuranceDataKey::EpochPayouts(epoch))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_insurance_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_insurance_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_insurance_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_insurance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_insurance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn pending_claim(env: &Env, claim_id: u64) -> InsuranceClaim {
+        let claim = Self::get_claim(env.clone(), claim_id);
+        if claim.status != ClaimStatus::Pending {
+            panic!("Claim already decided");
+        }
+        claim
+    }
+
+    fn set_shares(env: &Env, staker: &Address, shares: i128) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::Shares(staker.clone()), &shares);
+    }
+
+    fn save_claim(env: &Env, claim: &InsuranceClaim) {
+        env.storage()
+            .persistent()
+            .set(&InsuranceDataKey::Claim(claim.id), claim);
+    }
+
+    fn emit_claim(
+        env: &Env,
+        variant: fn(InsuranceClaimData) -> InsuranceEvent,
+        claim: &InsuranceClaim,
+    ) {
+        let event = DeFiEvent::Insurance(variant(InsuranceClaimData {
+            claim_id: claim.id,
+            claimant: claim.claimant.clone(),
+            amount: claim.amount,
+        }));
+        EventEmitter::emit_event(env, INSURANCE_TOPIC, event);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_insurance_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_insurance_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_insurance_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_insurance_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// Stakers own the pool token's whole balance, so it is never stray
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let config = Self::get_insurance_config(env.clone());
+        if config.token == *token {
+            None
+        } else {
+            Some(0)
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::escrow::{EscrowClient, EscrowStatus};
+use crate::event::{
+    DeFiEvent, EventEmitter, InvoiceEvent, InvoiceIssuedData, InvoicePaidData,
+    InvoiceStatusChangedData, INVOICE_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, Vec};
+
+const MAX_PAGE_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceStatus {
+    Issued,
+    Paid,
+    /// Issued and past its due date; still payable
+    Overdue,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub due_date: u64,
+    /// Hash of the off-chain invoice details
+    pub memo_hash: BytesN<32>,
+    pub status: InvoiceStatus,
+    pub issued_at: u64,
+    pub paid_at: Option<u64>,
+    /// `EscrowContract` holding the payment, when settled through escrow
+    pub escrow_contract: Option<Address>,
+    pub escrow_id: Option<Symbol>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InvoiceDataKey {
+    InvoiceCount,
+    Invoice(u64),
+    MerchantInvoices(Address),
+}
+
+#[contract]
+pub struct InvoiceContract;
+
+#[contractimpl]
+impl InvoiceContract {
+    pub fn issue_invoice(
+        env: Env,
+        merchant: Address,
+        payer: Address,
+        token: Address,
+        amount: i128,
+        due_date: u64,
+        memo_hash: BytesN<32>,
+    ) -> u64 {
+        merchant.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if due_date <= env.ledger().timestamp() {
+            panic!("Due date must be in the future");
+        }
+        if merchant == payer {
+            panic!("Merchant cannot invoice itself");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&InvoiceDataKey::InvoiceCount)
+            .unwrap_or(0)
+            + 1;
+        let invoice = Invoice {
+            id,
+            merchant: merchant.clone(),
+            payer: payer.clone(),
+            token,
+            amount,
+            due_date,
+            memo_hash,
+            status: InvoiceStatus::Issued,
+            issued_at: env.ledger().timestamp(),
+            paid_at: None,
+            escrow_contract: None,
+            escrow_id: None,
+        };
+        Self::save_invoice(&env, &invoice);
+        env.storage()
+            .instance()
+            .set(&InvoiceDataKey::InvoiceCount, &id);
+
+        let mut ids = Self::merchant_invoice_ids(&env, &merchant);
+        ids.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&InvoiceDataKey::MerchantInvoices(merchant.clone()), &ids);
+
+        let event = DeFiEvent::Invoice(InvoiceEvent::Issued(InvoiceIssuedData {
+            invoice_id: id,
+            merchant,
+            payer,
+            token: invoice.token,
+            amount,
+            due_date,
+        }));
+        EventEmitter::emit_event(&env, INVOICE_TOPIC, event);
+
+        id
+    }
+
+    /// Pays the invoice straight to the merchant (payer only)
+    pub fn pay_invoice(env: Env, invoice_id: u64) {
+        let mut invoice = Self::load_payable(&env, invoice_id);
+        invoice.payer.require_auth();
+
+        token::Client::new(&env, &invoice.token).transfer(
+            &invoice.payer,
+            &invoice.merchant,
+            &invoice.amount,
+        );
+        Self::mark_paid(&env, &mut invoice);
+    }
+
+    /// Pays the invoice into a new escrow on `escrow_contract` with the merchant
+    /// as recipient (payer only). The invoice counts as paid while the escrow
+    /// holds the funds; see `sync_invoice_escrow` for refunds.
+    pub fn pay_invoice_via_escrow(
+        env: Env,
+        invoice_id: u64,
+        escrow_contract: Address,
+        timeout_duration: u64,
+        dispute_period: u64,
+    ) -> Symbol {
+        let mut invoice = Self::load_payable(&env, invoice_id);
+        invoice.payer.require_auth();
+
+        let escrow = EscrowClient::new(&env, &escrow_contract).create(
+            &invoice.payer,
+            &invoice.merchant,
+            &invoice.token,
+            &invoice.amount,
+            &timeout_duration,
+            &dispute_period,
+        );
+        invoice.escrow_contract = Some(escrow_contract);
+        invoice.escrow_id = Some(escrow.id.clone());
+        Self::mark_paid(&env, &mut invoice);
+
+        escrow.id
+    }
+
+    /// Reopens an escrow-paid invoice whose escrow went back to the payer.
+    /// Callable by anyone; returns the invoice's status afterwards.
+    pub fn sync_invoice_escrow(env: Env, invoice_id: u64) -> InvoiceStatus {
+        let mut invoice = Self::get_invoice(env.clone(), invoice_id);
+        let (escrow_contract, escrow_id) = match (
+            &invoice.status,
+            &invoice.escrow_contract,
+            &invoice.escrow_id,
+        ) {
+            (InvoiceStatus::Paid, Some(contract), Some(id)) => (contract.clone(), id.clone()),
+            _ => panic!("Invoice is not held in escrow"),
+        };
+
+        let status = EscrowClient::new(&env, &escrow_contract)
+            .get_escrow(&escrow_id)
+            .status;
+        if status == EscrowStatus::Refunded || status == EscrowStatus::DisputeResolvedForSender {
+            invoice.status = InvoiceStatus::Issued;
+            invoice.paid_at = None;
+            invoice.escrow_contract = None;
+            invoice.escrow_id = None;
+            Self::save_invoice(&env, &invoice);
+            Self::emit_status_changed(&env, &invoice);
+        }
+
+        Self::effective_status(&env, &invoice)
+    }
+
+    /// Cancels an unpaid invoice (merchant only)
+    pub fn cancel_invoice(env: Env, invoice_id: u64) {
+        let mut invoice = Self::load_payable(&env, invoice_id);
+        invoice.merchant.require_auth();
+
+        invoice.status = InvoiceStatus::Cancelled;
+        Self::save_invoice(&env, &invoice);
+        Self::emit_status_changed(&env, &invoice);
+    }
+
+    /// The invoice, reported as `Overdue` once an unpaid invoice passes its due date
+    pub fn get_invoice(env: Env, invoice_id: u64) -> Invoice {
+        let mut invoice: Invoice = env
+            .storage()
+            .persistent()
+            .get(&InvoiceDataKey::Invoice(invoice_id))
+            .expect("Invoice not found");
+        invoice.status = Self::effective_status(&env, &invoice);
+        invoice
+    }
+
+    pub fn get_invoice_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&InvoiceDataKey::InvoiceCount)
+            .unwrap_or(0)
+    }
+
+    /// A merchant's invoices in issue order, `limit` capped at 50
+    pub fn get_merchant_invoices(
+        env: Env,
+        merchant: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Invoice> {
+        let ids = Self::merchant_invoice_ids(&env, &merchant);
+        let mut invoices = Vec::new(&env);
+        let mut index = offset;
+        while index < ids.len() && invoices.len() < limit.min(MAX_PAGE_SIZE) {
+            invoices.push_back(Self::get_invoice(env.clone(), ids.get_unchecked(index)));
+            index += 1;
+        }
+        invoices
+    }
+
+    fn load_payable(env: &Env, invoice_id: u64) -> Invoice {
+        let invoice = Self::get_invoice(env.clone(), invoice_id);
+        match invoice.status {
+            InvoiceStatus::Issued | InvoiceStatus::Overdue => invoice,
+            InvoiceStatus::Paid => panic!("Invoice already paid"),
+            InvoiceStatus::Cancelled => panic!("Invoice is cancelled"),
+        }
+    }
+
+    fn mark_paid(env: &Env, invoice: &mut Invoice) {
+        invoice.status = InvoiceStatus::Paid;
+        invoice.paid_at = Some(env.ledger().timestamp());
+        Self::save_invoice(env, invoice);
+
+        let event = DeFiEvent::Invoice(InvoiceEvent::Paid(InvoicePaidData {
+            invoice_id: invoice.id,
+            payer: invoice.payer.clone(),
+            amount: invoice.amount,
+            escrow_id: invoice.escrow_id.clone(),
+            paid_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(env, INVOICE_TOPIC, event);
+    }
+
+    fn effective_status(env: &Env, invoice: &Invoice) -> InvoiceStatus {
+        if invoice.status == InvoiceStatus::Issued && env.ledger().timestamp() > invoice.due_date {
+            InvoiceStatus::Overdue
+        } else {
+            invoice.status.clone()
+        }
+    }
+
+    fn merchant_invoice_ids(env: &Env, merchant: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&InvoiceDataKey::MerchantInvoices(merchant.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    // Overdue is derived on read, so only Issued/Paid/Cancelled are ever stored
+    fn save_invoice(env: &Env, invoice: &Invoice) {
+        let mut stored = invoice.clone();
+        if stored.status == InvoiceStatus::Overdue {
+            stored.status = InvoiceStatus::Issued;
+        }
+        env.storage()
+            .persistent()
+            .set(&InvoiceDataKey::Invoice(invoice.id), &stored);
+    }
+
+    fn emit_status_changed(env: &Env, invoice: &Invoice) {
+        let data = InvoiceStatusChangedData {
+            invoice_id: invoice.id,
+            changed_at: env.ledger().timestamp(),
+        };
+        let event = if invoice.status == InvoiceStatus::Cancelled {
+            DeFiEvent::Invoice(InvoiceEvent::Cancelled(data))
+        } else {
+            DeFiEvent::Invoice(InvoiceEvent::Reopened(data))
+        };
+        EventEmitter::emit_event(env, INVOICE_TOPIC, event);
+    }
+}
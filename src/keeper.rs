@@ -0,0 +1,61 @@
+use crate::utils::checked_mul_div;
+use soroban_sdk::{contracttype, Env};
+
+/// Highest tip a contract may configure: 1% of the amount a call moves
+pub const MAX_KEEPER_TIP_BPS: u32 = 100;
+
+/// Bounty paid to whoever runs a permissionless maintenance call, such as an
+/// escrow timeout or a due subscription charge. It is taken from the funds
+/// the call moves: `tip_bps` of the amount, capped at `max_tip`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperTip {
+    pub tip_bps: u32,
+    pub max_tip: i128,
+}
+
+impl KeeperTip {
+    pub fn validate(&self) {
+        if self.tip_bps > MAX_KEEPER_TIP_BPS || self.max_tip < 0 {
+            panic!("Invalid keeper tip");
+        }
+    }
+
+    /// Tip owed on a call moving `amount`
+    pub fn on(&self, amount: i128) -> i128 {
+        checked_mul_div(amount, i128::from(self.tip_bps), 10_000)
+            .unwrap_or(0)
+            .clamp(0, self.max_tip)
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum KeeperDataKey {
+    Tip,
+}
+
+// Contract-wide tip settings kept in the calling contract's own storage
+pub struct KeeperTips;
+
+impl KeeperTips {
+    /// Sets or clears the tip paid on every maintenance call
+    pub fn set_tip(env: &Env, tip: Option<KeeperTip>) {
+        match tip {
+            Some(tip) => {
+                tip.validate();
+                env.storage().instance().set(&KeeperDataKey::Tip, &tip)
+            }
+            None => env.storage().instance().remove(&KeeperDataKey::Tip),
+        }
+    }
+
+    pub fn get_tip(env: &Env) -> Option<KeeperTip> {
+        env.storage().instance().get(&KeeperDataKey::Tip)
+    }
+
+    /// Tip owed on a call moving `amount`; zero when no tip is configured
+    pub fn tip_on(env: &Env, amount: i128) -> i128 {
+        Self::get_tip(env).map_or(0, |tip| tip.on(amount))
+    }
+}
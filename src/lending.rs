@@ -0,0 +1,636 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::Currency;
+use crate::event::{
+    DeFiEvent, EventEmitter, LendingActionData, LendingEvent, LiquidationData, LENDING_TOPIC,
+};
+use crate::oracle::OracleContractClient;
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+const PRICE_PRECISION: i128 = 100_000_000;
+const INDEX_PRECISION: i128 = 1_000_000_000;
+const SECONDS_PER_YEAR: i128 = 31_536_000;
+/// Share of a borrower's debt a single liquidation may repay
+const CLOSE_FACTOR_BPS: i128 = 5000;
+/// Health factor of 1.0, the threshold below which positions can be liquidated
+pub const HEALTH_FACTOR_ONE: i128 = PRICE_PRECISION;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LendingConfig {
+    pub admin: Address,
+    /// Oracle pricing every market in `quote_currency`
+    pub oracle: Address,
+    pub quote_currency: Currency,
+    /// Extra collateral paid to liquidators on top of the debt they repay
+    pub liquidation_bonus_bps: u32,
+}
+
+/// Risk parameters of a market, set when it is listed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketParams {
+    pub token: Address,
+    /// Share of supplied value that can be borrowed against (LTV)
+    pub collateral_factor_bps: u32,
+    /// Share of supplied value at which a position becomes liquidatable
+    pub liquidation_threshold_bps: u32,
+    /// Annual simple interest charged on borrows
+    pub interest_rate_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Market {
+    pub currency: Currency,
+    pub params: MarketParams,
+    /// Tokens held and available to borrow or withdraw
+    pub cash: i128,
+    /// Outstanding debt including accrued interest
+    pub total_borrows: i128,
+    /// Supply shares; each is worth `(cash + total_borrows) / total_shares`
+    pub total_shares: i128,
+    /// Grows with accrued interest, scaled by 10^9
+    pub borrow_index: i128,
+    pub last_accrual: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserPosition {
+    pub shares: i128,
+    pub borrow_principal: i128,
+    /// Market borrow index when `borrow_principal` was last updated
+    pub borrow_index: i128,
+}
+
+/// Account totals valued in the quote currency
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountLiquidity {
+    pub collateral_value: i128,
+    /// Most the account may owe, from collateral factors
+    pub borrow_limit: i128,
+    /// Debt level at which the account becomes liquidatable
+    pub liquidation_value: i128,
+    pub debt_value: i128,
+    /// `liquidation_value / debt_value` scaled by 10^8; `i128::MAX` without debt
+    pub health_factor: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum LendingDataKey {
+    Config,
+    Markets,
+    Market(Currency),
+    Position(Address, Currency),
+    UserMarkets(Address),
+}
+
+#[contract]
+pub struct LendingContract;
+
+/// Over-collateralized lending: supplied tokens earn borrow interest and back
+/// loans in other markets; keepers liquidate accounts whose health factor
+/// drops below 1.
+#[contractimpl]
+impl LendingContract {
+    pub fn initialize_lending(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        quote_currency: Currency,
+        liquidation_bonus_bps: u32,
+    ) {
+        if env.storage().instance().has(&LendingDataKey::Config) {
+            panic!("Lending already initialized");
+        }
+        admin.require_auth();
+        if liquidation_bonus_bps as i128 > BASIS_POINTS_DIVISOR {
+            panic!("Invalid liquidation bonus");
+        }
+
+        let config = LendingConfig {
+            admin: admin.clone(),
+            oracle,
+            quote_currency,
+            liquidation_bonus_bps,
+        };
+        env.storage()
+            .instance()
+            .set(&LendingDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    pub fn get_lending_config(env: Env) -> LendingConfig {
+        env.storage()
+            .instance()
+            .get(&LendingDataKey::Config)
+            .expect("Lending not initialized")
+    }
+
+    /// Lists a market, or updates the risk parameters of a listed one (owner only)
+    pub fn list_market(env: Env, caller: Address, currency: Currency, params: MarketParams) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "list_market",
+            (currency.clone(), params.clone()),
+        );
+        if params.collateral_factor_bps > params.liquidation_threshold_bps
+            || params.liquidation_threshold_bps as i128 > BASIS_POINTS_DIVISOR
+        {
+            panic!("Invalid market parameters");
+        }
+
+        let market = match Self::load_market(&env, &currency) {
+            Some(mut market) => {
+                if market.params.token != params.token {
+                    panic!("Market token cannot change");
+                }
+                Self::accrue(&env, &mut market);
+                market.params = params;
+                market
+            }
+            None => {
+                let mut markets = Self::get_markets(env.clone());
+                markets.push_back(currency.clone());
+                env.storage()
+                    .instance()
+                    .set(&LendingDataKey::Markets, &markets);
+                Market {
+                    currency: currency.clone(),
+                    params,
+                    cash: 0,
+                    total_borrows: 0,
+                    total_shares: 0,
+                    borrow_index: INDEX_PRECISION,
+                    last_accrual: env.ledger().timestamp(),
+                }
+            }
+        };
+        Self::save_market(&env, &market);
+    }
+
+    pub fn get_markets(env: Env) -> Vec<Currency> {
+        env.storage()
+            .instance()
+            .get(&LendingDataKey::Markets)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// The market with interest accrued up to now
+    pub fn get_market(env: Env, currency: Currency) -> Market {
+        let mut market = Self::market(&env, &currency);
+        Self::accrue(&env, &mut market);
+        market
+    }
+
+    /// Supplies tokens that earn interest and count as collateral
+    pub fn supply_asset(env: Env, user: Address, currency: Currency, amount: i128) {
+        user.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let mut market = Self::get_market(env.clone(), currency.clone());
+        token::Client::new(&env, &market.params.token).transfer(
+            &user,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let supply_value = market.cash + market.total_borrows;
+        let shares = if market.total_shares == 0 || supply_value == 0 {
+            amount
+        } else {
+            amount * market.total_shares / supply_value
+        };
+        market.cash += amount;
+        market.total_shares += shares;
+        Self::save_market(&env, &market);
+
+        let mut position = Self::position(&env, &user, &currency);
+        position.shares += shares;
+        Self::save_position(&env, &user, &currency, &position);
+        Self::emit_action(&env, LendingEvent::Supplied, &user, currency, amount);
+    }
+
+    /// Withdraws supplied tokens as long as the account stays within its borrow limit
+    pub fn withdraw_asset(env: Env, user: Address, currency: Currency, amount: i128) {
+        user.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let mut market = Self::get_market(env.clone(), currency.clone());
+        if amount > market.cash {
+            panic!("Insufficient market liquidity");
+        }
+
+        // Round the burned shares up so withdrawals never take more than their value
+        let supply_value = market.cash + market.total_borrows;
+        let shares = (amount * market.total_shares + supply_value - 1) / supply_value;
+        let mut position = Self::position(&env, &user, &currency);
+        if shares > position.shares {
+            panic!("Insufficient supplied balance");
+        }
+        market.cash -= amount;
+        market.total_shares -= shares;
+        Self::save_market(&env, &market);
+        position.shares -= shares;
+        Self::save_position(&env, &user, &currency, &position);
+
+        let liquidity = Self::get_account_liquidity(env.clone(), user.clone());
+        if liquidity.debt_value > liquidity.borrow_limit {
+            panic!("Withdrawal exceeds borrow limit");
+        }
+
+        token::Client::new(&env, &market.params.token).transfer(
+            &env.current_contract_address(),
+            &user,
+            &amount,
+        );
+        Self::emit_action(&env, LendingEvent::Withdrawn, &user, currency, amount);
+    }
+
+    /// Borrows against the account's collateral up to its borrow limit
+    pub fn borrow_asset(env: Env, user: Address, currency: Currency, amount: i128) {
+        user.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let mut market = Self::get_market(env.clone(), currency.clone());
+        if amount > market.cash {
+            panic!("Insufficient market liquidity");
+        }
+
+        let mut position = Self::position(&env, &user, &currency);
+        position.borrow_principal = Self::debt(&market, &position) + amount;
+        position.borrow_index = market.borrow_index;
+        Self::save_position(&env, &user, &currency, &position);
+        market.cash -= amount;
+        market.total_borrows += amount;
+        Self::save_market(&env, &market);
+
+        let liquidity = Self::get_account_liquidity(env.clone(), user.clone());
+        if liquidity.debt_value > liquidity.borrow_limit {
+            panic!("Borrow exceeds borrow limit");
+        }
+
+        token::Client::new(&env, &market.params.token).transfer(
+            &env.current_contract_address(),
+            &user,
+            &amount,
+        );
+        Self::emit_action(&env, LendingEvent::Borrowed, &user, currency, amount);
+    }
+
+    /// Repays up to `amount` of `borrower`'s debt from `payer`, returning the amount repaid
+    pub fn repay_asset(
+        env: Env,
+        payer: Address,
+        borrower: Address,
+        currency: Currency,
+        amount: i128,
+    ) -> i128 {
+        payer.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let repaid = Self::repay(&env, &payer, &borrower, &currency, amount);
+        Self::emit_action(&env, LendingEvent::Repaid, &borrower, currency, repaid);
+        repaid
+    }
+
+    /// Repays part of an unhealthy account's debt and takes its collateral
+    /// shares, worth the repaid value plus the liquidation bonus (operator only).
+    /// Returns the collateral amount seized.
+    pub fn liquidate_position(
+        env: Env,
+        keeper: Address,
+        borrower: Address,
+        debt_currency: Currency,
+        collateral_currency: Currency,
+        amount: i128,
+    ) -> i128 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &keeper,
+            "liquidate_position",
+            (
+                borrower.clone(),
+                debt_currency.clone(),
+                collateral_currency.clone(),
+                amount,
+            ),
+        );
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if Self::get_health_factor(env.clone(), borrower.clone()) >= HEALTH_FACTOR_ONE {
+            panic!("Position is healthy");
+        }
+
+        let debt_market = Self::get_market(env.clone(), debt_currency.clone());
+        let debt = Self::debt(
+            &debt_market,
+            &Self::position(&env, &borrower, &debt_currency),
+        );
+        let max_repay = debt * CLOSE_FACTOR_BPS / BASIS_POINTS_DIVISOR;
+        let repaid = Self::repay(
+            &env,
+            &keeper,
+            &borrower,
+            &debt_currency,
+            amount.min(max_repay),
+        );
+
+        let config = Self::get_lending_config(env.clone());
+        let seize_value = Self::value(&env, &debt_currency, repaid)
+            * (BASIS_POINTS_DIVISOR + config.liquidation_bonus_bps as i128)
+            / BASIS_POINTS_DIVISOR;
+        let mut seized = seize_value * PRICE_PRECISION / Self::price(&env, &collateral_currency);
+
+        let market = Self::get_market(env.clone(), collateral_currency.clone());
+        let supply_value = market.cash + market.total_borrows;
+        let mut collateral = Self::position(&env, &borrower, &collateral_currency);
+        let mut shares = seized * market.total_shares / supply_value;
+        if shares > collateral.shares {
+            shares = collateral.shares;
+            seized = shares * supply_value / market.total_shares;
+        }
+        collateral.shares -= shares;
+        Self::save_position(&env, &borrower, &collateral_currency, &collateral);
+        let mut reward = Self::position(&env, &keeper, &collateral_currency);
+        reward.shares += shares;
+        Self::save_position(&env, &keeper, &collateral_currency, &reward);
+
+        let event = DeFiEvent::Lending(LendingEvent::Liquidated(LiquidationData {
+            borrower,
+            liquidator: keeper,
+            debt_currency,
+            repaid,
+            collateral_currency,
+            seized,
+        }));
+        EventEmitter::emit_event(&env, LENDING_TOPIC, event);
+
+        seized
+    }
+
+    /// Tokens the user could withdraw from a market, including earned interest
+    pub fn get_supplied_balance(env: Env, user: Address, currency: Currency) -> i128 {
+        let market = Self::get_market(env.clone(), currency.clone());
+        let position = Self::position(&env, &user, &currency);
+        Self::supplied(&market, &position)
+    }
+
+    /// The user's debt in a market, including accrued interest
+    pub fn get_debt_balance(env: Env, user: Address, currency: Currency) -> i128 {
+        let market = Self::get_market(env.clone(), currency.clone());
+        Self::debt(&market, &Self::position(&env, &user, &currency))
+    }
+
+    pub fn get_account_liquidity(env: Env, user: Address) -> AccountLiquidity {
+        let mut liquidity = AccountLiquidity {
+            collateral_value: 0,
+            borrow_limit: 0,
+            liquidation_value: 0,
+            debt_value: 0,
+            health_factor: i128::MAX,
+        };
+        for currency in Self::user_markets(&env, &user).iter() {
+            let market = Self::get_market(env.clone(), currency.clone());
+            let position = Self::position(&env, &user, &currency);
+
+            let supplied = Self::supplied(&market, &position);
+            if supplied > 0 {
+                let value = Self::value(&env, &currency, supplied);
+                liquidity.collateral_value += value;
+                liquidity.borrow_limit +=
+                    value * market.params.collateral_factor_bps as i128 / BASIS_POINTS_DIVISOR;
+                liquidity.liquidation_value +=
+                    value * market.params.liquidation_threshold_bps as i128 / BASIS_POINTS_DIVISOR;
+            }
+            let debt = Self::debt(&market, &position);
+            if debt > 0 {
+                liquidity.debt_value += Self::value(&env, &currency, debt);
+            }
+        }
+        if liquidity.debt_value > 0 {
+            liquidity.health_factor =
+                liquidity.liquidation_value * PRICE_PRECISION / liquidity.debt_value;
+        }
+        liquidity
+    }
+
+    /// Health factor scaled by 10^8; below `HEALTH_FACTOR_ONE` the account can be liquidated
+    pub fn get_health_factor(env: Env, user: Address) -> i128 {
+        Self::get_account_liquidity(env, user).health_factor
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_lending_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_lending_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_lending_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_lending_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_lending_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn repay(
+        env: &Env,
+        payer: &Address,
+        borrower: &Address,
+        currency: &Currency,
+        amount: i128,
+    ) -> i128 {
+        let mut market = Self::get_market(env.clone(), currency.clone());
+        let mut position = Self::position(env, borrower, currency);
+        let debt = Self::debt(&market, &position);
+        let repaid = amount.min(debt);
+        if repaid <= 0 {
+            panic!("No debt to repay");
+        }
+
+        token::Client::new(env, &market.params.token).transfer(
+            payer,
+            &env.current_contract_address(),
+            &repaid,
+        );
+        position.borrow_principal = debt - repaid;
+        position.borrow_index = market.borrow_index;
+        Self::save_position(env, borrower, currency, &position);
+        market.cash += repaid;
+        market.total_borrows = (market.total_borrows - repaid).max(0);
+        Self::save_market(env, &market);
+        repaid
+    }
+
+    fn accrue(env: &Env, market: &mut Market) {
+        let now = env.ledger().timestamp();
+        if now <= market.last_accrual {
+            return;
+        }
+        let elapsed = (now - market.last_accrual) as i128;
+        let rate = market.params.interest_rate_bps as i128;
+        market.total_borrows +=
+            market.total_borrows * rate * elapsed / (BASIS_POINTS_DIVISOR * SECONDS_PER_YEAR);
+        market.borrow_index +=
+            market.borrow_index * rate * elapsed / (BASIS_POINTS_DIVISOR * SECONDS_PER_YEAR);
+        market.last_accrual = now;
+    }
+
+    fn debt(market: &Market, position: &UserPosition) -> i128 {
+        if position.borrow_principal == 0 {
+            return 0;
+        }
+        position.borrow_principal * market.borrow_index / position.borrow_index
+    }
+
+    fn supplied(market: &Market, position: &UserPosition) -> i128 {
+        if market.total_shares == 0 {
+            return 0;
+        }
+        position.shares * (market.cash + market.total_borrows) / market.total_shares
+    }
+
+    // Price of one unit of `currency` in the quote currency, scaled by 10^8
+    fn price(env: &Env, currency: &Currency) -> i128 {
+        let config = Self::get_lending_config(env.clone());
+        if *currency == config.quote_currency {
+            return PRICE_PRECISION;
+        }
+        OracleContractClient::new(env, &config.oracle)
+            .get_price(currency, &config.quote_currency)
+            .price
+    }
+
+    fn value(env: &Env, currency: &Currency, amount: i128) -> i128 {
+        amount * Self::price(env, currency) / PRICE_PRECISION
+    }
+
+    fn market(env: &Env, currency: &Currency) -> Market {
+        Self::load_market(env, currency).expect("Market not listed")
+    }
+
+    fn load_market(env: &Env, currency: &Currency) -> Option<Market> {
+        env.storage()
+            .instance()
+            .get(&LendingDataKey::Market(currency.clone()))
+    }
+
+    fn save_market(env: &Env, market: &Market) {
+        env.storage()
+            .instance()
+            .set(&LendingDataKey::Market(market.currency.clone()), market);
+    }
+
+    fn position(env: &Env, user: &Address, currency: &Currency) -> UserPosition {
+        env.storage()
+            .persistent()
+            .get(&LendingDataKey::Position(user.clone(), currency.clone()))
+            .unwrap_or(UserPosition {
+                shares: 0,
+                borrow_principal: 0,
+                borrow_index: INDEX_PRECISION,
+            })
+    }
+
+    fn save_position(env: &Env, user: &Address, currency: &Currency, position: &UserPosition) {
+        env.storage().persistent().set(
+            &LendingDataKey::Position(user.clone(), currency.clone()),
+            position,
+        );
+
+        let mut markets = Self::user_markets(env, user);
+        if !markets.contains(currency) {
+            markets.push_back(currency.clone());
+            env.storage()
+                .persistent()
+                .set(&LendingDataKey::UserMarkets(user.clone()), &markets);
+        }
+    }
+
+    fn user_markets(env: &Env, user: &Address) -> Vec<Currency> {
+        env.storage()
+            .persistent()
+            .get(&LendingDataKey::UserMarkets(user.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn emit_action(
+        env: &Env,
+        variant: fn(LendingActionData) -> LendingEvent,
+        user: &Address,
+        currency: Currency,
+        amount: i128,
+    ) {
+        let event = DeFiEvent::Lending(variant(LendingActionData {
+            user: user.clone(),
+            currency,
+            amount,
+        }));
+        EventEmitter::emit_event(env, LENDING_TOPIC, event);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_lending_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_lending_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_lending_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_lending_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// Cash held by the markets lending `token`, all of it owed to suppliers
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mut owed = 0;
+        for currency in Self::get_markets(env.clone()).iter() {
+            let market = Self::market(env, &currency);
+            if market.params.token == *token {
+                owed += market.cash;
+            }
+        }
+        Some(owed)
+    }
+}
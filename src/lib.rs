@@ -1,23 +1,73 @@
 #![no_std]
 
+pub mod access;
+pub mod address_book;
+pub mod alias;
+pub mod auction;
+pub mod audit_log;
+pub mod blocklist;
+pub mod bps;
+pub mod bridge;
+pub mod compliance;
 pub mod conversion;
+pub mod dca;
 pub mod email_to_wallet;
 pub mod errors;
 pub mod escrow;
 pub mod event;
 pub mod events;
+pub mod factory;
 pub mod fees;
+pub mod guardian;
+pub mod idempotency;
+pub mod inheritance;
+pub mod insurance;
+pub mod invoice;
+pub mod keeper;
+pub mod lending;
+pub mod loyalty;
 pub mod mint;
 pub mod multisig;
 pub mod nonce;
+pub mod oracle;
+pub mod order_book;
+pub mod otc;
+pub mod p2p_market;
+pub mod payment_request;
+pub mod payroll;
 pub mod pool_manager;
+pub mod portfolio;
+pub mod promo;
+pub mod rate_limit;
 pub mod rate_lock;
+pub mod receipt;
+pub mod reference;
+pub mod referral;
+pub mod remittance;
+pub mod reputation;
+pub mod rescue;
+pub mod router;
+pub mod savings_group;
 pub mod schema;
+pub mod sep40;
+pub mod session;
+pub mod smart_wallet;
+pub mod snapshot;
+pub mod splitter;
+pub mod sponsorship;
+pub mod storage;
+pub mod stream;
+pub mod subscription;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+pub mod timelock;
 pub mod token;
+pub mod upgrade;
 pub mod utils;
+pub mod vesting;
 
 pub use crate::email_to_wallet::EmailToWalletContract;
-pub use crate::nonce::ContractError;
+pub use crate::errors::AppError;
 pub use crate::nonce::NonceTracker;
 pub use conversion::ConversionContract;
 pub use conversion::Currency;
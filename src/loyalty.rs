@@ -0,0 +1,351 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, LoyaltyAwardedData, LoyaltyEvent, LoyaltyRedeemedData, LOYALTY_TOPIC,
+};
+use crate::rescue::Rescue;
+use crate::token::LockedPayout;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+/// Volume units `points_per_volume` is quoted against
+pub const POINTS_PRECISION: i128 = 10_000;
+
+/// What a holder turns points into
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Redemption {
+    /// Credit taken off future platform fees
+    FeeDiscount,
+    /// Tokens paid out of the reward pool
+    Reward,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoyaltyConfig {
+    pub reward_token: Address,
+    /// Points awarded per `POINTS_PRECISION` units of volume
+    pub points_per_volume: i128,
+    /// Fee credit granted per point redeemed for a discount
+    pub discount_per_point: i128,
+    /// Reward tokens paid per point redeemed from the pool
+    pub reward_per_point: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum LoyaltyDataKey {
+    Admin,
+    Config,
+    RewardPool,
+    /// Seconds redeemed reward tokens stay locked on the token
+    RewardLockup,
+    Points(Address),
+    FeeCredit(Address),
+}
+
+#[contract]
+pub struct LoyaltyContract;
+
+/// Loyalty points ledger. Minters (the conversion contract and other trading
+/// venues) award points on volume; points cannot be transferred and are only
+/// spent by redeeming them for a fee credit or for tokens from the reward pool.
+#[contractimpl]
+impl LoyaltyContract {
+    pub fn initialize_loyalty(env: Env, admin: Address, config: LoyaltyConfig) {
+        if env.storage().instance().has(&LoyaltyDataKey::Admin) {
+            panic!("Loyalty already initialized");
+        }
+        admin.require_auth();
+        Self::validate_config(&config);
+        env.storage().instance().set(&LoyaltyDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&LoyaltyDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Change earn and redemption rates (owner only). The reward token can
+    /// only be swapped while the pool is empty.
+    pub fn set_loyalty_config(env: Env, caller: Address, config: LoyaltyConfig) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_loyalty_config",
+            config.clone(),
+        );
+        Self::validate_config(&config);
+        let current = Self::get_loyalty_config(env.clone());
+        if current.reward_token != config.reward_token
+            && Self::get_loyalty_reward_pool(env.clone()) > 0
+        {
+            panic!("Reward pool not empty");
+        }
+        env.storage()
+            .instance()
+            .set(&LoyaltyDataKey::Config, &config);
+    }
+
+    pub fn get_loyalty_config(env: Env) -> LoyaltyConfig {
+        env.storage()
+            .instance()
+            .get(&LoyaltyDataKey::Config)
+            .expect("Loyalty not initialized")
+    }
+
+    /// Award points to `account` for `volume` traded (minter only); returns
+    /// the points awarded
+    pub fn award_loyalty_points(env: Env, caller: Address, account: Address, volume: i128) -> i128 {
+        AccessControl::require_role(&env, Role::Minter, &caller);
+        if volume <= 0 {
+            panic!("Volume must be positive");
+        }
+        let config = Self::get_loyalty_config(env.clone());
+        let points = volume * config.points_per_volume / POINTS_PRECISION;
+        if points == 0 {
+            return 0;
+        }
+        let balance = Self::get_loyalty_points(env.clone(), account.clone());
+        Self::set_points(&env, &account, balance + points);
+
+        let event = DeFiEvent::Loyalty(LoyaltyEvent::Awarded(LoyaltyAwardedData {
+            account,
+            volume,
+            points,
+        }));
+        EventEmitter::emit_event(&env, LOYALTY_TOPIC, event);
+        points
+    }
+
+    pub fn get_loyalty_points(env: Env, account: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&LoyaltyDataKey::Points(account))
+            .unwrap_or(0)
+    }
+
+    /// Add reward tokens to the pool points are redeemed from
+    pub fn fund_loyalty_reward_pool(env: Env, funder: Address, amount: i128) {
+        funder.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let config = Self::get_loyalty_config(env.clone());
+        token::Client::new(&env, &config.reward_token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
+        let pool = Self::get_loyalty_reward_pool(env.clone());
+        env.storage()
+            .instance()
+            .set(&LoyaltyDataKey::RewardPool, &(pool + amount));
+    }
+
+    pub fn get_loyalty_reward_pool(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&LoyaltyDataKey::RewardPool)
+            .unwrap_or(0)
+    }
+
+    /// Spend `points` on a fee credit; returns the credit granted
+    pub fn redeem_points_for_discount(env: Env, account: Address, points: i128) -> i128 {
+        account.require_auth();
+        let config = Self::get_loyalty_config(env.clone());
+        if config.discount_per_point == 0 {
+            panic!("Fee discounts not offered");
+        }
+        Self::spend_points(&env, &account, points);
+        let credit = points * config.discount_per_point;
+        let balance = Self::get_loyalty_fee_credit(env.clone(), account.clone());
+        env.storage().persistent().set(
+            &LoyaltyDataKey::FeeCredit(account.clone()),
+            &(balance + credit),
+        );
+        Self::emit_redeemed(&env, account, points, Redemption::FeeDiscount, credit);
+        credit
+    }
+
+    /// Spend `points` on reward tokens from the pool; returns the amount paid
+    pub fn redeem_points_for_reward(env: Env, account: Address, points: i128) -> i128 {
+        account.require_auth();
+        let config = Self::get_loyalty_config(env.clone());
+        if config.reward_per_point == 0 {
+            panic!("Token rewards not offered");
+        }
+        Self::spend_points(&env, &account, points);
+        let reward = points * config.reward_per_point;
+        let pool = Self::get_loyalty_reward_pool(env.clone());
+        if reward > pool {
+            panic!("Insufficient reward pool");
+        }
+        env.storage()
+            .instance()
+            .set(&LoyaltyDataKey::RewardPool, &(pool - reward));
+        LockedPayout::pay(
+            &env,
+            &config.reward_token,
+            &account,
+            reward,
+            env.ledger().timestamp() + Self::get_loyalty_reward_lockup(env.clone()),
+        );
+        Self::emit_redeemed(&env, account, points, Redemption::Reward, reward);
+        reward
+    }
+
+    /// Keep redeemed reward tokens locked for `lockup` seconds, or 0 to pay
+    /// them out freely (owner only). Locking needs this contract to be a
+    /// lock source on the reward token.
+    pub fn set_loyalty_reward_lockup(env: Env, caller: Address, lockup: u64) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_loyalty_reward_lockup",
+            lockup,
+        );
+        env.storage()
+            .instance()
+            .set(&LoyaltyDataKey::RewardLockup, &lockup);
+    }
+
+    pub fn get_loyalty_reward_lockup(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&LoyaltyDataKey::RewardLockup)
+            .unwrap_or(0)
+    }
+
+    pub fn get_loyalty_fee_credit(env: Env, account: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&LoyaltyDataKey::FeeCredit(account))
+            .unwrap_or(0)
+    }
+
+    /// Draw `account`'s fee credit against a `fee` being charged (minter
+    /// only); returns the discount to take off the fee
+    pub fn apply_loyalty_fee_credit(
+        env: Env,
+        caller: Address,
+        account: Address,
+        fee: i128,
+    ) -> i128 {
+        AccessControl::require_role(&env, Role::Minter, &caller);
+        let credit = Self::get_loyalty_fee_credit(env.clone(), account.clone());
+        let discount = credit.min(fee.max(0));
+        if discount > 0 {
+            env.storage()
+                .persistent()
+                .set(&LoyaltyDataKey::FeeCredit(account), &(credit - discount));
+        }
+        discount
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_loyalty_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_loyalty_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_loyalty_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_loyalty_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_loyalty_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn validate_config(config: &LoyaltyConfig) {
+        if config.points_per_volume < 0
+            || config.discount_per_point < 0
+            || config.reward_per_point < 0
+        {
+            panic!("Invalid loyalty rates");
+        }
+    }
+
+    fn spend_points(env: &Env, account: &Address, points: i128) {
+        if points <= 0 {
+            panic!("Points must be positive");
+        }
+        let balance = Self::get_loyalty_points(env.clone(), account.clone());
+        if points > balance {
+            panic!("Insufficient points");
+        }
+        Self::set_points(env, account, balance - points);
+    }
+
+    fn set_points(env: &Env, account: &Address, points: i128) {
+        env.storage()
+            .persistent()
+            .set(&LoyaltyDataKey::Points(account.clone()), &points);
+    }
+
+    fn emit_redeemed(
+        env: &Env,
+        account: Address,
+        points: i128,
+        redemption: Redemption,
+        value: i128,
+    ) {
+        let event = DeFiEvent::Loyalty(LoyaltyEvent::Redeemed(LoyaltyRedeemedData {
+            account,
+            points,
+            redemption,
+            value,
+        }));
+        EventEmitter::emit_event(env, LOYALTY_TOPIC, event);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_loyalty_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_loyalty_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_loyalty_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_loyalty_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// The reward pool, which is paid out to point holders
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let config = Self::get_loyalty_config(env.clone());
+        if config.reward_token == *token {
+            Some(Self::get_loyalty_reward_pool(env.clone()))
+        } else {
+            Some(0)
+        }
+    }
+}
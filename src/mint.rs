@@ -1,6 +1,109 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::idempotency::Idempotency;
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::reference::PaymentReference;
 use crate::schema::TokenClient;
+use crate::upgrade::Upgrade;
 use soroban_sdk::symbol_short;
-use soroban_sdk::{contract, contractclient, contractimpl, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Symbol, Vec};
+
+/// A completed mint, kept for reconciliation against backend records
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintRecord {
+    pub id: u64,
+    /// Originating mint request (0 for direct mints)
+    pub request_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub token: Address,
+    /// Hash of the backend's deposit reference; for direct mints, the
+    /// payment reference they were made with (zero when none)
+    pub reference: BytesN<32>,
+    pub minted_at: u64,
+}
+
+/// Issuance caps; a cap of 0 leaves that dimension uncapped
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintLimits {
+    /// Maximum amount in a single mint
+    pub max_per_tx: i128,
+    /// Maximum amount minted to one recipient per UTC day
+    pub max_per_recipient_per_day: i128,
+    /// Maximum amount minted across all recipients per UTC day
+    pub max_global_per_day: i128,
+}
+
+/// Remaining mintable amounts under the current caps
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintHeadroom {
+    pub per_tx: i128,
+    pub recipient_daily: i128,
+    pub global_daily: i128,
+}
+
+/// Lifecycle of a queued mint request
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MintRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+/// A mint awaiting (or having received) checker approval
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintRequest {
+    pub id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub token: Address,
+    /// Hash of the backend's deposit reference
+    pub reference: BytesN<32>,
+    pub requested_at: u64,
+    pub expires_at: u64,
+    pub status: MintRequestStatus,
+    /// Id of the resulting mint record once approved (0 until then)
+    pub mint_id: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MintDataKey {
+    /// Number of mints performed so far
+    MintCount,
+    /// Mint record by id
+    Mint(u64),
+    /// Configured issuance caps
+    Limits,
+    /// Total minted on a given day
+    DailyMinted(u64),
+    /// Total minted to a recipient on a given day
+    RecipientDailyMinted(Address, u64),
+    /// Checker role for queued mint requests
+    Approver,
+    /// Number of mint requests created so far
+    RequestCount,
+    /// Mint request by id
+    Request(u64),
+    /// Mint ids issued to a recipient, in order
+    RecipientMints(Address),
+    /// Mint id for a backend reference hash
+    ReferenceMint(BytesN<32>),
+}
+
+const SECONDS_PER_DAY: u64 = 86400;
+const MINT_REQUEST_TTL: u64 = 3 * SECONDS_PER_DAY;
+const MAX_PAGE_SIZE: u32 = 50;
+const REQUEST_MINT_FN: Symbol = symbol_short!("mint_req");
+
+// Bump when the storage layout changes and add a step to `migrate_mint`
+const STORAGE_VERSION: u32 = 1;
 
 #[contract]
 pub struct MintContract;
@@ -13,19 +116,529 @@ impl MintContract {
         env.storage()
             .persistent()
             .set(&symbol_short!("admin"), &backend);
+        AccessControl::init_owner(&env, &backend);
+        Upgrade::init_version(&env, STORAGE_VERSION);
+    }
+
+    // Only minters can mint
+    pub fn mint_token(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+    ) -> u64 {
+        AccessControl::require_role(&env, Role::Minter, &caller);
+
+        // Once a checker is configured, issuance must go through the request queue
+        if env.storage().persistent().has(&MintDataKey::Approver) {
+            panic!("Two-step minting required");
+        }
+
+        let reference = PaymentReference::current(&env);
+        Self::execute_mint(&env, &recipient, amount, &token, 0, &reference)
+    }
+
+    /// Mint carrying the backend's payment reference, recorded on the mint
+    /// and in its event
+    pub fn mint_token_with_reference(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        reference: BytesN<32>,
+    ) -> u64 {
+        PaymentReference::attach(&env, &reference, || {
+            Self::mint_token(env.clone(), caller, recipient, amount, token)
+        })
     }
 
-    // Only admin can mint
-    pub fn mint_token(env: Env, recipient: Address, amount: i128, token: Address) {
-        let admin: Address = env
+    /// Mint under a client-generated idempotency key. Repeating a key the
+    /// minter already used returns the original mint id instead of minting
+    /// again.
+    pub fn mint_token_idempotent(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        key: BytesN<32>,
+    ) -> u64 {
+        if let Some(mint_id) = Idempotency::recall(&env, &caller, &key) {
+            AccessControl::require_role(&env, Role::Minter, &caller);
+            return mint_id;
+        }
+
+        let mint_id = Self::mint_token(env.clone(), caller.clone(), recipient, amount, token);
+        Idempotency::remember(&env, &caller, &key, &mint_id);
+        mint_id
+    }
+
+    /// Configure the checker role that approves queued mint requests (owner only)
+    pub fn set_mint_approver(env: Env, caller: Address, approver: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_mint_approver",
+            approver.clone(),
+        );
+
+        // The checker must not be able to raise requests itself
+        if AccessControl::has_role(&env, Role::Minter, &approver) {
+            panic!("Approver must not be a minter");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Approver, &approver);
+    }
+
+    pub fn get_mint_approver(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&MintDataKey::Approver)
+    }
+
+    /// Queue a mint for approval by the checker (minters only)
+    pub fn request_mint(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        reference: BytesN<32>,
+    ) -> u64 {
+        AccessControl::require_role(&env, Role::Minter, &caller);
+        if !RateLimiter::try_consume(&env, REQUEST_MINT_FN, &caller) {
+            panic!("Rate limit exceeded");
+        }
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if env
             .storage()
             .persistent()
-            .get(&symbol_short!("admin"))
-            .expect("admin not set");
-        admin.require_auth();
+            .has(&MintDataKey::ReferenceMint(reference.clone()))
+        {
+            panic!("Reference already minted");
+        }
+
+        let id: u64 = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::RequestCount)
+            .unwrap_or(0)
+            + 1;
+        let requested_at = env.ledger().timestamp();
+        let request = MintRequest {
+            id,
+            recipient: recipient.clone(),
+            amount,
+            token,
+            reference,
+            requested_at,
+            expires_at: requested_at + MINT_REQUEST_TTL,
+            status: MintRequestStatus::Pending,
+            mint_id: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Request(id), &request);
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::RequestCount, &id);
 
-        // Emit an event (for transparency)
         env.events()
-            .publish((symbol_short!("mint"), recipient.clone()), amount);
+            .publish((symbol_short!("mint_req"), recipient), (id, amount));
+
+        id
+    }
+
+    /// Approve a pending request and issue the tokens (approver only)
+    pub fn approve_mint(env: Env, request_id: u64) -> MintRequest {
+        let approver: Address = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::Approver)
+            .expect("approver not set");
+        approver.require_auth();
+
+        let mut request = Self::get_mint_request(env.clone(), request_id);
+        if request.status != MintRequestStatus::Pending {
+            panic!("Mint request is not pending");
+        }
+        if env.ledger().timestamp() > request.expires_at {
+            panic!("Mint request has expired");
+        }
+
+        request.mint_id = Self::execute_mint(
+            &env,
+            &request.recipient,
+            request.amount,
+            &request.token,
+            request_id,
+            &request.reference,
+        );
+        request.status = MintRequestStatus::Approved;
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Request(request_id), &request);
+
+        env.events()
+            .publish((symbol_short!("mint_ok"), request_id), request.mint_id);
+
+        request
+    }
+
+    /// Reject a pending request (approver only)
+    pub fn reject_mint(env: Env, request_id: u64) -> MintRequest {
+        let approver: Address = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::Approver)
+            .expect("approver not set");
+        approver.require_auth();
+
+        let mut request = Self::get_mint_request(env.clone(), request_id);
+        if request.status != MintRequestStatus::Pending {
+            panic!("Mint request is not pending");
+        }
+
+        request.status = MintRequestStatus::Rejected;
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Request(request_id), &request);
+
+        env.events()
+            .publish((symbol_short!("mint_rej"), request_id), request.amount);
+
+        request
+    }
+
+    /// Mark a pending request past its deadline as expired (callable by anyone)
+    pub fn expire_mint_request(env: Env, request_id: u64) -> MintRequest {
+        let mut request = Self::get_mint_request(env.clone(), request_id);
+        if request.status != MintRequestStatus::Pending {
+            panic!("Mint request is not pending");
+        }
+        if env.ledger().timestamp() <= request.expires_at {
+            panic!("Mint request has not expired yet");
+        }
+
+        request.status = MintRequestStatus::Expired;
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Request(request_id), &request);
+
+        env.events()
+            .publish((symbol_short!("mint_exp"), request_id), request.amount);
+
+        request
+    }
+
+    pub fn get_mint_request(env: Env, request_id: u64) -> MintRequest {
+        env.storage()
+            .persistent()
+            .get(&MintDataKey::Request(request_id))
+            .expect("mint request not found")
+    }
+
+    /// Limit how often each minter can queue requests, or clear the limit (owner only)
+    pub fn set_mint_request_rate_limit(env: Env, caller: Address, limit: Option<RateLimit>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_mint_request_rate_limit",
+            limit.clone(),
+        );
+        RateLimiter::set_limit(&env, REQUEST_MINT_FN, limit);
+    }
+
+    pub fn get_mint_request_rate_limit(env: Env) -> Option<RateLimit> {
+        RateLimiter::get_limit(&env, REQUEST_MINT_FN)
+    }
+
+    /// Configure issuance caps (owner only)
+    pub fn set_mint_limits(env: Env, caller: Address, limits: MintLimits) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_mint_limits",
+            limits.clone(),
+        );
+
+        if limits.max_per_tx < 0
+            || limits.max_per_recipient_per_day < 0
+            || limits.max_global_per_day < 0
+        {
+            panic!("Mint limits cannot be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Limits, &limits);
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_mint_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_mint_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_mint_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_mint_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_mint_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    /// Give up a role held by `account`
+    pub fn renounce_mint_role(env: Env, account: Address, role: Role) {
+        AccessControl::renounce_role(&env, &account, role);
+    }
+
+    pub fn has_mint_role(env: Env, role: Role, account: Address) -> bool {
+        AccessControl::has_role(&env, role, &account)
+    }
+
+    /// Replace the contract code (owner only)
+    pub fn upgrade_mint(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        Upgrade::upgrade(&env, &caller, new_wasm_hash);
+    }
+
+    /// Bring storage written by older code up to the current layout
+    pub fn migrate_mint(env: Env) -> u32 {
+        Upgrade::migrate(&env, STORAGE_VERSION, |env, version| {
+            // v1: admin checks moved to roles, so the existing admin becomes owner
+            if version == 1 {
+                if let Some(admin) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Address>(&symbol_short!("admin"))
+                {
+                    AccessControl::init_owner(env, &admin);
+                }
+            }
+        })
+    }
+
+    pub fn get_mint_storage_version(env: Env) -> u32 {
+        Upgrade::storage_version(&env)
+    }
+
+    pub fn get_mint_limits(env: Env) -> Option<MintLimits> {
+        env.storage().persistent().get(&MintDataKey::Limits)
+    }
+
+    /// Remaining amounts that can be minted to `recipient` right now
+    pub fn get_mint_headroom(env: Env, recipient: Address) -> MintHeadroom {
+        let limits: Option<MintLimits> = env.storage().persistent().get(&MintDataKey::Limits);
+        let limits = match limits {
+            Some(limits) => limits,
+            None => {
+                return MintHeadroom {
+                    per_tx: i128::MAX,
+                    recipient_daily: i128::MAX,
+                    global_daily: i128::MAX,
+                }
+            }
+        };
+
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let global_minted: i128 = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::DailyMinted(day))
+            .unwrap_or(0);
+        let recipient_minted: i128 = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::RecipientDailyMinted(recipient, day))
+            .unwrap_or(0);
+
+        MintHeadroom {
+            per_tx: Self::remaining(limits.max_per_tx, 0),
+            recipient_daily: Self::remaining(limits.max_per_recipient_per_day, recipient_minted),
+            global_daily: Self::remaining(limits.max_global_per_day, global_minted),
+        }
+    }
+
+    pub fn get_mint(env: Env, mint_id: u64) -> MintRecord {
+        env.storage()
+            .persistent()
+            .get(&MintDataKey::Mint(mint_id))
+            .expect("mint not found")
+    }
+
+    /// Page through all mints in issuance order
+    pub fn get_mint_history(env: Env, offset: u32, limit: u32) -> Vec<MintRecord> {
+        let count = Self::get_mint_count(env.clone());
+        let limit = limit.min(MAX_PAGE_SIZE) as u64;
+        let mut records = Vec::new(&env);
+
+        let mut id = offset as u64 + 1;
+        while id <= count && (records.len() as u64) < limit {
+            records.push_back(Self::get_mint(env.clone(), id));
+            id += 1;
+        }
+        records
+    }
+
+    /// Page through the mints issued to one recipient
+    pub fn get_recipient_mints(
+        env: Env,
+        recipient: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<MintRecord> {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::RecipientMints(recipient))
+            .unwrap_or(Vec::new(&env));
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let mut records = Vec::new(&env);
+
+        let mut index = offset;
+        while index < ids.len() && records.len() < limit {
+            records.push_back(Self::get_mint(env.clone(), ids.get(index).unwrap()));
+            index += 1;
+        }
+        records
+    }
+
+    /// Look up the mint issued for a backend deposit reference
+    pub fn get_mint_by_reference(env: Env, reference: BytesN<32>) -> Option<MintRecord> {
+        let id: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::ReferenceMint(reference));
+        id.map(|id| Self::get_mint(env, id))
+    }
+
+    pub fn get_mint_count(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&MintDataKey::MintCount)
+            .unwrap_or(0)
+    }
+
+    fn execute_mint(
+        env: &Env,
+        recipient: &Address,
+        amount: i128,
+        token: &Address,
+        request_id: u64,
+        reference: &BytesN<32>,
+    ) -> u64 {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        // Enforce issuance caps before anything moves
+        let headroom = Self::get_mint_headroom(env.clone(), recipient.clone());
+        if amount > headroom.per_tx {
+            panic!("Amount exceeds per-transaction mint cap");
+        }
+        if amount > headroom.recipient_daily {
+            panic!("Amount exceeds recipient daily mint cap");
+        }
+        if amount > headroom.global_daily {
+            panic!("Amount exceeds global daily mint cap");
+        }
+
+        // Invoke the token's mint; this contract must be the token's minting admin
+        let client = TokenClient::new(env, token);
+        let balance_before = client.balance(recipient);
+        if client.try_mint(recipient, &amount).is_err() {
+            panic!("Token mint failed");
+        }
+        if client.balance(recipient) != balance_before + amount {
+            panic!("Token mint did not credit recipient");
+        }
+
+        // Record the mint
+        let id: u64 = env
+            .storage()
+            .persistent()
+            .get(&MintDataKey::MintCount)
+            .unwrap_or(0)
+            + 1;
+        let record = MintRecord {
+            id,
+            request_id,
+            recipient: recipient.clone(),
+            amount,
+            token: token.clone(),
+            reference: reference.clone(),
+            minted_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Mint(id), &record);
+        env.storage().persistent().set(&MintDataKey::MintCount, &id);
+
+        // Index by recipient and by deposit reference for reconciliation
+        let recipient_index = MintDataKey::RecipientMints(recipient.clone());
+        let mut recipient_mints: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&recipient_index)
+            .unwrap_or(Vec::new(env));
+        recipient_mints.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&recipient_index, &recipient_mints);
+        if request_id != 0 {
+            env.storage()
+                .persistent()
+                .set(&MintDataKey::ReferenceMint(reference.clone()), &id);
+        }
+
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let global_key = MintDataKey::DailyMinted(day);
+        let global_minted: i128 = env.storage().persistent().get(&global_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&global_key, &(global_minted + amount));
+        let recipient_key = MintDataKey::RecipientDailyMinted(recipient.clone(), day);
+        let recipient_minted: i128 = env.storage().persistent().get(&recipient_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&recipient_key, &(recipient_minted + amount));
+
+        // Emit an event (for transparency)
+        env.events().publish(
+            (symbol_short!("mint"), recipient.clone()),
+            (amount, reference.clone()),
+        );
+
+        id
+    }
+
+    fn remaining(cap: i128, used: i128) -> i128 {
+        if cap == 0 {
+            i128::MAX
+        } else if used >= cap {
+            0
+        } else {
+            cap - used
+        }
     }
 }
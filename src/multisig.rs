@@ -26,7 +26,7 @@ const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
 #[contractimpl]
 impl MultiSigContract {
     pub fn initialize_multisig(env: Env, signers: Vec<Address>, threshold: u32) -> MultiSigConfig {
-        if threshold == 0 || threshold > signers.len() as u32 {
+        if threshold == 0 || threshold > signers.len() {
             panic!("Invalid threshold");
         }
 
@@ -55,28 +55,31 @@ impl MultiSigContract {
             nonce: config.nonce,
         };
 
-        let valid_signatures = signatures.len() as u32;
+        let valid_signatures = signatures.len();
 
-        let event = crate::event::DeFiEvent::MultisigTransactionProposed(
-            crate::event::MultisigTransactionProposedData {
-                nonce: config.nonce,
-                proposer: proposer.clone(),
-                operation_hash: operation.clone(),
-                threshold: config.threshold,
-                current_signatures: valid_signatures,
-                proposed_at: env.ledger().timestamp(),
-            },
-        );
+        let event =
+            crate::event::DeFiEvent::Multisig(crate::event::MultisigEvent::TransactionProposed(
+                crate::event::MultisigTransactionProposedData {
+                    nonce: config.nonce,
+                    proposer: proposer.clone(),
+                    operation_hash: operation.clone(),
+                    threshold: config.threshold,
+                    current_signatures: valid_signatures,
+                    proposed_at: env.ledger().timestamp(),
+                },
+            ));
         crate::event::EventEmitter::emit_event(&env, crate::event::MULTISIG_TOPIC, event);
 
         if valid_signatures >= config.threshold {
-            let exec_event = crate::event::DeFiEvent::MultisigTransactionExecuted(
-                crate::event::MultisigTransactionExecutedData {
-                    nonce: config.nonce,
-                    signers: config.signers.clone(),
-                    operation_hash: operation,
-                    executed_at: env.ledger().timestamp(),
-                },
+            let exec_event = crate::event::DeFiEvent::Multisig(
+                crate::event::MultisigEvent::TransactionExecuted(
+                    crate::event::MultisigTransactionExecutedData {
+                        nonce: config.nonce,
+                        signers: config.signers.clone(),
+                        operation_hash: operation,
+                        executed_at: env.ledger().timestamp(),
+                    },
+                ),
             );
             crate::event::EventEmitter::emit_event(&env, crate::event::MULTISIG_TOPIC, exec_event);
 
@@ -99,11 +102,15 @@ impl MultiSigContract {
         new_threshold: u32,
         proposer: Address,
     ) -> MultiSigConfig {
-        if new_threshold == 0 || new_threshold > new_signers.len() as u32 {
+        proposer.require_auth();
+        if new_threshold == 0 || new_threshold > new_signers.len() {
             panic!("Invalid threshold");
         }
 
         let old_config: MultiSigConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if !old_config.signers.contains(&proposer) {
+            panic!("Proposer is not a signer");
+        }
 
         let new_config = MultiSigConfig {
             signers: new_signers.clone(),
@@ -111,7 +118,7 @@ impl MultiSigContract {
             nonce: old_config.nonce,
         };
 
-        let event = crate::event::DeFiEvent::MultisigConfigUpdated(
+        let event = crate::event::DeFiEvent::Multisig(crate::event::MultisigEvent::ConfigUpdated(
             crate::event::MultisigConfigUpdatedData {
                 old_signers: old_config.signers,
                 new_signers,
@@ -119,7 +126,7 @@ impl MultiSigContract {
                 new_threshold,
                 updated_at: env.ledger().timestamp(),
             },
-        );
+        ));
         crate::event::EventEmitter::emit_event(&env, crate::event::MULTISIG_TOPIC, event);
         env.storage().instance().set(&CONFIG_KEY, &new_config);
         new_config
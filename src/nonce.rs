@@ -1,12 +1,12 @@
-#![no_std]
+use crate::errors::AppError;
+use crate::storage::DAY_IN_LEDGERS;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
 
-use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env, Symbol};
-
-#[contracterror]
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub enum ContractError {
-    InvalidNonce = 1,
-}
+/// Ledgers a nonce record stays live after its last use. An idle record is
+/// left to be archived rather than deleted: it stops costing rent, but it can
+/// only come back by being restored, so an old signature can't be replayed
+/// against a reset nonce.
+pub const NONCE_TTL: u32 = 30 * DAY_IN_LEDGERS;
 
 #[contract]
 pub struct NonceTracker;
@@ -18,19 +18,18 @@ impl NonceTracker {
         env.storage().persistent().get(&key).unwrap_or(0)
     }
 
-    pub fn check_and_update_nonce(
-        env: Env,
-        user: Address,
-        incoming: u64,
-    ) -> Result<u64, ContractError> {
+    pub fn check_and_update_nonce(env: Env, user: Address, incoming: u64) -> Result<u64, AppError> {
         let key = (user.clone(), symbol_short!("NONCE"));
         let stored: u64 = env.storage().persistent().get(&key).unwrap_or(0);
 
         if incoming <= stored {
-            return Err(ContractError::InvalidNonce);
+            return Err(AppError::InvalidNonce);
         }
 
         env.storage().persistent().set(&key, &incoming);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, NONCE_TTL, NONCE_TTL);
         Ok(incoming)
     }
 }
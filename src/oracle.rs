@@ -0,0 +1,319 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::Currency;
+use crate::event::{DeFiEvent, EventEmitter, PriceSubmittedData, ORACLE_TOPIC};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Vec};
+
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub admin: Address,
+    /// Fresh, non-outlier submissions needed before a price is served
+    pub min_submissions: u32,
+    /// Age in seconds after which a submission is ignored
+    pub max_staleness: u64,
+    /// Submissions further than this from the median are discarded as outliers
+    pub max_deviation_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSubmission {
+    pub feeder: Address,
+    /// Price scaled by 10^8, like conversion rates
+    pub price: i128,
+    pub submitted_at: u64,
+}
+
+/// Aggregated price for a currency pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Median of the accepted submissions, scaled by 10^8
+    pub price: i128,
+    /// Submission time of the oldest accepted submission
+    pub timestamp: u64,
+    /// Time after which the oldest accepted submission goes stale
+    pub valid_until: u64,
+    /// Number of submissions the median was taken over
+    pub sources: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OracleDataKey {
+    Config,
+    Feeders,
+    Submissions(Currency, Currency),
+}
+
+#[contract]
+pub struct OracleContract;
+
+/// Aggregates prices posted by independent feeders so no single key sets the
+/// rate used for conversions and rate locks.
+#[contractimpl]
+impl OracleContract {
+    pub fn initialize_oracle(
+        env: Env,
+        admin: Address,
+        min_submissions: u32,
+        max_staleness: u64,
+        max_deviation_bps: u32,
+    ) -> OracleConfig {
+        if env.storage().instance().has(&OracleDataKey::Config) {
+            panic!("Oracle already initialized");
+        }
+        admin.require_auth();
+
+        let config = OracleConfig {
+            admin: admin.clone(),
+            min_submissions,
+            max_staleness,
+            max_deviation_bps,
+        };
+        Self::validate_config(&config);
+        env.storage()
+            .instance()
+            .set(&OracleDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+        config
+    }
+
+    pub fn get_oracle_config(env: Env) -> OracleConfig {
+        env.storage()
+            .instance()
+            .get(&OracleDataKey::Config)
+            .expect("Oracle not initialized")
+    }
+
+    /// Updates the aggregation parameters (owner only)
+    pub fn set_oracle_params(
+        env: Env,
+        caller: Address,
+        min_submissions: u32,
+        max_staleness: u64,
+        max_deviation_bps: u32,
+    ) -> OracleConfig {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_oracle_params",
+            (min_submissions, max_staleness, max_deviation_bps),
+        );
+
+        let mut config = Self::get_oracle_config(env.clone());
+        config.min_submissions = min_submissions;
+        config.max_staleness = max_staleness;
+        config.max_deviation_bps = max_deviation_bps;
+        Self::validate_config(&config);
+        env.storage()
+            .instance()
+            .set(&OracleDataKey::Config, &config);
+        config
+    }
+
+    /// Registers an account allowed to submit prices (owner only)
+    pub fn add_price_feeder(env: Env, caller: Address, feeder: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "add_price_feeder",
+            feeder.clone(),
+        );
+
+        let mut feeders = Self::get_price_feeders(env.clone());
+        if feeders.contains(&feeder) {
+            panic!("Feeder already registered");
+        }
+        feeders.push_back(feeder);
+        env.storage()
+            .instance()
+            .set(&OracleDataKey::Feeders, &feeders);
+    }
+
+    /// Removes a feeder; its earlier submissions stop counting immediately (owner only)
+    pub fn remove_price_feeder(env: Env, caller: Address, feeder: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "remove_price_feeder",
+            feeder.clone(),
+        );
+
+        let mut feeders = Self::get_price_feeders(env.clone());
+        match feeders.first_index_of(&feeder) {
+            Some(index) => feeders.remove(index),
+            None => panic!("Feeder not registered"),
+        };
+        env.storage()
+            .instance()
+            .set(&OracleDataKey::Feeders, &feeders);
+    }
+
+    pub fn get_price_feeders(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&OracleDataKey::Feeders)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Records `feeder`'s price for a pair, replacing its previous submission
+    pub fn submit_price(
+        env: Env,
+        feeder: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        price: i128,
+    ) {
+        feeder.require_auth();
+        if !Self::get_price_feeders(env.clone()).contains(&feeder) {
+            panic!("Not a registered feeder");
+        }
+        if price <= 0 {
+            panic!("Price must be positive");
+        }
+
+        let key = OracleDataKey::Submissions(from_currency.clone(), to_currency.clone());
+        let mut submissions: Vec<PriceSubmission> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(&env));
+        let submission = PriceSubmission {
+            feeder: feeder.clone(),
+            price,
+            submitted_at: env.ledger().timestamp(),
+        };
+        match submissions.iter().position(|s| s.feeder == feeder) {
+            Some(index) => submissions.set(index as u32, submission),
+            None => submissions.push_back(submission),
+        }
+        env.storage().persistent().set(&key, &submissions);
+
+        let event = DeFiEvent::PriceSubmitted(PriceSubmittedData {
+            feeder,
+            from_currency,
+            to_currency,
+            price,
+            submitted_at: env.ledger().timestamp(),
+        });
+        EventEmitter::emit_event(&env, ORACLE_TOPIC, event);
+    }
+
+    pub fn get_price_submissions(
+        env: Env,
+        from_currency: Currency,
+        to_currency: Currency,
+    ) -> Vec<PriceSubmission> {
+        env.storage()
+            .persistent()
+            .get(&OracleDataKey::Submissions(from_currency, to_currency))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Median of the fresh submissions from current feeders, after discarding
+    /// outliers. Panics when too few submissions remain.
+    pub fn get_price(env: Env, from_currency: Currency, to_currency: Currency) -> PriceData {
+        let config = Self::get_oracle_config(env.clone());
+        let feeders = Self::get_price_feeders(env.clone());
+        let now = env.ledger().timestamp();
+
+        let mut fresh = Vec::new(&env);
+        for submission in Self::get_price_submissions(env.clone(), from_currency, to_currency) {
+            if feeders.contains(&submission.feeder)
+                && submission.submitted_at + config.max_staleness >= now
+            {
+                fresh.push_back(submission);
+            }
+        }
+        if fresh.is_empty() {
+            panic!("Not enough price submissions");
+        }
+
+        let mut prices = Vec::new(&env);
+        for submission in fresh.iter() {
+            prices.push_back(submission.price);
+        }
+        let reference = Self::median(&env, &prices);
+
+        let mut accepted = Vec::new(&env);
+        let mut oldest = now;
+        for submission in fresh.iter() {
+            let deviation = (submission.price - reference).abs() * BASIS_POINTS_DIVISOR / reference;
+            if deviation <= config.max_deviation_bps as i128 {
+                accepted.push_back(submission.price);
+                oldest = oldest.min(submission.submitted_at);
+            }
+        }
+        if accepted.len() < config.min_submissions {
+            panic!("Not enough price submissions");
+        }
+
+        PriceData {
+            price: Self::median(&env, &accepted),
+            timestamp: oldest,
+            valid_until: oldest + config.max_staleness,
+            sources: accepted.len(),
+        }
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_oracle_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_oracle_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_oracle_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_oracle_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_oracle_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn validate_config(config: &OracleConfig) {
+        if config.min_submissions == 0 || config.max_staleness == 0 {
+            panic!("Invalid oracle parameters");
+        }
+        if config.max_deviation_bps as i128 > BASIS_POINTS_DIVISOR {
+            panic!("Invalid oracle parameters");
+        }
+    }
+
+    // Even counts take the mean of the two middle values
+    fn median(env: &Env, values: &Vec<i128>) -> i128 {
+        let mut sorted: Vec<i128> = Vec::new(env);
+        for value in values.iter() {
+            let mut index = 0;
+            while index < sorted.len() && sorted.get_unchecked(index) < value {
+                index += 1;
+            }
+            sorted.insert(index, value);
+        }
+
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted.get_unchecked(mid - 1) + sorted.get_unchecked(mid)) / 2
+        } else {
+            sorted.get_unchecked(mid)
+        }
+    }
+}
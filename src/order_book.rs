@@ -0,0 +1,568 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, OrderBookEvent, OrderCancelledData, OrderFilledData, OrderPlacedData,
+    TradingSessionData, ORDER_BOOK_TOPIC,
+};
+use crate::rescue::Rescue;
+use crate::session::{TradingPair, TradingSession, TradingSessions};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+const PRICE_PRECISION: i128 = 100_000_000;
+/// Upper bound on resting orders per side, to keep matching within budget
+pub const MAX_ORDERS_PER_SIDE: u32 = 200;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderSide {
+    /// Buy base with quote
+    Buy,
+    /// Sell base for quote
+    Sell,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderBookConfig {
+    pub admin: Address,
+    /// Traded asset, e.g. the NGN stablecoin
+    pub base_token: Address,
+    /// Pricing asset, e.g. USDC
+    pub quote_token: Address,
+    pub maker_fee_bps: u32,
+    pub taker_fee_bps: u32,
+    pub fee_recipient: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    pub id: u64,
+    pub owner: Address,
+    pub side: OrderSide,
+    /// Quote per base unit, scaled by 10^8
+    pub price: i128,
+    /// Base amount still open
+    pub remaining: i128,
+    /// Quote still held for a buy order
+    pub locked_quote: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OrderBookDataKey {
+    Config,
+    NextId,
+    /// Resting order ids in priority order: best price first, then oldest
+    Book(OrderSide),
+    Order(u64),
+    /// Smallest base amount an order may rest with
+    Dust,
+}
+
+#[contract]
+pub struct OrderBookContract;
+
+/// Limit order book for one currency pair. Orders rest with their funds held
+/// here; crossing orders are matched with price-time priority at the resting
+/// (maker) order's price, and each side pays its maker or taker fee on what
+/// it receives. Market makers can trade through bot keys holding a bounded
+/// session instead of their master key.
+#[contractimpl]
+impl OrderBookContract {
+    pub fn initialize_order_book(
+        env: Env,
+        admin: Address,
+        base_token: Address,
+        quote_token: Address,
+        maker_fee_bps: u32,
+        taker_fee_bps: u32,
+        fee_recipient: Address,
+    ) {
+        if env.storage().instance().has(&OrderBookDataKey::Config) {
+            panic!("Order book already initialized");
+        }
+        admin.require_auth();
+        if base_token == quote_token {
+            panic!("Base and quote must differ");
+        }
+        Self::validate_fees(maker_fee_bps, taker_fee_bps);
+
+        let config = OrderBookConfig {
+            admin: admin.clone(),
+            base_token,
+            quote_token,
+            maker_fee_bps,
+            taker_fee_bps,
+            fee_recipient,
+        };
+        env.storage()
+            .instance()
+            .set(&OrderBookDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    pub fn get_order_book_config(env: Env) -> OrderBookConfig {
+        env.storage()
+            .instance()
+            .get(&OrderBookDataKey::Config)
+            .expect("Order book not initialized")
+    }
+
+    /// Update the trading fees (owner only)
+    pub fn set_order_book_fees(env: Env, caller: Address, maker_fee_bps: u32, taker_fee_bps: u32) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_order_book_fees",
+            (maker_fee_bps, taker_fee_bps),
+        );
+        Self::validate_fees(maker_fee_bps, taker_fee_bps);
+        let mut config = Self::get_order_book_config(env.clone());
+        config.maker_fee_bps = maker_fee_bps;
+        config.taker_fee_bps = taker_fee_bps;
+        env.storage()
+            .instance()
+            .set(&OrderBookDataKey::Config, &config);
+    }
+
+    /// Set the smallest base amount worth trading (owner only). Orders below it
+    /// are refused, and an order a partial fill leaves below it is closed with
+    /// its holdings returned, so no unfillable remainders rest on the book.
+    pub fn set_order_book_dust(env: Env, caller: Address, dust: i128) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_order_book_dust",
+            dust,
+        );
+        if dust < 0 {
+            panic!("Invalid dust threshold");
+        }
+        env.storage().instance().set(&OrderBookDataKey::Dust, &dust);
+    }
+
+    pub fn get_order_book_dust(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&OrderBookDataKey::Dust)
+            .unwrap_or(0)
+    }
+
+    /// Rest a limit order for `amount` of base at `price`. Sells hold the base
+    /// amount, buys hold its quote cost until filled or cancelled.
+    pub fn place_order(
+        env: Env,
+        owner: Address,
+        side: OrderSide,
+        price: i128,
+        amount: i128,
+    ) -> u64 {
+        owner.require_auth();
+        Self::open_order(&env, owner, side, price, amount, false)
+    }
+
+    /// Let `bot` trade this pair for `master` within a daily notional (in
+    /// quote) until `expires_at`. The master funds session orders through a
+    /// token allowance to this contract. Replaces any earlier session of `bot`.
+    pub fn grant_trading_session(
+        env: Env,
+        master: Address,
+        bot: Address,
+        pairs: Vec<TradingPair>,
+        max_daily_notional: i128,
+        expires_at: u64,
+    ) {
+        let session = TradingSession {
+            master: master.clone(),
+            pairs,
+            max_daily_notional,
+            expires_at,
+        };
+        TradingSessions::grant(&env, &bot, &session);
+
+        let event = DeFiEvent::OrderBook(OrderBookEvent::SessionGranted(TradingSessionData {
+            master,
+            bot,
+            expires_at,
+        }));
+        EventEmitter::emit_event(&env, ORDER_BOOK_TOPIC, event);
+    }
+
+    /// End `bot`'s session at once (its master only)
+    pub fn revoke_trading_session(env: Env, master: Address, bot: Address) {
+        TradingSessions::revoke(&env, &master, &bot);
+        let event = DeFiEvent::OrderBook(OrderBookEvent::SessionRevoked(TradingSessionData {
+            master,
+            bot,
+            expires_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, ORDER_BOOK_TOPIC, event);
+    }
+
+    pub fn get_trading_session(env: Env, bot: Address) -> TradingSession {
+        TradingSessions::get(&env, &bot).expect("Session not found")
+    }
+
+    /// Quote notional `bot` has placed today
+    pub fn get_trading_session_usage(env: Env, bot: Address) -> i128 {
+        TradingSessions::used_today(&env, &bot)
+    }
+
+    /// Place an order for the master of `bot`'s session, funded from the
+    /// master's allowance and counted against the session's daily notional
+    pub fn place_session_order(
+        env: Env,
+        bot: Address,
+        side: OrderSide,
+        price: i128,
+        amount: i128,
+    ) -> u64 {
+        let notional = Self::notional(price, amount);
+        let master = TradingSessions::authorize(&env, &bot, &Self::pair(&env), notional);
+        Self::open_order(&env, master, side, price, amount, true)
+    }
+
+    /// Cancel one of the master's orders through `bot`'s live session
+    pub fn cancel_session_order(env: Env, bot: Address, order_id: u64) {
+        let master = TradingSessions::authorize(&env, &bot, &Self::pair(&env), 0);
+        let order = Self::get_order(env.clone(), order_id);
+        if order.owner != master {
+            panic!("Order not owned by session master");
+        }
+        Self::close_order(&env, order);
+    }
+
+    /// Withdraw an open order and release what it still holds (order owner only)
+    pub fn cancel_order(env: Env, order_id: u64) {
+        let order = Self::get_order(env.clone(), order_id);
+        order.owner.require_auth();
+        Self::close_order(&env, order);
+    }
+
+    /// Match crossing orders from the top of the book, up to `max_fills`
+    /// fills. Callable by anyone; returns the number of fills made.
+    pub fn match_orders(env: Env, max_fills: u32) -> u32 {
+        let config = Self::get_order_book_config(env.clone());
+        let base = token::Client::new(&env, &config.base_token);
+        let quote = token::Client::new(&env, &config.quote_token);
+        let contract = env.current_contract_address();
+
+        let mut fills = 0;
+        while fills < max_fills {
+            let (Some(bid_id), Some(ask_id)) = (
+                Self::book(&env, OrderSide::Buy).first(),
+                Self::book(&env, OrderSide::Sell).first(),
+            ) else {
+                break;
+            };
+            let mut bid = Self::get_order(env.clone(), bid_id);
+            let mut ask = Self::get_order(env.clone(), ask_id);
+            if bid.price < ask.price {
+                break;
+            }
+
+            // The older order was resting first and sets the price
+            let bid_is_maker = bid.id < ask.id;
+            let price = if bid_is_maker { bid.price } else { ask.price };
+            let (buyer_fee_bps, seller_fee_bps) = if bid_is_maker {
+                (config.maker_fee_bps, config.taker_fee_bps)
+            } else {
+                (config.taker_fee_bps, config.maker_fee_bps)
+            };
+
+            let amount = bid.remaining.min(ask.remaining);
+            let cost = amount * price / PRICE_PRECISION;
+            let released = if amount == bid.remaining {
+                bid.locked_quote
+            } else {
+                amount * bid.price / PRICE_PRECISION
+            };
+
+            let buyer_fee = amount * buyer_fee_bps as i128 / BASIS_POINTS_DIVISOR;
+            let seller_fee = cost * seller_fee_bps as i128 / BASIS_POINTS_DIVISOR;
+            base.transfer(&contract, &bid.owner, &(amount - buyer_fee));
+            quote.transfer(&contract, &ask.owner, &(cost - seller_fee));
+            if buyer_fee > 0 {
+                base.transfer(&contract, &config.fee_recipient, &buyer_fee);
+            }
+            if seller_fee > 0 {
+                quote.transfer(&contract, &config.fee_recipient, &seller_fee);
+            }
+            // A buyer matched below its limit gets the difference back
+            if released > cost {
+                quote.transfer(&contract, &bid.owner, &(released - cost));
+            }
+
+            bid.remaining -= amount;
+            bid.locked_quote -= released;
+            ask.remaining -= amount;
+            fills += 1;
+
+            let event = DeFiEvent::OrderBook(OrderBookEvent::Filled(OrderFilledData {
+                buy_order_id: bid.id,
+                sell_order_id: ask.id,
+                price,
+                amount,
+            }));
+            EventEmitter::emit_event(&env, ORDER_BOOK_TOPIC, event);
+
+            let dust = Self::get_order_book_dust(env.clone());
+            for order in [&bid, &ask] {
+                if order.remaining == 0 {
+                    Self::remove(&env, order);
+                } else if order.remaining < dust {
+                    Self::close_order(&env, order.clone());
+                } else {
+                    Self::save(&env, order);
+                }
+            }
+        }
+        fills
+    }
+
+    pub fn get_order(env: Env, order_id: u64) -> Order {
+        env.storage()
+            .persistent()
+            .get(&OrderBookDataKey::Order(order_id))
+            .expect("Order not found")
+    }
+
+    /// Resting orders on one side, best price first
+    pub fn get_order_book_side(env: Env, side: OrderSide) -> Vec<Order> {
+        let mut orders = Vec::new(&env);
+        for order_id in Self::book(&env, side).iter() {
+            orders.push_back(Self::get_order(env.clone(), order_id));
+        }
+        orders
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_order_book_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_order_book_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_order_book_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_order_book_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_order_book_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn notional(price: i128, amount: i128) -> i128 {
+        if price <= 0 || amount <= 0 {
+            panic!("Price and amount must be positive");
+        }
+        let quote = amount * price / PRICE_PRECISION;
+        if quote <= 0 {
+            panic!("Order too small");
+        }
+        quote
+    }
+
+    fn pair(env: &Env) -> TradingPair {
+        let config = Self::get_order_book_config(env.clone());
+        TradingPair {
+            base: config.base_token,
+            quote: config.quote_token,
+        }
+    }
+
+    // Takes the funds the order holds from `owner`, directly or through its
+    // allowance to this contract, and queues the order behind every order at
+    // the same or a better price
+    fn open_order(
+        env: &Env,
+        owner: Address,
+        side: OrderSide,
+        price: i128,
+        amount: i128,
+        from_allowance: bool,
+    ) -> u64 {
+        let quote = Self::notional(price, amount);
+        if amount < Self::get_order_book_dust(env.clone()) {
+            panic!("Order below dust threshold");
+        }
+        let mut book = Self::book(env, side);
+        if book.len() >= MAX_ORDERS_PER_SIDE {
+            panic!("Order book side full");
+        }
+
+        let config = Self::get_order_book_config(env.clone());
+        let (held_token, held, locked_quote) = match side {
+            OrderSide::Sell => (config.base_token, amount, 0),
+            OrderSide::Buy => (config.quote_token, quote, quote),
+        };
+        let contract = env.current_contract_address();
+        let client = token::Client::new(env, &held_token);
+        if from_allowance {
+            client.transfer_from(&contract, &owner, &contract, &held);
+        } else {
+            client.transfer(&owner, &contract, &held);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&OrderBookDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&OrderBookDataKey::NextId, &(id + 1));
+        let order = Order {
+            id,
+            owner: owner.clone(),
+            side,
+            price,
+            remaining: amount,
+            locked_quote,
+        };
+        Self::save(env, &order);
+
+        let mut position = book.len();
+        for (index, resting_id) in book.iter().enumerate() {
+            let resting = Self::get_order(env.clone(), resting_id);
+            let better = match side {
+                OrderSide::Buy => price > resting.price,
+                OrderSide::Sell => price < resting.price,
+            };
+            if better {
+                position = index as u32;
+                break;
+            }
+        }
+        book.insert(position, id);
+        Self::save_book(env, side, &book);
+
+        let event = DeFiEvent::OrderBook(OrderBookEvent::Placed(OrderPlacedData {
+            order_id: id,
+            owner,
+            side,
+            price,
+            amount,
+        }));
+        EventEmitter::emit_event(env, ORDER_BOOK_TOPIC, event);
+        id
+    }
+
+    // Returns what the order still holds to its owner and takes it off the book
+    fn close_order(env: &Env, order: Order) {
+        let config = Self::get_order_book_config(env.clone());
+        let (held_token, held) = match order.side {
+            OrderSide::Sell => (config.base_token, order.remaining),
+            OrderSide::Buy => (config.quote_token, order.locked_quote),
+        };
+        token::Client::new(env, &held_token).transfer(
+            &env.current_contract_address(),
+            &order.owner,
+            &held,
+        );
+        Self::remove(env, &order);
+
+        let event = DeFiEvent::OrderBook(OrderBookEvent::Cancelled(OrderCancelledData {
+            order_id: order.id,
+            owner: order.owner,
+            remaining: order.remaining,
+        }));
+        EventEmitter::emit_event(env, ORDER_BOOK_TOPIC, event);
+    }
+
+    fn validate_fees(maker_fee_bps: u32, taker_fee_bps: u32) {
+        if maker_fee_bps as i128 > BASIS_POINTS_DIVISOR
+            || taker_fee_bps as i128 > BASIS_POINTS_DIVISOR
+        {
+            panic!("Invalid fee");
+        }
+    }
+
+    fn book(env: &Env, side: OrderSide) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&OrderBookDataKey::Book(side))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn save_book(env: &Env, side: OrderSide, book: &Vec<u64>) {
+        env.storage()
+            .persistent()
+            .set(&OrderBookDataKey::Book(side), book);
+    }
+
+    fn save(env: &Env, order: &Order) {
+        env.storage()
+            .persistent()
+            .set(&OrderBookDataKey::Order(order.id), order);
+    }
+
+    fn remove(env: &Env, order: &Order) {
+        let mut book = Self::book(env, order.side);
+        if let Some(index) = book.first_index_of(order.id) {
+            book.remove(index);
+        }
+        Self::save_book(env, order.side, &book);
+        env.storage()
+            .persistent()
+            .remove(&OrderBookDataKey::Order(order.id));
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_order_book_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_order_book_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_order_book_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_order_book_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` held for resting orders: base for sells, quote for buys
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let config = Self::get_order_book_config(env.clone());
+        let mut owed = 0;
+        if config.base_token == *token {
+            for order in Self::get_order_book_side(env.clone(), OrderSide::Sell).iter() {
+                owed += order.remaining;
+            }
+        }
+        if config.quote_token == *token {
+            for order in Self::get_order_book_side(env.clone(), OrderSide::Buy).iter() {
+                owed += order.locked_quote;
+            }
+        }
+        Some(owed)
+    }
+}
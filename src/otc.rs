@@ -0,0 +1,376 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{DeFiEvent, EventEmitter, OtcDealData, OtcEvent, OtcLegFundedData, OTC_TOPIC};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Vec};
+
+/// Upper bound on token legs per deal
+pub const MAX_DEAL_LEGS: u32 = 10;
+
+/// One token transfer in a deal, paid by one party to the other
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DealLeg {
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub funded: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DealStatus {
+    Open,
+    Settled,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deal {
+    pub id: u64,
+    pub party_a: Address,
+    pub party_b: Address,
+    pub legs: Vec<DealLeg>,
+    /// Bank reference of a fiat payment an attestor must confirm before settlement
+    pub fiat_reference: Option<BytesN<32>>,
+    pub fiat_attested: bool,
+    pub approved_by_a: bool,
+    pub approved_by_b: bool,
+    pub expires_at: u64,
+    pub status: DealStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OtcDataKey {
+    Admin,
+    NextId,
+    Deal(u64),
+}
+
+#[contract]
+pub struct OtcContract;
+
+/// Deal room for block trades between two institutions. A deal bundles any
+/// number of token legs in either direction plus an optional fiat leg
+/// confirmed by an attestor; every leg is deposited here and the whole deal
+/// settles in one step once funded and signed off by both parties.
+#[contractimpl]
+impl OtcContract {
+    pub fn initialize_otc(env: Env, admin: Address) {
+        if env.storage().instance().has(&OtcDataKey::Admin) {
+            panic!("OTC already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&OtcDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Open a deal with `counterparty`
+    pub fn propose_deal(
+        env: Env,
+        proposer: Address,
+        counterparty: Address,
+        legs: Vec<DealLeg>,
+        fiat_reference: Option<BytesN<32>>,
+        expires_at: u64,
+    ) -> u64 {
+        proposer.require_auth();
+        if proposer == counterparty {
+            panic!("Counterparty cannot be the proposer");
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Expiry must be in the future");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&OtcDataKey::NextId)
+            .unwrap_or(1);
+        env.storage().instance().set(&OtcDataKey::NextId, &(id + 1));
+
+        let mut deal = Deal {
+            id,
+            party_a: proposer.clone(),
+            party_b: counterparty.clone(),
+            legs: Vec::new(&env),
+            fiat_reference: None,
+            fiat_attested: false,
+            approved_by_a: false,
+            approved_by_b: false,
+            expires_at,
+            status: DealStatus::Open,
+        };
+        Self::set_terms(&env, &mut deal, legs, fiat_reference);
+        Self::save(&env, &deal);
+
+        let event = DeFiEvent::Otc(OtcEvent::Proposed(OtcDealData {
+            deal_id: id,
+            party_a: proposer,
+            party_b: counterparty,
+        }));
+        EventEmitter::emit_event(&env, OTC_TOPIC, event);
+        id
+    }
+
+    /// Change the terms before any leg is funded; clears both sign-offs
+    pub fn amend_deal(
+        env: Env,
+        caller: Address,
+        deal_id: u64,
+        legs: Vec<DealLeg>,
+        fiat_reference: Option<BytesN<32>>,
+    ) {
+        let mut deal = Self::open_deal(&env, deal_id);
+        Self::require_party(&deal, &caller);
+        if deal.legs.iter().any(|leg| leg.funded) || deal.fiat_attested {
+            panic!("Deal already funded");
+        }
+
+        Self::set_terms(&env, &mut deal, legs, fiat_reference);
+        deal.approved_by_a = false;
+        deal.approved_by_b = false;
+        Self::save(&env, &deal);
+    }
+
+    /// Deposit the tokens for one leg (its payer only)
+    pub fn fund_deal_leg(env: Env, deal_id: u64, leg_index: u32) {
+        let mut deal = Self::open_deal(&env, deal_id);
+        let mut leg = deal.legs.get(leg_index).expect("Leg not found");
+        leg.payer.require_auth();
+        if leg.funded {
+            panic!("Leg already funded");
+        }
+
+        token::Client::new(&env, &leg.token).transfer(
+            &leg.payer,
+            &env.current_contract_address(),
+            &leg.amount,
+        );
+        leg.funded = true;
+        deal.legs.set(leg_index, leg.clone());
+        Self::save(&env, &deal);
+
+        let event = DeFiEvent::Otc(OtcEvent::LegFunded(OtcLegFundedData {
+            deal_id,
+            leg_index,
+            payer: leg.payer,
+        }));
+        EventEmitter::emit_event(&env, OTC_TOPIC, event);
+    }
+
+    /// Confirm the off-chain fiat payment was received (attestor only)
+    pub fn attest_fiat_leg(env: Env, attestor: Address, deal_id: u64) {
+        AccessControl::require_role(&env, Role::Attestor, &attestor);
+        let mut deal = Self::open_deal(&env, deal_id);
+        if deal.fiat_reference.is_none() {
+            panic!("Deal has no fiat leg");
+        }
+        deal.fiat_attested = true;
+        Self::save(&env, &deal);
+    }
+
+    /// Sign off on the deal's current terms (either party)
+    pub fn approve_deal(env: Env, party: Address, deal_id: u64) {
+        let mut deal = Self::open_deal(&env, deal_id);
+        Self::require_party(&deal, &party);
+        if party == deal.party_a {
+            deal.approved_by_a = true;
+        } else {
+            deal.approved_by_b = true;
+        }
+        Self::save(&env, &deal);
+    }
+
+    /// Pay out every leg to its receiver once all legs are funded, any fiat
+    /// leg is attested and both parties have signed off
+    pub fn settle_deal(env: Env, deal_id: u64) {
+        let mut deal = Self::open_deal(&env, deal_id);
+        if env.ledger().timestamp() >= deal.expires_at {
+            panic!("Deal expired");
+        }
+        if !deal.approved_by_a || !deal.approved_by_b {
+            panic!("Deal not approved by both parties");
+        }
+        if deal.legs.iter().any(|leg| !leg.funded) {
+            panic!("Deal not fully funded");
+        }
+        if deal.fiat_reference.is_some() && !deal.fiat_attested {
+            panic!("Fiat leg not attested");
+        }
+
+        for leg in deal.legs.iter() {
+            let receiver = if leg.payer == deal.party_a {
+                &deal.party_b
+            } else {
+                &deal.party_a
+            };
+            token::Client::new(&env, &leg.token).transfer(
+                &env.current_contract_address(),
+                receiver,
+                &leg.amount,
+            );
+        }
+        deal.status = DealStatus::Settled;
+        Self::save(&env, &deal);
+
+        let event = DeFiEvent::Otc(OtcEvent::Settled(OtcDealData {
+            deal_id,
+            party_a: deal.party_a,
+            party_b: deal.party_b,
+        }));
+        EventEmitter::emit_event(&env, OTC_TOPIC, event);
+    }
+
+    /// Call off an unsettled deal and return every funded leg to its payer (either party)
+    pub fn cancel_deal(env: Env, caller: Address, deal_id: u64) {
+        let mut deal = Self::open_deal(&env, deal_id);
+        Self::require_party(&deal, &caller);
+
+        for leg in deal.legs.iter().filter(|leg| leg.funded) {
+            token::Client::new(&env, &leg.token).transfer(
+                &env.current_contract_address(),
+                &leg.payer,
+                &leg.amount,
+            );
+        }
+        deal.status = DealStatus::Cancelled;
+        Self::save(&env, &deal);
+
+        let event = DeFiEvent::Otc(OtcEvent::Cancelled(OtcDealData {
+            deal_id,
+            party_a: deal.party_a,
+            party_b: deal.party_b,
+        }));
+        EventEmitter::emit_event(&env, OTC_TOPIC, event);
+    }
+
+    pub fn get_deal(env: Env, deal_id: u64) -> Deal {
+        env.storage()
+            .persistent()
+            .get(&OtcDataKey::Deal(deal_id))
+            .expect("Deal not found")
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_otc_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_otc_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_otc_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_otc_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_otc_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn set_terms(
+        env: &Env,
+        deal: &mut Deal,
+        legs: Vec<DealLeg>,
+        fiat_reference: Option<BytesN<32>>,
+    ) {
+        if legs.is_empty() || legs.len() > MAX_DEAL_LEGS {
+            panic!("Invalid number of legs");
+        }
+        let mut terms = Vec::new(env);
+        for mut leg in legs.iter() {
+            if leg.amount <= 0 {
+                panic!("Leg amount must be positive");
+            }
+            if leg.payer != deal.party_a && leg.payer != deal.party_b {
+                panic!("Leg payer must be a party");
+            }
+            leg.funded = false;
+            terms.push_back(leg);
+        }
+        deal.legs = terms;
+        deal.fiat_reference = fiat_reference;
+    }
+
+    fn open_deal(env: &Env, deal_id: u64) -> Deal {
+        let deal = Self::get_deal(env.clone(), deal_id);
+        if deal.status != DealStatus::Open {
+            panic!("Deal not open");
+        }
+        deal
+    }
+
+    fn require_party(deal: &Deal, caller: &Address) {
+        if *caller != deal.party_a && *caller != deal.party_b {
+            panic!("Not a party to the deal");
+        }
+        caller.require_auth();
+    }
+
+    fn save(env: &Env, deal: &Deal) {
+        env.storage()
+            .persistent()
+            .set(&OtcDataKey::Deal(deal.id), deal);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_otc_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_otc_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_otc_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_otc_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` deposited into the funded legs of open deals
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let next_id: u64 = env
+            .storage()
+            .instance()
+            .get(&OtcDataKey::NextId)
+            .unwrap_or(1);
+        let mut owed = 0;
+        for id in 1..next_id {
+            let deal = Self::get_deal(env.clone(), id);
+            if deal.status != DealStatus::Open {
+                continue;
+            }
+            for leg in deal.legs.iter() {
+                if leg.funded && leg.token == *token {
+                    owed += leg.amount;
+                }
+            }
+        }
+        Some(owed)
+    }
+}
@@ -0,0 +1,444 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::Currency;
+use crate::event::{
+    DeFiEvent, EventEmitter, P2pEvent, P2pOfferPostedData, P2pTradeData, P2pTradeOpenedData,
+    P2pTradeSettledData, P2P_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+const PRICE_PRECISION: i128 = 100_000_000;
+
+/// Seller's terms for an offer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct P2pOfferTerms {
+    /// Fiat the buyer pays by bank transfer
+    pub fiat: Currency,
+    /// Fiat per token, scaled by 10^8
+    pub price: i128,
+    pub min_trade: i128,
+    pub max_trade: i128,
+    /// Seconds a buyer has to send the fiat after opening a trade
+    pub payment_window: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct P2pOffer {
+    pub id: u64,
+    pub seller: Address,
+    pub token: Address,
+    pub terms: P2pOfferTerms,
+    /// Tokens still open to new trades
+    pub available: i128,
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum P2pTradeStatus {
+    /// Tokens locked, waiting for the buyer's bank transfer
+    AwaitingPayment,
+    /// Buyer says the fiat is on its way
+    PaymentSent,
+    Disputed,
+    /// Tokens released to the buyer
+    Released,
+    /// Tokens returned to the seller
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct P2pTrade {
+    pub id: u64,
+    pub offer_id: u64,
+    pub buyer: Address,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub fiat_amount: i128,
+    pub payment_deadline: u64,
+    pub status: P2pTradeStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum P2pDataKey {
+    Admin,
+    NextOfferId,
+    NextTradeId,
+    Offer(u64),
+    Trade(u64),
+}
+
+#[contract]
+pub struct P2pMarketContract;
+
+/// Peer-to-peer fiat marketplace. Sellers deposit tokens behind an offer;
+/// taking an offer locks the traded amount in escrow until the seller
+/// confirms the buyer's bank transfer. Trades nobody pays for go back to the
+/// offer, and operators arbitrate disputed payments.
+#[contractimpl]
+impl P2pMarketContract {
+    pub fn initialize_p2p(env: Env, admin: Address) {
+        if env.storage().instance().has(&P2pDataKey::Admin) {
+            panic!("P2P market already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&P2pDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Deposit `amount` of `token` and offer it for fiat on `terms`
+    pub fn post_p2p_offer(
+        env: Env,
+        seller: Address,
+        token: Address,
+        amount: i128,
+        terms: P2pOfferTerms,
+    ) -> u64 {
+        seller.require_auth();
+        if amount <= 0 || terms.price <= 0 {
+            panic!("Amount and price must be positive");
+        }
+        if terms.min_trade <= 0 || terms.min_trade > terms.max_trade {
+            panic!("Invalid trade limits");
+        }
+        if terms.payment_window == 0 {
+            panic!("Payment window must be positive");
+        }
+        token::Client::new(&env, &token).transfer(
+            &seller,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let id = Self::next_id(&env, P2pDataKey::NextOfferId);
+        let offer = P2pOffer {
+            id,
+            seller: seller.clone(),
+            token: token.clone(),
+            terms: terms.clone(),
+            available: amount,
+            active: true,
+        };
+        Self::save_offer(&env, &offer);
+
+        let event = DeFiEvent::P2p(P2pEvent::OfferPosted(P2pOfferPostedData {
+            offer_id: id,
+            seller,
+            token,
+            amount,
+            fiat: terms.fiat,
+            price: terms.price,
+        }));
+        EventEmitter::emit_event(&env, P2P_TOPIC, event);
+        id
+    }
+
+    /// Close an offer and return its untaken tokens (seller only). Trades
+    /// already opened against it carry on.
+    pub fn withdraw_p2p_offer(env: Env, offer_id: u64) -> i128 {
+        let mut offer = Self::get_p2p_offer(env.clone(), offer_id);
+        offer.seller.require_auth();
+        if !offer.active {
+            panic!("Offer not active");
+        }
+        let refunded = offer.available;
+        if refunded > 0 {
+            token::Client::new(&env, &offer.token).transfer(
+                &env.current_contract_address(),
+                &offer.seller,
+                &refunded,
+            );
+        }
+        offer.available = 0;
+        offer.active = false;
+        Self::save_offer(&env, &offer);
+        refunded
+    }
+
+    /// Take `amount` from an offer, locking it in escrow for the buyer
+    pub fn open_p2p_trade(env: Env, buyer: Address, offer_id: u64, amount: i128) -> u64 {
+        buyer.require_auth();
+        let mut offer = Self::get_p2p_offer(env.clone(), offer_id);
+        if !offer.active {
+            panic!("Offer not active");
+        }
+        if buyer == offer.seller {
+            panic!("Cannot trade with yourself");
+        }
+        if amount < offer.terms.min_trade || amount > offer.terms.max_trade {
+            panic!("Amount outside offer limits");
+        }
+        if amount > offer.available {
+            panic!("Insufficient offer liquidity");
+        }
+        offer.available -= amount;
+        Self::save_offer(&env, &offer);
+
+        let id = Self::next_id(&env, P2pDataKey::NextTradeId);
+        let trade = P2pTrade {
+            id,
+            offer_id,
+            buyer: buyer.clone(),
+            seller: offer.seller,
+            token: offer.token,
+            amount,
+            fiat_amount: amount * offer.terms.price / PRICE_PRECISION,
+            payment_deadline: env.ledger().timestamp() + offer.terms.payment_window,
+            status: P2pTradeStatus::AwaitingPayment,
+        };
+        Self::save_trade(&env, &trade);
+
+        let event = DeFiEvent::P2p(P2pEvent::TradeOpened(P2pTradeOpenedData {
+            trade_id: id,
+            offer_id,
+            buyer,
+            amount,
+            fiat_amount: trade.fiat_amount,
+            payment_deadline: trade.payment_deadline,
+        }));
+        EventEmitter::emit_event(&env, P2P_TOPIC, event);
+        id
+    }
+
+    /// Tell the seller the bank transfer has been sent (buyer only)
+    pub fn mark_p2p_fiat_sent(env: Env, trade_id: u64) {
+        let mut trade = Self::get_p2p_trade(env.clone(), trade_id);
+        trade.buyer.require_auth();
+        if trade.status != P2pTradeStatus::AwaitingPayment {
+            panic!("Trade not awaiting payment");
+        }
+        if env.ledger().timestamp() >= trade.payment_deadline {
+            panic!("Payment window closed");
+        }
+        trade.status = P2pTradeStatus::PaymentSent;
+        Self::save_trade(&env, &trade);
+
+        let event = DeFiEvent::P2p(P2pEvent::FiatSent(P2pTradeData {
+            trade_id,
+            caller: trade.buyer,
+        }));
+        EventEmitter::emit_event(&env, P2P_TOPIC, event);
+    }
+
+    /// Confirm the fiat arrived and release the tokens to the buyer (seller only)
+    pub fn confirm_p2p_fiat_received(env: Env, trade_id: u64) {
+        let mut trade = Self::open_trade(&env, trade_id);
+        trade.seller.require_auth();
+        Self::settle(&env, &mut trade, true);
+    }
+
+    /// Give up an unpaid trade: the buyer may cancel at any time before
+    /// marking payment, anyone once the payment window has lapsed
+    pub fn cancel_p2p_trade(env: Env, trade_id: u64) {
+        let mut trade = Self::get_p2p_trade(env.clone(), trade_id);
+        if trade.status != P2pTradeStatus::AwaitingPayment {
+            panic!("Trade not awaiting payment");
+        }
+        if env.ledger().timestamp() < trade.payment_deadline {
+            trade.buyer.require_auth();
+        }
+        Self::settle(&env, &mut trade, false);
+    }
+
+    /// Escalate a trade whose payment is contested (buyer or seller)
+    pub fn dispute_p2p_trade(env: Env, caller: Address, trade_id: u64) {
+        caller.require_auth();
+        let mut trade = Self::get_p2p_trade(env.clone(), trade_id);
+        if caller != trade.buyer && caller != trade.seller {
+            panic!("Not a trade party");
+        }
+        if trade.status != P2pTradeStatus::PaymentSent {
+            panic!("Only paid trades can be disputed");
+        }
+        trade.status = P2pTradeStatus::Disputed;
+        Self::save_trade(&env, &trade);
+
+        let event = DeFiEvent::P2p(P2pEvent::Disputed(P2pTradeData { trade_id, caller }));
+        EventEmitter::emit_event(&env, P2P_TOPIC, event);
+    }
+
+    /// Rule on a disputed trade: release to the buyer if the fiat was paid,
+    /// otherwise return the tokens to the seller (operator only)
+    pub fn resolve_p2p_dispute(env: Env, arbiter: Address, trade_id: u64, release_to_buyer: bool) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &arbiter,
+            "resolve_p2p_dispute",
+            (trade_id, release_to_buyer),
+        );
+        let mut trade = Self::get_p2p_trade(env.clone(), trade_id);
+        if trade.status != P2pTradeStatus::Disputed {
+            panic!("Trade not disputed");
+        }
+        Self::settle(&env, &mut trade, release_to_buyer);
+    }
+
+    pub fn get_p2p_offer(env: Env, offer_id: u64) -> P2pOffer {
+        env.storage()
+            .persistent()
+            .get(&P2pDataKey::Offer(offer_id))
+            .expect("Offer not found")
+    }
+
+    pub fn get_p2p_trade(env: Env, trade_id: u64) -> P2pTrade {
+        env.storage()
+            .persistent()
+            .get(&P2pDataKey::Trade(trade_id))
+            .expect("Trade not found")
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_p2p_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_p2p_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_p2p_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_p2p_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_p2p_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn open_trade(env: &Env, trade_id: u64) -> P2pTrade {
+        let trade = Self::get_p2p_trade(env.clone(), trade_id);
+        if matches!(
+            trade.status,
+            P2pTradeStatus::Released | P2pTradeStatus::Cancelled
+        ) {
+            panic!("Trade already settled");
+        }
+        trade
+    }
+
+    // Pays the locked tokens to the buyer, or hands them back to the seller:
+    // onto the offer while it is still open, otherwise straight to the wallet
+    fn settle(env: &Env, trade: &mut P2pTrade, to_buyer: bool) {
+        let receiver = if to_buyer {
+            trade.status = P2pTradeStatus::Released;
+            Some(trade.buyer.clone())
+        } else {
+            trade.status = P2pTradeStatus::Cancelled;
+            let mut offer = Self::get_p2p_offer(env.clone(), trade.offer_id);
+            if offer.active {
+                offer.available += trade.amount;
+                Self::save_offer(env, &offer);
+                None
+            } else {
+                Some(trade.seller.clone())
+            }
+        };
+        if let Some(receiver) = receiver {
+            token::Client::new(env, &trade.token).transfer(
+                &env.current_contract_address(),
+                &receiver,
+                &trade.amount,
+            );
+        }
+        Self::save_trade(env, trade);
+
+        let event = DeFiEvent::P2p(P2pEvent::Settled(P2pTradeSettledData {
+            trade_id: trade.id,
+            released_to_buyer: to_buyer,
+            amount: trade.amount,
+        }));
+        EventEmitter::emit_event(env, P2P_TOPIC, event);
+    }
+
+    fn next_id(env: &Env, key: P2pDataKey) -> u64 {
+        let id: u64 = env.storage().instance().get(&key).unwrap_or(1);
+        env.storage().instance().set(&key, &(id + 1));
+        id
+    }
+
+    fn save_offer(env: &Env, offer: &P2pOffer) {
+        env.storage()
+            .persistent()
+            .set(&P2pDataKey::Offer(offer.id), offer);
+    }
+
+    fn save_trade(env: &Env, trade: &P2pTrade) {
+        env.storage()
+            .persistent()
+            .set(&P2pDataKey::Trade(trade.id), trade);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_p2p_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_p2p_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_p2p_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_p2p_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` behind active offers and locked in unsettled trades
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mut owed = 0;
+        let next_offer: u64 = env
+            .storage()
+            .instance()
+            .get(&P2pDataKey::NextOfferId)
+            .unwrap_or(1);
+        for id in 1..next_offer {
+            let offer = Self::get_p2p_offer(env.clone(), id);
+            if offer.active && offer.token == *token {
+                owed += offer.available;
+            }
+        }
+        let next_trade: u64 = env
+            .storage()
+            .instance()
+            .get(&P2pDataKey::NextTradeId)
+            .unwrap_or(1);
+        for id in 1..next_trade {
+            let trade = Self::get_p2p_trade(env.clone(), id);
+            let open = !matches!(
+                trade.status,
+                P2pTradeStatus::Released | P2pTradeStatus::Cancelled
+            );
+            if open && trade.token == *token {
+                owed += trade.amount;
+            }
+        }
+        Some(owed)
+    }
+}
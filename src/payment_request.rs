@@ -0,0 +1,282 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, PaymentRequestClosedData, PaymentRequestCreatedData,
+    PaymentRequestEvent, PaymentRequestReminderData, PAYMENT_REQUEST_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String};
+
+/// Longest memo a request may carry, in bytes
+pub const MAX_MEMO_LEN: u32 = 140;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PaymentRequestStatus {
+    Pending,
+    Paid,
+    Declined,
+    Cancelled,
+    /// Passed its expiry without being paid
+    Expired,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRequest {
+    pub id: u64,
+    pub requester: Address,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub memo: String,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub last_reminded_at: u64,
+    pub status: PaymentRequestStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PaymentRequestDataKey {
+    Admin,
+    ReminderInterval,
+    NextId,
+    Request(u64),
+}
+
+#[contract]
+pub struct PaymentRequestContract;
+
+/// "Request money" flow. A requester asks a payer for an amount with a memo
+/// and a deadline; the payer pays it in one approval or declines it, and a
+/// request nobody acts on lapses at its deadline. Requesters can nudge the
+/// payer with reminder events, at most once per reminder interval.
+#[contractimpl]
+impl PaymentRequestContract {
+    pub fn initialize_payment_requests(env: Env, admin: Address, reminder_interval: u64) {
+        if env.storage().instance().has(&PaymentRequestDataKey::Admin) {
+            panic!("Payment requests already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&PaymentRequestDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&PaymentRequestDataKey::ReminderInterval, &reminder_interval);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Change the minimum gap between reminders (owner only)
+    pub fn set_payment_reminder_interval(env: Env, caller: Address, reminder_interval: u64) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_payment_reminder_interval",
+            reminder_interval,
+        );
+        env.storage()
+            .instance()
+            .set(&PaymentRequestDataKey::ReminderInterval, &reminder_interval);
+    }
+
+    pub fn get_payment_reminder_interval(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&PaymentRequestDataKey::ReminderInterval)
+            .expect("Payment requests not initialized")
+    }
+
+    /// Ask `payer` for `amount` of `token` before `expires_at`
+    pub fn create_payment_request(
+        env: Env,
+        requester: Address,
+        payer: Address,
+        token: Address,
+        amount: i128,
+        expires_at: u64,
+        memo: String,
+    ) -> u64 {
+        requester.require_auth();
+        if requester == payer {
+            panic!("Cannot request payment from yourself");
+        }
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            panic!("Expiry must be in the future");
+        }
+        if memo.len() > MAX_MEMO_LEN {
+            panic!("Memo too long");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&PaymentRequestDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&PaymentRequestDataKey::NextId, &(id + 1));
+
+        let request = PaymentRequest {
+            id,
+            requester: requester.clone(),
+            payer: payer.clone(),
+            token: token.clone(),
+            amount,
+            memo: memo.clone(),
+            created_at: now,
+            expires_at,
+            last_reminded_at: now,
+            status: PaymentRequestStatus::Pending,
+        };
+        Self::save(&env, &request);
+
+        let event =
+            DeFiEvent::PaymentRequest(PaymentRequestEvent::Created(PaymentRequestCreatedData {
+                request_id: id,
+                requester,
+                payer,
+                token,
+                amount,
+                expires_at,
+                memo,
+            }));
+        EventEmitter::emit_event(&env, PAYMENT_REQUEST_TOPIC, event);
+        id
+    }
+
+    /// Approve and pay a pending request (payer only)
+    pub fn pay_payment_request(env: Env, request_id: u64) {
+        let mut request = Self::pending(&env, request_id);
+        request.payer.require_auth();
+        token::Client::new(&env, &request.token).transfer(
+            &request.payer,
+            &request.requester,
+            &request.amount,
+        );
+        Self::close(&env, &mut request, PaymentRequestStatus::Paid);
+    }
+
+    /// Refuse a pending request (payer only)
+    pub fn decline_payment_request(env: Env, request_id: u64) {
+        let mut request = Self::pending(&env, request_id);
+        request.payer.require_auth();
+        Self::close(&env, &mut request, PaymentRequestStatus::Declined);
+    }
+
+    /// Withdraw a pending request (requester only)
+    pub fn cancel_payment_request(env: Env, request_id: u64) {
+        let mut request = Self::pending(&env, request_id);
+        request.requester.require_auth();
+        Self::close(&env, &mut request, PaymentRequestStatus::Cancelled);
+    }
+
+    /// Nudge the payer about a pending request (requester only)
+    pub fn remind_payment_request(env: Env, request_id: u64) {
+        let mut request = Self::pending(&env, request_id);
+        request.requester.require_auth();
+        let now = env.ledger().timestamp();
+        if now < request.last_reminded_at + Self::get_payment_reminder_interval(env.clone()) {
+            panic!("Reminder sent too recently");
+        }
+        request.last_reminded_at = now;
+        Self::save(&env, &request);
+
+        let event =
+            DeFiEvent::PaymentRequest(PaymentRequestEvent::Reminded(PaymentRequestReminderData {
+                request_id,
+                payer: request.payer,
+                amount: request.amount,
+                expires_at: request.expires_at,
+            }));
+        EventEmitter::emit_event(&env, PAYMENT_REQUEST_TOPIC, event);
+    }
+
+    /// Record that an unpaid request has lapsed; callable by anyone
+    pub fn expire_payment_request(env: Env, request_id: u64) {
+        let mut request = Self::stored(&env, request_id);
+        if request.status != PaymentRequestStatus::Pending {
+            panic!("Request not pending");
+        }
+        if env.ledger().timestamp() < request.expires_at {
+            panic!("Request not expired");
+        }
+        Self::close(&env, &mut request, PaymentRequestStatus::Expired);
+    }
+
+    /// The request with its current status; pending requests past their
+    /// expiry read as expired
+    pub fn get_payment_request(env: Env, request_id: u64) -> PaymentRequest {
+        let mut request = Self::stored(&env, request_id);
+        if request.status == PaymentRequestStatus::Pending
+            && env.ledger().timestamp() >= request.expires_at
+        {
+            request.status = PaymentRequestStatus::Expired;
+        }
+        request
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_payment_request_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_payment_request_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_payment_request_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_payment_request_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_payment_request_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn stored(env: &Env, request_id: u64) -> PaymentRequest {
+        env.storage()
+            .persistent()
+            .get(&PaymentRequestDataKey::Request(request_id))
+            .expect("Request not found")
+    }
+
+    fn pending(env: &Env, request_id: u64) -> PaymentRequest {
+        let request = Self::get_payment_request(env.clone(), request_id);
+        match request.status {
+            PaymentRequestStatus::Pending => request,
+            PaymentRequestStatus::Expired => panic!("Request expired"),
+            _ => panic!("Request not pending"),
+        }
+    }
+
+    fn close(env: &Env, request: &mut PaymentRequest, status: PaymentRequestStatus) {
+        request.status = status;
+        Self::save(env, request);
+        let event =
+            DeFiEvent::PaymentRequest(PaymentRequestEvent::Closed(PaymentRequestClosedData {
+                request_id: request.id,
+                status,
+            }));
+        EventEmitter::emit_event(env, PAYMENT_REQUEST_TOPIC, event);
+    }
+
+    fn save(env: &Env, request: &PaymentRequest) {
+        env.storage()
+            .persistent()
+            .set(&PaymentRequestDataKey::Request(request.id), request);
+    }
+}
@@ -0,0 +1,442 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::{ConversionContractClient, Currency};
+use crate::event::{
+    DeFiEvent, EventEmitter, PayrollEvent, PayrollRunData, SalaryPaidData, PAYROLL_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const RATE_PRECISION: i128 = 100_000_000;
+const MAX_BATCH_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayrollConfig {
+    pub employer: Address,
+    /// Currency salaries are denominated in
+    pub base_currency: Currency,
+    /// Conversion contract quoting rates for employees paid in another currency
+    pub conversion: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Employee {
+    pub address: Address,
+    /// Salary per pay period in the base currency
+    pub salary: i128,
+    /// Currency the employee is paid in
+    pub currency: Currency,
+    /// Seconds between pay dates
+    pub pay_interval: u64,
+    pub next_pay_date: u64,
+    pub total_paid_base: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PayrollDataKey {
+    Config,
+    CurrencyToken(Currency),
+    Treasury(Currency),
+    Employees,
+    Employee(Address),
+}
+
+#[contract]
+pub struct PayrollContract;
+
+/// Salaries paid from an employer-funded treasury. Keepers holding the operator
+/// role run batches that pay every employee whose pay date has arrived.
+#[contractimpl]
+impl PayrollContract {
+    pub fn initialize_payroll(
+        env: Env,
+        employer: Address,
+        base_currency: Currency,
+        base_token: Address,
+    ) {
+        if env.storage().instance().has(&PayrollDataKey::Config) {
+            panic!("Payroll already initialized");
+        }
+        employer.require_auth();
+
+        let config = PayrollConfig {
+            employer: employer.clone(),
+            base_currency: base_currency.clone(),
+            conversion: None,
+        };
+        env.storage()
+            .instance()
+            .set(&PayrollDataKey::Config, &config);
+        env.storage()
+            .instance()
+            .set(&PayrollDataKey::CurrencyToken(base_currency), &base_token);
+        AccessControl::init_owner(&env, &employer);
+    }
+
+    pub fn get_payroll_config(env: Env) -> PayrollConfig {
+        env.storage()
+            .instance()
+            .get(&PayrollDataKey::Config)
+            .expect("Payroll not initialized")
+    }
+
+    /// Sets the conversion contract used to price non-base payouts (owner only)
+    pub fn set_payroll_conversion(env: Env, caller: Address, conversion: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_payroll_conversion",
+            conversion.clone(),
+        );
+        let mut config = Self::get_payroll_config(env.clone());
+        config.conversion = conversion;
+        env.storage()
+            .instance()
+            .set(&PayrollDataKey::Config, &config);
+    }
+
+    /// Registers the token paid out for a currency (owner only)
+    pub fn set_currency_token(env: Env, caller: Address, currency: Currency, token: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_currency_token",
+            (currency.clone(), token.clone()),
+        );
+        if Self::get_treasury_balance(env.clone(), currency.clone()) > 0 {
+            panic!("Treasury still holds the current token");
+        }
+        env.storage()
+            .instance()
+            .set(&PayrollDataKey::CurrencyToken(currency), &token);
+    }
+
+    pub fn get_currency_token(env: Env, currency: Currency) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&PayrollDataKey::CurrencyToken(currency))
+    }
+
+    /// Adds `amount` of a currency's token to the treasury
+    pub fn fund_payroll(env: Env, funder: Address, currency: Currency, amount: i128) {
+        funder.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let token = Self::currency_token(&env, &currency);
+        token::Client::new(&env, &token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
+        Self::adjust_treasury(&env, &currency, amount);
+    }
+
+    /// Takes funds back out of the treasury (owner only)
+    pub fn withdraw_payroll_funds(
+        env: Env,
+        caller: Address,
+        currency: Currency,
+        amount: i128,
+        to: Address,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "withdraw_payroll_funds",
+            (currency.clone(), amount, to.clone()),
+        );
+        if amount <= 0 || amount > Self::get_treasury_balance(env.clone(), currency.clone()) {
+            panic!("Invalid withdrawal amount");
+        }
+
+        Self::adjust_treasury(&env, &currency, -amount);
+        token::Client::new(&env, &Self::currency_token(&env, &currency)).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+    }
+
+    pub fn get_treasury_balance(env: Env, currency: Currency) -> i128 {
+        env.storage()
+            .instance()
+            .get(&PayrollDataKey::Treasury(currency))
+            .unwrap_or(0)
+    }
+
+    /// Registers an employee paid in the base currency from `first_pay_date` (owner only)
+    pub fn add_employee(
+        env: Env,
+        caller: Address,
+        employee: Address,
+        salary: i128,
+        pay_interval: u64,
+        first_pay_date: u64,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "add_employee",
+            (employee.clone(), salary, pay_interval, first_pay_date),
+        );
+        if salary <= 0 || pay_interval == 0 {
+            panic!("Invalid salary terms");
+        }
+        let mut employees = Self::get_employees(env.clone());
+        if employees.contains(&employee) {
+            panic!("Employee already registered");
+        }
+
+        let record = Employee {
+            address: employee.clone(),
+            salary,
+            currency: Self::get_payroll_config(env.clone()).base_currency,
+            pay_interval,
+            next_pay_date: first_pay_date,
+            total_paid_base: 0,
+        };
+        Self::save_employee(&env, &record);
+        employees.push_back(employee);
+        env.storage()
+            .persistent()
+            .set(&PayrollDataKey::Employees, &employees);
+    }
+
+    /// Changes the salary of a registered employee (owner only)
+    pub fn set_employee_salary(env: Env, caller: Address, employee: Address, salary: i128) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_employee_salary",
+            (employee.clone(), salary),
+        );
+        if salary <= 0 {
+            panic!("Invalid salary terms");
+        }
+        let mut record = Self::get_employee(env.clone(), employee);
+        record.salary = salary;
+        Self::save_employee(&env, &record);
+    }
+
+    /// Lets an employee choose the currency they are paid in
+    pub fn set_payout_currency(env: Env, employee: Address, currency: Currency) {
+        employee.require_auth();
+        let config = Self::get_payroll_config(env.clone());
+        Self::currency_token(&env, &currency);
+        if currency != config.base_currency && config.conversion.is_none() {
+            panic!("Conversion not configured");
+        }
+
+        let mut record = Self::get_employee(env.clone(), employee);
+        record.currency = currency;
+        Self::save_employee(&env, &record);
+    }
+
+    /// Removes an employee; nothing further is paid to them (owner only)
+    pub fn remove_employee(env: Env, caller: Address, employee: Address) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "remove_employee",
+            employee.clone(),
+        );
+        let mut employees = Self::get_employees(env.clone());
+        match employees.first_index_of(&employee) {
+            Some(index) => employees.remove(index),
+            None => panic!("Employee not found"),
+        };
+        env.storage()
+            .persistent()
+            .set(&PayrollDataKey::Employees, &employees);
+        env.storage()
+            .persistent()
+            .remove(&PayrollDataKey::Employee(employee));
+    }
+
+    pub fn get_employee(env: Env, employee: Address) -> Employee {
+        env.storage()
+            .persistent()
+            .get(&PayrollDataKey::Employee(employee))
+            .expect("Employee not found")
+    }
+
+    pub fn get_employees(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&PayrollDataKey::Employees)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Pays every due employee among `limit` employees starting at `offset`
+    /// (operator only, `limit` capped at 50). Each employee is paid at most one
+    /// period per run. Returns the number of employees paid.
+    pub fn run_payroll(env: Env, caller: Address, offset: u32, limit: u32) -> u32 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "run_payroll",
+            (offset, limit),
+        );
+        let config = Self::get_payroll_config(env.clone());
+        let employees = Self::get_employees(env.clone());
+        let now = env.ledger().timestamp();
+
+        let end = employees.len().min(offset + limit.min(MAX_BATCH_SIZE));
+        let mut paid = 0u32;
+        let mut total_base = 0i128;
+        for index in offset..end {
+            let mut employee = Self::get_employee(env.clone(), employees.get_unchecked(index));
+            if employee.next_pay_date > now {
+                continue;
+            }
+
+            let amount = Self::payout_amount(&env, &config, &employee);
+            if amount > Self::get_treasury_balance(env.clone(), employee.currency.clone()) {
+                panic!("Insufficient treasury balance");
+            }
+            Self::adjust_treasury(&env, &employee.currency, -amount);
+            token::Client::new(&env, &Self::currency_token(&env, &employee.currency)).transfer(
+                &env.current_contract_address(),
+                &employee.address,
+                &amount,
+            );
+
+            let pay_date = employee.next_pay_date;
+            employee.next_pay_date += employee.pay_interval;
+            employee.total_paid_base += employee.salary;
+            Self::save_employee(&env, &employee);
+            paid += 1;
+            total_base += employee.salary;
+
+            let event = DeFiEvent::Payroll(PayrollEvent::SalaryPaid(SalaryPaidData {
+                employee: employee.address,
+                currency: employee.currency,
+                amount,
+                base_amount: employee.salary,
+                pay_date,
+            }));
+            EventEmitter::emit_event(&env, PAYROLL_TOPIC, event);
+        }
+
+        let event = DeFiEvent::Payroll(PayrollEvent::RunCompleted(PayrollRunData {
+            offset,
+            employees_paid: paid,
+            total_base_amount: total_base,
+            run_at: now,
+        }));
+        EventEmitter::emit_event(&env, PAYROLL_TOPIC, event);
+
+        paid
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_payroll_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_payroll_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_payroll_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_payroll_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_payroll_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    // Salary converted to the employee's currency at the conversion contract's
+    // current rate
+    fn payout_amount(env: &Env, config: &PayrollConfig, employee: &Employee) -> i128 {
+        if employee.currency == config.base_currency {
+            return employee.salary;
+        }
+        let conversion = config
+            .conversion
+            .clone()
+            .expect("Conversion not configured");
+        let rate = ConversionContractClient::new(env, &conversion)
+            .conversion_rate(&config.base_currency, &employee.currency)
+            .rate;
+        employee.salary * rate / RATE_PRECISION
+    }
+
+    fn currency_token(env: &Env, currency: &Currency) -> Address {
+        Self::get_currency_token(env.clone(), currency.clone())
+            .expect("No token registered for currency")
+    }
+
+    fn adjust_treasury(env: &Env, currency: &Currency, delta: i128) {
+        let balance = Self::get_treasury_balance(env.clone(), currency.clone()) + delta;
+        env.storage()
+            .instance()
+            .set(&PayrollDataKey::Treasury(currency.clone()), &balance);
+    }
+
+    fn save_employee(env: &Env, employee: &Employee) {
+        env.storage().persistent().set(
+            &PayrollDataKey::Employee(employee.address.clone()),
+            employee,
+        );
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_payroll_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_payroll_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_payroll_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_payroll_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// Treasury balances held for salaries in `token`
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mut owed = 0;
+        for currency in Currency::ALL {
+            if Self::get_currency_token(env.clone(), currency.clone()).as_ref() == Some(token) {
+                owed += Self::get_treasury_balance(env.clone(), currency);
+            }
+        }
+        Some(owed)
+    }
+}
@@ -1,7 +1,20 @@
-use soroban_sdk::{contract, contractimpl, contractmeta, contracttype, log, Address, Env, Vec};
-
+use soroban_sdk::{
+    contract, contractimpl, contractmeta, contracttype, log, token, Address, BytesN, Env, Val, Vec,
+};
+
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::blocklist::BlocklistCheck;
+use crate::bps::Bps;
 use crate::conversion::Currency;
-use crate::utils::{validate_address, validate_positive_amount};
+use crate::errors::{fail, or_overflow, AppError};
+use crate::guardian::{GuardianCheck, Subsystem};
+use crate::snapshot::{ConfigSnapshots, SnapshotInfo};
+use crate::storage;
+use crate::upgrade::Upgrade;
+use crate::utils::{
+    checked_add, checked_mul_div, checked_sub, validate_address, validate_positive_amount,
+};
 
 /// Liquidity pool for a specific currency
 #[contracttype]
@@ -9,8 +22,10 @@ use crate::utils::{validate_address, validate_positive_amount};
 pub struct LiquidityPool {
     /// Currency of the pool
     pub currency: Currency,
-    /// Total liquidity in the pool
+    /// Total liquidity in the pool, including yield compounded into it
     pub total_liquidity: i128,
+    /// Shares issued against `total_liquidity`
+    pub total_shares: i128,
     /// Available liquidity for conversions
     pub available_liquidity: i128,
     /// Reserved liquidity (locked in active conversions)
@@ -35,8 +50,10 @@ pub struct LiquidityPosition {
     pub provider: Address,
     /// Currency of the position
     pub currency: Currency,
-    /// Amount of liquidity provided
+    /// Value of the position's shares in the pool currency
     pub liquidity_amount: i128,
+    /// Pool shares held; they gain value as yield is compounded into the pool
+    pub shares: i128,
     /// Share of the pool (basis points)
     pub pool_share_bps: u32,
     /// Timestamp when liquidity was added
@@ -49,6 +66,58 @@ pub struct LiquidityPosition {
     pub lock_until: u64,
 }
 
+/// `LiquidityPool` as storage version 1 stored it, without shares
+#[contracttype]
+#[derive(Clone)]
+struct LiquidityPoolV1 {
+    currency: Currency,
+    total_liquidity: i128,
+    available_liquidity: i128,
+    reserved_liquidity: i128,
+    provider_count: u32,
+    created_at: u64,
+    last_activity_at: u64,
+    min_liquidity_threshold: i128,
+    utilization_rate_bps: u32,
+}
+
+/// `LiquidityPosition` as storage version 1 stored it, without shares
+#[contracttype]
+#[derive(Clone)]
+struct LiquidityPositionV1 {
+    provider: Address,
+    currency: Currency,
+    liquidity_amount: i128,
+    pool_share_bps: u32,
+    added_at: u64,
+    last_modified_at: u64,
+    accumulated_rewards: i128,
+    lock_until: u64,
+}
+
+/// What `remove_liquidity` would do if called now, computed without
+/// changing state
+#[contracttype]
+#[derive(Clone)]
+pub struct RemoveLiquidityPreview {
+    /// The position afterwards, with rewards settled and its new share
+    pub position: LiquidityPosition,
+    /// The pool afterwards
+    pub pool: LiquidityPool,
+    /// Rewards the provider could claim afterwards
+    pub pending_rewards: i128,
+}
+
+/// A provider's reward checkpoint in one pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardAccount {
+    /// Pool reward index when the provider's rewards were last settled
+    pub index: i128,
+    /// Rewards settled but not yet claimed
+    pub unclaimed: i128,
+}
+
 /// Pool manager configuration
 #[contracttype]
 #[derive(Clone)]
@@ -109,6 +178,18 @@ pub enum PoolDataKey {
     ProviderRewards(Address),
     /// List of all providers for a specific currency
     CurrencyProviders(Currency),
+    /// Shared blocklist checked before accepting liquidity
+    Blocklist,
+    /// Guardian whose pools breaker halts this contract
+    Guardian,
+    /// Rewards accrued per pool share, scaled by `REWARD_INDEX_PRECISION`
+    RewardIndex(Currency),
+    /// Reward checkpoint for provider and currency
+    Rewards(Address, Currency),
+    /// Token a currency's pool is supplied and withdrawn in
+    Token(Currency),
+    /// Pool tokens held for rewards accrued but not yet claimed
+    RewardReserve(Currency),
 }
 
 #[contract]
@@ -121,12 +202,16 @@ contractmeta!(
 );
 
 const DEFAULT_MIN_LIQUIDITY: i128 = 100_000_000; // 1 unit with 8 decimals
-const DEFAULT_MAX_LIQUIDITY: i128 = 1_000_000_000_000; // 10,000 units with 8 decimals
-const DEFAULT_LOCK_PERIOD: u64 = 86400; // 24 hours
-const DEFAULT_REWARD_RATE_BPS: u32 = 10; // 0.1%
 const DEFAULT_UTILIZATION_WARNING_BPS: u32 = 8000; // 80%
-const MAX_UTILIZATION_BPS: u32 = 9500; // 95%
 const BASIS_POINTS_DIVISOR: i128 = 10000;
+const MAX_REWARD_RATE_BPS: u32 = 1000; // 10%
+/// Scale of the per-share reward index
+const REWARD_INDEX_PRECISION: i128 = 1_000_000_000_000;
+/// Scale of the share price reported by `get_share_price`
+const SHARE_PRICE_PRECISION: i128 = 1_000_000_000;
+
+// Bump when the storage layout changes and add a step to `migrate_pool`
+const STORAGE_VERSION: u32 = 3;
 
 #[contractimpl]
 impl PoolManagerContract {
@@ -142,14 +227,6 @@ impl PoolManagerContract {
         admin.require_auth();
         validate_address(&env, &admin).unwrap();
 
-        if min_liquidity <= 0 || max_liquidity <= min_liquidity {
-            panic!("Invalid liquidity limits");
-        }
-
-        if reward_rate_bps > 1000 {
-            panic!("Reward rate too high, maximum is 10%");
-        }
-
         let config = PoolManagerConfig {
             admin: admin.clone(),
             min_liquidity_amount: min_liquidity,
@@ -159,6 +236,7 @@ impl PoolManagerContract {
             utilization_warning_bps: DEFAULT_UTILIZATION_WARNING_BPS,
             is_paused: false,
         };
+        Self::check_config(&env, &config);
 
         // Initialize active currencies list
         let active_currencies: Vec<Currency> = Vec::new(&env);
@@ -171,6 +249,8 @@ impl PoolManagerContract {
         env.storage()
             .instance()
             .set(&PoolDataKey::PositionCounter, &0u64);
+        AccessControl::init_owner(&env, &admin);
+        Upgrade::init_version(&env, STORAGE_VERSION);
 
         log!(&env, "Pool manager initialized by admin: {}", admin);
         config
@@ -184,21 +264,73 @@ impl PoolManagerContract {
         amount: i128,
         lock_period: Option<u64>,
     ) -> LiquidityPosition {
+        Self::require_operational(&env);
         provider.require_auth();
 
+        let lock_period =
+            lock_period.unwrap_or(Self::get_pool_config_internal(&env).default_lock_period);
+        let lock_until = env.ledger().timestamp() + lock_period;
+        Self::supply(
+            env,
+            provider.clone(),
+            provider,
+            currency,
+            amount,
+            Some(lock_until),
+        )
+    }
+
+    /// Add liquidity on a provider's behalf (fee reporters only), leaving any
+    /// lock on their position as it is. Lets the conversion contract put
+    /// users' idle balances to work; the tokens come from the reporter.
+    pub fn supply_liquidity_for(
+        env: Env,
+        reporter: Address,
+        provider: Address,
+        currency: Currency,
+        amount: i128,
+    ) -> LiquidityPosition {
+        Self::require_operational(&env);
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        Self::supply(env, reporter, provider, currency, amount, None)
+    }
+
+    // Take `amount` of the pool's token from `funder` and credit it to
+    // `provider`'s position
+    fn supply(
+        env: Env,
+        funder: Address,
+        provider: Address,
+        currency: Currency,
+        amount: i128,
+        lock_until: Option<u64>,
+    ) -> LiquidityPosition {
         let config = Self::get_pool_config_internal(&env);
         if config.is_paused {
-            panic!("Pool manager is paused");
+            fail(&env, AppError::Paused, "Pool manager is paused");
+        }
+        if let Some(blocklist) = Self::get_pool_blocklist(env.clone()) {
+            if BlocklistCheck::any_blocked(&env, &blocklist, &[&provider]) {
+                fail(&env, AppError::Unauthorized, "Address is blocked");
+            }
         }
 
         validate_positive_amount(amount).unwrap();
 
         if amount < config.min_liquidity_amount || amount > config.max_liquidity_amount {
-            panic!("Amount outside allowed liquidity limits");
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Amount outside allowed liquidity limits",
+            );
         }
 
         let current_time = env.ledger().timestamp();
-        let lock_until = lock_period.unwrap_or(config.default_lock_period) + current_time;
+        token::Client::new(&env, &Self::pool_token(&env, &currency)).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
 
         // Get or create pool for currency
         let mut pool = Self::get_or_create_pool(&env, &currency);
@@ -206,39 +338,47 @@ impl PoolManagerContract {
         // Get or create provider position
         let mut position = Self::get_or_create_position(&env, &provider, &currency);
 
+        // Mint shares at the price before this deposit
+        let shares = Self::shares_for(&env, &pool, amount);
+        if shares == 0 {
+            fail(
+                &env,
+                AppError::InvalidAmount,
+                "Amount too small to mint a pool share",
+            );
+        }
+
         // Update pool totals
-        pool.total_liquidity += amount;
-        pool.available_liquidity += amount;
+        pool.total_liquidity = or_overflow(&env, checked_add(pool.total_liquidity, amount));
+        pool.total_shares = or_overflow(&env, checked_add(pool.total_shares, shares));
+        pool.available_liquidity = or_overflow(&env, checked_add(pool.available_liquidity, amount));
         pool.last_activity_at = current_time;
 
-        if position.liquidity_amount == 0 {
+        // Update the providers list if this is a new provider
+        if position.shares == 0 {
             pool.provider_count += 1;
+            Self::add_provider_to_currency(&env, &provider, &currency);
         }
+        Self::settle_rewards(&env, &mut position);
 
         // Update position
-        position.liquidity_amount += amount;
+        position.shares = or_overflow(&env, checked_add(position.shares, shares));
         position.last_modified_at = current_time;
-        position.lock_until = lock_until;
-
-        // Update the providers list if this is a new provider
-        if position.liquidity_amount == amount {
-            // This is a new provider
-            Self::add_provider_to_currency(&env, &provider, &currency);
+        if let Some(lock_until) = lock_until {
+            position.lock_until = lock_until;
         }
 
-        // Store position first
-        env.storage().instance().set(
-            &PoolDataKey::Position(provider.clone(), currency.clone()),
-            &position,
-        );
-
-        // Recalculate shares for all providers in this currency pool
-        Self::recalculate_all_shares(&env, &currency, pool.total_liquidity);
-
         // Update utilization rate
-        pool.utilization_rate_bps = Self::calculate_utilization_rate(&pool);
+        pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &pool);
 
-        // Store updates
+        // Other positions' value and share follow from the pool totals, so
+        // only this one is written
+        let updated_position = Self::valued(&env, position, &pool);
+        storage::write(
+            &env,
+            &PoolDataKey::Position(provider.clone(), currency.clone()),
+            &updated_position,
+        );
         env.storage()
             .instance()
             .set(&PoolDataKey::Pool(currency.clone()), &pool);
@@ -246,9 +386,6 @@ impl PoolManagerContract {
         // Update active currencies if this is a new pool
         Self::update_active_currencies(&env, &currency);
 
-        // Get updated position to get correct share
-        let updated_position = Self::get_position_internal(&env, &provider, &currency);
-
         // Emit event
         Self::publish_pool_event(
             &env,
@@ -289,87 +426,87 @@ impl PoolManagerContract {
         currency: Currency,
         amount: i128,
     ) -> LiquidityPosition {
+        Self::require_operational(&env);
         provider.require_auth();
+        Self::take(env, provider.clone(), provider, currency, amount, true)
+    }
+
+    /// Remove liquidity on a provider's behalf (fee reporters only), even
+    /// while their position is locked. The tokens go to the reporter, which
+    /// must only take back what it supplied through `supply_liquidity_for`.
+    pub fn withdraw_liquidity_for(
+        env: Env,
+        reporter: Address,
+        provider: Address,
+        currency: Currency,
+        amount: i128,
+    ) -> LiquidityPosition {
+        Self::require_operational(&env);
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        Self::take(env, provider, reporter, currency, amount, false)
+    }
 
+    // Burn the shares of `provider`'s position worth `amount` and pay that
+    // much of the pool's token to `to`
+    fn take(
+        env: Env,
+        provider: Address,
+        to: Address,
+        currency: Currency,
+        amount: i128,
+        honour_lock: bool,
+    ) -> LiquidityPosition {
         let config = Self::get_pool_config_internal(&env);
         if config.is_paused {
-            panic!("Pool manager is paused");
+            fail(&env, AppError::Paused, "Pool manager is paused");
         }
 
         validate_positive_amount(amount).unwrap();
 
         let current_time = env.ledger().timestamp();
 
-        // Get provider position
+        // Get provider position and pool
         let mut position = Self::get_position_internal(&env, &provider, &currency);
-
-        if position.lock_until > current_time {
-            panic!("Liquidity is still locked");
-        }
-
-        if position.liquidity_amount < amount {
-            panic!("Insufficient liquidity to remove");
-        }
-
-        // Get pool
         let mut pool = Self::get_pool_internal(&env, &currency);
-
-        if pool.available_liquidity < amount {
-            panic!("Pool has insufficient available liquidity");
+        if honour_lock {
+            Self::check_unlocked(&env, &position);
         }
+        Self::check_removal(&env, &position, &pool, amount);
+        let shares = Self::shares_to_burn(&env, &pool, amount);
 
         // Update pool totals
-        pool.total_liquidity -= amount;
-        pool.available_liquidity -= amount;
+        pool.total_liquidity = or_overflow(&env, checked_sub(pool.total_liquidity, amount));
+        pool.total_shares = or_overflow(&env, checked_sub(pool.total_shares, shares));
+        pool.available_liquidity = or_overflow(&env, checked_sub(pool.available_liquidity, amount));
         pool.last_activity_at = current_time;
 
-        // Update position
-        position.liquidity_amount -= amount;
+        // Update position, banking what it earned on the old shares first
+        Self::settle_rewards(&env, &mut position);
+        position.shares = or_overflow(&env, checked_sub(position.shares, shares));
         position.last_modified_at = current_time;
 
-        if position.liquidity_amount == 0 {
-            pool.provider_count -= 1;
-        }
+        // Update utilization rate
+        pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &pool);
 
-        // Handle provider removal if they have no liquidity left
-        if position.liquidity_amount == 0 {
+        let key = PoolDataKey::Position(provider.clone(), currency.clone());
+        if position.shares == 0 {
+            // Remove the provider if they have no shares left
+            pool.provider_count -= 1;
             Self::remove_provider_from_currency(&env, &provider, &currency);
+            env.storage().persistent().remove(&key);
         }
-
-        // Store or remove position
-        if position.liquidity_amount == 0 {
-            // Remove position if no liquidity left
-            env.storage()
-                .instance()
-                .remove(&PoolDataKey::Position(provider.clone(), currency.clone()));
-        } else {
-            // Store updated position
-            env.storage().instance().set(
-                &PoolDataKey::Position(provider.clone(), currency.clone()),
-                &position,
-            );
+        let updated_position = Self::valued(&env, position, &pool);
+        if updated_position.shares > 0 {
+            storage::write(&env, &key, &updated_position);
         }
-
-        // Recalculate shares for all providers in this currency pool
-        Self::recalculate_all_shares(&env, &currency, pool.total_liquidity);
-
-        // Update utilization rate
-        pool.utilization_rate_bps = Self::calculate_utilization_rate(&pool);
-
-        // Store updates
         env.storage()
             .instance()
             .set(&PoolDataKey::Pool(currency.clone()), &pool);
-
-        // Get updated position for correct share (if still exists)
-        let updated_position = if position.liquidity_amount > 0 {
-            Self::get_position_internal(&env, &provider, &currency)
-        } else {
-            // For removed positions, set share to 0
-            let mut removed_position = position.clone();
-            removed_position.pool_share_bps = 0;
-            removed_position
-        };
+        token::Client::new(&env, &Self::pool_token(&env, &currency)).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
 
         // Emit event
         Self::publish_pool_event(
@@ -393,6 +530,57 @@ impl PoolManagerContract {
         updated_position
     }
 
+    /// Preview `remove_liquidity` for the frontend. Fails with the same errors
+    /// the removal itself would hit, apart from the provider's authorization.
+    pub fn simulate_remove_liquidity(
+        env: Env,
+        provider: Address,
+        currency: Currency,
+        amount: i128,
+    ) -> RemoveLiquidityPreview {
+        Self::require_operational(&env);
+        if Self::get_pool_config_internal(&env).is_paused {
+            fail(&env, AppError::Paused, "Pool manager is paused");
+        }
+        validate_positive_amount(amount).unwrap();
+
+        let current_time = env.ledger().timestamp();
+        let mut position = Self::get_position_internal(&env, &provider, &currency);
+        let mut pool = Self::get_pool_internal(&env, &currency);
+        Self::check_unlocked(&env, &position);
+        Self::check_removal(&env, &position, &pool, amount);
+        let shares = Self::shares_to_burn(&env, &pool, amount);
+
+        pool.total_liquidity = or_overflow(&env, checked_sub(pool.total_liquidity, amount));
+        pool.total_shares = or_overflow(&env, checked_sub(pool.total_shares, shares));
+        pool.available_liquidity = or_overflow(&env, checked_sub(pool.available_liquidity, amount));
+        pool.last_activity_at = current_time;
+        pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &pool);
+
+        // Settling banks everything pending on the old shares
+        let pending_rewards =
+            Self::get_pending_rewards(env.clone(), provider.clone(), currency.clone());
+        let unclaimed = Self::get_reward_account(&env, &provider, &currency).unclaimed;
+        position.accumulated_rewards = or_overflow(
+            &env,
+            checked_add(
+                position.accumulated_rewards,
+                or_overflow(&env, checked_sub(pending_rewards, unclaimed)),
+            ),
+        );
+        position.shares = or_overflow(&env, checked_sub(position.shares, shares));
+        position.last_modified_at = current_time;
+        if position.shares == 0 {
+            pool.provider_count -= 1;
+        }
+
+        RemoveLiquidityPreview {
+            position: Self::valued(&env, position, &pool),
+            pool,
+            pending_rewards,
+        }
+    }
+
     /// Update pool balance during conversion operations
     pub fn update_pool_on_conversion(
         env: Env,
@@ -401,6 +589,7 @@ impl PoolManagerContract {
         from_amount: i128,
         to_amount: i128,
     ) -> (LiquidityPool, LiquidityPool) {
+        Self::require_operational(&env);
         // This function should be called by the conversion contract
         // For now, we'll allow any caller but in production this should be restricted
 
@@ -409,22 +598,31 @@ impl PoolManagerContract {
         // Update source currency pool (liquidity consumed)
         let mut from_pool = Self::get_pool_internal(&env, &from_currency);
         if from_pool.available_liquidity < from_amount {
-            panic!("Insufficient pool liquidity for conversion");
+            fail(
+                &env,
+                AppError::InsufficientBalance,
+                "Insufficient pool liquidity for conversion",
+            );
         }
 
-        from_pool.available_liquidity -= from_amount;
-        from_pool.reserved_liquidity += from_amount;
+        from_pool.available_liquidity = or_overflow(
+            &env,
+            checked_sub(from_pool.available_liquidity, from_amount),
+        );
+        from_pool.reserved_liquidity =
+            or_overflow(&env, checked_add(from_pool.reserved_liquidity, from_amount));
         from_pool.last_activity_at = current_time;
-        from_pool.utilization_rate_bps = Self::calculate_utilization_rate(&from_pool);
+        from_pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &from_pool);
 
         // Update target currency pool (liquidity added)
         let mut to_pool = Self::get_pool_internal(&env, &to_currency);
-        to_pool.available_liquidity += to_amount;
+        to_pool.available_liquidity =
+            or_overflow(&env, checked_add(to_pool.available_liquidity, to_amount));
         if to_pool.reserved_liquidity >= to_amount {
             to_pool.reserved_liquidity -= to_amount;
         }
         to_pool.last_activity_at = current_time;
-        to_pool.utilization_rate_bps = Self::calculate_utilization_rate(&to_pool);
+        to_pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &to_pool);
 
         // Store updates
         env.storage()
@@ -465,42 +663,215 @@ impl PoolManagerContract {
         (from_pool, to_pool)
     }
 
-    /// Distribute rewards to liquidity providers
-    pub fn distribute_rewards(
+    /// Record conversion fees earned in `currency` (fee reporters only). The
+    /// providers' share of `fee` is spread over the pool's liquidity by raising
+    /// its reward index, so every provider accrues without being visited, and
+    /// the reporter pays that share into the reward reserve in the pool's
+    /// token. Returns the amount added to provider rewards.
+    pub fn report_pool_fees(env: Env, reporter: Address, currency: Currency, fee: i128) -> i128 {
+        Self::require_operational(&env);
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        validate_positive_amount(fee).unwrap();
+
+        let reward = Self::fee_reward(&env, fee);
+        let accrued = Self::accrue_to_pool(&env, &currency, reward);
+        if accrued > 0 {
+            Self::reserve_rewards(&env, &reporter, &currency, accrued);
+        }
+        accrued
+    }
+
+    /// What `report_pool_fees` would take from its reporter for `fee`: the
+    /// providers' share, or nothing while the `currency` pool has no shares
+    pub fn get_pool_fee_reward(env: Env, currency: Currency, fee: i128) -> i128 {
+        let pool: Option<LiquidityPool> =
+            env.storage().instance().get(&PoolDataKey::Pool(currency));
+        match pool {
+            Some(pool) if pool.total_shares > 0 && fee > 0 => Self::fee_reward(&env, fee),
+            _ => 0,
+        }
+    }
+
+    /// Record spread earned on conversions into `currency` (fee reporters
+    /// only). Unlike fees, all of it goes to the pool's providers, so the
+    /// reporter pays it into the reward reserve in the pool's token. Nothing
+    /// is taken from an empty pool's reporter; returns the amount accrued.
+    pub fn report_pool_spread(
         env: Env,
+        reporter: Address,
         currency: Currency,
-        total_fee_amount: i128,
-    ) -> Vec<(Address, i128)> {
-        let config = Self::get_pool_config_internal(&env);
-        config.admin.require_auth();
+        spread: i128,
+    ) -> i128 {
+        Self::require_operational(&env);
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        validate_positive_amount(spread).unwrap();
+
+        let accrued = Self::accrue_to_pool(&env, &currency, spread);
+        if accrued > 0 {
+            Self::reserve_rewards(&env, &reporter, &currency, accrued);
+        }
+        accrued
+    }
 
-        let pool = Self::get_pool_internal(&env, &currency);
-        let reward_amount =
-            (total_fee_amount * i128::from(config.provider_reward_rate_bps)) / BASIS_POINTS_DIVISOR;
+    /// Pay out everything `provider` has accrued in the `currency` pool, in the
+    /// pool's token from its reward reserve. The claimed amount is also
+    /// reported in a `ProviderRewarded` event.
+    pub fn claim_rewards(env: Env, provider: Address, currency: Currency) -> i128 {
+        Self::require_operational(&env);
+        provider.require_auth();
 
-        if reward_amount <= 0 {
-            return Vec::new(&env);
+        let key = PoolDataKey::Position(provider.clone(), currency.clone());
+        if let Some(mut position) = storage::read::<PoolDataKey, LiquidityPosition>(&env, &key) {
+            Self::settle_rewards(&env, &mut position);
+            storage::write(&env, &key, &position);
         }
 
-        let rewards: Vec<(Address, i128)> = Vec::new(&env);
-        let _active_currencies: Vec<Currency> = env
-            .storage()
+        let rewards_key = PoolDataKey::Rewards(provider.clone(), currency.clone());
+        let Some(mut account) = storage::read::<PoolDataKey, RewardAccount>(&env, &rewards_key)
+        else {
+            return 0;
+        };
+        let claimed = account.unclaimed;
+        if claimed > 0 {
+            let reserve = Self::get_pool_reward_reserve(env.clone(), currency.clone());
+            if claimed > reserve {
+                fail(
+                    &env,
+                    AppError::InsufficientBalance,
+                    "Reward reserve below claim",
+                );
+            }
+            env.storage().instance().set(
+                &PoolDataKey::RewardReserve(currency.clone()),
+                &or_overflow(&env, checked_sub(reserve, claimed)),
+            );
+            token::Client::new(&env, &Self::pool_token(&env, &currency)).transfer(
+                &env.current_contract_address(),
+                &provider,
+                &claimed,
+            );
+            account.unclaimed = 0;
+            storage::write(&env, &rewards_key, &account);
+            Self::publish_pool_event(
+                &env,
+                PoolManagerEvent::ProviderRewarded(provider, currency, claimed),
+            );
+        }
+        claimed
+    }
+
+    /// Rewards `provider` could claim from the `currency` pool right now
+    pub fn get_pending_rewards(env: Env, provider: Address, currency: Currency) -> i128 {
+        let shares = storage::read::<PoolDataKey, LiquidityPosition>(
+            &env,
+            &PoolDataKey::Position(provider.clone(), currency.clone()),
+        )
+        .map_or(0, |position| position.shares);
+        let account = Self::get_reward_account(&env, &provider, &currency);
+        let index = Self::get_reward_index(env.clone(), currency);
+        account.unclaimed
+            + or_overflow(
+                &env,
+                checked_mul_div(shares, index - account.index, REWARD_INDEX_PRECISION),
+            )
+    }
+
+    /// Compound yield earned in `currency` into its pool (fee reporters only).
+    /// The yield must already be held by this contract in the pool's token,
+    /// beyond the pool's liquidity. Unlike reported fees it is not claimed
+    /// separately: it raises the value of every share at once. Returns the
+    /// new share price.
+    pub fn compound_pool_yield(
+        env: Env,
+        reporter: Address,
+        currency: Currency,
+        amount: i128,
+    ) -> i128 {
+        Self::require_operational(&env);
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        validate_positive_amount(amount).unwrap();
+
+        let mut pool = Self::get_pool_internal(&env, &currency);
+        if pool.total_shares == 0 {
+            fail(
+                &env,
+                AppError::InvalidState,
+                "Pool has no shares to compound into",
+            );
+        }
+        if amount > Self::unallocated(&env, &currency, &pool) {
+            fail(
+                &env,
+                AppError::InsufficientBalance,
+                "Yield is not held by the pool",
+            );
+        }
+        pool.total_liquidity = or_overflow(&env, checked_add(pool.total_liquidity, amount));
+        pool.available_liquidity = or_overflow(&env, checked_add(pool.available_liquidity, amount));
+        pool.last_activity_at = env.ledger().timestamp();
+        pool.utilization_rate_bps = Self::calculate_utilization_rate(&env, &pool);
+        env.storage()
             .instance()
-            .get(&PoolDataKey::ActiveCurrencies)
-            .unwrap_or_else(|| Vec::new(&env));
+            .set(&PoolDataKey::Pool(currency.clone()), &pool);
+
+        Self::publish_pool_event(
+            &env,
+            PoolManagerEvent::PoolBalanceUpdated(
+                currency,
+                pool.total_liquidity,
+                pool.available_liquidity,
+                pool.reserved_liquidity,
+            ),
+        );
+        Self::share_price(&env, &pool)
+    }
 
-        // Find all positions for this currency
-        // Note: In a real implementation, you'd want to maintain an index of positions per currency
-        // For this example, we'll use a simplified approach
+    /// Value of one share of the `currency` pool, scaled by 10^9
+    pub fn get_share_price(env: Env, currency: Currency) -> i128 {
+        Self::share_price(&env, &Self::get_pool_internal(&env, &currency))
+    }
 
-        log!(
+    /// Pool tokens held for the `currency` pool's unclaimed rewards
+    pub fn get_pool_reward_reserve(env: Env, currency: Currency) -> i128 {
+        env.storage()
+            .instance()
+            .get(&PoolDataKey::RewardReserve(currency))
+            .unwrap_or(0)
+    }
+
+    pub fn get_reward_index(env: Env, currency: Currency) -> i128 {
+        env.storage()
+            .instance()
+            .get(&PoolDataKey::RewardIndex(currency))
+            .unwrap_or(0)
+    }
+
+    /// Set or clear the token the `currency` pool is supplied and withdrawn
+    /// in (owner only). It cannot change while the pool holds liquidity.
+    pub fn set_pool_token(env: Env, caller: Address, currency: Currency, token: Option<Address>) {
+        AccessControl::require_audited_role(
             &env,
-            "Distributing {} units in rewards to {} providers",
-            reward_amount,
-            pool.provider_count
+            Role::Owner,
+            &caller,
+            "set_pool_token",
+            (currency.clone(), token.clone()),
         );
+        let pool: Option<LiquidityPool> = env
+            .storage()
+            .instance()
+            .get(&PoolDataKey::Pool(currency.clone()));
+        if pool.is_some_and(|pool| pool.total_liquidity > 0) {
+            fail(&env, AppError::InvalidState, "Pool already holds liquidity");
+        }
+        let key = PoolDataKey::Token(currency);
+        match token {
+            Some(token) => env.storage().instance().set(&key, &token),
+            None => env.storage().instance().remove(&key),
+        }
+    }
 
-        rewards
+    pub fn get_pool_token(env: Env, currency: Currency) -> Option<Address> {
+        env.storage().instance().get(&PoolDataKey::Token(currency))
     }
 
     /// Get liquidity pool information
@@ -510,7 +881,21 @@ impl PoolManagerContract {
 
     /// Get liquidity position for a provider
     pub fn get_position(env: Env, provider: Address, currency: Currency) -> LiquidityPosition {
-        Self::get_position_internal(&env, &provider, &currency)
+        let position = Self::get_position_internal(&env, &provider, &currency);
+        Self::valued(&env, position, &Self::get_pool_internal(&env, &currency))
+    }
+
+    /// All of a provider's positions across the active pools
+    pub fn get_provider_positions(env: Env, provider: Address) -> Vec<LiquidityPosition> {
+        let mut positions = Vec::new(&env);
+        for currency in Self::get_active_currencies(env.clone()).iter() {
+            let key = PoolDataKey::Position(provider.clone(), currency.clone());
+            if let Some(position) = storage::read(&env, &key) {
+                let pool = Self::get_pool_internal(&env, &currency);
+                positions.push_back(Self::valued(&env, position, &pool));
+            }
+        }
+        positions
     }
 
     /// Get pool manager configuration
@@ -518,6 +903,72 @@ impl PoolManagerContract {
         Self::get_pool_config_internal(&env)
     }
 
+    /// Change the liquidity limits, lock period and reward rate (owner only).
+    /// Take a snapshot first to be able to roll the change back.
+    pub fn update_pool_config(
+        env: Env,
+        caller: Address,
+        min_liquidity: i128,
+        max_liquidity: i128,
+        lock_period: u64,
+        reward_rate_bps: u32,
+    ) -> PoolManagerConfig {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "update_pool_config",
+            (min_liquidity, max_liquidity, lock_period, reward_rate_bps),
+        );
+        let config = PoolManagerConfig {
+            min_liquidity_amount: min_liquidity,
+            max_liquidity_amount: max_liquidity,
+            default_lock_period: lock_period,
+            provider_reward_rate_bps: reward_rate_bps,
+            ..Self::get_pool_config_internal(&env)
+        };
+        Self::check_config(&env, &config);
+        env.storage()
+            .instance()
+            .set(&PoolDataKey::PoolConfig, &config);
+        config
+    }
+
+    /// Save the configuration in force as a new snapshot version (owner only)
+    pub fn snapshot_pool_config(env: Env, caller: Address) -> u32 {
+        AccessControl::require_audited_role(&env, Role::Owner, &caller, "snapshot_pool_config", ());
+        ConfigSnapshots::take(&env, &Self::get_pool_config_internal(&env), &caller)
+    }
+
+    /// Put a snapshot's configuration back in force (owner only). The admin
+    /// and the emergency pause stay as they are.
+    pub fn rollback_pool_config(env: Env, caller: Address, version: u32) -> PoolManagerConfig {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "rollback_pool_config",
+            version,
+        );
+        let snapshot: PoolManagerConfig = ConfigSnapshots::restore(&env, version, &caller)
+            .unwrap_or_else(|| fail(&env, AppError::NotFound, "Snapshot not found"));
+        let current = Self::get_pool_config_internal(&env);
+        let config = PoolManagerConfig {
+            admin: current.admin,
+            is_paused: current.is_paused,
+            ..snapshot
+        };
+        Self::check_config(&env, &config);
+        env.storage()
+            .instance()
+            .set(&PoolDataKey::PoolConfig, &config);
+        config
+    }
+
+    pub fn get_pool_config_snapshot(env: Env, version: u32) -> Option<SnapshotInfo> {
+        ConfigSnapshots::info(&env, version)
+    }
+
     /// Get all active currencies with pools
     pub fn get_active_currencies(env: Env) -> Vec<Currency> {
         env.storage()
@@ -527,9 +978,9 @@ impl PoolManagerContract {
     }
 
     /// Emergency pause functionality
-    pub fn emergency_pause(env: Env) -> bool {
+    pub fn emergency_pause(env: Env, caller: Address) -> bool {
+        AccessControl::require_audited_role(&env, Role::Pauser, &caller, "emergency_pause", ());
         let mut config = Self::get_pool_config_internal(&env);
-        config.admin.require_auth();
 
         config.is_paused = true;
         env.storage()
@@ -538,17 +989,17 @@ impl PoolManagerContract {
 
         Self::publish_pool_event(
             &env,
-            PoolManagerEvent::EmergencyPauseActivated(config.admin.clone()),
+            PoolManagerEvent::EmergencyPauseActivated(caller.clone()),
         );
 
-        log!(&env, "Emergency pause activated by admin: {}", config.admin);
+        log!(&env, "Emergency pause activated by: {}", caller);
         true
     }
 
     /// Resume operations after emergency pause
-    pub fn resume_operations(env: Env) -> bool {
+    pub fn resume_operations(env: Env, caller: Address) -> bool {
+        AccessControl::require_audited_role(&env, Role::Pauser, &caller, "resume_operations", ());
         let mut config = Self::get_pool_config_internal(&env);
-        config.admin.require_auth();
 
         config.is_paused = false;
         env.storage()
@@ -557,27 +1008,267 @@ impl PoolManagerContract {
 
         Self::publish_pool_event(
             &env,
-            PoolManagerEvent::EmergencyPauseDeactivated(config.admin.clone()),
+            PoolManagerEvent::EmergencyPauseDeactivated(caller.clone()),
         );
 
-        log!(&env, "Operations resumed by admin: {}", config.admin);
+        log!(&env, "Operations resumed by: {}", caller);
         true
     }
 
+    /// Set or clear the shared blocklist checked on deposits (owner only)
+    pub fn set_pool_blocklist(env: Env, caller: Address, blocklist: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_pool_blocklist",
+            blocklist.clone(),
+        );
+        match blocklist {
+            Some(blocklist) => env
+                .storage()
+                .instance()
+                .set(&PoolDataKey::Blocklist, &blocklist),
+            None => env.storage().instance().remove(&PoolDataKey::Blocklist),
+        }
+    }
+
+    pub fn get_pool_blocklist(env: Env) -> Option<Address> {
+        env.storage().instance().get(&PoolDataKey::Blocklist)
+    }
+
+    /// Set or clear the guardian whose pools breaker halts liquidity changes (owner only)
+    pub fn set_pool_guardian(env: Env, caller: Address, guardian: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_pool_guardian",
+            guardian.clone(),
+        );
+        match guardian {
+            Some(guardian) => env
+                .storage()
+                .instance()
+                .set(&PoolDataKey::Guardian, &guardian),
+            None => env.storage().instance().remove(&PoolDataKey::Guardian),
+        }
+    }
+
+    pub fn get_pool_guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&PoolDataKey::Guardian)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_pool_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_pool_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_pool_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_pool_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_pool_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    /// Give up a role held by `account`
+    pub fn renounce_pool_role(env: Env, account: Address, role: Role) {
+        AccessControl::renounce_role(&env, &account, role);
+    }
+
+    pub fn has_pool_role(env: Env, role: Role, account: Address) -> bool {
+        AccessControl::has_role(&env, role, &account)
+    }
+
+    /// Replace the contract code (owner only)
+    pub fn upgrade_pool(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        Upgrade::upgrade(&env, &caller, new_wasm_hash);
+    }
+
+    /// Bring storage written by older code up to the current layout
+    pub fn migrate_pool(env: Env) -> u32 {
+        Upgrade::migrate(&env, STORAGE_VERSION, |env, version| {
+            // v1: admin checks moved to roles, so the existing admin becomes owner
+            if version == 1 {
+                if let Some(admin) = env
+                    .storage()
+                    .instance()
+                    .get::<_, PoolManagerConfig>(&PoolDataKey::PoolConfig)
+                    .map(|config| config.admin)
+                {
+                    AccessControl::init_owner(env, &admin);
+                }
+            }
+            // v2: provider lists and positions moved from instance to
+            // persistent storage. Positions move as stored, since they only
+            // take the current layout in v3.
+            if version == 2 {
+                for currency in Self::get_active_currencies(env.clone()).iter() {
+                    let providers = storage::move_from_instance::<_, Vec<Address>>(
+                        env,
+                        &PoolDataKey::CurrencyProviders(currency.clone()),
+                    );
+                    for provider in providers.unwrap_or_else(|| Vec::new(env)).iter() {
+                        storage::move_from_instance::<_, Val>(
+                            env,
+                            &PoolDataKey::Position(provider, currency.clone()),
+                        );
+                    }
+                }
+            }
+            // v3: pools and positions are accounted in shares, issued one per
+            // unit of liquidity already in the pool
+            if version == 3 {
+                for currency in Self::get_active_currencies(env.clone()).iter() {
+                    let pool_key = PoolDataKey::Pool(currency.clone());
+                    if let Some(pool) = env
+                        .storage()
+                        .instance()
+                        .get::<_, LiquidityPoolV1>(&pool_key)
+                    {
+                        let pool = LiquidityPool {
+                            currency: pool.currency,
+                            total_liquidity: pool.total_liquidity,
+                            total_shares: pool.total_liquidity,
+                            available_liquidity: pool.available_liquidity,
+                            reserved_liquidity: pool.reserved_liquidity,
+                            provider_count: pool.provider_count,
+                            created_at: pool.created_at,
+                            last_activity_at: pool.last_activity_at,
+                            min_liquidity_threshold: pool.min_liquidity_threshold,
+                            utilization_rate_bps: pool.utilization_rate_bps,
+                        };
+                        env.storage().instance().set(&pool_key, &pool);
+                    }
+                    let providers: Vec<Address> =
+                        storage::read(env, &PoolDataKey::CurrencyProviders(currency.clone()))
+                            .unwrap_or_else(|| Vec::new(env));
+                    for provider in providers.iter() {
+                        let key = PoolDataKey::Position(provider, currency.clone());
+                        let Some(position) = storage::read::<_, LiquidityPositionV1>(env, &key)
+                        else {
+                            continue;
+                        };
+                        let position = LiquidityPosition {
+                            provider: position.provider,
+                            currency: position.currency,
+                            liquidity_amount: position.liquidity_amount,
+                            shares: position.liquidity_amount,
+                            pool_share_bps: position.pool_share_bps,
+                            added_at: position.added_at,
+                            last_modified_at: position.last_modified_at,
+                            accumulated_rewards: position.accumulated_rewards,
+                            lock_until: position.lock_until,
+                        };
+                        storage::write(env, &key, &position);
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn get_pool_storage_version(env: Env) -> u32 {
+        Upgrade::storage_version(&env)
+    }
+
     // Private helper methods
 
+    fn require_operational(env: &Env) {
+        if let Some(guardian) = Self::get_pool_guardian(env.clone()) {
+            if GuardianCheck::is_halted(env, &guardian, Subsystem::Pools) {
+                fail(env, AppError::Paused, "Pools halted by guardian");
+            }
+        }
+    }
+
+    fn check_config(env: &Env, config: &PoolManagerConfig) {
+        if config.min_liquidity_amount <= 0
+            || config.max_liquidity_amount <= config.min_liquidity_amount
+        {
+            fail(env, AppError::InvalidConfig, "Invalid liquidity limits");
+        }
+
+        if Bps::capped(config.provider_reward_rate_bps, MAX_REWARD_RATE_BPS).is_err() {
+            fail(
+                env,
+                AppError::InvalidConfig,
+                "Reward rate too high, maximum is 10%",
+            );
+        }
+    }
+
     fn get_pool_config_internal(env: &Env) -> PoolManagerConfig {
         env.storage()
             .instance()
             .get(&PoolDataKey::PoolConfig)
-            .unwrap_or_else(|| panic!("Pool manager not initialized"))
+            .unwrap_or_else(|| {
+                fail(
+                    env,
+                    AppError::NotInitialized,
+                    "Pool manager not initialized",
+                )
+            })
+    }
+
+    fn pool_token(env: &Env, currency: &Currency) -> Address {
+        Self::get_pool_token(env.clone(), currency.clone())
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "No token for pool currency"))
+    }
+
+    // What this contract holds of the pool's token beyond the pool's
+    // liquidity and its reward reserve
+    fn unallocated(env: &Env, currency: &Currency, pool: &LiquidityPool) -> i128 {
+        let held = token::Client::new(env, &Self::pool_token(env, currency))
+            .balance(&env.current_contract_address());
+        let reserve = Self::get_pool_reward_reserve(env.clone(), currency.clone());
+        or_overflow(
+            env,
+            checked_sub(
+                held,
+                or_overflow(env, checked_add(pool.total_liquidity, reserve)),
+            ),
+        )
+    }
+
+    // Take `amount` of the pool's token from `from` into the reward reserve
+    // that claims are paid from
+    fn reserve_rewards(env: &Env, from: &Address, currency: &Currency, amount: i128) {
+        token::Client::new(env, &Self::pool_token(env, currency)).transfer(
+            from,
+            &env.current_contract_address(),
+            &amount,
+        );
+        let reserve = or_overflow(
+            env,
+            checked_add(
+                Self::get_pool_reward_reserve(env.clone(), currency.clone()),
+                amount,
+            ),
+        );
+        env.storage()
+            .instance()
+            .set(&PoolDataKey::RewardReserve(currency.clone()), &reserve);
     }
 
     fn get_pool_internal(env: &Env, currency: &Currency) -> LiquidityPool {
         env.storage()
             .instance()
             .get(&PoolDataKey::Pool(currency.clone()))
-            .unwrap_or_else(|| panic!("Pool not found for currency"))
+            .unwrap_or_else(|| fail(env, AppError::NotFound, "Pool not found for currency"))
     }
 
     fn get_or_create_pool(env: &Env, currency: &Currency) -> LiquidityPool {
@@ -589,6 +1280,7 @@ impl PoolManagerContract {
                 LiquidityPool {
                     currency: currency.clone(),
                     total_liquidity: 0,
+                    total_shares: 0,
                     available_liquidity: 0,
                     reserved_liquidity: 0,
                     provider_count: 0,
@@ -605,10 +1297,11 @@ impl PoolManagerContract {
         provider: &Address,
         currency: &Currency,
     ) -> LiquidityPosition {
-        env.storage()
-            .instance()
-            .get(&PoolDataKey::Position(provider.clone(), currency.clone()))
-            .unwrap_or_else(|| panic!("Liquidity position not found"))
+        storage::read(
+            env,
+            &PoolDataKey::Position(provider.clone(), currency.clone()),
+        )
+        .unwrap_or_else(|| fail(env, AppError::NotFound, "Liquidity position not found"))
     }
 
     fn get_or_create_position(
@@ -616,37 +1309,210 @@ impl PoolManagerContract {
         provider: &Address,
         currency: &Currency,
     ) -> LiquidityPosition {
+        storage::read(
+            env,
+            &PoolDataKey::Position(provider.clone(), currency.clone()),
+        )
+        .unwrap_or_else(|| {
+            let current_time = env.ledger().timestamp();
+            LiquidityPosition {
+                provider: provider.clone(),
+                currency: currency.clone(),
+                liquidity_amount: 0,
+                shares: 0,
+                pool_share_bps: 0,
+                added_at: current_time,
+                last_modified_at: current_time,
+                accumulated_rewards: 0,
+                lock_until: 0,
+            }
+        })
+    }
+
+    // Raise the pool's reward index by `reward` spread over its shares;
+    // nothing accrues to an empty pool. Returns the amount accrued.
+    // The providers' share of a reported fee
+    fn fee_reward(env: &Env, fee: i128) -> i128 {
+        let config = Self::get_pool_config_internal(env);
+        or_overflow(
+            env,
+            Bps::new(config.provider_reward_rate_bps).and_then(|rate| rate.of(fee)),
+        )
+    }
+
+    fn accrue_to_pool(env: &Env, currency: &Currency, reward: i128) -> i128 {
+        let pool: Option<LiquidityPool> = env
+            .storage()
+            .instance()
+            .get(&PoolDataKey::Pool(currency.clone()));
+        let Some(pool) = pool.filter(|pool| pool.total_shares > 0) else {
+            return 0;
+        };
+        if reward <= 0 {
+            return 0;
+        }
+
+        let increase = or_overflow(
+            env,
+            checked_mul_div(reward, REWARD_INDEX_PRECISION, pool.total_shares),
+        );
+        let index = or_overflow(
+            env,
+            checked_add(
+                Self::get_reward_index(env.clone(), currency.clone()),
+                increase,
+            ),
+        );
         env.storage()
             .instance()
-            .get(&PoolDataKey::Position(provider.clone(), currency.clone()))
-            .unwrap_or_else(|| {
-                let current_time = env.ledger().timestamp();
-                LiquidityPosition {
-                    provider: provider.clone(),
-                    currency: currency.clone(),
-                    liquidity_amount: 0,
-                    pool_share_bps: 0,
-                    added_at: current_time,
-                    last_modified_at: current_time,
-                    accumulated_rewards: 0,
-                    lock_until: 0,
-                }
-            })
+            .set(&PoolDataKey::RewardIndex(currency.clone()), &index);
+        reward
+    }
+
+    fn get_reward_account(env: &Env, provider: &Address, currency: &Currency) -> RewardAccount {
+        storage::read(
+            env,
+            &PoolDataKey::Rewards(provider.clone(), currency.clone()),
+        )
+        .unwrap_or(RewardAccount {
+            index: 0,
+            unclaimed: 0,
+        })
+    }
+
+    // Reasons `remove_liquidity` would refuse to take `amount` out of a position
+    fn check_unlocked(env: &Env, position: &LiquidityPosition) {
+        if position.lock_until > env.ledger().timestamp() {
+            fail(env, AppError::InvalidState, "Liquidity is still locked");
+        }
     }
 
-    fn calculate_pool_share(position_amount: i128, total_pool_amount: i128) -> u32 {
-        if total_pool_amount == 0 {
+    fn check_removal(env: &Env, position: &LiquidityPosition, pool: &LiquidityPool, amount: i128) {
+        if Self::value_of(env, pool, position.shares) < amount {
+            fail(
+                env,
+                AppError::InsufficientBalance,
+                "Insufficient liquidity to remove",
+            );
+        }
+
+        if pool.available_liquidity < amount {
+            fail(
+                env,
+                AppError::InsufficientBalance,
+                "Pool has insufficient available liquidity",
+            );
+        }
+    }
+
+    // Bank what the position earned since its last checkpoint on its current
+    // shares; runs before every change to those shares
+    fn settle_rewards(env: &Env, position: &mut LiquidityPosition) {
+        let index = Self::get_reward_index(env.clone(), position.currency.clone());
+        let mut account = Self::get_reward_account(env, &position.provider, &position.currency);
+        if account.index == index {
+            return;
+        }
+        let earned = or_overflow(
+            env,
+            checked_mul_div(
+                position.shares,
+                index - account.index,
+                REWARD_INDEX_PRECISION,
+            ),
+        );
+        account.index = index;
+        account.unclaimed = or_overflow(env, checked_add(account.unclaimed, earned));
+        position.accumulated_rewards =
+            or_overflow(env, checked_add(position.accumulated_rewards, earned));
+        storage::write(
+            env,
+            &PoolDataKey::Rewards(position.provider.clone(), position.currency.clone()),
+            &account,
+        );
+    }
+
+    fn calculate_pool_share(env: &Env, shares: i128, total_shares: i128) -> u32 {
+        if total_shares == 0 {
+            return 0;
+        }
+        or_overflow(
+            env,
+            checked_mul_div(shares, BASIS_POINTS_DIVISOR, total_shares),
+        ) as u32
+    }
+
+    fn share_price(env: &Env, pool: &LiquidityPool) -> i128 {
+        if pool.total_shares == 0 {
+            return SHARE_PRICE_PRECISION;
+        }
+        or_overflow(
+            env,
+            checked_mul_div(
+                pool.total_liquidity,
+                SHARE_PRICE_PRECISION,
+                pool.total_shares,
+            ),
+        )
+    }
+
+    // Shares `amount` buys at the current price; an empty pool issues one
+    // share per unit
+    fn shares_for(env: &Env, pool: &LiquidityPool, amount: i128) -> i128 {
+        if pool.total_shares == 0 || pool.total_liquidity == 0 {
+            return amount;
+        }
+        or_overflow(
+            env,
+            checked_mul_div(amount, pool.total_shares, pool.total_liquidity),
+        )
+    }
+
+    // Shares to burn for withdrawing `amount`, rounded up so a withdrawal
+    // never takes more than the shares it burns are worth
+    fn shares_to_burn(env: &Env, pool: &LiquidityPool, amount: i128) -> i128 {
+        let shares = Self::shares_for(env, pool, amount);
+        if Self::value_of(env, pool, shares) < amount {
+            shares + 1
+        } else {
+            shares
+        }
+    }
+
+    fn value_of(env: &Env, pool: &LiquidityPool, shares: i128) -> i128 {
+        if pool.total_shares == 0 {
             return 0;
         }
-        ((position_amount * BASIS_POINTS_DIVISOR) / total_pool_amount) as u32
+        or_overflow(
+            env,
+            checked_mul_div(shares, pool.total_liquidity, pool.total_shares),
+        )
     }
 
-    fn calculate_utilization_rate(pool: &LiquidityPool) -> u32 {
+    // The position with its value and pool share at the pool's current totals
+    fn valued(
+        env: &Env,
+        mut position: LiquidityPosition,
+        pool: &LiquidityPool,
+    ) -> LiquidityPosition {
+        position.liquidity_amount = Self::value_of(env, pool, position.shares);
+        position.pool_share_bps =
+            Self::calculate_pool_share(env, position.shares, pool.total_shares);
+        position
+    }
+
+    fn calculate_utilization_rate(env: &Env, pool: &LiquidityPool) -> u32 {
         if pool.total_liquidity == 0 {
             return 0;
         }
-        let utilized = pool.total_liquidity - pool.available_liquidity;
-        ((utilized * BASIS_POINTS_DIVISOR) / pool.total_liquidity) as u32
+        let utilized = or_overflow(
+            env,
+            checked_sub(pool.total_liquidity, pool.available_liquidity),
+        );
+        or_overflow(
+            env,
+            checked_mul_div(utilized, BASIS_POINTS_DIVISOR, pool.total_liquidity),
+        ) as u32
     }
 
     fn update_active_currencies(env: &Env, currency: &Currency) {
@@ -678,11 +1544,9 @@ impl PoolManagerContract {
     }
 
     fn add_provider_to_currency(env: &Env, provider: &Address, currency: &Currency) {
-        let mut providers: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&PoolDataKey::CurrencyProviders(currency.clone()))
-            .unwrap_or_else(|| Vec::new(env));
+        let mut providers: Vec<Address> =
+            storage::read(env, &PoolDataKey::CurrencyProviders(currency.clone()))
+                .unwrap_or_else(|| Vec::new(env));
 
         // Check if provider already exists
         let mut found = false;
@@ -695,7 +1559,8 @@ impl PoolManagerContract {
 
         if !found {
             providers.push_back(provider.clone());
-            env.storage().instance().set(
+            storage::write(
+                env,
                 &PoolDataKey::CurrencyProviders(currency.clone()),
                 &providers,
             );
@@ -703,11 +1568,9 @@ impl PoolManagerContract {
     }
 
     fn remove_provider_from_currency(env: &Env, provider: &Address, currency: &Currency) {
-        let mut providers: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&PoolDataKey::CurrencyProviders(currency.clone()))
-            .unwrap_or_else(|| Vec::new(env));
+        let providers: Vec<Address> =
+            storage::read(env, &PoolDataKey::CurrencyProviders(currency.clone()))
+                .unwrap_or_else(|| Vec::new(env));
 
         // Find and remove the provider
         let mut new_providers = Vec::new(env);
@@ -717,39 +1580,10 @@ impl PoolManagerContract {
             }
         }
 
-        env.storage().instance().set(
+        storage::write(
+            env,
             &PoolDataKey::CurrencyProviders(currency.clone()),
             &new_providers,
         );
     }
-
-    fn recalculate_all_shares(env: &Env, currency: &Currency, total_liquidity: i128) {
-        let providers: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&PoolDataKey::CurrencyProviders(currency.clone()))
-            .unwrap_or_else(|| Vec::new(env));
-
-        for provider in providers.iter() {
-            if let Some(mut position) = env
-                .storage()
-                .instance()
-                .get::<PoolDataKey, LiquidityPosition>(&PoolDataKey::Position(
-                    provider.clone(),
-                    currency.clone(),
-                ))
-            {
-                position.pool_share_bps = if total_liquidity > 0 {
-                    Self::calculate_pool_share(position.liquidity_amount, total_liquidity)
-                } else {
-                    0
-                };
-
-                env.storage().instance().set(
-                    &PoolDataKey::Position(provider.clone(), currency.clone()),
-                    &position,
-                );
-            }
-        }
-    }
 }
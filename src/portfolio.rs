@@ -0,0 +1,165 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::conversion::{ConversionContractClient, Currency};
+use crate::escrow::{EscrowClient, EscrowInfo, EscrowStatus};
+use crate::order_book::{Order, OrderBookContractClient, OrderSide};
+use crate::pool_manager::{LiquidityPosition, PoolManagerContractClient};
+use crate::rate_lock::{RateLock, RateLockContractClient};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, Vec};
+
+/// Contracts a portfolio is read from; a source left unset contributes an
+/// empty section
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioSources {
+    pub conversion: Option<Address>,
+    pub escrow: Option<Address>,
+    pub pool_manager: Option<Address>,
+    pub rate_lock: Option<Address>,
+    pub order_book: Option<Address>,
+}
+
+/// Everything a user holds across the platform at one ledger timestamp
+#[contracttype]
+pub struct Portfolio {
+    pub user: Address,
+    /// Internal conversion balances
+    pub balances: Map<Currency, i128>,
+    /// Active and disputed escrows the user sends or receives
+    pub open_escrows: Vec<EscrowInfo>,
+    pub pool_positions: Vec<LiquidityPosition>,
+    /// Rate locks that have not expired
+    pub rate_locks: Vec<RateLock>,
+    /// Orders resting on the order book
+    pub open_orders: Vec<Order>,
+    pub as_of: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PortfolioDataKey {
+    Sources,
+}
+
+#[contract]
+pub struct PortfolioContract;
+
+/// Read-only aggregation of a user's positions, so a client can load them
+/// with one simulated call instead of querying each contract
+#[contractimpl]
+impl PortfolioContract {
+    pub fn initialize_portfolio(env: Env, admin: Address, sources: PortfolioSources) {
+        if env.storage().instance().has(&PortfolioDataKey::Sources) {
+            panic!("Portfolio already initialized");
+        }
+        admin.require_auth();
+        AccessControl::init_owner(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&PortfolioDataKey::Sources, &sources);
+    }
+
+    /// Point the view at a different set of contracts (owner only)
+    pub fn set_portfolio_sources(env: Env, caller: Address, sources: PortfolioSources) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_portfolio_sources",
+            sources.clone(),
+        );
+        env.storage()
+            .instance()
+            .set(&PortfolioDataKey::Sources, &sources);
+    }
+
+    pub fn get_portfolio_sources(env: Env) -> PortfolioSources {
+        env.storage()
+            .instance()
+            .get(&PortfolioDataKey::Sources)
+            .expect("Portfolio not initialized")
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_portfolio_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_portfolio_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_portfolio_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    pub fn get_portfolio(env: Env, user: Address) -> Portfolio {
+        let sources = Self::get_portfolio_sources(env.clone());
+        let now = env.ledger().timestamp();
+
+        let balances = match &sources.conversion {
+            Some(conversion) => {
+                ConversionContractClient::new(&env, conversion)
+                    .get_user_balance(&user)
+                    .balances
+            }
+            None => Map::new(&env),
+        };
+
+        let mut open_escrows = Vec::new(&env);
+        if let Some(escrow) = &sources.escrow {
+            for info in EscrowClient::new(&env, escrow)
+                .get_escrows_by_participant(&user)
+                .iter()
+            {
+                if matches!(info.status, EscrowStatus::Active | EscrowStatus::Disputed) {
+                    open_escrows.push_back(info);
+                }
+            }
+        }
+
+        let pool_positions = match &sources.pool_manager {
+            Some(pool_manager) => {
+                PoolManagerContractClient::new(&env, pool_manager).get_provider_positions(&user)
+            }
+            None => Vec::new(&env),
+        };
+
+        let mut rate_locks = Vec::new(&env);
+        if let Some(rate_lock) = &sources.rate_lock {
+            for lock in RateLockContractClient::new(&env, rate_lock)
+                .get_locks(&user)
+                .iter()
+            {
+                if now <= lock.expires_at {
+                    rate_locks.push_back(lock);
+                }
+            }
+        }
+
+        let mut open_orders = Vec::new(&env);
+        if let Some(order_book) = &sources.order_book {
+            let client = OrderBookContractClient::new(&env, order_book);
+            for side in [OrderSide::Buy, OrderSide::Sell] {
+                for order in client.get_order_book_side(&side).iter() {
+                    if order.owner == user {
+                        open_orders.push_back(order);
+                    }
+                }
+            }
+        }
+
+        Portfolio {
+            user,
+            balances,
+            open_escrows,
+            pool_positions,
+            rate_locks,
+            open_orders,
+            as_of: now,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bps::Bps;
+use crate::storage;
+use soroban_sdk::{contracttype, Bytes, BytesN, Env};
+
+/// Campaign code issued by an admin. Only the SHA-256 hash of the code is
+/// stored; a user presents the code itself to redeem it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromoCode {
+    /// Share of the platform fee waived, in basis points
+    pub discount_bps: u32,
+    pub max_uses: u32,
+    /// Redemptions so far
+    pub uses: u32,
+    /// Last timestamp the code can be redeemed at
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PromoDataKey {
+    Code(BytesN<32>),
+}
+
+// Codes live in the issuing contract's own storage, so escrow and conversion
+// campaigns are tracked separately
+pub struct Promos;
+
+impl Promos {
+    pub fn issue(
+        env: &Env,
+        code_hash: &BytesN<32>,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> PromoCode {
+        if discount_bps == 0 || Bps::new(discount_bps).is_err() || max_uses == 0 {
+            panic!("Invalid promo code");
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Promo code already expired");
+        }
+        let key = PromoDataKey::Code(code_hash.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Promo code already issued");
+        }
+        let promo = PromoCode {
+            discount_bps,
+            max_uses,
+            uses: 0,
+            expires_at,
+        };
+        storage::write(env, &key, &promo);
+        promo
+    }
+
+    pub fn revoke(env: &Env, code_hash: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .remove(&PromoDataKey::Code(code_hash.clone()));
+    }
+
+    pub fn get(env: &Env, code_hash: &BytesN<32>) -> Option<PromoCode> {
+        storage::read(env, &PromoDataKey::Code(code_hash.clone()))
+    }
+
+    /// Uses up one redemption of `code`, returning its hash and discount
+    pub fn redeem(env: &Env, code: &Bytes) -> (BytesN<32>, u32) {
+        let code_hash: BytesN<32> = env.crypto().sha256(code).into();
+        let mut promo = Self::get(env, &code_hash).unwrap_or_else(|| panic!("Unknown promo code"));
+        if env.ledger().timestamp() > promo.expires_at {
+            panic!("Promo code expired");
+        }
+        if promo.uses >= promo.max_uses {
+            panic!("Promo code used up");
+        }
+        promo.uses += 1;
+        storage::write(env, &PromoDataKey::Code(code_hash.clone()), &promo);
+        (code_hash, promo.discount_bps)
+    }
+
+    /// `fee` after waiving `discount_bps` of it
+    pub fn discounted(fee: i128, discount_bps: u32) -> i128 {
+        fee - Bps::new(discount_bps)
+            .and_then(|discount| discount.of(fee))
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,95 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+/// Token bucket settings: up to `capacity` calls in a burst, with one call
+/// regained every `refill_interval` seconds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub refill_interval: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateBucket {
+    pub tokens: u32,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RateLimitDataKey {
+    Limit(Symbol),
+    Bucket(Symbol, Address),
+}
+
+// Per-address, per-function buckets kept in the calling contract's own storage
+pub struct RateLimiter;
+
+impl RateLimiter {
+    /// Sets or clears the limit for `function`
+    pub fn set_limit(env: &Env, function: Symbol, limit: Option<RateLimit>) {
+        let key = RateLimitDataKey::Limit(function);
+        match limit {
+            Some(limit) => {
+                if limit.capacity == 0 || limit.refill_interval == 0 {
+                    panic!("Invalid rate limit");
+                }
+                env.storage().instance().set(&key, &limit)
+            }
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    pub fn get_limit(env: &Env, function: Symbol) -> Option<RateLimit> {
+        env.storage()
+            .instance()
+            .get(&RateLimitDataKey::Limit(function))
+    }
+
+    /// Takes one call from `account`'s bucket for `function`. Returns false,
+    /// leaving the bucket untouched, when it is empty; always true for
+    /// functions without a limit.
+    pub fn try_consume(env: &Env, function: Symbol, account: &Address) -> bool {
+        let Some(limit) = Self::get_limit(env, function.clone()) else {
+            return true;
+        };
+        let mut bucket = Self::refilled(env, &limit, &function, account);
+        if bucket.tokens == 0 {
+            return false;
+        }
+
+        bucket.tokens -= 1;
+        env.storage().persistent().set(
+            &RateLimitDataKey::Bucket(function, account.clone()),
+            &bucket,
+        );
+        true
+    }
+
+    fn refilled(env: &Env, limit: &RateLimit, function: &Symbol, account: &Address) -> RateBucket {
+        let now = env.ledger().timestamp();
+        let bucket: RateBucket = env
+            .storage()
+            .persistent()
+            .get(&RateLimitDataKey::Bucket(function.clone(), account.clone()))
+            .unwrap_or(RateBucket {
+                tokens: limit.capacity,
+                updated_at: now,
+            });
+
+        // Whole intervals only, so partial progress towards the next call is kept
+        let regained = (now - bucket.updated_at) / limit.refill_interval;
+        if bucket.tokens as u64 + regained >= limit.capacity as u64 {
+            RateBucket {
+                tokens: limit.capacity,
+                updated_at: now,
+            }
+        } else {
+            RateBucket {
+                tokens: bucket.tokens + regained as u32,
+                updated_at: bucket.updated_at + regained * limit.refill_interval,
+            }
+        }
+    }
+}
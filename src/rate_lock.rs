@@ -1,35 +1,532 @@
-#![no_std]
+use crate::audit_log::AdminAudit;
+use crate::conversion::Currency;
+use crate::errors::AppError;
+use crate::event::{
+    DeFiEvent, EventEmitter, RateLockClosedData, RateLockConsumedData, RateLockCreatedData,
+    RateLockEvent, RATE_LOCK_TOPIC,
+};
+use crate::oracle::OracleContractClient;
+use crate::rate_limit::{RateLimit, RateLimiter};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol, Vec,
+};
 
-use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env, Symbol};
+/// Pricing and limits for rate locks
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLockConfig {
+    pub admin: Address,
+    /// Receives lock fees
+    pub treasury: Address,
+    /// Token lock fees are paid in
+    pub fee_token: Address,
+    /// Fee per hour of lock time, in basis points of the locked amount
+    pub fee_bps_per_hour: u32,
+    /// Longest total duration a lock may cover, including extensions
+    pub max_lock_duration: u64,
+}
 
-#[contracterror]
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum RateLockError {
-    NoRateLocked = 1,
-    RateExpired = 2,
+/// A locked quote for converting up to `remaining_amount` of `from_currency`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLock {
+    pub lock_id: u64,
+    pub user: Address,
+    pub from_currency: Currency,
+    pub to_currency: Currency,
+    pub rate: i128,
+    /// Amount the lock was created for
+    pub max_amount: i128,
+    /// Amount still convertible at the locked rate
+    pub remaining_amount: i128,
+    pub locked_at: u64,
+    pub expires_at: u64,
+    /// Total fees paid for this lock, including extensions
+    pub fee_paid: i128,
 }
 
+const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
+const PAUSED_KEY: Symbol = symbol_short!("PAUSED");
+const ORACLE_KEY: Symbol = symbol_short!("ORACLE");
+const LOCK_RATE_FN: Symbol = symbol_short!("lock_rate");
+const DEFAULT_MAX_LOCK_DURATION: u64 = 86400; // 24 hours
+const MAX_FEE_BPS_PER_HOUR: u32 = 100; // 1% of the locked amount per hour
+const SECONDS_PER_HOUR: i128 = 3600;
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+/// Most expired locks `lock_rate` clears from a user's index on the way in
+const LOCK_SWEEP_LIMIT: u32 = 5;
+/// Most locks one `gc_expired_locks` call will look at
+pub const MAX_GC_BATCH: u32 = 50;
+
 #[contract]
 pub struct RateLockContract;
 
 #[contractimpl]
 impl RateLockContract {
-    pub fn lock_rate(env: Env, user: Address, rate: i128, duration_seconds: u64) {
-        let expiry = env.ledger().timestamp() + duration_seconds;
-        let key = (user.clone(), symbol_short!("RATELOCK"));
-        env.storage().persistent().set(&key, &(rate, expiry));
+    pub fn initialize_rate_lock(
+        env: Env,
+        admin: Address,
+        treasury: Address,
+        fee_token: Address,
+        fee_bps_per_hour: u32,
+    ) -> Result<RateLockConfig, AppError> {
+        if env.storage().instance().has(&CONFIG_KEY) {
+            return Err(AppError::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        if fee_bps_per_hour > MAX_FEE_BPS_PER_HOUR {
+            return Err(AppError::InvalidAmount);
+        }
+
+        let config = RateLockConfig {
+            admin,
+            treasury,
+            fee_token,
+            fee_bps_per_hour,
+            max_lock_duration: DEFAULT_MAX_LOCK_DURATION,
+        };
+        env.storage().instance().set(&CONFIG_KEY, &config);
+        Ok(config)
+    }
+
+    pub fn get_rate_lock_config(env: Env) -> Option<RateLockConfig> {
+        env.storage().instance().get(&CONFIG_KEY)
+    }
+
+    /// Sets the longest total duration a lock may cover. Existing locks keep their expiry.
+    pub fn set_max_lock_duration(
+        env: Env,
+        admin: Address,
+        max_lock_duration: u64,
+    ) -> Result<RateLockConfig, AppError> {
+        let mut config = Self::require_admin(&env, &admin)?;
+        AdminAudit::record(&env, &admin, "set_max_lock_duration", max_lock_duration);
+        if max_lock_duration == 0 {
+            return Err(AppError::InvalidDuration);
+        }
+
+        config.max_lock_duration = max_lock_duration;
+        env.storage().instance().set(&CONFIG_KEY, &config);
+        Ok(config)
+    }
+
+    /// Stops new locks and extensions during volatile markets. Existing locks remain usable.
+    pub fn set_locks_paused(env: Env, admin: Address, paused: bool) -> Result<(), AppError> {
+        Self::require_admin(&env, &admin)?;
+        AdminAudit::record(&env, &admin, "set_locks_paused", paused);
+        env.storage().instance().set(&PAUSED_KEY, &paused);
+        Ok(())
     }
 
-    pub fn validate_conversion(env: Env, user: Address) -> Result<i128, RateLockError> {
-        let key = (user.clone(), symbol_short!("RATELOCK"));
-        let stored: Option<(i128, u64)> = env.storage().persistent().get(&key);
+    pub fn is_locks_paused(env: Env) -> bool {
+        env.storage().instance().get(&PAUSED_KEY).unwrap_or(false)
+    }
+
+    /// Requires locks to be taken at the oracle's current price, or clears it (admin only)
+    pub fn set_rate_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Option<Address>,
+    ) -> Result<(), AppError> {
+        Self::require_admin(&env, &admin)?;
+        AdminAudit::record(&env, &admin, "set_rate_oracle", oracle.clone());
+        match oracle {
+            Some(oracle) => env.storage().instance().set(&ORACLE_KEY, &oracle),
+            None => env.storage().instance().remove(&ORACLE_KEY),
+        }
+        Ok(())
+    }
+
+    pub fn get_rate_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ORACLE_KEY)
+    }
+
+    /// Limits how often each user can create locks, or clears the limit (admin only)
+    pub fn set_lock_rate_limit(
+        env: Env,
+        admin: Address,
+        limit: Option<RateLimit>,
+    ) -> Result<(), AppError> {
+        Self::require_admin(&env, &admin)?;
+        AdminAudit::record(&env, &admin, "set_lock_rate_limit", limit.clone());
+        RateLimiter::set_limit(&env, LOCK_RATE_FN, limit);
+        Ok(())
+    }
+
+    pub fn get_lock_rate_limit(env: Env) -> Option<RateLimit> {
+        RateLimiter::get_limit(&env, LOCK_RATE_FN)
+    }
 
-        let (rate, expiry) = stored.ok_or(RateLockError::NoRateLocked)?;
+    /// Sets or clears the shared audit log admin actions are journaled to (admin only)
+    pub fn set_rate_lock_audit_log(
+        env: Env,
+        admin: Address,
+        audit_log: Option<Address>,
+    ) -> Result<(), AppError> {
+        Self::require_admin(&env, &admin)?;
+        AdminAudit::record(&env, &admin, "set_rate_lock_audit_log", audit_log.clone());
+        AdminAudit::set_log(&env, audit_log);
+        Ok(())
+    }
 
-        if env.ledger().timestamp() > expiry {
-            return Err(RateLockError::RateExpired);
+    pub fn get_rate_lock_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Fee charged for locking `amount` for `duration_seconds`
+    pub fn quote_lock_fee(env: Env, amount: i128, duration_seconds: u64) -> i128 {
+        match Self::get_rate_lock_config(env) {
+            Some(config) => Self::lock_fee(&config, amount, duration_seconds),
+            None => 0,
         }
+    }
 
-        Ok(rate)
+    pub fn lock_rate(
+        env: Env,
+        user: Address,
+        from_currency: Currency,
+        to_currency: Currency,
+        rate: i128,
+        max_amount: i128,
+        duration_seconds: u64,
+    ) -> Result<u64, AppError> {
+        if Self::is_locks_paused(env.clone()) {
+            return Err(AppError::LocksPaused);
+        }
+        if rate <= 0 || max_amount <= 0 {
+            return Err(AppError::InvalidAmount);
+        }
+        if duration_seconds == 0 || duration_seconds > Self::max_lock_duration(&env) {
+            return Err(AppError::InvalidDuration);
+        }
+
+        // With an oracle configured the quoted rate must be the current oracle price
+        if let Some(oracle) = Self::get_rate_oracle(env.clone()) {
+            let price =
+                OracleContractClient::new(&env, &oracle).get_price(&from_currency, &to_currency);
+            if price.price != rate {
+                return Err(AppError::QuoteMismatch);
+            }
+        }
+
+        // Paying a lock fee or drawing on the user's rate limit requires their authorization
+        let fee_paid = Self::quote_lock_fee(env.clone(), max_amount, duration_seconds);
+        if fee_paid > 0 || RateLimiter::get_limit(&env, LOCK_RATE_FN).is_some() {
+            user.require_auth();
+        }
+        if !RateLimiter::try_consume(&env, LOCK_RATE_FN, &user) {
+            return Err(AppError::RateLimited);
+        }
+        Self::collect_fee(&env, &user, fee_paid);
+        Self::sweep_expired(&env, &user);
+
+        let lock_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("LOCKCNT"))
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("LOCKCNT"), &lock_id);
+
+        let lock = RateLock {
+            lock_id,
+            user: user.clone(),
+            from_currency,
+            to_currency,
+            rate,
+            max_amount,
+            remaining_amount: max_amount,
+            locked_at: env.ledger().timestamp(),
+            expires_at: env.ledger().timestamp() + duration_seconds,
+            fee_paid,
+        };
+        let key = (
+            user.clone(),
+            lock.from_currency.clone(),
+            lock.to_currency.clone(),
+            lock_id,
+        );
+        env.storage().persistent().set(&key, &lock);
+
+        let mut index = Self::lock_index(&env, &user);
+        index.push_back((
+            lock.from_currency.clone(),
+            lock.to_currency.clone(),
+            lock_id,
+        ));
+        Self::set_lock_index(&env, &user, &index);
+
+        let event = DeFiEvent::RateLock(RateLockEvent::Created(RateLockCreatedData {
+            lock_id,
+            user,
+            from_currency: lock.from_currency,
+            to_currency: lock.to_currency,
+            rate,
+            max_amount,
+            fee_paid,
+            expires_at: lock.expires_at,
+            created_at: lock.locked_at,
+        }));
+        EventEmitter::emit_event(&env, RATE_LOCK_TOPIC, event);
+
+        Ok(lock_id)
+    }
+
+    /// Validates a conversion against the user's lock and consumes `amount` from it.
+    /// Returns the locked rate.
+    pub fn validate_conversion(
+        env: Env,
+        user: Address,
+        lock_id: u64,
+        from_currency: Currency,
+        to_currency: Currency,
+        amount: i128,
+    ) -> Result<i128, AppError> {
+        if amount <= 0 {
+            return Err(AppError::InvalidAmount);
+        }
+
+        let key = (user.clone(), from_currency, to_currency, lock_id);
+        let stored: Option<RateLock> = env.storage().persistent().get(&key);
+
+        let mut lock = match stored {
+            Some(lock) => lock,
+            // Distinguish a lock held for another pair from no lock at all
+            None => match Self::get_rate_lock(env.clone(), user.clone(), lock_id) {
+                Some(_) => return Err(AppError::PairMismatch),
+                None => return Err(AppError::NoRateLocked),
+            },
+        };
+
+        if env.ledger().timestamp() > lock.expires_at {
+            return Err(AppError::RateExpired);
+        }
+
+        if amount > lock.remaining_amount {
+            return Err(AppError::AmountExceedsLock);
+        }
+
+        lock.remaining_amount -= amount;
+        if lock.remaining_amount == 0 {
+            env.storage().persistent().remove(&key);
+            Self::remove_from_index(&env, &user, lock_id);
+        } else {
+            env.storage().persistent().set(&key, &lock);
+        }
+
+        let event = DeFiEvent::RateLock(RateLockEvent::Consumed(RateLockConsumedData {
+            lock_id,
+            user,
+            amount,
+            remaining_amount: lock.remaining_amount,
+            consumed_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, RATE_LOCK_TOPIC, event);
+
+        Ok(lock.rate)
+    }
+
+    /// Extends an unexpired lock, charging the fee for the extra time on the remaining amount
+    pub fn extend_lock(
+        env: Env,
+        user: Address,
+        lock_id: u64,
+        extra_seconds: u64,
+    ) -> Result<RateLock, AppError> {
+        user.require_auth();
+        if Self::is_locks_paused(env.clone()) {
+            return Err(AppError::LocksPaused);
+        }
+
+        let mut lock = Self::get_rate_lock(env.clone(), user.clone(), lock_id)
+            .ok_or(AppError::NoRateLocked)?;
+        if env.ledger().timestamp() > lock.expires_at {
+            return Err(AppError::RateExpired);
+        }
+
+        let new_expiry = lock.expires_at + extra_seconds;
+        if extra_seconds == 0 || new_expiry - lock.locked_at > Self::max_lock_duration(&env) {
+            return Err(AppError::InvalidDuration);
+        }
+
+        let fee = Self::quote_lock_fee(env.clone(), lock.remaining_amount, extra_seconds);
+        Self::collect_fee(&env, &user, fee);
+        lock.fee_paid += fee;
+        lock.expires_at = new_expiry;
+
+        let key = (
+            user,
+            lock.from_currency.clone(),
+            lock.to_currency.clone(),
+            lock_id,
+        );
+        env.storage().persistent().set(&key, &lock);
+
+        Ok(lock)
+    }
+
+    /// Releases a lock before it expires. Fees already paid are not refunded.
+    pub fn cancel_lock(env: Env, user: Address, lock_id: u64) -> Result<(), AppError> {
+        user.require_auth();
+
+        let lock = Self::get_rate_lock(env.clone(), user.clone(), lock_id)
+            .ok_or(AppError::NoRateLocked)?;
+        Self::close_lock(&env, &user, &lock);
+
+        let event = DeFiEvent::RateLock(RateLockEvent::Cancelled(RateLockClosedData {
+            lock_id,
+            user,
+            unused_amount: lock.remaining_amount,
+            closed_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, RATE_LOCK_TOPIC, event);
+        Ok(())
+    }
+
+    /// Clears an expired lock from storage. Callable by anyone.
+    pub fn expire_lock(env: Env, user: Address, lock_id: u64) -> Result<(), AppError> {
+        let lock = Self::get_rate_lock(env.clone(), user.clone(), lock_id)
+            .ok_or(AppError::NoRateLocked)?;
+        if env.ledger().timestamp() <= lock.expires_at {
+            return Err(AppError::LockNotExpired);
+        }
+
+        Self::close_expired(&env, &user, &lock);
+        Ok(())
+    }
+
+    /// Clears expired locks in bulk, skipping any that are unknown or still
+    /// live. Callable by anyone. Returns how many were cleared.
+    pub fn gc_expired_locks(env: Env, keys: Vec<(Address, u64)>) -> Result<u32, AppError> {
+        if keys.len() > MAX_GC_BATCH {
+            return Err(AppError::InvalidAmount);
+        }
+
+        let mut cleared = 0;
+        for (user, lock_id) in keys.iter() {
+            if let Some(lock) = Self::get_rate_lock(env.clone(), user.clone(), lock_id) {
+                if env.ledger().timestamp() > lock.expires_at {
+                    Self::close_expired(&env, &user, &lock);
+                    cleared += 1;
+                }
+            }
+        }
+        Ok(cleared)
+    }
+
+    pub fn get_rate_lock(env: Env, user: Address, lock_id: u64) -> Option<RateLock> {
+        for (from_currency, to_currency, id) in Self::lock_index(&env, &user).iter() {
+            if id == lock_id {
+                let key = (user, from_currency, to_currency, lock_id);
+                return env.storage().persistent().get(&key);
+            }
+        }
+        None
+    }
+
+    /// All outstanding locks held by a user, oldest first
+    pub fn get_locks(env: Env, user: Address) -> Vec<RateLock> {
+        let mut locks = Vec::new(&env);
+        for (from_currency, to_currency, lock_id) in Self::lock_index(&env, &user).iter() {
+            let key = (user.clone(), from_currency, to_currency, lock_id);
+            if let Some(lock) = env.storage().persistent().get::<_, RateLock>(&key) {
+                locks.push_back(lock);
+            }
+        }
+        locks
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<RateLockConfig, AppError> {
+        let config = Self::get_rate_lock_config(env.clone()).ok_or(AppError::NotInitialized)?;
+        if config.admin != *admin {
+            return Err(AppError::Unauthorized);
+        }
+        admin.require_auth();
+        Ok(config)
+    }
+
+    fn close_lock(env: &Env, user: &Address, lock: &RateLock) {
+        let key = (
+            user.clone(),
+            lock.from_currency.clone(),
+            lock.to_currency.clone(),
+            lock.lock_id,
+        );
+        env.storage().persistent().remove(&key);
+        Self::remove_from_index(env, user, lock.lock_id);
+    }
+
+    fn close_expired(env: &Env, user: &Address, lock: &RateLock) {
+        Self::close_lock(env, user, lock);
+
+        let event = DeFiEvent::RateLock(RateLockEvent::Expired(RateLockClosedData {
+            lock_id: lock.lock_id,
+            user: user.clone(),
+            unused_amount: lock.remaining_amount,
+            closed_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(env, RATE_LOCK_TOPIC, event);
+    }
+
+    // Clear a few of the user's lapsed locks, so quotes that were never used
+    // don't pile up in storage
+    fn sweep_expired(env: &Env, user: &Address) {
+        let now = env.ledger().timestamp();
+        let mut swept = 0;
+        for lock in Self::get_locks(env.clone(), user.clone()).iter() {
+            if swept == LOCK_SWEEP_LIMIT {
+                break;
+            }
+            if now > lock.expires_at {
+                Self::close_expired(env, user, &lock);
+                swept += 1;
+            }
+        }
+    }
+
+    fn max_lock_duration(env: &Env) -> u64 {
+        match Self::get_rate_lock_config(env.clone()) {
+            Some(config) => config.max_lock_duration,
+            None => DEFAULT_MAX_LOCK_DURATION,
+        }
+    }
+
+    fn lock_fee(config: &RateLockConfig, amount: i128, duration_seconds: u64) -> i128 {
+        amount * i128::from(config.fee_bps_per_hour) * i128::from(duration_seconds)
+            / (BASIS_POINTS_DIVISOR * SECONDS_PER_HOUR)
+    }
+
+    fn collect_fee(env: &Env, user: &Address, fee: i128) {
+        if fee > 0 {
+            let config = Self::get_rate_lock_config(env.clone()).unwrap();
+            token::Client::new(env, &config.fee_token).transfer(user, &config.treasury, &fee);
+        }
+    }
+
+    fn lock_index(env: &Env, user: &Address) -> Vec<(Currency, Currency, u64)> {
+        env.storage()
+            .persistent()
+            .get(&(user.clone(), symbol_short!("LOCKS")))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn set_lock_index(env: &Env, user: &Address, index: &Vec<(Currency, Currency, u64)>) {
+        env.storage()
+            .persistent()
+            .set(&(user.clone(), symbol_short!("LOCKS")), index);
+    }
+
+    fn remove_from_index(env: &Env, user: &Address, lock_id: u64) {
+        let mut index = Self::lock_index(env, user);
+        for (i, (_, _, id)) in index.iter().enumerate() {
+            if id == lock_id {
+                index.remove(i as u32);
+                break;
+            }
+        }
+        Self::set_lock_index(env, user, &index);
     }
 }
@@ -0,0 +1,162 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, ReceiptEvent, ReceiptMintedData, ReceiptTransferredData, RECEIPT_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+/// What sort of locked position a receipt stands for
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PositionKind {
+    Escrow,
+    Vesting,
+    Liquidity,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub id: u64,
+    /// Contract holding the position, the only one that can burn the receipt
+    pub issuer: Address,
+    pub kind: PositionKind,
+    pub owner: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReceiptDataKey {
+    Admin,
+    NextId,
+    Receipt(u64),
+}
+
+#[contract]
+pub struct ReceiptContract;
+
+/// Non-fungible receipts for locked positions. Issuing contracts (escrow,
+/// vesting, pools) mint a receipt when a position opens and pay whoever holds
+/// it when the position settles, so the position can change hands while
+/// the funds stay locked.
+#[contractimpl]
+impl ReceiptContract {
+    pub fn initialize_receipts(env: Env, admin: Address) {
+        if env.storage().instance().has(&ReceiptDataKey::Admin) {
+            panic!("Receipts already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&ReceiptDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Mint a receipt for a position held by `issuer` (minter only)
+    pub fn mint_receipt(env: Env, issuer: Address, kind: PositionKind, to: Address) -> u64 {
+        AccessControl::require_role(&env, Role::Minter, &issuer);
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&ReceiptDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&ReceiptDataKey::NextId, &(id + 1));
+
+        let receipt = Receipt {
+            id,
+            issuer: issuer.clone(),
+            kind,
+            owner: to.clone(),
+        };
+        Self::save(&env, &receipt);
+
+        let event = DeFiEvent::Receipt(ReceiptEvent::Minted(ReceiptMintedData {
+            receipt_id: id,
+            issuer,
+            kind,
+            to,
+        }));
+        EventEmitter::emit_event(&env, RECEIPT_TOPIC, event);
+        id
+    }
+
+    /// Hand a receipt, and the right to its position, to `to` (holder only)
+    pub fn transfer_receipt(env: Env, from: Address, to: Address, receipt_id: u64) {
+        from.require_auth();
+        let mut receipt = Self::get_receipt(env.clone(), receipt_id);
+        if receipt.owner != from {
+            panic!("Not the receipt holder");
+        }
+        receipt.owner = to.clone();
+        Self::save(&env, &receipt);
+
+        let event = DeFiEvent::Receipt(ReceiptEvent::Transferred(ReceiptTransferredData {
+            receipt_id,
+            from,
+            to,
+        }));
+        EventEmitter::emit_event(&env, RECEIPT_TOPIC, event);
+    }
+
+    /// Retire the receipt of a settled position (its issuer only)
+    pub fn burn_receipt(env: Env, issuer: Address, receipt_id: u64) {
+        issuer.require_auth();
+        let receipt = Self::get_receipt(env.clone(), receipt_id);
+        if receipt.issuer != issuer {
+            panic!("Not the receipt issuer");
+        }
+        env.storage()
+            .persistent()
+            .remove(&ReceiptDataKey::Receipt(receipt_id));
+
+        let event = DeFiEvent::Receipt(ReceiptEvent::Burned(ReceiptTransferredData {
+            receipt_id,
+            from: receipt.owner,
+            to: issuer,
+        }));
+        EventEmitter::emit_event(&env, RECEIPT_TOPIC, event);
+    }
+
+    pub fn get_receipt(env: Env, receipt_id: u64) -> Receipt {
+        env.storage()
+            .persistent()
+            .get(&ReceiptDataKey::Receipt(receipt_id))
+            .expect("Receipt not found")
+    }
+
+    pub fn receipt_owner(env: Env, receipt_id: u64) -> Address {
+        Self::get_receipt(env, receipt_id).owner
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_receipt_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_receipt_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_receipt_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_receipt_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_receipt_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn save(env: &Env, receipt: &Receipt) {
+        env.storage()
+            .persistent()
+            .set(&ReceiptDataKey::Receipt(receipt.id), receipt);
+    }
+}
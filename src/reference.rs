@@ -0,0 +1,38 @@
+use soroban_sdk::{contracttype, BytesN, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReferenceDataKey {
+    Current,
+}
+
+// External reference a backend attaches to an operation so the events it
+// emits can be reconciled against banking rails. It is held in the calling
+// contract's temporary storage only while the operation runs; events emitted
+// outside one carry all zeroes.
+pub struct PaymentReference;
+
+impl PaymentReference {
+    /// The reference events carry when none was supplied
+    pub fn none(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[0; 32])
+    }
+
+    /// Reference of the operation in progress
+    pub fn current(env: &Env) -> BytesN<32> {
+        env.storage()
+            .temporary()
+            .get(&ReferenceDataKey::Current)
+            .unwrap_or_else(|| Self::none(env))
+    }
+
+    /// Runs `op` with `reference` attached to every event it emits
+    pub fn attach<T>(env: &Env, reference: &BytesN<32>, op: impl FnOnce() -> T) -> T {
+        env.storage()
+            .temporary()
+            .set(&ReferenceDataKey::Current, reference);
+        let result = op();
+        env.storage().temporary().remove(&ReferenceDataKey::Current);
+        result
+    }
+}
@@ -0,0 +1,365 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, ReferralAccruedData, ReferralClaimedData, ReferralEvent,
+    ReferralRegisteredData, REFERRAL_TOPIC,
+};
+use crate::rescue::Rescue;
+use crate::token::LockedPayout;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+/// Upper bound on a tier's share of a reported fee
+pub const MAX_COMMISSION_BPS: u32 = 5_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralConfig {
+    /// Token commissions are paid out in
+    pub commission_token: Address,
+    /// Share of a referee's fees paid to their direct referrer
+    pub level1_bps: u32,
+    /// Share of a referee's fees paid to their referrer's referrer
+    pub level2_bps: u32,
+    /// Seconds a vesting window stays locked after it opens
+    pub vesting_period: u64,
+}
+
+/// A referrer's commissions. New commissions join the open vesting window,
+/// which unlocks into `claimable` once `vests_at` has passed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralBalance {
+    pub vesting: i128,
+    pub vests_at: u64,
+    pub claimable: i128,
+    pub total_earned: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReferralDataKey {
+    Admin,
+    Config,
+    Pool,
+    /// Seconds claimed commissions stay locked on the token
+    RewardLockup,
+    Referrer(Address),
+    Balance(Address),
+}
+
+#[contract]
+pub struct ReferralContract;
+
+/// Referral program. Each account can name its referrer once; fee-charging
+/// contracts with the minter role report the fees referees pay, and the
+/// direct and second-level referrers accrue a cut that vests before it can
+/// be claimed from the commission pool.
+#[contractimpl]
+impl ReferralContract {
+    pub fn initialize_referral(env: Env, admin: Address, config: ReferralConfig) {
+        if env.storage().instance().has(&ReferralDataKey::Admin) {
+            panic!("Referral program already initialized");
+        }
+        admin.require_auth();
+        Self::validate_config(&config);
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::Config, &config);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Change commission tiers and vesting for future accruals (owner only)
+    pub fn set_referral_config(env: Env, caller: Address, config: ReferralConfig) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_referral_config",
+            config.clone(),
+        );
+        Self::validate_config(&config);
+        let current = Self::get_referral_config(env.clone());
+        if current.commission_token != config.commission_token
+            && Self::get_referral_pool(env.clone()) > 0
+        {
+            panic!("Commission pool not empty");
+        }
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::Config, &config);
+    }
+
+    pub fn get_referral_config(env: Env) -> ReferralConfig {
+        env.storage()
+            .instance()
+            .get(&ReferralDataKey::Config)
+            .expect("Referral program not initialized")
+    }
+
+    /// Record `referrer` as the account that referred `referee`. The link is
+    /// permanent.
+    pub fn register_referral(env: Env, referee: Address, referrer: Address) {
+        referee.require_auth();
+        if referee == referrer {
+            panic!("Cannot refer yourself");
+        }
+        if Self::get_referrer(env.clone(), referee.clone()).is_some() {
+            panic!("Referrer already set");
+        }
+        if Self::get_referrer(env.clone(), referrer.clone()) == Some(referee.clone()) {
+            panic!("Referral cycle");
+        }
+        env.storage()
+            .persistent()
+            .set(&ReferralDataKey::Referrer(referee.clone()), &referrer);
+
+        let event = DeFiEvent::Referral(ReferralEvent::Registered(ReferralRegisteredData {
+            referee,
+            referrer,
+        }));
+        EventEmitter::emit_event(&env, REFERRAL_TOPIC, event);
+    }
+
+    pub fn get_referrer(env: Env, referee: Address) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&ReferralDataKey::Referrer(referee))
+    }
+
+    /// Report a `fee` paid by `trader` (minter only) and accrue commissions
+    /// up the two referral tiers; returns the total commission accrued
+    pub fn report_referral_fee(env: Env, reporter: Address, trader: Address, fee: i128) -> i128 {
+        AccessControl::require_role(&env, Role::Minter, &reporter);
+        if fee <= 0 {
+            panic!("Fee must be positive");
+        }
+        let config = Self::get_referral_config(env.clone());
+        let Some(level1) = Self::get_referrer(env.clone(), trader.clone()) else {
+            return 0;
+        };
+        let mut total = Self::accrue(&env, &config, &trader, &level1, 1, fee, config.level1_bps);
+        if let Some(level2) = Self::get_referrer(env.clone(), level1) {
+            total += Self::accrue(&env, &config, &trader, &level2, 2, fee, config.level2_bps);
+        }
+        total
+    }
+
+    pub fn get_referral_balance(env: Env, referrer: Address) -> ReferralBalance {
+        let mut balance = Self::load_balance(&env, &referrer);
+        Self::unlock(&env, &mut balance);
+        balance
+    }
+
+    /// Add commission tokens to the pool claims are paid from
+    pub fn fund_referral_pool(env: Env, funder: Address, amount: i128) {
+        funder.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let config = Self::get_referral_config(env.clone());
+        token::Client::new(&env, &config.commission_token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
+        let pool = Self::get_referral_pool(env.clone());
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::Pool, &(pool + amount));
+    }
+
+    pub fn get_referral_pool(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&ReferralDataKey::Pool)
+            .unwrap_or(0)
+    }
+
+    /// Pay out the referrer's vested commissions; returns the amount paid
+    pub fn claim_referral_commission(env: Env, referrer: Address) -> i128 {
+        referrer.require_auth();
+        let mut balance = Self::get_referral_balance(env.clone(), referrer.clone());
+        let amount = balance.claimable;
+        if amount == 0 {
+            panic!("Nothing to claim");
+        }
+        let pool = Self::get_referral_pool(env.clone());
+        if amount > pool {
+            panic!("Insufficient commission pool");
+        }
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::Pool, &(pool - amount));
+        balance.claimable = 0;
+        Self::save_balance(&env, &referrer, &balance);
+
+        let config = Self::get_referral_config(env.clone());
+        LockedPayout::pay(
+            &env,
+            &config.commission_token,
+            &referrer,
+            amount,
+            env.ledger().timestamp() + Self::get_referral_reward_lockup(env.clone()),
+        );
+        let event = DeFiEvent::Referral(ReferralEvent::Claimed(ReferralClaimedData {
+            referrer,
+            amount,
+        }));
+        EventEmitter::emit_event(&env, REFERRAL_TOPIC, event);
+        amount
+    }
+
+    /// Keep claimed commissions locked for `lockup` seconds, or 0 to pay them
+    /// out freely (owner only). Locking needs this contract to be a lock
+    /// source on the commission token.
+    pub fn set_referral_reward_lockup(env: Env, caller: Address, lockup: u64) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_referral_reward_lockup",
+            lockup,
+        );
+        env.storage()
+            .instance()
+            .set(&ReferralDataKey::RewardLockup, &lockup);
+    }
+
+    pub fn get_referral_reward_lockup(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&ReferralDataKey::RewardLockup)
+            .unwrap_or(0)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_referral_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_referral_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_referral_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_referral_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_referral_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn accrue(
+        env: &Env,
+        config: &ReferralConfig,
+        trader: &Address,
+        referrer: &Address,
+        tier: u32,
+        fee: i128,
+        bps: u32,
+    ) -> i128 {
+        let commission = fee * i128::from(bps) / 10_000;
+        if commission == 0 {
+            return 0;
+        }
+        let mut balance = Self::get_referral_balance(env.clone(), referrer.clone());
+        if balance.vesting == 0 {
+            balance.vests_at = env.ledger().timestamp() + config.vesting_period;
+        }
+        balance.vesting += commission;
+        balance.total_earned += commission;
+        Self::save_balance(env, referrer, &balance);
+
+        let event = DeFiEvent::Referral(ReferralEvent::Accrued(ReferralAccruedData {
+            referrer: referrer.clone(),
+            trader: trader.clone(),
+            tier,
+            fee,
+            commission,
+            vests_at: balance.vests_at,
+        }));
+        EventEmitter::emit_event(env, REFERRAL_TOPIC, event);
+        commission
+    }
+
+    // Moves a vesting window whose lock has passed into the claimable balance
+    fn unlock(env: &Env, balance: &mut ReferralBalance) {
+        if balance.vesting > 0 && env.ledger().timestamp() >= balance.vests_at {
+            balance.claimable += balance.vesting;
+            balance.vesting = 0;
+        }
+    }
+
+    fn validate_config(config: &ReferralConfig) {
+        if config.level1_bps > MAX_COMMISSION_BPS || config.level2_bps > MAX_COMMISSION_BPS {
+            panic!("Commission too high");
+        }
+    }
+
+    fn load_balance(env: &Env, referrer: &Address) -> ReferralBalance {
+        env.storage()
+            .persistent()
+            .get(&ReferralDataKey::Balance(referrer.clone()))
+            .unwrap_or(ReferralBalance {
+                vesting: 0,
+                vests_at: 0,
+                claimable: 0,
+                total_earned: 0,
+            })
+    }
+
+    fn save_balance(env: &Env, referrer: &Address, balance: &ReferralBalance) {
+        env.storage()
+            .persistent()
+            .set(&ReferralDataKey::Balance(referrer.clone()), balance);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_referral_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_referral_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_referral_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_referral_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// The commission pool, which is paid out to referrers
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let config = Self::get_referral_config(env.clone());
+        if config.commission_token == *token {
+            Some(Self::get_referral_pool(env.clone()))
+        } else {
+            Some(0)
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, RemittanceEvent, RemittanceSentData, RemittanceSettledData,
+    REMITTANCE_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RemittanceStatus {
+    Pending,
+    Delivered,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Remittance {
+    pub id: u64,
+    pub sender: Address,
+    pub agent: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Hash of the recipient's off-chain payout details
+    pub recipient_ref: BytesN<32>,
+    pub deadline: u64,
+    pub status: RemittanceStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RemittanceDataKey {
+    Admin,
+    DeliveryTimeout,
+    NextId,
+    Agent(Address),
+    Remittance(u64),
+}
+
+#[contract]
+pub struct RemittanceContract;
+
+/// Corridor for cash-out remittances. The sender's stablecoins stay locked
+/// here until the payout agent attests fiat delivery, an attestor rules on a
+/// stuck transfer, or the delivery window lapses and the sender is refunded.
+#[contractimpl]
+impl RemittanceContract {
+    pub fn initialize_remittance(env: Env, admin: Address, delivery_timeout: u64) {
+        if env.storage().instance().has(&RemittanceDataKey::Admin) {
+            panic!("Remittance already initialized");
+        }
+        admin.require_auth();
+        if delivery_timeout == 0 {
+            panic!("Delivery timeout must be positive");
+        }
+        env.storage()
+            .instance()
+            .set(&RemittanceDataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&RemittanceDataKey::DeliveryTimeout, &delivery_timeout);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Change how long agents have to deliver new remittances (owner only)
+    pub fn set_delivery_timeout(env: Env, caller: Address, delivery_timeout: u64) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_delivery_timeout",
+            delivery_timeout,
+        );
+        if delivery_timeout == 0 {
+            panic!("Delivery timeout must be positive");
+        }
+        env.storage()
+            .instance()
+            .set(&RemittanceDataKey::DeliveryTimeout, &delivery_timeout);
+    }
+
+    pub fn get_delivery_timeout(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&RemittanceDataKey::DeliveryTimeout)
+            .expect("Remittance not initialized")
+    }
+
+    /// Register or deregister a payout agent (operator only)
+    pub fn set_payout_agent(env: Env, caller: Address, agent: Address, enabled: bool) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_payout_agent",
+            (agent.clone(), enabled),
+        );
+        let key = RemittanceDataKey::Agent(agent);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn is_payout_agent(env: Env, agent: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&RemittanceDataKey::Agent(agent))
+    }
+
+    /// Lock `amount` of `token` for `agent` to pay out in fiat
+    pub fn send_remittance(
+        env: Env,
+        sender: Address,
+        agent: Address,
+        token: Address,
+        amount: i128,
+        recipient_ref: BytesN<32>,
+    ) -> u64 {
+        sender.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if !Self::is_payout_agent(env.clone(), agent.clone()) {
+            panic!("Unknown payout agent");
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&RemittanceDataKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&RemittanceDataKey::NextId, &(id + 1));
+
+        let remittance = Remittance {
+            id,
+            sender: sender.clone(),
+            agent: agent.clone(),
+            token,
+            amount,
+            recipient_ref,
+            deadline: env.ledger().timestamp() + Self::get_delivery_timeout(env.clone()),
+            status: RemittanceStatus::Pending,
+        };
+        Self::save(&env, &remittance);
+
+        let event = DeFiEvent::Remittance(RemittanceEvent::Sent(RemittanceSentData {
+            remittance_id: id,
+            sender,
+            agent,
+            amount,
+            deadline: remittance.deadline,
+        }));
+        EventEmitter::emit_event(&env, REMITTANCE_TOPIC, event);
+        id
+    }
+
+    /// Attest the fiat reached the recipient and collect the locked funds (the agent only)
+    pub fn attest_delivery(env: Env, agent: Address, remittance_id: u64) {
+        agent.require_auth();
+        let mut remittance = Self::pending(&env, remittance_id);
+        if remittance.agent != agent {
+            panic!("Not the payout agent");
+        }
+        if env.ledger().timestamp() >= remittance.deadline {
+            panic!("Delivery window closed");
+        }
+        Self::settle(&env, &mut remittance, true);
+    }
+
+    /// Fallback ruling on a pending remittance the agent has not attested:
+    /// pay the agent if delivery is confirmed, otherwise refund the sender (attestor only)
+    pub fn arbitrate_delivery(env: Env, arbiter: Address, remittance_id: u64, delivered: bool) {
+        AccessControl::require_role(&env, Role::Attestor, &arbiter);
+        let mut remittance = Self::pending(&env, remittance_id);
+        Self::settle(&env, &mut remittance, delivered);
+    }
+
+    /// Return an undelivered remittance to its sender once the window has lapsed
+    pub fn refund_remittance(env: Env, remittance_id: u64) {
+        let mut remittance = Self::pending(&env, remittance_id);
+        if env.ledger().timestamp() < remittance.deadline {
+            panic!("Delivery window still open");
+        }
+        Self::settle(&env, &mut remittance, false);
+    }
+
+    pub fn get_remittance(env: Env, remittance_id: u64) -> Remittance {
+        env.storage()
+            .persistent()
+            .get(&RemittanceDataKey::Remittance(remittance_id))
+            .expect("Remittance not found")
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_remittance_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_remittance_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_remittance_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_remittance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_remittance_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    fn pending(env: &Env, remittance_id: u64) -> Remittance {
+        let remittance = Self::get_remittance(env.clone(), remittance_id);
+        if remittance.status != RemittanceStatus::Pending {
+            panic!("Remittance not pending");
+        }
+        remittance
+    }
+
+    // Releases the locked funds to the agent on delivery, or back to the sender
+    fn settle(env: &Env, remittance: &mut Remittance, delivered: bool) {
+        let (receiver, status) = if delivered {
+            (remittance.agent.clone(), RemittanceStatus::Delivered)
+        } else {
+            (remittance.sender.clone(), RemittanceStatus::Refunded)
+        };
+        token::Client::new(env, &remittance.token).transfer(
+            &env.current_contract_address(),
+            &receiver,
+            &remittance.amount,
+        );
+        remittance.status = status;
+        Self::save(env, remittance);
+
+        let data = RemittanceSettledData {
+            remittance_id: remittance.id,
+            receiver,
+            amount: remittance.amount,
+        };
+        let event = DeFiEvent::Remittance(if delivered {
+            RemittanceEvent::Delivered(data)
+        } else {
+            RemittanceEvent::Refunded(data)
+        });
+        EventEmitter::emit_event(env, REMITTANCE_TOPIC, event);
+    }
+
+    fn save(env: &Env, remittance: &Remittance) {
+        env.storage()
+            .persistent()
+            .set(&RemittanceDataKey::Remittance(remittance.id), remittance);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_remittance_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_remittance_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_remittance_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_remittance_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` locked in pending remittances
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let next_id: u64 = env
+            .storage()
+            .instance()
+            .get(&RemittanceDataKey::NextId)
+            .unwrap_or(1);
+        let mut owed = 0;
+        for id in 1..next_id {
+            let remittance = Self::get_remittance(env.clone(), id);
+            if remittance.status == RemittanceStatus::Pending && remittance.token == *token {
+                owed += remittance.amount;
+            }
+        }
+        Some(owed)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+/// Highest possible score
+pub const MAX_REPUTATION_SCORE: u32 = 1000;
+/// Score of an address with no recorded history
+pub const NEUTRAL_REPUTATION_SCORE: u32 = 500;
+// Weight of the neutral prior, in outcomes, so a few early trades cannot move
+// a score to either extreme
+const PRIOR_WEIGHT: u64 = 5;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TradeOutcome {
+    /// Settled without a dispute
+    Completed,
+    /// Went through a dispute that was resolved in the subject's favour
+    Disputed,
+    /// Went through a dispute that was resolved against the subject
+    Defaulted,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reputation {
+    pub completed: u32,
+    pub disputed: u32,
+    pub defaulted: u32,
+    /// 0 to `MAX_REPUTATION_SCORE`
+    pub score: u32,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReputationDataKey {
+    Admin,
+    Reputation(Address),
+}
+
+#[contract]
+pub struct ReputationContract;
+
+/// Per-address counterparty statistics. Contracts holding the operator role
+/// (escrow, swaps) record how each trade ended, and anyone can read the
+/// resulting score.
+#[contractimpl]
+impl ReputationContract {
+    pub fn initialize_reputation(env: Env, admin: Address) {
+        if env.storage().instance().has(&ReputationDataKey::Admin) {
+            panic!("Reputation already initialized");
+        }
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&ReputationDataKey::Admin, &admin);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Records how a trade ended for `subject` (operator only)
+    pub fn record_outcome(env: Env, recorder: Address, subject: Address, outcome: TradeOutcome) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &recorder,
+            "record_outcome",
+            (subject.clone(), outcome.clone()),
+        );
+
+        let mut reputation = Self::get_reputation(env.clone(), subject.clone());
+        match outcome {
+            TradeOutcome::Completed => reputation.completed += 1,
+            TradeOutcome::Disputed => reputation.disputed += 1,
+            TradeOutcome::Defaulted => reputation.defaulted += 1,
+        }
+        reputation.score = Self::score(&reputation);
+        reputation.updated_at = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&ReputationDataKey::Reputation(subject), &reputation);
+    }
+
+    pub fn get_reputation(env: Env, subject: Address) -> Reputation {
+        env.storage()
+            .persistent()
+            .get(&ReputationDataKey::Reputation(subject))
+            .unwrap_or(Reputation {
+                completed: 0,
+                disputed: 0,
+                defaulted: 0,
+                score: NEUTRAL_REPUTATION_SCORE,
+                updated_at: 0,
+            })
+    }
+
+    pub fn get_reputation_score(env: Env, subject: Address) -> u32 {
+        Self::get_reputation(env, subject).score
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_reputation_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_reputation_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_reputation_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_reputation_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_reputation_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    // Completed trades count fully, disputes won count half and defaults count
+    // nothing, blended with a neutral prior
+    fn score(reputation: &Reputation) -> u32 {
+        let max = MAX_REPUTATION_SCORE as u64;
+        let total = reputation.completed as u64
+            + reputation.disputed as u64
+            + reputation.defaulted as u64
+            + PRIOR_WEIGHT;
+        let points = reputation.completed as u64 * max
+            + reputation.disputed as u64 * max / 2
+            + PRIOR_WEIGHT * NEUTRAL_REPUTATION_SCORE as u64;
+        (points / total) as u32
+    }
+}
@@ -0,0 +1,68 @@
+use crate::audit_log::AdminAudit;
+use crate::event::SYSTEM_TOPIC;
+use soroban_sdk::{contracttype, symbol_short, token, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RescueDataKey {
+    Timelock,
+}
+
+// Recovery of tokens sent straight to a contract address instead of through
+// its entry points, which would otherwise be stuck for good. Only the
+// governance timelock appointed on the contract can move them, so a rescue is
+// public for the timelock's delay before it runs. Each contract reports what it
+// owes users in the token, and a rescue may only take the balance above that;
+// a contract that cannot total its holdings of a token reports `None`, which
+// rules out rescuing that token at all.
+pub struct Rescue;
+
+impl Rescue {
+    /// Appoints or clears the timelock allowed to rescue tokens
+    pub fn set_timelock(env: &Env, timelock: Option<Address>) {
+        match timelock {
+            Some(timelock) => env
+                .storage()
+                .instance()
+                .set(&RescueDataKey::Timelock, &timelock),
+            None => env.storage().instance().remove(&RescueDataKey::Timelock),
+        }
+    }
+
+    pub fn get_timelock(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&RescueDataKey::Timelock)
+    }
+
+    /// Sends `amount` of `token` held by the contract to `to`, out of the
+    /// balance not owed to users (`liabilities`)
+    pub fn rescue_tokens(
+        env: &Env,
+        token: &Address,
+        amount: i128,
+        to: &Address,
+        liabilities: Option<i128>,
+    ) {
+        let timelock = Self::get_timelock(env).unwrap_or_else(|| panic!("Rescue timelock not set"));
+        timelock.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let liabilities = liabilities.unwrap_or_else(|| panic!("Token is held for users"));
+        let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+        if amount > balance - liabilities {
+            panic!("Amount exceeds unowed balance");
+        }
+        AdminAudit::record(
+            env,
+            &timelock,
+            "rescue_tokens",
+            (token.clone(), amount, to.clone()),
+        );
+
+        token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+        env.events().publish(
+            (SYSTEM_TOPIC, symbol_short!("rescued"), token.clone()),
+            (to.clone(), amount),
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Val, Vec};
+
+/// Upper bound on calls per route, to stay well inside the invocation budget
+pub const MAX_ROUTE_CALLS: u32 = 16;
+
+/// One step of a route
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteCall {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+#[contract]
+pub struct RouterContract;
+
+/// Runs an ordered list of calls in one invocation, e.g. lock rate, convert,
+/// then create an escrow. A failing step aborts the invocation, so either
+/// every step applies or none does.
+#[contractimpl]
+impl RouterContract {
+    /// Execute `calls` in order on behalf of `caller` and return each result.
+    /// The caller's authorization covers what the steps require from them.
+    pub fn execute_route(env: Env, caller: Address, calls: Vec<RouteCall>) -> Vec<Val> {
+        caller.require_auth();
+        if calls.is_empty() {
+            panic!("Route is empty");
+        }
+        if calls.len() > MAX_ROUTE_CALLS {
+            panic!("Too many calls in route");
+        }
+
+        let mut results = Vec::new(&env);
+        for call in calls.iter() {
+            let result: Val = env.invoke_contract(&call.contract, &call.function, call.args);
+            results.push_back(result);
+        }
+        results
+    }
+}
@@ -0,0 +1,329 @@
+use crate::event::{
+    DeFiEvent, EventEmitter, SavingsContributionData, SavingsEvent, SavingsPenaltyData,
+    SavingsRoundPaidData, SAVINGS_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const MAX_MEMBERS: u32 = 50;
+const MAX_PENALTY_BPS: u32 = 5000;
+const BASIS_POINTS_DIVISOR: i128 = 10000;
+
+/// Rules of a savings group, fixed at creation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupTerms {
+    /// Paid by every member each round
+    pub contribution_amount: i128,
+    /// Seconds members have to contribute before a round can be closed without them
+    pub period_duration: u64,
+    pub max_members: u32,
+    /// Charged from a member's collateral for each missed contribution
+    pub penalty_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupStatus {
+    Forming,
+    Active,
+    Completed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupMember {
+    pub address: Address,
+    /// Security deposit of one contribution, used to cover missed rounds and
+    /// returned when the group completes
+    pub collateral: i128,
+    pub missed_rounds: u32,
+}
+
+/// A rotating savings group (ajo / esusu): each round every member contributes
+/// and the whole pot goes to one member, in join order, until everyone has
+/// received it once
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsGroup {
+    pub id: u64,
+    pub admin: Address,
+    pub token: Address,
+    pub terms: GroupTerms,
+    pub members: Vec<GroupMember>,
+    pub status: GroupStatus,
+    /// Zero-based; the recipient of round `n` is `members[n]`
+    pub current_round: u32,
+    pub round_started_at: u64,
+    /// Members who have contributed in the current round
+    pub round_contributors: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SavingsDataKey {
+    GroupCount,
+    Group(u64),
+}
+
+#[contract]
+pub struct SavingsGroupContract;
+
+#[contractimpl]
+impl SavingsGroupContract {
+    pub fn create_savings_group(
+        env: Env,
+        admin: Address,
+        token: Address,
+        terms: GroupTerms,
+    ) -> u64 {
+        admin.require_auth();
+        if terms.contribution_amount <= 0 || terms.period_duration == 0 {
+            panic!("Invalid group terms");
+        }
+        if terms.max_members < 2 || terms.max_members > MAX_MEMBERS {
+            panic!("Invalid group size");
+        }
+        if terms.penalty_bps > MAX_PENALTY_BPS {
+            panic!("Penalty too high");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&SavingsDataKey::GroupCount)
+            .unwrap_or(0)
+            + 1;
+        let group = SavingsGroup {
+            id,
+            admin,
+            token,
+            terms,
+            members: Vec::new(&env),
+            status: GroupStatus::Forming,
+            current_round: 0,
+            round_started_at: 0,
+            round_contributors: Vec::new(&env),
+        };
+        Self::save_group(&env, &group);
+        env.storage()
+            .instance()
+            .set(&SavingsDataKey::GroupCount, &id);
+
+        id
+    }
+
+    /// Joins a forming group, depositing one contribution as collateral
+    pub fn join_savings_group(env: Env, group_id: u64, member: Address) {
+        member.require_auth();
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        if group.status != GroupStatus::Forming {
+            panic!("Group is not accepting members");
+        }
+        if group.members.len() >= group.terms.max_members {
+            panic!("Group is full");
+        }
+        if Self::member_index(&group, &member).is_some() {
+            panic!("Already a member");
+        }
+
+        token::Client::new(&env, &group.token).transfer(
+            &member,
+            &env.current_contract_address(),
+            &group.terms.contribution_amount,
+        );
+        group.members.push_back(GroupMember {
+            address: member,
+            collateral: group.terms.contribution_amount,
+            missed_rounds: 0,
+        });
+        Self::save_group(&env, &group);
+    }
+
+    /// Removes a member before the group starts and returns their collateral (group admin only)
+    pub fn remove_group_member(env: Env, group_id: u64, member: Address) {
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        group.admin.require_auth();
+        if group.status != GroupStatus::Forming {
+            panic!("Group has already started");
+        }
+
+        let index = Self::member_index(&group, &member).expect("Not a member");
+        let removed = group.members.get_unchecked(index);
+        group.members.remove(index);
+        Self::save_group(&env, &group);
+        Self::pay(&env, &group, &removed.address, removed.collateral);
+    }
+
+    /// Fixes the payout order and opens the first round (group admin only)
+    pub fn start_savings_group(env: Env, group_id: u64) {
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        group.admin.require_auth();
+        if group.status != GroupStatus::Forming {
+            panic!("Group has already started");
+        }
+        if group.members.len() < 2 {
+            panic!("Not enough members");
+        }
+
+        group.status = GroupStatus::Active;
+        group.round_started_at = env.ledger().timestamp();
+        Self::save_group(&env, &group);
+    }
+
+    /// Cancels a group that has not started and refunds every member (group admin only)
+    pub fn cancel_savings_group(env: Env, group_id: u64) {
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        group.admin.require_auth();
+        if group.status != GroupStatus::Forming {
+            panic!("Group has already started");
+        }
+
+        group.status = GroupStatus::Cancelled;
+        Self::save_group(&env, &group);
+        for member in group.members.iter() {
+            Self::pay(&env, &group, &member.address, member.collateral);
+        }
+    }
+
+    /// Pays the member's contribution for the current round
+    pub fn contribute(env: Env, group_id: u64, member: Address) {
+        member.require_auth();
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        if group.status != GroupStatus::Active {
+            panic!("Group is not active");
+        }
+        if Self::member_index(&group, &member).is_none() {
+            panic!("Not a member");
+        }
+        if group.round_contributors.contains(&member) {
+            panic!("Already contributed this round");
+        }
+
+        token::Client::new(&env, &group.token).transfer(
+            &member,
+            &env.current_contract_address(),
+            &group.terms.contribution_amount,
+        );
+        group.round_contributors.push_back(member.clone());
+        Self::save_group(&env, &group);
+
+        let event = DeFiEvent::Savings(SavingsEvent::Contribution(SavingsContributionData {
+            group_id,
+            round: group.current_round,
+            member,
+            amount: group.terms.contribution_amount,
+        }));
+        EventEmitter::emit_event(&env, SAVINGS_TOPIC, event);
+    }
+
+    /// Pays the pot to this round's recipient. Callable by anyone once every member
+    /// has contributed, or after the period ends, in which case missed contributions
+    /// and penalties are taken from the defaulters' collateral.
+    pub fn close_round(env: Env, group_id: u64) -> i128 {
+        let mut group = Self::get_savings_group(env.clone(), group_id);
+        if group.status != GroupStatus::Active {
+            panic!("Group is not active");
+        }
+        let everyone_paid = group.round_contributors.len() == group.members.len();
+        let deadline = group.round_started_at + group.terms.period_duration;
+        if !everyone_paid && env.ledger().timestamp() < deadline {
+            panic!("Round is still open");
+        }
+
+        let round = group.current_round;
+        let contribution = group.terms.contribution_amount;
+        let penalty = contribution * group.terms.penalty_bps as i128 / BASIS_POINTS_DIVISOR;
+        let mut pot = contribution * group.round_contributors.len() as i128;
+        let mut missed = 0u32;
+        for index in 0..group.members.len() {
+            let mut member = group.members.get_unchecked(index);
+            if group.round_contributors.contains(&member.address) {
+                continue;
+            }
+
+            let charged = (contribution + penalty).min(member.collateral);
+            member.collateral -= charged;
+            member.missed_rounds += 1;
+            pot += charged;
+            missed += 1;
+            group.members.set(index, member.clone());
+
+            let event = DeFiEvent::Savings(SavingsEvent::PenaltyCharged(SavingsPenaltyData {
+                group_id,
+                round,
+                member: member.address,
+                amount: charged,
+            }));
+            EventEmitter::emit_event(&env, SAVINGS_TOPIC, event);
+        }
+
+        let recipient = group.members.get_unchecked(round).address;
+        group.current_round += 1;
+        group.round_started_at = env.ledger().timestamp();
+        group.round_contributors = Vec::new(&env);
+        if group.current_round == group.members.len() {
+            group.status = GroupStatus::Completed;
+        }
+        Self::save_group(&env, &group);
+
+        Self::pay(&env, &group, &recipient, pot);
+        let event = DeFiEvent::Savings(SavingsEvent::RoundPaid(SavingsRoundPaidData {
+            group_id,
+            round,
+            recipient,
+            amount: pot,
+            missed_contributions: missed,
+        }));
+        EventEmitter::emit_event(&env, SAVINGS_TOPIC, event);
+
+        // Remaining collateral goes back once everyone has had their turn
+        if group.status == GroupStatus::Completed {
+            for member in group.members.iter() {
+                Self::pay(&env, &group, &member.address, member.collateral);
+            }
+        }
+
+        pot
+    }
+
+    pub fn get_savings_group(env: Env, group_id: u64) -> SavingsGroup {
+        env.storage()
+            .persistent()
+            .get(&SavingsDataKey::Group(group_id))
+            .expect("Savings group not found")
+    }
+
+    /// Member who receives the pot of the current round
+    pub fn get_round_recipient(env: Env, group_id: u64) -> Address {
+        let group = Self::get_savings_group(env, group_id);
+        if group.status != GroupStatus::Active {
+            panic!("Group is not active");
+        }
+        group.members.get_unchecked(group.current_round).address
+    }
+
+    fn member_index(group: &SavingsGroup, address: &Address) -> Option<u32> {
+        group
+            .members
+            .iter()
+            .position(|m| m.address == *address)
+            .map(|i| i as u32)
+    }
+
+    fn pay(env: &Env, group: &SavingsGroup, to: &Address, amount: i128) {
+        if amount > 0 {
+            token::Client::new(env, &group.token).transfer(
+                &env.current_contract_address(),
+                to,
+                &amount,
+            );
+        }
+    }
+
+    fn save_group(env: &Env, group: &SavingsGroup) {
+        env.storage()
+            .persistent()
+            .set(&SavingsDataKey::Group(group.id), group);
+    }
+}
@@ -1,9 +1,9 @@
-use soroban_sdk::symbol_short;
-use soroban_sdk::{contractclient, contracttype, Address, Env, Map, Symbol, Vec};
+use soroban_sdk::{contractclient, contracttype, Address, Env};
 
 #[contractclient(name = "TokenClient")]
 pub trait TokenTrait {
     fn mint(env: Env, to: Address, amount: i128);
+    fn balance(env: Env, id: Address) -> i128;
 }
 
 #[contracttype]
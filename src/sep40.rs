@@ -0,0 +1,73 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol, Vec};
+
+// Rates used by the conversion contract carry 8 decimals
+const RATE_DECIMALS: u32 = 8;
+
+/// Asset identifier used by SEP-40 price feeds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    /// Off-chain asset or fiat currency, e.g. `NGN`
+    Other(Symbol),
+}
+
+/// Price record returned by SEP-40 feeds, quoted in the feed's base asset
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Read side of the SEP-40 oracle interface, implemented by Reflector and
+/// other standard Stellar price feeds
+#[contractclient(name = "Sep40Client")]
+pub trait Sep40Interface {
+    fn base(env: Env) -> Asset;
+    fn assets(env: Env) -> Vec<Asset>;
+    fn decimals(env: Env) -> u32;
+    fn resolution(env: Env) -> u32;
+    fn price(env: Env, asset: Asset, timestamp: u64) -> Option<PriceData>;
+    fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>>;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+/// Where the conversion contract reads a currency pair's rate from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateFeed {
+    /// SEP-40 oracle contract
+    pub oracle: Address,
+    /// Asset whose price in the oracle's base currency gives the rate
+    pub asset: Asset,
+    /// Set when the pair's source currency is the oracle's base, e.g. a
+    /// USD-based feed pricing NGN used for USD to NGN conversions
+    pub invert: bool,
+    /// Prices older than this many seconds are treated as stale
+    pub max_age: u64,
+}
+
+/// Latest rate from `feed` scaled to 8 decimals, or `None` when the feed has
+/// no price or its last price is stale
+pub fn read_feed_rate(env: &Env, feed: &RateFeed) -> Option<i128> {
+    let client = Sep40Client::new(env, &feed.oracle);
+    let data = client.lastprice(&feed.asset)?;
+    if data.price <= 0 || data.timestamp + feed.max_age < env.ledger().timestamp() {
+        return None;
+    }
+
+    let decimals = client.decimals();
+    let rate = if feed.invert {
+        10i128.pow(RATE_DECIMALS + decimals) / data.price
+    } else if decimals >= RATE_DECIMALS {
+        data.price / 10i128.pow(decimals - RATE_DECIMALS)
+    } else {
+        data.price * 10i128.pow(RATE_DECIMALS - decimals)
+    };
+    if rate > 0 {
+        Some(rate)
+    } else {
+        None
+    }
+}
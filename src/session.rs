@@ -0,0 +1,128 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TradingPair {
+    pub base: Address,
+    pub quote: Address,
+}
+
+/// Bounded trading rights a master key delegates to a bot key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TradingSession {
+    pub master: Address,
+    pub pairs: Vec<TradingPair>,
+    /// Most quote notional the bot may trade per UTC day
+    pub max_daily_notional: i128,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionUsage {
+    pub day: u64,
+    pub notional: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SessionDataKey {
+    Session(Address),
+    Usage(Address),
+}
+
+// Bot sessions kept in the trading contract's own storage
+pub struct TradingSessions;
+
+impl TradingSessions {
+    /// Opens or replaces `bot`'s session on behalf of `session.master`
+    pub fn grant(env: &Env, bot: &Address, session: &TradingSession) {
+        session.master.require_auth();
+        if *bot == session.master {
+            panic!("Bot key cannot be the master");
+        }
+        if session.pairs.is_empty() || session.max_daily_notional <= 0 {
+            panic!("Invalid session limits");
+        }
+        if session.expires_at <= env.ledger().timestamp() {
+            panic!("Session expiry must be in the future");
+        }
+        if let Some(existing) = Self::get(env, bot) {
+            if existing.master != session.master {
+                panic!("Bot key bound to another master");
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&SessionDataKey::Session(bot.clone()), session);
+        env.storage()
+            .persistent()
+            .remove(&SessionDataKey::Usage(bot.clone()));
+    }
+
+    /// Ends `bot`'s session immediately (its master only)
+    pub fn revoke(env: &Env, master: &Address, bot: &Address) {
+        master.require_auth();
+        let session = Self::get(env, bot).expect("Session not found");
+        if session.master != *master {
+            panic!("Not the session master");
+        }
+        env.storage()
+            .persistent()
+            .remove(&SessionDataKey::Session(bot.clone()));
+        env.storage()
+            .persistent()
+            .remove(&SessionDataKey::Usage(bot.clone()));
+    }
+
+    pub fn get(env: &Env, bot: &Address) -> Option<TradingSession> {
+        env.storage()
+            .persistent()
+            .get(&SessionDataKey::Session(bot.clone()))
+    }
+
+    /// Notional `bot` has traded today
+    pub fn used_today(env: &Env, bot: &Address) -> i128 {
+        let usage: Option<SessionUsage> = env
+            .storage()
+            .persistent()
+            .get(&SessionDataKey::Usage(bot.clone()));
+        match usage {
+            Some(usage) if usage.day == Self::today(env) => usage.notional,
+            _ => 0,
+        }
+    }
+
+    /// Checks `bot`'s signature and live session for `pair`, books `notional`
+    /// against today's allowance and returns the master it trades for
+    pub fn authorize(env: &Env, bot: &Address, pair: &TradingPair, notional: i128) -> Address {
+        bot.require_auth();
+        let session = Self::get(env, bot).expect("Session not found");
+        if env.ledger().timestamp() >= session.expires_at {
+            panic!("Session expired");
+        }
+        if !session.pairs.contains(pair) {
+            panic!("Pair not allowed in session");
+        }
+
+        let used = Self::used_today(env, bot) + notional;
+        if used > session.max_daily_notional {
+            panic!("Session daily notional exceeded");
+        }
+        let usage = SessionUsage {
+            day: Self::today(env),
+            notional: used,
+        };
+        env.storage()
+            .persistent()
+            .set(&SessionDataKey::Usage(bot.clone()), &usage);
+        session.master
+    }
+
+    fn today(env: &Env) -> u64 {
+        env.ledger().timestamp() / SECONDS_PER_DAY
+    }
+}
@@ -0,0 +1,449 @@
+use crate::errors::AppError;
+use crate::event::{
+    DeFiEvent, EventEmitter, WalletAllowlistData, WalletCosignerChangedData, WalletDestinationData,
+    WalletEvent, WalletPolicyRemovedData, WalletPolicySetData, WalletRecoveryData, WALLET_TOPIC,
+};
+use soroban_sdk::auth::{Context, ContractContext, CustomAccountInterface};
+use soroban_sdk::crypto::Hash;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol, TryFromVal,
+    Vec,
+};
+
+const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+
+/// One ed25519 signature over the authorization payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WalletSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+/// Per-token spending rules. Spends beyond either bound need the co-signer.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendPolicy {
+    /// Most the owner key alone can move per UTC day
+    pub daily_limit: i128,
+    /// Largest single spend the owner key alone can make
+    pub cosign_threshold: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DailySpend {
+    pub day: u64,
+    pub amount: i128,
+}
+
+/// Guardians who can jointly rotate a lost owner key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryConfig {
+    pub guardians: Vec<Address>,
+    /// Approvals needed to start a recovery
+    pub threshold: u32,
+    /// Seconds the current key has to veto a started recovery
+    pub delay: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRecovery {
+    pub new_key: BytesN<32>,
+    pub approvals: Vec<Address>,
+    /// When the rotation can be executed, 0 until enough guardians approve
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum WalletDataKey {
+    OwnerKey,
+    Cosigner,
+    Policy(Address),
+    Spent(Address),
+    AllowlistEnabled,
+    Destination(Address),
+    Recovery,
+    PendingRecovery,
+}
+
+#[contract]
+pub struct SmartWalletContract;
+
+/// User wallet implemented as a custom account. The owner key signs every
+/// call; token spends that exceed the per-token policy, go to a destination
+/// off the allowlist, or change the wallet's own settings also need the 2FA
+/// co-signer when one is configured. A lost owner key can be replaced by
+/// recovery guardians after a delay the current key can veto.
+#[contractimpl]
+impl SmartWalletContract {
+    /// Deploy and initialize in the same transaction, as there is no address
+    /// to authorize this call yet
+    pub fn initialize_wallet(env: Env, owner_key: BytesN<32>, cosigner: Option<BytesN<32>>) {
+        if env.storage().instance().has(&WalletDataKey::OwnerKey) {
+            panic!("Wallet already initialized");
+        }
+        env.storage()
+            .instance()
+            .set(&WalletDataKey::OwnerKey, &owner_key);
+        if let Some(cosigner) = cosigner {
+            env.storage()
+                .instance()
+                .set(&WalletDataKey::Cosigner, &cosigner);
+        }
+    }
+
+    pub fn get_wallet_owner_key(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&WalletDataKey::OwnerKey)
+            .expect("Wallet not initialized")
+    }
+
+    /// Set or clear the 2FA co-signer key (wallet auth)
+    pub fn set_wallet_cosigner(env: Env, cosigner: Option<BytesN<32>>) {
+        env.current_contract_address().require_auth();
+
+        match cosigner.clone() {
+            Some(key) => env.storage().instance().set(&WalletDataKey::Cosigner, &key),
+            None => env.storage().instance().remove(&WalletDataKey::Cosigner),
+        }
+
+        let event = DeFiEvent::Wallet(WalletEvent::CosignerChanged(WalletCosignerChangedData {
+            wallet: env.current_contract_address(),
+            cosigner,
+        }));
+        EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+    }
+
+    pub fn get_wallet_cosigner(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&WalletDataKey::Cosigner)
+    }
+
+    /// Set or clear the spending rules for `token` (wallet auth)
+    pub fn set_wallet_spend_policy(env: Env, token: Address, policy: Option<SpendPolicy>) {
+        env.current_contract_address().require_auth();
+
+        let key = WalletDataKey::Policy(token.clone());
+        let event = match policy {
+            Some(policy) => {
+                if policy.daily_limit < 0 || policy.cosign_threshold < 0 {
+                    panic!("Invalid spend policy");
+                }
+                env.storage().instance().set(&key, &policy);
+                WalletEvent::PolicySet(WalletPolicySetData {
+                    wallet: env.current_contract_address(),
+                    token,
+                    daily_limit: policy.daily_limit,
+                    cosign_threshold: policy.cosign_threshold,
+                })
+            }
+            None => {
+                env.storage().instance().remove(&key);
+                WalletEvent::PolicyRemoved(WalletPolicyRemovedData {
+                    wallet: env.current_contract_address(),
+                    token,
+                })
+            }
+        };
+        EventEmitter::emit_event(&env, WALLET_TOPIC, DeFiEvent::Wallet(event));
+    }
+
+    pub fn get_wallet_spend_policy(env: Env, token: Address) -> Option<SpendPolicy> {
+        env.storage().instance().get(&WalletDataKey::Policy(token))
+    }
+
+    /// Amount of `token` spent so far in the current day
+    pub fn get_wallet_spent_today(env: Env, token: Address) -> i128 {
+        Self::spent_today(&env, &token).amount
+    }
+
+    /// Only allow spends to allowlisted destinations without the co-signer (wallet auth)
+    pub fn set_wallet_allowlist_enabled(env: Env, enabled: bool) {
+        env.current_contract_address().require_auth();
+
+        env.storage()
+            .instance()
+            .set(&WalletDataKey::AllowlistEnabled, &enabled);
+
+        let event = DeFiEvent::Wallet(WalletEvent::AllowlistToggled(WalletAllowlistData {
+            wallet: env.current_contract_address(),
+            enabled,
+        }));
+        EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+    }
+
+    pub fn is_wallet_allowlist_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&WalletDataKey::AllowlistEnabled)
+            .unwrap_or(false)
+    }
+
+    /// Add `destination` to or remove it from the allowlist (wallet auth)
+    pub fn set_wallet_destination(env: Env, destination: Address, allowed: bool) {
+        env.current_contract_address().require_auth();
+
+        let key = WalletDataKey::Destination(destination.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        let event = DeFiEvent::Wallet(WalletEvent::DestinationSet(WalletDestinationData {
+            wallet: env.current_contract_address(),
+            destination,
+            allowed,
+        }));
+        EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+    }
+
+    pub fn is_wallet_destination_allowed(env: Env, destination: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&WalletDataKey::Destination(destination))
+    }
+
+    /// Set or clear the recovery guardians, cancelling any pending recovery (wallet auth)
+    pub fn set_wallet_recovery(env: Env, config: Option<RecoveryConfig>) {
+        env.current_contract_address().require_auth();
+
+        match config {
+            Some(config) => {
+                if config.threshold == 0 || config.threshold > config.guardians.len() {
+                    panic!("Invalid recovery threshold");
+                }
+                env.storage()
+                    .instance()
+                    .set(&WalletDataKey::Recovery, &config);
+            }
+            None => env.storage().instance().remove(&WalletDataKey::Recovery),
+        }
+        env.storage()
+            .instance()
+            .remove(&WalletDataKey::PendingRecovery);
+    }
+
+    pub fn get_wallet_recovery(env: Env) -> Option<RecoveryConfig> {
+        env.storage().instance().get(&WalletDataKey::Recovery)
+    }
+
+    pub fn get_pending_wallet_recovery(env: Env) -> Option<PendingRecovery> {
+        env.storage()
+            .instance()
+            .get(&WalletDataKey::PendingRecovery)
+    }
+
+    /// Approve rotating the owner key to `new_key` (guardian only). The veto
+    /// delay starts once the threshold is reached.
+    pub fn approve_wallet_recovery(env: Env, guardian: Address, new_key: BytesN<32>) {
+        guardian.require_auth();
+        let config = Self::get_wallet_recovery(env.clone()).expect("Recovery not configured");
+        if !config.guardians.contains(&guardian) {
+            panic!("Not a recovery guardian");
+        }
+
+        let mut pending =
+            Self::get_pending_wallet_recovery(env.clone()).unwrap_or(PendingRecovery {
+                new_key: new_key.clone(),
+                approvals: Vec::new(&env),
+                executable_at: 0,
+            });
+        if pending.new_key != new_key {
+            panic!("Another recovery is pending");
+        }
+        if pending.approvals.contains(&guardian) {
+            panic!("Recovery already approved");
+        }
+
+        pending.approvals.push_back(guardian);
+        if pending.executable_at == 0 && pending.approvals.len() >= config.threshold {
+            pending.executable_at = env.ledger().timestamp() + config.delay;
+
+            let event = DeFiEvent::Wallet(WalletEvent::RecoveryStarted(WalletRecoveryData {
+                wallet: env.current_contract_address(),
+                new_key,
+                executable_at: pending.executable_at,
+            }));
+            EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+        }
+        env.storage()
+            .instance()
+            .set(&WalletDataKey::PendingRecovery, &pending);
+    }
+
+    /// Veto the pending recovery; needs only the current owner key (wallet auth)
+    pub fn cancel_wallet_recovery(env: Env) {
+        env.current_contract_address().require_auth();
+        let pending = Self::get_pending_wallet_recovery(env.clone()).expect("No pending recovery");
+        env.storage()
+            .instance()
+            .remove(&WalletDataKey::PendingRecovery);
+
+        let event = DeFiEvent::Wallet(WalletEvent::RecoveryCancelled(WalletRecoveryData {
+            wallet: env.current_contract_address(),
+            new_key: pending.new_key,
+            executable_at: pending.executable_at,
+        }));
+        EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+    }
+
+    /// Rotate the owner key once an approved recovery's delay has passed
+    pub fn execute_wallet_recovery(env: Env) {
+        let pending = Self::get_pending_wallet_recovery(env.clone()).expect("No pending recovery");
+        if pending.executable_at == 0 {
+            panic!("Recovery not approved");
+        }
+        if env.ledger().timestamp() < pending.executable_at {
+            panic!("Recovery delay not elapsed");
+        }
+
+        env.storage()
+            .instance()
+            .set(&WalletDataKey::OwnerKey, &pending.new_key);
+        env.storage()
+            .instance()
+            .remove(&WalletDataKey::PendingRecovery);
+
+        let event = DeFiEvent::Wallet(WalletEvent::Recovered(WalletRecoveryData {
+            wallet: env.current_contract_address(),
+            new_key: pending.new_key,
+            executable_at: pending.executable_at,
+        }));
+        EventEmitter::emit_event(&env, WALLET_TOPIC, event);
+    }
+
+    // Token, destination and amount of a call that moves the wallet's funds.
+    // Approvals count as spends so an allowance cannot get around the limits.
+    fn spend_of(env: &Env, context: &ContractContext) -> Option<(Option<Address>, i128)> {
+        let args = &context.args;
+        let (destination, amount) = if context.fn_name == symbol_short!("transfer")
+            || context.fn_name == symbol_short!("approve")
+        {
+            (Some(args.get(1)?), args.get(2)?)
+        } else if context.fn_name == symbol_short!("burn") {
+            (None, args.get(1)?)
+        } else {
+            return None;
+        };
+
+        let destination = match destination {
+            Some(val) => Some(Address::try_from_val(env, &val).ok()?),
+            None => None,
+        };
+        Some((destination, i128::try_from_val(env, &amount).ok()?))
+    }
+
+    fn spent_today(env: &Env, token: &Address) -> DailySpend {
+        let day = env.ledger().timestamp() / DAY_IN_SECONDS;
+        env.storage()
+            .persistent()
+            .get(&WalletDataKey::Spent(token.clone()))
+            .filter(|spent: &DailySpend| spent.day == day)
+            .unwrap_or(DailySpend { day, amount: 0 })
+    }
+
+    // Whether a spend breaks this wallet's policies, recording it against the
+    // token's daily total
+    fn check_spend(
+        env: &Env,
+        token: &Address,
+        destination: &Option<Address>,
+        amount: i128,
+    ) -> bool {
+        let mut violation = false;
+        if let Some(destination) = destination {
+            if Self::is_wallet_allowlist_enabled(env.clone())
+                && !Self::is_wallet_destination_allowed(env.clone(), destination.clone())
+            {
+                violation = true;
+            }
+        }
+
+        if let Some(policy) = Self::get_wallet_spend_policy(env.clone(), token.clone()) {
+            let mut spent = Self::spent_today(env, token);
+            spent.amount += amount;
+            if amount > policy.cosign_threshold || spent.amount > policy.daily_limit {
+                violation = true;
+            }
+            env.storage()
+                .persistent()
+                .set(&WalletDataKey::Spent(token.clone()), &spent);
+        }
+        violation
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for SmartWalletContract {
+    type Signature = Vec<WalletSignature>;
+    type Error = AppError;
+
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signatures: Vec<WalletSignature>,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), AppError> {
+        let owner_key: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&WalletDataKey::OwnerKey)
+            .ok_or(AppError::NotInitialized)?;
+        let cosigner = Self::get_wallet_cosigner(env.clone());
+
+        let payload: BytesN<32> = signature_payload.to_bytes();
+        let mut owner_signed = false;
+        let mut cosigner_signed = false;
+        for signature in signatures.iter() {
+            if signature.public_key == owner_key {
+                owner_signed = true;
+            } else if Some(&signature.public_key) == cosigner.as_ref() {
+                cosigner_signed = true;
+            } else {
+                return Err(AppError::UnknownSigner);
+            }
+            env.crypto().ed25519_verify(
+                &signature.public_key,
+                &payload.clone().into(),
+                &signature.signature,
+            );
+        }
+        if !owner_signed {
+            return Err(AppError::MissingOwnerSignature);
+        }
+
+        let mut needs_cosigner = false;
+        for context in auth_contexts.iter() {
+            let violation = match &context {
+                Context::Contract(call) if call.contract == env.current_contract_address() => {
+                    // Settings changes need every configured signer, except the
+                    // recovery veto which must work with the owner key alone
+                    cosigner.is_some()
+                        && call.fn_name != Symbol::new(&env, "cancel_wallet_recovery")
+                }
+                Context::Contract(call) => match Self::spend_of(&env, call) {
+                    Some((destination, amount)) => {
+                        Self::check_spend(&env, &call.contract, &destination, amount)
+                    }
+                    None => false,
+                },
+                _ => cosigner.is_some(),
+            };
+            needs_cosigner |= violation;
+        }
+
+        if needs_cosigner && !cosigner_signed {
+            return Err(match cosigner {
+                Some(_) => AppError::CosignerRequired,
+                None => AppError::PolicyViolation,
+            });
+        }
+        Ok(())
+    }
+}
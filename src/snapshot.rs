@@ -0,0 +1,81 @@
+use crate::event::SYSTEM_TOPIC;
+use crate::storage;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, IntoVal, TryFromVal, Val};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SnapshotDataKey {
+    /// Version of the latest snapshot
+    Latest,
+    /// Configuration as of a snapshot, by version
+    Config(u32),
+    /// Who took a snapshot and when, by version
+    Info(u32),
+}
+
+/// Audit record of one configuration snapshot
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotInfo {
+    pub version: u32,
+    pub taken_by: Address,
+    pub taken_at: u64,
+}
+
+// Numbered copies of a contract's configuration, kept in its own storage so
+// a bad parameter push can be reverted in one call. Snapshots and rollbacks
+// are published under the system topic as an audit trail.
+pub struct ConfigSnapshots;
+
+impl ConfigSnapshots {
+    /// Stores `config` as the next version and returns that version
+    pub fn take<C>(env: &Env, config: &C, taken_by: &Address) -> u32
+    where
+        C: IntoVal<Env, Val>,
+    {
+        let version = Self::latest(env) + 1;
+        storage::write(env, &SnapshotDataKey::Config(version), config);
+        storage::write(
+            env,
+            &SnapshotDataKey::Info(version),
+            &SnapshotInfo {
+                version,
+                taken_by: taken_by.clone(),
+                taken_at: env.ledger().timestamp(),
+            },
+        );
+        env.storage()
+            .instance()
+            .set(&SnapshotDataKey::Latest, &version);
+        env.events().publish(
+            (SYSTEM_TOPIC, symbol_short!("cfg_snap"), version),
+            taken_by.clone(),
+        );
+        version
+    }
+
+    /// The configuration stored as `version`, for the caller to put back in
+    /// force; `None` when there is no such snapshot
+    pub fn restore<C>(env: &Env, version: u32, restored_by: &Address) -> Option<C>
+    where
+        C: TryFromVal<Env, Val>,
+    {
+        let config = storage::read(env, &SnapshotDataKey::Config(version))?;
+        env.events().publish(
+            (SYSTEM_TOPIC, symbol_short!("cfg_roll"), version),
+            restored_by.clone(),
+        );
+        Some(config)
+    }
+
+    pub fn latest(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&SnapshotDataKey::Latest)
+            .unwrap_or(0)
+    }
+
+    pub fn info(env: &Env, version: u32) -> Option<SnapshotInfo> {
+        storage::read(env, &SnapshotDataKey::Info(version))
+    }
+}
@@ -0,0 +1,255 @@
+use crate::event::{
+    DeFiEvent, EventEmitter, SplitDepositedData, SplitEvent, SplitReleasedData, SPLIT_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const MAX_PAYEES: u32 = 50;
+const MAX_SPLIT_TOKENS: u32 = 20;
+
+/// Revenue shared between payees in proportion to their shares
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Split {
+    pub id: u64,
+    /// Account allowed to change the payee list
+    pub controller: Address,
+    pub payees: Vec<Address>,
+    /// `shares[i]` belongs to `payees[i]`
+    pub shares: Vec<u32>,
+    pub total_shares: u32,
+    /// Tokens ever deposited into the split
+    pub tokens: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SplitDataKey {
+    SplitCount,
+    Split(u64),
+    /// Total deposited into a split for a token since the payee list was last set
+    Received(u64, Address),
+    Released(u64, Address, Address),
+    TotalReleased(u64, Address),
+}
+
+#[contract]
+pub struct SplitterContract;
+
+/// Tokens deposited into a split are owed to its payees pro rata and can be
+/// pulled by each payee with `release_share` or pushed to all of them with `distribute`.
+#[contractimpl]
+impl SplitterContract {
+    pub fn create_split(
+        env: Env,
+        controller: Address,
+        payees: Vec<Address>,
+        shares: Vec<u32>,
+    ) -> u64 {
+        controller.require_auth();
+        let total_shares = Self::validate_payees(&payees, &shares);
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&SplitDataKey::SplitCount)
+            .unwrap_or(0)
+            + 1;
+        let split = Split {
+            id,
+            controller,
+            payees,
+            shares,
+            total_shares,
+            tokens: Vec::new(&env),
+        };
+        Self::save_split(&env, &split);
+        env.storage().instance().set(&SplitDataKey::SplitCount, &id);
+
+        id
+    }
+
+    /// Replaces the payee list (controller only). Everything owed under the old
+    /// list is paid out first; rounding dust carries over to the new list.
+    pub fn update_split(env: Env, split_id: u64, payees: Vec<Address>, shares: Vec<u32>) {
+        let mut split = Self::get_split(env.clone(), split_id);
+        split.controller.require_auth();
+        let total_shares = Self::validate_payees(&payees, &shares);
+
+        for token in split.tokens.iter() {
+            Self::distribute(env.clone(), split_id, token.clone());
+
+            let leftover = Self::get_total_received(env.clone(), split_id, token.clone())
+                - Self::total_released(&env, split_id, &token);
+            for payee in split.payees.iter() {
+                env.storage().persistent().remove(&SplitDataKey::Released(
+                    split_id,
+                    token.clone(),
+                    payee,
+                ));
+            }
+            env.storage()
+                .persistent()
+                .remove(&SplitDataKey::TotalReleased(split_id, token.clone()));
+            env.storage()
+                .persistent()
+                .set(&SplitDataKey::Received(split_id, token), &leftover);
+        }
+
+        split.payees = payees;
+        split.shares = shares;
+        split.total_shares = total_shares;
+        Self::save_split(&env, &split);
+    }
+
+    /// Sends `amount` of `token` from `from` into the split
+    pub fn deposit_to_split(env: Env, from: Address, split_id: u64, token: Address, amount: i128) {
+        from.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let mut split = Self::get_split(env.clone(), split_id);
+        if !split.tokens.contains(&token) {
+            if split.tokens.len() >= MAX_SPLIT_TOKENS {
+                panic!("Too many tokens in split");
+            }
+            split.tokens.push_back(token.clone());
+            Self::save_split(&env, &split);
+        }
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+        let received = Self::get_total_received(env.clone(), split_id, token.clone()) + amount;
+        env.storage()
+            .persistent()
+            .set(&SplitDataKey::Received(split_id, token.clone()), &received);
+
+        let event = DeFiEvent::Split(SplitEvent::Deposited(SplitDepositedData {
+            split_id,
+            from,
+            token,
+            amount,
+        }));
+        EventEmitter::emit_event(&env, SPLIT_TOPIC, event);
+    }
+
+    /// Pays `payee` their share of `token` not yet released. Anyone may call it
+    /// since the funds can only go to the payee.
+    pub fn release_share(env: Env, split_id: u64, token: Address, payee: Address) -> i128 {
+        let split = Self::get_split(env.clone(), split_id);
+        let amount = Self::releasable(&env, &split, &token, &payee);
+        if amount <= 0 {
+            panic!("Nothing to release");
+        }
+        Self::pay(&env, split_id, &token, &payee, amount);
+        amount
+    }
+
+    /// Releases every payee's pending share of `token`, returning the total paid
+    pub fn distribute(env: Env, split_id: u64, token: Address) -> i128 {
+        let split = Self::get_split(env.clone(), split_id);
+        let mut total = 0;
+        for payee in split.payees.iter() {
+            let amount = Self::releasable(&env, &split, &token, &payee);
+            if amount > 0 {
+                Self::pay(&env, split_id, &token, &payee, amount);
+                total += amount;
+            }
+        }
+        total
+    }
+
+    pub fn get_split(env: Env, split_id: u64) -> Split {
+        env.storage()
+            .persistent()
+            .get(&SplitDataKey::Split(split_id))
+            .expect("Split not found")
+    }
+
+    pub fn get_releasable_amount(env: Env, split_id: u64, token: Address, payee: Address) -> i128 {
+        let split = Self::get_split(env.clone(), split_id);
+        Self::releasable(&env, &split, &token, &payee)
+    }
+
+    pub fn get_released_amount(env: Env, split_id: u64, token: Address, payee: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&SplitDataKey::Released(split_id, token, payee))
+            .unwrap_or(0)
+    }
+
+    pub fn get_total_received(env: Env, split_id: u64, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&SplitDataKey::Received(split_id, token))
+            .unwrap_or(0)
+    }
+
+    fn validate_payees(payees: &Vec<Address>, shares: &Vec<u32>) -> u32 {
+        if payees.is_empty() || payees.len() != shares.len() {
+            panic!("Payees and shares length mismatch");
+        }
+        if payees.len() > MAX_PAYEES {
+            panic!("Too many payees");
+        }
+
+        let mut total = 0u32;
+        for (index, payee) in payees.iter().enumerate() {
+            if payees.first_index_of(&payee) != Some(index as u32) {
+                panic!("Duplicate payee");
+            }
+            let share = shares.get_unchecked(index as u32);
+            if share == 0 {
+                panic!("Shares must be positive");
+            }
+            total = total.checked_add(share).expect("Shares overflow");
+        }
+        total
+    }
+
+    fn releasable(env: &Env, split: &Split, token: &Address, payee: &Address) -> i128 {
+        let index = match split.payees.first_index_of(payee) {
+            Some(index) => index,
+            None => panic!("Not a payee"),
+        };
+        let received = Self::get_total_received(env.clone(), split.id, token.clone());
+        let entitled =
+            received * split.shares.get_unchecked(index) as i128 / split.total_shares as i128;
+        entitled - Self::get_released_amount(env.clone(), split.id, token.clone(), payee.clone())
+    }
+
+    fn total_released(env: &Env, split_id: u64, token: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&SplitDataKey::TotalReleased(split_id, token.clone()))
+            .unwrap_or(0)
+    }
+
+    fn pay(env: &Env, split_id: u64, token: &Address, payee: &Address, amount: i128) {
+        let released =
+            Self::get_released_amount(env.clone(), split_id, token.clone(), payee.clone()) + amount;
+        env.storage().persistent().set(
+            &SplitDataKey::Released(split_id, token.clone(), payee.clone()),
+            &released,
+        );
+        let total = Self::total_released(env, split_id, token) + amount;
+        env.storage().persistent().set(
+            &SplitDataKey::TotalReleased(split_id, token.clone()),
+            &total,
+        );
+
+        token::Client::new(env, token).transfer(&env.current_contract_address(), payee, &amount);
+
+        let event = DeFiEvent::Split(SplitEvent::Released(SplitReleasedData {
+            split_id,
+            token: token.clone(),
+            payee: payee.clone(),
+            amount,
+        }));
+        EventEmitter::emit_event(env, SPLIT_TOPIC, event);
+    }
+
+    fn save_split(env: &Env, split: &Split) {
+        env.storage()
+            .persistent()
+            .set(&SplitDataKey::Split(split.id), split);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, SponsoredCallData, SponsorshipEvent, SponsorshipFundedData,
+    SPONSORSHIP_TOPIC,
+};
+use crate::rescue::Rescue;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Symbol, Val, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorshipConfig {
+    /// Token the sponsor's credits are held in
+    pub fee_token: Address,
+    /// Credits paid to the relayer for each sponsored call
+    pub fee_per_call: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorQuota {
+    /// Sponsored calls the user may make in total
+    pub limit: u32,
+    pub used: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SponsorshipDataKey {
+    Admin,
+    Config,
+    Function(Address, Symbol),
+    Quota(Address),
+}
+
+#[contract]
+pub struct SponsorshipContract;
+
+/// Fee sponsorship for users without XLM. Platform relayers (operators) submit
+/// the transaction and pay the network fee; each relayed call to an allowed
+/// function is charged against the user's quota and reimbursed to the relayer
+/// from the sponsor's credits.
+#[contractimpl]
+impl SponsorshipContract {
+    pub fn initialize_sponsorship(
+        env: Env,
+        admin: Address,
+        fee_token: Address,
+        fee_per_call: i128,
+    ) {
+        if env.storage().instance().has(&SponsorshipDataKey::Admin) {
+            panic!("Sponsorship already initialized");
+        }
+        admin.require_auth();
+        if fee_per_call < 0 {
+            panic!("Invalid fee per call");
+        }
+        env.storage()
+            .instance()
+            .set(&SponsorshipDataKey::Admin, &admin);
+        env.storage().instance().set(
+            &SponsorshipDataKey::Config,
+            &SponsorshipConfig {
+                fee_token,
+                fee_per_call,
+            },
+        );
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    pub fn get_sponsorship_config(env: Env) -> SponsorshipConfig {
+        env.storage()
+            .instance()
+            .get(&SponsorshipDataKey::Config)
+            .expect("Sponsorship not initialized")
+    }
+
+    /// Add credits to the sponsorship pool
+    pub fn fund_sponsorship(env: Env, funder: Address, amount: i128) {
+        funder.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let config = Self::get_sponsorship_config(env.clone());
+        token::Client::new(&env, &config.fee_token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let event = DeFiEvent::Sponsorship(SponsorshipEvent::Funded(SponsorshipFundedData {
+            funder,
+            amount,
+        }));
+        EventEmitter::emit_event(&env, SPONSORSHIP_TOPIC, event);
+    }
+
+    /// Credits left to reimburse relayers with
+    pub fn get_sponsor_credits(env: Env) -> i128 {
+        let config = Self::get_sponsorship_config(env.clone());
+        token::Client::new(&env, &config.fee_token).balance(&env.current_contract_address())
+    }
+
+    /// Take credits back out of the pool (owner only)
+    pub fn withdraw_sponsorship(env: Env, caller: Address, to: Address, amount: i128) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "withdraw_sponsorship",
+            (to.clone(), amount),
+        );
+        let config = Self::get_sponsorship_config(env.clone());
+        token::Client::new(&env, &config.fee_token).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+    }
+
+    /// Allow or stop sponsoring calls to `function` on `contract` (owner only)
+    pub fn set_sponsored_function(
+        env: Env,
+        caller: Address,
+        contract: Address,
+        function: Symbol,
+        enabled: bool,
+    ) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_sponsored_function",
+            (contract.clone(), function.clone(), enabled),
+        );
+        let key = SponsorshipDataKey::Function(contract, function);
+        if enabled {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+
+    pub fn is_sponsored_function(env: Env, contract: Address, function: Symbol) -> bool {
+        env.storage()
+            .instance()
+            .has(&SponsorshipDataKey::Function(contract, function))
+    }
+
+    /// Register `user` for sponsorship or change their quota, keeping what
+    /// they have used so far (operator only)
+    pub fn set_sponsored_user_quota(env: Env, caller: Address, user: Address, limit: u32) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &caller,
+            "set_sponsored_user_quota",
+            (user.clone(), limit),
+        );
+        let used = Self::get_sponsored_user_quota(env.clone(), user.clone())
+            .map(|quota| quota.used)
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &SponsorshipDataKey::Quota(user),
+            &SponsorQuota { limit, used },
+        );
+    }
+
+    pub fn get_sponsored_user_quota(env: Env, user: Address) -> Option<SponsorQuota> {
+        env.storage()
+            .persistent()
+            .get(&SponsorshipDataKey::Quota(user))
+    }
+
+    /// Invoke `function` on `contract` for `user` and reimburse the relayer
+    /// (operator only). The user authorizes this call, which also covers any
+    /// authorization the target function requires from them.
+    pub fn relay_sponsored(
+        env: Env,
+        relayer: Address,
+        user: Address,
+        contract: Address,
+        function: Symbol,
+        args: Vec<Val>,
+    ) -> Val {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &relayer,
+            "relay_sponsored",
+            (
+                user.clone(),
+                contract.clone(),
+                function.clone(),
+                args.clone(),
+            ),
+        );
+        user.require_auth();
+        if !Self::is_sponsored_function(env.clone(), contract.clone(), function.clone()) {
+            panic!("Function not sponsored");
+        }
+
+        let quota_key = SponsorshipDataKey::Quota(user.clone());
+        let mut quota =
+            Self::get_sponsored_user_quota(env.clone(), user.clone()).expect("User not sponsored");
+        if quota.used >= quota.limit {
+            panic!("Sponsorship quota exhausted");
+        }
+        quota.used += 1;
+        env.storage().persistent().set(&quota_key, &quota);
+
+        let config = Self::get_sponsorship_config(env.clone());
+        if config.fee_per_call > 0 {
+            let client = token::Client::new(&env, &config.fee_token);
+            if client.balance(&env.current_contract_address()) < config.fee_per_call {
+                panic!("Sponsorship credits exhausted");
+            }
+            client.transfer(
+                &env.current_contract_address(),
+                &relayer,
+                &config.fee_per_call,
+            );
+        }
+
+        let result: Val = env.invoke_contract(&contract, &function, args);
+
+        let event = DeFiEvent::Sponsorship(SponsorshipEvent::Relayed(SponsoredCallData {
+            user,
+            contract,
+            function,
+            relayer,
+            fee: config.fee_per_call,
+        }));
+        EventEmitter::emit_event(&env, SPONSORSHIP_TOPIC, event);
+        result
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_sponsorship_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_sponsorship_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_sponsorship_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_sponsorship_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_sponsorship_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_sponsorship_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_sponsorship_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_sponsorship_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_sponsorship_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// Credits are the fee token's whole balance, so it is never stray
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let config = Self::get_sponsorship_config(env.clone());
+        if config.fee_token == *token {
+            None
+        } else {
+            Some(0)
+        }
+    }
+}
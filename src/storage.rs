@@ -0,0 +1,158 @@
+//! Storage layout and TTL helpers.
+//!
+//! Instance storage is loaded in full on every invocation and shares one
+//! entry (and one size limit) with the contract itself, so it only holds
+//! bounded configuration: admins, policies, hooks, counters and per-currency
+//! settings. Anything keyed by a user, position or record id lives in
+//! persistent storage, where each entry has its own TTL. `STORAGE_LAYOUT`
+//! lists where the per-entity records of each contract live.
+
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+/// Ledgers closed in a day at five seconds per ledger
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// TTL an instance is extended to whenever a record is written
+pub const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+/// Remaining TTL below which the instance is extended
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// TTL a persistent record is extended to when read or written
+pub const PERSISTENT_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+/// Remaining TTL below which a persistent record is extended
+pub const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 7 * DAY_IN_LEDGERS;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageTier {
+    Instance,
+    Persistent,
+}
+
+/// Where one kind of key is stored
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyLayout {
+    pub contract: &'static str,
+    pub key: &'static str,
+    pub tier: StorageTier,
+}
+
+const fn persistent(contract: &'static str, key: &'static str) -> KeyLayout {
+    KeyLayout {
+        contract,
+        key,
+        tier: StorageTier::Persistent,
+    }
+}
+
+/// Per-entity records and the tier they live in
+pub const STORAGE_LAYOUT: &[KeyLayout] = &[
+    persistent("ConversionContract", "DataKey::Balance(Address)"),
+    persistent("ConversionContract", "DataKey::Transaction(Symbol)"),
+    persistent(
+        "ConversionContract",
+        "DataKey::BalanceHistoryLen(Address, Currency)",
+    ),
+    persistent(
+        "ConversionContract",
+        "DataKey::BalanceHistory(Address, Currency, u32)",
+    ),
+    persistent("ConversionContract", "DataKey::WithdrawalGuard(Address)"),
+    persistent("ConversionContract", "DataKey::PendingGuardChange(Address)"),
+    persistent("ConversionContract", "DataKey::Withdrawal(u64)"),
+    persistent("ConversionContract", "DataKey::LimitOrder(u64)"),
+    persistent(
+        "ConversionContract",
+        "DataKey::AutoSupplied(Address, Currency)",
+    ),
+    persistent("ConversionContract", "PromoDataKey::Code(BytesN<32>)"),
+    persistent("ConversionContract", "SnapshotDataKey::Config(u32)"),
+    persistent("ConversionContract", "SnapshotDataKey::Info(u32)"),
+    persistent(
+        "AddressBookContract",
+        "AddressBookDataKey::Beneficiary(Address, Address)",
+    ),
+    persistent(
+        "AddressBookContract",
+        "AddressBookDataKey::WhitelistOnlyUntil(Address)",
+    ),
+    persistent("TokenContract", "Address (balance)"),
+    persistent("TokenContract", "(ALLOW, Address, Address)"),
+    persistent("TokenContract", "(CHKPT, Address)"),
+    persistent("TokenContract", "(LOCKS, Address)"),
+    persistent("PoolManager", "PoolDataKey::Position(Address, Currency)"),
+    persistent("PoolManager", "PoolDataKey::CurrencyProviders(Currency)"),
+    persistent("PoolManager", "PoolDataKey::Rewards(Address, Currency)"),
+    persistent("PoolManager", "SnapshotDataKey::Config(u32)"),
+    persistent("PoolManager", "SnapshotDataKey::Info(u32)"),
+    persistent("EscrowContract", "Symbol (escrow id)"),
+    persistent("EscrowContract", "(Symbol, DISPUTE)"),
+    persistent("EscrowContract", "(Symbol, RECEIPT)"),
+    persistent("EscrowContract", "(Symbol, DELEGATE)"),
+    persistent("EscrowContract", "(Symbol, NOTES)"),
+    persistent("EscrowContract", "(Symbol, TIMEOUT)"),
+    persistent("EscrowContract", "(Symbol, PROMO)"),
+    persistent("EscrowContract", "(Symbol, ESCALATE)"),
+    persistent("EscrowContract", "(Symbol, CROWD)"),
+    persistent("EscrowContract", "(Symbol, CONTRIB, Address)"),
+    persistent("EscrowContract", "(Symbol, DEPOSIT)"),
+    persistent("EscrowContract", "PromoDataKey::Code(BytesN<32>)"),
+    persistent("FeeSplitterContract", "SnapshotDataKey::Config(u32)"),
+    persistent("FeeSplitterContract", "SnapshotDataKey::Info(u32)"),
+    persistent("AuditLogContract", "AuditLogDataKey::Entry(u64)"),
+];
+
+/// Keep the contract instance, and the configuration in it, alive
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Keep a persistent record alive; the record must exist
+pub fn extend_persistent<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val>,
+{
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+/// Read a persistent record, extending its TTL when it exists
+pub fn read<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+{
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent(env, key);
+    }
+    value
+}
+
+/// Write a persistent record and extend both it and the contract instance
+pub fn write<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    extend_persistent(env, key);
+    extend_instance(env);
+}
+
+/// Move a record an older layout kept in instance storage to its persistent
+/// key, for storage migrations. Returns the record when there was one.
+pub fn move_from_instance<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let value: V = env.storage().instance().get(key)?;
+    env.storage().instance().remove(key);
+    write(env, key, &value);
+    Some(value)
+}
@@ -0,0 +1,299 @@
+use crate::event::{
+    DeFiEvent, EventEmitter, StreamCancelledData, StreamCreatedData, StreamEvent,
+    StreamPauseChangedData, StreamWithdrawnData, STREAM_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamStatus {
+    Active,
+    Paused,
+    Cancelled,
+    /// Fully streamed and withdrawn
+    Completed,
+}
+
+/// Tokens paid out to `recipient` continuously over `duration` seconds of
+/// streaming time. Paused time does not count, so pausing pushes the end back.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub deposit: i128,
+    /// Nominal per-second rate, `deposit / duration` rounded down
+    pub rate_per_second: i128,
+    pub start_time: u64,
+    /// Streaming seconds needed to pay out the whole deposit
+    pub duration: u64,
+    /// Streaming seconds accrued before the current active period
+    pub accrued_seconds: u64,
+    /// Start of the current active period
+    pub active_since: u64,
+    pub withdrawn: i128,
+    pub status: StreamStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StreamDataKey {
+    StreamCount,
+    Stream(u64),
+    AccountStreams(Address),
+}
+
+#[contract]
+pub struct StreamContract;
+
+#[contractimpl]
+impl StreamContract {
+    /// Deposits `deposit` tokens from the sender, streamed between `start_time`
+    /// and `stop_time`
+    pub fn create_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        deposit: i128,
+        start_time: u64,
+        stop_time: u64,
+    ) -> u64 {
+        sender.require_auth();
+        if deposit <= 0 {
+            panic!("Deposit must be positive");
+        }
+        if stop_time <= start_time || start_time < env.ledger().timestamp() {
+            panic!("Invalid stream times");
+        }
+        if sender == recipient {
+            panic!("Cannot stream to self");
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &deposit,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&StreamDataKey::StreamCount)
+            .unwrap_or(0)
+            + 1;
+        let duration = stop_time - start_time;
+        let stream = Stream {
+            id,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token,
+            deposit,
+            rate_per_second: deposit / duration as i128,
+            start_time,
+            duration,
+            accrued_seconds: 0,
+            active_since: start_time,
+            withdrawn: 0,
+            status: StreamStatus::Active,
+        };
+        Self::save_stream(&env, &stream);
+        env.storage()
+            .instance()
+            .set(&StreamDataKey::StreamCount, &id);
+        Self::index_stream(&env, &sender, id);
+        Self::index_stream(&env, &recipient, id);
+
+        let event = DeFiEvent::Stream(StreamEvent::Created(StreamCreatedData {
+            stream_id: id,
+            sender,
+            recipient,
+            token: stream.token,
+            deposit,
+            start_time,
+            stop_time,
+        }));
+        EventEmitter::emit_event(&env, STREAM_TOPIC, event);
+
+        id
+    }
+
+    /// Amount streamed to the recipient so far, including withdrawals
+    pub fn get_streamed_amount(env: Env, stream_id: u64) -> i128 {
+        let stream = Self::get_stream(env.clone(), stream_id);
+        Self::streamed(&env, &stream)
+    }
+
+    pub fn get_withdrawable_amount(env: Env, stream_id: u64) -> i128 {
+        let stream = Self::get_stream(env.clone(), stream_id);
+        Self::streamed(&env, &stream) - stream.withdrawn
+    }
+
+    /// Pays everything streamed and not yet withdrawn to the recipient
+    pub fn withdraw_streamed(env: Env, stream_id: u64) -> i128 {
+        let mut stream = Self::get_stream(env.clone(), stream_id);
+        stream.recipient.require_auth();
+        if stream.status == StreamStatus::Cancelled || stream.status == StreamStatus::Completed {
+            panic!("Stream is closed");
+        }
+
+        let amount = Self::streamed(&env, &stream) - stream.withdrawn;
+        if amount <= 0 {
+            panic!("Nothing to withdraw");
+        }
+
+        stream.withdrawn += amount;
+        if stream.withdrawn == stream.deposit {
+            stream.status = StreamStatus::Completed;
+        }
+        Self::save_stream(&env, &stream);
+        token::Client::new(&env, &stream.token).transfer(
+            &env.current_contract_address(),
+            &stream.recipient,
+            &amount,
+        );
+
+        let event = DeFiEvent::Stream(StreamEvent::Withdrawn(StreamWithdrawnData {
+            stream_id,
+            recipient: stream.recipient,
+            amount,
+            total_withdrawn: stream.withdrawn,
+            withdrawn_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, STREAM_TOPIC, event);
+
+        amount
+    }
+
+    /// Stops accrual until resumed (sender only)
+    pub fn pause_stream(env: Env, stream_id: u64) {
+        let mut stream = Self::get_stream(env.clone(), stream_id);
+        stream.sender.require_auth();
+        if stream.status != StreamStatus::Active {
+            panic!("Stream is not active");
+        }
+
+        stream.accrued_seconds = Self::streamed_seconds(&env, &stream);
+        stream.status = StreamStatus::Paused;
+        Self::save_stream(&env, &stream);
+        Self::emit_pause_changed(&env, &stream, true);
+    }
+
+    /// Continues accrual from now; the paused time is added to the end (sender only)
+    pub fn resume_stream(env: Env, stream_id: u64) {
+        let mut stream = Self::get_stream(env.clone(), stream_id);
+        stream.sender.require_auth();
+        if stream.status != StreamStatus::Paused {
+            panic!("Stream is not paused");
+        }
+
+        stream.active_since = env.ledger().timestamp().max(stream.start_time);
+        stream.status = StreamStatus::Active;
+        Self::save_stream(&env, &stream);
+        Self::emit_pause_changed(&env, &stream, false);
+    }
+
+    /// Ends the stream, paying the recipient what has streamed and refunding the
+    /// rest to the sender (sender only)
+    pub fn cancel_stream(env: Env, stream_id: u64) -> (i128, i128) {
+        let mut stream = Self::get_stream(env.clone(), stream_id);
+        stream.sender.require_auth();
+        if stream.status == StreamStatus::Cancelled || stream.status == StreamStatus::Completed {
+            panic!("Stream is closed");
+        }
+
+        let streamed = Self::streamed(&env, &stream);
+        let recipient_amount = streamed - stream.withdrawn;
+        let sender_amount = stream.deposit - streamed;
+        stream.withdrawn = streamed;
+        stream.status = StreamStatus::Cancelled;
+        Self::save_stream(&env, &stream);
+
+        let token = token::Client::new(&env, &stream.token);
+        if recipient_amount > 0 {
+            token.transfer(
+                &env.current_contract_address(),
+                &stream.recipient,
+                &recipient_amount,
+            );
+        }
+        if sender_amount > 0 {
+            token.transfer(
+                &env.current_contract_address(),
+                &stream.sender,
+                &sender_amount,
+            );
+        }
+
+        let event = DeFiEvent::Stream(StreamEvent::Cancelled(StreamCancelledData {
+            stream_id,
+            recipient_amount,
+            sender_amount,
+            cancelled_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, STREAM_TOPIC, event);
+
+        (recipient_amount, sender_amount)
+    }
+
+    pub fn get_stream(env: Env, stream_id: u64) -> Stream {
+        env.storage()
+            .persistent()
+            .get(&StreamDataKey::Stream(stream_id))
+            .expect("Stream not found")
+    }
+
+    /// Ids of streams the account sends or receives
+    pub fn get_account_streams(env: Env, account: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&StreamDataKey::AccountStreams(account))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn streamed_seconds(env: &Env, stream: &Stream) -> u64 {
+        let now = env.ledger().timestamp();
+        let mut seconds = stream.accrued_seconds;
+        if stream.status == StreamStatus::Active && now > stream.active_since {
+            seconds += now - stream.active_since;
+        }
+        seconds.min(stream.duration)
+    }
+
+    fn streamed(env: &Env, stream: &Stream) -> i128 {
+        if stream.status == StreamStatus::Cancelled || stream.status == StreamStatus::Completed {
+            return stream.withdrawn;
+        }
+        stream.deposit * Self::streamed_seconds(env, stream) as i128 / stream.duration as i128
+    }
+
+    fn save_stream(env: &Env, stream: &Stream) {
+        env.storage()
+            .persistent()
+            .set(&StreamDataKey::Stream(stream.id), stream);
+    }
+
+    fn index_stream(env: &Env, account: &Address, stream_id: u64) {
+        let mut ids = Self::get_account_streams(env.clone(), account.clone());
+        ids.push_back(stream_id);
+        env.storage()
+            .persistent()
+            .set(&StreamDataKey::AccountStreams(account.clone()), &ids);
+    }
+
+    fn emit_pause_changed(env: &Env, stream: &Stream, paused: bool) {
+        let data = StreamPauseChangedData {
+            stream_id: stream.id,
+            streamed: Self::streamed(env, stream),
+            changed_at: env.ledger().timestamp(),
+        };
+        let event = if paused {
+            DeFiEvent::Stream(StreamEvent::Paused(data))
+        } else {
+            DeFiEvent::Stream(StreamEvent::Resumed(data))
+        };
+        EventEmitter::emit_event(env, STREAM_TOPIC, event);
+    }
+}
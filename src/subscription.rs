@@ -0,0 +1,313 @@
+use crate::event::{
+    DeFiEvent, EventEmitter, InvoiceChargedData, SubscriptionCancelledData,
+    SubscriptionCreatedData, SubscriptionEvent, BILLING_TOPIC,
+};
+use crate::keeper::KeeperTip;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+/// What a merchant charges, and how often
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub id: u64,
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Seconds between charges
+    pub interval: u64,
+    /// Inactive plans take no new subscribers and charge no one
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionStatus {
+    Active,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub id: u64,
+    pub plan_id: u64,
+    pub subscriber: Address,
+    /// Most the subscriber allows to be pulled over the subscription's lifetime
+    pub spending_cap: i128,
+    pub total_charged: i128,
+    /// Number of invoices issued so far
+    pub invoice_count: u32,
+    pub next_charge_at: u64,
+    pub status: SubscriptionStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SubscriptionDataKey {
+    PlanCount,
+    Plan(u64),
+    SubscriptionCount,
+    Subscription(u64),
+    SubscriberSubscriptions(Address),
+    KeeperTip(u64),
+}
+
+#[contract]
+pub struct SubscriptionContract;
+
+/// Recurring billing: subscribers approve this contract to pull a plan's
+/// amount each interval, and keepers call `charge` when a payment is due.
+#[contractimpl]
+impl SubscriptionContract {
+    pub fn create_plan(
+        env: Env,
+        merchant: Address,
+        token: Address,
+        amount: i128,
+        interval: u64,
+    ) -> u64 {
+        merchant.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if interval == 0 {
+            panic!("Interval must be positive");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&SubscriptionDataKey::PlanCount)
+            .unwrap_or(0)
+            + 1;
+        let plan = Plan {
+            id,
+            merchant,
+            token,
+            amount,
+            interval,
+            active: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&SubscriptionDataKey::Plan(id), &plan);
+        env.storage()
+            .instance()
+            .set(&SubscriptionDataKey::PlanCount, &id);
+
+        id
+    }
+
+    /// Stops new subscriptions and further charges on the plan (merchant only)
+    pub fn deactivate_plan(env: Env, plan_id: u64) {
+        let mut plan = Self::get_plan(env.clone(), plan_id);
+        plan.merchant.require_auth();
+        plan.active = false;
+        env.storage()
+            .persistent()
+            .set(&SubscriptionDataKey::Plan(plan_id), &plan);
+    }
+
+    /// Sets or clears the tip a keeper earns on each charge of the plan,
+    /// paid out of the charged amount (merchant only)
+    pub fn set_plan_keeper_tip(env: Env, plan_id: u64, tip: Option<KeeperTip>) {
+        let plan = Self::get_plan(env.clone(), plan_id);
+        plan.merchant.require_auth();
+        let key = SubscriptionDataKey::KeeperTip(plan_id);
+        match tip {
+            Some(tip) => {
+                tip.validate();
+                env.storage().persistent().set(&key, &tip);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_plan_keeper_tip(env: Env, plan_id: u64) -> Option<KeeperTip> {
+        env.storage()
+            .persistent()
+            .get(&SubscriptionDataKey::KeeperTip(plan_id))
+    }
+
+    /// Subscribes to a plan and approves this contract to pull up to
+    /// `spending_cap` until `expiration_ledger`. The first charge is due immediately.
+    pub fn subscribe(
+        env: Env,
+        subscriber: Address,
+        plan_id: u64,
+        spending_cap: i128,
+        expiration_ledger: u32,
+    ) -> u64 {
+        subscriber.require_auth();
+        let plan = Self::get_plan(env.clone(), plan_id);
+        if !plan.active {
+            panic!("Plan is not active");
+        }
+        if spending_cap < plan.amount {
+            panic!("Spending cap below plan amount");
+        }
+
+        token::Client::new(&env, &plan.token).approve(
+            &subscriber,
+            &env.current_contract_address(),
+            &spending_cap,
+            &expiration_ledger,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&SubscriptionDataKey::SubscriptionCount)
+            .unwrap_or(0)
+            + 1;
+        let subscription = Subscription {
+            id,
+            plan_id,
+            subscriber: subscriber.clone(),
+            spending_cap,
+            total_charged: 0,
+            invoice_count: 0,
+            next_charge_at: env.ledger().timestamp(),
+            status: SubscriptionStatus::Active,
+        };
+        Self::save_subscription(&env, &subscription);
+        env.storage()
+            .instance()
+            .set(&SubscriptionDataKey::SubscriptionCount, &id);
+
+        let mut ids = Self::get_subscriber_subscriptions(env.clone(), subscriber.clone());
+        ids.push_back(id);
+        env.storage().persistent().set(
+            &SubscriptionDataKey::SubscriberSubscriptions(subscriber.clone()),
+            &ids,
+        );
+
+        let event = DeFiEvent::Subscription(SubscriptionEvent::Created(SubscriptionCreatedData {
+            subscription_id: id,
+            plan_id,
+            subscriber,
+            merchant: plan.merchant,
+            spending_cap,
+        }));
+        EventEmitter::emit_event(&env, BILLING_TOPIC, event);
+
+        id
+    }
+
+    /// Pulls the plan amount from the subscriber to the merchant when a payment
+    /// is due. Callable by anyone, typically a keeper; `keeper` receives the
+    /// plan's keeper tip out of the amount.
+    pub fn charge(env: Env, keeper: Address, subscription_id: u64) -> i128 {
+        let mut subscription = Self::get_subscription(env.clone(), subscription_id);
+        if subscription.status != SubscriptionStatus::Active {
+            panic!("Subscription is not active");
+        }
+        let plan = Self::get_plan(env.clone(), subscription.plan_id);
+        if !plan.active {
+            panic!("Plan is not active");
+        }
+        let now = env.ledger().timestamp();
+        if now < subscription.next_charge_at {
+            panic!("Payment not due");
+        }
+        if subscription.total_charged + plan.amount > subscription.spending_cap {
+            panic!("Spending cap reached");
+        }
+
+        let period_start = subscription.next_charge_at;
+        subscription.total_charged += plan.amount;
+        subscription.invoice_count += 1;
+        subscription.next_charge_at += plan.interval;
+        Self::save_subscription(&env, &subscription);
+
+        let tip =
+            Self::get_plan_keeper_tip(env.clone(), plan.id).map_or(0, |tip| tip.on(plan.amount));
+        let token = token::Client::new(&env, &plan.token);
+        token.transfer_from(
+            &env.current_contract_address(),
+            &subscription.subscriber,
+            &plan.merchant,
+            &(plan.amount - tip),
+        );
+        if tip > 0 {
+            token.transfer_from(
+                &env.current_contract_address(),
+                &subscription.subscriber,
+                &keeper,
+                &tip,
+            );
+        }
+
+        let event = DeFiEvent::Subscription(SubscriptionEvent::Invoiced(InvoiceChargedData {
+            subscription_id,
+            invoice_number: subscription.invoice_count,
+            subscriber: subscription.subscriber,
+            merchant: plan.merchant,
+            token: plan.token,
+            amount: plan.amount,
+            period_start,
+            period_end: subscription.next_charge_at,
+            charged_at: now,
+        }));
+        EventEmitter::emit_event(&env, BILLING_TOPIC, event);
+
+        plan.amount
+    }
+
+    /// Ends the subscription and revokes the allowance (subscriber only)
+    pub fn cancel_subscription(env: Env, subscription_id: u64) {
+        let mut subscription = Self::get_subscription(env.clone(), subscription_id);
+        subscription.subscriber.require_auth();
+        if subscription.status != SubscriptionStatus::Active {
+            panic!("Subscription is not active");
+        }
+
+        subscription.status = SubscriptionStatus::Cancelled;
+        Self::save_subscription(&env, &subscription);
+
+        let plan = Self::get_plan(env.clone(), subscription.plan_id);
+        token::Client::new(&env, &plan.token).approve(
+            &subscription.subscriber,
+            &env.current_contract_address(),
+            &0,
+            &env.ledger().sequence(),
+        );
+
+        let event =
+            DeFiEvent::Subscription(SubscriptionEvent::Cancelled(SubscriptionCancelledData {
+                subscription_id,
+                subscriber: subscription.subscriber,
+                total_charged: subscription.total_charged,
+                cancelled_at: env.ledger().timestamp(),
+            }));
+        EventEmitter::emit_event(&env, BILLING_TOPIC, event);
+    }
+
+    pub fn get_plan(env: Env, plan_id: u64) -> Plan {
+        env.storage()
+            .persistent()
+            .get(&SubscriptionDataKey::Plan(plan_id))
+            .expect("Plan not found")
+    }
+
+    pub fn get_subscription(env: Env, subscription_id: u64) -> Subscription {
+        env.storage()
+            .persistent()
+            .get(&SubscriptionDataKey::Subscription(subscription_id))
+            .expect("Subscription not found")
+    }
+
+    pub fn get_subscriber_subscriptions(env: Env, subscriber: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&SubscriptionDataKey::SubscriberSubscriptions(subscriber))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn save_subscription(env: &Env, subscription: &Subscription) {
+        env.storage().persistent().set(
+            &SubscriptionDataKey::Subscription(subscription.id),
+            subscription,
+        );
+    }
+}
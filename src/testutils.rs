@@ -0,0 +1,74 @@
+//! Shared fixtures for the integration tests: ledger time travel, funded
+//! accounts and initialized contracts. Only compiled for tests or with the
+//! `testutils` feature, so none of it reaches the deployed wasm.
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, Address, Env};
+
+use crate::conversion::{ConversionContract, ConversionContractClient, Currency};
+use crate::pool_manager::PoolManagerContractClient;
+
+pub const HOUR: u64 = 60 * 60;
+pub const DAY: u64 = 24 * HOUR;
+
+/// A fresh environment with every `require_auth` mocked
+pub fn test_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env
+}
+
+/// Moves the ledger clock forward by `seconds`
+pub fn advance_ledger(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+/// Pins the ledger clock to `timestamp`
+pub fn set_ledger_time(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|li| li.timestamp = timestamp);
+}
+
+/// Registers a Stellar asset contract issued by a throwaway admin
+pub fn create_token(env: &Env) -> token::Client<'_> {
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::Client::new(env, &asset.address())
+}
+
+/// Mints `amount` of `token` to `to`
+pub fn fund(env: &Env, token: &Address, to: &Address, amount: i128) {
+    token::StellarAssetClient::new(env, token).mint(to, &amount);
+}
+
+/// A freshly generated account holding `amount` of `token`
+pub fn funded_user(env: &Env, token: &Address, amount: i128) -> Address {
+    let user = Address::generate(env);
+    fund(env, token, &user, amount);
+    user
+}
+
+/// Gives every currency's pool in `pools` a token of its own, set by the
+/// pool owner, and mints `amount` of each to every one of `holders`
+pub fn fund_pools(
+    env: &Env,
+    pools: &PoolManagerContractClient,
+    owner: &Address,
+    holders: &[&Address],
+    amount: i128,
+) {
+    for currency in Currency::ALL {
+        let token = create_token(env);
+        pools.set_pool_token(owner, &currency, &Some(token.address.clone()));
+        for holder in holders {
+            fund(env, &token.address, holder, amount);
+        }
+    }
+}
+
+/// A conversion contract with no fee and a 1..=1_000_000_000 amount range,
+/// along with its admin (who also collects fees)
+pub fn setup_conversion(env: &Env) -> (ConversionContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let client = ConversionContractClient::new(env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(&admin, &0, &admin, &1, &1_000_000_000);
+    (client, admin)
+}
@@ -0,0 +1,242 @@
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, TimelockCancelledData, TimelockEvent, TimelockExecutedData,
+    TimelockQueuedData, TIMELOCK_TOPIC,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Val, Vec};
+
+// Queued calls must be executed within this window after their eta
+const GRACE_PERIOD: u64 = 14 * 86400;
+const MAX_DELAY: u64 = 30 * 86400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelockConfig {
+    /// Account allowed to queue and cancel calls, typically a multisig
+    pub admin: Address,
+    /// Delay applied to targets without their own minimum
+    pub min_delay: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+/// A cross-contract call announced ahead of time and executable once `eta` is reached
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelockOperation {
+    pub id: u64,
+    pub target: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+    pub queued_at: u64,
+    pub eta: u64,
+    pub status: TimelockStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TimelockDataKey {
+    Config,
+    TargetDelay(Address),
+    OperationCount,
+    Operation(u64),
+}
+
+#[contract]
+pub struct TimelockContract;
+
+/// Contracts route admin actions through the timelock by making the timelock's
+/// address their admin; executed calls are then authorized as coming from it.
+#[contractimpl]
+impl TimelockContract {
+    pub fn initialize_timelock(env: Env, admin: Address, min_delay: u64) -> TimelockConfig {
+        if env.storage().instance().has(&TimelockDataKey::Config) {
+            panic!("Timelock already initialized");
+        }
+        admin.require_auth();
+        if min_delay > MAX_DELAY {
+            panic!("Delay too long");
+        }
+
+        let config = TimelockConfig { admin, min_delay };
+        env.storage()
+            .instance()
+            .set(&TimelockDataKey::Config, &config);
+        config
+    }
+
+    pub fn get_timelock_config(env: Env) -> TimelockConfig {
+        env.storage()
+            .instance()
+            .get(&TimelockDataKey::Config)
+            .expect("Timelock not initialized")
+    }
+
+    /// Sets the minimum delay for calls to `target`. Cannot go below the global minimum.
+    pub fn set_target_delay(env: Env, target: Address, delay: u64) {
+        let config = Self::get_timelock_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "set_target_delay",
+            (target.clone(), delay),
+        );
+        if delay < config.min_delay {
+            panic!("Delay below minimum");
+        }
+        if delay > MAX_DELAY {
+            panic!("Delay too long");
+        }
+
+        env.storage()
+            .instance()
+            .set(&TimelockDataKey::TargetDelay(target), &delay);
+    }
+
+    pub fn get_target_delay(env: Env, target: Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&TimelockDataKey::TargetDelay(target))
+            .unwrap_or(Self::get_timelock_config(env).min_delay)
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (admin only)
+    pub fn set_timelock_audit_log(env: Env, audit_log: Option<Address>) {
+        let config = Self::get_timelock_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "set_timelock_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_timelock_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Announces a call to `target.function(args)` that can run after `delay` seconds
+    pub fn queue(env: Env, target: Address, function: Symbol, args: Vec<Val>, delay: u64) -> u64 {
+        let config = Self::get_timelock_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(
+            &env,
+            &config.admin,
+            "queue",
+            (target.clone(), function.clone(), args.clone(), delay),
+        );
+        if delay < Self::get_target_delay(env.clone(), target.clone()) {
+            panic!("Delay below minimum for target");
+        }
+        if delay > MAX_DELAY {
+            panic!("Delay too long");
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&TimelockDataKey::OperationCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&TimelockDataKey::OperationCount, &id);
+
+        let now = env.ledger().timestamp();
+        let operation = TimelockOperation {
+            id,
+            target: target.clone(),
+            function: function.clone(),
+            args,
+            queued_at: now,
+            eta: now + delay,
+            status: TimelockStatus::Queued,
+        };
+        env.storage()
+            .persistent()
+            .set(&TimelockDataKey::Operation(id), &operation);
+
+        let event = DeFiEvent::Timelock(TimelockEvent::Queued(TimelockQueuedData {
+            operation_id: id,
+            target,
+            function,
+            eta: operation.eta,
+        }));
+        EventEmitter::emit_event(&env, TIMELOCK_TOPIC, event);
+
+        id
+    }
+
+    /// Runs a queued call once its eta has passed. Callable by anyone.
+    pub fn execute(env: Env, operation_id: u64) -> Val {
+        let mut operation = Self::get_operation(env.clone(), operation_id);
+        if operation.status != TimelockStatus::Queued {
+            panic!("Operation is not queued");
+        }
+        let now = env.ledger().timestamp();
+        if now < operation.eta {
+            panic!("Operation is still timelocked");
+        }
+        if now > operation.eta + GRACE_PERIOD {
+            panic!("Operation is stale");
+        }
+
+        operation.status = TimelockStatus::Executed;
+        env.storage()
+            .persistent()
+            .set(&TimelockDataKey::Operation(operation_id), &operation);
+
+        let result: Val = env.invoke_contract(
+            &operation.target,
+            &operation.function,
+            operation.args.clone(),
+        );
+
+        let event = DeFiEvent::Timelock(TimelockEvent::Executed(TimelockExecutedData {
+            operation_id,
+            target: operation.target,
+            function: operation.function,
+            executed_at: now,
+        }));
+        EventEmitter::emit_event(&env, TIMELOCK_TOPIC, event);
+
+        result
+    }
+
+    pub fn cancel(env: Env, operation_id: u64) {
+        let config = Self::get_timelock_config(env.clone());
+        config.admin.require_auth();
+        AdminAudit::record(&env, &config.admin, "cancel", operation_id);
+
+        let mut operation = Self::get_operation(env.clone(), operation_id);
+        if operation.status != TimelockStatus::Queued {
+            panic!("Operation is not queued");
+        }
+        operation.status = TimelockStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&TimelockDataKey::Operation(operation_id), &operation);
+
+        let event = DeFiEvent::Timelock(TimelockEvent::Cancelled(TimelockCancelledData {
+            operation_id,
+            cancelled_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, TIMELOCK_TOPIC, event);
+    }
+
+    pub fn get_operation(env: Env, operation_id: u64) -> TimelockOperation {
+        env.storage()
+            .persistent()
+            .get(&TimelockDataKey::Operation(operation_id))
+            .expect("Operation not found")
+    }
+}
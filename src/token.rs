@@ -1,4 +1,28 @@
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use crate::address_book::BeneficiaryCheck;
+use crate::alias::Alias;
+use crate::audit_log::AdminAudit;
+use crate::blocklist::BlocklistCheck;
+use crate::compliance::{ComplianceCheck, CompliancePolicy};
+use crate::email_to_wallet::EmailToWalletContractClient;
+use crate::errors::or_overflow;
+use crate::nonce::NonceTracker;
+use crate::storage;
+use crate::utils::{checked_add, checked_sub};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, String,
+    Symbol, Vec,
+};
+
+/// Off-chain documentation for the asset, such as reserve attestations and
+/// terms, that wallets and explorers can fetch and check against `hash`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub uri: String,
+    /// SHA-256 of the document at `uri`
+    pub hash: BytesN<32>,
+}
 
 #[contracttype]
 #[derive(Clone)]
@@ -7,6 +31,7 @@ pub struct TokenConfig {
     name: Symbol,
     symbol: Symbol,
     decimals: u32,
+    metadata: Option<TokenMetadata>,
 }
 
 #[contracttype]
@@ -16,6 +41,24 @@ pub struct Balance {
 }
 
 const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
+const ALLOWANCE_KEY: Symbol = symbol_short!("ALLOW");
+const PERMIT_KEY: Symbol = symbol_short!("PERMIT");
+const SNAPSHOT_ID_KEY: Symbol = symbol_short!("SNAP_ID");
+const CHECKPOINT_KEY: Symbol = symbol_short!("CHKPT");
+const ALIAS_REGISTRY_KEY: Symbol = symbol_short!("ALIASREG");
+const COMPLIANCE_POLICY_KEY: Symbol = symbol_short!("KYCPOL");
+const BLOCKLIST_KEY: Symbol = symbol_short!("BLOCKLIST");
+const ADDRESS_BOOK_KEY: Symbol = symbol_short!("ADDRBOOK");
+const LOCK_SOURCE_KEY: Symbol = symbol_short!("LOCKSRC");
+const LOCKS_KEY: Symbol = symbol_short!("LOCKS");
+
+/// Most pending unlock dates an account can carry at once
+pub const MAX_LOCKS_PER_ACCOUNT: u32 = 20;
+
+// XDR-encoded ScVal::Address(ScAddress::Account(ed25519 key)) is 44 bytes,
+// with the raw public key in the trailing 32 bytes.
+const ACCOUNT_ADDRESS_XDR_LEN: u32 = 44;
+const ACCOUNT_KEY_OFFSET: u32 = 12;
 
 #[contract]
 pub struct TokenContract;
@@ -34,6 +77,7 @@ impl TokenContract {
             name,
             symbol,
             decimals,
+            metadata: None,
         };
         env.storage().instance().set(&CONFIG_KEY, &config);
         config
@@ -52,24 +96,23 @@ impl TokenContract {
         if minter != config.admin {
             panic!("Only admin can mint");
         }
+        AdminAudit::record(&env, &minter, "mint", (to.clone(), amount));
 
         // Update balance
-        let mut to_balance: Balance = env
-            .storage()
-            .instance()
-            .get(&to)
-            .unwrap_or(Balance { amount: 0 });
-        to_balance.amount += amount;
-        env.storage().instance().set(&to, &to_balance);
+        let mut to_balance: Balance = storage::read(&env, &to).unwrap_or(Balance { amount: 0 });
+        to_balance.amount = or_overflow(&env, checked_add(to_balance.amount, amount));
+        Self::write_balance(&env, &to, &to_balance);
 
         // Emit token mint event
-        let event = crate::event::DeFiEvent::TokenMinted(crate::event::TokenMintedData {
-            token: env.current_contract_address(),
-            to: to.clone(),
-            amount,
-            minter: minter.clone(),
-            minted_at: env.ledger().timestamp(),
-        });
+        let event = crate::event::DeFiEvent::Token(crate::event::TokenEvent::Minted(
+            crate::event::TokenMintedData {
+                token: env.current_contract_address(),
+                to: to.clone(),
+                amount,
+                minter: minter.clone(),
+                minted_at: env.ledger().timestamp(),
+            },
+        ));
         crate::event::EventEmitter::emit_event(&env, crate::event::TOKEN_TOPIC, event);
     }
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
@@ -80,28 +123,22 @@ impl TokenContract {
 
         // Require from authorization
         from.require_auth();
+        Self::check_transfer_allowed(&env, &from, &to, amount);
 
         // Update balances
-        let mut from_balance: Balance = env
-            .storage()
-            .instance()
-            .get(&from)
-            .unwrap_or(Balance { amount: 0 });
-        let mut to_balance: Balance = env
-            .storage()
-            .instance()
-            .get(&to)
-            .unwrap_or(Balance { amount: 0 });
+        let mut from_balance: Balance = storage::read(&env, &from).unwrap_or(Balance { amount: 0 });
+        let mut to_balance: Balance = storage::read(&env, &to).unwrap_or(Balance { amount: 0 });
 
         if from_balance.amount < amount {
             panic!("Insufficient balance");
         }
+        Self::check_unlocked(&env, &from, amount);
 
-        from_balance.amount -= amount;
-        to_balance.amount += amount;
+        from_balance.amount = or_overflow(&env, checked_sub(from_balance.amount, amount));
+        to_balance.amount = or_overflow(&env, checked_add(to_balance.amount, amount));
 
-        env.storage().instance().set(&from, &from_balance);
-        env.storage().instance().set(&to, &to_balance);
+        Self::write_balance(&env, &from, &from_balance);
+        Self::write_balance(&env, &to, &to_balance);
 
         // Emit token transfer event
         crate::event::EventEmitter::emit_token_transfer(
@@ -114,15 +151,557 @@ impl TokenContract {
             to_balance.amount,
         );
     }
-    pub fn balance(env: Env, of: Address) -> i128 {
-        let balance: Balance = env
+    pub fn set_token_alias_registry(env: Env, admin: Address, registry: Address) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set alias registry");
+        }
+        AdminAudit::record(&env, &admin, "set_token_alias_registry", registry.clone());
+
+        env.storage().instance().set(&ALIAS_REGISTRY_KEY, &registry);
+    }
+    // KYC requirements for transfers at or above the policy threshold, None to clear
+    pub fn set_token_compliance_policy(env: Env, admin: Address, policy: Option<CompliancePolicy>) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set compliance policy");
+        }
+        AdminAudit::record(&env, &admin, "set_token_compliance_policy", policy.clone());
+
+        match policy {
+            Some(policy) => env
+                .storage()
+                .instance()
+                .set(&COMPLIANCE_POLICY_KEY, &policy),
+            None => env.storage().instance().remove(&COMPLIANCE_POLICY_KEY),
+        }
+    }
+    pub fn get_token_compliance_policy(env: Env) -> Option<CompliancePolicy> {
+        env.storage().instance().get(&COMPLIANCE_POLICY_KEY)
+    }
+    // shared blocklist checked on every transfer, None to clear
+    pub fn set_token_blocklist(env: Env, admin: Address, blocklist: Option<Address>) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set blocklist");
+        }
+        AdminAudit::record(&env, &admin, "set_token_blocklist", blocklist.clone());
+
+        match blocklist {
+            Some(blocklist) => env.storage().instance().set(&BLOCKLIST_KEY, &blocklist),
+            None => env.storage().instance().remove(&BLOCKLIST_KEY),
+        }
+    }
+    pub fn get_token_blocklist(env: Env) -> Option<Address> {
+        env.storage().instance().get(&BLOCKLIST_KEY)
+    }
+    // address book whose whitelist-only holders may only send to approved
+    // beneficiaries, None to clear
+    pub fn set_token_address_book(env: Env, admin: Address, address_book: Option<Address>) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set address book");
+        }
+        AdminAudit::record(&env, &admin, "set_token_address_book", address_book.clone());
+
+        match address_book {
+            Some(address_book) => env
+                .storage()
+                .instance()
+                .set(&ADDRESS_BOOK_KEY, &address_book),
+            None => env.storage().instance().remove(&ADDRESS_BOOK_KEY),
+        }
+    }
+    pub fn get_token_address_book(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ADDRESS_BOOK_KEY)
+    }
+    // shared log admin calls are journaled to, None to clear
+    pub fn set_token_audit_log(env: Env, admin: Address, audit_log: Option<Address>) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set audit log");
+        }
+        AdminAudit::record(&env, &admin, "set_token_audit_log", audit_log.clone());
+        AdminAudit::set_log(&env, audit_log);
+    }
+    pub fn get_token_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+    // allow or stop a contract (vesting, rewards) sending tokens that stay
+    // locked in the recipient's balance until a set time
+    pub fn set_token_lock_source(env: Env, admin: Address, source: Address, allowed: bool) {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set lock sources");
+        }
+        AdminAudit::record(
+            &env,
+            &admin,
+            "set_token_lock_source",
+            (source.clone(), allowed),
+        );
+
+        let key = (LOCK_SOURCE_KEY, source);
+        if allowed {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+    pub fn is_token_lock_source(env: Env, source: Address) -> bool {
+        env.storage().instance().has(&(LOCK_SOURCE_KEY, source))
+    }
+    // transfer from a lock source; the recipient cannot move the tokens on
+    // until `unlock_at`
+    pub fn transfer_locked(env: Env, from: Address, to: Address, amount: i128, unlock_at: u64) {
+        if !Self::is_token_lock_source(env.clone(), from.clone()) {
+            panic!("Not a lock source");
+        }
+        if unlock_at <= env.ledger().timestamp() {
+            panic!("Unlock time must be in the future");
+        }
+        Self::transfer(env.clone(), from.clone(), to.clone(), amount);
+
+        // Entries already unlocked are dropped; ones sharing a date are merged
+        let key = (LOCKS_KEY, to.clone());
+        let now = env.ledger().timestamp();
+        let mut locks = Vec::new(&env);
+        let mut merged = false;
+        for (at, locked) in Self::get_token_locks(env.clone(), to.clone()).iter() {
+            if at <= now {
+                continue;
+            }
+            if at == unlock_at {
+                locks.push_back((at, or_overflow(&env, checked_add(locked, amount))));
+                merged = true;
+            } else {
+                locks.push_back((at, locked));
+            }
+        }
+        if !merged {
+            if locks.len() >= MAX_LOCKS_PER_ACCOUNT {
+                panic!("Too many pending locks");
+            }
+            locks.push_back((unlock_at, amount));
+        }
+        storage::write(&env, &key, &locks);
+
+        let event = crate::event::DeFiEvent::Token(crate::event::TokenEvent::Locked(
+            crate::event::TokenLockedData {
+                token: env.current_contract_address(),
+                to,
+                amount,
+                source: from,
+                unlock_at,
+            },
+        ));
+        crate::event::EventEmitter::emit_event(&env, crate::event::TOKEN_TOPIC, event);
+    }
+    // (unlock time, amount) entries recorded for an account, including ones
+    // that have since unlocked
+    pub fn get_token_locks(env: Env, of: Address) -> Vec<(u64, i128)> {
+        storage::read(&env, &(LOCKS_KEY, of)).unwrap_or(Vec::new(&env))
+    }
+    pub fn get_locked_balance(env: Env, of: Address) -> i128 {
+        let now = env.ledger().timestamp();
+        Self::get_token_locks(env, of)
+            .iter()
+            .filter(|(unlock_at, _)| *unlock_at > now)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+    // the part of the balance the holder can transfer now
+    pub fn get_unlocked_balance(env: Env, of: Address) -> i128 {
+        let locked = Self::get_locked_balance(env.clone(), of.clone());
+        (Self::balance(env, of) - locked).max(0)
+    }
+    // point wallets at the asset's documentation, None to clear
+    pub fn set_token_metadata(env: Env, admin: Address, metadata: Option<TokenMetadata>) {
+        admin.require_auth();
+
+        let mut config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can set metadata");
+        }
+        AdminAudit::record(&env, &admin, "set_token_metadata", metadata.clone());
+        if let Some(metadata) = &metadata {
+            if metadata.uri.is_empty() {
+                panic!("Metadata URI is empty");
+            }
+        }
+
+        config.metadata = metadata.clone();
+        env.storage().instance().set(&CONFIG_KEY, &config);
+
+        let event = crate::event::DeFiEvent::Token(crate::event::TokenEvent::MetadataUpdated(
+            crate::event::TokenMetadataUpdatedData {
+                token: env.current_contract_address(),
+                metadata,
+                updated_at: env.ledger().timestamp(),
+            },
+        ));
+        crate::event::EventEmitter::emit_event(&env, crate::event::TOKEN_TOPIC, event);
+    }
+    pub fn get_token_metadata(env: Env) -> Option<TokenMetadata> {
+        Self::get_token_config(env).metadata
+    }
+    // resolve the recipient from an alias through the email registry
+    pub fn transfer_to_alias(env: Env, from: Address, alias: Alias, amount: i128) -> Address {
+        let registry: Address = env
             .storage()
             .instance()
-            .get(&of)
-            .unwrap_or(Balance { amount: 0 });
+            .get(&ALIAS_REGISTRY_KEY)
+            .expect("Alias registry not set");
+        let to = EmailToWalletContractClient::new(&env, &registry)
+            .resolve_alias(&alias.kind, &alias.value);
+        Self::transfer(env, from, to.clone(), amount);
+        to
+    }
+    pub fn mint_batch(env: Env, minter: Address, recipients: Vec<(Address, i128)>) {
+        if recipients.is_empty() {
+            panic!("Batch must not be empty");
+        }
+        for (_, amount) in recipients.iter() {
+            if amount <= 0 {
+                panic!("Amount must be positive");
+            }
+        }
+
+        // Require minter (admin) authorization once for the whole batch
+        minter.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if minter != config.admin {
+            panic!("Only admin can mint");
+        }
+        AdminAudit::record(&env, &minter, "mint_batch", recipients.clone());
+
+        for (to, amount) in recipients.iter() {
+            let mut to_balance: Balance = storage::read(&env, &to).unwrap_or(Balance { amount: 0 });
+            to_balance.amount = or_overflow(&env, checked_add(to_balance.amount, amount));
+            Self::write_balance(&env, &to, &to_balance);
+
+            let event = crate::event::DeFiEvent::Token(crate::event::TokenEvent::Minted(
+                crate::event::TokenMintedData {
+                    token: env.current_contract_address(),
+                    to: to.clone(),
+                    amount,
+                    minter: minter.clone(),
+                    minted_at: env.ledger().timestamp(),
+                },
+            ));
+            crate::event::EventEmitter::emit_event(&env, crate::event::TOKEN_TOPIC, event);
+        }
+    }
+    pub fn transfer_batch(env: Env, from: Address, transfers: Vec<(Address, i128)>) {
+        if transfers.is_empty() {
+            panic!("Batch must not be empty");
+        }
+        for (_, amount) in transfers.iter() {
+            if amount <= 0 {
+                panic!("Amount must be positive");
+            }
+        }
+        let mut total: i128 = 0;
+        for (_, amount) in transfers.iter() {
+            total = or_overflow(&env, checked_add(total, amount));
+        }
+
+        // Require from authorization once for the whole batch
+        from.require_auth();
+
+        let from_balance: Balance = storage::read(&env, &from).unwrap_or(Balance { amount: 0 });
+        if from_balance.amount < total {
+            panic!("Insufficient balance");
+        }
+        Self::check_unlocked(&env, &from, total);
+
+        for (to, amount) in transfers.iter() {
+            Self::check_transfer_allowed(&env, &from, &to, amount);
+            let mut from_balance: Balance = storage::read(&env, &from).unwrap();
+            let mut to_balance: Balance = storage::read(&env, &to).unwrap_or(Balance { amount: 0 });
+
+            from_balance.amount = or_overflow(&env, checked_sub(from_balance.amount, amount));
+            Self::write_balance(&env, &from, &from_balance);
+            to_balance.amount = or_overflow(&env, checked_add(to_balance.amount, amount));
+            Self::write_balance(&env, &to, &to_balance);
+
+            crate::event::EventEmitter::emit_token_transfer(
+                &env,
+                env.current_contract_address(),
+                from.clone(),
+                to.clone(),
+                amount,
+                from_balance.amount,
+                to_balance.amount,
+            );
+        }
+    }
+    pub fn approve(env: Env, owner: Address, spender: Address, amount: i128) {
+        if amount < 0 {
+            panic!("Amount cannot be negative");
+        }
+
+        owner.require_auth();
+
+        Self::set_allowance(&env, &owner, &spender, amount);
+    }
+    pub fn allowance(env: Env, owner: Address, spender: Address) -> i128 {
+        storage::read(&env, &(ALLOWANCE_KEY, owner, spender)).unwrap_or(0)
+    }
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        spender.require_auth();
+        Self::check_transfer_allowed(&env, &from, &to, amount);
+
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            panic!("Insufficient allowance");
+        }
+
+        let mut from_balance: Balance = storage::read(&env, &from).unwrap_or(Balance { amount: 0 });
+        if from_balance.amount < amount {
+            panic!("Insufficient balance");
+        }
+        Self::check_unlocked(&env, &from, amount);
+
+        Self::set_allowance(
+            &env,
+            &from,
+            &spender,
+            or_overflow(&env, checked_sub(allowance, amount)),
+        );
+
+        from_balance.amount = or_overflow(&env, checked_sub(from_balance.amount, amount));
+        Self::write_balance(&env, &from, &from_balance);
+        let mut to_balance: Balance = storage::read(&env, &to).unwrap_or(Balance { amount: 0 });
+        to_balance.amount = or_overflow(&env, checked_add(to_balance.amount, amount));
+        Self::write_balance(&env, &to, &to_balance);
+
+        crate::event::EventEmitter::emit_token_transfer(
+            &env,
+            env.current_contract_address(),
+            from.clone(),
+            to.clone(),
+            amount,
+            from_balance.amount,
+            to_balance.amount,
+        );
+    }
+    // Sets an allowance from an off-chain ed25519 signature by the owner's account key,
+    // so a relayer can submit the approval on the owner's behalf. Nonces are tracked
+    // per owner and must strictly increase.
+    pub fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        deadline: u64,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) {
+        if amount < 0 {
+            panic!("Amount cannot be negative");
+        }
+        if env.ledger().timestamp() > deadline {
+            panic!("Permit expired");
+        }
+
+        let public_key = Self::account_public_key(&env, &owner);
+        let payload = Self::permit_payload(
+            env.clone(),
+            owner.clone(),
+            spender.clone(),
+            amount,
+            deadline,
+            nonce,
+        );
+        env.crypto()
+            .ed25519_verify(&public_key, &payload.into(), &signature);
+
+        if NonceTracker::check_and_update_nonce(env.clone(), owner.clone(), nonce).is_err() {
+            panic!("Invalid permit nonce");
+        }
+
+        Self::set_allowance(&env, &owner, &spender, amount);
+    }
+    pub fn get_permit_nonce(env: Env, owner: Address) -> u64 {
+        NonceTracker::get_nonce(env, owner)
+    }
+    // The 32-byte digest the owner signs to authorize a permit
+    pub fn permit_payload(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        deadline: u64,
+        nonce: u64,
+    ) -> BytesN<32> {
+        let message = (
+            PERMIT_KEY,
+            env.current_contract_address(),
+            owner,
+            spender,
+            amount,
+            deadline,
+            nonce,
+        )
+            .to_xdr(&env);
+        env.crypto().sha256(&message).into()
+    }
+    // Records a new snapshot id; balances at this point stay queryable via `balance_at`
+    pub fn snapshot(env: Env, admin: Address) -> u32 {
+        admin.require_auth();
+
+        let config: TokenConfig = env.storage().instance().get(&CONFIG_KEY).unwrap();
+        if admin != config.admin {
+            panic!("Only admin can snapshot");
+        }
+        AdminAudit::record(&env, &admin, "snapshot", ());
+
+        let snapshot_id = Self::current_snapshot_id(env.clone()) + 1;
+        env.storage().instance().set(&SNAPSHOT_ID_KEY, &snapshot_id);
+
+        let event = crate::event::DeFiEvent::Token(crate::event::TokenEvent::SnapshotTaken(
+            crate::event::TokenSnapshotData {
+                token: env.current_contract_address(),
+                snapshot_id,
+                taken_at: env.ledger().timestamp(),
+            },
+        ));
+        crate::event::EventEmitter::emit_event(&env, crate::event::TOKEN_TOPIC, event);
+
+        snapshot_id
+    }
+    pub fn current_snapshot_id(env: Env) -> u32 {
+        env.storage().instance().get(&SNAPSHOT_ID_KEY).unwrap_or(0)
+    }
+    pub fn balance_at(env: Env, of: Address, snapshot_id: u32) -> i128 {
+        if snapshot_id == 0 || snapshot_id > Self::current_snapshot_id(env.clone()) {
+            panic!("Invalid snapshot id");
+        }
+
+        // The first checkpoint taken at or after the requested snapshot holds the
+        // balance as of that snapshot; with none, the balance has not changed since.
+        let checkpoints: Vec<(u32, i128)> =
+            storage::read(&env, &(CHECKPOINT_KEY, of.clone())).unwrap_or(Vec::new(&env));
+        match checkpoints.binary_search((snapshot_id, i128::MIN)) {
+            Ok(index) | Err(index) if index < checkpoints.len() => {
+                checkpoints.get(index).unwrap().1
+            }
+            _ => Self::balance(env, of),
+        }
+    }
+    pub fn balance(env: Env, of: Address) -> i128 {
+        let balance: Balance = storage::read(&env, &of).unwrap_or(Balance { amount: 0 });
         balance.amount
     }
     pub fn get_token_config(env: Env) -> TokenConfig {
         env.storage().instance().get(&CONFIG_KEY).unwrap()
     }
+
+    fn write_balance(env: &Env, account: &Address, balance: &Balance) {
+        let snapshot_id = Self::current_snapshot_id(env.clone());
+        if snapshot_id > 0 {
+            let key = (CHECKPOINT_KEY, account.clone());
+            let mut checkpoints: Vec<(u32, i128)> =
+                storage::read(env, &key).unwrap_or(Vec::new(env));
+            let already_checkpointed = match checkpoints.last() {
+                Some((last_id, _)) => last_id == snapshot_id,
+                None => false,
+            };
+            if !already_checkpointed {
+                let previous = Self::balance(env.clone(), account.clone());
+                checkpoints.push_back((snapshot_id, previous));
+                storage::write(env, &key, &checkpoints);
+            }
+        }
+
+        storage::write(env, account, balance);
+    }
+
+    fn check_transfer_allowed(env: &Env, from: &Address, to: &Address, amount: i128) {
+        if let Some(blocklist) = Self::get_token_blocklist(env.clone()) {
+            if BlocklistCheck::any_blocked(env, &blocklist, &[from, to]) {
+                panic!("Address is blocked");
+            }
+        }
+        if let Some(address_book) = Self::get_token_address_book(env.clone()) {
+            if !BeneficiaryCheck::is_approved(env, &address_book, from, to) {
+                panic!("Recipient not in sender's address book");
+            }
+        }
+        if let Some(policy) = Self::get_token_compliance_policy(env.clone()) {
+            if !ComplianceCheck::is_allowed(env, &policy, from, amount)
+                || !ComplianceCheck::is_allowed(env, &policy, to, amount)
+            {
+                panic!("KYC tier too low");
+            }
+        }
+    }
+
+    fn check_unlocked(env: &Env, from: &Address, amount: i128) {
+        if amount > Self::get_unlocked_balance(env.clone(), from.clone()) {
+            panic!("Insufficient unlocked balance");
+        }
+    }
+
+    fn set_allowance(env: &Env, owner: &Address, spender: &Address, amount: i128) {
+        storage::write(
+            env,
+            &(ALLOWANCE_KEY, owner.clone(), spender.clone()),
+            &amount,
+        );
+
+        crate::event::EventEmitter::emit_token_approval(
+            env,
+            env.current_contract_address(),
+            owner.clone(),
+            spender.clone(),
+            amount,
+        );
+    }
+
+    fn account_public_key(env: &Env, owner: &Address) -> BytesN<32> {
+        let xdr: Bytes = owner.clone().to_xdr(env);
+        if xdr.len() != ACCOUNT_ADDRESS_XDR_LEN {
+            panic!("Permit owner must be an account address");
+        }
+        xdr.slice(ACCOUNT_KEY_OFFSET..ACCOUNT_ADDRESS_XDR_LEN)
+            .try_into()
+            .unwrap()
+    }
+}
+
+// Payouts from contracts registered as lock sources on the platform token
+pub struct LockedPayout;
+
+impl LockedPayout {
+    /// Sends `amount` of `token` from the calling contract to `to`, locked on
+    /// the token until `unlock_at` when that is still ahead. Locking needs
+    /// `token` to be a `TokenContract` with the caller as a lock source.
+    pub fn pay(env: &Env, token: &Address, to: &Address, amount: i128, unlock_at: u64) {
+        let contract = env.current_contract_address();
+        if unlock_at > env.ledger().timestamp() {
+            TokenContractClient::new(env, token)
+                .transfer_locked(&contract, to, &amount, &unlock_at);
+        } else {
+            soroban_sdk::token::Client::new(env, token).transfer(&contract, to, &amount);
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use crate::access::{AccessControl, Role};
+use crate::event::{
+    ContractUpgradedData, DeFiEvent, EventEmitter, StorageMigratedData, SYSTEM_TOPIC,
+};
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum UpgradeDataKey {
+    StorageVersion,
+}
+
+// Code upgrades and storage migrations shared by the deployable contracts. Each
+// contract keeps its own storage version in instance storage; instances deployed
+// before versioning was introduced read as version 0.
+pub struct Upgrade;
+
+impl Upgrade {
+    /// Records the storage layout a freshly initialized contract starts at
+    pub fn init_version(env: &Env, version: u32) {
+        env.storage()
+            .instance()
+            .set(&UpgradeDataKey::StorageVersion, &version);
+    }
+
+    pub fn storage_version(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&UpgradeDataKey::StorageVersion)
+            .unwrap_or(0)
+    }
+
+    /// Replaces the contract's code (owner only). Hand `Owner` to a timelock or
+    /// multisig contract to put upgrades behind a delay or a quorum.
+    pub fn upgrade(env: &Env, caller: &Address, new_wasm_hash: BytesN<32>) {
+        AccessControl::require_audited_role(
+            env,
+            Role::Owner,
+            caller,
+            "upgrade",
+            new_wasm_hash.clone(),
+        );
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        let event = DeFiEvent::ContractUpgraded(ContractUpgradedData {
+            new_wasm_hash,
+            upgraded_by: caller.clone(),
+            upgraded_at: env.ledger().timestamp(),
+        });
+        EventEmitter::emit_event(env, SYSTEM_TOPIC, event);
+    }
+
+    /// Runs `step` for every version after the stored one up to `target`, then
+    /// records `target`. Steps only move storage forward, so anyone may trigger it.
+    pub fn migrate(env: &Env, target: u32, step: impl Fn(&Env, u32)) -> u32 {
+        let from_version = Self::storage_version(env);
+        if from_version > target {
+            panic!("Storage is newer than contract code");
+        }
+        if from_version == target {
+            return target;
+        }
+
+        for version in (from_version + 1)..=target {
+            step(env, version);
+        }
+        Self::init_version(env, target);
+
+        let event = DeFiEvent::StorageMigrated(StorageMigratedData {
+            from_version,
+            to_version: target,
+            migrated_at: env.ledger().timestamp(),
+        });
+        EventEmitter::emit_event(env, SYSTEM_TOPIC, event);
+
+        target
+    }
+}
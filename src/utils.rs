@@ -1,10 +1,8 @@
+use crate::alias::{derive_alias_address, hash_alias, AliasKind};
+use crate::bps::Bps;
 use crate::conversion::Currency;
 use crate::errors::AppError;
-use soroban_sdk::vec;
-use soroban_sdk::xdr::FromXdr;
-use soroban_sdk::{
-    contracttype, log, token, xdr::ScAddressType, Address, Bytes, BytesN, Env, String,
-};
+use soroban_sdk::{log, token, Address, BytesN, Env, String};
 
 /// Validates that an amount is positive
 pub fn validate_positive_amount(amount: i128) -> Result<(), AppError> {
@@ -14,6 +12,24 @@ pub fn validate_positive_amount(amount: i128) -> Result<(), AppError> {
     Ok(())
 }
 
+/// `a + b`, or `Overflow` when the sum leaves the i128 range
+pub fn checked_add(a: i128, b: i128) -> Result<i128, AppError> {
+    a.checked_add(b).ok_or(AppError::Overflow)
+}
+
+/// `a - b`, or `Overflow` when the difference leaves the i128 range
+pub fn checked_sub(a: i128, b: i128) -> Result<i128, AppError> {
+    a.checked_sub(b).ok_or(AppError::Overflow)
+}
+
+/// `a * b / denominator` rounded toward zero, or `Overflow` when the product
+/// leaves the i128 range or the denominator is zero
+pub fn checked_mul_div(a: i128, b: i128, denominator: i128) -> Result<i128, AppError> {
+    a.checked_mul(b)
+        .and_then(|product| product.checked_div(denominator))
+        .ok_or(AppError::Overflow)
+}
+
 /// Validates that a timestamp is in the future
 pub fn validate_future_timestamp(env: &Env, timestamp: u64) -> Result<(), AppError> {
     if timestamp <= env.ledger().timestamp() {
@@ -23,7 +39,7 @@ pub fn validate_future_timestamp(env: &Env, timestamp: u64) -> Result<(), AppErr
 }
 
 /// Validates an address
-pub fn validate_address(env: &Env, address: &Address) -> Result<(), AppError> {
+pub fn validate_address(_env: &Env, address: &Address) -> Result<(), AppError> {
     if address.to_string().is_empty() {
         return Err(AppError::InvalidAddress);
     }
@@ -56,8 +72,8 @@ pub fn transfer_tokens(
         from.clone(),
         to.clone(),
         *amount,
-        from_balance_before - amount,
-        to_balance_before + amount,
+        checked_sub(from_balance_before, *amount)?,
+        checked_add(to_balance_before, *amount)?,
     );
 
     log!(env, "transferred {} tokens from {} to {}", amount, from, to);
@@ -78,8 +94,7 @@ pub fn compute_exchange_rate(offer_amount: i128, request_amount: i128) -> Result
     }
 
     // Return rate scaled by 10^8 for precision
-    let rate = (offer_amount * 100_000_000) / request_amount;
-    Ok(rate)
+    checked_mul_div(offer_amount, 100_000_000, request_amount)
 }
 
 /// Validates currency is supported
@@ -120,13 +135,13 @@ pub fn calculate_conversion_amount(
     input_amount: i128,
     exchange_rate: i128,
     rate_precision: i128,
-) -> i128 {
-    (input_amount * exchange_rate) / rate_precision
+) -> Result<i128, AppError> {
+    checked_mul_div(input_amount, exchange_rate, rate_precision)
 }
 
 /// Calculates platform fee
-pub fn calculate_platform_fee(amount: i128, fee_basis_points: u32) -> i128 {
-    (amount * i128::from(fee_basis_points)) / 10000
+pub fn calculate_platform_fee(amount: i128, fee_basis_points: u32) -> Result<i128, AppError> {
+    Bps::new(fee_basis_points)?.of(amount)
 }
 
 /// Formats currency display name
@@ -155,7 +170,7 @@ pub fn get_currency_symbol(currency: &Currency) -> &'static str {
 
 /// Validates rate lock duration
 pub fn validate_rate_lock_duration(
-    env: &Env,
+    _env: &Env,
     duration: u64,
     max_duration: u64,
 ) -> Result<(), AppError> {
@@ -183,9 +198,9 @@ pub fn update_balance_atomically(
         if current_balance < amount_change {
             return Err(AppError::InsufficientBalance);
         }
-        current_balance - amount_change
+        checked_sub(current_balance, amount_change)?
     } else {
-        current_balance + amount_change
+        checked_add(current_balance, amount_change)?
     };
     log!(
         env,
@@ -197,40 +212,22 @@ pub fn update_balance_atomically(
     );
     Ok(new_balance)
 }
-pub fn validate_token_contract(env: &Env, _token_address: &Address) -> bool {
+pub fn validate_token_contract(_env: &Env, _token_address: &Address) -> bool {
     true
 }
 
-pub fn validate_token_balance(env: &Env, _token_address: &Address, _amount: i128) -> bool {
+pub fn validate_token_balance(_env: &Env, _token_address: &Address, _amount: i128) -> bool {
     true
 }
 
-// derive wallet address from email
+// hash an email address for use as a registry key
 
-pub fn derive_wallet_address_from_email(env: &Env, email: &String) -> Result<Address, AppError> {
-    if email.len() == 0 {
-        return Err(AppError::InvalidAddress);
-    }
-
-    let len = email.len() as usize;
-    if len > 256 {
-        return Err(AppError::InvalidAddress);
-    }
-
-    let mut buf = [0u8; 256];
-    email.copy_into_slice(&mut buf[..len]);
-    let email_bytes = Bytes::from_slice(env, &buf[..len]);
-
-    let hash: BytesN<32> = env.crypto().sha256(&email_bytes).into();
-
-    let mut xdr: [u8; 40] = [0; 40];
-    xdr[3] = 18;
-    xdr[7] = 1;
-    let slice: &mut [u8; 32] = (&mut xdr[8..40]).try_into().unwrap();
-    hash.copy_into_slice(slice);
+pub fn hash_email(env: &Env, email: &String) -> Result<BytesN<32>, AppError> {
+    hash_alias(env, AliasKind::Email, email)
+}
 
-    let addr_bytes = Bytes::from_slice(env, &xdr);
-    let address = Address::from_xdr(env, &addr_bytes).map_err(|_| AppError::InvalidAddress)?;
+// derive wallet address from email
 
-    Ok(address)
+pub fn derive_wallet_address_from_email(env: &Env, email: &String) -> Result<Address, AppError> {
+    derive_alias_address(env, AliasKind::Email, email)
 }
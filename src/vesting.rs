@@ -0,0 +1,433 @@
+use crate::access::{AccessControl, Role};
+use crate::audit_log::AdminAudit;
+use crate::event::{
+    DeFiEvent, EventEmitter, VestingClaimedData, VestingCreatedData, VestingEvent,
+    VestingRevokedData, VESTING_TOPIC,
+};
+use crate::receipt::{PositionKind, ReceiptContractClient};
+use crate::rescue::Rescue;
+use crate::token::LockedPayout;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Timing of a vesting schedule
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingTerms {
+    pub start_time: u64,
+    /// Nothing vests until `start_time + cliff_duration`
+    pub cliff_duration: u64,
+    /// Everything has vested at `start_time + duration`
+    pub duration: u64,
+    /// Whether the owner may revoke the unvested portion
+    pub revocable: bool,
+    /// Claimed tokens stay locked on the token until this time; 0 for none
+    pub unlock_time: u64,
+}
+
+/// Tokens released to a beneficiary after a cliff and then linearly until the end
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingSchedule {
+    pub id: u64,
+    pub beneficiary: Address,
+    /// Account that funded the schedule and receives revoked tokens
+    pub funder: Address,
+    pub token: Address,
+    /// Amount the schedule vests in total; reduced to the vested amount on revocation
+    pub total_amount: i128,
+    pub claimed_amount: i128,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum VestingDataKey {
+    ScheduleCount,
+    Schedule(u64),
+    BeneficiarySchedules(Address),
+    /// Receipt contract that tokenizes new schedules
+    Receipts,
+    /// Receipt contract and receipt id whose holder may claim a schedule
+    Receipt(u64),
+    /// Time until which a schedule's claimed tokens stay locked
+    UnlockTime(u64),
+}
+
+#[contract]
+pub struct VestingContract;
+
+#[contractimpl]
+impl VestingContract {
+    pub fn initialize_vesting(env: Env, admin: Address) {
+        if env.storage().instance().has(&VestingDataKey::ScheduleCount) {
+            panic!("Vesting already initialized");
+        }
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&VestingDataKey::ScheduleCount, &0u64);
+        AccessControl::init_owner(&env, &admin);
+    }
+
+    /// Creates a schedule funded by `funder`, who must hold the operator role
+    pub fn create_vesting_schedule(
+        env: Env,
+        funder: Address,
+        beneficiary: Address,
+        token: Address,
+        total_amount: i128,
+        terms: VestingTerms,
+    ) -> u64 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Operator,
+            &funder,
+            "create_vesting_schedule",
+            (
+                beneficiary.clone(),
+                token.clone(),
+                total_amount,
+                terms.clone(),
+            ),
+        );
+        if total_amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if terms.duration == 0 || terms.cliff_duration > terms.duration {
+            panic!("Invalid vesting terms");
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &total_amount,
+        );
+
+        let id = Self::get_vesting_schedule_count(env.clone()) + 1;
+        let schedule = VestingSchedule {
+            id,
+            beneficiary: beneficiary.clone(),
+            funder,
+            token,
+            total_amount,
+            claimed_amount: 0,
+            start_time: terms.start_time,
+            cliff_time: terms.start_time + terms.cliff_duration,
+            end_time: terms.start_time + terms.duration,
+            revocable: terms.revocable,
+            revoked: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&VestingDataKey::Schedule(id), &schedule);
+        env.storage()
+            .instance()
+            .set(&VestingDataKey::ScheduleCount, &id);
+        if terms.unlock_time > 0 {
+            env.storage()
+                .persistent()
+                .set(&VestingDataKey::UnlockTime(id), &terms.unlock_time);
+        }
+
+        let mut ids = Self::get_beneficiary_schedules(env.clone(), beneficiary.clone());
+        ids.push_back(id);
+        env.storage().persistent().set(
+            &VestingDataKey::BeneficiarySchedules(beneficiary.clone()),
+            &ids,
+        );
+        if let Some(receipts) = Self::get_vesting_receipts(env.clone()) {
+            let receipt_id = ReceiptContractClient::new(&env, &receipts).mint_receipt(
+                &env.current_contract_address(),
+                &PositionKind::Vesting,
+                &beneficiary,
+            );
+            env.storage()
+                .persistent()
+                .set(&VestingDataKey::Receipt(id), &(receipts, receipt_id));
+        }
+
+        let event = DeFiEvent::Vesting(VestingEvent::Created(VestingCreatedData {
+            schedule_id: id,
+            beneficiary,
+            token: schedule.token,
+            total_amount,
+            cliff_time: schedule.cliff_time,
+            end_time: schedule.end_time,
+        }));
+        EventEmitter::emit_event(&env, VESTING_TOPIC, event);
+
+        id
+    }
+
+    /// Amount vested so far, including what has already been claimed
+    pub fn get_vested_amount(env: Env, schedule_id: u64) -> i128 {
+        let schedule = Self::get_vesting_schedule(env.clone(), schedule_id);
+        Self::vested(&schedule, env.ledger().timestamp())
+    }
+
+    /// Amount the beneficiary can claim now
+    pub fn get_claimable_amount(env: Env, schedule_id: u64) -> i128 {
+        let schedule = Self::get_vesting_schedule(env.clone(), schedule_id);
+        Self::vested(&schedule, env.ledger().timestamp()) - schedule.claimed_amount
+    }
+
+    /// Transfers everything vested and not yet claimed to the beneficiary, or
+    /// to the holder of the schedule's receipt when it has one
+    pub fn claim(env: Env, schedule_id: u64) -> i128 {
+        let mut schedule = Self::get_vesting_schedule(env.clone(), schedule_id);
+        let receipt = Self::get_vesting_receipt(env.clone(), schedule_id);
+        let claimant = match &receipt {
+            Some((receipts, receipt_id)) => {
+                ReceiptContractClient::new(&env, receipts).receipt_owner(receipt_id)
+            }
+            None => schedule.beneficiary.clone(),
+        };
+        claimant.require_auth();
+
+        let amount = Self::vested(&schedule, env.ledger().timestamp()) - schedule.claimed_amount;
+        if amount <= 0 {
+            panic!("Nothing to claim");
+        }
+
+        schedule.claimed_amount += amount;
+        env.storage()
+            .persistent()
+            .set(&VestingDataKey::Schedule(schedule_id), &schedule);
+        LockedPayout::pay(
+            &env,
+            &schedule.token,
+            &claimant,
+            amount,
+            Self::get_vesting_unlock_time(env.clone(), schedule_id),
+        );
+        if schedule.claimed_amount == schedule.total_amount {
+            Self::retire_receipt(&env, schedule_id);
+        }
+
+        let event = DeFiEvent::Vesting(VestingEvent::Claimed(VestingClaimedData {
+            schedule_id,
+            beneficiary: claimant,
+            amount,
+            total_claimed: schedule.claimed_amount,
+            claimed_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, VESTING_TOPIC, event);
+
+        amount
+    }
+
+    /// Stops further vesting and returns the unvested tokens to the funder (owner
+    /// only). Tokens already vested stay claimable by the beneficiary.
+    pub fn revoke_vesting(env: Env, caller: Address, schedule_id: u64) -> i128 {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "revoke_vesting",
+            schedule_id,
+        );
+
+        let mut schedule = Self::get_vesting_schedule(env.clone(), schedule_id);
+        if !schedule.revocable {
+            panic!("Schedule is not revocable");
+        }
+        if schedule.revoked {
+            panic!("Schedule already revoked");
+        }
+
+        let vested = Self::vested(&schedule, env.ledger().timestamp());
+        let refunded = schedule.total_amount - vested;
+        schedule.total_amount = vested;
+        schedule.revoked = true;
+        env.storage()
+            .persistent()
+            .set(&VestingDataKey::Schedule(schedule_id), &schedule);
+        if refunded > 0 {
+            token::Client::new(&env, &schedule.token).transfer(
+                &env.current_contract_address(),
+                &schedule.funder,
+                &refunded,
+            );
+        }
+        if schedule.claimed_amount == schedule.total_amount {
+            Self::retire_receipt(&env, schedule_id);
+        }
+
+        let event = DeFiEvent::Vesting(VestingEvent::Revoked(VestingRevokedData {
+            schedule_id,
+            vested,
+            refunded,
+            revoked_at: env.ledger().timestamp(),
+        }));
+        EventEmitter::emit_event(&env, VESTING_TOPIC, event);
+
+        refunded
+    }
+
+    /// Set or clear the receipt contract new schedules are tokenized with
+    /// (owner only). This contract must hold the receipt contract's minter role.
+    pub fn set_vesting_receipts(env: Env, caller: Address, receipts: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_vesting_receipts",
+            receipts.clone(),
+        );
+        match receipts {
+            Some(receipts) => env
+                .storage()
+                .instance()
+                .set(&VestingDataKey::Receipts, &receipts),
+            None => env.storage().instance().remove(&VestingDataKey::Receipts),
+        }
+    }
+
+    pub fn get_vesting_receipts(env: Env) -> Option<Address> {
+        env.storage().instance().get(&VestingDataKey::Receipts)
+    }
+
+    /// Receipt contract and id tokenizing a schedule, if any
+    pub fn get_vesting_receipt(env: Env, schedule_id: u64) -> Option<(Address, u64)> {
+        env.storage()
+            .persistent()
+            .get(&VestingDataKey::Receipt(schedule_id))
+    }
+
+    pub fn get_vesting_schedule(env: Env, schedule_id: u64) -> VestingSchedule {
+        env.storage()
+            .persistent()
+            .get(&VestingDataKey::Schedule(schedule_id))
+            .expect("Vesting schedule not found")
+    }
+
+    /// Time until which the schedule's claimed tokens stay locked, 0 if never
+    pub fn get_vesting_unlock_time(env: Env, schedule_id: u64) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&VestingDataKey::UnlockTime(schedule_id))
+            .unwrap_or(0)
+    }
+
+    pub fn get_vesting_schedule_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&VestingDataKey::ScheduleCount)
+            .unwrap_or(0)
+    }
+
+    /// Schedules in creation order, `limit` capped at 50
+    pub fn get_vesting_schedules(env: Env, offset: u64, limit: u32) -> Vec<VestingSchedule> {
+        let count = Self::get_vesting_schedule_count(env.clone());
+        let mut schedules = Vec::new(&env);
+        let mut id = offset + 1;
+        while id <= count && schedules.len() < limit.min(MAX_PAGE_SIZE) {
+            schedules.push_back(Self::get_vesting_schedule(env.clone(), id));
+            id += 1;
+        }
+        schedules
+    }
+
+    pub fn get_beneficiary_schedules(env: Env, beneficiary: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&VestingDataKey::BeneficiarySchedules(beneficiary))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Set or clear the shared audit log admin actions are journaled to (owner only)
+    pub fn set_vesting_audit_log(env: Env, caller: Address, audit_log: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_vesting_audit_log",
+            audit_log.clone(),
+        );
+        AdminAudit::set_log(&env, audit_log);
+    }
+
+    pub fn get_vesting_audit_log(env: Env) -> Option<Address> {
+        AdminAudit::get_log(&env)
+    }
+
+    /// Grant a role (owner only)
+    pub fn grant_vesting_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::grant_role(&env, &owner, role, &account);
+    }
+
+    /// Revoke a role (owner only)
+    pub fn revoke_vesting_role(env: Env, owner: Address, role: Role, account: Address) {
+        AccessControl::revoke_role(&env, &owner, role, &account);
+    }
+
+    // Burns the receipt of a schedule with nothing left to claim
+    fn retire_receipt(env: &Env, schedule_id: u64) {
+        if let Some((receipts, receipt_id)) = Self::get_vesting_receipt(env.clone(), schedule_id) {
+            ReceiptContractClient::new(env, &receipts)
+                .burn_receipt(&env.current_contract_address(), &receipt_id);
+            env.storage()
+                .persistent()
+                .remove(&VestingDataKey::Receipt(schedule_id));
+        }
+    }
+
+    fn vested(schedule: &VestingSchedule, now: u64) -> i128 {
+        if schedule.revoked || now >= schedule.end_time {
+            return schedule.total_amount;
+        }
+        if now < schedule.cliff_time {
+            return 0;
+        }
+
+        let elapsed = (now - schedule.start_time) as i128;
+        let duration = (schedule.end_time - schedule.start_time) as i128;
+        schedule.total_amount * elapsed / duration
+    }
+
+    /// Appoint the governance timelock allowed to rescue stray tokens (owner only)
+    pub fn set_vesting_rescue_timelock(env: Env, caller: Address, timelock: Option<Address>) {
+        AccessControl::require_audited_role(
+            &env,
+            Role::Owner,
+            &caller,
+            "set_vesting_rescue_timelock",
+            timelock.clone(),
+        );
+        Rescue::set_timelock(&env, timelock);
+    }
+
+    pub fn get_vesting_rescue_timelock(env: Env) -> Option<Address> {
+        Rescue::get_timelock(&env)
+    }
+
+    /// Recover tokens sent directly to this contract (governance timelock only)
+    pub fn rescue_vesting_tokens(env: Env, token: Address, amount: i128, to: Address) {
+        Rescue::rescue_tokens(
+            &env,
+            &token,
+            amount,
+            &to,
+            Self::tracked_liabilities(&env, &token),
+        );
+    }
+
+    /// `token` still to be claimed from schedules
+    fn tracked_liabilities(env: &Env, token: &Address) -> Option<i128> {
+        let mut owed = 0;
+        for id in 1..=Self::get_vesting_schedule_count(env.clone()) {
+            let schedule = Self::get_vesting_schedule(env.clone(), id);
+            if schedule.token == *token {
+                owed += schedule.total_amount - schedule.claimed_amount;
+            }
+        }
+        Some(owed)
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{symbol_short, Address, Env};
+use stellar_multisig_contract::address_book::{AddressBookContract, AddressBookContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient};
+
+const DELAY: u64 = 86_400;
+
+fn setup_address_book(env: &Env) -> (AddressBookContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let client = AddressBookContractClient::new(env, &env.register(AddressBookContract, ()));
+    client.initialize_address_book(&admin, &DELAY);
+    (client, admin)
+}
+
+#[test]
+fn test_whitelist_only_mode_waits_out_new_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (book, _) = setup_address_book(&env);
+    let (user, friend) = (Address::generate(&env), Address::generate(&env));
+
+    // Without whitelist-only mode anyone can be paid
+    assert!(book.is_approved(&user, &friend));
+    book.set_whitelist_only(&user, &true);
+    assert!(!book.is_approved(&user, &friend));
+    assert!(book.is_approved(&user, &user));
+
+    let entry = book.add_beneficiary(&user, &friend, &symbol_short!("friend"));
+    assert_eq!(entry.active_at, entry.added_at + DELAY);
+    assert!(!book.is_approved(&user, &friend));
+    advance_ledger(&env, DELAY);
+    assert!(book.is_approved(&user, &friend));
+
+    // Relabelling keeps the entry active
+    book.add_beneficiary(&user, &friend, &symbol_short!("bob"));
+    assert!(book.is_approved(&user, &friend));
+    book.remove_beneficiary(&user, &friend);
+    assert!(!book.is_approved(&user, &friend));
+    assert!(book.try_remove_beneficiary(&user, &friend).is_err());
+
+    // Turning the mode off takes the delay too
+    book.set_whitelist_only(&user, &false);
+    assert!(book.is_whitelist_only(&user));
+    advance_ledger(&env, DELAY);
+    assert!(!book.is_whitelist_only(&user));
+    assert!(book.is_approved(&user, &friend));
+}
+
+#[test]
+fn test_token_transfers_limited_to_approved_beneficiaries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (book, admin) = setup_address_book(&env);
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+
+    let token = TokenContractClient::new(&env, &env.register(TokenContract, ()));
+    token.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    token.mint(&admin, &alice, &1_000);
+    token.set_token_address_book(&admin, &Some(book.address.clone()));
+    token.transfer(&alice, &bob, &100);
+
+    book.set_whitelist_only(&alice, &true);
+    assert!(token.try_transfer(&alice, &bob, &100).is_err());
+    // Only the sender's own book matters
+    token.transfer(&bob, &alice, &50);
+
+    book.add_beneficiary(&alice, &bob, &symbol_short!("bob"));
+    advance_ledger(&env, DELAY);
+    token.transfer(&alice, &bob, &100);
+    assert_eq!(token.balance(&bob), 150);
+}
@@ -0,0 +1,176 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::auction::{
+    AuctionContract, AuctionContractClient, AuctionKind, AuctionParams, AuctionStatus,
+};
+use stellar_multisig_contract::event::{AuctionEvent, DeFiEvent, EventEnvelope};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+struct Setup<'a> {
+    client: AuctionContractClient<'a>,
+    seller: Address,
+    lot: token::Client<'a>,
+    bids: token::Client<'a>,
+}
+
+fn setup_auction_house(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let seller = Address::generate(env);
+    let client = AuctionContractClient::new(env, &env.register(AuctionContract, ()));
+    client.initialize_auction_house(&admin);
+    client.grant_auction_role(&admin, &Role::Operator, &seller);
+
+    let lot = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let bids = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &lot.address()).mint(&seller, &1_000);
+    Setup {
+        client,
+        seller,
+        lot: token::Client::new(env, &lot.address()),
+        bids: token::Client::new(env, &bids.address()),
+    }
+}
+
+fn bidder(env: &Env, setup: &Setup, balance: i128) -> Address {
+    let bidder = Address::generate(env);
+    token::StellarAssetClient::new(env, &setup.bids.address).mint(&bidder, &balance);
+    bidder
+}
+
+fn params(start_price: i128, reserve_price: i128) -> AuctionParams {
+    AuctionParams {
+        start_price,
+        reserve_price,
+        min_increment_bps: 500,
+        duration: 3600,
+        extension_window: 300,
+    }
+}
+
+#[test]
+fn test_english_auction_outbids_extends_and_settles() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_auction_house(&env);
+    let alice = bidder(&env, &setup, 5_000);
+    let bob = bidder(&env, &setup, 5_000);
+
+    let id = setup.client.create_auction(
+        &setup.seller,
+        &setup.lot.address,
+        &1_000,
+        &setup.bids.address,
+        &AuctionKind::English,
+        &params(0, 1_000),
+    );
+    assert_eq!(setup.lot.balance(&setup.client.address), 1_000);
+
+    assert!(setup.client.try_place_bid(&id, &alice, &999).is_err());
+    setup.client.place_bid(&id, &alice, &1_000);
+    // 5% minimum increment
+    assert_eq!(setup.client.get_min_bid(&id), 1_050);
+    assert!(setup.client.try_place_bid(&id, &bob, &1_049).is_err());
+
+    // A last-minute bid pushes the end back and refunds the previous bidder
+    set_ledger_time(&env, 3500);
+    setup.client.place_bid(&id, &bob, &1_200);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Auction(AuctionEvent::BidPlaced(data)) => {
+            assert_eq!(data.bidder, bob);
+            assert_eq!(data.end_time, 3800);
+        }
+        _ => panic!("expected AuctionEvent::BidPlaced"),
+    }
+    assert_eq!(setup.client.get_auction(&id).end_time, 3800);
+    assert_eq!(setup.bids.balance(&alice), 5_000);
+
+    set_ledger_time(&env, 3700);
+    assert!(setup.client.try_settle_auction(&id).is_err());
+    assert!(setup.client.try_cancel_auction(&id).is_err());
+    set_ledger_time(&env, 3800);
+    assert!(setup.client.try_place_bid(&id, &alice, &2_000).is_err());
+    setup.client.settle_auction(&id);
+
+    assert_eq!(setup.lot.balance(&bob), 1_000);
+    assert_eq!(setup.bids.balance(&setup.seller), 1_200);
+    assert_eq!(setup.client.get_auction(&id).status, AuctionStatus::Settled);
+    assert!(setup.client.try_settle_auction(&id).is_err());
+}
+
+#[test]
+fn test_dutch_auction_price_decays_to_first_taker() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_auction_house(&env);
+    let buyer = bidder(&env, &setup, 5_000);
+
+    let id = setup.client.create_auction(
+        &setup.seller,
+        &setup.lot.address,
+        &1_000,
+        &setup.bids.address,
+        &AuctionKind::Dutch,
+        &params(2_000, 800),
+    );
+    assert_eq!(setup.client.get_min_bid(&id), 2_000);
+
+    set_ledger_time(&env, 1800);
+    assert_eq!(setup.client.get_min_bid(&id), 1_400);
+    assert!(setup.client.try_place_bid(&id, &buyer, &1_300).is_err());
+
+    // Pays the current price, not the bid ceiling
+    setup.client.place_bid(&id, &buyer, &1_500);
+    assert_eq!(setup.bids.balance(&buyer), 3_600);
+    assert_eq!(setup.bids.balance(&setup.seller), 1_400);
+    assert_eq!(setup.lot.balance(&buyer), 1_000);
+    assert_eq!(setup.client.get_auction(&id).status, AuctionStatus::Settled);
+}
+
+#[test]
+fn test_unsold_lots_return_to_seller() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_auction_house(&env);
+
+    assert!(setup
+        .client
+        .try_create_auction(
+            &Address::generate(&env),
+            &setup.lot.address,
+            &100,
+            &setup.bids.address,
+            &AuctionKind::English,
+            &params(0, 100),
+        )
+        .is_err());
+
+    let cancelled = setup.client.create_auction(
+        &setup.seller,
+        &setup.lot.address,
+        &400,
+        &setup.bids.address,
+        &AuctionKind::English,
+        &params(0, 100),
+    );
+    setup.client.cancel_auction(&cancelled);
+    assert_eq!(
+        setup.client.get_auction(&cancelled).status,
+        AuctionStatus::Cancelled
+    );
+
+    let expired = setup.client.create_auction(
+        &setup.seller,
+        &setup.lot.address,
+        &600,
+        &setup.bids.address,
+        &AuctionKind::Dutch,
+        &params(900, 300),
+    );
+    set_ledger_time(&env, 3600);
+    setup.client.settle_auction(&expired);
+    assert_eq!(setup.lot.balance(&setup.seller), 1_000);
+}
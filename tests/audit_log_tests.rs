@@ -0,0 +1,70 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Symbol};
+use stellar_multisig_contract::audit_log::{AuditLogContract, AuditLogContractClient};
+use stellar_multisig_contract::blocklist::{BlocklistContract, BlocklistContractClient};
+
+fn setup_audit_log(env: &Env) -> (AuditLogContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let client = AuditLogContractClient::new(env, &env.register(AuditLogContract, ()));
+    client.initialize_audit_log(&admin);
+    (client, admin)
+}
+
+#[test]
+fn test_admin_actions_are_hash_chained_in_the_log() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (log, log_admin) = setup_audit_log(&env);
+
+    let admin = Address::generate(&env);
+    let blocklist_id = env.register(BlocklistContract, ());
+    let blocklist = BlocklistContractClient::new(&env, &blocklist_id);
+    blocklist.initialize_blocklist(&admin);
+    blocklist.set_blocklist_audit_log(&admin, &Some(log.address.clone()));
+
+    // Only registered contracts may append
+    let flagged = Address::generate(&env);
+    assert!(blocklist
+        .try_block_address(&admin, &flagged, &symbol_short!("ofac"))
+        .is_err());
+    log.set_audit_source(&log_admin, &blocklist_id, &true);
+    assert!(log.is_audit_source(&blocklist_id));
+
+    blocklist.block_address(&admin, &flagged, &symbol_short!("ofac"));
+    blocklist.unblock_address(&admin, &flagged);
+    assert_eq!(log.get_admin_action_count(), 2);
+
+    let first = log.get_admin_action(&0).unwrap();
+    assert_eq!(first.actor, admin);
+    assert_eq!(first.contract, blocklist_id);
+    assert_eq!(first.function, Symbol::new(&env, "block_address"));
+    assert_eq!(first.prev_hash, BytesN::from_array(&env, &[0; 32]));
+    let params_hash: BytesN<32> = env
+        .crypto()
+        .sha256(&(flagged.clone(), symbol_short!("ofac")).to_xdr(&env))
+        .into();
+    assert_eq!(first.params_hash, params_hash);
+
+    let second = log.get_admin_action(&1).unwrap();
+    assert_eq!(second.function, Symbol::new(&env, "unblock_address"));
+    let first_hash: BytesN<32> = env.crypto().sha256(&first.clone().to_xdr(&env)).into();
+    assert_eq!(second.prev_hash, first_hash);
+    let second_hash: BytesN<32> = env.crypto().sha256(&second.clone().to_xdr(&env)).into();
+    assert_eq!(log.get_audit_head(), second_hash);
+
+    // Pages run oldest first and stop at the end of the log
+    let page = log.get_admin_actions(&1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap(), second);
+    assert_eq!(log.get_admin_actions(&0, &1).get(0).unwrap(), first);
+    assert!(log.get_admin_actions(&5, &10).is_empty());
+
+    // Clearing the log is itself journaled, then reporting stops
+    blocklist.set_blocklist_audit_log(&admin, &None);
+    assert_eq!(log.get_admin_action_count(), 3);
+    blocklist.block_address(&admin, &flagged, &symbol_short!("ofac"));
+    assert_eq!(log.get_admin_action_count(), 3);
+}
@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{symbol_short, Address, Env, TryFromVal};
+use stellar_multisig_contract::blocklist::{BlocklistContract, BlocklistContractClient};
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::event::{BlocklistEvent, DeFiEvent, EventEnvelope};
+use stellar_multisig_contract::pool_manager::{PoolManagerContract, PoolManagerContractClient};
+use stellar_multisig_contract::testutils::fund_pools;
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient};
+
+fn setup_blocklist(env: &Env) -> (BlocklistContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let client = BlocklistContractClient::new(env, &env.register(BlocklistContract, ()));
+    client.initialize_blocklist(&admin);
+    (client, admin)
+}
+
+#[test]
+fn test_block_and_unblock_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_blocklist(&env);
+    let flagged = Address::generate(&env);
+
+    assert!(client
+        .try_block_address(&flagged, &flagged, &symbol_short!("ofac"))
+        .is_err());
+    client.block_address(&admin, &flagged, &symbol_short!("ofac"));
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Blocklist(BlocklistEvent::Blocked(data)) => {
+            assert_eq!(data.account, flagged);
+            assert_eq!(data.reason, symbol_short!("ofac"));
+        }
+        _ => panic!("expected BlocklistEvent::Blocked"),
+    }
+    assert!(client.is_blocked(&flagged));
+    assert_eq!(client.get_block_entry(&flagged).unwrap().blocked_by, admin);
+
+    client.unblock_address(&admin, &flagged);
+    assert!(!client.is_blocked(&flagged));
+    assert!(client.try_unblock_address(&admin, &flagged).is_err());
+}
+
+#[test]
+fn test_blocked_address_rejected_by_token_and_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (blocklist, admin) = setup_blocklist(&env);
+    let alice = Address::generate(&env);
+    let flagged = Address::generate(&env);
+    blocklist.block_address(&admin, &flagged, &symbol_short!("ofac"));
+
+    let token = TokenContractClient::new(&env, &env.register(TokenContract, ()));
+    token.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    token.mint(&admin, &alice, &1_000);
+    token.mint(&admin, &flagged, &1_000);
+    token.set_token_blocklist(&admin, &Some(blocklist.address.clone()));
+    assert!(token.try_transfer(&alice, &flagged, &100).is_err());
+    assert!(token.try_transfer(&flagged, &alice, &100).is_err());
+    token.transfer(&alice, &admin, &100);
+
+    let pool = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+    pool.initialize_pool_manager(&admin, &1_000, &1_000_000, &0, &50);
+    fund_pools(&env, &pool, &admin, &[&alice, &flagged], 10_000);
+    pool.set_pool_blocklist(&admin, &Some(blocklist.address.clone()));
+    assert!(pool
+        .try_add_liquidity(&flagged, &Currency::NGN, &10_000, &None)
+        .is_err());
+    pool.add_liquidity(&alice, &Currency::NGN, &10_000, &None);
+
+    blocklist.unblock_address(&admin, &flagged);
+    pool.add_liquidity(&flagged, &Currency::NGN, &10_000, &None);
+    assert_eq!(pool.get_pool(&Currency::NGN).total_liquidity, 20_000);
+}
@@ -0,0 +1,258 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, vec, Address, Bytes, BytesN, Env, TryFromVal, Vec};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::bridge::{
+    AttestorSignature, BridgeContract, BridgeContractClient, BridgeMode,
+};
+use stellar_multisig_contract::event::{BridgeEvent, DeFiEvent, EventEnvelope};
+
+struct Setup<'a> {
+    client: BridgeContractClient<'a>,
+    admin: Address,
+    attestors: [SigningKey; 3],
+    usdc: token::Client<'a>,
+    wrapped: token::Client<'a>,
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+fn setup_bridge(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let attestors = [
+        SigningKey::from_bytes(&[1u8; 32]),
+        SigningKey::from_bytes(&[2u8; 32]),
+        SigningKey::from_bytes(&[3u8; 32]),
+    ];
+    let keys = vec![
+        env,
+        public_key(env, &attestors[0]),
+        public_key(env, &attestors[1]),
+        public_key(env, &attestors[2]),
+    ];
+    let client = BridgeContractClient::new(env, &env.register(BridgeContract, ()));
+    client.initialize_bridge(&admin, &keys, &2);
+
+    // USDC is native here; the wrapped asset is administered by the bridge
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let wrapped = env.register_stellar_asset_contract_v2(client.address.clone());
+    client.set_bridge_asset(&admin, &usdc.address(), &BridgeMode::Locked);
+    client.set_bridge_asset(&admin, &wrapped.address(), &BridgeMode::Wrapped);
+    Setup {
+        client,
+        admin,
+        attestors,
+        usdc: token::Client::new(env, &usdc.address()),
+        wrapped: token::Client::new(env, &wrapped.address()),
+    }
+}
+
+fn sign(
+    env: &Env,
+    setup: &Setup,
+    signers: &[&SigningKey],
+    transfer_id: &BytesN<32>,
+    token: &Address,
+    recipient: &Address,
+    amount: i128,
+) -> Vec<AttestorSignature> {
+    let payload = setup
+        .client
+        .bridge_in_payload(transfer_id, token, recipient, &amount);
+    let mut signatures = Vec::new(env);
+    for key in signers {
+        signatures.push_back(AttestorSignature {
+            public_key: public_key(env, key),
+            signature: BytesN::from_array(env, &key.sign(&payload.to_array()).to_bytes()),
+        });
+    }
+    signatures
+}
+
+#[test]
+fn test_locked_asset_round_trip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_bridge(&env);
+    let user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &s.usdc.address).mint(&user, &1_000);
+
+    let dest = Bytes::from_array(&env, &[0xab; 20]);
+    assert_eq!(
+        s.client.bridge_out(&user, &s.usdc.address, &600, &1, &dest),
+        1
+    );
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Bridge(BridgeEvent::Outbound(data)) => {
+            assert_eq!(data.amount, 600);
+            assert_eq!(data.dest_chain, 1);
+            assert_eq!(data.dest_address, dest);
+        }
+        _ => panic!("expected BridgeEvent::Outbound"),
+    }
+    assert_eq!(s.usdc.balance(&s.client.address), 600);
+
+    // Coming back: the remote mirror was burned and two attestors sign
+    let transfer_id = BytesN::from_array(&env, &[9u8; 32]);
+    let signatures = sign(
+        &env,
+        &s,
+        &[&s.attestors[0], &s.attestors[2]],
+        &transfer_id,
+        &s.usdc.address,
+        &user,
+        250,
+    );
+    s.client
+        .bridge_in(&transfer_id, &s.usdc.address, &user, &250, &signatures);
+    assert_eq!(s.usdc.balance(&user), 650);
+    assert!(s.client.is_bridge_transfer_processed(&transfer_id));
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &s.usdc.address, &user, &250, &signatures)
+        .is_err());
+}
+
+#[test]
+fn test_wrapped_asset_mints_and_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_bridge(&env);
+    let user = Address::generate(&env);
+
+    let transfer_id = BytesN::from_array(&env, &[4u8; 32]);
+    let signatures = sign(
+        &env,
+        &s,
+        &[&s.attestors[1], &s.attestors[0]],
+        &transfer_id,
+        &s.wrapped.address,
+        &user,
+        500,
+    );
+    s.client
+        .bridge_in(&transfer_id, &s.wrapped.address, &user, &500, &signatures);
+    assert_eq!(s.wrapped.balance(&user), 500);
+
+    let dest = Bytes::from_array(&env, &[0xcd; 20]);
+    s.client
+        .bridge_out(&user, &s.wrapped.address, &200, &1, &dest);
+    assert_eq!(s.wrapped.balance(&user), 300);
+    assert_eq!(s.wrapped.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_inbound_transfer_needs_valid_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_bridge(&env);
+    let user = Address::generate(&env);
+    let transfer_id = BytesN::from_array(&env, &[5u8; 32]);
+    let token = s.wrapped.address.clone();
+
+    let single = sign(
+        &env,
+        &s,
+        &[&s.attestors[0]],
+        &transfer_id,
+        &token,
+        &user,
+        100,
+    );
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &token, &user, &100, &single)
+        .is_err());
+
+    let duplicated = sign(
+        &env,
+        &s,
+        &[&s.attestors[0], &s.attestors[0]],
+        &transfer_id,
+        &token,
+        &user,
+        100,
+    );
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &token, &user, &100, &duplicated)
+        .is_err());
+
+    let outsider = SigningKey::from_bytes(&[8u8; 32]);
+    let forged = sign(
+        &env,
+        &s,
+        &[&s.attestors[0], &outsider],
+        &transfer_id,
+        &token,
+        &user,
+        100,
+    );
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &token, &user, &100, &forged)
+        .is_err());
+
+    // Signatures over a different amount do not carry over
+    let valid = sign(
+        &env,
+        &s,
+        &[&s.attestors[0], &s.attestors[1]],
+        &transfer_id,
+        &token,
+        &user,
+        100,
+    );
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &token, &user, &1_000, &valid)
+        .is_err());
+    assert_eq!(s.wrapped.balance(&user), 0);
+}
+
+#[test]
+fn test_pause_halts_bridge() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_bridge(&env);
+    let monitor = Address::generate(&env);
+    s.client
+        .grant_bridge_role(&s.admin, &Role::Pauser, &monitor);
+    let user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &s.usdc.address).mint(&user, &1_000);
+    let dest = Bytes::from_array(&env, &[0xab; 20]);
+
+    assert!(s.client.try_pause_bridge(&user).is_err());
+    s.client.pause_bridge(&monitor);
+    assert!(s.client.is_bridge_paused());
+    assert!(s
+        .client
+        .try_bridge_out(&user, &s.usdc.address, &100, &1, &dest)
+        .is_err());
+
+    let transfer_id = BytesN::from_array(&env, &[6u8; 32]);
+    let signatures = sign(
+        &env,
+        &s,
+        &[&s.attestors[0], &s.attestors[1]],
+        &transfer_id,
+        &s.wrapped.address,
+        &user,
+        100,
+    );
+    assert!(s
+        .client
+        .try_bridge_in(&transfer_id, &s.wrapped.address, &user, &100, &signatures)
+        .is_err());
+
+    assert!(s.client.try_unpause_bridge(&monitor).is_err());
+    s.client.unpause_bridge(&s.admin);
+    s.client
+        .bridge_in(&transfer_id, &s.wrapped.address, &user, &100, &signatures);
+    assert_eq!(s.wrapped.balance(&user), 100);
+}
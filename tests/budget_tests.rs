@@ -0,0 +1,162 @@
+#![cfg(test)]
+
+//! Budget regression tests for the hot paths. Each test measures one call
+//! with a fresh budget and fails when its CPU or memory cost climbs past the
+//! ceiling below. Ceilings sit roughly 25% above the measured cost; lower
+//! them when an optimisation lands, and only raise them deliberately.
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env};
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract};
+use stellar_multisig_contract::p2p_market::{
+    P2pMarketContract, P2pMarketContractClient, P2pOfferTerms,
+};
+use stellar_multisig_contract::pool_manager::{PoolManagerContract, PoolManagerContractClient};
+use stellar_multisig_contract::testutils::{fund_pools, set_ledger_time};
+use stellar_multisig_contract::ConversionContract;
+
+struct Ceiling {
+    cpu: u64,
+    mem: u64,
+}
+
+/// Run `call` on a fresh default budget and check it stays under `ceiling`
+fn assert_within_budget<T>(env: &Env, name: &str, ceiling: Ceiling, call: impl FnOnce() -> T) {
+    let mut budget = env.cost_estimate().budget();
+    budget.reset_default();
+    call();
+    let (cpu, mem) = (budget.cpu_instruction_cost(), budget.memory_bytes_cost());
+    assert!(
+        cpu <= ceiling.cpu,
+        "{name}: {cpu} CPU instructions, ceiling {}",
+        ceiling.cpu
+    );
+    assert!(
+        mem <= ceiling.mem,
+        "{name}: {mem} memory bytes, ceiling {}",
+        ceiling.mem
+    );
+}
+
+fn setup_token(env: &Env, holder: &Address) -> Address {
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &sac.address()).mint(holder, &10_000_000);
+    sac.address()
+}
+
+#[test]
+fn test_create_escrow_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (admin, sender, recipient) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    let token = setup_token(&env, &sender);
+    let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+    escrow.initialize(&admin);
+
+    assert_within_budget(
+        &env,
+        "create escrow",
+        Ceiling {
+            cpu: 400_000,
+            mem: 72_000,
+        },
+        || escrow.create(&sender, &recipient, &token, &5_000, &86_400, &86_400),
+    );
+}
+
+#[test]
+fn test_convert_currency_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (admin, user) = (Address::generate(&env), Address::generate(&env));
+    let client = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(
+        &admin,
+        &50u32,
+        &Address::generate(&env),
+        &100i128,
+        &1_000_000_000i128,
+    );
+    client.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000i128,
+        &3600u64,
+    );
+    client.deposit(&user, &Currency::USD, &100_000i128);
+
+    assert_within_budget(
+        &env,
+        "convert",
+        Ceiling {
+            cpu: 480_000,
+            mem: 72_000,
+        },
+        || client.convert_currency(&user, &Currency::USD, &Currency::NGN, &1_000i128),
+    );
+}
+
+#[test]
+fn test_accept_p2p_offer_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (admin, seller, buyer) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    let token = setup_token(&env, &seller);
+    let market = P2pMarketContractClient::new(&env, &env.register(P2pMarketContract, ()));
+    market.initialize_p2p(&admin);
+    let offer_id = market.post_p2p_offer(
+        &seller,
+        &token,
+        &600_000,
+        &P2pOfferTerms {
+            fiat: Currency::NGN,
+            price: 100_000_000,
+            min_trade: 10_000,
+            max_trade: 200_000,
+            payment_window: 3_600,
+        },
+    );
+
+    assert_within_budget(
+        &env,
+        "accept offer",
+        Ceiling {
+            cpu: 180_000,
+            mem: 35_000,
+        },
+        || market.open_p2p_trade(&buyer, &offer_id, &50_000),
+    );
+}
+
+#[test]
+fn test_add_liquidity_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    set_ledger_time(&env, 1_000);
+    let (admin, provider) = (Address::generate(&env), Address::generate(&env));
+    let pools = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+    pools.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86_400, &50);
+    fund_pools(&env, &pools, &admin, &[&provider], 10_000_000_000);
+    pools.add_liquidity(&provider, &Currency::USD, &5_000_000_000, &None);
+
+    // Raised for the token transfer into the pool
+    assert_within_budget(
+        &env,
+        "add liquidity",
+        Ceiling {
+            cpu: 700_000,
+            mem: 105_000,
+        },
+        || pools.add_liquidity(&provider, &Currency::USD, &2_000_000_000, &None),
+    );
+}
@@ -0,0 +1,115 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{symbol_short, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::compliance::{
+    ComplianceContract, ComplianceContractClient, CompliancePolicy,
+};
+use stellar_multisig_contract::conversion::{
+    ConversionContract, ConversionContractClient, Currency,
+};
+use stellar_multisig_contract::event::{ComplianceEvent, DeFiEvent, EventEnvelope};
+use stellar_multisig_contract::testutils::set_ledger_time;
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient};
+
+const YEAR: u64 = 31_536_000;
+
+fn setup_compliance(env: &Env) -> (ComplianceContractClient<'_>, Address, Address) {
+    let admin = Address::generate(env);
+    let attestor = Address::generate(env);
+    let client = ComplianceContractClient::new(env, &env.register(ComplianceContract, ()));
+    client.initialize_compliance(&admin);
+    client.grant_compliance_role(&admin, &Role::Attestor, &attestor);
+    (client, admin, attestor)
+}
+
+#[test]
+fn test_kyc_tier_expires_and_revokes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, attestor) = setup_compliance(&env);
+    let user = Address::generate(&env);
+    assert_eq!(client.get_kyc_tier(&user), 0);
+
+    client.attest_kyc(&attestor, &user, &2, &YEAR);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Compliance(ComplianceEvent::Attested(data)) => {
+            assert_eq!(data.subject, user);
+            assert_eq!(data.tier, 2);
+        }
+        _ => panic!("expected ComplianceEvent::Attested"),
+    }
+    assert_eq!(client.get_kyc_tier(&user), 2);
+    assert!(client.try_attest_kyc(&attestor, &user, &4, &YEAR).is_err());
+
+    set_ledger_time(&env, YEAR);
+    assert_eq!(client.get_kyc_tier(&user), 0);
+    assert_eq!(client.get_kyc_record(&user).unwrap().tier, 2);
+
+    client.attest_kyc(&attestor, &user, &3, &(2 * YEAR));
+    client.revoke_kyc(&attestor, &user);
+    assert_eq!(client.get_kyc_record(&user), None);
+    assert_eq!(client.get_kyc_tier(&user), 0);
+}
+
+#[test]
+fn test_attest_requires_attestor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _attestor) = setup_compliance(&env);
+    let user = Address::generate(&env);
+    let operator = Address::generate(&env);
+    client.grant_compliance_role(&admin, &Role::Operator, &operator);
+
+    assert!(client.try_attest_kyc(&operator, &user, &1, &YEAR).is_err());
+    assert!(client.try_attest_kyc(&user, &user, &3, &YEAR).is_err());
+    assert_eq!(client.get_kyc_tier(&user), 0);
+}
+
+#[test]
+fn test_policy_enforced_on_transfers_and_conversions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (registry, admin, attestor) = setup_compliance(&env);
+    let verified = Address::generate(&env);
+    let unverified = Address::generate(&env);
+    registry.attest_kyc(&attestor, &verified, &2, &YEAR);
+    registry.attest_kyc(&attestor, &admin, &3, &YEAR);
+    let policy = CompliancePolicy {
+        registry: registry.address.clone(),
+        min_tier: 2,
+        threshold: 1_000,
+    };
+
+    let token = TokenContractClient::new(&env, &env.register(TokenContract, ()));
+    token.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    token.mint(&admin, &verified, &10_000);
+    token.set_token_compliance_policy(&admin, &Some(policy.clone()));
+
+    // Small transfers stay open to everyone
+    token.transfer(&verified, &unverified, &999);
+    assert!(token.try_transfer(&verified, &unverified, &1_000).is_err());
+    token.transfer(&verified, &admin, &1_000);
+    assert_eq!(token.balance(&unverified), 999);
+
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(&admin, &0, &admin, &1, &1_000_000_000);
+    conversion.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &3600,
+    );
+    conversion.set_conversion_compliance_policy(&admin, &Some(policy));
+    conversion.deposit(&verified, &Currency::USD, &5_000);
+    conversion.deposit(&unverified, &Currency::USD, &5_000);
+
+    conversion.convert_currency(&unverified, &Currency::USD, &Currency::NGN, &500);
+    assert!(conversion
+        .try_convert_currency(&unverified, &Currency::USD, &Currency::NGN, &2_000)
+        .is_err());
+    conversion.convert_currency(&verified, &Currency::USD, &Currency::NGN, &2_000);
+}
@@ -0,0 +1,186 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::dca::{DcaContract, DcaContractClient};
+use stellar_multisig_contract::event::{DcaEvent, DeFiEvent, EventEnvelope};
+use stellar_multisig_contract::keeper::KeeperTip;
+use stellar_multisig_contract::oracle::{OracleContract, OracleContractClient};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+const DAY: u64 = 86_400;
+
+struct Setup<'a> {
+    client: DcaContractClient<'a>,
+    admin: Address,
+    user: Address,
+    ngn: token::Client<'a>,
+    usd: token::Client<'a>,
+}
+
+fn setup_vault(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+
+    let oracle = OracleContractClient::new(env, &env.register(OracleContract, ()));
+    oracle.initialize_oracle(&admin, &1, &(365 * DAY), &10_000);
+    oracle.add_price_feeder(&admin, &admin);
+    // 1 NGN = 0.000625 USD
+    oracle.submit_price(&admin, &Currency::NGN, &Currency::USD, &62_500);
+
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let usd = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &ngn.address()).mint(&user, &1_000_000);
+    token::StellarAssetClient::new(env, &usd.address()).mint(&admin, &1_000);
+
+    let client = DcaContractClient::new(env, &env.register(DcaContract, ()));
+    client.initialize_dca(&admin, &oracle.address);
+    client.grant_dca_role(&admin, &Role::Operator, &admin);
+    client.set_dca_market(&admin, &Currency::NGN, &ngn.address());
+    client.set_dca_market(&admin, &Currency::USD, &usd.address());
+    client.provide_dca_liquidity(&admin, &Currency::USD, &1_000);
+    Setup {
+        client,
+        admin,
+        user,
+        ngn: token::Client::new(env, &ngn.address()),
+        usd: token::Client::new(env, &usd.address()),
+    }
+}
+
+#[test]
+fn test_dca_buys_on_schedule_at_oracle_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_vault(&env);
+
+    let id = s.client.create_dca_plan(
+        &s.user,
+        &Currency::NGN,
+        &Currency::USD,
+        &500_000,
+        &200_000,
+        &(7 * DAY),
+    );
+
+    assert_eq!(s.client.execute_dca(&s.admin, &id), 125);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Dca(DcaEvent::Executed(data)) => {
+            assert_eq!(data.spent, 200_000);
+            assert_eq!(data.received, 125);
+            assert_eq!(data.remaining, 300_000);
+        }
+        _ => panic!("expected DcaEvent::Executed"),
+    }
+    assert!(s.client.try_execute_dca(&s.admin, &id).is_err());
+
+    set_ledger_time(&env, 7 * DAY);
+    s.client.execute_dca(&s.admin, &id);
+    set_ledger_time(&env, 14 * DAY);
+    // The last purchase spends only what is left
+    assert_eq!(s.client.execute_dca(&s.admin, &id), 62);
+
+    let plan = s.client.get_dca_plan(&id);
+    assert_eq!(plan.balance, 0);
+    assert_eq!(plan.purchased, 312);
+    assert!(!plan.active);
+    assert_eq!(s.usd.balance(&s.user), 312);
+    assert_eq!(s.client.get_dca_liquidity(&Currency::USD), 688);
+    assert_eq!(s.client.get_dca_liquidity(&Currency::NGN), 500_000);
+
+    // Collected NGN can be taken out by the operator
+    s.client
+        .withdraw_dca_liquidity(&s.admin, &Currency::NGN, &500_000);
+    assert_eq!(s.ngn.balance(&s.admin), 500_000);
+}
+
+#[test]
+fn test_cancel_dca_refunds_unspent_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_vault(&env);
+
+    let id = s.client.create_dca_plan(
+        &s.user,
+        &Currency::NGN,
+        &Currency::USD,
+        &500_000,
+        &200_000,
+        &DAY,
+    );
+    s.client.execute_dca(&s.admin, &id);
+
+    assert_eq!(s.client.cancel_dca_plan(&id), 300_000);
+    assert_eq!(s.ngn.balance(&s.user), 800_000);
+    set_ledger_time(&env, DAY);
+    assert!(s.client.try_execute_dca(&s.admin, &id).is_err());
+    assert!(s.client.try_cancel_dca_plan(&id).is_err());
+}
+
+#[test]
+fn test_dca_purchase_needs_vault_liquidity() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_vault(&env);
+    s.client
+        .withdraw_dca_liquidity(&s.admin, &Currency::USD, &900);
+
+    assert!(s
+        .client
+        .try_create_dca_plan(
+            &s.user,
+            &Currency::NGN,
+            &Currency::NGN,
+            &1_000,
+            &1_000,
+            &DAY
+        )
+        .is_err());
+    let id = s.client.create_dca_plan(
+        &s.user,
+        &Currency::NGN,
+        &Currency::USD,
+        &400_000,
+        &400_000,
+        &DAY,
+    );
+    // 400_000 NGN buys 250 USD but only 100 is available
+    assert!(s.client.try_execute_dca(&s.admin, &id).is_err());
+    assert_eq!(s.client.get_dca_plan(&id).balance, 400_000);
+}
+
+#[test]
+fn test_keeper_tip_taken_from_purchase() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_vault(&env);
+    let keeper = Address::generate(&env);
+
+    let tip = KeeperTip {
+        tip_bps: 100,
+        max_tip: 10,
+    };
+    assert!(s
+        .client
+        .try_set_dca_keeper_tip(&s.user, &Some(tip.clone()))
+        .is_err());
+    s.client.set_dca_keeper_tip(&s.admin, &Some(tip.clone()));
+    assert_eq!(s.client.get_dca_keeper_tip(), Some(tip));
+
+    let id = s.client.create_dca_plan(
+        &s.user,
+        &Currency::NGN,
+        &Currency::USD,
+        &200_000,
+        &200_000,
+        &DAY,
+    );
+    // 125 USD bought; the keeper takes 1% of it
+    assert_eq!(s.client.execute_dca(&keeper, &id), 125);
+    assert_eq!(s.usd.balance(&keeper), 1);
+    assert_eq!(s.usd.balance(&s.user), 124);
+    assert_eq!(s.client.get_dca_plan(&id).purchased, 125);
+}
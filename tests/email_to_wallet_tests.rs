@@ -0,0 +1,214 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, BytesN, Env, String};
+use stellar_multisig_contract::email_to_wallet::{
+    EmailPaymentStatus, EmailToWalletContract, EmailToWalletContractClient,
+};
+use stellar_multisig_contract::testutils::set_ledger_time;
+use stellar_multisig_contract::utils::{derive_wallet_address_from_email, hash_email};
+
+fn setup_email_registry(env: &Env) -> (EmailToWalletContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let backend = Address::generate(env);
+
+    let contract_id = env.register(EmailToWalletContract, ());
+    let client = EmailToWalletContractClient::new(env, &contract_id);
+    client.initialize_email_registry(&admin, &backend);
+
+    (client, backend)
+}
+
+#[test]
+fn test_resolve_falls_back_to_derived_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _backend) = setup_email_registry(&env);
+
+    let email = String::from_str(&env, "alice@example.com");
+    let derived = derive_wallet_address_from_email(&env, &email).unwrap();
+
+    assert_eq!(client.resolve(&email), derived);
+    assert_eq!(client.get_wallet_from_email(&email), derived);
+}
+
+#[test]
+fn test_resolve_prefers_verified_registration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _backend) = setup_email_registry(&env);
+
+    let email = String::from_str(&env, "alice@example.com");
+    let email_hash = hash_email(&env, &email).unwrap();
+    let wallet = Address::generate(&env);
+
+    // A pending claim is not used until the backend verifies it
+    client.register_email(&email_hash, &wallet);
+    assert_eq!(
+        client.resolve(&email),
+        derive_wallet_address_from_email(&env, &email).unwrap()
+    );
+
+    let registration = client.verify_email(&email_hash, &wallet);
+    assert!(registration.verified);
+    assert_eq!(client.resolve(&email), wallet);
+}
+
+#[test]
+#[should_panic(expected = "Wallet does not match registration")]
+fn test_verify_email_rejects_replaced_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _backend) = setup_email_registry(&env);
+
+    let email_hash = hash_email(&env, &String::from_str(&env, "alice@example.com")).unwrap();
+    let wallet = Address::generate(&env);
+    let squatter = Address::generate(&env);
+
+    client.register_email(&email_hash, &wallet);
+    client.register_email(&email_hash, &squatter);
+    client.verify_email(&email_hash, &wallet);
+}
+
+#[test]
+#[should_panic(expected = "Email already registered")]
+fn test_verified_email_cannot_be_reclaimed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _backend) = setup_email_registry(&env);
+
+    let email_hash = hash_email(&env, &String::from_str(&env, "alice@example.com")).unwrap();
+    let wallet = Address::generate(&env);
+
+    client.register_email(&email_hash, &wallet);
+    client.verify_email(&email_hash, &wallet);
+    client.register_email(&email_hash, &Address::generate(&env));
+}
+
+fn setup_email_payment(
+    env: &Env,
+) -> (
+    EmailToWalletContractClient<'_>,
+    token::Client<'_>,
+    Address,
+    BytesN<32>,
+) {
+    let (client, _backend) = setup_email_registry(env);
+    let sender = Address::generate(env);
+
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(token_admin);
+    token::StellarAssetClient::new(env, &token.address()).mint(&sender, &10_000);
+
+    let email_hash = hash_email(env, &String::from_str(env, "alice@example.com")).unwrap();
+    (
+        client,
+        token::Client::new(env, &token.address()),
+        sender,
+        email_hash,
+    )
+}
+
+#[test]
+fn test_email_payments_claimed_after_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, sender, email_hash) = setup_email_payment(&env);
+
+    client.send_to_email(&sender, &email_hash, &token.address, &1_000);
+    client.send_to_email(&sender, &email_hash, &token.address, &500);
+    assert_eq!(client.get_pending_payments(&email_hash).len(), 2);
+    assert_eq!(token.balance(&client.address), 1_500);
+
+    let wallet = Address::generate(&env);
+    client.register_email(&email_hash, &wallet);
+    client.verify_email(&email_hash, &wallet);
+
+    assert_eq!(client.claim_email_payments(&email_hash), 2);
+    assert_eq!(token.balance(&wallet), 1_500);
+    assert_eq!(client.get_pending_payments(&email_hash).len(), 0);
+    assert_eq!(
+        client.get_email_payment(&1).status,
+        EmailPaymentStatus::Claimed
+    );
+}
+
+#[test]
+#[should_panic(expected = "Email not verified")]
+fn test_email_payments_require_verified_claimant() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, sender, email_hash) = setup_email_payment(&env);
+
+    client.send_to_email(&sender, &email_hash, &token.address, &1_000);
+    client.register_email(&email_hash, &Address::generate(&env));
+    client.claim_email_payments(&email_hash);
+}
+
+#[test]
+fn test_expired_email_payment_refunded_to_sender() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, sender, email_hash) = setup_email_payment(&env);
+
+    let payment_id = client.send_to_email(&sender, &email_hash, &token.address, &1_000);
+    assert!(client.try_refund_email_payment(&payment_id).is_err());
+
+    set_ledger_time(&env, client.get_email_payment(&payment_id).expires_at + 1);
+    client.refund_email_payment(&payment_id);
+
+    assert_eq!(token.balance(&sender), 10_000);
+    assert_eq!(client.get_pending_payments(&email_hash).len(), 0);
+    assert_eq!(
+        client.get_email_payment(&payment_id).status,
+        EmailPaymentStatus::Refunded
+    );
+}
+
+fn setup_bound_email(env: &Env) -> (EmailToWalletContractClient<'_>, BytesN<32>, Address) {
+    let (client, _backend) = setup_email_registry(env);
+    client.set_recovery_authority(&Address::generate(env));
+
+    let email_hash = hash_email(env, &String::from_str(env, "alice@example.com")).unwrap();
+    let wallet = Address::generate(env);
+    client.register_email(&email_hash, &wallet);
+    client.verify_email(&email_hash, &wallet);
+
+    (client, email_hash, wallet)
+}
+
+#[test]
+fn test_email_rebind_after_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, email_hash, _lost_wallet) = setup_bound_email(&env);
+    let new_wallet = Address::generate(&env);
+
+    let rebind = client.initiate_rebind(&email_hash, &new_wallet);
+    assert!(client.try_execute_rebind(&email_hash).is_err());
+
+    set_ledger_time(&env, rebind.executable_at);
+    let registration = client.execute_rebind(&email_hash);
+
+    assert_eq!(registration.wallet, new_wallet);
+    assert_eq!(
+        client.resolve(&String::from_str(&env, "alice@example.com")),
+        new_wallet
+    );
+    assert!(client.get_pending_rebind(&email_hash).is_none());
+}
+
+#[test]
+#[should_panic(expected = "No pending rebind")]
+fn test_email_rebind_cancelled_by_current_wallet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, email_hash, wallet) = setup_bound_email(&env);
+
+    let rebind = client.initiate_rebind(&email_hash, &Address::generate(&env));
+    client.cancel_rebind(&email_hash);
+    assert_eq!(client.get_registration(&email_hash).unwrap().wallet, wallet);
+
+    set_ledger_time(&env, rebind.executable_at);
+    client.execute_rebind(&email_hash);
+}
@@ -3,8 +3,31 @@
 mod mock_token;
 
 use mock_token::{MockToken, MockTokenClient};
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env};
-use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract, EscrowStatus};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events},
+    xdr::{ContractEventBody, ScVal},
+    Address, Bytes, BytesN, Env, String, Symbol, TryFromVal, Val,
+};
+use stellar_multisig_contract::access::{AccessDataKey, Role};
+use stellar_multisig_contract::alias::{derive_alias_address, Alias, AliasKind};
+use stellar_multisig_contract::blocklist::{BlocklistContract, BlocklistContractClient};
+use stellar_multisig_contract::compliance::{
+    ComplianceContract, ComplianceContractClient, CompliancePolicy,
+};
+use stellar_multisig_contract::email_to_wallet::EmailToWalletContract;
+use stellar_multisig_contract::escrow::{
+    DeductionStatus, DisputeNote, EscrowClient, EscrowContract, EscrowStatus, ReleaseDelegation,
+    ReleasePreview, ReputationPolicy, TimeoutDestination,
+};
+use stellar_multisig_contract::event::{DeFiEvent, EscrowEvent, EventEnvelope};
+use stellar_multisig_contract::guardian::{GuardianContract, GuardianContractClient, Subsystem};
+use stellar_multisig_contract::inheritance::CLAIM_NOTICE_PERIOD;
+use stellar_multisig_contract::keeper::KeeperTip;
+use stellar_multisig_contract::rate_limit::RateLimit;
+use stellar_multisig_contract::reputation::{ReputationContract, ReputationContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
+use stellar_multisig_contract::upgrade::UpgradeDataKey;
 
 fn setup_test_env() -> (Env, Address, Address, Address, Address) {
     let env = Env::default();
@@ -62,6 +85,283 @@ fn test_create_escrow_success() {
     assert_eq!(token_client.balance(&escrow_contract_id), 500);
 }
 
+#[test]
+fn test_create_idempotent_returns_original_escrow() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let key = BytesN::from_array(&env, &[1; 32]);
+
+    let first = client.create_idempotent(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &key,
+    );
+    let retry = client.create_idempotent(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &key,
+    );
+    assert_eq!(retry.id, first.id);
+    assert_eq!(client.get_escrow_count(), 1);
+    assert_eq!(token_client.balance(&sender), 10_000 - 500);
+
+    // A replay reports the escrow as it stands now
+    client.release(&first.id);
+    let replay = client.create_idempotent(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &key,
+    );
+    assert_eq!(replay.status, EscrowStatus::Released);
+
+    // Keys are scoped to the sender
+    let other_sender = Address::generate(&env);
+    token_client.mint(&other_sender, &1_000);
+    let other = client.create_idempotent(
+        &other_sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &key,
+    );
+    assert_ne!(other.id, first.id);
+    assert_eq!(client.get_escrow_count(), 2);
+}
+
+#[test]
+fn test_timeout_destination_chosen_at_creation() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let keeper = Address::generate(&env);
+    let fallback = Address::generate(&env);
+
+    let default = client.create(&sender, &recipient, &token_contract_id, &100, &3600, &1800);
+    assert_eq!(
+        client.get_timeout_destination(&default.id),
+        TimeoutDestination::Recipient
+    );
+    let deposit = client.create_with_timeout_destination(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &TimeoutDestination::Sender,
+    );
+    let third_party = client.create_with_timeout_destination(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &300,
+        &3600,
+        &1800,
+        &TimeoutDestination::Fallback(fallback.clone()),
+    );
+    assert_eq!(
+        client.get_timeout_destination(&deposit.id),
+        TimeoutDestination::Sender
+    );
+    assert_eq!(token_client.balance(&sender), 10_000 - 900);
+
+    advance_ledger(&env, 3600);
+    let returned = client.check_timeout(&keeper, &deposit.id);
+    assert_eq!(returned.status, EscrowStatus::Refunded);
+    assert_eq!(token_client.balance(&sender), 10_000 - 400);
+
+    let redirected = client.check_timeout(&keeper, &third_party.id);
+    assert_eq!(redirected.status, EscrowStatus::AutoReleased);
+    assert_eq!(token_client.balance(&fallback), 300);
+
+    client.check_timeout(&keeper, &default.id);
+    assert_eq!(token_client.balance(&recipient), 100);
+}
+
+#[test]
+fn test_crowdfunded_escrow_activates_or_refunds_contributors() {
+    let (env, escrow_contract_id, token_contract_id, organizer, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    token_client.mint(&alice, &1_000);
+    token_client.mint(&bob, &1_000);
+
+    let pooled = client.create_crowdfunded(
+        &organizer,
+        &recipient,
+        &token_contract_id,
+        &900,
+        &600,
+        &3600,
+        &1800,
+    );
+    assert_eq!(pooled.status, EscrowStatus::Funding);
+    assert_eq!(token_client.balance(&organizer), 10_000);
+
+    assert_eq!(client.contribute_to_escrow(&alice, &pooled.id, &300), 300);
+    // Only what the escrow still needs is taken
+    assert_eq!(client.contribute_to_escrow(&bob, &pooled.id, &1_000), 600);
+    assert_eq!(token_client.balance(&bob), 400);
+    assert_eq!(client.get_contribution(&pooled.id, &bob), 600);
+    assert_eq!(client.get_escrow(&pooled.id).status, EscrowStatus::Active);
+
+    // A refund after activation goes back to the contributors pro-rata
+    client.refund(&pooled.id);
+    assert_eq!(token_client.balance(&organizer), 10_000);
+    assert_eq!(client.claim_contribution_refund(&alice, &pooled.id), 300);
+    assert_eq!(client.claim_contribution_refund(&bob, &pooled.id), 600);
+    assert_eq!(token_client.balance(&bob), 1_000);
+
+    let missed = client.create_crowdfunded(
+        &organizer,
+        &recipient,
+        &token_contract_id,
+        &900,
+        &600,
+        &3600,
+        &1800,
+    );
+    client.contribute_to_escrow(&alice, &missed.id, &200);
+    assert!(client
+        .try_claim_contribution_refund(&alice, &missed.id)
+        .is_err());
+
+    advance_ledger(&env, 600);
+    assert!(client
+        .try_contribute_to_escrow(&bob, &missed.id, &700)
+        .is_err());
+    assert_eq!(client.claim_contribution_refund(&alice, &missed.id), 200);
+    assert_eq!(token_client.balance(&alice), 1_000);
+    assert_eq!(
+        client.get_escrow(&missed.id).status,
+        EscrowStatus::FundingFailed
+    );
+    assert!(client
+        .try_claim_contribution_refund(&alice, &missed.id)
+        .is_err());
+}
+
+#[test]
+fn test_deposit_settles_uncontested_deductions_and_disputes_the_rest() {
+    let (env, escrow_contract_id, token_contract_id, tenant, landlord) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let keeper = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_dispute_fee(&admin, &50);
+
+    let deposit =
+        client.create_deposit(&tenant, &landlord, &token_contract_id, &1_000, &3600, &1800);
+    assert_eq!(
+        client.claim_deduction(&deposit.id, &200, &symbol_short!("cleaning")),
+        0
+    );
+    assert_eq!(
+        client.claim_deduction(&deposit.id, &300, &symbol_short!("damage")),
+        1
+    );
+    assert!(client
+        .try_claim_deduction(&deposit.id, &600, &symbol_short!("rent"))
+        .is_err());
+    // Contesting opens the deposit's dispute, which pays the dispute fee
+    client.contest_deduction(&deposit.id, &1);
+    assert_eq!(token_client.balance(&tenant), 8_950);
+    assert!(client.try_contest_deduction(&deposit.id, &1).is_err());
+    assert_eq!(
+        client.get_deductions(&deposit.id).get(1).unwrap().status,
+        DeductionStatus::Contested
+    );
+
+    // The deposit cannot be released, taken back or disputed whole
+    assert!(client.try_release(&deposit.id).is_err());
+    assert!(client.try_refund(&deposit.id).is_err());
+    assert!(client
+        .try_initiate_dispute(&deposit.id, &symbol_short!("unfair"))
+        .is_err());
+    assert!(client.try_settle_deposit(&keeper, &deposit.id).is_err());
+
+    advance_ledger(&env, 3600);
+    assert!(client.try_check_timeout(&keeper, &deposit.id).is_err());
+    assert!(client
+        .try_claim_deduction(&deposit.id, &100, &symbol_short!("late"))
+        .is_err());
+    let settled = client.settle_deposit(&keeper, &deposit.id);
+    assert_eq!(settled.status, EscrowStatus::Disputed);
+    assert_eq!(settled.amount, 300);
+    assert_eq!(token_client.balance(&landlord), 200);
+    assert_eq!(token_client.balance(&tenant), 9_450);
+
+    // Only the contested item is left, and only arbitration resolves it
+    advance_ledger(&env, 1800);
+    assert!(client
+        .try_check_dispute_timeout(&keeper, &deposit.id)
+        .is_err());
+    client.admin_resolve_dispute(&admin, &deposit.id, &true);
+    assert_eq!(token_client.balance(&landlord), 500);
+    assert_eq!(token_client.balance(&escrow_contract_id), 50);
+
+    // Without contests the split settles outright
+    let deposit =
+        client.create_deposit(&tenant, &landlord, &token_contract_id, &1_000, &3600, &1800);
+    client.claim_deduction(&deposit.id, &100, &symbol_short!("keys"));
+    advance_ledger(&env, 3600);
+    let settled = client.settle_deposit(&keeper, &deposit.id);
+    assert_eq!(settled.status, EscrowStatus::DepositSettled);
+    assert_eq!(token_client.balance(&landlord), 600);
+    assert_eq!(token_client.balance(&tenant), 9_350);
+}
+
+#[test]
+fn test_contested_deduction_cannot_be_self_resolved() {
+    let (env, escrow_contract_id, token_contract_id, tenant, landlord) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let keeper = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let deposit =
+        client.create_deposit(&tenant, &landlord, &token_contract_id, &1_000, &3600, &1800);
+    client.claim_deduction(&deposit.id, &400, &symbol_short!("damage"));
+    client.contest_deduction(&deposit.id, &0);
+    advance_ledger(&env, 3600);
+    client.settle_deposit(&keeper, &deposit.id);
+    assert_eq!(token_client.balance(&tenant), 9_600);
+
+    // Neither party can settle the contested amount in their own favour
+    assert!(client.try_resolve_dispute_for_sender(&deposit.id).is_err());
+    assert!(client
+        .try_resolve_dispute_for_recipient(&deposit.id)
+        .is_err());
+    assert_eq!(token_client.balance(&tenant), 9_600);
+    assert_eq!(token_client.balance(&escrow_contract_id), 400);
+
+    client.admin_resolve_dispute(&admin, &deposit.id, &false);
+    assert_eq!(token_client.balance(&tenant), 10_000);
+    assert_eq!(
+        client.get_escrow(&deposit.id).status,
+        EscrowStatus::DisputeResolvedForSender
+    );
+}
+
 #[test]
 fn test_release_escrow_success() {
     let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
@@ -81,6 +381,36 @@ fn test_release_escrow_success() {
     assert_eq!(token_client.balance(&escrow_contract_id), 0);
 }
 
+#[test]
+fn test_simulate_release_previews_without_paying() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let escrow_info = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+
+    let preview = client.simulate_release(&escrow_info.id);
+    assert_eq!(
+        preview,
+        ReleasePreview {
+            escrow_id: escrow_info.id.clone(),
+            payee: recipient.clone(),
+            token: token_contract_id.clone(),
+            amount: 500,
+            status: EscrowStatus::Released,
+        }
+    );
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(
+        client.get_escrow(&escrow_info.id).status,
+        EscrowStatus::Active
+    );
+
+    let released = client.release(&escrow_info.id);
+    assert_eq!(released.status, preview.status);
+    assert_eq!(token_client.balance(&preview.payee), preview.amount);
+    assert!(client.try_simulate_release(&escrow_info.id).is_err());
+}
+
 #[test]
 fn test_initiate_dispute_by_sender() {
     let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
@@ -259,15 +589,15 @@ fn test_admin_functions() {
     assert!(!client.is_paused());
 
     // Set dispute fee
-    client.set_dispute_fee(&100);
+    client.set_dispute_fee(&admin, &100);
     assert_eq!(client.get_dispute_fee(), 100);
 
     // Pause contract
-    client.set_paused(&true);
+    client.set_paused(&admin, &true);
     assert!(client.is_paused());
 
     // Unpause
-    client.set_paused(&false);
+    client.set_paused(&admin, &false);
     assert!(!client.is_paused());
 }
 
@@ -280,7 +610,7 @@ fn test_paused_contract_validation() {
 
     // Initialize and pause contract
     client.initialize(&admin);
-    client.set_paused(&true);
+    client.set_paused(&admin, &true);
 
     // Try to create escrow while paused - should fail
     client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
@@ -324,3 +654,889 @@ fn test_query_functions() {
     let disputed_escrows = client.get_escrows_by_status(&EscrowStatus::Disputed);
     assert_eq!(disputed_escrows.len(), 1);
 }
+
+#[test]
+fn test_create_escrow_for_phone_alias() {
+    let (env, escrow_contract_id, token_contract_id, sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_escrow_alias_registry(&admin, &env.register(EmailToWalletContract, ()));
+
+    // With no registration the recipient is the address derived from the phone number
+    let phone = String::from_str(&env, "+2348012345678");
+    let alias = Alias {
+        kind: AliasKind::Phone,
+        value: phone.clone(),
+    };
+    let escrow_info =
+        client.create_for_alias(&sender, &alias, &token_contract_id, &500, &3600, &1800);
+
+    assert_eq!(
+        escrow_info.recipient,
+        derive_alias_address(&env, AliasKind::Phone, &phone).unwrap()
+    );
+    assert_eq!(escrow_info.amount, 500);
+}
+
+fn last_escrow_event(env: &Env) -> DeFiEvent {
+    let (_, _, data) = env.events().all().last().unwrap();
+    EventEnvelope::try_from_val(env, &data).unwrap().event
+}
+
+#[test]
+fn test_dispute_events_emitted() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+
+    let escrow_info = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&escrow_info.id, &symbol_short!("FRAUD"));
+    match last_escrow_event(&env) {
+        DeFiEvent::EscrowDisputeInitiated(data) => {
+            assert_eq!(data.escrow_id, escrow_info.id);
+            assert_eq!(data.initiated_by, sender);
+            assert_eq!(data.reason, symbol_short!("FRAUD"));
+        }
+        _ => panic!("expected EscrowDisputeInitiated"),
+    }
+
+    client.resolve_dispute_for_recipient(&escrow_info.id);
+    match last_escrow_event(&env) {
+        DeFiEvent::EscrowDisputeResolved(data) => {
+            assert_eq!(data.resolved_for, recipient);
+            assert!(data.resolved_for_recipient);
+        }
+        _ => panic!("expected EscrowDisputeResolved"),
+    }
+}
+
+#[test]
+fn test_admin_action_events_emitted() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.set_dispute_fee(&admin, &250);
+    match last_escrow_event(&env) {
+        DeFiEvent::EscrowDisputeFeeUpdated(data) => {
+            assert_eq!(data.admin, admin);
+            assert_eq!(data.old_fee, 0);
+            assert_eq!(data.new_fee, 250);
+        }
+        _ => panic!("expected EscrowDisputeFeeUpdated"),
+    }
+
+    client.set_paused(&admin, &true);
+    assert!(matches!(
+        last_escrow_event(&env),
+        DeFiEvent::EscrowPaused(_)
+    ));
+    client.set_paused(&admin, &false);
+    assert!(matches!(
+        last_escrow_event(&env),
+        DeFiEvent::EscrowUnpaused(_)
+    ));
+
+    let new_admin = Address::generate(&env);
+    client.transfer_admin(&new_admin);
+    match last_escrow_event(&env) {
+        DeFiEvent::EscrowAdminTransferred(data) => {
+            assert_eq!(data.previous_admin, admin);
+            assert_eq!(data.new_admin, new_admin);
+        }
+        _ => panic!("expected EscrowAdminTransferred"),
+    }
+}
+
+#[test]
+fn test_recent_events_journal() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Journal is off by default
+    client.set_dispute_fee(&admin, &1);
+    assert_eq!(client.get_recent_events(&0, &10).len(), 0);
+
+    client.set_event_journal_capacity(&admin, &3);
+    for fee in 2..=6 {
+        client.set_dispute_fee(&admin, &fee);
+    }
+
+    // Only the last three events are kept, newest first
+    let recent = client.get_recent_events(&0, &10);
+    assert_eq!(recent.len(), 3);
+    let fees: [i128; 3] = core::array::from_fn(|i| match recent.get(i as u32).unwrap().event {
+        DeFiEvent::EscrowDisputeFeeUpdated(data) => data.new_fee,
+        _ => panic!("expected EscrowDisputeFeeUpdated"),
+    });
+    assert_eq!(fees, [6, 5, 4]);
+
+    let page = client.get_recent_events(&1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(
+        page.get(0).unwrap().sequence,
+        recent.get(1).unwrap().sequence
+    );
+}
+
+#[test]
+fn test_failure_reported_as_contract_error_event() {
+    let (env, escrow_contract_id, token_contract_id, sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+
+    assert!(client
+        .try_create(&sender, &sender, &token_contract_id, &500, &3600, &1800)
+        .is_err());
+
+    // The event is rolled back with the call but kept among the failed-call events
+    let errors: std::vec::Vec<_> = env
+        .host()
+        .get_events()
+        .unwrap()
+        .0
+        .into_iter()
+        .filter(|e| e.failed_call)
+        .map(|e| {
+            let ContractEventBody::V0(body) = e.event.body;
+            body
+        })
+        .filter(|body| body.topics.first() == Some(&ScVal::Symbol("SYSTEM".try_into().unwrap())))
+        .collect();
+    assert_eq!(errors.len(), 1);
+
+    let data = Val::try_from_val(&env, &errors[0].data).unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::ContractError(data) => {
+            assert_eq!(data.contract_address, escrow_contract_id);
+            assert_eq!(data.error_type, Symbol::new(&env, "InvalidAddress"));
+        }
+        _ => panic!("expected ContractError"),
+    }
+}
+
+#[test]
+fn test_pauser_role_can_pause() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.grant_escrow_role(&admin, &Role::Pauser, &pauser);
+    match last_escrow_event(&env) {
+        DeFiEvent::RoleGranted(data) => {
+            assert_eq!(data.role, Role::Pauser);
+            assert_eq!(data.account, pauser);
+            assert_eq!(data.changed_by, admin);
+        }
+        _ => panic!("expected RoleGranted"),
+    }
+
+    client.set_paused(&pauser, &true);
+    assert!(client.is_paused());
+
+    // Pausing does not carry any other admin rights
+    assert!(client.try_set_dispute_fee(&pauser, &100).is_err());
+
+    client.revoke_escrow_role(&admin, &Role::Pauser, &pauser);
+    assert!(!client.has_escrow_role(&Role::Pauser, &pauser));
+    assert!(client.try_set_paused(&pauser, &false).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Missing required role")]
+fn test_renounced_role_cannot_be_used() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.grant_escrow_role(&admin, &Role::Operator, &operator);
+    client.set_dispute_fee(&operator, &100);
+    assert_eq!(client.get_dispute_fee(), 100);
+
+    client.renounce_escrow_role(&operator, &Role::Operator);
+    client.set_dispute_fee(&operator, &200);
+}
+
+#[test]
+#[should_panic(expected = "Missing required role")]
+fn test_upgrade_requires_owner() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    client.initialize(&Address::generate(&env));
+
+    client.upgrade_escrow(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[0; 32]),
+    );
+}
+
+#[test]
+fn test_migrate_legacy_escrow_storage() {
+    let (env, escrow_contract_id, _token_contract_id, _sender, _recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Already current, nothing to do
+    assert_eq!(client.get_escrow_storage_version(), 2);
+    assert_eq!(client.migrate_escrow(), 2);
+
+    // Simulate an instance deployed before roles and storage versioning
+    env.as_contract(&escrow_contract_id, || {
+        env.storage()
+            .instance()
+            .remove(&AccessDataKey::Role(Role::Owner, admin.clone()));
+        env.storage()
+            .instance()
+            .remove(&UpgradeDataKey::StorageVersion);
+    });
+    assert_eq!(client.get_escrow_storage_version(), 0);
+    assert!(!client.has_escrow_role(&Role::Owner, &admin));
+
+    assert_eq!(client.migrate_escrow(), 2);
+    match last_escrow_event(&env) {
+        DeFiEvent::StorageMigrated(data) => {
+            assert_eq!(data.from_version, 0);
+            assert_eq!(data.to_version, 2);
+        }
+        _ => panic!("expected StorageMigrated"),
+    }
+    assert!(client.has_escrow_role(&Role::Owner, &admin));
+}
+
+#[test]
+fn test_migrate_moves_instance_escrows_to_persistent_storage() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    client.initialize(&Address::generate(&env));
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &1000, &3600, &1800);
+    client.initiate_dispute(&escrow.id, &symbol_short!("late"));
+
+    // Simulate records written by storage version 1, which kept them in instance storage
+    let dispute_key = (escrow.id.clone(), symbol_short!("DISPUTE"));
+    env.as_contract(&escrow_contract_id, || {
+        let record: Val = env.storage().persistent().get(&escrow.id).unwrap();
+        let dispute: Val = env.storage().persistent().get(&dispute_key).unwrap();
+        env.storage().persistent().remove(&escrow.id);
+        env.storage().persistent().remove(&dispute_key);
+        env.storage().instance().set(&escrow.id, &record);
+        env.storage().instance().set(&dispute_key, &dispute);
+        env.storage()
+            .instance()
+            .set(&UpgradeDataKey::StorageVersion, &1u32);
+    });
+
+    assert_eq!(client.migrate_escrow(), 2);
+    env.as_contract(&escrow_contract_id, || {
+        assert!(!env.storage().instance().has(&escrow.id));
+        assert!(!env.storage().instance().has(&dispute_key));
+        assert!(env.storage().persistent().has(&escrow.id));
+    });
+    assert_eq!(client.get_escrow(&escrow.id).status, EscrowStatus::Disputed);
+    assert!(client.get_dispute_info(&escrow.id).is_some());
+}
+
+#[test]
+fn test_reputation_policy_gates_escrows_and_dispute_fees() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_dispute_fee(&admin, &100);
+
+    let reputation = ReputationContractClient::new(&env, &env.register(ReputationContract, ()));
+    reputation.initialize_reputation(&admin);
+    reputation.grant_reputation_role(&admin, &Role::Operator, &escrow_contract_id);
+    let mut policy = ReputationPolicy {
+        registry: reputation.address.clone(),
+        min_score: 450,
+        low_score_threshold: 500,
+        low_score_dispute_fee_bps: 20000,
+    };
+    client.set_reputation_policy(&admin, &Some(policy.clone()));
+
+    // A neutral score pays the base dispute fee
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&escrow.id, &symbol_short!("late"));
+    assert_eq!(token_client.balance(&sender), 10_000 - 500 - 100);
+    client.admin_resolve_dispute(&admin, &escrow.id, &true);
+    assert_eq!(reputation.get_reputation(&sender).defaulted, 1);
+    assert_eq!(reputation.get_reputation(&recipient).disputed, 1);
+
+    // Losing the dispute drops the sender below the floor
+    let score = reputation.get_reputation_score(&sender);
+    assert!(score < 450);
+    assert!(client
+        .try_create(&sender, &recipient, &token_contract_id, &500, &3600, &1800)
+        .is_err());
+
+    // Above a lower floor, but disputes now cost double
+    policy.min_score = 400;
+    client.set_reputation_policy(&admin, &Some(policy));
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&escrow.id, &symbol_short!("late"));
+    assert_eq!(
+        token_client.balance(&sender),
+        10_000 - 500 - 100 - 500 - 200
+    );
+
+    client.set_reputation_policy(&admin, &None);
+    assert_eq!(client.get_reputation_policy(), None);
+}
+
+#[test]
+fn test_compliance_policy_requires_kyc_for_large_escrows() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let registry = ComplianceContractClient::new(&env, &env.register(ComplianceContract, ()));
+    registry.initialize_compliance(&admin);
+    registry.grant_compliance_role(&admin, &Role::Attestor, &admin);
+    registry.attest_kyc(&admin, &sender, &1, &3600);
+    client.set_escrow_compliance_policy(
+        &admin,
+        &Some(CompliancePolicy {
+            registry: registry.address.clone(),
+            min_tier: 1,
+            threshold: 1_000,
+        }),
+    );
+
+    client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    // The recipient is not verified
+    assert!(client
+        .try_create(
+            &sender,
+            &recipient,
+            &token_contract_id,
+            &1_000,
+            &3600,
+            &1800
+        )
+        .is_err());
+
+    registry.attest_kyc(&admin, &recipient, &1, &3600);
+    client.create(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &1_000,
+        &3600,
+        &1800,
+    );
+    assert_eq!(client.get_escrow_count(), 2);
+}
+
+#[test]
+fn test_blocked_party_cannot_create_or_release_escrow() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let blocklist = BlocklistContractClient::new(&env, &env.register(BlocklistContract, ()));
+    blocklist.initialize_blocklist(&admin);
+    client.set_escrow_blocklist(&admin, &Some(blocklist.address.clone()));
+
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    blocklist.block_address(&admin, &recipient, &symbol_short!("ofac"));
+    assert!(client.try_release(&escrow.id).is_err());
+    assert!(client
+        .try_create(&sender, &recipient, &token_contract_id, &500, &3600, &1800)
+        .is_err());
+
+    // Funds can still go back to a clean sender
+    client.refund(&escrow.id);
+    assert_eq!(client.get_escrow(&escrow.id).status, EscrowStatus::Refunded);
+}
+
+#[test]
+fn test_frozen_recipient_escrow_redirected_to_custody() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let admin = Address::generate(&env);
+    let custody = Address::generate(&env);
+    client.initialize(&admin);
+
+    let blocklist = BlocklistContractClient::new(&env, &env.register(BlocklistContract, ()));
+    blocklist.initialize_blocklist(&admin);
+    client.set_escrow_blocklist(&admin, &Some(blocklist.address.clone()));
+
+    let frozen = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    let clean = client.create(
+        &sender,
+        &Address::generate(&env),
+        &token_contract_id,
+        &300,
+        &3600,
+        &1800,
+    );
+    blocklist.block_address(&admin, &recipient, &symbol_short!("freeze"));
+
+    // Neither a timeout nor a dispute ruling pays a frozen recipient
+    advance_ledger(&env, 3600);
+    assert!(client.try_check_timeout(&admin, &frozen.id).is_err());
+    assert_eq!(client.get_escrow(&frozen.id).status, EscrowStatus::Active);
+
+    assert!(client.try_redirect_to_custody(&admin, &frozen.id).is_err());
+    assert!(client
+        .try_set_escrow_custody(&Address::generate(&env), &Some(custody.clone()))
+        .is_err());
+    client.set_escrow_custody(&admin, &Some(custody.clone()));
+    assert_eq!(client.get_escrow_custody(), Some(custody.clone()));
+
+    assert!(client
+        .try_redirect_to_custody(&Address::generate(&env), &frozen.id)
+        .is_err());
+    assert!(client.try_redirect_to_custody(&admin, &clean.id).is_err());
+
+    let info = client.redirect_to_custody(&admin, &frozen.id);
+    assert_eq!(info.status, EscrowStatus::RedirectedToCustody);
+    assert_eq!(token_client.balance(&custody), 500);
+    assert_eq!(token_client.balance(&recipient), 0);
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::RedirectedToCustody(redirect)) => {
+            assert_eq!(redirect.frozen_payee, recipient);
+            assert_eq!(redirect.custody, custody);
+            assert_eq!(redirect.amount, 500);
+        }
+        _ => panic!("expected RedirectedToCustody"),
+    }
+    assert!(client.try_redirect_to_custody(&admin, &frozen.id).is_err());
+
+    // An unfrozen escrow still settles normally
+    client.check_timeout(&admin, &clean.id);
+    assert_eq!(
+        client.get_escrow(&clean.id).status,
+        EscrowStatus::AutoReleased
+    );
+}
+
+#[test]
+fn test_guardian_breaker_puts_escrow_in_safe_mode() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let guardian = GuardianContractClient::new(&env, &env.register(GuardianContract, ()));
+    guardian.initialize_guardian(&admin);
+    client.set_escrow_guardian(&admin, &Some(guardian.address.clone()));
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+
+    guardian.trip_breaker(&admin, &Subsystem::Escrow, &symbol_short!("exploit"));
+    assert!(client.try_release(&escrow.id).is_err());
+    assert!(client.try_refund(&escrow.id).is_err());
+    assert!(client
+        .try_create(&sender, &recipient, &token_contract_id, &500, &3600, &1800)
+        .is_err());
+    assert_eq!(client.get_escrow(&escrow.id).status, EscrowStatus::Active);
+
+    guardian.reset_breaker(&admin, &Subsystem::Escrow);
+    client.release(&escrow.id);
+    assert_eq!(client.get_escrow(&escrow.id).status, EscrowStatus::Released);
+}
+
+#[test]
+fn test_dispute_initiation_rate_limited() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_dispute_rate_limit(
+        &admin,
+        &Some(RateLimit {
+            capacity: 1,
+            refill_interval: 86_400,
+        }),
+    );
+
+    let first = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    let second = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&first.id, &symbol_short!("late"));
+    assert!(client
+        .try_initiate_dispute(&second.id, &symbol_short!("late"))
+        .is_err());
+    assert!(client.can_dispute(&second.id));
+
+    advance_ledger(&env, 86_400);
+    client.initiate_dispute(&second.id, &symbol_short!("late"));
+    assert_eq!(client.get_escrow(&second.id).status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_beneficiary_takes_over_open_escrows_of_inactive_owner() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let heir = Address::generate(&env);
+    let inactivity = 30 * 24 * 60 * 60;
+
+    let open = client.create(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &(3 * inactivity),
+        &1800,
+    );
+    let closed = client.create(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &(3 * inactivity),
+        &1800,
+    );
+    client.release(&closed.id);
+    client.set_escrow_beneficiary(&sender, &Some(heir.clone()), &inactivity);
+
+    advance_ledger(&env, inactivity);
+    client.announce_escrow_inheritance(&heir, &sender);
+    advance_ledger(&env, CLAIM_NOTICE_PERIOD);
+    let claimed = client.claim_escrow_inheritance(&heir, &sender);
+    assert_eq!(claimed, soroban_sdk::vec![&env, open.id.clone()]);
+    assert_eq!(client.get_escrow(&open.id).sender, heir);
+    assert_eq!(client.get_escrow(&closed.id).sender, sender);
+
+    // The beneficiary now controls the escrow and receives refunds
+    client.refund(&open.id);
+    let token = MockTokenClient::new(&env, &token_contract_id);
+    assert_eq!(token.balance(&heir), 500);
+}
+
+#[test]
+fn test_approved_operator_releases_until_expiry() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let operator = Address::generate(&env);
+
+    let first = client.create(&sender, &recipient, &token_contract_id, &500, &7200, &1800);
+    let second = client.create(&sender, &recipient, &token_contract_id, &300, &7200, &1800);
+    assert!(client
+        .try_release_as_operator(&operator, &first.id)
+        .is_err());
+
+    client.approve_release_operator(&first.id, &operator, &3600);
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::ReleaseDelegated(data)) => {
+            assert_eq!(data.escrow_id, first.id);
+            assert_eq!(data.sender, sender);
+            assert_eq!(data.operator, operator);
+            assert_eq!(data.expires_at, 3600);
+        }
+        _ => panic!("expected ReleaseDelegated"),
+    }
+
+    // The approval covers one escrow and one operator only
+    assert!(client
+        .try_release_as_operator(&operator, &second.id)
+        .is_err());
+    assert!(client
+        .try_release_as_operator(&Address::generate(&env), &first.id)
+        .is_err());
+
+    let released = client.release_as_operator(&operator, &first.id);
+    assert_eq!(released.status, EscrowStatus::Released);
+    assert_eq!(token_client.balance(&recipient), 500);
+    match last_escrow_event(&env) {
+        DeFiEvent::EscrowReleased(data) => assert_eq!(data.released_by, operator),
+        _ => panic!("expected EscrowReleased"),
+    }
+    // Settling the escrow consumes the approval
+    assert_eq!(client.get_release_operator(&first.id), None);
+
+    client.approve_release_operator(&second.id, &operator, &3600);
+    advance_ledger(&env, 3601);
+    assert!(client
+        .try_release_as_operator(&operator, &second.id)
+        .is_err());
+    assert!(client
+        .try_approve_release_operator(&second.id, &operator, &3601)
+        .is_err());
+}
+
+#[test]
+fn test_sender_revokes_release_operator() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let operator = Address::generate(&env);
+
+    let escrow_info = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    assert!(client.try_revoke_release_operator(&escrow_info.id).is_err());
+
+    client.approve_release_operator(&escrow_info.id, &operator, &1800);
+    assert_eq!(
+        client.get_release_operator(&escrow_info.id),
+        Some(ReleaseDelegation {
+            operator: operator.clone(),
+            expires_at: 1800,
+        })
+    );
+
+    client.revoke_release_operator(&escrow_info.id);
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::ReleaseDelegationRevoked(data)) => {
+            assert_eq!(data.operator, operator);
+        }
+        _ => panic!("expected ReleaseDelegationRevoked"),
+    }
+    assert!(client
+        .try_release_as_operator(&operator, &escrow_info.id)
+        .is_err());
+
+    // The sender can still release directly
+    client.release(&escrow_info.id);
+}
+
+#[test]
+fn test_keeper_tipped_for_closing_timeouts() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let admin = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    client.initialize(&admin);
+
+    // 1% capped at 3
+    let tip = KeeperTip {
+        tip_bps: 100,
+        max_tip: 3,
+    };
+    assert!(client
+        .try_set_escrow_keeper_tip(&Address::generate(&env), &Some(tip.clone()))
+        .is_err());
+    assert!(client
+        .try_set_escrow_keeper_tip(
+            &admin,
+            &Some(KeeperTip {
+                tip_bps: 101,
+                max_tip: 3
+            })
+        )
+        .is_err());
+    client.set_escrow_keeper_tip(&admin, &Some(tip.clone()));
+    assert_eq!(client.get_escrow_keeper_tip(), Some(tip));
+
+    let timed_out = client.create(&sender, &recipient, &token_contract_id, &200, &3600, &1800);
+    let disputed = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&disputed.id, &symbol_short!("LATE"));
+    advance_ledger(&env, 3600);
+
+    let info = client.check_timeout(&keeper, &timed_out.id);
+    assert_eq!(info.status, EscrowStatus::AutoReleased);
+    assert_eq!(token_client.balance(&recipient), 198);
+    assert_eq!(token_client.balance(&keeper), 2);
+
+    let info = client.check_dispute_timeout(&keeper, &disputed.id);
+    assert_eq!(info.status, EscrowStatus::DisputeResolvedForRecipient);
+    assert_eq!(token_client.balance(&recipient), 198 + 497);
+    assert_eq!(token_client.balance(&keeper), 2 + 3);
+
+    // Settlements the parties perform themselves carry no tip
+    let released = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.release(&released.id);
+    assert_eq!(token_client.balance(&recipient), 198 + 497 + 500);
+    assert_eq!(token_client.balance(&keeper), 5);
+}
+
+#[test]
+fn test_dispute_notes_anchor_mediation_log() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let admin = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    client.initialize(&admin);
+    client.grant_escrow_role(&admin, &Role::Operator, &arbitrator);
+
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    let first = BytesN::from_array(&env, &[1; 32]);
+    // Nothing to mediate until a dispute is opened
+    assert!(client
+        .try_add_dispute_note(&sender, &escrow.id, &first)
+        .is_err());
+
+    client.initiate_dispute(&escrow.id, &symbol_short!("LATE"));
+    assert_eq!(client.add_dispute_note(&sender, &escrow.id, &first), 0);
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::DisputeNoteAdded(data)) => {
+            assert_eq!(data.author, sender);
+            assert_eq!(data.hash, first);
+            assert_eq!(data.index, 0);
+        }
+        _ => panic!("expected EscrowEvent::DisputeNoteAdded"),
+    }
+    advance_ledger(&env, 60);
+    let second = BytesN::from_array(&env, &[2; 32]);
+    let third = BytesN::from_array(&env, &[3; 32]);
+    assert_eq!(client.add_dispute_note(&recipient, &escrow.id, &second), 1);
+    assert_eq!(client.add_dispute_note(&arbitrator, &escrow.id, &third), 2);
+    assert!(client
+        .try_add_dispute_note(&Address::generate(&env), &escrow.id, &third)
+        .is_err());
+
+    let notes = client.get_dispute_notes(&escrow.id);
+    assert_eq!(notes.len(), 3);
+    assert_eq!(
+        notes.get_unchecked(1),
+        DisputeNote {
+            author: recipient.clone(),
+            hash: second,
+            added_at: 60,
+        }
+    );
+    assert_eq!(notes.get_unchecked(2).author, arbitrator);
+
+    // The log is closed once the dispute is resolved, but stays readable
+    client.admin_resolve_dispute(&arbitrator, &escrow.id, &true);
+    assert!(client
+        .try_add_dispute_note(&sender, &escrow.id, &first)
+        .is_err());
+    assert_eq!(client.get_dispute_notes(&escrow.id).len(), 3);
+}
+
+#[test]
+fn test_promo_code_discounts_dispute_fee() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_dispute_fee(&admin, &100);
+
+    let code = Bytes::from_slice(&env, b"LAUNCH50");
+    let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+    assert!(client
+        .try_issue_escrow_promo_code(&sender, &code_hash, &5_000, &1, &3600)
+        .is_err());
+    client.issue_escrow_promo_code(&admin, &code_hash, &5_000, &1, &3600);
+
+    let escrow = client.create_with_promo(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &code,
+    );
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::PromoRedeemed(data)) => {
+            assert_eq!(data.escrow_id, escrow.id);
+            assert_eq!(data.code_hash, code_hash);
+            assert_eq!(data.sender, sender);
+            assert_eq!(data.discount_bps, 5_000);
+        }
+        _ => panic!("expected EscrowEvent::PromoRedeemed"),
+    }
+    assert_eq!(client.get_escrow_promo_code(&code_hash).unwrap().uses, 1);
+
+    // The single use is spent
+    assert!(client
+        .try_create_with_promo(
+            &sender,
+            &recipient,
+            &token_contract_id,
+            &500,
+            &3600,
+            &1800,
+            &code,
+        )
+        .is_err());
+
+    client.initiate_dispute(&escrow.id, &symbol_short!("LATE"));
+    assert_eq!(token_client.balance(&sender), 10_000 - 500 - 50);
+}
+
+#[test]
+fn test_payment_reference_carried_on_escrow_events() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let reference = BytesN::from_array(&env, &[5; 32]);
+    let last_reference = || {
+        let (_, _, data) = env.events().all().last().unwrap();
+        EventEnvelope::try_from_val(&env, &data).unwrap().reference
+    };
+
+    let escrow = client.create_with_reference(
+        &sender,
+        &recipient,
+        &token_contract_id,
+        &500,
+        &3600,
+        &1800,
+        &reference,
+    );
+    assert_eq!(last_reference(), reference);
+
+    // Calls made without one carry the zero reference
+    let other = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    assert_eq!(last_reference(), BytesN::from_array(&env, &[0; 32]));
+    client.release(&other.id);
+    assert_eq!(last_reference(), BytesN::from_array(&env, &[0; 32]));
+
+    let payout = BytesN::from_array(&env, &[6; 32]);
+    client.release_with_reference(&escrow.id, &payout);
+    assert_eq!(last_reference(), payout);
+}
+
+#[test]
+fn test_expired_dispute_escalates_to_arbitration_panel() {
+    let (env, escrow_contract_id, token_contract_id, sender, recipient) = setup_test_env();
+    let client = EscrowClient::new(&env, &escrow_contract_id);
+    let token_client = MockTokenClient::new(&env, &token_contract_id);
+    let admin = Address::generate(&env);
+    let panel = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_arbitration_panel(&admin, &Some(panel.clone()));
+    assert_eq!(client.get_arbitration_panel(), Some(panel.clone()));
+
+    let escrow = client.create(&sender, &recipient, &token_contract_id, &500, &3600, &1800);
+    client.initiate_dispute(&escrow.id, &symbol_short!("LATE"));
+    advance_ledger(&env, 1800);
+
+    // No silent default to the recipient once a panel is configured
+    assert!(client
+        .try_resolve_dispute_for_recipient(&escrow.id)
+        .is_err());
+    assert!(client.try_resolve_dispute_for_sender(&escrow.id).is_err());
+
+    let info = client.check_dispute_timeout(&keeper, &escrow.id);
+    assert_eq!(info.status, EscrowStatus::Disputed);
+    match last_escrow_event(&env) {
+        DeFiEvent::Escrow(EscrowEvent::DisputeEscalated(data)) => {
+            assert_eq!(data.escrow_id, escrow.id);
+            assert_eq!(data.panel, panel);
+            assert_eq!(data.escalated_by, keeper);
+            assert_eq!(data.escalated_at, 1800);
+        }
+        _ => panic!("expected EscrowEvent::DisputeEscalated"),
+    }
+    assert_eq!(
+        client.get_dispute_escalation(&escrow.id).unwrap().panel,
+        panel
+    );
+    assert!(client
+        .try_check_dispute_timeout(&keeper, &escrow.id)
+        .is_err());
+
+    // Only the panel the dispute went to can settle it
+    assert!(client
+        .try_resolve_escalated_dispute(&admin, &escrow.id, &false)
+        .is_err());
+    let resolved = client.resolve_escalated_dispute(&panel, &escrow.id, &false);
+    assert_eq!(resolved.status, EscrowStatus::DisputeResolvedForSender);
+    assert_eq!(token_client.balance(&sender), 10_000);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
@@ -0,0 +1,47 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{Address, BytesN, Env, TryFromVal};
+use stellar_multisig_contract::email_to_wallet::{
+    EmailToWalletContract, EmailToWalletContractClient,
+};
+use stellar_multisig_contract::event::{
+    DeFiEvent, EmailEvent, EventEnvelope, EVENT_SCHEMA_VERSION,
+};
+
+fn last_envelope(env: &Env) -> EventEnvelope {
+    let (_, _, data) = env.events().all().last().unwrap();
+    EventEnvelope::try_from_val(env, &data).unwrap()
+}
+
+#[test]
+fn test_event_envelope_sequence_per_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry_id = env.register(EmailToWalletContract, ());
+    let registry = EmailToWalletContractClient::new(&env, &registry_id);
+    registry.initialize_email_registry(&Address::generate(&env), &Address::generate(&env));
+    let other_id = env.register(EmailToWalletContract, ());
+    let other = EmailToWalletContractClient::new(&env, &other_id);
+
+    let wallet = Address::generate(&env);
+    registry.register_email(&BytesN::from_array(&env, &[1; 32]), &wallet);
+    let first = last_envelope(&env);
+    assert_eq!(first.version, EVENT_SCHEMA_VERSION);
+    assert_eq!(first.contract, registry_id);
+    assert_eq!(first.sequence, 1);
+    assert!(matches!(
+        first.event,
+        DeFiEvent::Email(EmailEvent::Registered(_))
+    ));
+
+    registry.register_email(&BytesN::from_array(&env, &[2; 32]), &wallet);
+    assert_eq!(last_envelope(&env).sequence, 2);
+
+    // Each contract numbers its own events
+    other.register_email(&BytesN::from_array(&env, &[1; 32]), &wallet);
+    let other_envelope = last_envelope(&env);
+    assert_eq!(other_envelope.contract, other_id);
+    assert_eq!(other_envelope.sequence, 1);
+}
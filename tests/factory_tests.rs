@@ -0,0 +1,66 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, BytesN, Env};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::factory::{
+    EscrowFactoryContract, EscrowFactoryContractClient, EscrowInitParams,
+};
+
+fn setup_factory(env: &Env) -> (EscrowFactoryContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let factory_id = env.register(EscrowFactoryContract, ());
+    let factory = EscrowFactoryContractClient::new(env, &factory_id);
+    factory.initialize_factory(&admin, &BytesN::from_array(env, &[1; 32]));
+
+    (factory, admin)
+}
+
+#[test]
+fn test_escrow_wasm_hash_updated_by_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin) = setup_factory(&env);
+
+    let new_hash = BytesN::from_array(&env, &[2; 32]);
+    let operator = Address::generate(&env);
+    factory.grant_factory_role(&admin, &Role::Operator, &operator);
+    assert!(factory
+        .try_set_escrow_wasm_hash(&operator, &new_hash)
+        .is_err());
+
+    factory.set_escrow_wasm_hash(&admin, &new_hash);
+    assert_eq!(factory.get_factory_config().escrow_wasm_hash, new_hash);
+    assert_eq!(factory.get_deployment_count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Missing required role")]
+fn test_deploy_escrow_requires_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _admin) = setup_factory(&env);
+
+    let client = Address::generate(&env);
+    factory.deploy_escrow(
+        &client,
+        &client,
+        &EscrowInitParams {
+            admin: client.clone(),
+            dispute_fee: 0,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Factory already initialized")]
+fn test_factory_cannot_be_reinitialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _admin) = setup_factory(&env);
+
+    factory.initialize_factory(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[3; 32]),
+    );
+}
@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{symbol_short, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::{
+    ConversionContract, ConversionContractClient, Currency,
+};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, GuardianEvent};
+use stellar_multisig_contract::guardian::{GuardianContract, GuardianContractClient, Subsystem};
+use stellar_multisig_contract::pool_manager::{PoolManagerContract, PoolManagerContractClient};
+use stellar_multisig_contract::testutils::fund_pools;
+
+fn setup_guardian(env: &Env) -> (GuardianContractClient<'_>, Address, Address) {
+    let admin = Address::generate(env);
+    let monitor = Address::generate(env);
+    let client = GuardianContractClient::new(env, &env.register(GuardianContract, ()));
+    client.initialize_guardian(&admin);
+    client.grant_guardian_role(&admin, &Role::Pauser, &monitor);
+    (client, admin, monitor)
+}
+
+#[test]
+fn test_monitor_trips_and_owner_resets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, monitor) = setup_guardian(&env);
+
+    assert!(client
+        .try_trip_breaker(
+            &Address::generate(&env),
+            &Subsystem::Swap,
+            &symbol_short!("depeg")
+        )
+        .is_err());
+    client.trip_breaker(&monitor, &Subsystem::Swap, &symbol_short!("depeg"));
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Guardian(GuardianEvent::Tripped(data)) => {
+            assert_eq!(data.subsystem, Subsystem::Swap);
+            assert_eq!(data.monitor, monitor);
+        }
+        _ => panic!("expected GuardianEvent::Tripped"),
+    }
+    assert!(client.is_tripped(&Subsystem::Swap));
+    assert!(!client.is_tripped(&Subsystem::Escrow));
+    assert_eq!(
+        client.get_breaker(&Subsystem::Swap).unwrap().reason,
+        symbol_short!("depeg")
+    );
+
+    // Monitors can halt but only governance can resume
+    assert!(client
+        .try_reset_breaker(&monitor, &Subsystem::Swap)
+        .is_err());
+    client.reset_breaker(&admin, &Subsystem::Swap);
+    assert!(!client.is_tripped(&Subsystem::Swap));
+    assert!(client.try_reset_breaker(&admin, &Subsystem::Swap).is_err());
+}
+
+#[test]
+fn test_tripped_breaker_halts_only_its_subsystem() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (guardian, admin, monitor) = setup_guardian(&env);
+    let user = Address::generate(&env);
+
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(&admin, &0, &admin, &1, &1_000_000_000);
+    conversion.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &3600,
+    );
+    conversion.set_conversion_guardian(&admin, &Some(guardian.address.clone()));
+    conversion.deposit(&user, &Currency::USD, &5_000);
+
+    let pool = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+    pool.initialize_pool_manager(&admin, &1_000, &1_000_000, &0, &50);
+    fund_pools(&env, &pool, &admin, &[&user], 20_000);
+    pool.set_pool_guardian(&admin, &Some(guardian.address.clone()));
+
+    guardian.trip_breaker(&monitor, &Subsystem::Conversion, &symbol_short!("oracle"));
+    assert!(conversion
+        .try_convert_currency(&user, &Currency::USD, &Currency::NGN, &500)
+        .is_err());
+    assert!(conversion.try_deposit(&user, &Currency::USD, &500).is_err());
+    // Reads still work in safe mode
+    assert_eq!(
+        conversion
+            .get_user_balance(&user)
+            .balances
+            .get(Currency::USD),
+        Some(5_000)
+    );
+    pool.add_liquidity(&user, &Currency::NGN, &10_000, &None);
+
+    guardian.trip_breaker(&monitor, &Subsystem::Pools, &symbol_short!("drain"));
+    assert!(pool
+        .try_add_liquidity(&user, &Currency::NGN, &10_000, &None)
+        .is_err());
+
+    guardian.reset_breaker(&admin, &Subsystem::Conversion);
+    conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &500);
+}
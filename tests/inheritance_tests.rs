@@ -0,0 +1,112 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{Address, Env, TryFromVal};
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, InheritanceEvent};
+use stellar_multisig_contract::inheritance::CLAIM_NOTICE_PERIOD;
+use stellar_multisig_contract::testutils::{self, advance_ledger};
+
+const INACTIVITY: u64 = 180 * 24 * 60 * 60;
+
+fn setup_conversion(env: &Env) -> (ConversionContractClient<'_>, Address) {
+    let (client, admin) = testutils::setup_conversion(env);
+    client.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &(2 * INACTIVITY),
+    );
+    (client, admin)
+}
+
+#[test]
+fn test_beneficiary_inherits_balances_after_inactivity_and_notice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_conversion(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+
+    client.deposit(&owner, &Currency::USD, &5_000);
+    client.deposit(&heir, &Currency::USD, &100);
+    client.set_conversion_beneficiary(&owner, &Some(heir.clone()), &INACTIVITY);
+    assert_eq!(
+        client
+            .get_conversion_inheritance(&owner)
+            .unwrap()
+            .beneficiary,
+        heir
+    );
+
+    // Nothing can be announced while the owner is active, or by anyone else
+    assert!(client
+        .try_announce_conversion_inheritance(&heir, &owner)
+        .is_err());
+    advance_ledger(&env, INACTIVITY);
+    assert!(client
+        .try_announce_conversion_inheritance(&Address::generate(&env), &owner)
+        .is_err());
+
+    client.announce_conversion_inheritance(&heir, &owner);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Inheritance(InheritanceEvent::Announced(data)) => {
+            assert_eq!(data.owner, owner);
+            assert_eq!(data.claimable_at, INACTIVITY + CLAIM_NOTICE_PERIOD);
+        }
+        _ => panic!("expected InheritanceEvent::Announced"),
+    }
+    assert!(client
+        .try_claim_conversion_inheritance(&heir, &owner)
+        .is_err());
+
+    advance_ledger(&env, CLAIM_NOTICE_PERIOD);
+    let balance = client.claim_conversion_inheritance(&heir, &owner);
+    assert_eq!(balance.balances.get(Currency::USD), Some(5_100));
+    assert_eq!(
+        client.get_user_balance(&owner).balances.get(Currency::USD),
+        None
+    );
+    assert_eq!(client.get_conversion_inheritance(&owner), None);
+    assert!(client
+        .try_claim_conversion_inheritance(&heir, &owner)
+        .is_err());
+}
+
+#[test]
+fn test_owner_activity_cancels_announced_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_conversion(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+
+    client.deposit(&owner, &Currency::USD, &5_000);
+    client.set_conversion_beneficiary(&owner, &Some(heir.clone()), &INACTIVITY);
+    advance_ledger(&env, INACTIVITY);
+    client.announce_conversion_inheritance(&heir, &owner);
+
+    // Any owner interaction during the notice period restarts the clock
+    advance_ledger(&env, 60);
+    client.convert_currency(&owner, &Currency::USD, &Currency::NGN, &1_000);
+    let plan = client.get_conversion_inheritance(&owner).unwrap();
+    assert_eq!(plan.announced_at, 0);
+    assert_eq!(plan.last_active, INACTIVITY + 60);
+
+    advance_ledger(&env, CLAIM_NOTICE_PERIOD);
+    assert!(client
+        .try_claim_conversion_inheritance(&heir, &owner)
+        .is_err());
+    assert!(client
+        .try_announce_conversion_inheritance(&heir, &owner)
+        .is_err());
+
+    // Opting out removes the plan altogether
+    client.set_conversion_beneficiary(&owner, &None, &0);
+    assert_eq!(client.get_conversion_inheritance(&owner), None);
+    assert!(client
+        .try_set_conversion_beneficiary(&owner, &Some(owner.clone()), &INACTIVITY)
+        .is_err());
+}
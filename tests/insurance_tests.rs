@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, BytesN, Env, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, InsuranceEvent};
+use stellar_multisig_contract::insurance::{
+    ClaimStatus, InsuranceContract, InsuranceContractClient,
+};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+const EPOCH: u64 = 7 * 86_400;
+
+fn setup_insurance(
+    env: &Env,
+) -> (
+    InsuranceContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+) {
+    let admin = Address::generate(env);
+    let staker = Address::generate(env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &usdc.address()).mint(&staker, &10_000);
+
+    let client = InsuranceContractClient::new(env, &env.register(InsuranceContract, ()));
+    // At most 10% of the pool may be paid out per epoch
+    client.initialize_insurance(&admin, &usdc.address(), &EPOCH, &1000, &EPOCH);
+    client.stake_backstop(&staker, &10_000);
+
+    (
+        client,
+        admin,
+        staker,
+        token::Client::new(env, &usdc.address()),
+    )
+}
+
+#[test]
+fn test_fees_accrue_to_backstop_stakers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, staker, usdc) = setup_insurance(&env);
+    let collector = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc.address).mint(&collector, &2_000);
+
+    client.fund_insurance(&collector, &1_000);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Insurance(InsuranceEvent::Funded(data)) => assert_eq!(data.amount, 1_000),
+        _ => panic!("expected InsuranceEvent::Funded"),
+    }
+    // Fees sent straight to the pool count as well
+    usdc.transfer(&collector, &client.address, &1_000);
+    assert_eq!(client.get_pool_value(), 12_000);
+
+    // A later staker buys in at the higher share price
+    let late = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc.address).mint(&late, &1_200);
+    assert_eq!(client.stake_backstop(&late, &1_200), 1_000);
+
+    client.request_unstake(&staker, &10_000);
+    assert!(client.try_withdraw_unstaked(&staker).is_err());
+    advance_ledger(&env, EPOCH);
+    assert_eq!(client.withdraw_unstaked(&staker), 12_000);
+    assert_eq!(usdc.balance(&staker), 12_000);
+    assert_eq!(client.get_total_shares(), 1_000);
+}
+
+#[test]
+fn test_approved_claims_capped_per_epoch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, staker, usdc) = setup_insurance(&env);
+    let victim = Address::generate(&env);
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+
+    let first = client.submit_claim(&victim, &600, &evidence);
+    let second = client.submit_claim(&victim, &600, &evidence);
+    assert!(client.try_approve_claim(&staker, &first).is_err());
+
+    client.approve_claim(&admin, &first);
+    assert_eq!(usdc.balance(&victim), 600);
+    assert_eq!(client.get_claim(&first).status, ClaimStatus::Paid);
+    assert!(client.try_approve_claim(&admin, &first).is_err());
+    // The cap is 1_000 for this epoch
+    assert!(client.try_approve_claim(&admin, &second).is_err());
+    assert_eq!(client.get_epoch_payouts(&0).unwrap().paid, 600);
+
+    advance_ledger(&env, EPOCH);
+    client.approve_claim(&admin, &second);
+    assert_eq!(usdc.balance(&victim), 1_200);
+    // Stakers absorb the loss
+    assert_eq!(client.get_pool_value(), 8_800);
+}
+
+#[test]
+fn test_rejected_claim_pays_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _staker, usdc) = setup_insurance(&env);
+    let claimant = Address::generate(&env);
+    let claim_id = client.submit_claim(&claimant, &500, &BytesN::from_array(&env, &[1; 32]));
+
+    client.reject_claim(&admin, &claim_id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Insurance(InsuranceEvent::ClaimRejected(data)) => {
+            assert_eq!(data.claim_id, claim_id)
+        }
+        _ => panic!("expected InsuranceEvent::ClaimRejected"),
+    }
+    assert_eq!(client.get_claim(&claim_id).status, ClaimStatus::Rejected);
+    assert!(client.try_approve_claim(&admin, &claim_id).is_err());
+    assert_eq!(usdc.balance(&claimant), 0);
+}
@@ -0,0 +1,113 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, BytesN, Env};
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowStatus};
+use stellar_multisig_contract::invoice::{InvoiceContract, InvoiceContractClient, InvoiceStatus};
+use stellar_multisig_contract::testutils::advance_ledger;
+use stellar_multisig_contract::EscrowContract;
+
+const DAY: u64 = 86_400;
+
+fn setup_invoice(
+    env: &Env,
+) -> (
+    InvoiceContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+    u64,
+) {
+    let merchant = Address::generate(env);
+    let payer = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &token.address()).mint(&payer, &10_000);
+
+    let client = InvoiceContractClient::new(env, &env.register(InvoiceContract, ()));
+    let invoice_id = client.issue_invoice(
+        &merchant,
+        &payer,
+        &token.address(),
+        &2_500,
+        &(env.ledger().timestamp() + 7 * DAY),
+        &BytesN::from_array(env, &[7; 32]),
+    );
+
+    (
+        client,
+        merchant,
+        payer,
+        token::Client::new(env, &token.address()),
+        invoice_id,
+    )
+}
+
+#[test]
+fn test_pay_invoice_directly() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, _payer, token, id) = setup_invoice(&env);
+
+    // Overdue invoices can still be paid
+    advance_ledger(&env, 8 * DAY);
+    assert_eq!(client.get_invoice(&id).status, InvoiceStatus::Overdue);
+
+    client.pay_invoice(&id);
+    let invoice = client.get_invoice(&id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.paid_at, Some(env.ledger().timestamp()));
+    assert_eq!(token.balance(&merchant), 2_500);
+    assert!(client.try_pay_invoice(&id).is_err());
+    assert!(client.try_cancel_invoice(&id).is_err());
+}
+
+#[test]
+fn test_pay_invoice_via_escrow_and_reopen_on_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, payer, token, id) = setup_invoice(&env);
+    let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+
+    let escrow_id = client.pay_invoice_via_escrow(&id, &escrow.address, &(3 * DAY), &DAY);
+    assert_eq!(client.get_invoice(&id).status, InvoiceStatus::Paid);
+    assert_eq!(token.balance(&escrow.address), 2_500);
+    let info = escrow.get_escrow(&escrow_id);
+    assert_eq!(info.sender, payer);
+    assert_eq!(info.recipient, merchant);
+
+    escrow.refund(&escrow_id);
+    assert_eq!(escrow.get_escrow(&escrow_id).status, EscrowStatus::Refunded);
+    assert_eq!(client.sync_invoice_escrow(&id), InvoiceStatus::Issued);
+    assert_eq!(client.get_invoice(&id).escrow_id, None);
+
+    client.pay_invoice(&id);
+    assert_eq!(token.balance(&merchant), 2_500);
+}
+
+#[test]
+fn test_merchant_invoices_paginated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, payer, token, first) = setup_invoice(&env);
+    for amount in [100, 200, 300] {
+        client.issue_invoice(
+            &merchant,
+            &payer,
+            &token.address,
+            &amount,
+            &(env.ledger().timestamp() + DAY),
+            &BytesN::from_array(&env, &[1; 32]),
+        );
+    }
+    client.cancel_invoice(&first);
+
+    let page = client.get_merchant_invoices(&merchant, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).amount, 100);
+    assert_eq!(page.get_unchecked(1).amount, 200);
+
+    let all = client.get_merchant_invoices(&merchant, &0, &100);
+    assert_eq!(all.len(), 4);
+    assert_eq!(all.get_unchecked(0).status, InvoiceStatus::Cancelled);
+    assert_eq!(client.get_invoice_count(), 4);
+}
@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::lending::{
+    LendingContract, LendingContractClient, MarketParams, HEALTH_FACTOR_ONE,
+};
+use stellar_multisig_contract::oracle::{OracleContract, OracleContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+const YEAR: u64 = 31_536_000;
+
+struct LendingSetup<'a> {
+    client: LendingContractClient<'a>,
+    oracle: OracleContractClient<'a>,
+    admin: Address,
+    lender: Address,
+    borrower: Address,
+    ngn: token::Client<'a>,
+}
+
+fn setup_lending(env: &Env) -> LendingSetup<'_> {
+    let admin = Address::generate(env);
+    let lender = Address::generate(env);
+    let borrower = Address::generate(env);
+
+    let oracle = OracleContractClient::new(env, &env.register(OracleContract, ()));
+    oracle.initialize_oracle(&admin, &1, &(10 * YEAR), &10_000);
+    oracle.add_price_feeder(&admin, &admin);
+    // 1 NGN = 0.000625 USD
+    oracle.submit_price(&admin, &Currency::NGN, &Currency::USD, &62_500);
+
+    let usd = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &usd.address()).mint(&borrower, &1_000);
+    token::StellarAssetClient::new(env, &ngn.address()).mint(&lender, &1_000_000);
+
+    let client = LendingContractClient::new(env, &env.register(LendingContract, ()));
+    client.initialize_lending(&admin, &oracle.address, &Currency::USD, &500);
+    client.list_market(
+        &admin,
+        &Currency::USD,
+        &MarketParams {
+            token: usd.address(),
+            collateral_factor_bps: 7500,
+            liquidation_threshold_bps: 8000,
+            interest_rate_bps: 500,
+        },
+    );
+    client.list_market(
+        &admin,
+        &Currency::NGN,
+        &MarketParams {
+            token: ngn.address(),
+            collateral_factor_bps: 5000,
+            liquidation_threshold_bps: 6000,
+            interest_rate_bps: 1000,
+        },
+    );
+
+    client.supply_asset(&lender, &Currency::NGN, &1_000_000);
+    client.supply_asset(&borrower, &Currency::USD, &1_000);
+
+    LendingSetup {
+        client,
+        oracle,
+        admin,
+        lender,
+        borrower,
+        ngn: token::Client::new(env, &ngn.address()),
+    }
+}
+
+#[test]
+fn test_borrow_accrues_interest_for_suppliers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_lending(&env);
+
+    // 1_000 USD at 75% LTV allows 750 USD, i.e. 1_200_000 NGN
+    s.client.borrow_asset(&s.borrower, &Currency::NGN, &800_000);
+    assert_eq!(s.ngn.balance(&s.borrower), 800_000);
+    assert!(s
+        .client
+        .try_borrow_asset(&s.borrower, &Currency::NGN, &500_000)
+        .is_err());
+    assert!(s
+        .client
+        .try_withdraw_asset(&s.borrower, &Currency::USD, &500)
+        .is_err());
+
+    advance_ledger(&env, YEAR);
+    assert_eq!(
+        s.client.get_debt_balance(&s.borrower, &Currency::NGN),
+        880_000
+    );
+    assert_eq!(
+        s.client.get_supplied_balance(&s.lender, &Currency::NGN),
+        1_080_000
+    );
+
+    token::StellarAssetClient::new(&env, &s.ngn.address).mint(&s.borrower, &80_000);
+    let repaid = s
+        .client
+        .repay_asset(&s.borrower, &s.borrower, &Currency::NGN, &1_000_000);
+    assert_eq!(repaid, 880_000);
+    assert_eq!(s.client.get_debt_balance(&s.borrower, &Currency::NGN), 0);
+
+    s.client
+        .withdraw_asset(&s.lender, &Currency::NGN, &1_080_000);
+    assert_eq!(s.ngn.balance(&s.lender), 1_080_000);
+}
+
+#[test]
+fn test_keeper_liquidates_unhealthy_position() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_lending(&env);
+    let keeper = Address::generate(&env);
+    s.client
+        .grant_lending_role(&s.admin, &Role::Operator, &keeper);
+    token::StellarAssetClient::new(&env, &s.ngn.address).mint(&keeper, &1_000_000);
+
+    s.client.borrow_asset(&s.borrower, &Currency::NGN, &800_000);
+    assert!(s
+        .client
+        .try_liquidate_position(
+            &keeper,
+            &s.borrower,
+            &Currency::NGN,
+            &Currency::USD,
+            &100_000
+        )
+        .is_err());
+
+    // NGN strengthens, so the 800_000 NGN debt is now worth 880 USD
+    s.oracle
+        .submit_price(&s.admin, &Currency::NGN, &Currency::USD, &110_000);
+    let health_before = s.client.get_health_factor(&s.borrower);
+    assert!(health_before < HEALTH_FACTOR_ONE);
+    assert!(s
+        .client
+        .try_liquidate_position(
+            &s.lender,
+            &s.borrower,
+            &Currency::NGN,
+            &Currency::USD,
+            &100_000
+        )
+        .is_err());
+
+    // Repayment is capped at half the debt; 440 USD plus a 5% bonus is seized
+    let seized = s.client.liquidate_position(
+        &keeper,
+        &s.borrower,
+        &Currency::NGN,
+        &Currency::USD,
+        &1_000_000,
+    );
+    assert_eq!(seized, 462);
+    assert_eq!(s.ngn.balance(&keeper), 600_000);
+    assert_eq!(
+        s.client.get_debt_balance(&s.borrower, &Currency::NGN),
+        400_000
+    );
+    assert_eq!(
+        s.client.get_supplied_balance(&s.borrower, &Currency::USD),
+        538
+    );
+    assert_eq!(s.client.get_supplied_balance(&keeper, &Currency::USD), 462);
+    assert!(s.client.get_health_factor(&s.borrower) > health_before);
+}
+
+#[test]
+fn test_list_market_requires_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_lending(&env);
+    let token = Address::generate(&env);
+    let params = MarketParams {
+        token,
+        collateral_factor_bps: 6000,
+        liquidation_threshold_bps: 7000,
+        interest_rate_bps: 300,
+    };
+
+    assert!(s
+        .client
+        .try_list_market(&s.lender, &Currency::EUR, &params)
+        .is_err());
+    let inverted = MarketParams {
+        collateral_factor_bps: 8000,
+        ..params.clone()
+    };
+    assert!(s
+        .client
+        .try_list_market(&s.admin, &Currency::EUR, &inverted)
+        .is_err());
+
+    s.client.list_market(&s.admin, &Currency::EUR, &params);
+    assert_eq!(s.client.get_markets().len(), 3);
+    assert_eq!(s.client.get_lending_config().quote_currency, Currency::USD);
+}
@@ -0,0 +1,137 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, LoyaltyEvent};
+use stellar_multisig_contract::loyalty::{
+    LoyaltyConfig, LoyaltyContract, LoyaltyContractClient, Redemption,
+};
+use stellar_multisig_contract::ConversionContract;
+
+struct Setup<'a> {
+    client: LoyaltyContractClient<'a>,
+    admin: Address,
+    reward: token::Client<'a>,
+}
+
+fn setup_loyalty(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let reward = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &reward.address()).mint(&admin, &10_000);
+
+    let client = LoyaltyContractClient::new(env, &env.register(LoyaltyContract, ()));
+    client.initialize_loyalty(
+        &admin,
+        &LoyaltyConfig {
+            reward_token: reward.address(),
+            // One point per 100 units of volume
+            points_per_volume: 100,
+            discount_per_point: 100,
+            reward_per_point: 5,
+        },
+    );
+    client.fund_loyalty_reward_pool(&admin, &10_000);
+    Setup {
+        client,
+        admin,
+        reward: token::Client::new(env, &reward.address()),
+    }
+}
+
+#[test]
+fn test_points_redeem_for_pool_rewards() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_loyalty(&env);
+    let venue = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    assert!(s
+        .client
+        .try_award_loyalty_points(&venue, &user, &50_000)
+        .is_err());
+    s.client.grant_loyalty_role(&s.admin, &Role::Minter, &venue);
+    assert_eq!(s.client.award_loyalty_points(&venue, &user, &50_000), 500);
+    assert_eq!(s.client.get_loyalty_points(&user), 500);
+
+    assert_eq!(s.client.redeem_points_for_reward(&user, &200), 1_000);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Loyalty(LoyaltyEvent::Redeemed(data)) => {
+            assert_eq!(data.points, 200);
+            assert_eq!(data.redemption, Redemption::Reward);
+            assert_eq!(data.value, 1_000);
+        }
+        _ => panic!("expected LoyaltyEvent::Redeemed"),
+    }
+    assert_eq!(s.reward.balance(&user), 1_000);
+    assert_eq!(s.client.get_loyalty_reward_pool(), 9_000);
+    assert_eq!(s.client.get_loyalty_points(&user), 300);
+    assert!(s.client.try_redeem_points_for_reward(&user, &301).is_err());
+}
+
+#[test]
+fn test_redemption_limited_by_reward_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_loyalty(&env);
+    let user = Address::generate(&env);
+    s.client
+        .grant_loyalty_role(&s.admin, &Role::Minter, &s.admin);
+    s.client.award_loyalty_points(&s.admin, &user, &100_000_000);
+
+    // 1_000_000 points would pay 5_000_000 but the pool holds 10_000
+    assert!(s
+        .client
+        .try_redeem_points_for_reward(&user, &1_000_000)
+        .is_err());
+    assert_eq!(s.client.get_loyalty_points(&user), 1_000_000);
+
+    // The reward token cannot be swapped out from under a funded pool
+    let mut config = s.client.get_loyalty_config();
+    config.reward_token = Address::generate(&env);
+    assert!(s.client.try_set_loyalty_config(&s.admin, &config).is_err());
+}
+
+#[test]
+fn test_conversions_earn_points_and_spend_fee_credit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_loyalty(&env);
+    let user = Address::generate(&env);
+
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(
+        &s.admin,
+        &50u32,
+        &Address::generate(&env),
+        &100i128,
+        &1_000_000_000i128,
+    );
+    // 1 USD = 800 NGN
+    conversion.update_rate(
+        &s.admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000i128,
+        &3600u64,
+    );
+    conversion.deposit(&user, &Currency::USD, &100_000i128);
+    conversion.set_conversion_loyalty(&s.admin, &Some(s.client.address.clone()));
+    s.client
+        .grant_loyalty_role(&s.admin, &Role::Minter, &conversion.address);
+
+    let tx = conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+    assert_eq!(tx.platform_fee, 40_000);
+    assert_eq!(s.client.get_loyalty_points(&user), 100);
+
+    // 100 points buy 10_000 of fee credit, spent on the next conversion
+    assert_eq!(s.client.redeem_points_for_discount(&user, &100), 10_000);
+    let tx = conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+    assert_eq!(tx.platform_fee, 30_000);
+    assert_eq!(tx.amount_received, 7_970_000);
+    assert_eq!(s.client.get_loyalty_fee_credit(&user), 0);
+    assert_eq!(s.client.get_loyalty_points(&user), 100);
+}
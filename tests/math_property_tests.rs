@@ -0,0 +1,313 @@
+#![cfg(test)]
+
+//! Property tests for the fee, rate and share math. Inputs come from a
+//! seeded generator so every failure reproduces; each property prints the
+//! case that broke it.
+
+use soroban_sdk::testutils::{Address as _, EnvTestConfig};
+use soroban_sdk::{symbol_short, token, Address, Env};
+use stellar_multisig_contract::bps::{Bps, MAX_BPS};
+use stellar_multisig_contract::conversion::ConversionContractClient;
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract};
+use stellar_multisig_contract::pool_manager::{PoolManagerContract, PoolManagerContractClient};
+use stellar_multisig_contract::testutils::{fund_pools, set_ledger_time};
+use stellar_multisig_contract::{
+    calculate_conversion_amount, calculate_platform_fee, checked_add, checked_mul_div, checked_sub,
+    compute_exchange_rate, AppError, ConversionContract,
+};
+
+const CASES: usize = 512;
+const RATE_PRECISION: i128 = 100_000_000;
+/// Largest amount the properties draw: a trillion units at 12 decimals
+const MAX_AMOUNT: i128 = 1_000_000_000_000_000_000_000_000;
+
+/// xorshift64* generator, enough to spread inputs across their ranges
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform-ish value in `lo..=hi`, biased toward small magnitudes so
+    /// both edges of the range get exercised
+    fn range(&mut self, lo: i128, hi: i128) -> i128 {
+        let span = (hi - lo) as u128 + 1;
+        let wide = ((self.next() as u128) << 64) | self.next() as u128;
+        let scale = match self.next() % 4 {
+            0 => span.min(1_000),
+            1 => span.min(1_000_000_000),
+            _ => span,
+        };
+        lo + (wide % scale) as i128
+    }
+}
+
+/// A test environment that skips the snapshot file, since the properties
+/// build one per case
+fn fresh_env() -> Env {
+    let env = Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    });
+    env.mock_all_auths();
+    env
+}
+
+#[test]
+fn prop_platform_fee_is_bounded_and_monotonic() {
+    let mut rng = Rng::new(0xfee);
+    for _ in 0..CASES {
+        let amount = rng.range(0, MAX_AMOUNT);
+        let bps = rng.range(0, 10_000) as u32;
+        let fee = calculate_platform_fee(amount, bps).unwrap();
+        let case = (amount, bps);
+
+        assert!(fee >= 0 && fee <= amount, "{case:?}: fee {fee}");
+        // Rounds down, never by a whole unit or more
+        let exact = amount * bps as i128;
+        assert!(
+            fee * 10_000 <= exact && exact < (fee + 1) * 10_000,
+            "{case:?}"
+        );
+
+        let more = amount + rng.range(0, 1_000_000);
+        assert!(
+            calculate_platform_fee(more, bps).unwrap() >= fee,
+            "{case:?}"
+        );
+        if bps < 10_000 {
+            assert!(
+                calculate_platform_fee(amount, bps + 1).unwrap() >= fee,
+                "{case:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn prop_exchange_rate_rejects_non_positive_and_is_monotonic() {
+    let mut rng = Rng::new(0x5a7e);
+    for _ in 0..CASES {
+        let offer = rng.range(1, MAX_AMOUNT);
+        let request = rng.range(1, MAX_AMOUNT);
+        let case = (offer, request);
+
+        let rate = compute_exchange_rate(offer, request).unwrap();
+        assert!(rate >= 0, "{case:?}");
+        assert!(
+            compute_exchange_rate(offer + 1, request).unwrap() >= rate,
+            "{case:?}"
+        );
+        assert!(
+            compute_exchange_rate(offer, request + 1).unwrap() <= rate,
+            "{case:?}"
+        );
+
+        let bad = -rng.range(0, MAX_AMOUNT);
+        assert_eq!(
+            compute_exchange_rate(bad, request),
+            Err(AppError::InvalidAmount)
+        );
+        assert_eq!(
+            compute_exchange_rate(offer, bad),
+            Err(AppError::InvalidAmount)
+        );
+    }
+}
+
+#[test]
+fn prop_conversion_round_trip_never_creates_value() {
+    let mut rng = Rng::new(0xc0de);
+    for _ in 0..CASES {
+        let amount = rng.range(0, MAX_AMOUNT);
+        // From a millionth to a million units out per unit in
+        let rate = rng.range(RATE_PRECISION / 1_000_000, RATE_PRECISION * 1_000_000);
+        let inverse = RATE_PRECISION * RATE_PRECISION / rate;
+        let case = (amount, rate);
+
+        let out = calculate_conversion_amount(amount, rate, RATE_PRECISION).unwrap();
+        assert!(out >= 0, "{case:?}");
+        let back = calculate_conversion_amount(out, inverse, RATE_PRECISION).unwrap();
+        assert!(back <= amount, "{case:?}: got back {back}");
+
+        let more = amount + rng.range(0, 1_000_000);
+        assert!(
+            calculate_conversion_amount(more, rate, RATE_PRECISION).unwrap() >= out,
+            "{case:?}"
+        );
+    }
+}
+
+#[test]
+fn prop_pool_shares_never_exceed_the_pool() {
+    let mut rng = Rng::new(0x9001);
+    for _ in 0..16 {
+        let env = fresh_env();
+        set_ledger_time(&env, 1_000);
+        let pools = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+        let owner = Address::generate(&env);
+        pools.initialize_pool_manager(&owner, &1_000_000_000, &100_000_000_000, &86_400, &50);
+
+        let providers = rng.range(1, 6) as usize;
+        let mut amounts = [0i128; 6];
+        let mut total = 0;
+        for amount in amounts.iter_mut().take(providers) {
+            *amount = rng.range(1_000_000_000, 100_000_000_000);
+            total += *amount;
+        }
+        let addresses: [Address; 6] = core::array::from_fn(|_| Address::generate(&env));
+        let holders: Vec<&Address> = addresses.iter().collect();
+        fund_pools(&env, &pools, &owner, &holders, 100_000_000_000);
+        for (provider, amount) in addresses.iter().zip(amounts).take(providers) {
+            pools.add_liquidity(provider, &Currency::USD, &amount, &None);
+        }
+
+        let mut shares = 0u32;
+        for (provider, amount) in addresses.iter().zip(amounts).take(providers) {
+            let position = pools.get_position(provider, &Currency::USD);
+            assert_eq!(position.liquidity_amount, amount);
+            assert_eq!(
+                position.pool_share_bps as i128,
+                amount * 10_000 / total,
+                "{amounts:?}"
+            );
+            shares += position.pool_share_bps;
+        }
+        // Each share rounds down by less than one basis point
+        assert!(
+            shares <= 10_000 && shares > 10_000 - providers as u32,
+            "{amounts:?}: {shares}"
+        );
+    }
+}
+
+#[test]
+fn prop_dispute_fee_conserves_value() {
+    let mut rng = Rng::new(0xd15);
+    for _ in 0..16 {
+        let env = fresh_env();
+        let (admin, sender, recipient) = (
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        );
+        let funds = rng.range(2, 1_000_000_000_000);
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        token::StellarAssetClient::new(&env, &sac.address()).mint(&sender, &funds);
+        let token = token::Client::new(&env, &sac.address());
+
+        let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+        escrow.initialize(&admin);
+        let amount = rng.range(1, funds - 1);
+        let fee = rng.range(0, funds - amount);
+        escrow.set_dispute_fee(&admin, &fee);
+        let case = (funds, amount, fee);
+
+        let info = escrow.create(&sender, &recipient, &token.address, &amount, &3_600, &1_800);
+        escrow.initiate_dispute(&info.id, &symbol_short!("late"));
+        assert_eq!(token.balance(&sender), funds - amount - fee, "{case:?}");
+        assert_eq!(token.balance(&escrow.address), amount + fee, "{case:?}");
+
+        escrow.admin_resolve_dispute(&admin, &info.id, &(rng.next() & 1 == 0));
+        let total =
+            token.balance(&sender) + token.balance(&recipient) + token.balance(&escrow.address);
+        assert_eq!(total, funds, "{case:?}");
+        // The escrowed amount leaves in full; only the fee stays behind
+        assert_eq!(token.balance(&escrow.address), fee, "{case:?}");
+    }
+}
+
+#[test]
+fn prop_bps_never_exceed_the_whole() {
+    let mut rng = Rng::new(0xb95);
+    for _ in 0..CASES {
+        let amount = rng.range(0, MAX_AMOUNT);
+        let (a, b) = (rng.range(0, 20_000) as u32, rng.range(0, 20_000) as u32);
+        let case = (amount, a, b);
+
+        let (Ok(first), Ok(second)) = (Bps::new(a), Bps::new(b)) else {
+            assert!(a > MAX_BPS || b > MAX_BPS, "{case:?}");
+            assert_eq!(Bps::capped(a.max(b), u32::MAX), Err(AppError::InvalidBps));
+            continue;
+        };
+        // A rate and what is left of it make up the amount, less rounding
+        let split = first.of(amount).unwrap() + first.complement().of(amount).unwrap();
+        assert!(split <= amount && split >= amount - 1, "{case:?}");
+        match first.checked_add(second) {
+            Ok(sum) => assert_eq!(sum.get(), a + b, "{case:?}"),
+            Err(error) => assert!(a + b > MAX_BPS && error == AppError::InvalidBps),
+        }
+    }
+    assert_eq!(Bps::capped(1001, 1000), Err(AppError::InvalidBps));
+    assert_eq!(Bps::FULL.of(i128::MAX / 10_000), Ok(i128::MAX / 10_000));
+}
+
+#[test]
+fn test_amount_math_reports_overflow_at_i128_extremes() {
+    assert_eq!(checked_add(i128::MAX, 1), Err(AppError::Overflow));
+    assert_eq!(checked_sub(i128::MIN, 1), Err(AppError::Overflow));
+    assert_eq!(checked_add(i128::MAX - 1, 1), Ok(i128::MAX));
+    assert_eq!(checked_mul_div(i128::MAX, 2, 2), Err(AppError::Overflow));
+    assert_eq!(checked_mul_div(i128::MAX, 1, 1), Ok(i128::MAX));
+    assert_eq!(checked_mul_div(1, 1, 0), Err(AppError::Overflow));
+
+    assert_eq!(
+        calculate_platform_fee(i128::MAX, 2),
+        Err(AppError::Overflow)
+    );
+    assert_eq!(
+        calculate_platform_fee(i128::MAX / 10_000, 10_000),
+        Ok(i128::MAX / 10_000)
+    );
+    assert_eq!(compute_exchange_rate(i128::MAX, 1), Err(AppError::Overflow));
+    assert_eq!(
+        calculate_conversion_amount(i128::MAX, RATE_PRECISION + 1, RATE_PRECISION),
+        Err(AppError::Overflow)
+    );
+}
+
+#[test]
+fn test_balances_and_pools_reject_overflowing_deposits() {
+    let env = fresh_env();
+    let user = Address::generate(&env);
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(
+        &Address::generate(&env),
+        &50u32,
+        &Address::generate(&env),
+        &1i128,
+        &i128::MAX,
+    );
+    conversion.deposit(&user, &Currency::USD, &i128::MAX);
+    assert!(conversion.try_deposit(&user, &Currency::USD, &1).is_err());
+    assert_eq!(
+        conversion
+            .get_user_balance(&user)
+            .balances
+            .get(Currency::USD),
+        Some(i128::MAX)
+    );
+
+    let pools = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+    let owner = Address::generate(&env);
+    pools.initialize_pool_manager(&owner, &1, &i128::MAX, &86_400, &50);
+    // Share math scales by 10_000, so a position that large cannot be priced
+    let provider = Address::generate(&env);
+    fund_pools(&env, &pools, &owner, &[&provider], i128::MAX);
+    assert!(pools
+        .try_add_liquidity(&provider, &Currency::USD, &i128::MAX, &None)
+        .is_err());
+    pools.add_liquidity(&provider, &Currency::USD, &(i128::MAX / 10_000), &None);
+    assert_eq!(
+        pools.get_position(&provider, &Currency::USD).pool_share_bps,
+        10_000
+    );
+}
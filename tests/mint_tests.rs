@@ -1,30 +1,85 @@
 use soroban_sdk::testutils::MockAuth;
 use soroban_sdk::testutils::MockAuthInvoke;
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, IntoVal};
-use stellar_multisig_contract::{mint::MintContract, mint::MintContractClient};
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, IntoVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::mint::{
+    MintContract, MintContractClient, MintLimits, MintRequestStatus,
+};
+use stellar_multisig_contract::rate_limit::RateLimit;
+use stellar_multisig_contract::testutils::{advance_ledger, set_ledger_time};
+
+fn setup_mint(env: &Env) -> (MintContractClient<'_>, Address, Address) {
+    let admin = Address::generate(env);
+    let mint_contract_id = env.register(MintContract, ());
+    let client = MintContractClient::new(env, &mint_contract_id);
+
+    // Stellar asset whose issuing admin is the mint contract
+    let token = env
+        .register_stellar_asset_contract_v2(mint_contract_id.clone())
+        .address();
+
+    (client, admin, token)
+}
 
 #[test]
 fn test_successful_minting_by_admin() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
     let user = Address::generate(&env);
-    let token = Address::generate(&env);
 
-    // Dummy token contract (mocked)
-    struct DummyToken;
-    impl DummyToken {
-        pub fn mint(env: &Env, to: &Address, amount: &i128) {
-            env.events()
-                .publish((symbol_short!("minted"), to.clone()), amount.clone());
-        }
-    }
+    client.init(&admin);
+    let mint_id = client.mint_token(&admin, &user, &1000, &token);
+
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 1000);
+    assert_eq!(client.get_mint_count(), 1);
 
-    let mint_contract_id = env.register_contract(None, MintContract);
+    let record = client.get_mint(&mint_id);
+    assert_eq!(record.recipient, user);
+    assert_eq!(record.amount, 1000);
+    assert_eq!(record.token, token);
+}
+
+#[test]
+fn test_idempotent_mint_is_issued_once() {
+    let env = Env::default();
     env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let user = Address::generate(&env);
+    client.init(&admin);
+
+    let key = BytesN::from_array(&env, &[3; 32]);
+    let mint_id = client.mint_token_idempotent(&admin, &user, &1000, &token, &key);
+    assert_eq!(
+        client.mint_token_idempotent(&admin, &user, &1000, &token, &key),
+        mint_id
+    );
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 1000);
+    assert_eq!(client.get_mint_count(), 1);
+
+    let other = BytesN::from_array(&env, &[4; 32]);
+    client.mint_token_idempotent(&admin, &user, &1000, &token, &other);
+    assert_eq!(client.get_mint_count(), 2);
+}
 
-    let client = MintContractClient::new(&env, &mint_contract_id);
+#[test]
+fn test_mint_with_payment_reference() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let user = Address::generate(&env);
     client.init(&admin);
-    client.mint_token(&user, &1000, &token);
+
+    let reference = BytesN::from_array(&env, &[9; 32]);
+    let mint_id = client.mint_token_with_reference(&admin, &user, &1000, &token, &reference);
+    assert_eq!(client.get_mint(&mint_id).reference, reference);
+
+    // The reference is not carried over to later mints
+    let plain = client.mint_token(&admin, &user, &1000, &token);
+    assert_eq!(
+        client.get_mint(&plain).reference,
+        BytesN::from_array(&env, &[0; 32])
+    );
 }
 
 #[test]
@@ -37,7 +92,7 @@ fn test_non_admin_cannot_mint() {
     let token = Address::generate(&env);
     let user = Address::generate(&env);
 
-    let contract_id = env.register_contract(None, MintContract);
+    let contract_id = env.register(MintContract, ());
     let client = MintContractClient::new(&env, &contract_id);
 
     env.mock_auths(&[MockAuth {
@@ -45,11 +100,346 @@ fn test_non_admin_cannot_mint() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "mint_token",
-            args: (&user, &1000i128, &token).into_val(&env),
+            args: (&admin, &user, &1000i128, &token).into_val(&env),
             sub_invokes: &[],
         },
     }]);
 
     client.init(&backend);
-    client.mint_token(&attacker, &500, &token);
+    client.mint_token(&attacker, &attacker, &500, &token);
+}
+
+#[test]
+#[should_panic(expected = "Token mint failed")]
+fn test_mint_fails_without_token_admin_rights() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _token) = setup_mint(&env);
+    let user = Address::generate(&env);
+
+    // Asset administered by someone other than the mint contract
+    let foreign_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    client.init(&admin);
+    client.mint_token(&admin, &user, &1000, &foreign_token);
+}
+
+#[test]
+fn test_mint_headroom_tracks_daily_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.init(&admin);
+    client.set_mint_limits(
+        &admin,
+        &MintLimits {
+            max_per_tx: 1_000,
+            max_per_recipient_per_day: 1_500,
+            max_global_per_day: 2_000,
+        },
+    );
+
+    client.mint_token(&admin, &alice, &1_000, &token);
+    let headroom = client.get_mint_headroom(&alice);
+    assert_eq!(headroom.per_tx, 1_000);
+    assert_eq!(headroom.recipient_daily, 500);
+    assert_eq!(headroom.global_daily, 1_000);
+
+    client.mint_token(&admin, &bob, &1_000, &token);
+    assert_eq!(client.get_mint_headroom(&bob).global_daily, 0);
+
+    // Caps reset on the next day
+    advance_ledger(&env, 86_400);
+    let headroom = client.get_mint_headroom(&alice);
+    assert_eq!(headroom.recipient_daily, 1_500);
+    assert_eq!(headroom.global_daily, 2_000);
+    client.mint_token(&admin, &alice, &1_000, &token);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds per-transaction mint cap")]
+fn test_mint_per_tx_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    client.set_mint_limits(
+        &admin,
+        &MintLimits {
+            max_per_tx: 1_000,
+            max_per_recipient_per_day: 0,
+            max_global_per_day: 0,
+        },
+    );
+    client.mint_token(&admin, &user, &1_001, &token);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds recipient daily mint cap")]
+fn test_mint_recipient_daily_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    client.set_mint_limits(
+        &admin,
+        &MintLimits {
+            max_per_tx: 0,
+            max_per_recipient_per_day: 1_500,
+            max_global_per_day: 0,
+        },
+    );
+    client.mint_token(&admin, &user, &1_000, &token);
+    client.mint_token(&admin, &user, &600, &token);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds global daily mint cap")]
+fn test_mint_global_daily_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+
+    client.init(&admin);
+    client.set_mint_limits(
+        &admin,
+        &MintLimits {
+            max_per_tx: 0,
+            max_per_recipient_per_day: 0,
+            max_global_per_day: 1_500,
+        },
+    );
+    client.mint_token(&admin, &Address::generate(&env), &1_000, &token);
+    client.mint_token(&admin, &Address::generate(&env), &600, &token);
+}
+
+#[test]
+fn test_two_step_mint_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let approver = Address::generate(&env);
+    let user = Address::generate(&env);
+    let reference = BytesN::from_array(&env, &[1u8; 32]);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &approver);
+
+    let request_id = client.request_mint(&admin, &user, &750, &token, &reference);
+    let request = client.get_mint_request(&request_id);
+    assert_eq!(request.status, MintRequestStatus::Pending);
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 0);
+
+    let approved = client.approve_mint(&request_id);
+    assert_eq!(approved.status, MintRequestStatus::Approved);
+    assert_eq!(approved.mint_id, 1);
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 750);
+}
+
+#[test]
+#[should_panic(expected = "Two-step minting required")]
+fn test_direct_mint_blocked_once_approver_set() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &Address::generate(&env));
+    client.mint_token(&admin, &Address::generate(&env), &100, &token);
+}
+
+#[test]
+#[should_panic(expected = "Mint request is not pending")]
+fn test_rejected_mint_cannot_be_approved() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let reference = BytesN::from_array(&env, &[2u8; 32]);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &Address::generate(&env));
+    let request_id =
+        client.request_mint(&admin, &Address::generate(&env), &100, &token, &reference);
+
+    let rejected = client.reject_mint(&request_id);
+    assert_eq!(rejected.status, MintRequestStatus::Rejected);
+    client.approve_mint(&request_id);
+}
+
+#[test]
+fn test_mint_request_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let reference = BytesN::from_array(&env, &[3u8; 32]);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &Address::generate(&env));
+    let request_id =
+        client.request_mint(&admin, &Address::generate(&env), &100, &token, &reference);
+
+    let request = client.get_mint_request(&request_id);
+    set_ledger_time(&env, request.expires_at + 1);
+
+    assert!(client.try_approve_mint(&request_id).is_err());
+    let expired = client.expire_mint_request(&request_id);
+    assert_eq!(expired.status, MintRequestStatus::Expired);
+}
+
+#[test]
+fn test_mint_history_pagination_and_reference_lookup() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let approver = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &approver);
+
+    for (i, recipient) in [&alice, &bob, &alice].iter().enumerate() {
+        let reference = BytesN::from_array(&env, &[i as u8 + 10; 32]);
+        let request_id = client.request_mint(
+            &admin,
+            recipient,
+            &(100 * (i as i128 + 1)),
+            &token,
+            &reference,
+        );
+        client.approve_mint(&request_id);
+    }
+
+    let page = client.get_mint_history(&0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().amount, 100);
+    assert_eq!(page.get(1).unwrap().recipient, bob);
+
+    let page = client.get_mint_history(&2, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().request_id, 3);
+
+    let alice_mints = client.get_recipient_mints(&alice, &0, &10);
+    assert_eq!(alice_mints.len(), 2);
+    assert_eq!(alice_mints.get(1).unwrap().amount, 300);
+
+    let record = client
+        .get_mint_by_reference(&BytesN::from_array(&env, &[11u8; 32]))
+        .unwrap();
+    assert_eq!(record.recipient, bob);
+    assert!(client
+        .get_mint_by_reference(&BytesN::from_array(&env, &[99u8; 32]))
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "Reference already minted")]
+fn test_mint_request_reference_reuse() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let user = Address::generate(&env);
+    let reference = BytesN::from_array(&env, &[4u8; 32]);
+
+    client.init(&admin);
+    client.set_mint_approver(&admin, &Address::generate(&env));
+    let request_id = client.request_mint(&admin, &user, &100, &token, &reference);
+    client.approve_mint(&request_id);
+    client.request_mint(&admin, &user, &100, &token, &reference);
+}
+
+#[test]
+fn test_minter_role_can_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    let minter = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    assert!(client
+        .try_mint_token(&minter, &user, &1000, &token)
+        .is_err());
+
+    client.grant_mint_role(&admin, &Role::Minter, &minter);
+    client.mint_token(&minter, &user, &1000, &token);
+    assert_eq!(token::Client::new(&env, &token).balance(&user), 1000);
+
+    // Minters cannot change issuance caps
+    assert!(client
+        .try_set_mint_limits(
+            &minter,
+            &MintLimits {
+                max_per_tx: 1,
+                max_per_recipient_per_day: 1,
+                max_global_per_day: 1,
+            }
+        )
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Approver must not be a minter")]
+fn test_minter_cannot_be_approver() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _token) = setup_mint(&env);
+    let minter = Address::generate(&env);
+
+    client.init(&admin);
+    client.grant_mint_role(&admin, &Role::Minter, &minter);
+    client.set_mint_approver(&admin, &minter);
+}
+
+#[test]
+fn test_mint_requests_rate_limited_per_minter() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_mint(&env);
+    client.init(&admin);
+    client.set_mint_approver(&admin, &Address::generate(&env));
+    client.set_mint_request_rate_limit(
+        &admin,
+        &Some(RateLimit {
+            capacity: 1,
+            refill_interval: 3600,
+        }),
+    );
+
+    let user = Address::generate(&env);
+    client.request_mint(
+        &admin,
+        &user,
+        &100,
+        &token,
+        &BytesN::from_array(&env, &[1u8; 32]),
+    );
+    assert!(client
+        .try_request_mint(
+            &admin,
+            &user,
+            &100,
+            &token,
+            &BytesN::from_array(&env, &[2u8; 32])
+        )
+        .is_err());
+
+    advance_ledger(&env, 3600);
+    client.request_mint(
+        &admin,
+        &user,
+        &100,
+        &token,
+        &BytesN::from_array(&env, &[2u8; 32]),
+    );
+    assert_eq!(client.get_mint_request(&2).amount, 100);
 }
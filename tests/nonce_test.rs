@@ -1,9 +1,10 @@
 #![cfg(test)]
 
+use soroban_sdk::testutils::storage::Persistent as _;
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{Address, Env};
-use stellar_multisig_contract::nonce::ContractError;
-use stellar_multisig_contract::nonce::NonceTracker;
+use soroban_sdk::{symbol_short, Address, Env};
+use stellar_multisig_contract::errors::AppError;
+use stellar_multisig_contract::nonce::{NonceTracker, NONCE_TTL};
 
 #[test]
 fn test_nonce_tracker() {
@@ -19,8 +20,21 @@ fn test_nonce_tracker() {
 
     env.as_contract(&contract_id, || {
         let err = NonceTracker::check_and_update_nonce(env.clone(), user.clone(), 1).unwrap_err();
-        assert_eq!(err, ContractError::InvalidNonce);
+        assert_eq!(err, AppError::InvalidNonce);
         NonceTracker::check_and_update_nonce(env.clone(), user.clone(), 2).unwrap();
         assert_eq!(NonceTracker::get_nonce(env.clone(), user.clone()), 2);
     })
 }
+
+#[test]
+fn test_nonce_record_kept_live_while_in_use() {
+    let env = Env::default();
+    let contract_id = env.register(NonceTracker, ());
+    let user = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        NonceTracker::check_and_update_nonce(env.clone(), user.clone(), 1).unwrap();
+        let key = (user.clone(), symbol_short!("NONCE"));
+        assert_eq!(env.storage().persistent().get_ttl(&key), NONCE_TTL);
+    });
+}
@@ -0,0 +1,122 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::errors::AppError;
+use stellar_multisig_contract::oracle::{OracleContract, OracleContractClient};
+use stellar_multisig_contract::rate_lock::{RateLockContract, RateLockContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
+use stellar_multisig_contract::ConversionContract;
+
+fn setup_oracle(env: &Env) -> (OracleContractClient<'_>, Address, [Address; 3]) {
+    let admin = Address::generate(env);
+    let oracle_id = env.register(OracleContract, ());
+    let oracle = OracleContractClient::new(env, &oracle_id);
+    // Two fresh submissions within 5% of the median, valid for 5 minutes
+    oracle.initialize_oracle(&admin, &2, &300, &500);
+
+    let feeders = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    for feeder in feeders.iter() {
+        oracle.add_price_feeder(&admin, feeder);
+    }
+
+    (oracle, admin, feeders)
+}
+
+#[test]
+fn test_oracle_median_discards_outliers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _admin, feeders) = setup_oracle(&env);
+
+    oracle.submit_price(&feeders[0], &Currency::USD, &Currency::NGN, &100_000_000);
+    oracle.submit_price(&feeders[1], &Currency::USD, &Currency::NGN, &102_000_000);
+    oracle.submit_price(&feeders[2], &Currency::USD, &Currency::NGN, &150_000_000);
+
+    let price = oracle.get_price(&Currency::USD, &Currency::NGN);
+    assert_eq!(price.price, 101_000_000);
+    assert_eq!(price.sources, 2);
+    assert_eq!(price.valid_until, price.timestamp + 300);
+}
+
+#[test]
+#[should_panic(expected = "Not enough price submissions")]
+fn test_oracle_ignores_stale_submissions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _admin, feeders) = setup_oracle(&env);
+
+    oracle.submit_price(&feeders[0], &Currency::USD, &Currency::NGN, &100_000_000);
+    advance_ledger(&env, 301);
+    oracle.submit_price(&feeders[1], &Currency::USD, &Currency::NGN, &100_000_000);
+
+    oracle.get_price(&Currency::USD, &Currency::NGN);
+}
+
+#[test]
+fn test_removed_feeder_no_longer_counts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, admin, feeders) = setup_oracle(&env);
+
+    oracle.submit_price(&feeders[0], &Currency::USD, &Currency::NGN, &100_000_000);
+    oracle.submit_price(&feeders[1], &Currency::USD, &Currency::NGN, &100_000_000);
+    assert!(oracle.try_get_price(&Currency::USD, &Currency::NGN).is_ok());
+
+    oracle.remove_price_feeder(&admin, &feeders[1]);
+    assert!(oracle
+        .try_get_price(&Currency::USD, &Currency::NGN)
+        .is_err());
+    assert!(oracle
+        .try_submit_price(&feeders[1], &Currency::USD, &Currency::NGN, &100_000_000)
+        .is_err());
+}
+
+#[test]
+fn test_conversion_uses_oracle_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _oracle_admin, feeders) = setup_oracle(&env);
+    oracle.submit_price(&feeders[0], &Currency::USD, &Currency::NGN, &80_000_000_000);
+    oracle.submit_price(&feeders[1], &Currency::USD, &Currency::NGN, &80_000_000_000);
+
+    let admin = Address::generate(&env);
+    let client = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(&admin, &0, &Address::generate(&env), &100, &1_000_000_000);
+    client.set_price_oracle(&admin, &Some(oracle.address.clone()));
+
+    // No admin rate was ever posted for the pair
+    let user = Address::generate(&env);
+    client.deposit(&user, &Currency::USD, &1_000);
+    let conversion = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &1_000);
+    assert_eq!(conversion.rate, 80_000_000_000);
+    assert_eq!(conversion.amount_received, 800_000);
+}
+
+#[test]
+fn test_rate_lock_requires_oracle_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _oracle_admin, feeders) = setup_oracle(&env);
+    oracle.submit_price(&feeders[0], &Currency::NGN, &Currency::USD, &125_000);
+    oracle.submit_price(&feeders[1], &Currency::NGN, &Currency::USD, &125_000);
+
+    let admin = Address::generate(&env);
+    let client = RateLockContractClient::new(&env, &env.register(RateLockContract, ()));
+    client.initialize_rate_lock(&admin, &admin, &Address::generate(&env), &0);
+    client.set_rate_oracle(&admin, &Some(oracle.address.clone()));
+
+    let user = Address::generate(&env);
+    let err = client
+        .try_lock_rate(&user, &Currency::NGN, &Currency::USD, &130_000, &1_000, &60)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::QuoteMismatch);
+
+    client.lock_rate(&user, &Currency::NGN, &Currency::USD, &125_000, &1_000, &60);
+}
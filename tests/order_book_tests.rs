@@ -0,0 +1,291 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, vec, Address, Env, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, OrderBookEvent};
+use stellar_multisig_contract::order_book::{
+    OrderBookContract, OrderBookContractClient, OrderSide,
+};
+use stellar_multisig_contract::session::TradingPair;
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+const DAY: u64 = 86_400;
+
+struct Setup<'a> {
+    client: OrderBookContractClient<'a>,
+    admin: Address,
+    fees: Address,
+    ngn: token::Client<'a>,
+    usdc: token::Client<'a>,
+}
+
+fn setup_book(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let fees = Address::generate(env);
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env));
+
+    let client = OrderBookContractClient::new(env, &env.register(OrderBookContract, ()));
+    // 0.1% maker, 0.3% taker
+    client.initialize_order_book(&admin, &ngn.address(), &usdc.address(), &10, &30, &fees);
+    Setup {
+        client,
+        admin,
+        fees,
+        ngn: token::Client::new(env, &ngn.address()),
+        usdc: token::Client::new(env, &usdc.address()),
+    }
+}
+
+fn seller(env: &Env, setup: &Setup) -> Address {
+    let seller = Address::generate(env);
+    token::StellarAssetClient::new(env, &setup.ngn.address).mint(&seller, &500_000_000);
+    seller
+}
+
+fn buyer(env: &Env, setup: &Setup) -> Address {
+    let buyer = Address::generate(env);
+    token::StellarAssetClient::new(env, &setup.usdc.address).mint(&buyer, &500_000);
+    buyer
+}
+
+#[test]
+fn test_matching_follows_price_time_priority() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let (first, second, cheapest) = (seller(&env, &s), seller(&env, &s), seller(&env, &s));
+    let taker = buyer(&env, &s);
+
+    // Prices are USDC per NGN scaled by 10^8: 62_500 is 0.000625
+    let first_ask = s
+        .client
+        .place_order(&first, &OrderSide::Sell, &62_500, &100_000_000);
+    let second_ask = s
+        .client
+        .place_order(&second, &OrderSide::Sell, &62_500, &100_000_000);
+    s.client
+        .place_order(&cheapest, &OrderSide::Sell, &60_000, &100_000_000);
+    s.client
+        .place_order(&taker, &OrderSide::Buy, &64_000, &200_000_000);
+    assert_eq!(s.usdc.balance(&taker), 372_000);
+
+    assert_eq!(s.client.match_orders(&10), 2);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::OrderBook(OrderBookEvent::Filled(data)) => {
+            assert_eq!(data.sell_order_id, first_ask);
+            assert_eq!(data.price, 62_500);
+        }
+        _ => panic!("expected OrderBookEvent::Filled"),
+    }
+
+    // Fills at the makers' prices, with the unused limit refunded
+    assert_eq!(s.usdc.balance(&taker), 500_000 - 60_000 - 62_500);
+    assert_eq!(s.ngn.balance(&taker), 2 * 99_700_000);
+    assert_eq!(s.usdc.balance(&cheapest), 59_940);
+    assert_eq!(s.usdc.balance(&first), 62_438);
+    assert_eq!(s.usdc.balance(&second), 0);
+    assert_eq!(s.ngn.balance(&s.fees), 600_000);
+    assert_eq!(s.usdc.balance(&s.fees), 122);
+
+    let asks = s.client.get_order_book_side(&OrderSide::Sell);
+    assert_eq!(asks.len(), 1);
+    assert_eq!(asks.get(0).unwrap().id, second_ask);
+    assert!(s.client.get_order_book_side(&OrderSide::Buy).is_empty());
+    assert_eq!(s.usdc.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_resting_bid_sets_price_for_later_ask() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let maker = buyer(&env, &s);
+    let taker = seller(&env, &s);
+
+    s.client
+        .place_order(&maker, &OrderSide::Buy, &64_000, &100_000_000);
+    s.client
+        .place_order(&taker, &OrderSide::Sell, &66_000, &100_000_000);
+    assert_eq!(s.client.match_orders(&10), 0);
+
+    s.client
+        .place_order(&taker, &OrderSide::Sell, &60_000, &50_000_000);
+    assert_eq!(s.client.match_orders(&10), 1);
+    // The seller crossed the spread and gets the bid price, less the taker fee
+    assert_eq!(s.usdc.balance(&taker), 32_000 - 96);
+    assert_eq!(s.ngn.balance(&maker), 50_000_000 - 50_000);
+
+    let bid = s
+        .client
+        .get_order_book_side(&OrderSide::Buy)
+        .get(0)
+        .unwrap();
+    assert_eq!(bid.remaining, 50_000_000);
+    assert_eq!(bid.locked_quote, 32_000);
+}
+
+#[test]
+fn test_cancel_releases_held_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let maker = seller(&env, &s);
+    let taker = buyer(&env, &s);
+
+    let ask = s
+        .client
+        .place_order(&maker, &OrderSide::Sell, &62_500, &100_000_000);
+    let bid = s
+        .client
+        .place_order(&taker, &OrderSide::Buy, &62_500, &40_000_000);
+    s.client
+        .place_order(&taker, &OrderSide::Buy, &62_500, &40_000_000);
+    assert_eq!(s.client.match_orders(&1), 1);
+    assert!(s.client.try_get_order(&bid).is_err());
+
+    s.client.cancel_order(&ask);
+    assert_eq!(s.ngn.balance(&maker), 460_000_000);
+    assert!(s.client.get_order_book_side(&OrderSide::Sell).is_empty());
+    assert_eq!(s.client.match_orders(&10), 0);
+
+    assert!(s
+        .client
+        .try_place_order(&taker, &OrderSide::Buy, &1, &1_000)
+        .is_err());
+}
+
+fn session_pair(s: &Setup) -> TradingPair {
+    TradingPair {
+        base: s.ngn.address.clone(),
+        quote: s.usdc.address.clone(),
+    }
+}
+
+#[test]
+fn test_session_bot_trades_master_inventory_within_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let master = seller(&env, &s);
+    let bot = Address::generate(&env);
+    s.ngn
+        .approve(&master, &s.client.address, &300_000_000, &1_000);
+
+    s.client.grant_trading_session(
+        &master,
+        &bot,
+        &vec![&env, session_pair(&s)],
+        &100_000,
+        &(2 * DAY),
+    );
+    let order = s
+        .client
+        .place_session_order(&bot, &OrderSide::Sell, &62_500, &100_000_000);
+    assert_eq!(s.client.get_order(&order).owner, master);
+    assert_eq!(s.ngn.balance(&master), 400_000_000);
+    assert_eq!(s.client.get_trading_session_usage(&bot), 62_500);
+
+    // A second order would take the day past 100_000 USDC notional
+    assert!(s
+        .client
+        .try_place_session_order(&bot, &OrderSide::Sell, &62_500, &100_000_000)
+        .is_err());
+    set_ledger_time(&env, DAY);
+    assert_eq!(s.client.get_trading_session_usage(&bot), 0);
+    s.client
+        .place_session_order(&bot, &OrderSide::Sell, &62_500, &100_000_000);
+
+    s.client.cancel_session_order(&bot, &order);
+    assert_eq!(s.ngn.balance(&master), 400_000_000);
+
+    set_ledger_time(&env, 2 * DAY);
+    assert!(s
+        .client
+        .try_place_session_order(&bot, &OrderSide::Sell, &62_500, &1_000_000)
+        .is_err());
+}
+
+#[test]
+fn test_revoked_or_foreign_session_cannot_trade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let master = seller(&env, &s);
+    let other = seller(&env, &s);
+    let bot = Address::generate(&env);
+    s.ngn
+        .approve(&master, &s.client.address, &300_000_000, &1_000);
+
+    // Sessions for another pair are not accepted here
+    let elsewhere = TradingPair {
+        base: s.ngn.address.clone(),
+        quote: Address::generate(&env),
+    };
+    s.client
+        .grant_trading_session(&master, &bot, &vec![&env, elsewhere], &100_000, &DAY);
+    assert!(s
+        .client
+        .try_place_session_order(&bot, &OrderSide::Sell, &62_500, &1_000_000)
+        .is_err());
+
+    s.client
+        .grant_trading_session(&master, &bot, &vec![&env, session_pair(&s)], &100_000, &DAY);
+    assert!(s
+        .client
+        .try_grant_trading_session(&other, &bot, &vec![&env, session_pair(&s)], &1, &DAY)
+        .is_err());
+    let foreign = s
+        .client
+        .place_order(&other, &OrderSide::Sell, &62_500, &1_000_000);
+    assert!(s.client.try_cancel_session_order(&bot, &foreign).is_err());
+
+    assert!(s.client.try_revoke_trading_session(&other, &bot).is_err());
+    s.client.revoke_trading_session(&master, &bot);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::OrderBook(OrderBookEvent::SessionRevoked(data)) => {
+            assert_eq!(data.master, master);
+            assert_eq!(data.bot, bot);
+        }
+        _ => panic!("expected OrderBookEvent::SessionRevoked"),
+    }
+    assert!(s
+        .client
+        .try_place_session_order(&bot, &OrderSide::Sell, &62_500, &1_000_000)
+        .is_err());
+}
+
+#[test]
+fn test_dust_remainders_are_closed_after_partial_fill() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_book(&env);
+    let maker = seller(&env, &s);
+    let taker = buyer(&env, &s);
+
+    assert!(s
+        .client
+        .try_set_order_book_dust(&maker, &10_000_000)
+        .is_err());
+    s.client.set_order_book_dust(&s.admin, &10_000_000);
+    assert_eq!(s.client.get_order_book_dust(), 10_000_000);
+    assert!(s
+        .client
+        .try_place_order(&taker, &OrderSide::Buy, &62_500, &5_000_000)
+        .is_err());
+
+    let ask = s
+        .client
+        .place_order(&maker, &OrderSide::Sell, &62_500, &100_000_000);
+    s.client
+        .place_order(&taker, &OrderSide::Buy, &62_500, &95_000_000);
+    assert_eq!(s.client.match_orders(&10), 1);
+
+    // The 5_000_000 left on the ask is below the threshold and handed back
+    assert!(s.client.try_get_order(&ask).is_err());
+    assert!(s.client.get_order_book_side(&OrderSide::Sell).is_empty());
+    assert_eq!(s.ngn.balance(&maker), 500_000_000 - 95_000_000);
+    assert_eq!(s.ngn.balance(&s.client.address), 0);
+}
@@ -0,0 +1,198 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, vec, Address, BytesN, Env, TryFromVal, Vec};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, OtcEvent};
+use stellar_multisig_contract::otc::{DealLeg, DealStatus, OtcContract, OtcContractClient};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+struct Setup<'a> {
+    client: OtcContractClient<'a>,
+    admin: Address,
+    bank_a: Address,
+    bank_b: Address,
+    ngn: token::Client<'a>,
+    usdc: token::Client<'a>,
+}
+
+fn setup_otc(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let client = OtcContractClient::new(env, &env.register(OtcContract, ()));
+    client.initialize_otc(&admin);
+
+    let bank_a = Address::generate(env);
+    let bank_b = Address::generate(env);
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &ngn.address()).mint(&bank_a, &10_000_000);
+    token::StellarAssetClient::new(env, &usdc.address()).mint(&bank_b, &10_000);
+    Setup {
+        client,
+        admin,
+        bank_a,
+        bank_b,
+        ngn: token::Client::new(env, &ngn.address()),
+        usdc: token::Client::new(env, &usdc.address()),
+    }
+}
+
+fn leg(payer: &Address, token: &token::Client, amount: i128) -> DealLeg {
+    DealLeg {
+        payer: payer.clone(),
+        token: token.address.clone(),
+        amount,
+        funded: false,
+    }
+}
+
+fn block_trade(env: &Env, setup: &Setup) -> Vec<DealLeg> {
+    vec![
+        env,
+        leg(&setup.bank_a, &setup.ngn, 8_000_000),
+        leg(&setup.bank_b, &setup.usdc, 5_000),
+    ]
+}
+
+#[test]
+fn test_deal_settles_all_legs_atomically() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_otc(&env);
+
+    let id = setup.client.propose_deal(
+        &setup.bank_a,
+        &setup.bank_b,
+        &block_trade(&env, &setup),
+        &None,
+        &3600,
+    );
+
+    setup.client.fund_deal_leg(&id, &0);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Otc(OtcEvent::LegFunded(data)) => {
+            assert_eq!(data.leg_index, 0);
+            assert_eq!(data.payer, setup.bank_a);
+        }
+        _ => panic!("expected OtcEvent::LegFunded"),
+    }
+    setup.client.approve_deal(&setup.bank_a, &id);
+    setup.client.approve_deal(&setup.bank_b, &id);
+    // The USDC leg is still unfunded
+    assert!(setup.client.try_settle_deal(&id).is_err());
+
+    setup.client.fund_deal_leg(&id, &1);
+    assert!(setup.client.try_fund_deal_leg(&id, &1).is_err());
+    setup.client.settle_deal(&id);
+
+    assert_eq!(setup.ngn.balance(&setup.bank_b), 8_000_000);
+    assert_eq!(setup.usdc.balance(&setup.bank_a), 5_000);
+    assert_eq!(setup.client.get_deal(&id).status, DealStatus::Settled);
+    assert!(setup.client.try_cancel_deal(&setup.bank_a, &id).is_err());
+}
+
+#[test]
+fn test_fiat_leg_requires_attestation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_otc(&env);
+    let attestor = Address::generate(&env);
+    setup
+        .client
+        .grant_otc_role(&setup.admin, &Role::Attestor, &attestor);
+
+    // Bank B pays NGN off-chain against on-chain USDC
+    let legs = vec![&env, leg(&setup.bank_b, &setup.usdc, 5_000)];
+    let reference = BytesN::from_array(&env, &[7u8; 32]);
+    let id =
+        setup
+            .client
+            .propose_deal(&setup.bank_a, &setup.bank_b, &legs, &Some(reference), &3600);
+    setup.client.fund_deal_leg(&id, &0);
+    setup.client.approve_deal(&setup.bank_a, &id);
+    setup.client.approve_deal(&setup.bank_b, &id);
+    assert!(setup.client.try_settle_deal(&id).is_err());
+
+    assert!(setup
+        .client
+        .try_attest_fiat_leg(&Address::generate(&env), &id)
+        .is_err());
+    setup.client.attest_fiat_leg(&attestor, &id);
+    setup.client.settle_deal(&id);
+    assert_eq!(setup.usdc.balance(&setup.bank_a), 5_000);
+}
+
+#[test]
+fn test_amendment_resets_sign_offs() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_otc(&env);
+
+    let id = setup.client.propose_deal(
+        &setup.bank_a,
+        &setup.bank_b,
+        &block_trade(&env, &setup),
+        &None,
+        &3600,
+    );
+    setup.client.approve_deal(&setup.bank_a, &id);
+    setup.client.approve_deal(&setup.bank_b, &id);
+
+    let repriced = vec![
+        &env,
+        leg(&setup.bank_a, &setup.ngn, 8_200_000),
+        leg(&setup.bank_b, &setup.usdc, 5_000),
+    ];
+    setup
+        .client
+        .amend_deal(&setup.bank_b, &id, &repriced, &None);
+    let deal = setup.client.get_deal(&id);
+    assert!(!deal.approved_by_a && !deal.approved_by_b);
+    assert_eq!(deal.legs.get(0).unwrap().amount, 8_200_000);
+
+    // Terms are frozen once a leg is funded
+    setup.client.fund_deal_leg(&id, &0);
+    assert!(setup
+        .client
+        .try_amend_deal(&setup.bank_a, &id, &block_trade(&env, &setup), &None)
+        .is_err());
+    assert!(setup
+        .client
+        .try_amend_deal(&Address::generate(&env), &id, &repriced, &None)
+        .is_err());
+}
+
+#[test]
+fn test_cancel_refunds_funded_legs() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_otc(&env);
+
+    let outsider = Address::generate(&env);
+    let bad_legs = vec![&env, leg(&outsider, &setup.usdc, 5_000)];
+    assert!(setup
+        .client
+        .try_propose_deal(&setup.bank_a, &setup.bank_b, &bad_legs, &None, &3600)
+        .is_err());
+
+    let id = setup.client.propose_deal(
+        &setup.bank_a,
+        &setup.bank_b,
+        &block_trade(&env, &setup),
+        &None,
+        &3600,
+    );
+    setup.client.fund_deal_leg(&id, &1);
+    setup.client.approve_deal(&setup.bank_a, &id);
+    setup.client.approve_deal(&setup.bank_b, &id);
+    setup.client.fund_deal_leg(&id, &0);
+
+    set_ledger_time(&env, 3600);
+    assert!(setup.client.try_settle_deal(&id).is_err());
+    setup.client.cancel_deal(&setup.bank_b, &id);
+
+    assert_eq!(setup.ngn.balance(&setup.bank_a), 10_000_000);
+    assert_eq!(setup.usdc.balance(&setup.bank_b), 10_000);
+    assert_eq!(setup.client.get_deal(&id).status, DealStatus::Cancelled);
+}
@@ -0,0 +1,140 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, P2pEvent};
+use stellar_multisig_contract::p2p_market::{
+    P2pMarketContract, P2pMarketContractClient, P2pOfferTerms, P2pTradeStatus,
+};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+const HOUR: u64 = 3_600;
+
+struct Setup<'a> {
+    client: P2pMarketContractClient<'a>,
+    admin: Address,
+    seller: Address,
+    buyer: Address,
+    ngn: token::Client<'a>,
+    offer_id: u64,
+}
+
+fn setup_market(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let seller = Address::generate(env);
+    let buyer = Address::generate(env);
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &ngn.address()).mint(&seller, &1_000_000);
+
+    let client = P2pMarketContractClient::new(env, &env.register(P2pMarketContract, ()));
+    client.initialize_p2p(&admin);
+    client.grant_p2p_role(&admin, &Role::Operator, &admin);
+    let offer_id = client.post_p2p_offer(
+        &seller,
+        &ngn.address(),
+        &600_000,
+        &P2pOfferTerms {
+            fiat: Currency::NGN,
+            // One fiat naira per token
+            price: 100_000_000,
+            min_trade: 10_000,
+            max_trade: 200_000,
+            payment_window: HOUR,
+        },
+    );
+    Setup {
+        client,
+        admin,
+        seller,
+        buyer,
+        ngn: token::Client::new(env, &ngn.address()),
+        offer_id,
+    }
+}
+
+#[test]
+fn test_trade_releases_after_seller_confirms_fiat() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_market(&env);
+
+    let trade_id = s.client.open_p2p_trade(&s.buyer, &s.offer_id, &150_000);
+    assert_eq!(s.client.get_p2p_offer(&s.offer_id).available, 450_000);
+    assert_eq!(s.client.get_p2p_trade(&trade_id).fiat_amount, 150_000);
+
+    s.client.mark_p2p_fiat_sent(&trade_id);
+    assert!(s.client.try_cancel_p2p_trade(&trade_id).is_err());
+    s.client.confirm_p2p_fiat_received(&trade_id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::P2p(P2pEvent::Settled(data)) => {
+            assert!(data.released_to_buyer);
+            assert_eq!(data.amount, 150_000);
+        }
+        _ => panic!("expected P2pEvent::Settled"),
+    }
+    assert_eq!(s.ngn.balance(&s.buyer), 150_000);
+    assert_eq!(
+        s.client.get_p2p_trade(&trade_id).status,
+        P2pTradeStatus::Released
+    );
+    assert!(s.client.try_confirm_p2p_fiat_received(&trade_id).is_err());
+
+    assert_eq!(s.client.withdraw_p2p_offer(&s.offer_id), 450_000);
+    assert_eq!(s.ngn.balance(&s.seller), 850_000);
+}
+
+#[test]
+fn test_unpaid_trade_returns_to_offer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_market(&env);
+
+    assert!(s
+        .client
+        .try_open_p2p_trade(&s.buyer, &s.offer_id, &300_000)
+        .is_err());
+    assert!(s
+        .client
+        .try_open_p2p_trade(&s.seller, &s.offer_id, &50_000)
+        .is_err());
+    let trade_id = s.client.open_p2p_trade(&s.buyer, &s.offer_id, &50_000);
+
+    set_ledger_time(&env, HOUR);
+    assert!(s.client.try_mark_p2p_fiat_sent(&trade_id).is_err());
+    s.client.cancel_p2p_trade(&trade_id);
+    assert_eq!(s.client.get_p2p_offer(&s.offer_id).available, 600_000);
+    assert_eq!(
+        s.client.get_p2p_trade(&trade_id).status,
+        P2pTradeStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_operator_resolves_disputed_payment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_market(&env);
+
+    let paid = s.client.open_p2p_trade(&s.buyer, &s.offer_id, &100_000);
+    let unpaid = s.client.open_p2p_trade(&s.buyer, &s.offer_id, &100_000);
+    assert!(s.client.try_dispute_p2p_trade(&s.buyer, &paid).is_err());
+    s.client.mark_p2p_fiat_sent(&paid);
+    s.client.mark_p2p_fiat_sent(&unpaid);
+    s.client.dispute_p2p_trade(&s.buyer, &paid);
+    s.client.dispute_p2p_trade(&s.seller, &unpaid);
+
+    assert!(s
+        .client
+        .try_resolve_p2p_dispute(&s.seller, &paid, &false)
+        .is_err());
+    s.client.resolve_p2p_dispute(&s.admin, &paid, &true);
+    assert_eq!(s.ngn.balance(&s.buyer), 100_000);
+
+    // Once the offer is closed, a refunded trade goes straight back to the seller
+    s.client.withdraw_p2p_offer(&s.offer_id);
+    s.client.resolve_p2p_dispute(&s.admin, &unpaid, &false);
+    assert_eq!(s.ngn.balance(&s.seller), 900_000);
+}
@@ -0,0 +1,115 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, String, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, PaymentRequestEvent};
+use stellar_multisig_contract::payment_request::{
+    PaymentRequestContract, PaymentRequestContractClient, PaymentRequestStatus,
+};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+const HOUR: u64 = 3_600;
+
+struct Setup<'a> {
+    client: PaymentRequestContractClient<'a>,
+    requester: Address,
+    payer: Address,
+    token: token::Client<'a>,
+}
+
+fn setup_requests(env: &Env) -> Setup<'_> {
+    let requester = Address::generate(env);
+    let payer = Address::generate(env);
+    let usd = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &usd.address()).mint(&payer, &1_000);
+
+    let client = PaymentRequestContractClient::new(env, &env.register(PaymentRequestContract, ()));
+    client.initialize_payment_requests(&Address::generate(env), &HOUR);
+    Setup {
+        client,
+        requester,
+        payer,
+        token: token::Client::new(env, &usd.address()),
+    }
+}
+
+fn request(env: &Env, s: &Setup, amount: i128) -> u64 {
+    s.client.create_payment_request(
+        &s.requester,
+        &s.payer,
+        &s.token.address,
+        &amount,
+        &(24 * HOUR),
+        &String::from_str(env, "dinner"),
+    )
+}
+
+#[test]
+fn test_payer_pays_request() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_requests(&env);
+
+    let id = request(&env, &s, 400);
+    s.client.pay_payment_request(&id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::PaymentRequest(PaymentRequestEvent::Closed(data)) => {
+            assert_eq!(data.request_id, id);
+            assert_eq!(data.status, PaymentRequestStatus::Paid);
+        }
+        _ => panic!("expected PaymentRequestEvent::Closed"),
+    }
+    assert_eq!(s.token.balance(&s.requester), 400);
+    assert_eq!(s.token.balance(&s.payer), 600);
+    assert!(s.client.try_pay_payment_request(&id).is_err());
+    assert!(s.client.try_decline_payment_request(&id).is_err());
+}
+
+#[test]
+fn test_reminders_are_rate_limited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_requests(&env);
+    let id = request(&env, &s, 400);
+
+    assert!(s.client.try_remind_payment_request(&id).is_err());
+    set_ledger_time(&env, HOUR);
+    s.client.remind_payment_request(&id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::PaymentRequest(PaymentRequestEvent::Reminded(data)) => {
+            assert_eq!(data.payer, s.payer);
+            assert_eq!(data.amount, 400);
+        }
+        _ => panic!("expected PaymentRequestEvent::Reminded"),
+    }
+    assert!(s.client.try_remind_payment_request(&id).is_err());
+
+    s.client.decline_payment_request(&id);
+    assert_eq!(
+        s.client.get_payment_request(&id).status,
+        PaymentRequestStatus::Declined
+    );
+}
+
+#[test]
+fn test_unpaid_request_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_requests(&env);
+    let id = request(&env, &s, 400);
+    let cancelled = request(&env, &s, 100);
+    s.client.cancel_payment_request(&cancelled);
+
+    assert!(s.client.try_expire_payment_request(&id).is_err());
+    set_ledger_time(&env, 24 * HOUR);
+    assert_eq!(
+        s.client.get_payment_request(&id).status,
+        PaymentRequestStatus::Expired
+    );
+    assert!(s.client.try_pay_payment_request(&id).is_err());
+    s.client.expire_payment_request(&id);
+    assert!(s.client.try_expire_payment_request(&cancelled).is_err());
+    assert_eq!(s.token.balance(&s.payer), 1_000);
+}
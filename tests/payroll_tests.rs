@@ -0,0 +1,121 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::{
+    ConversionContract, ConversionContractClient, Currency,
+};
+use stellar_multisig_contract::payroll::{PayrollContract, PayrollContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+const MONTH: u64 = 30 * 86_400;
+
+fn setup_payroll(
+    env: &Env,
+) -> (
+    PayrollContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+) {
+    let employer = Address::generate(env);
+    let keeper = Address::generate(env);
+    let ngn = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &ngn.address()).mint(&employer, &10_000_000);
+
+    let client = PayrollContractClient::new(env, &env.register(PayrollContract, ()));
+    client.initialize_payroll(&employer, &Currency::NGN, &ngn.address());
+    client.grant_payroll_role(&employer, &Role::Operator, &keeper);
+    client.fund_payroll(&employer, &Currency::NGN, &1_000_000);
+
+    (
+        client,
+        employer,
+        keeper,
+        token::Client::new(env, &ngn.address()),
+    )
+}
+
+#[test]
+fn test_run_payroll_pays_due_employees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, employer, keeper, ngn) = setup_payroll(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let now = env.ledger().timestamp();
+    client.add_employee(&employer, &alice, &300_000, &MONTH, &now);
+    client.add_employee(&employer, &bob, &200_000, &MONTH, &(now + MONTH));
+
+    assert_eq!(client.run_payroll(&keeper, &0, &50), 1);
+    assert_eq!(ngn.balance(&alice), 300_000);
+    assert_eq!(ngn.balance(&bob), 0);
+    // Alice is not due again until next month
+    assert_eq!(client.run_payroll(&keeper, &0, &50), 0);
+
+    advance_ledger(&env, MONTH);
+    assert_eq!(client.run_payroll(&keeper, &0, &50), 2);
+    assert_eq!(ngn.balance(&alice), 600_000);
+    assert_eq!(ngn.balance(&bob), 200_000);
+    assert_eq!(client.get_treasury_balance(&Currency::NGN), 200_000);
+    assert_eq!(client.get_employee(&alice).total_paid_base, 600_000);
+}
+
+#[test]
+fn test_employee_paid_in_chosen_currency() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, employer, keeper, _ngn) = setup_payroll(&env);
+
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(&employer, &0, &Address::generate(&env), &1, &1_000_000_000);
+    // 1 NGN = 0.000625 USD
+    conversion.update_rate(&employer, &Currency::NGN, &Currency::USD, &62_500, &MONTH);
+    client.set_payroll_conversion(&employer, &Some(conversion.address.clone()));
+
+    let usd = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    token::StellarAssetClient::new(&env, &usd.address()).mint(&employer, &10_000);
+    client.set_currency_token(&employer, &Currency::USD, &usd.address());
+    client.fund_payroll(&employer, &Currency::USD, &10_000);
+
+    let alice = Address::generate(&env);
+    client.add_employee(
+        &employer,
+        &alice,
+        &1_600_000,
+        &MONTH,
+        &env.ledger().timestamp(),
+    );
+    client.set_payout_currency(&alice, &Currency::USD);
+
+    assert_eq!(client.run_payroll(&keeper, &0, &50), 1);
+    assert_eq!(
+        token::Client::new(&env, &usd.address()).balance(&alice),
+        1_000
+    );
+    assert_eq!(client.get_treasury_balance(&Currency::USD), 9_000);
+    assert_eq!(client.get_treasury_balance(&Currency::NGN), 1_000_000);
+}
+
+#[test]
+fn test_run_payroll_requires_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, employer, _keeper, _ngn) = setup_payroll(&env);
+    let alice = Address::generate(&env);
+    client.add_employee(
+        &employer,
+        &alice,
+        &2_000_000,
+        &MONTH,
+        &env.ledger().timestamp(),
+    );
+
+    assert!(client
+        .try_run_payroll(&Address::generate(&env), &0, &50)
+        .is_err());
+    // Salary exceeds the treasury, so the whole batch reverts
+    assert!(client.try_run_payroll(&employer, &0, &50).is_err());
+    assert_eq!(client.get_employee(&alice).total_paid_base, 0);
+}
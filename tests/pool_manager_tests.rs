@@ -1,31 +1,12 @@
 #![cfg(test)]
 
-use soroban_sdk::{
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger, LedgerInfo},
-    Address, Env, InvokeError,
-};
-use stellar_multisig_contract::{
-    conversion::Currency,
-    pool_manager::{
-        LiquidityPool, LiquidityPosition, PoolManagerConfig, PoolManagerContract, PoolManagerEvent,
-    },
-};
+use soroban_sdk::{contracttype, testutils::Address as _, token::TokenClient, vec, Address, Env};
+use stellar_multisig_contract::pool_manager::PoolDataKey;
+use stellar_multisig_contract::upgrade::UpgradeDataKey;
+use stellar_multisig_contract::{conversion::Currency, pool_manager::PoolManagerContract};
 
 fn create_pool_manager_contract(env: &Env) -> Address {
-    env.register_contract(None, PoolManagerContract)
-}
-
-fn advance_ledger(env: &Env, timestamp: u64) {
-    env.ledger().set(LedgerInfo {
-        timestamp,
-        protocol_version: 22,
-        sequence_number: 10,
-        network_id: Default::default(),
-        base_reserve: 10,
-        min_temp_entry_ttl: 10,
-        min_persistent_entry_ttl: 10,
-        max_entry_ttl: 3110400,
-    });
+    env.register(PoolManagerContract, ())
 }
 
 #[test]
@@ -90,7 +71,7 @@ fn test_initialize_with_high_reward_rate() {
 fn test_add_liquidity() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -99,10 +80,14 @@ fn test_add_liquidity() {
 
     // Initialize pool manager
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
 
     // Add liquidity
     let amount = 5_000_000_000; // 50 units
     let position = client.add_liquidity(&provider, &Currency::USD, &amount, &None);
+    let usd = TokenClient::new(&env, &client.get_pool_token(&Currency::USD).unwrap());
+    assert_eq!(usd.balance(&contract_address), amount);
+    assert_eq!(usd.balance(&provider), 1_000_000_000_000 - amount);
 
     assert_eq!(position.provider, provider);
     assert_eq!(position.currency, Currency::USD);
@@ -123,7 +108,7 @@ fn test_add_liquidity() {
 fn test_add_liquidity_multiple_providers() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider1 = Address::generate(&env);
@@ -133,14 +118,21 @@ fn test_add_liquidity_multiple_providers() {
 
     // Initialize pool manager
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(
+        &env,
+        &client,
+        &admin,
+        &[&provider1, &provider2],
+        1_000_000_000_000,
+    );
 
     // First provider adds 60% of liquidity
     let amount1 = 6_000_000_000; // 60 units
-    let position1 = client.add_liquidity(&provider1, &Currency::USD, &amount1, &None);
+    client.add_liquidity(&provider1, &Currency::USD, &amount1, &None);
 
     // Second provider adds 40% of liquidity
     let amount2 = 4_000_000_000; // 40 units
-    let position2 = client.add_liquidity(&provider2, &Currency::USD, &amount2, &None);
+    client.add_liquidity(&provider2, &Currency::USD, &amount2, &None);
 
     // Check positions - need to retrieve current position states
     let current_position1 = client.get_position(&provider1, &Currency::USD);
@@ -193,7 +185,7 @@ fn test_add_liquidity_above_maximum() {
 fn test_remove_liquidity() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -202,15 +194,22 @@ fn test_remove_liquidity() {
 
     // Initialize and add liquidity
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let amount = 5_000_000_000;
     client.add_liquidity(&provider, &Currency::USD, &amount, &Some(0)); // No lock period
 
     // Wait a bit to ensure we can remove liquidity
-    advance_ledger(&env, 2000);
+    set_ledger_time(&env, 2000);
 
     // Remove half the liquidity
     let remove_amount = 2_500_000_000;
     let position = client.remove_liquidity(&provider, &Currency::USD, &remove_amount);
+    let usd = TokenClient::new(&env, &client.get_pool_token(&Currency::USD).unwrap());
+    assert_eq!(usd.balance(&contract_address), amount - remove_amount);
+    assert_eq!(
+        usd.balance(&provider),
+        1_000_000_000_000 - amount + remove_amount
+    );
 
     assert_eq!(position.liquidity_amount, amount - remove_amount);
     assert_eq!(position.pool_share_bps, 10000); // Still 100% since only one provider
@@ -222,11 +221,70 @@ fn test_remove_liquidity() {
     assert_eq!(pool.provider_count, 1);
 }
 
+#[test]
+fn test_simulate_remove_liquidity_matches_removal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    set_ledger_time(&env, 1000);
+
+    let admin = Address::generate(&env);
+    let (provider, other) = (Address::generate(&env), Address::generate(&env));
+    let client = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &0, &1000);
+    fund_pools(
+        &env,
+        &client,
+        &admin,
+        &[&admin, &provider, &other],
+        1_000_000_000_000,
+    );
+    client.add_liquidity(&provider, &Currency::USD, &3_000_000_000, &None);
+    client.add_liquidity(&other, &Currency::USD, &1_000_000_000, &None);
+    client.report_pool_fees(&admin, &Currency::USD, &4_000_000);
+    set_ledger_time(&env, 2000);
+
+    let preview = client.simulate_remove_liquidity(&provider, &Currency::USD, &1_000_000_000);
+    // Nothing changed yet
+    assert_eq!(
+        client
+            .get_position(&provider, &Currency::USD)
+            .liquidity_amount,
+        3_000_000_000
+    );
+    assert_eq!(
+        client.get_pool(&Currency::USD).total_liquidity,
+        4_000_000_000
+    );
+    assert!(client
+        .try_simulate_remove_liquidity(&provider, &Currency::USD, &3_000_000_001)
+        .is_err());
+
+    let position = client.remove_liquidity(&provider, &Currency::USD, &1_000_000_000);
+    let pool = client.get_pool(&Currency::USD);
+    assert_eq!(preview.position.liquidity_amount, position.liquidity_amount);
+    assert_eq!(preview.position.pool_share_bps, position.pool_share_bps);
+    assert_eq!(preview.position.pool_share_bps, 6666);
+    assert_eq!(
+        preview.position.accumulated_rewards,
+        position.accumulated_rewards
+    );
+    assert_eq!(preview.position.last_modified_at, 2000);
+    assert_eq!(preview.pool.total_liquidity, pool.total_liquidity);
+    assert_eq!(preview.pool.available_liquidity, pool.available_liquidity);
+    assert_eq!(preview.pool.provider_count, pool.provider_count);
+    assert_eq!(preview.pool.utilization_rate_bps, pool.utilization_rate_bps);
+    assert_eq!(
+        preview.pending_rewards,
+        client.get_pending_rewards(&provider, &Currency::USD)
+    );
+    assert_eq!(preview.pending_rewards, 300_000);
+}
+
 #[test]
 fn test_remove_all_liquidity() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -235,10 +293,11 @@ fn test_remove_all_liquidity() {
 
     // Initialize and add liquidity
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let amount = 5_000_000_000;
     client.add_liquidity(&provider, &Currency::USD, &amount, &Some(0)); // No lock period
 
-    advance_ledger(&env, 2000);
+    set_ledger_time(&env, 2000);
 
     // Remove all liquidity
     let position = client.remove_liquidity(&provider, &Currency::USD, &amount);
@@ -257,7 +316,7 @@ fn test_remove_all_liquidity() {
 fn test_remove_liquidity_while_locked() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -266,6 +325,7 @@ fn test_remove_liquidity_while_locked() {
 
     // Initialize and add liquidity with lock period
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let amount = 5_000_000_000;
     client.add_liquidity(&provider, &Currency::USD, &amount, &Some(86400)); // 24h lock
 
@@ -278,7 +338,7 @@ fn test_remove_liquidity_while_locked() {
 fn test_remove_more_than_available() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -287,10 +347,11 @@ fn test_remove_more_than_available() {
 
     // Initialize and add liquidity
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let amount = 5_000_000_000;
     client.add_liquidity(&provider, &Currency::USD, &amount, &Some(0)); // No lock
 
-    advance_ledger(&env, 2000);
+    set_ledger_time(&env, 2000);
 
     // Try to remove more than available
     client.remove_liquidity(&provider, &Currency::USD, &(amount + 1_000_000_000));
@@ -300,7 +361,7 @@ fn test_remove_more_than_available() {
 fn test_update_pool_balance_on_conversion() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -309,6 +370,7 @@ fn test_update_pool_balance_on_conversion() {
 
     // Initialize and add liquidity to both currencies
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
 
     let usd_amount = 10_000_000_000;
     let eur_amount = 8_000_000_000;
@@ -340,7 +402,7 @@ fn test_update_pool_balance_on_conversion() {
 fn test_conversion_insufficient_liquidity() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -349,6 +411,7 @@ fn test_conversion_insufficient_liquidity() {
 
     // Initialize with small liquidity
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let small_amount = 1_000_000_000; // 10 units
     client.add_liquidity(&provider, &Currency::USD, &small_amount, &Some(0));
 
@@ -368,7 +431,6 @@ fn test_emergency_pause_and_resume() {
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let provider = Address::generate(&env);
     let contract_address = create_pool_manager_contract(&env);
     let client = PoolManagerContractClient::new(&env, &contract_address);
 
@@ -376,14 +438,14 @@ fn test_emergency_pause_and_resume() {
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
 
     // Pause operations
-    let paused = client.emergency_pause();
+    let paused = client.emergency_pause(&admin);
     assert!(paused);
 
     let config = client.get_pool_config();
     assert!(config.is_paused);
 
     // Resume operations
-    let resumed = client.resume_operations();
+    let resumed = client.resume_operations(&admin);
     assert!(resumed);
 
     let config = client.get_pool_config();
@@ -403,7 +465,7 @@ fn test_add_liquidity_while_paused() {
 
     // Initialize and pause
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
-    client.emergency_pause();
+    client.emergency_pause(&admin);
 
     // Try to add liquidity while paused (should fail)
     client.add_liquidity(&provider, &Currency::USD, &5_000_000_000, &None);
@@ -421,6 +483,7 @@ fn test_get_active_currencies() {
 
     // Initialize
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
 
     // Initially no active currencies
     let currencies = client.get_active_currencies();
@@ -451,6 +514,13 @@ fn test_multiple_currency_pools() {
 
     // Initialize
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(
+        &env,
+        &client,
+        &admin,
+        &[&provider1, &provider2],
+        1_000_000_000_000,
+    );
 
     // Add liquidity to different currencies
     client.add_liquidity(&provider1, &Currency::USD, &10_000_000_000, &None);
@@ -480,7 +550,7 @@ fn test_multiple_currency_pools() {
 fn test_pool_utilization_calculation() {
     let env = Env::default();
     env.mock_all_auths();
-    advance_ledger(&env, 1000);
+    set_ledger_time(&env, 1000);
 
     let admin = Address::generate(&env);
     let provider = Address::generate(&env);
@@ -489,6 +559,7 @@ fn test_pool_utilization_calculation() {
 
     // Initialize and add liquidity
     client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    fund_pools(&env, &client, &admin, &[&provider], 1_000_000_000_000);
     let total_liquidity = 10_000_000_000; // 100 units
     client.add_liquidity(&provider, &Currency::USD, &total_liquidity, &Some(0));
 
@@ -513,5 +584,419 @@ fn test_pool_utilization_calculation() {
     assert_eq!(pool.utilization_rate_bps, 5000); // 50%
 }
 
+#[test]
+fn test_rewards_accrue_by_share_of_reported_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (early, late) = (Address::generate(&env), Address::generate(&env));
+    let client = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    // Providers earn 10% of reported fees
+    client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &0, &1000);
+    fund_pools(
+        &env,
+        &client,
+        &admin,
+        &[&admin, &early, &late],
+        1_000_000_000_000,
+    );
+    let usd = TokenClient::new(&env, &client.get_pool_token(&Currency::USD).unwrap());
+
+    // Fees reported before anyone provides liquidity go nowhere
+    assert_eq!(
+        client.report_pool_fees(&admin, &Currency::USD, &1_000_000),
+        0
+    );
+
+    client.add_liquidity(&early, &Currency::USD, &1_000_000_000, &None);
+    assert!(client
+        .try_report_pool_fees(&early, &Currency::USD, &1_000_000)
+        .is_err());
+    assert_eq!(
+        client.report_pool_fees(&admin, &Currency::USD, &1_000_000),
+        100_000
+    );
+
+    client.add_liquidity(&late, &Currency::USD, &3_000_000_000, &None);
+    client.report_pool_fees(&admin, &Currency::USD, &4_000_000);
+
+    // The early provider had the whole pool for the first fee, a quarter after
+    assert_eq!(client.get_pending_rewards(&early, &Currency::USD), 200_000);
+    assert_eq!(client.get_pending_rewards(&late, &Currency::USD), 300_000);
+
+    // Leaving the pool keeps what was earned claimable
+    client.remove_liquidity(&early, &Currency::USD, &1_000_000_000);
+    client.report_pool_fees(&admin, &Currency::USD, &3_000_000);
+    // The reporter paid the providers' share of every fee into the reserve
+    assert_eq!(client.get_pool_reward_reserve(&Currency::USD), 800_000);
+    assert_eq!(usd.balance(&admin), 1_000_000_000_000 - 800_000);
+
+    assert_eq!(client.claim_rewards(&early, &Currency::USD), 200_000);
+    assert_eq!(client.claim_rewards(&early, &Currency::USD), 0);
+    assert_eq!(usd.balance(&early), 1_000_000_000_000 + 200_000);
+
+    assert_eq!(client.claim_rewards(&late, &Currency::USD), 600_000);
+    assert_eq!(
+        usd.balance(&late),
+        1_000_000_000_000 - 3_000_000_000 + 600_000
+    );
+    assert_eq!(client.get_pool_reward_reserve(&Currency::USD), 0);
+    assert_eq!(
+        client
+            .get_position(&late, &Currency::USD)
+            .accumulated_rewards,
+        600_000
+    );
+    assert_eq!(client.get_pending_rewards(&late, &Currency::USD), 0);
+}
+
+#[test]
+fn test_conversion_fees_feed_pool_rewards() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let pools = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    pools.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &0, &1000);
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    // The conversion contract holds the tokens backing users' balances
+    fund_pools(
+        &env,
+        &pools,
+        &admin,
+        &[&provider, &conversion.address],
+        1_000_000_000_000,
+    );
+    pools.add_liquidity(&provider, &Currency::NGN, &1_000_000_000, &None);
+
+    // 0.5% fee
+    conversion.initialize_conversion(&admin, &50, &admin, &1, &1_000_000_000);
+    conversion.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &3600,
+    );
+    pools.grant_pool_role(&admin, &Role::Minter, &conversion.address);
+    conversion.set_conversion_pool_manager(&admin, &Some(pools.address.clone()));
+    assert_eq!(
+        conversion.get_conversion_pool_manager(),
+        Some(pools.address.clone())
+    );
+
+    let user = Address::generate(&env);
+    conversion.deposit(&user, &Currency::USD, &10_000);
+    let tx = conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000);
+    assert_eq!(tx.platform_fee, 40_000);
+
+    // A tenth of the NGN fee goes to the only NGN provider
+    assert_eq!(pools.get_pending_rewards(&provider, &Currency::NGN), 4_000);
+
+    // All of a pair's spread does: 1% of ₦8,000,000, plus a tenth of the fee
+    // on the ₦7,920,000 left
+    conversion.set_pair_spread(&admin, &Currency::USD, &Currency::NGN, &Some(100));
+    conversion.deposit(&user, &Currency::USD, &10_000);
+    conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000);
+    assert_eq!(
+        pools.get_pending_rewards(&provider, &Currency::NGN),
+        4_000 + 80_000 + 3_960
+    );
+
+    // The rewards themselves moved into the pool's reward reserve
+    let rewards = 4_000 + 80_000 + 3_960;
+    let ngn = TokenClient::new(&env, &pools.get_pool_token(&Currency::NGN).unwrap());
+    assert_eq!(pools.get_pool_reward_reserve(&Currency::NGN), rewards);
+    assert_eq!(ngn.balance(&pools.address), 1_000_000_000 + rewards);
+    assert_eq!(
+        ngn.balance(&conversion.address),
+        1_000_000_000_000 - rewards
+    );
+
+    assert_eq!(pools.claim_rewards(&provider, &Currency::NGN), rewards);
+    assert_eq!(
+        ngn.balance(&provider),
+        1_000_000_000_000 - 1_000_000_000 + rewards
+    );
+}
+
+#[test]
+fn test_idle_conversion_balances_supplied_to_pools() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let pools = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    pools.initialize_pool_manager(&admin, &1, &100_000_000_000, &86_400, &1000);
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(&admin, &50, &admin, &1, &1_000_000_000);
+    conversion.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &3600,
+    );
+    pools.grant_pool_role(&admin, &Role::Minter, &conversion.address);
+    // The conversion contract holds the tokens backing users' balances
+    fund_pools(
+        &env,
+        &pools,
+        &admin,
+        &[&conversion.address],
+        1_000_000_000_000,
+    );
+
+    let user = Address::generate(&env);
+    conversion.deposit(&user, &Currency::USD, &10_000);
+    assert!(conversion
+        .try_set_auto_supply(&user, &Currency::USD, &true)
+        .is_err());
+    conversion.set_conversion_pool_manager(&admin, &Some(pools.address.clone()));
+    conversion.set_auto_supply(&user, &Currency::USD, &true);
+    conversion.set_auto_supply(&user, &Currency::NGN, &true);
+    assert_eq!(
+        conversion.get_auto_supplied(&user, &Currency::USD),
+        Some(10_000)
+    );
+    let position = pools.get_position(&user, &Currency::USD);
+    assert_eq!(position.liquidity_amount, 10_000);
+    assert_eq!(position.lock_until, 0);
+    let usd = TokenClient::new(&env, &pools.get_pool_token(&Currency::USD).unwrap());
+    assert_eq!(usd.balance(&pools.address), 10_000);
+
+    // Converting draws the source back from its pool and supplies the proceeds
+    let tx = conversion.convert_currency(&user, &Currency::USD, &Currency::NGN, &4_000);
+    assert_eq!(
+        conversion.get_auto_supplied(&user, &Currency::USD),
+        Some(6_000)
+    );
+    assert_eq!(
+        pools.get_position(&user, &Currency::USD).liquidity_amount,
+        6_000
+    );
+    assert_eq!(
+        conversion.get_auto_supplied(&user, &Currency::NGN),
+        Some(tx.amount_received)
+    );
+    let balances = conversion.get_user_balance(&user).balances;
+    assert_eq!(balances.get(Currency::USD), Some(6_000));
+    assert_eq!(balances.get(Currency::NGN), Some(tx.amount_received));
+
+    // The supplied balance earns a share of later conversion fees
+    let other = Address::generate(&env);
+    conversion.deposit(&other, &Currency::USD, &10_000);
+    conversion.convert_currency(&other, &Currency::USD, &Currency::NGN, &10_000);
+    assert!(pools.get_pending_rewards(&user, &Currency::NGN) > 0);
+
+    conversion.withdraw(&user, &Currency::USD, &6_000);
+    assert_eq!(conversion.get_auto_supplied(&user, &Currency::USD), Some(0));
+    assert_eq!(usd.balance(&pools.address), 0);
+    assert_eq!(usd.balance(&conversion.address), 1_000_000_000_000);
+    assert!(pools.try_get_position(&user, &Currency::USD).is_err());
+
+    conversion.set_auto_supply(&user, &Currency::NGN, &false);
+    assert_eq!(conversion.get_auto_supplied(&user, &Currency::NGN), None);
+    assert!(pools.try_get_position(&user, &Currency::NGN).is_err());
+    assert_eq!(
+        conversion
+            .get_user_balance(&user)
+            .balances
+            .get(Currency::NGN),
+        Some(tx.amount_received)
+    );
+}
+
+#[test]
+fn test_compounded_yield_raises_share_value() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (early, late) = (Address::generate(&env), Address::generate(&env));
+    let client = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &0, &1000);
+    fund_pools(&env, &client, &admin, &[&early, &late], 1_000_000_000_000);
+    client.add_liquidity(&early, &Currency::USD, &1_000_000_000, &None);
+    assert_eq!(client.get_share_price(&Currency::USD), 1_000_000_000);
+
+    assert!(client
+        .try_compound_pool_yield(&early, &Currency::USD, &1_000_000_000)
+        .is_err());
+    // Only yield the pool actually holds can be compounded
+    assert!(client
+        .try_compound_pool_yield(&admin, &Currency::USD, &1_000_000_000)
+        .is_err());
+    let usd = TokenClient::new(&env, &client.get_pool_token(&Currency::USD).unwrap());
+    usd.transfer(&late, &client.address, &1_000_000_000);
+    assert_eq!(
+        client.compound_pool_yield(&admin, &Currency::USD, &1_000_000_000),
+        2_000_000_000
+    );
+    let position = client.get_position(&early, &Currency::USD);
+    assert_eq!(position.shares, 1_000_000_000);
+    assert_eq!(position.liquidity_amount, 2_000_000_000);
+
+    // A later deposit buys shares at the raised price
+    let position = client.add_liquidity(&late, &Currency::USD, &2_000_000_000, &None);
+    assert_eq!(position.shares, 1_000_000_000);
+    assert_eq!(position.pool_share_bps, 5000);
+    assert_eq!(
+        client.get_position(&early, &Currency::USD).pool_share_bps,
+        5000
+    );
+
+    // Withdrawing the whole value burns every share and pays out the yield
+    let position = client.remove_liquidity(&early, &Currency::USD, &2_000_000_000);
+    assert_eq!(usd.balance(&early), 1_001_000_000_000);
+    assert_eq!(position.shares, 0);
+    assert_eq!(position.liquidity_amount, 0);
+    let pool = client.get_pool(&Currency::USD);
+    assert_eq!(pool.total_liquidity, 2_000_000_000);
+    assert_eq!(pool.total_shares, 1_000_000_000);
+    assert_eq!(pool.provider_count, 1);
+    assert_eq!(
+        client.get_position(&late, &Currency::USD).pool_share_bps,
+        10000
+    );
+}
+
+// `LiquidityPool` and `LiquidityPosition` as storage version 1 kept them
+#[contracttype]
+#[derive(Clone)]
+struct PoolWithoutShares {
+    currency: Currency,
+    total_liquidity: i128,
+    available_liquidity: i128,
+    reserved_liquidity: i128,
+    provider_count: u32,
+    created_at: u64,
+    last_activity_at: u64,
+    min_liquidity_threshold: i128,
+    utilization_rate_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+struct PositionWithoutShares {
+    provider: Address,
+    currency: Currency,
+    liquidity_amount: i128,
+    pool_share_bps: u32,
+    added_at: u64,
+    last_modified_at: u64,
+    accumulated_rewards: i128,
+    lock_until: u64,
+}
+
+#[test]
+fn test_migrate_issues_shares_to_legacy_pools_and_positions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    let client = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    client.initialize_pool_manager(&admin, &1_000, &1_000_000, &0, &50);
+    assert_eq!(client.migrate_pool(), 3);
+
+    // Simulate a storage version 1 pool, which kept positions in instance
+    // storage and had no shares
+    let position = |provider: &Address, amount: i128, share_bps: u32| PositionWithoutShares {
+        provider: provider.clone(),
+        currency: Currency::USD,
+        liquidity_amount: amount,
+        pool_share_bps: share_bps,
+        added_at: 0,
+        last_modified_at: 0,
+        accumulated_rewards: 0,
+        lock_until: 0,
+    };
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.set(
+            &PoolDataKey::Pool(Currency::USD),
+            &PoolWithoutShares {
+                currency: Currency::USD,
+                total_liquidity: 4_000,
+                available_liquidity: 4_000,
+                reserved_liquidity: 0,
+                provider_count: 2,
+                created_at: 0,
+                last_activity_at: 0,
+                min_liquidity_threshold: 1_000,
+                utilization_rate_bps: 0,
+            },
+        );
+        storage.set(&PoolDataKey::ActiveCurrencies, &vec![&env, Currency::USD]);
+        storage.set(
+            &PoolDataKey::CurrencyProviders(Currency::USD),
+            &vec![&env, alice.clone(), bob.clone()],
+        );
+        storage.set(
+            &PoolDataKey::Position(alice.clone(), Currency::USD),
+            &position(&alice, 3_000, 7500),
+        );
+        storage.set(
+            &PoolDataKey::Position(bob.clone(), Currency::USD),
+            &position(&bob, 1_000, 2500),
+        );
+        storage.set(&UpgradeDataKey::StorageVersion, &1u32);
+    });
+
+    assert_eq!(client.migrate_pool(), 3);
+    let pool = client.get_pool(&Currency::USD);
+    assert_eq!(pool.total_liquidity, 4_000);
+    assert_eq!(pool.total_shares, 4_000);
+    assert_eq!(client.get_share_price(&Currency::USD), 1_000_000_000);
+    let position = client.get_position(&alice, &Currency::USD);
+    assert_eq!(position.shares, 3_000);
+    assert_eq!(position.liquidity_amount, 3_000);
+    assert_eq!(position.pool_share_bps, 7500);
+    assert_eq!(client.get_position(&bob, &Currency::USD).shares, 1_000);
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .instance()
+            .has(&PoolDataKey::Position(alice.clone(), Currency::USD)));
+    });
+}
+
+#[test]
+fn test_pool_config_rolls_back_to_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = PoolManagerContractClient::new(&env, &create_pool_manager_contract(&env));
+    client.initialize_pool_manager(&admin, &1_000_000_000, &100_000_000_000, &86400, &50);
+    let version = client.snapshot_pool_config(&admin);
+    assert_eq!(
+        client.get_pool_config_snapshot(&version).unwrap().version,
+        1
+    );
+
+    assert!(client
+        .try_update_pool_config(&admin, &1_000_000_000, &100_000_000_000, &0, &1500)
+        .is_err());
+    client.update_pool_config(&admin, &1, &2, &0, &1000);
+    client.emergency_pause(&admin);
+
+    // The rollback restores parameters but leaves the pause in place
+    let config = client.rollback_pool_config(&admin, &version);
+    assert_eq!(config.min_liquidity_amount, 1_000_000_000);
+    assert_eq!(config.default_lock_period, 86400);
+    assert_eq!(config.provider_reward_rate_bps, 50);
+    assert!(config.is_paused);
+    assert!(client
+        .try_rollback_pool_config(&Address::generate(&env), &version)
+        .is_err());
+}
+
 // Add this line at the end to ensure tests compile
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::{ConversionContract, ConversionContractClient};
 use stellar_multisig_contract::pool_manager::PoolManagerContractClient;
+use stellar_multisig_contract::testutils::{fund_pools, set_ledger_time};
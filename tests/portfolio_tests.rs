@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{symbol_short, Address, Env};
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract, EscrowStatus};
+use stellar_multisig_contract::order_book::{
+    OrderBookContract, OrderBookContractClient, OrderSide,
+};
+use stellar_multisig_contract::pool_manager::{PoolManagerContract, PoolManagerContractClient};
+use stellar_multisig_contract::portfolio::{
+    PortfolioContract, PortfolioContractClient, PortfolioSources,
+};
+use stellar_multisig_contract::rate_lock::{RateLockContract, RateLockContractClient};
+use stellar_multisig_contract::testutils::{
+    advance_ledger, create_token, fund, fund_pools, funded_user, setup_conversion,
+};
+
+const NO_SOURCES: PortfolioSources = PortfolioSources {
+    conversion: None,
+    escrow: None,
+    pool_manager: None,
+    rate_lock: None,
+    order_book: None,
+};
+
+fn setup_portfolio(
+    env: &Env,
+    sources: &PortfolioSources,
+) -> (PortfolioContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let client = PortfolioContractClient::new(env, &env.register(PortfolioContract, ()));
+    client.initialize_portfolio(&admin, sources);
+    (client, admin)
+}
+
+#[test]
+fn test_portfolio_aggregates_every_source() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = create_token(&env);
+    let user = funded_user(&env, &token.address, 10_000);
+    let counterparty = funded_user(&env, &token.address, 10_000);
+
+    let (conversion, _) = setup_conversion(&env);
+    conversion.deposit(&user, &Currency::USD, &2_500);
+
+    let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+    let sent = escrow.create(&user, &counterparty, &token.address, &500, &3600, &1800);
+    let received = escrow.create(&counterparty, &user, &token.address, &700, &3600, &1800);
+    let settled = escrow.create(&user, &counterparty, &token.address, &100, &3600, &1800);
+    escrow.release(&settled.id);
+    escrow.initiate_dispute(&received.id, &symbol_short!("LATE"));
+
+    let pools = PoolManagerContractClient::new(&env, &env.register(PoolManagerContract, ()));
+    let pool_owner = Address::generate(&env);
+    pools.initialize_pool_manager(&pool_owner, &1_000, &1_000_000, &0, &50);
+    fund_pools(&env, &pools, &pool_owner, &[&user, &counterparty], 5_000);
+    pools.add_liquidity(&user, &Currency::NGN, &5_000, &None);
+    pools.add_liquidity(&counterparty, &Currency::USD, &5_000, &None);
+
+    let locks = RateLockContractClient::new(&env, &env.register(RateLockContract, ()));
+    locks.lock_rate(&user, &Currency::NGN, &Currency::USD, &125_000, &1_000, &60);
+    let live_lock = locks.lock_rate(&user, &Currency::USD, &Currency::NGN, &80_000, &1_000, &600);
+
+    let quote = create_token(&env);
+    let book = OrderBookContractClient::new(&env, &env.register(OrderBookContract, ()));
+    book.initialize_order_book(
+        &Address::generate(&env),
+        &token.address,
+        &quote.address,
+        &10,
+        &30,
+        &Address::generate(&env),
+    );
+    fund(&env, &quote.address, &user, 1_000);
+    let bid = book.place_order(&user, &OrderSide::Buy, &50_000_000, &1_000);
+    book.place_order(&counterparty, &OrderSide::Sell, &90_000_000, &1_000);
+
+    let (client, _) = setup_portfolio(
+        &env,
+        &PortfolioSources {
+            conversion: Some(conversion.address.clone()),
+            escrow: Some(escrow.address.clone()),
+            pool_manager: Some(pools.address.clone()),
+            rate_lock: Some(locks.address.clone()),
+            order_book: Some(book.address.clone()),
+        },
+    );
+
+    advance_ledger(&env, 61);
+    let portfolio = client.get_portfolio(&user);
+    assert_eq!(portfolio.user, user);
+    assert_eq!(portfolio.as_of, 61);
+    assert_eq!(portfolio.balances.get(Currency::USD), Some(2_500));
+
+    // Settled escrows drop out; disputed ones are still open
+    assert_eq!(portfolio.open_escrows.len(), 2);
+    assert_eq!(portfolio.open_escrows.get_unchecked(0).id, sent.id);
+    let disputed = portfolio.open_escrows.get_unchecked(1);
+    assert_eq!(disputed.id, received.id);
+    assert_eq!(disputed.status, EscrowStatus::Disputed);
+
+    assert_eq!(portfolio.pool_positions.len(), 1);
+    assert_eq!(
+        portfolio.pool_positions.get_unchecked(0).currency,
+        Currency::NGN
+    );
+
+    // The one minute lock has expired
+    assert_eq!(portfolio.rate_locks.len(), 1);
+    assert_eq!(portfolio.rate_locks.get_unchecked(0).lock_id, live_lock);
+
+    assert_eq!(portfolio.open_orders.len(), 1);
+    assert_eq!(portfolio.open_orders.get_unchecked(0).id, bid);
+}
+
+#[test]
+fn test_unset_sources_leave_sections_empty() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_portfolio(&env, &NO_SOURCES);
+    let user = Address::generate(&env);
+
+    let portfolio = client.get_portfolio(&user);
+    assert!(portfolio.balances.is_empty());
+    assert!(portfolio.open_escrows.is_empty());
+    assert!(portfolio.pool_positions.is_empty());
+    assert!(portfolio.rate_locks.is_empty());
+    assert!(portfolio.open_orders.is_empty());
+
+    let (conversion, _) = setup_conversion(&env);
+    conversion.deposit(&user, &Currency::NGN, &9_000);
+    let sources = PortfolioSources {
+        conversion: Some(conversion.address.clone()),
+        ..NO_SOURCES
+    };
+    assert!(client
+        .try_set_portfolio_sources(&Address::generate(&env), &sources)
+        .is_err());
+    client.set_portfolio_sources(&admin, &sources);
+    assert_eq!(client.get_portfolio_sources(), sources);
+    assert_eq!(
+        client.get_portfolio(&user).balances.get(Currency::NGN),
+        Some(9_000)
+    );
+}
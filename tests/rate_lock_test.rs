@@ -1,10 +1,14 @@
 #![cfg(test)]
 
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::testutils::Ledger;
-use soroban_sdk::{Address, Env};
-use stellar_multisig_contract::rate_lock::RateLockContractClient as RateLockClient;
-use stellar_multisig_contract::rate_lock::{RateLockContract, RateLockError};
+use soroban_sdk::testutils::Events;
+use soroban_sdk::{token, vec, Address, Env, IntoVal, TryFromVal};
+use stellar_multisig_contract::conversion::Currency;
+use stellar_multisig_contract::errors::AppError;
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, RateLockEvent, RATE_LOCK_TOPIC};
+use stellar_multisig_contract::rate_limit::RateLimit;
+use stellar_multisig_contract::rate_lock::{RateLockContract, RateLockContractClient};
+use stellar_multisig_contract::testutils::advance_ledger;
 
 #[test]
 fn test_lock_and_validate_rate() {
@@ -13,21 +17,488 @@ fn test_lock_and_validate_rate() {
     let contract_id = env.register(RateLockContract, ());
 
     // Lock rate
-    env.as_contract(&contract_id, || {
-        RateLockContract::lock_rate(env.clone(), user.clone(), 100, 60);
+    let lock_id = env.as_contract(&contract_id, || {
+        RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::NGN,
+            Currency::USD,
+            100,
+            1_000,
+            60,
+        )
+        .unwrap()
     });
 
     // Validate inside contract context
     let rate = env.as_contract(&contract_id, || {
-        RateLockContract::validate_conversion(env.clone(), user.clone()).unwrap()
+        RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::NGN,
+            Currency::USD,
+            400,
+        )
+        .unwrap()
     });
     assert_eq!(rate, 100);
 
     // Advance time
-    env.ledger().set_timestamp(env.ledger().timestamp() + 61);
+    advance_ledger(&env, 61);
 
     let err = env.as_contract(&contract_id, || {
-        RateLockContract::validate_conversion(env.clone(), user.clone()).unwrap_err()
+        RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::NGN,
+            Currency::USD,
+            400,
+        )
+        .unwrap_err()
+    });
+    assert_eq!(err, AppError::RateExpired);
+}
+
+#[test]
+fn test_rate_lock_amount_is_consumed() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let contract_id = env.register(RateLockContract, ());
+
+    env.as_contract(&contract_id, || {
+        let lock_id = RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::NGN,
+            Currency::USD,
+            100,
+            1_000,
+            60,
+        )
+        .unwrap();
+
+        RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::NGN,
+            Currency::USD,
+            600,
+        )
+        .unwrap();
+        let lock = RateLockContract::get_rate_lock(env.clone(), user.clone(), lock_id).unwrap();
+        assert_eq!(lock.remaining_amount, 400);
+
+        let err = RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::NGN,
+            Currency::USD,
+            500,
+        )
+        .unwrap_err();
+        assert_eq!(err, AppError::AmountExceedsLock);
+
+        // Using the remainder consumes the lock entirely
+        RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::NGN,
+            Currency::USD,
+            400,
+        )
+        .unwrap();
+        assert!(RateLockContract::get_rate_lock(env.clone(), user.clone(), lock_id).is_none());
+        assert_eq!(
+            RateLockContract::get_locks(env.clone(), user.clone()).len(),
+            0
+        );
+    });
+}
+
+#[test]
+fn test_rate_lock_pair_mismatch() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let contract_id = env.register(RateLockContract, ());
+
+    env.as_contract(&contract_id, || {
+        let lock_id = RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::NGN,
+            Currency::USD,
+            100,
+            1_000,
+            60,
+        )
+        .unwrap();
+
+        let err = RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id,
+            Currency::USD,
+            Currency::NGN,
+            100,
+        )
+        .unwrap_err();
+        assert_eq!(err, AppError::PairMismatch);
+
+        let err = RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            lock_id + 1,
+            Currency::NGN,
+            Currency::USD,
+            100,
+        )
+        .unwrap_err();
+        assert_eq!(err, AppError::NoRateLocked);
     });
-    assert_eq!(err, RateLockError::RateExpired);
+}
+
+#[test]
+fn test_concurrent_rate_locks() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let contract_id = env.register(RateLockContract, ());
+
+    env.as_contract(&contract_id, || {
+        let first = RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::NGN,
+            Currency::USD,
+            100,
+            1_000,
+            60,
+        )
+        .unwrap();
+        let second = RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::NGN,
+            Currency::USD,
+            105,
+            2_000,
+            120,
+        )
+        .unwrap();
+        let third = RateLockContract::lock_rate(
+            env.clone(),
+            user.clone(),
+            Currency::EUR,
+            Currency::USD,
+            110,
+            500,
+            60,
+        )
+        .unwrap();
+
+        // A newer quote does not overwrite the earlier one
+        let locks = RateLockContract::get_locks(env.clone(), user.clone());
+        assert_eq!(locks.len(), 3);
+        assert_eq!(locks.get(0).unwrap().rate, 100);
+        assert_eq!(locks.get(1).unwrap().rate, 105);
+
+        let rate = RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            first,
+            Currency::NGN,
+            Currency::USD,
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(rate, 100);
+
+        let rate = RateLockContract::validate_conversion(
+            env.clone(),
+            user.clone(),
+            second,
+            Currency::NGN,
+            Currency::USD,
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(rate, 105);
+
+        let locks = RateLockContract::get_locks(env.clone(), user.clone());
+        assert_eq!(locks.len(), 2);
+        assert_eq!(locks.get(0).unwrap().lock_id, second);
+        assert_eq!(locks.get(1).unwrap().lock_id, third);
+    });
+}
+
+fn setup_fee_bearing_locks(
+    env: &Env,
+) -> (
+    RateLockContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+) {
+    let admin = Address::generate(env);
+    let treasury = Address::generate(env);
+    let user = Address::generate(env);
+
+    let fee_token = env.register_stellar_asset_contract_v2(admin.clone());
+    token::StellarAssetClient::new(env, &fee_token.address()).mint(&user, &1_000_000);
+
+    let contract_id = env.register(RateLockContract, ());
+    let client = RateLockContractClient::new(env, &contract_id);
+    // 10 bps of the locked amount per hour
+    client.initialize_rate_lock(&admin, &treasury, &fee_token.address(), &10);
+
+    (
+        client,
+        user,
+        treasury,
+        token::Client::new(env, &fee_token.address()),
+    )
+}
+
+#[test]
+fn test_rate_lock_fee_charged_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, treasury, fee_token) = setup_fee_bearing_locks(&env);
+
+    // 100_000 locked for two hours at 10 bps/hour = 200
+    assert_eq!(client.quote_lock_fee(&100_000, &7_200), 200);
+    let lock_id = client.lock_rate(
+        &user,
+        &Currency::NGN,
+        &Currency::USD,
+        &100,
+        &100_000,
+        &7_200,
+    );
+
+    assert_eq!(fee_token.balance(&treasury), 200);
+    assert_eq!(fee_token.balance(&user), 1_000_000 - 200);
+    assert_eq!(client.get_rate_lock(&user, &lock_id).unwrap().fee_paid, 200);
+}
+
+#[test]
+fn test_extend_rate_lock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, treasury, fee_token) = setup_fee_bearing_locks(&env);
+
+    let lock_id = client.lock_rate(
+        &user,
+        &Currency::NGN,
+        &Currency::USD,
+        &100,
+        &100_000,
+        &3_600,
+    );
+    let lock = client.extend_lock(&user, &lock_id, &3_600);
+
+    assert_eq!(lock.expires_at, lock.locked_at + 7_200);
+    assert_eq!(lock.fee_paid, 200);
+    assert_eq!(fee_token.balance(&treasury), 200);
+
+    // Total duration is capped at 24 hours
+    let err = client
+        .try_extend_lock(&user, &lock_id, &(86_400 - 7_200 + 1))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::InvalidDuration);
+}
+
+#[test]
+fn test_rate_lock_duration_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, _treasury, _fee_token) = setup_fee_bearing_locks(&env);
+
+    let err = client
+        .try_lock_rate(
+            &user,
+            &Currency::NGN,
+            &Currency::USD,
+            &100,
+            &100_000,
+            &86_401,
+        )
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::InvalidDuration);
+}
+
+fn last_rate_lock_event(env: &Env) -> DeFiEvent {
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, vec![env, RATE_LOCK_TOPIC.into_val(env)]);
+    EventEnvelope::try_from_val(env, &data).unwrap().event
+}
+
+#[test]
+fn test_rate_lock_admin_controls() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, _treasury, _fee_token) = setup_fee_bearing_locks(&env);
+    let admin = client.get_rate_lock_config().unwrap().admin;
+
+    let err = client
+        .try_set_max_lock_duration(&user, &3_600)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::Unauthorized);
+
+    client.set_max_lock_duration(&admin, &3_600);
+    let err = client
+        .try_lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &3_601)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::InvalidDuration);
+    let lock_id = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+
+    // Pausing blocks new locks and extensions but not conversions
+    client.set_locks_paused(&admin, &true);
+    assert!(client.is_locks_paused());
+    let err = client
+        .try_lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::LocksPaused);
+    let err = client
+        .try_extend_lock(&user, &lock_id, &600)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::LocksPaused);
+    assert_eq!(
+        client.validate_conversion(&user, &lock_id, &Currency::NGN, &Currency::USD, &500),
+        100
+    );
+
+    client.set_locks_paused(&admin, &false);
+    client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+}
+
+#[test]
+fn test_rate_lock_lifecycle_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, _treasury, _fee_token) = setup_fee_bearing_locks(&env);
+
+    let lock_id = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    match last_rate_lock_event(&env) {
+        DeFiEvent::RateLock(RateLockEvent::Created(data)) => {
+            assert_eq!(data.lock_id, lock_id);
+            assert_eq!(data.max_amount, 1_000);
+        }
+        _ => panic!("expected RateLockEvent::Created"),
+    }
+
+    client.validate_conversion(&user, &lock_id, &Currency::NGN, &Currency::USD, &400);
+    match last_rate_lock_event(&env) {
+        DeFiEvent::RateLock(RateLockEvent::Consumed(data)) => {
+            assert_eq!(data.amount, 400);
+            assert_eq!(data.remaining_amount, 600);
+        }
+        _ => panic!("expected RateLockEvent::Consumed"),
+    }
+
+    client.cancel_lock(&user, &lock_id);
+    match last_rate_lock_event(&env) {
+        DeFiEvent::RateLock(RateLockEvent::Cancelled(data)) => assert_eq!(data.unused_amount, 600),
+        _ => panic!("expected RateLockEvent::Cancelled"),
+    }
+    assert!(client.get_rate_lock(&user, &lock_id).is_none());
+
+    // Expiry can only be recorded once the lock has lapsed
+    let lock_id = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    let err = client
+        .try_expire_lock(&user, &lock_id)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::LockNotExpired);
+
+    advance_ledger(&env, 601);
+    client.expire_lock(&user, &lock_id);
+    match last_rate_lock_event(&env) {
+        DeFiEvent::RateLock(RateLockEvent::Expired(data)) => assert_eq!(data.unused_amount, 1_000),
+        _ => panic!("expected RateLockEvent::Expired"),
+    }
+    assert_eq!(client.get_locks(&user).len(), 0);
+}
+
+#[test]
+fn test_lock_creation_rate_limited_per_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, _treasury, fee_token) = setup_fee_bearing_locks(&env);
+    let admin = client.get_rate_lock_config().unwrap().admin;
+    let other = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &fee_token.address).mint(&other, &1_000_000);
+
+    // Bursts of two locks, then one more every ten minutes
+    let limit = RateLimit {
+        capacity: 2,
+        refill_interval: 600,
+    };
+    assert_eq!(
+        client.try_set_lock_rate_limit(&user, &Some(limit.clone())),
+        Err(Ok(AppError::Unauthorized))
+    );
+    client.set_lock_rate_limit(&admin, &Some(limit.clone()));
+    assert_eq!(client.get_lock_rate_limit(), Some(limit));
+
+    for _ in 0..2 {
+        client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    }
+    let err = client
+        .try_lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, AppError::RateLimited);
+    // Buckets are per user
+    client.lock_rate(&other, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+
+    advance_ledger(&env, 600);
+    client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    assert!(client
+        .try_lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600)
+        .is_err());
+
+    client.set_lock_rate_limit(&admin, &None);
+    client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+}
+
+#[test]
+fn test_expired_locks_garbage_collected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, user, _treasury, _fee_token) = setup_fee_bearing_locks(&env);
+
+    let lapsed = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &60);
+    let also_lapsed = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &60);
+    let live = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    advance_ledger(&env, 61);
+
+    // Unknown and unexpired entries are skipped
+    let keys = vec![
+        &env,
+        (user.clone(), lapsed),
+        (user.clone(), live),
+        (Address::generate(&env), 99),
+    ];
+    assert_eq!(client.gc_expired_locks(&keys), 1);
+    assert!(client.get_rate_lock(&user, &lapsed).is_none());
+    assert_eq!(client.get_locks(&user).len(), 2);
+
+    // A new lock sweeps the user's remaining lapsed ones
+    let fresh = client.lock_rate(&user, &Currency::NGN, &Currency::USD, &100, &1_000, &600);
+    assert!(client.get_rate_lock(&user, &also_lapsed).is_none());
+    let locks = client.get_locks(&user);
+    assert_eq!(locks.len(), 2);
+    assert_eq!(locks.get_unchecked(0).lock_id, live);
+    assert_eq!(locks.get_unchecked(1).lock_id, fresh);
 }
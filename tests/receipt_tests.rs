@@ -0,0 +1,130 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, ReceiptEvent};
+use stellar_multisig_contract::receipt::{PositionKind, ReceiptContract, ReceiptContractClient};
+use stellar_multisig_contract::testutils::set_ledger_time;
+use stellar_multisig_contract::vesting::{VestingContract, VestingContractClient, VestingTerms};
+
+const DAY: u64 = 86_400;
+
+fn setup_receipts(env: &Env, admin: &Address) -> ReceiptContractClient<'static> {
+    let client = ReceiptContractClient::new(env, &env.register(ReceiptContract, ()));
+    client.initialize_receipts(admin);
+    client
+}
+
+fn setup_token(env: &Env, holder: &Address) -> token::Client<'static> {
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &sac.address()).mint(holder, &1_000_000);
+    token::Client::new(env, &sac.address())
+}
+
+#[test]
+fn test_only_holder_transfers_and_only_issuer_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let receipts = setup_receipts(&env, &admin);
+    let (issuer, alice, bob) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    assert!(receipts
+        .try_mint_receipt(&issuer, &PositionKind::Liquidity, &alice)
+        .is_err());
+    receipts.grant_receipt_role(&admin, &Role::Minter, &issuer);
+    let id = receipts.mint_receipt(&issuer, &PositionKind::Liquidity, &alice);
+
+    assert!(receipts.try_transfer_receipt(&bob, &bob, &id).is_err());
+    receipts.transfer_receipt(&alice, &bob, &id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Receipt(ReceiptEvent::Transferred(data)) => {
+            assert_eq!(data.from, alice);
+            assert_eq!(data.to, bob);
+        }
+        _ => panic!("expected ReceiptEvent::Transferred"),
+    }
+    assert_eq!(receipts.receipt_owner(&id), bob);
+
+    assert!(receipts.try_burn_receipt(&bob, &id).is_err());
+    receipts.burn_receipt(&issuer, &id);
+    assert!(receipts.try_get_receipt(&id).is_err());
+}
+
+#[test]
+fn test_vesting_claims_follow_the_receipt() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let (beneficiary, buyer) = (Address::generate(&env), Address::generate(&env));
+    let receipts = setup_receipts(&env, &admin);
+    let token = setup_token(&env, &admin);
+
+    let vesting = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+    vesting.initialize_vesting(&admin);
+    vesting.set_vesting_receipts(&admin, &Some(receipts.address.clone()));
+    receipts.grant_receipt_role(&admin, &Role::Minter, &vesting.address);
+
+    let id = vesting.create_vesting_schedule(
+        &admin,
+        &beneficiary,
+        &token.address,
+        &100_000,
+        &VestingTerms {
+            start_time: 0,
+            cliff_duration: 0,
+            duration: 100 * DAY,
+            revocable: false,
+            unlock_time: 0,
+        },
+    );
+    let (_, receipt_id) = vesting.get_vesting_receipt(&id).unwrap();
+    assert_eq!(receipts.receipt_owner(&receipt_id), beneficiary);
+
+    set_ledger_time(&env, 40 * DAY);
+    vesting.claim(&id);
+    receipts.transfer_receipt(&beneficiary, &buyer, &receipt_id);
+
+    set_ledger_time(&env, 100 * DAY);
+    assert_eq!(vesting.claim(&id), 60_000);
+    assert_eq!(token.balance(&beneficiary), 40_000);
+    assert_eq!(token.balance(&buyer), 60_000);
+    // Fully claimed, so the receipt is retired
+    assert!(vesting.get_vesting_receipt(&id).is_none());
+    assert!(receipts.try_get_receipt(&receipt_id).is_err());
+}
+
+#[test]
+fn test_escrow_pays_receipt_holder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let (sender, recipient, buyer) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    let receipts = setup_receipts(&env, &admin);
+    let token = setup_token(&env, &sender);
+
+    let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+    escrow.initialize(&admin);
+    escrow.set_escrow_receipts(&admin, &Some(receipts.address.clone()));
+    receipts.grant_receipt_role(&admin, &Role::Minter, &escrow.address);
+
+    let info = escrow.create(&sender, &recipient, &token.address, &5_000, &DAY, &DAY);
+    let (_, receipt_id) = escrow.get_escrow_receipt(&info.id).unwrap();
+    receipts.transfer_receipt(&recipient, &buyer, &receipt_id);
+
+    escrow.release(&info.id);
+    assert_eq!(token.balance(&buyer), 5_000);
+    assert_eq!(token.balance(&recipient), 0);
+    assert!(escrow.get_escrow_receipt(&info.id).is_none());
+}
@@ -0,0 +1,154 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract, EscrowStatus};
+
+#[contracttype]
+enum HostileKey {
+    Balance(Address),
+    Target,
+    Attempts,
+    Blocked,
+}
+
+/// Token that calls back into the escrow from inside `transfer`, trying to
+/// settle the same escrow a second time
+#[contract]
+struct HostileToken;
+
+#[contractimpl]
+impl HostileToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .instance()
+            .set(&HostileKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&HostileKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    /// Arm the callback against `escrow_id` held by `escrow`
+    pub fn arm(env: Env, escrow: Address, escrow_id: Symbol) {
+        env.storage()
+            .instance()
+            .set(&HostileKey::Target, &(escrow, escrow_id));
+    }
+
+    pub fn attempts(env: Env) -> (u32, u32) {
+        let get = |key: HostileKey| env.storage().instance().get(&key).unwrap_or(0u32);
+        (get(HostileKey::Attempts), get(HostileKey::Blocked))
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let from_balance = Self::balance(env.clone(), from.clone());
+        if from_balance < amount {
+            panic!("insufficient balance");
+        }
+        env.storage()
+            .instance()
+            .set(&HostileKey::Balance(from), &(from_balance - amount));
+        let to_balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .instance()
+            .set(&HostileKey::Balance(to), &(to_balance + amount));
+
+        let target: Option<(Address, Symbol)> = env.storage().instance().get(&HostileKey::Target);
+        if let Some((escrow, escrow_id)) = target {
+            let (attempts, blocked) = Self::attempts(env.clone());
+            let client = EscrowClient::new(&env, &escrow);
+            let reentered = client.try_release(&escrow_id).is_ok()
+                || client.try_refund(&escrow_id).is_ok();
+            env.storage()
+                .instance()
+                .set(&HostileKey::Attempts, &(attempts + 1));
+            if !reentered {
+                env.storage()
+                    .instance()
+                    .set(&HostileKey::Blocked, &(blocked + 1));
+            }
+        }
+    }
+}
+
+struct Setup<'a> {
+    escrow: EscrowClient<'a>,
+    token: HostileTokenClient<'a>,
+    sender: Address,
+    recipient: Address,
+    escrow_id: Symbol,
+}
+
+fn setup_hostile_escrow(env: &Env) -> Setup<'_> {
+    let sender = Address::generate(env);
+    let recipient = Address::generate(env);
+    let token = HostileTokenClient::new(env, &env.register(HostileToken, ()));
+    token.mint(&sender, &10_000);
+
+    let escrow = EscrowClient::new(env, &env.register(EscrowContract, ()));
+    escrow.initialize(&Address::generate(env));
+    let info = escrow.create(&sender, &recipient, &token.address, &1_000, &3_600, &1_800);
+    token.arm(&escrow.address, &info.id);
+    Setup {
+        escrow,
+        token,
+        sender,
+        recipient,
+        escrow_id: info.id,
+    }
+}
+
+#[test]
+fn test_reentrant_token_cannot_release_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_hostile_escrow(&env);
+
+    let info = s.escrow.release(&s.escrow_id);
+    assert_eq!(info.status, EscrowStatus::Released);
+    assert_eq!(s.token.attempts(), (1, 1));
+    assert_eq!(s.token.balance(&s.recipient), 1_000);
+    assert_eq!(s.token.balance(&s.escrow.address), 0);
+    assert_eq!(s.token.balance(&s.sender), 9_000);
+}
+
+#[test]
+fn test_reentrant_token_cannot_refund_after_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_hostile_escrow(&env);
+
+    s.escrow.refund(&s.escrow_id);
+    assert_eq!(s.token.attempts(), (1, 1));
+    assert_eq!(
+        s.escrow.get_escrow(&s.escrow_id).status,
+        EscrowStatus::Refunded
+    );
+    assert_eq!(s.token.balance(&s.sender), 10_000);
+    // The escrow is settled, so a later call is turned away too
+    assert!(s.escrow.try_release(&s.escrow_id).is_err());
+    assert_eq!(s.token.balance(&s.recipient), 0);
+}
+
+#[test]
+fn test_dispute_is_recorded_before_the_fee_is_collected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_hostile_escrow(&env);
+    let admin = s.escrow.get_admin();
+    s.escrow.set_dispute_fee(&admin, &100);
+
+    s.escrow.initiate_dispute(&s.escrow_id, &symbol_short!("late"));
+    // The callback from the fee transfer was turned away and the dispute stands
+    assert_eq!(s.token.attempts(), (1, 1));
+    assert_eq!(
+        s.escrow.get_escrow(&s.escrow_id).status,
+        EscrowStatus::Disputed
+    );
+    assert_eq!(s.token.balance(&s.escrow.address), 1_100);
+}
@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, ReferralEvent};
+use stellar_multisig_contract::referral::{
+    ReferralConfig, ReferralContract, ReferralContractClient,
+};
+use stellar_multisig_contract::testutils::set_ledger_time;
+use stellar_multisig_contract::ConversionContract;
+
+const DAY: u64 = 86_400;
+
+struct Setup<'a> {
+    client: ReferralContractClient<'a>,
+    admin: Address,
+    commission: token::Client<'a>,
+}
+
+fn setup_program(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let commission = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &commission.address()).mint(&admin, &100_000);
+
+    let client = ReferralContractClient::new(env, &env.register(ReferralContract, ()));
+    client.initialize_referral(
+        &admin,
+        &ReferralConfig {
+            commission_token: commission.address(),
+            level1_bps: 2_000,
+            level2_bps: 500,
+            vesting_period: 7 * DAY,
+        },
+    );
+    client.fund_referral_pool(&admin, &100_000);
+    client.grant_referral_role(&admin, &Role::Minter, &admin);
+    Setup {
+        client,
+        admin,
+        commission: token::Client::new(env, &commission.address()),
+    }
+}
+
+#[test]
+fn test_fees_pay_both_referral_tiers_after_vesting() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_program(&env);
+    let (top, middle, trader) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    s.client.register_referral(&middle, &top);
+    s.client.register_referral(&trader, &middle);
+
+    assert_eq!(
+        s.client.report_referral_fee(&s.admin, &trader, &10_000),
+        2_500
+    );
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Referral(ReferralEvent::Accrued(data)) => {
+            assert_eq!(data.referrer, top);
+            assert_eq!(data.tier, 2);
+            assert_eq!(data.commission, 500);
+            assert_eq!(data.vests_at, 7 * DAY);
+        }
+        _ => panic!("expected ReferralEvent::Accrued"),
+    }
+    assert_eq!(s.client.get_referral_balance(&middle).vesting, 2_000);
+    assert!(s.client.try_claim_referral_commission(&middle).is_err());
+
+    set_ledger_time(&env, 7 * DAY);
+    assert_eq!(s.client.claim_referral_commission(&middle), 2_000);
+    assert_eq!(s.client.claim_referral_commission(&top), 500);
+    assert_eq!(s.commission.balance(&middle), 2_000);
+    assert_eq!(s.client.get_referral_pool(), 97_500);
+    assert_eq!(s.client.get_referral_balance(&middle).total_earned, 2_000);
+
+    // Fees from the top of the chain pay nobody
+    assert_eq!(s.client.report_referral_fee(&s.admin, &top, &10_000), 0);
+}
+
+#[test]
+fn test_referral_links_are_set_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_program(&env);
+    let (alice, bob, carol) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    assert!(s.client.try_register_referral(&alice, &alice).is_err());
+    s.client.register_referral(&alice, &bob);
+    assert!(s.client.try_register_referral(&alice, &carol).is_err());
+    assert!(s.client.try_register_referral(&bob, &alice).is_err());
+    assert_eq!(s.client.get_referrer(&alice), Some(bob.clone()));
+
+    // Only minters can report fees
+    assert!(s
+        .client
+        .try_report_referral_fee(&carol, &alice, &10_000)
+        .is_err());
+}
+
+#[test]
+fn test_conversion_fees_accrue_referral_commission() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let s = setup_program(&env);
+    let (referrer, user) = (Address::generate(&env), Address::generate(&env));
+    s.client.register_referral(&user, &referrer);
+
+    let conversion = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    conversion.initialize_conversion(
+        &s.admin,
+        &50u32,
+        &Address::generate(&env),
+        &100i128,
+        &1_000_000_000i128,
+    );
+    // 1 USD = 0.85 EUR
+    conversion.update_rate(
+        &s.admin,
+        &Currency::USD,
+        &Currency::EUR,
+        &85_000_000i128,
+        &3600u64,
+    );
+    conversion.deposit(&user, &Currency::USD, &100_000i128);
+    conversion.set_conversion_referrals(&s.admin, &Some(s.client.address.clone()));
+    s.client
+        .grant_referral_role(&s.admin, &Role::Minter, &conversion.address);
+
+    let tx = conversion.convert_currency(&user, &Currency::USD, &Currency::EUR, &100_000i128);
+    assert_eq!(tx.platform_fee, 425);
+    assert_eq!(s.client.get_referral_balance(&referrer).vesting, 85);
+}
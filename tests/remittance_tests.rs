@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, BytesN, Env, TryFromVal};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, RemittanceEvent};
+use stellar_multisig_contract::remittance::{
+    RemittanceContract, RemittanceContractClient, RemittanceStatus,
+};
+use stellar_multisig_contract::testutils::set_ledger_time;
+
+struct Setup<'a> {
+    client: RemittanceContractClient<'a>,
+    admin: Address,
+    agent: Address,
+    sender: Address,
+    usdc: token::Client<'a>,
+}
+
+fn setup_corridor(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let client = RemittanceContractClient::new(env, &env.register(RemittanceContract, ()));
+    client.initialize_remittance(&admin, &86_400);
+    client.grant_remittance_role(&admin, &Role::Operator, &admin);
+
+    let agent = Address::generate(env);
+    client.set_payout_agent(&admin, &agent, &true);
+
+    let sender = Address::generate(env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &usdc.address()).mint(&sender, &1_000);
+    Setup {
+        client,
+        admin,
+        agent,
+        sender,
+        usdc: token::Client::new(env, &usdc.address()),
+    }
+}
+
+fn send(env: &Env, setup: &Setup, amount: i128) -> u64 {
+    setup.client.send_remittance(
+        &setup.sender,
+        &setup.agent,
+        &setup.usdc.address,
+        &amount,
+        &BytesN::from_array(env, &[1u8; 32]),
+    )
+}
+
+#[test]
+fn test_agent_attestation_releases_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_corridor(&env);
+
+    assert!(setup
+        .client
+        .try_send_remittance(
+            &setup.sender,
+            &Address::generate(&env),
+            &setup.usdc.address,
+            &100,
+            &BytesN::from_array(&env, &[1u8; 32]),
+        )
+        .is_err());
+
+    let id = send(&env, &setup, 400);
+    assert_eq!(setup.usdc.balance(&setup.client.address), 400);
+    assert_eq!(setup.client.get_remittance(&id).deadline, 86_400);
+
+    assert!(setup
+        .client
+        .try_attest_delivery(&Address::generate(&env), &id)
+        .is_err());
+    setup.client.attest_delivery(&setup.agent, &id);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Remittance(RemittanceEvent::Delivered(data)) => {
+            assert_eq!(data.receiver, setup.agent);
+            assert_eq!(data.amount, 400);
+        }
+        _ => panic!("expected RemittanceEvent::Delivered"),
+    }
+
+    assert_eq!(setup.usdc.balance(&setup.agent), 400);
+    assert_eq!(
+        setup.client.get_remittance(&id).status,
+        RemittanceStatus::Delivered
+    );
+    assert!(setup.client.try_refund_remittance(&id).is_err());
+}
+
+#[test]
+fn test_undelivered_remittance_refunds_after_timeout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_corridor(&env);
+
+    let id = send(&env, &setup, 400);
+    set_ledger_time(&env, 86_399);
+    assert!(setup.client.try_refund_remittance(&id).is_err());
+
+    set_ledger_time(&env, 86_400);
+    assert!(setup.client.try_attest_delivery(&setup.agent, &id).is_err());
+    setup.client.refund_remittance(&id);
+    assert_eq!(setup.usdc.balance(&setup.sender), 1_000);
+    assert_eq!(
+        setup.client.get_remittance(&id).status,
+        RemittanceStatus::Refunded
+    );
+}
+
+#[test]
+fn test_arbiter_rules_on_stuck_remittance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_corridor(&env);
+    let arbiter = Address::generate(&env);
+    setup
+        .client
+        .grant_remittance_role(&setup.admin, &Role::Attestor, &arbiter);
+
+    let delivered = send(&env, &setup, 300);
+    let failed = send(&env, &setup, 200);
+    assert!(setup
+        .client
+        .try_arbitrate_delivery(&setup.agent, &delivered, &true)
+        .is_err());
+
+    setup.client.arbitrate_delivery(&arbiter, &delivered, &true);
+    setup.client.arbitrate_delivery(&arbiter, &failed, &false);
+    assert_eq!(setup.usdc.balance(&setup.agent), 300);
+    assert_eq!(setup.usdc.balance(&setup.sender), 700);
+
+    // Deregistered agents cannot take new remittances
+    setup
+        .client
+        .set_payout_agent(&setup.admin, &setup.agent, &false);
+    assert!(!setup.client.is_payout_agent(&setup.agent));
+    assert!(setup
+        .client
+        .try_send_remittance(
+            &setup.sender,
+            &setup.agent,
+            &setup.usdc.address,
+            &100,
+            &BytesN::from_array(&env, &[1u8; 32]),
+        )
+        .is_err());
+}
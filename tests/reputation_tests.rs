@@ -0,0 +1,56 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::reputation::{
+    ReputationContract, ReputationContractClient, TradeOutcome, NEUTRAL_REPUTATION_SCORE,
+};
+
+fn setup_reputation(env: &Env) -> (ReputationContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let recorder = Address::generate(env);
+    let client = ReputationContractClient::new(env, &env.register(ReputationContract, ()));
+    client.initialize_reputation(&admin);
+    client.grant_reputation_role(&admin, &Role::Operator, &recorder);
+    (client, recorder)
+}
+
+#[test]
+fn test_score_follows_recorded_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, recorder) = setup_reputation(&env);
+    let trader = Address::generate(&env);
+    assert_eq!(
+        client.get_reputation_score(&trader),
+        NEUTRAL_REPUTATION_SCORE
+    );
+
+    // One completed trade only nudges the neutral prior
+    client.record_outcome(&recorder, &trader, &TradeOutcome::Completed);
+    assert_eq!(client.get_reputation_score(&trader), 583);
+
+    for _ in 0..4 {
+        client.record_outcome(&recorder, &trader, &TradeOutcome::Completed);
+    }
+    client.record_outcome(&recorder, &trader, &TradeOutcome::Defaulted);
+    let reputation = client.get_reputation(&trader);
+    assert_eq!(reputation.completed, 5);
+    assert_eq!(reputation.defaulted, 1);
+    // (5 * 1000 + 5 * 500) / 11
+    assert_eq!(reputation.score, 681);
+}
+
+#[test]
+fn test_record_outcome_requires_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _recorder) = setup_reputation(&env);
+    let trader = Address::generate(&env);
+
+    assert!(client
+        .try_record_outcome(&trader, &trader, &TradeOutcome::Completed)
+        .is_err());
+    assert_eq!(client.get_reputation(&trader).completed, 0);
+}
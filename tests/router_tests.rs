@@ -0,0 +1,130 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, vec, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+use stellar_multisig_contract::conversion::{
+    ConversionContract, ConversionContractClient, ConversionTx, Currency,
+};
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract, EscrowInfo};
+use stellar_multisig_contract::router::{RouteCall, RouterContract, RouterContractClient};
+
+fn step(env: &Env, contract: &Address, function: &str, args: Vec<Val>) -> RouteCall {
+    RouteCall {
+        contract: contract.clone(),
+        function: Symbol::new(env, function),
+        args,
+    }
+}
+
+fn setup_conversion(env: &Env) -> ConversionContractClient<'_> {
+    let admin = Address::generate(env);
+    let client = ConversionContractClient::new(env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(&admin, &0, &admin, &1, &1_000_000);
+    client.update_rate(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000,
+        &3600,
+    );
+    client
+}
+
+#[test]
+fn test_route_runs_steps_in_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let router = RouterContractClient::new(&env, &env.register(RouterContract, ()));
+    let conversion = setup_conversion(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let escrow = EscrowClient::new(&env, &env.register(EscrowContract, ()));
+    escrow.initialize(&Address::generate(&env));
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&user, &1_000);
+
+    let calls = vec![
+        &env,
+        step(
+            &env,
+            &conversion.address,
+            "deposit",
+            (user.clone(), Currency::USD, 500i128).into_val(&env),
+        ),
+        step(
+            &env,
+            &conversion.address,
+            "convert_currency",
+            (user.clone(), Currency::USD, Currency::NGN, 200i128).into_val(&env),
+        ),
+        step(
+            &env,
+            &escrow.address,
+            "create",
+            (
+                user.clone(),
+                recipient.clone(),
+                asset.address(),
+                400i128,
+                3600u64,
+                1800u64,
+            )
+                .into_val(&env),
+        ),
+    ];
+    let results = router.execute_route(&user, &calls);
+
+    assert_eq!(results.len(), 3);
+    let tx = ConversionTx::try_from_val(&env, &results.get(1).unwrap()).unwrap();
+    assert_eq!(tx.amount_received, 160_000);
+    let info = EscrowInfo::try_from_val(&env, &results.get(2).unwrap()).unwrap();
+    assert_eq!(info.recipient, recipient);
+    assert_eq!(
+        conversion
+            .get_user_balance(&user)
+            .balances
+            .get(Currency::USD),
+        Some(300)
+    );
+    assert_eq!(
+        token::Client::new(&env, &asset.address()).balance(&user),
+        600
+    );
+}
+
+#[test]
+fn test_failing_step_reverts_whole_route() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let router = RouterContractClient::new(&env, &env.register(RouterContract, ()));
+    let conversion = setup_conversion(&env);
+    let user = Address::generate(&env);
+
+    // The conversion exceeds the deposit, so the deposit must not stick either
+    let calls = vec![
+        &env,
+        step(
+            &env,
+            &conversion.address,
+            "deposit",
+            (user.clone(), Currency::USD, 500i128).into_val(&env),
+        ),
+        step(
+            &env,
+            &conversion.address,
+            "convert_currency",
+            (user.clone(), Currency::USD, Currency::NGN, 900i128).into_val(&env),
+        ),
+    ];
+    assert!(router.try_execute_route(&user, &calls).is_err());
+    assert_eq!(
+        conversion
+            .get_user_balance(&user)
+            .balances
+            .get(Currency::USD),
+        None
+    );
+
+    assert!(router.try_execute_route(&user, &Vec::new(&env)).is_err());
+}
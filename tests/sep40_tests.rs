@@ -0,0 +1,119 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency};
+use stellar_multisig_contract::sep40::{Asset, PriceData, RateFeed};
+use stellar_multisig_contract::testutils::advance_ledger;
+use stellar_multisig_contract::ConversionContract;
+
+// Reflector-style feed with 14 decimals
+#[contract]
+pub struct MockPriceFeed;
+
+#[contractimpl]
+impl MockPriceFeed {
+    pub fn set_price(env: Env, asset: Asset, price: i128) {
+        let data = PriceData {
+            price,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&asset, &data);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&asset)
+    }
+}
+
+fn setup_feed(
+    env: &Env,
+) -> (
+    ConversionContractClient<'_>,
+    Address,
+    MockPriceFeedClient<'_>,
+) {
+    let admin = Address::generate(env);
+    let client = ConversionContractClient::new(env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(&admin, &0, &Address::generate(env), &100, &1_000_000_000);
+
+    let feed = MockPriceFeedClient::new(env, &env.register(MockPriceFeed, ()));
+    (client, admin, feed)
+}
+
+#[test]
+fn test_conversion_reads_sep40_feed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, feed) = setup_feed(&env);
+
+    // 1 NGN = 0.00125 USD on a USD-based feed
+    let ngn = Asset::Other(symbol_short!("NGN"));
+    feed.set_price(&ngn, &125_000_000_000);
+    let rate_feed = RateFeed {
+        oracle: feed.address.clone(),
+        asset: ngn,
+        invert: false,
+        max_age: 600,
+    };
+    client.set_rate_feed(
+        &admin,
+        &Currency::NGN,
+        &Currency::USD,
+        &Some(rate_feed.clone()),
+    );
+    client.set_rate_feed(
+        &admin,
+        &Currency::USD,
+        &Currency::NGN,
+        &Some(RateFeed {
+            invert: true,
+            ..rate_feed
+        }),
+    );
+
+    let user = Address::generate(&env);
+    client.deposit(&user, &Currency::NGN, &800_000);
+    client.deposit(&user, &Currency::USD, &1_000);
+
+    let conversion = client.convert_currency(&user, &Currency::NGN, &Currency::USD, &800_000);
+    assert_eq!(conversion.rate, 125_000);
+    assert_eq!(conversion.amount_received, 1_000);
+
+    let conversion = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &1_000);
+    assert_eq!(conversion.rate, 80_000_000_000);
+    assert_eq!(conversion.amount_received, 800_000);
+}
+
+#[test]
+fn test_stale_sep40_feed_falls_back_to_admin_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, feed) = setup_feed(&env);
+
+    let ngn = Asset::Other(symbol_short!("NGN"));
+    feed.set_price(&ngn, &125_000_000_000);
+    client.set_rate_feed(
+        &admin,
+        &Currency::NGN,
+        &Currency::USD,
+        &Some(RateFeed {
+            oracle: feed.address.clone(),
+            asset: ngn,
+            invert: false,
+            max_age: 600,
+        }),
+    );
+
+    advance_ledger(&env, 601);
+    client.update_rate(&admin, &Currency::NGN, &Currency::USD, &120_000, &3600);
+
+    let user = Address::generate(&env);
+    client.deposit(&user, &Currency::NGN, &800_000);
+    let conversion = client.convert_currency(&user, &Currency::NGN, &Currency::USD, &800_000);
+    assert_eq!(conversion.rate, 120_000);
+}
@@ -0,0 +1,306 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::auth::{Context, ContractContext};
+use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::{symbol_short, vec, Address, BytesN, Env, IntoVal, Symbol, Vec};
+use stellar_multisig_contract::errors::AppError;
+use stellar_multisig_contract::smart_wallet::{
+    RecoveryConfig, SmartWalletContract, SmartWalletContractClient, SpendPolicy, WalletSignature,
+};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+struct Wallet<'a> {
+    client: SmartWalletContractClient<'a>,
+    owner: SigningKey,
+    cosigner: SigningKey,
+    token: Address,
+}
+
+fn setup_wallet(env: &Env, with_cosigner: bool) -> Wallet<'_> {
+    let owner = SigningKey::from_bytes(&[1u8; 32]);
+    let cosigner = SigningKey::from_bytes(&[2u8; 32]);
+    let client = SmartWalletContractClient::new(env, &env.register(SmartWalletContract, ()));
+    let cosigner_key = BytesN::from_array(env, &cosigner.verifying_key().to_bytes());
+    client.initialize_wallet(
+        &BytesN::from_array(env, &owner.verifying_key().to_bytes()),
+        &with_cosigner.then_some(cosigner_key),
+    );
+
+    let token = Address::generate(env);
+    env.mock_all_auths();
+    client.set_wallet_spend_policy(
+        &token,
+        &Some(SpendPolicy {
+            daily_limit: 1_000,
+            cosign_threshold: 600,
+        }),
+    );
+    env.set_auths(&[]);
+    Wallet {
+        client,
+        owner,
+        cosigner,
+        token,
+    }
+}
+
+fn sign(env: &Env, key: &SigningKey, payload: &BytesN<32>) -> WalletSignature {
+    WalletSignature {
+        public_key: BytesN::from_array(env, &key.verifying_key().to_bytes()),
+        signature: BytesN::from_array(env, &key.sign(&payload.to_array()).to_bytes()),
+    }
+}
+
+fn call(contract: &Address, fn_name: Symbol, args: Vec<soroban_sdk::Val>) -> Context {
+    Context::Contract(ContractContext {
+        contract: contract.clone(),
+        fn_name,
+        args,
+    })
+}
+
+fn transfer(env: &Env, wallet: &Wallet, to: &Address, amount: i128) -> Context {
+    let args = (wallet.client.address.clone(), to.clone(), amount).into_val(env);
+    call(&wallet.token, symbol_short!("transfer"), args)
+}
+
+fn check_auth(
+    env: &Env,
+    wallet: &Wallet,
+    signers: &[&SigningKey],
+    contexts: Vec<Context>,
+) -> Result<(), AppError> {
+    let payload = BytesN::random(env);
+    let mut signatures: Vec<WalletSignature> = Vec::new(env);
+    for signer in signers {
+        signatures.push_back(sign(env, signer, &payload));
+    }
+    env.try_invoke_contract_check_auth::<AppError>(
+        &wallet.client.address,
+        &payload,
+        signatures.into_val(env),
+        &contexts,
+    )
+    .map_err(|err| err.unwrap())
+}
+
+#[test]
+fn test_spends_beyond_policy_need_cosigner() {
+    let env = Env::default();
+    let wallet = setup_wallet(&env, true);
+    let merchant = Address::generate(&env);
+
+    let small = vec![&env, transfer(&env, &wallet, &merchant, 500)];
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], small.clone()),
+        Ok(())
+    );
+    assert_eq!(wallet.client.get_wallet_spent_today(&wallet.token), 500);
+
+    // Over the single-spend threshold
+    let large = vec![&env, transfer(&env, &wallet, &merchant, 700)];
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], large.clone()),
+        Err(AppError::CosignerRequired)
+    );
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner, &wallet.cosigner], large),
+        Ok(())
+    );
+
+    // Over the daily limit: 500 + 700 already spent today
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], small.clone()),
+        Err(AppError::CosignerRequired)
+    );
+    advance_ledger(&env, 24 * 60 * 60);
+    assert_eq!(wallet.client.get_wallet_spent_today(&wallet.token), 0);
+    assert_eq!(check_auth(&env, &wallet, &[&wallet.owner], small), Ok(()));
+
+    // Approvals count as spends
+    let args = (
+        wallet.client.address.clone(),
+        merchant.clone(),
+        900i128,
+        1_000u32,
+    )
+        .into_val(&env);
+    let approve = vec![&env, call(&wallet.token, symbol_short!("approve"), args)];
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], approve),
+        Err(AppError::CosignerRequired)
+    );
+}
+
+#[test]
+fn test_signers_and_settings_changes() {
+    let env = Env::default();
+    let wallet = setup_wallet(&env, true);
+    let merchant = Address::generate(&env);
+    let contexts = vec![&env, transfer(&env, &wallet, &merchant, 100)];
+
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.cosigner], contexts.clone()),
+        Err(AppError::MissingOwnerSignature)
+    );
+    let stranger = SigningKey::from_bytes(&[9u8; 32]);
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner, &stranger], contexts),
+        Err(AppError::UnknownSigner)
+    );
+
+    // Changing the wallet's own settings needs every configured signer
+    let settings = vec![
+        &env,
+        call(
+            &wallet.client.address,
+            Symbol::new(&env, "set_wallet_cosigner"),
+            (Option::<BytesN<32>>::None,).into_val(&env),
+        ),
+    ];
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], settings.clone()),
+        Err(AppError::CosignerRequired)
+    );
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner, &wallet.cosigner], settings),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_destination_allowlist_without_cosigner() {
+    let env = Env::default();
+    let wallet = setup_wallet(&env, false);
+    let merchant = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    wallet.client.set_wallet_allowlist_enabled(&true);
+    wallet.client.set_wallet_destination(&merchant, &true);
+    env.set_auths(&[]);
+    assert!(wallet.client.is_wallet_destination_allowed(&merchant));
+
+    assert_eq!(
+        check_auth(
+            &env,
+            &wallet,
+            &[&wallet.owner],
+            vec![&env, transfer(&env, &wallet, &merchant, 100)]
+        ),
+        Ok(())
+    );
+    // Without a co-signer, policy breaches are refused outright
+    assert_eq!(
+        check_auth(
+            &env,
+            &wallet,
+            &[&wallet.owner],
+            vec![&env, transfer(&env, &wallet, &stranger, 100)]
+        ),
+        Err(AppError::PolicyViolation)
+    );
+    assert_eq!(
+        check_auth(
+            &env,
+            &wallet,
+            &[&wallet.owner],
+            vec![&env, transfer(&env, &wallet, &merchant, 2_000)]
+        ),
+        Err(AppError::PolicyViolation)
+    );
+}
+
+fn setup_recovery(env: &Env, wallet: &Wallet) -> (Address, Address, Address) {
+    let (alice, bob, carol) = (
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    );
+    env.mock_all_auths();
+    wallet.client.set_wallet_recovery(&Some(RecoveryConfig {
+        guardians: vec![env, alice.clone(), bob.clone(), carol.clone()],
+        threshold: 2,
+        delay: 3 * 24 * 60 * 60,
+    }));
+    (alice, bob, carol)
+}
+
+#[test]
+fn test_guardians_rotate_owner_key_after_delay() {
+    let env = Env::default();
+    let wallet = setup_wallet(&env, true);
+    let (alice, bob, _carol) = setup_recovery(&env, &wallet);
+    let new_owner = SigningKey::from_bytes(&[3u8; 32]);
+    let new_key = BytesN::from_array(&env, &new_owner.verifying_key().to_bytes());
+
+    assert!(wallet
+        .client
+        .try_approve_wallet_recovery(&Address::generate(&env), &new_key)
+        .is_err());
+    wallet.client.approve_wallet_recovery(&alice, &new_key);
+    assert!(wallet
+        .client
+        .try_approve_wallet_recovery(&alice, &new_key)
+        .is_err());
+    assert_eq!(
+        wallet
+            .client
+            .get_pending_wallet_recovery()
+            .unwrap()
+            .executable_at,
+        0
+    );
+    assert!(wallet.client.try_execute_wallet_recovery().is_err());
+
+    wallet.client.approve_wallet_recovery(&bob, &new_key);
+    assert!(wallet.client.try_execute_wallet_recovery().is_err());
+    advance_ledger(&env, 3 * 24 * 60 * 60);
+    wallet.client.execute_wallet_recovery();
+    assert_eq!(wallet.client.get_wallet_owner_key(), new_key);
+    assert_eq!(wallet.client.get_pending_wallet_recovery(), None);
+
+    // The old key no longer signs for the wallet
+    env.set_auths(&[]);
+    let contexts = vec![&env, transfer(&env, &wallet, &Address::generate(&env), 100)];
+    assert_eq!(
+        check_auth(&env, &wallet, &[&wallet.owner], contexts.clone()),
+        Err(AppError::UnknownSigner)
+    );
+    assert_eq!(check_auth(&env, &wallet, &[&new_owner], contexts), Ok(()));
+}
+
+#[test]
+fn test_current_key_vetoes_recovery() {
+    let env = Env::default();
+    let wallet = setup_wallet(&env, true);
+    let (alice, _bob, carol) = setup_recovery(&env, &wallet);
+    let new_key = BytesN::from_array(&env, &[4u8; 32]);
+
+    wallet.client.approve_wallet_recovery(&alice, &new_key);
+    assert!(wallet
+        .client
+        .try_approve_wallet_recovery(&carol, &BytesN::from_array(&env, &[5u8; 32]))
+        .is_err());
+    wallet.client.approve_wallet_recovery(&carol, &new_key);
+
+    // The veto needs only the owner key, even with a co-signer configured
+    env.set_auths(&[]);
+    let veto = vec![
+        &env,
+        call(
+            &wallet.client.address,
+            Symbol::new(&env, "cancel_wallet_recovery"),
+            Vec::new(&env),
+        ),
+    ];
+    assert_eq!(check_auth(&env, &wallet, &[&wallet.owner], veto), Ok(()));
+
+    env.mock_all_auths();
+    wallet.client.cancel_wallet_recovery();
+    assert_eq!(wallet.client.get_pending_wallet_recovery(), None);
+    advance_ledger(&env, 3 * 24 * 60 * 60);
+    assert!(wallet.client.try_execute_wallet_recovery().is_err());
+    assert_ne!(wallet.client.get_wallet_owner_key(), new_key);
+}
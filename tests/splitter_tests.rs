@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, vec, Address, Env, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, SplitEvent};
+use stellar_multisig_contract::splitter::{SplitterContract, SplitterContractClient};
+
+fn setup_split(
+    env: &Env,
+) -> (
+    SplitterContractClient<'_>,
+    Address,
+    Address,
+    Address,
+    Address,
+    u64,
+) {
+    let controller = Address::generate(env);
+    let alice = Address::generate(env);
+    let bob = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &token.address()).mint(&controller, &100_000);
+
+    let client = SplitterContractClient::new(env, &env.register(SplitterContract, ()));
+    // Alice takes 70%, Bob 30%
+    let split_id = client.create_split(
+        &controller,
+        &vec![env, alice.clone(), bob.clone()],
+        &vec![env, 70, 30],
+    );
+
+    (client, controller, alice, bob, token.address(), split_id)
+}
+
+#[test]
+fn test_release_pays_pro_rata_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, controller, alice, bob, token, split_id) = setup_split(&env);
+    let balances = token::Client::new(&env, &token);
+
+    client.deposit_to_split(&controller, &split_id, &token, &10_000);
+    assert_eq!(client.release_share(&split_id, &token, &alice), 7_000);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Split(SplitEvent::Released(data)) => {
+            assert_eq!(data.payee, alice);
+            assert_eq!(data.amount, 7_000);
+        }
+        _ => panic!("expected SplitEvent::Released"),
+    }
+    assert_eq!(balances.balance(&alice), 7_000);
+    assert!(client.try_release_share(&split_id, &token, &alice).is_err());
+
+    // Later deposits accrue on top of what was already released
+    client.deposit_to_split(&controller, &split_id, &token, &5_000);
+    assert_eq!(
+        client.get_releasable_amount(&split_id, &token, &alice),
+        3_500
+    );
+    assert_eq!(client.distribute(&split_id, &token), 8_000);
+    assert_eq!(balances.balance(&alice), 10_500);
+    assert_eq!(balances.balance(&bob), 4_500);
+}
+
+#[test]
+fn test_update_split_settles_old_payees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, controller, alice, bob, token, split_id) = setup_split(&env);
+    let carol = Address::generate(&env);
+    let balances = token::Client::new(&env, &token);
+
+    client.deposit_to_split(&controller, &split_id, &token, &1_000);
+    client.update_split(&split_id, &vec![&env, carol.clone()], &vec![&env, 1]);
+    assert_eq!(balances.balance(&alice), 700);
+    assert_eq!(balances.balance(&bob), 300);
+
+    client.deposit_to_split(&controller, &split_id, &token, &2_000);
+    assert_eq!(client.release_share(&split_id, &token, &carol), 2_000);
+    assert!(client
+        .try_get_releasable_amount(&split_id, &token, &alice)
+        .is_err());
+}
+
+#[test]
+fn test_invalid_payee_lists_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let controller = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let client = SplitterContractClient::new(&env, &env.register(SplitterContract, ()));
+
+    assert!(client
+        .try_create_split(&controller, &vec![&env, payee.clone()], &vec![&env, 1, 2])
+        .is_err());
+    assert!(client
+        .try_create_split(
+            &controller,
+            &vec![&env, payee.clone(), payee.clone()],
+            &vec![&env, 1, 1]
+        )
+        .is_err());
+    assert!(client
+        .try_create_split(&controller, &vec![&env, payee], &vec![&env, 0])
+        .is_err());
+}
@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, vec, Address, BytesN, Env, IntoVal, Symbol};
+use stellar_multisig_contract::access::Role;
+use stellar_multisig_contract::email_to_wallet::{
+    EmailToWalletContract, EmailToWalletContractClient,
+};
+use stellar_multisig_contract::sponsorship::{
+    SponsorQuota, SponsorshipContract, SponsorshipContractClient,
+};
+
+struct Setup<'a> {
+    client: SponsorshipContractClient<'a>,
+    admin: Address,
+    relayer: Address,
+    fee_token: token::Client<'a>,
+    registry: EmailToWalletContractClient<'a>,
+}
+
+fn setup_sponsorship(env: &Env) -> Setup<'_> {
+    let admin = Address::generate(env);
+    let relayer = Address::generate(env);
+    let fee_token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &fee_token.address()).mint(&admin, &1_000);
+
+    let client = SponsorshipContractClient::new(env, &env.register(SponsorshipContract, ()));
+    client.initialize_sponsorship(&admin, &fee_token.address(), &100);
+    client.grant_sponsorship_role(&admin, &Role::Operator, &relayer);
+    client.fund_sponsorship(&admin, &250);
+
+    let registry = EmailToWalletContractClient::new(env, &env.register(EmailToWalletContract, ()));
+    registry.initialize_email_registry(&admin, &Address::generate(env));
+    client.set_sponsored_function(
+        &admin,
+        &registry.address,
+        &Symbol::new(env, "register_email"),
+        &true,
+    );
+
+    Setup {
+        client,
+        admin,
+        relayer,
+        fee_token: token::Client::new(env, &fee_token.address()),
+        registry,
+    }
+}
+
+fn relay_register(env: &Env, setup: &Setup, user: &Address, seed: u8) -> bool {
+    let email_hash = BytesN::from_array(env, &[seed; 32]);
+    setup
+        .client
+        .try_relay_sponsored(
+            &setup.relayer,
+            user,
+            &setup.registry.address,
+            &Symbol::new(env, "register_email"),
+            &vec![env, email_hash.into_val(env), user.into_val(env)],
+        )
+        .is_ok()
+}
+
+#[test]
+fn test_relayed_calls_reimbursed_from_credits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sponsorship(&env);
+    let user = Address::generate(&env);
+
+    // Unregistered users are not sponsored
+    assert!(!relay_register(&env, &setup, &user, 1));
+    setup
+        .client
+        .set_sponsored_user_quota(&setup.relayer, &user, &5);
+
+    assert!(relay_register(&env, &setup, &user, 1));
+    assert_eq!(
+        setup
+            .registry
+            .get_registration(&BytesN::from_array(&env, &[1u8; 32]))
+            .unwrap()
+            .wallet,
+        user
+    );
+    assert_eq!(setup.fee_token.balance(&setup.relayer), 100);
+    assert_eq!(setup.client.get_sponsor_credits(), 150);
+
+    assert!(relay_register(&env, &setup, &user, 2));
+    // 50 credits left, less than one call
+    assert!(!relay_register(&env, &setup, &user, 3));
+    assert_eq!(
+        setup.client.get_sponsored_user_quota(&user),
+        Some(SponsorQuota { limit: 5, used: 2 })
+    );
+
+    // Only operators relay, and only allowed functions
+    assert!(setup
+        .client
+        .try_relay_sponsored(
+            &Address::generate(&env),
+            &user,
+            &setup.registry.address,
+            &Symbol::new(&env, "register_email"),
+            &vec![&env],
+        )
+        .is_err());
+    assert!(setup
+        .client
+        .try_relay_sponsored(
+            &setup.relayer,
+            &user,
+            &setup.registry.address,
+            &Symbol::new(&env, "set_email_backend"),
+            &vec![&env, user.into_val(&env)],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_user_quota_caps_sponsored_calls() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sponsorship(&env);
+    let user = Address::generate(&env);
+
+    setup
+        .client
+        .set_sponsored_user_quota(&setup.relayer, &user, &1);
+    assert!(relay_register(&env, &setup, &user, 1));
+    assert!(!relay_register(&env, &setup, &user, 2));
+
+    // Raising the quota keeps the usage count
+    setup
+        .client
+        .set_sponsored_user_quota(&setup.relayer, &user, &2);
+    assert!(relay_register(&env, &setup, &user, 2));
+    assert_eq!(
+        setup.client.get_sponsored_user_quota(&user).unwrap().used,
+        2
+    );
+
+    setup
+        .client
+        .withdraw_sponsorship(&setup.admin, &setup.admin, &50);
+    assert_eq!(setup.client.get_sponsor_credits(), 0);
+}
@@ -0,0 +1,50 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::storage::Persistent as _;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{symbol_short, Address, Env};
+use stellar_multisig_contract::conversion::{ConversionContractClient, Currency, DataKey};
+use stellar_multisig_contract::storage::PERSISTENT_LIFETIME_THRESHOLD;
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient};
+use stellar_multisig_contract::ConversionContract;
+
+#[test]
+fn test_token_balances_are_persistent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let token = TokenContractClient::new(&env, &env.register(TokenContract, ()));
+    token.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    token.mint(&admin, &holder, &5_000);
+
+    env.as_contract(&token.address, || {
+        assert!(!env.storage().instance().has(&holder));
+        assert!(env.storage().persistent().has(&holder));
+        assert!(env.storage().persistent().get_ttl(&holder) > PERSISTENT_LIFETIME_THRESHOLD);
+    });
+    assert_eq!(token.balance(&holder), 5_000);
+}
+
+#[test]
+fn test_conversion_balances_are_persistent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = ConversionContractClient::new(&env, &env.register(ConversionContract, ()));
+    client.initialize_conversion(
+        &admin,
+        &50u32,
+        &Address::generate(&env),
+        &100i128,
+        &1_000_000_000i128,
+    );
+    client.deposit(&user, &Currency::USD, &100_000i128);
+
+    let key = DataKey::Balance(user.clone());
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&key));
+        assert!(env.storage().persistent().get_ttl(&key) > PERSISTENT_LIFETIME_THRESHOLD);
+    });
+}
@@ -0,0 +1,98 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env};
+use stellar_multisig_contract::stream::{StreamContract, StreamContractClient, StreamStatus};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+fn setup_stream(
+    env: &Env,
+) -> (
+    StreamContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+    u64,
+) {
+    let sender = Address::generate(env);
+    let recipient = Address::generate(env);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &token.address()).mint(&sender, &100_000);
+
+    let client = StreamContractClient::new(env, &env.register(StreamContract, ()));
+    // 86_400 tokens over one day, one token per second
+    let start = env.ledger().timestamp();
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token.address(),
+        &86_400,
+        &start,
+        &(start + 86_400),
+    );
+
+    (
+        client,
+        sender,
+        recipient,
+        token::Client::new(env, &token.address()),
+        stream_id,
+    )
+}
+
+#[test]
+fn test_stream_accrues_per_second() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _sender, recipient, token, stream_id) = setup_stream(&env);
+    assert_eq!(client.get_stream(&stream_id).rate_per_second, 1);
+
+    advance_ledger(&env, 3_600);
+    assert_eq!(client.withdraw_streamed(&stream_id), 3_600);
+    assert_eq!(token.balance(&recipient), 3_600);
+
+    advance_ledger(&env, 100_000);
+    assert_eq!(client.withdraw_streamed(&stream_id), 82_800);
+    assert_eq!(
+        client.get_stream(&stream_id).status,
+        StreamStatus::Completed
+    );
+}
+
+#[test]
+fn test_paused_stream_stops_accruing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _sender, _recipient, _token, stream_id) = setup_stream(&env);
+
+    advance_ledger(&env, 1_000);
+    client.pause_stream(&stream_id);
+    advance_ledger(&env, 5_000);
+    assert_eq!(client.get_streamed_amount(&stream_id), 1_000);
+
+    client.resume_stream(&stream_id);
+    advance_ledger(&env, 500);
+    assert_eq!(client.get_streamed_amount(&stream_id), 1_500);
+
+    // The paused time is added to the end of the stream
+    advance_ledger(&env, 84_899);
+    assert_eq!(client.get_withdrawable_amount(&stream_id), 86_399);
+}
+
+#[test]
+fn test_cancel_stream_splits_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, sender, recipient, token, stream_id) = setup_stream(&env);
+
+    advance_ledger(&env, 600);
+    client.withdraw_streamed(&stream_id);
+    advance_ledger(&env, 400);
+
+    assert_eq!(client.cancel_stream(&stream_id), (400, 85_400));
+    assert_eq!(token.balance(&recipient), 1_000);
+    assert_eq!(token.balance(&sender), 100_000 - 1_000);
+    assert!(client.try_withdraw_streamed(&stream_id).is_err());
+    assert_eq!(client.get_account_streams(&recipient).len(), 1);
+}
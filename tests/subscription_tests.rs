@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{token, Address, Env, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, SubscriptionEvent};
+use stellar_multisig_contract::keeper::KeeperTip;
+use stellar_multisig_contract::subscription::{
+    SubscriptionContract, SubscriptionContractClient, SubscriptionStatus,
+};
+use stellar_multisig_contract::testutils::advance_ledger;
+
+const MONTH: u64 = 30 * 86_400;
+
+fn setup_subscription(
+    env: &Env,
+) -> (
+    SubscriptionContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+    u64,
+) {
+    let merchant = Address::generate(env);
+    let subscriber = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &token.address()).mint(&subscriber, &10_000);
+
+    let client = SubscriptionContractClient::new(env, &env.register(SubscriptionContract, ()));
+    let plan_id = client.create_plan(&merchant, &token.address(), &1_000, &MONTH);
+    // Allows three monthly charges
+    let subscription_id = client.subscribe(
+        &subscriber,
+        &plan_id,
+        &3_000,
+        &(env.ledger().sequence() + 1_000),
+    );
+
+    (
+        client,
+        merchant,
+        subscriber,
+        token::Client::new(env, &token.address()),
+        subscription_id,
+    )
+}
+
+#[test]
+fn test_charge_when_due_emits_invoice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, subscriber, token, id) = setup_subscription(&env);
+    let keeper = Address::generate(&env);
+
+    assert_eq!(client.charge(&keeper, &id), 1_000);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Subscription(SubscriptionEvent::Invoiced(data)) => {
+            assert_eq!(data.invoice_number, 1);
+            assert_eq!(data.amount, 1_000);
+            assert_eq!(data.period_end - data.period_start, MONTH);
+        }
+        _ => panic!("expected SubscriptionEvent::Invoiced"),
+    }
+    assert_eq!(token.balance(&merchant), 1_000);
+
+    // Not due again until the interval has passed
+    assert!(client.try_charge(&keeper, &id).is_err());
+    advance_ledger(&env, MONTH);
+    client.charge(&keeper, &id);
+    assert_eq!(token.balance(&subscriber), 8_000);
+    assert_eq!(client.get_subscription(&id).invoice_count, 2);
+}
+
+#[test]
+fn test_charges_stop_at_spending_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, _subscriber, token, id) = setup_subscription(&env);
+    let keeper = Address::generate(&env);
+
+    for _ in 0..3 {
+        client.charge(&keeper, &id);
+        advance_ledger(&env, MONTH);
+    }
+    assert!(client.try_charge(&keeper, &id).is_err());
+    assert_eq!(token.balance(&merchant), 3_000);
+}
+
+#[test]
+fn test_cancelled_subscription_cannot_be_charged() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _merchant, subscriber, token, id) = setup_subscription(&env);
+    let keeper = Address::generate(&env);
+
+    client.charge(&keeper, &id);
+    client.cancel_subscription(&id);
+    assert_eq!(
+        client.get_subscription(&id).status,
+        SubscriptionStatus::Cancelled
+    );
+    assert_eq!(token.allowance(&subscriber, &client.address), 0);
+
+    advance_ledger(&env, MONTH);
+    assert!(client.try_charge(&keeper, &id).is_err());
+}
+
+#[test]
+fn test_keeper_tip_paid_out_of_charge() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, merchant, subscriber, token, id) = setup_subscription(&env);
+    let keeper = Address::generate(&env);
+    let plan_id = client.get_subscription(&id).plan_id;
+
+    let tip = KeeperTip {
+        tip_bps: 50,
+        max_tip: 100,
+    };
+    client.set_plan_keeper_tip(&plan_id, &Some(tip.clone()));
+    assert_eq!(client.get_plan_keeper_tip(&plan_id), Some(tip));
+
+    assert_eq!(client.charge(&keeper, &id), 1_000);
+    assert_eq!(token.balance(&merchant), 995);
+    assert_eq!(token.balance(&keeper), 5);
+    assert_eq!(token.balance(&subscriber), 9_000);
+
+    client.set_plan_keeper_tip(&plan_id, &None);
+    advance_ledger(&env, MONTH);
+    client.charge(&keeper, &id);
+    assert_eq!(token.balance(&merchant), 1_995);
+    assert_eq!(token.balance(&keeper), 5);
+}
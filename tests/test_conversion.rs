@@ -1,7 +1,15 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, Bytes, BytesN, Env, TryFromVal,
+};
 
 use stellar_multisig_contract::{
-    conversion::{ConversionContractClient, ConversionStatus, Currency},
+    access::Role,
+    conversion::{
+        BalanceChangeKind, ConversionContractClient, ConversionEvent, ConversionStatus, Currency,
+        WithdrawalGuard,
+    },
+    testutils::{advance_ledger, create_token, fund},
     ConversionContract,
 };
 
@@ -31,11 +39,12 @@ fn setup_contract(
     );
 }
 
-fn setup_exchange_rates(_env: &Env, client: &ConversionContractClient<'static>, _admin: &Address) {
+fn setup_exchange_rates(_env: &Env, client: &ConversionContractClient<'static>, admin: &Address) {
     // Set up common exchange rates (scaled by 10^8 for precision)
 
     // USD to NGN: 1 USD = 800 NGN
     client.update_rate(
+        admin,
         &Currency::USD,
         &Currency::NGN,
         &80_000_000_000i128,
@@ -43,22 +52,53 @@ fn setup_exchange_rates(_env: &Env, client: &ConversionContractClient<'static>,
     );
 
     // NGN to USD: 1 NGN = 0.00125 USD
-    client.update_rate(&Currency::NGN, &Currency::USD, &125_000i128, &3600u64);
+    client.update_rate(
+        admin,
+        &Currency::NGN,
+        &Currency::USD,
+        &125_000i128,
+        &3600u64,
+    );
 
     // USD to EUR: 1 USD = 0.85 EUR
-    client.update_rate(&Currency::USD, &Currency::EUR, &85_000_000i128, &3600u64);
+    client.update_rate(
+        admin,
+        &Currency::USD,
+        &Currency::EUR,
+        &85_000_000i128,
+        &3600u64,
+    );
 
     // EUR to USD: 1 EUR = 1.176 USD
-    client.update_rate(&Currency::EUR, &Currency::USD, &117_600_000i128, &3600u64);
+    client.update_rate(
+        admin,
+        &Currency::EUR,
+        &Currency::USD,
+        &117_600_000i128,
+        &3600u64,
+    );
 
     // GBP to USD: 1 GBP = 1.25 USD
-    client.update_rate(&Currency::GBP, &Currency::USD, &125_000_000i128, &3600u64);
+    client.update_rate(
+        admin,
+        &Currency::GBP,
+        &Currency::USD,
+        &125_000_000i128,
+        &3600u64,
+    );
 
     // USD to GBP: 1 USD = 0.8 GBP
-    client.update_rate(&Currency::USD, &Currency::GBP, &80_000_000i128, &3600u64);
+    client.update_rate(
+        admin,
+        &Currency::USD,
+        &Currency::GBP,
+        &80_000_000i128,
+        &3600u64,
+    );
 
     // BTC to USD: 1 BTC = 50,000 USD (for testing)
     client.update_rate(
+        admin,
         &Currency::BTC,
         &Currency::USD,
         &5_000_000_000_000i128,
@@ -67,6 +107,7 @@ fn setup_exchange_rates(_env: &Env, client: &ConversionContractClient<'static>,
 
     // ETH to USD: 1 ETH = 3,000 USD (for testing)
     client.update_rate(
+        admin,
         &Currency::ETH,
         &Currency::USD,
         &300_000_000_000i128,
@@ -445,3 +486,609 @@ fn test_zero_balance_conversion_failure() {
     // Fund with zero balance - this should fail with InvalidAmount
     client.deposit(&user, &Currency::USD, &0i128);
 }
+
+#[test]
+fn test_rate_feeder_role_updates_rates() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+
+    let feeder = Address::generate(&env);
+    assert!(client
+        .try_update_rate(
+            &feeder,
+            &Currency::USD,
+            &Currency::NGN,
+            &80_000_000_000i128,
+            &3600u64
+        )
+        .is_err());
+
+    client.grant_conversion_role(&admin, &Role::RateFeeder, &feeder);
+    client.update_rate(
+        &feeder,
+        &Currency::USD,
+        &Currency::NGN,
+        &80_000_000_000i128,
+        &3600u64,
+    );
+    assert_eq!(
+        client.get_rate(&Currency::USD, &Currency::NGN).rate,
+        80_000_000_000i128
+    );
+}
+
+#[test]
+fn test_removed_rate_can_no_longer_be_used() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    let feeder = Address::generate(&env);
+    client.grant_conversion_role(&admin, &Role::RateFeeder, &feeder);
+    assert!(client
+        .try_remove_rate(&feeder, &Currency::USD, &Currency::NGN)
+        .is_err());
+
+    client.remove_rate(&admin, &Currency::USD, &Currency::NGN);
+    assert!(client.try_get_rate(&Currency::USD, &Currency::NGN).is_err());
+    assert!(client
+        .try_convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128)
+        .is_err());
+    // Nothing left to remove
+    assert!(client
+        .try_remove_rate(&admin, &Currency::USD, &Currency::NGN)
+        .is_err());
+
+    // Other pairs are untouched
+    client.convert_currency(&user, &Currency::USD, &Currency::EUR, &10_000i128);
+}
+
+#[test]
+fn test_suspended_pair_rejects_conversions_until_resumed() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    let pauser = Address::generate(&env);
+    assert!(client
+        .try_suspend_pair(&pauser, &Currency::USD, &Currency::NGN)
+        .is_err());
+    client.grant_conversion_role(&admin, &Role::Pauser, &pauser);
+    client.suspend_pair(&pauser, &Currency::USD, &Currency::NGN);
+    assert!(client.is_pair_suspended(&Currency::USD, &Currency::NGN));
+    assert!(!client.is_pair_suspended(&Currency::NGN, &Currency::USD));
+
+    assert!(client
+        .try_conversion_rate(&Currency::USD, &Currency::NGN)
+        .is_err());
+    assert!(client
+        .try_convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128)
+        .is_err());
+    // The reverse direction is a separate pair
+    client.convert_currency(&user, &Currency::NGN, &Currency::USD, &100_000i128);
+
+    client.resume_pair(&pauser, &Currency::USD, &Currency::NGN);
+    assert!(client
+        .try_resume_pair(&pauser, &Currency::USD, &Currency::NGN)
+        .is_err());
+    let tx = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+    assert_eq!(tx.status, ConversionStatus::Completed);
+}
+
+#[test]
+fn test_registered_decimals_normalize_conversion_math() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    client.initialize_conversion(
+        &admin,
+        &50u32, // 0.5% fee
+        &fee_collector,
+        &100i128,
+        &2_000_000_000_000_000_000i128, // max amount: 2 ETH in wei
+    );
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    assert!(client
+        .try_set_currency_decimals(&user, &Currency::ETH, &18)
+        .is_err());
+    assert!(client
+        .try_set_currency_decimals(&admin, &Currency::ETH, &19)
+        .is_err());
+    client.set_currency_decimals(&admin, &Currency::ETH, &18);
+    client.set_currency_decimals(&admin, &Currency::USD, &2);
+    assert_eq!(client.get_currency_decimals(&Currency::ETH), Some(18));
+    assert_eq!(client.get_currency_decimals(&Currency::NGN), None);
+
+    // 1 ETH at $3,000 is 300,000 cents, less the 0.5% fee
+    let conversion = client.convert_currency(
+        &user,
+        &Currency::ETH,
+        &Currency::USD,
+        &1_000_000_000_000_000_000i128,
+    );
+    assert_eq!(conversion.platform_fee, 1_500);
+    assert_eq!(conversion.amount_received, 298_500);
+
+    // ₦4,000 in kobo is $5.00 in cents once NGN is registered too
+    client.set_currency_decimals(&admin, &Currency::NGN, &2);
+    let conversion = client.convert_currency(&user, &Currency::NGN, &Currency::USD, &400_000i128);
+    assert_eq!(conversion.amount_received, 500 - 2);
+}
+
+#[test]
+fn test_dust_outputs_rejected_and_leftovers_swept() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    assert!(client
+        .try_set_dust_threshold(&user, &Currency::USD, &Some(100))
+        .is_err());
+    assert!(client
+        .try_set_dust_threshold(&admin, &Currency::USD, &Some(-1))
+        .is_err());
+    client.set_dust_threshold(&admin, &Currency::USD, &Some(100));
+    client.set_dust_threshold(&admin, &Currency::NGN, &Some(1_000));
+    assert_eq!(client.get_dust_threshold(&Currency::USD), 100);
+    assert_eq!(client.get_dust_threshold(&Currency::EUR), 0);
+
+    // ₦500 in kobo buys only 62 cents, below the USD threshold
+    assert!(client
+        .try_convert_currency(&user, &Currency::NGN, &Currency::USD, &50_000i128)
+        .is_err());
+
+    // Converting all but 500 kobo sweeps the leftover to the fee collector
+    let conversion = client.convert_currency(&user, &Currency::NGN, &Currency::USD, &999_500i128);
+    assert_eq!(conversion.amount, 999_500);
+    let balances = client.get_user_balance(&user).balances;
+    assert_eq!(balances.get(Currency::NGN), Some(0));
+    assert_eq!(
+        client
+            .get_user_balance(&fee_collector)
+            .balances
+            .get(Currency::NGN),
+        Some(500)
+    );
+
+    client.set_dust_threshold(&admin, &Currency::USD, &None);
+    assert_eq!(client.get_dust_threshold(&Currency::USD), 0);
+}
+
+#[test]
+fn test_pair_spread_applied_below_mid_rate() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    let risk = Address::generate(&env);
+    assert!(client
+        .try_set_pair_spread(&risk, &Currency::USD, &Currency::NGN, &Some(100))
+        .is_err());
+    client.grant_conversion_role(&admin, &Role::Operator, &risk);
+    assert!(client
+        .try_set_pair_spread(&risk, &Currency::USD, &Currency::NGN, &Some(1_001))
+        .is_err());
+    client.set_pair_spread(&risk, &Currency::USD, &Currency::NGN, &Some(100));
+    assert_eq!(client.get_pair_spread(&Currency::USD, &Currency::NGN), 100);
+    assert_eq!(client.get_pair_spread(&Currency::NGN, &Currency::USD), 0);
+
+    // $100 at 800 less 1% is ₦79,200, then the 0.5% fee
+    let conversion = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+    assert_eq!(conversion.rate, 79_200_000_000);
+    assert_eq!(conversion.platform_fee, 39_600);
+    assert_eq!(conversion.amount_received, 7_880_400);
+    // With no pool to pay it to, the ₦800 spread goes to the fee collector
+    assert_eq!(
+        client
+            .get_user_balance(&fee_collector)
+            .balances
+            .get(Currency::NGN),
+        Some(80_000)
+    );
+
+    client.set_pair_spread(&risk, &Currency::USD, &Currency::NGN, &None);
+    let conversion = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+    assert_eq!(conversion.rate, 80_000_000_000);
+}
+
+#[test]
+fn test_idempotent_conversion_replays_original_tx() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+    let usd_before = client
+        .get_user_balance(&user)
+        .balances
+        .get(Currency::USD)
+        .unwrap();
+
+    let key = BytesN::from_array(&env, &[7; 32]);
+    let first =
+        client.convert_currency_idempotent(&user, &Currency::USD, &Currency::NGN, &10_000, &key);
+    let retry =
+        client.convert_currency_idempotent(&user, &Currency::USD, &Currency::NGN, &10_000, &key);
+    assert_eq!(retry.tx_id, first.tx_id);
+    assert_eq!(retry.amount_received, first.amount_received);
+    assert_eq!(
+        client.get_user_balance(&user).balances.get(Currency::USD),
+        Some(usd_before - 10_000)
+    );
+
+    // A fresh key is a new conversion
+    let other = BytesN::from_array(&env, &[8; 32]);
+    let second =
+        client.convert_currency_idempotent(&user, &Currency::USD, &Currency::NGN, &10_000, &other);
+    assert_ne!(second.tx_id, first.tx_id);
+    assert_eq!(
+        client.get_user_balance(&user).balances.get(Currency::USD),
+        Some(usd_before - 20_000)
+    );
+}
+
+#[test]
+fn test_balance_history_journals_deposits_and_conversions() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    let conversion = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000i128);
+
+    let usd = client.get_balance_history(&user, &Currency::USD, &0, &10);
+    assert_eq!(usd.len(), 2);
+    assert_eq!(client.get_balance_history_len(&user, &Currency::USD), 2);
+    let deposit = usd.get_unchecked(0);
+    assert_eq!(deposit.kind, BalanceChangeKind::Deposit);
+    assert_eq!(deposit.amount, 100_000);
+    assert_eq!(deposit.balance, 100_000);
+    assert_eq!(deposit.tx_id, None);
+    let spent = usd.get_unchecked(1);
+    assert_eq!(spent.kind, BalanceChangeKind::ConversionOut);
+    assert_eq!(spent.amount, -10_000);
+    assert_eq!(spent.balance, 90_000);
+    assert_eq!(spent.tx_id, Some(conversion.tx_id.clone()));
+
+    let ngn = client.get_balance_history(&user, &Currency::NGN, &1, &10);
+    assert_eq!(ngn.len(), 1);
+    let received = ngn.get_unchecked(0);
+    assert_eq!(received.kind, BalanceChangeKind::ConversionIn);
+    assert_eq!(received.amount, 7_960_000);
+    assert_eq!(received.balance, 8_960_000);
+    assert_eq!(received.tx_id, Some(conversion.tx_id));
+
+    assert!(client
+        .get_balance_history(&Address::generate(&env), &Currency::USD, &0, &10)
+        .is_empty());
+}
+
+#[test]
+fn test_guarded_withdrawals_cool_off_and_can_be_cancelled() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    let user = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    client.deposit(&user, &Currency::USD, &100_000i128);
+    let usd = || client.get_user_balance(&user).balances.get(Currency::USD);
+
+    assert_eq!(client.withdraw(&user, &Currency::USD, &10_000), None);
+    assert_eq!(usd(), Some(90_000));
+
+    let guard = WithdrawalGuard {
+        threshold: 5_000,
+        delay: 3600,
+        guardian: Some(guardian.clone()),
+    };
+    assert!(client
+        .try_set_withdrawal_guard(
+            &user,
+            &Some(WithdrawalGuard {
+                delay: 0,
+                ..guard.clone()
+            })
+        )
+        .is_err());
+    client.set_withdrawal_guard(&user, &Some(guard.clone()));
+    assert_eq!(client.get_withdrawal_guard(&user), Some(guard));
+
+    // Small withdrawals still go straight through
+    assert_eq!(client.withdraw(&user, &Currency::USD, &1_000), None);
+    let queued = client.withdraw(&user, &Currency::USD, &20_000).unwrap();
+    assert_eq!(usd(), Some(69_000));
+    let pending = client.get_pending_withdrawal(&queued).unwrap();
+    assert_eq!(pending.amount, 20_000);
+    assert!(client.try_execute_withdrawal(&queued).is_err());
+
+    assert!(client
+        .try_cancel_withdrawal(&Address::generate(&env), &queued)
+        .is_err());
+    client.cancel_withdrawal(&guardian, &queued);
+    assert_eq!(usd(), Some(89_000));
+    assert_eq!(client.get_pending_withdrawal(&queued), None);
+
+    let queued = client.withdraw(&user, &Currency::USD, &20_000).unwrap();
+    advance_ledger(&env, 3600);
+    client.execute_withdrawal(&queued);
+    assert_eq!(client.get_pending_withdrawal(&queued), None);
+    assert!(client.try_cancel_withdrawal(&user, &queued).is_err());
+    assert_eq!(usd(), Some(69_000));
+
+    // Dropping the guard waits out the guard's own delay
+    client.set_withdrawal_guard(&user, &None);
+    assert!(client.get_withdrawal_guard(&user).is_some());
+    assert!(client.withdraw(&user, &Currency::USD, &10_000).is_some());
+    advance_ledger(&env, 3600);
+    assert_eq!(client.get_withdrawal_guard(&user), None);
+    assert_eq!(client.withdraw(&user, &Currency::USD, &10_000), None);
+}
+
+#[test]
+fn test_promo_code_waives_conversion_fee() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    let full = client.convert_currency(&user, &Currency::USD, &Currency::NGN, &10_000);
+    assert!(full.platform_fee > 0);
+
+    let quarter = Bytes::from_slice(&env, b"QUARTER");
+    let quarter_hash: BytesN<32> = env.crypto().sha256(&quarter).into();
+    let free = Bytes::from_slice(&env, b"FREEFX");
+    let free_hash: BytesN<32> = env.crypto().sha256(&free).into();
+    assert!(client
+        .try_issue_promo_code(&user, &free_hash, &10_000, &1, &3600)
+        .is_err());
+    client.issue_promo_code(&admin, &quarter_hash, &2_500, &5, &60);
+    client.issue_promo_code(&admin, &free_hash, &10_000, &1, &3600);
+    assert!(client
+        .try_issue_promo_code(&admin, &free_hash, &10_000, &1, &3600)
+        .is_err());
+
+    let discounted = client.convert_currency_with_promo(
+        &user,
+        &Currency::USD,
+        &Currency::NGN,
+        &10_000,
+        &quarter,
+    );
+    assert_eq!(
+        discounted.platform_fee,
+        full.platform_fee - full.platform_fee / 4
+    );
+    let waived =
+        client.convert_currency_with_promo(&user, &Currency::USD, &Currency::NGN, &10_000, &free);
+    assert_eq!(waived.platform_fee, 0);
+    assert_eq!(
+        waived.amount_received,
+        full.amount_received + full.platform_fee
+    );
+    assert_eq!(client.get_promo_code(&free_hash).unwrap().uses, 1);
+
+    // Used up, expired, revoked or unknown codes are refused
+    assert!(client
+        .try_convert_currency_with_promo(&user, &Currency::USD, &Currency::NGN, &10_000, &free)
+        .is_err());
+    advance_ledger(&env, 61);
+    assert!(client
+        .try_convert_currency_with_promo(&user, &Currency::USD, &Currency::NGN, &10_000, &quarter)
+        .is_err());
+    client.revoke_promo_code(&admin, &quarter_hash);
+    assert_eq!(client.get_promo_code(&quarter_hash), None);
+    let unknown = Bytes::from_slice(&env, b"NOPE");
+    assert!(client
+        .try_convert_currency_with_promo(&user, &Currency::USD, &Currency::NGN, &10_000, &unknown)
+        .is_err());
+}
+
+#[test]
+fn test_payment_reference_carried_on_conversion_events() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+    let last_event = || {
+        let (_, _, data) = env.events().all().last().unwrap();
+        <(ConversionEvent, BytesN<32>)>::try_from_val(&env, &data).unwrap()
+    };
+
+    let reference = BytesN::from_array(&env, &[4; 32]);
+    let tx = client.convert_currency_with_reference(
+        &user,
+        &Currency::USD,
+        &Currency::NGN,
+        &10_000,
+        &reference,
+    );
+    let (event, carried) = last_event();
+    assert!(matches!(event, ConversionEvent::ConversionCompleted(id, ..) if id == tx.tx_id));
+    assert_eq!(carried, reference);
+
+    client.withdraw(&user, &Currency::USD, &1_000);
+    let (event, carried) = last_event();
+    assert!(matches!(event, ConversionEvent::Withdrawn(..)));
+    assert_eq!(carried, BytesN::from_array(&env, &[0; 32]));
+
+    let payout = BytesN::from_array(&env, &[5; 32]);
+    client.withdraw_with_reference(&user, &Currency::USD, &1_000, &payout);
+    assert_eq!(last_event().1, payout);
+}
+
+#[test]
+fn test_external_payout_sends_backing_token() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    let user = Address::generate(&env);
+    let wallet = Address::generate(&env);
+    client.deposit(&user, &Currency::USD, &100_000i128);
+
+    assert!(client
+        .try_payout_external(&user, &Currency::USD, &1_000, &wallet)
+        .is_err());
+    let usdc = create_token(&env);
+    client.set_backing_token(&admin, &Currency::USD, &Some(usdc.address.clone()));
+    fund(&env, &usdc.address, &client.address, 1_000_000);
+
+    assert_eq!(
+        client.payout_external(&user, &Currency::USD, &30_000, &wallet),
+        None
+    );
+    assert_eq!(usdc.balance(&wallet), 30_000);
+    assert_eq!(
+        client.get_user_balance(&user).balances.get(Currency::USD),
+        Some(70_000)
+    );
+    assert!(client
+        .try_payout_external(&user, &Currency::USD, &70_001, &wallet)
+        .is_err());
+
+    // Guarded payouts are sent when the withdrawal is released
+    client.set_withdrawal_guard(
+        &user,
+        &Some(WithdrawalGuard {
+            threshold: 5_000,
+            delay: 3600,
+            guardian: None,
+        }),
+    );
+    let queued = client
+        .payout_external(&user, &Currency::USD, &20_000, &wallet)
+        .unwrap();
+    assert_eq!(
+        client.get_pending_withdrawal(&queued).unwrap().destination,
+        Some(wallet.clone())
+    );
+    assert_eq!(usdc.balance(&wallet), 30_000);
+    advance_ledger(&env, 3600);
+    client.execute_withdrawal(&queued);
+    assert_eq!(usdc.balance(&wallet), 50_000);
+    assert_eq!(usdc.balance(&client.address), 950_000);
+}
+
+#[test]
+fn test_config_rolls_back_to_snapshot() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    let outsider = Address::generate(&env);
+
+    assert!(client.try_snapshot_conversion_config(&outsider).is_err());
+    let version = client.snapshot_conversion_config(&admin);
+    assert_eq!(version, 1);
+    let info = client.get_conversion_config_snapshot(&version).unwrap();
+    assert_eq!(info.taken_by, admin);
+
+    // A bad push: fees sent to the wrong collector, limits far too tight
+    assert!(client
+        .try_update_conversion_config(&admin, &5_000, &outsider, &100, &1_000)
+        .is_err());
+    client.update_conversion_config(&admin, &100, &outsider, &100, &1_000);
+    assert_eq!(client.get_conversion_config().fee_collector, outsider);
+
+    assert!(client.try_rollback_conversion_config(&admin, &2).is_err());
+    let config = client.rollback_conversion_config(&admin, &version);
+    assert_eq!(config.fee_bps, 50);
+    assert_eq!(config.fee_collector, fee_collector);
+    assert_eq!(config.max_conversion_amount, 1_000_000_000);
+    assert_eq!(client.get_conversion_config().fee_collector, fee_collector);
+    assert_eq!(client.snapshot_conversion_config(&admin), 2);
+}
+
+#[test]
+fn test_limit_order_fills_in_parts_once_rate_is_reached() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    // Convert NGN to USD once 1 NGN buys at least 0.0013 USD
+    let order = client.place_limit_order(
+        &user,
+        &Currency::NGN,
+        &Currency::USD,
+        &600_000i128,
+        &130_000i128,
+    );
+    assert!(client
+        .try_execute_limit_order(&order.id, &400_000i128)
+        .is_err());
+
+    client.update_rate(
+        &admin,
+        &Currency::NGN,
+        &Currency::USD,
+        &140_000i128,
+        &3600u64,
+    );
+    let tx = client.execute_limit_order(&order.id, &400_000i128);
+    assert_eq!(tx.user, user);
+    assert_eq!(tx.amount, 400_000);
+    assert_eq!(
+        client.get_limit_order(&order.id).unwrap().remaining,
+        200_000
+    );
+    let balances = client.get_user_balance(&user).balances;
+    assert_eq!(balances.get(Currency::NGN).unwrap(), 600_000);
+
+    let stranger = Address::generate(&env);
+    assert!(client.try_cancel_limit_order(&stranger, &order.id).is_err());
+    client.cancel_limit_order(&user, &order.id);
+    assert_eq!(client.get_limit_order(&order.id), None);
+    assert!(client
+        .try_execute_limit_order(&order.id, &200_000i128)
+        .is_err());
+}
+
+#[test]
+fn test_limit_order_fill_takes_a_remainder_below_the_minimum() {
+    let (env, client, admin, fee_collector) = create_test_env();
+    env.mock_all_auths();
+    setup_contract(&env, &client, &admin, &fee_collector);
+    setup_exchange_rates(&env, &client, &admin);
+    let user = Address::generate(&env);
+    fund_user_account(&env, &client, &admin, &user);
+
+    // An order below the minimum could never fill
+    assert!(client
+        .try_place_limit_order(&user, &Currency::NGN, &Currency::USD, &99i128, &100_000i128)
+        .is_err());
+    let order = client.place_limit_order(
+        &user,
+        &Currency::NGN,
+        &Currency::USD,
+        &600_000i128,
+        &100_000i128,
+    );
+
+    // Leaving 50 would strand it under the 100 minimum, so the fill takes it
+    let tx = client.execute_limit_order(&order.id, &599_950i128);
+    assert_eq!(tx.amount, 600_000);
+    assert_eq!(client.get_limit_order(&order.id), None);
+}
@@ -0,0 +1,161 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, Env, IntoVal, Symbol};
+use stellar_multisig_contract::escrow::{EscrowClient, EscrowContract};
+use stellar_multisig_contract::testutils::{advance_ledger, create_token, fund, funded_user};
+use stellar_multisig_contract::timelock::{
+    TimelockContract, TimelockContractClient, TimelockStatus,
+};
+
+fn setup_timelock(env: &Env) -> (TimelockContractClient<'_>, EscrowClient<'_>) {
+    let timelock_id = env.register(TimelockContract, ());
+    let timelock = TimelockContractClient::new(env, &timelock_id);
+    timelock.initialize_timelock(&Address::generate(env), &3_600);
+
+    // The escrow's admin actions can only be performed through the timelock
+    let escrow_id = env.register(EscrowContract, ());
+    let escrow = EscrowClient::new(env, &escrow_id);
+    escrow.initialize(&timelock_id);
+
+    (timelock, escrow)
+}
+
+#[test]
+fn test_timelocked_admin_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (timelock, escrow) = setup_timelock(&env);
+
+    let operation_id = timelock.queue(
+        &escrow.address,
+        &Symbol::new(&env, "set_dispute_fee"),
+        &vec![
+            &env,
+            timelock.address.into_val(&env),
+            250i128.into_val(&env),
+        ],
+        &3_600,
+    );
+    assert!(timelock.try_execute(&operation_id).is_err());
+
+    // Execution needs no signatures: the escrow sees the call as coming from its admin
+    env.set_auths(&[]);
+    advance_ledger(&env, 3_600);
+    timelock.execute(&operation_id);
+
+    assert_eq!(escrow.get_dispute_fee(), 250);
+    assert_eq!(
+        timelock.get_operation(&operation_id).status,
+        TimelockStatus::Executed
+    );
+}
+
+#[test]
+#[should_panic(expected = "Delay below minimum for target")]
+fn test_timelock_per_target_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (timelock, escrow) = setup_timelock(&env);
+
+    timelock.set_target_delay(&escrow.address, &86_400);
+    timelock.queue(
+        &escrow.address,
+        &Symbol::new(&env, "set_paused"),
+        &vec![&env, timelock.address.into_val(&env), true.into_val(&env)],
+        &3_600,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Operation is not queued")]
+fn test_cancelled_operation_cannot_execute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (timelock, escrow) = setup_timelock(&env);
+
+    let operation_id = timelock.queue(
+        &escrow.address,
+        &Symbol::new(&env, "set_paused"),
+        &vec![&env, timelock.address.into_val(&env), true.into_val(&env)],
+        &3_600,
+    );
+    timelock.cancel(&operation_id);
+
+    advance_ledger(&env, 3_600);
+    timelock.execute(&operation_id);
+}
+
+#[test]
+fn test_stray_tokens_rescued_through_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (timelock, escrow) = setup_timelock(&env);
+    let token = create_token(&env);
+    let treasury = Address::generate(&env);
+
+    // Sent straight to the contract, outside any escrow
+    fund(&env, &token.address, &escrow.address, 1_000);
+    assert!(escrow
+        .try_rescue_escrow_tokens(&token.address, &1_000, &treasury)
+        .is_err());
+    escrow.set_escrow_rescue_timelock(&timelock.address, &Some(timelock.address.clone()));
+    assert_eq!(
+        escrow.get_escrow_rescue_timelock(),
+        Some(timelock.address.clone())
+    );
+
+    // Nobody else can move the funds
+    env.set_auths(&[]);
+    assert!(escrow
+        .try_rescue_escrow_tokens(&token.address, &1_000, &treasury)
+        .is_err());
+
+    env.mock_all_auths();
+    let operation_id = timelock.queue(
+        &escrow.address,
+        &Symbol::new(&env, "rescue_escrow_tokens"),
+        &vec![
+            &env,
+            token.address.into_val(&env),
+            1_000i128.into_val(&env),
+            treasury.into_val(&env),
+        ],
+        &3_600,
+    );
+    env.set_auths(&[]);
+    advance_ledger(&env, 3_600);
+    timelock.execute(&operation_id);
+
+    assert_eq!(token.balance(&treasury), 1_000);
+    assert_eq!(token.balance(&escrow.address), 0);
+}
+
+#[test]
+fn test_rescue_cannot_take_escrowed_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (timelock, escrow) = setup_timelock(&env);
+    let token = create_token(&env);
+    let treasury = Address::generate(&env);
+    escrow.set_escrow_rescue_timelock(&timelock.address, &Some(timelock.address.clone()));
+
+    let sender = funded_user(&env, &token.address, 1_000);
+    escrow.create(
+        &sender,
+        &Address::generate(&env),
+        &token.address,
+        &1_000,
+        &86_400,
+        &3_600,
+    );
+    fund(&env, &token.address, &escrow.address, 100);
+
+    // Only the stray 100 is not owed to the escrow's parties
+    assert!(escrow
+        .try_rescue_escrow_tokens(&token.address, &101, &treasury)
+        .is_err());
+    escrow.rescue_escrow_tokens(&token.address, &100, &treasury);
+    assert_eq!(token.balance(&treasury), 100);
+    assert_eq!(token.balance(&escrow.address), 1_000);
+}
@@ -0,0 +1,410 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events},
+    vec,
+    xdr::FromXdr,
+    Address, Bytes, BytesN, Env, String, TryFromVal,
+};
+use stellar_multisig_contract::alias::{hash_alias, Alias, AliasKind};
+use stellar_multisig_contract::email_to_wallet::{
+    EmailToWalletContract, EmailToWalletContractClient,
+};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, TokenEvent};
+use stellar_multisig_contract::testutils::set_ledger_time;
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient, TokenMetadata};
+
+fn setup_token(env: &Env) -> (TokenContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let contract_id = env.register(TokenContract, ());
+    let client = TokenContractClient::new(env, &contract_id);
+    client.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    (client, admin)
+}
+
+#[test]
+fn test_mint_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.mint_batch(
+        &admin,
+        &vec![&env, (alice.clone(), 1_000i128), (bob.clone(), 2_500i128)],
+    );
+
+    assert_eq!(client.balance(&alice), 1_000);
+    assert_eq!(client.balance(&bob), 2_500);
+}
+
+#[test]
+#[should_panic(expected = "Only admin can mint")]
+fn test_mint_batch_non_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_token(&env);
+
+    let attacker = Address::generate(&env);
+    client.mint_batch(&attacker, &vec![&env, (attacker.clone(), 1_000i128)]);
+}
+
+#[test]
+fn test_transfer_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let employer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&admin, &employer, &10_000);
+
+    client.transfer_batch(
+        &employer,
+        &vec![&env, (alice.clone(), 3_000i128), (bob.clone(), 4_000i128)],
+    );
+
+    assert_eq!(client.balance(&employer), 3_000);
+    assert_eq!(client.balance(&alice), 3_000);
+    assert_eq!(client.balance(&bob), 4_000);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_transfer_batch_insufficient_total() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let employer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&admin, &employer, &5_000);
+
+    // Each leg is individually affordable but the batch total is not
+    client.transfer_batch(
+        &employer,
+        &vec![&env, (alice.clone(), 3_000i128), (bob.clone(), 3_000i128)],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Amount must be positive")]
+fn test_transfer_batch_rejects_zero_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let employer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    client.mint(&admin, &employer, &5_000);
+
+    client.transfer_batch(&employer, &vec![&env, (alice.clone(), 0i128)]);
+}
+
+#[test]
+#[should_panic(expected = "Arithmetic overflow")]
+fn test_transfer_batch_rejects_overflowing_total() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let employer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&admin, &employer, &5_000);
+
+    // A wrapped total must not slip under the balance check
+    client.transfer_batch(
+        &employer,
+        &vec![&env, (alice.clone(), i128::MAX), (bob.clone(), 2i128)],
+    );
+}
+
+fn account_address(env: &Env, public_key: &[u8; 32]) -> Address {
+    // ScVal::Address(ScAddress::Account(PublicKey::Ed25519(key)))
+    let mut xdr = [0u8; 44];
+    xdr[3] = 18;
+    xdr[12..44].copy_from_slice(public_key);
+    Address::from_xdr(env, &Bytes::from_slice(env, &xdr)).unwrap()
+}
+
+fn sign_permit(
+    client: &TokenContractClient,
+    signing_key: &SigningKey,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    deadline: u64,
+    nonce: u64,
+) -> BytesN<64> {
+    let digest = client.permit_payload(owner, spender, &amount, &deadline, &nonce);
+    let signature = signing_key.sign(&digest.to_array());
+    BytesN::from_array(owner.env(), &signature.to_bytes())
+}
+
+#[test]
+fn test_permit_sets_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let owner = account_address(&env, &signing_key.verifying_key().to_bytes());
+    let spender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.mint(&admin, &owner, &5_000);
+
+    let signature = sign_permit(&client, &signing_key, &owner, &spender, 2_000, 100, 1);
+    client.permit(&owner, &spender, &2_000, &100, &1, &signature);
+
+    assert_eq!(client.allowance(&owner, &spender), 2_000);
+    assert_eq!(client.get_permit_nonce(&owner), 1);
+
+    client.transfer_from(&spender, &owner, &recipient, &1_500);
+    assert_eq!(client.balance(&recipient), 1_500);
+    assert_eq!(client.allowance(&owner, &spender), 500);
+}
+
+#[test]
+#[should_panic(expected = "Invalid permit nonce")]
+fn test_permit_replay_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_token(&env);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let owner = account_address(&env, &signing_key.verifying_key().to_bytes());
+    let spender = Address::generate(&env);
+
+    let signature = sign_permit(&client, &signing_key, &owner, &spender, 2_000, 100, 1);
+    client.permit(&owner, &spender, &2_000, &100, &1, &signature);
+    client.permit(&owner, &spender, &2_000, &100, &1, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Permit expired")]
+fn test_permit_expired() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_token(&env);
+    set_ledger_time(&env, 500);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let owner = account_address(&env, &signing_key.verifying_key().to_bytes());
+    let spender = Address::generate(&env);
+
+    let signature = sign_permit(&client, &signing_key, &owner, &spender, 2_000, 100, 1);
+    client.permit(&owner, &spender, &2_000, &100, &1, &signature);
+}
+
+#[test]
+#[should_panic]
+fn test_permit_wrong_signer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_token(&env);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let attacker_key = SigningKey::from_bytes(&[9u8; 32]);
+    let owner = account_address(&env, &signing_key.verifying_key().to_bytes());
+    let spender = Address::generate(&env);
+
+    let signature = sign_permit(&client, &attacker_key, &owner, &spender, 2_000, 100, 1);
+    client.permit(&owner, &spender, &2_000, &100, &1, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient allowance")]
+fn test_transfer_from_without_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&admin, &owner, &5_000);
+    client.approve(&owner, &spender, &100);
+
+    client.transfer_from(&spender, &owner, &spender, &200);
+}
+
+#[test]
+fn test_balance_at_snapshots() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&admin, &alice, &1_000);
+
+    let first = client.snapshot(&admin);
+    client.transfer(&alice, &bob, &400);
+
+    let second = client.snapshot(&admin);
+    let third = client.snapshot(&admin);
+    client.mint(&admin, &bob, &100);
+    client.transfer(&bob, &alice, &50);
+
+    assert_eq!(client.balance_at(&alice, &first), 1_000);
+    assert_eq!(client.balance_at(&bob, &first), 0);
+    assert_eq!(client.balance_at(&alice, &second), 600);
+    assert_eq!(client.balance_at(&bob, &second), 400);
+    assert_eq!(client.balance_at(&bob, &third), 400);
+
+    // Accounts untouched since a snapshot report their live balance
+    assert_eq!(client.balance(&alice), 650);
+    assert_eq!(client.balance(&bob), 450);
+    let fourth = client.snapshot(&admin);
+    assert_eq!(client.balance_at(&alice, &fourth), 650);
+}
+
+#[test]
+#[should_panic(expected = "Invalid snapshot id")]
+fn test_balance_at_future_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let snapshot_id = client.snapshot(&admin);
+    client.balance_at(&admin, &(snapshot_id + 1));
+}
+
+#[test]
+#[should_panic(expected = "Only admin can snapshot")]
+fn test_snapshot_non_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup_token(&env);
+
+    let attacker = Address::generate(&env);
+    client.snapshot(&attacker);
+}
+
+#[test]
+fn test_transfer_to_username_alias() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+
+    let registry_id = env.register(EmailToWalletContract, ());
+    let registry = EmailToWalletContractClient::new(&env, &registry_id);
+    registry.initialize_email_registry(&Address::generate(&env), &Address::generate(&env));
+
+    let username = String::from_str(&env, "ada_lovelace");
+    let username_hash = hash_alias(&env, AliasKind::Username, &username).unwrap();
+    let wallet = Address::generate(&env);
+    registry.register_email(&username_hash, &wallet);
+    registry.verify_email(&username_hash, &wallet);
+
+    client.set_token_alias_registry(&admin, &registry_id);
+
+    let sender = Address::generate(&env);
+    client.mint(&admin, &sender, &1_000);
+    let alias = Alias {
+        kind: AliasKind::Username,
+        value: username,
+    };
+    let to = client.transfer_to_alias(&sender, &alias, &400);
+
+    assert_eq!(to, wallet);
+    assert_eq!(client.balance(&wallet), 400);
+    assert_eq!(client.balance(&sender), 600);
+}
+
+#[test]
+fn test_admin_publishes_asset_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup_token(&env);
+    assert_eq!(client.get_token_metadata(), None);
+
+    let metadata = TokenMetadata {
+        uri: String::from_str(&env, "ipfs://bafy-reserve-attestation-2026-09"),
+        hash: BytesN::from_array(&env, &[7; 32]),
+    };
+    assert!(client
+        .try_set_token_metadata(&Address::generate(&env), &Some(metadata.clone()))
+        .is_err());
+    assert!(client
+        .try_set_token_metadata(
+            &admin,
+            &Some(TokenMetadata {
+                uri: String::from_str(&env, ""),
+                hash: metadata.hash.clone(),
+            })
+        )
+        .is_err());
+
+    set_ledger_time(&env, 500);
+    client.set_token_metadata(&admin, &Some(metadata.clone()));
+    assert_eq!(client.get_token_metadata(), Some(metadata.clone()));
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Token(TokenEvent::MetadataUpdated(data)) => {
+            assert_eq!(data.token, client.address);
+            assert_eq!(data.metadata, Some(metadata));
+            assert_eq!(data.updated_at, 500);
+        }
+        _ => panic!("expected TokenEvent::MetadataUpdated"),
+    }
+
+    client.set_token_metadata(&admin, &None);
+    assert_eq!(client.get_token_metadata(), None);
+}
+
+#[test]
+fn test_locked_tokens_only_move_once_unlocked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    set_ledger_time(&env, 1_000);
+    let (client, admin) = setup_token(&env);
+    let (vesting, alice, bob) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    client.mint(&admin, &vesting, &10_000);
+
+    // Only registered lock sources can lock what they send
+    assert!(client
+        .try_transfer_locked(&vesting, &alice, &1_000, &2_000)
+        .is_err());
+    client.set_token_lock_source(&admin, &vesting, &true);
+    client.transfer_locked(&vesting, &alice, &1_000, &2_000);
+    client.transfer_locked(&vesting, &alice, &500, &3_000);
+    client.transfer(&vesting, &alice, &200);
+
+    assert_eq!(client.balance(&alice), 1_700);
+    assert_eq!(client.get_locked_balance(&alice), 1_500);
+    assert_eq!(client.get_unlocked_balance(&alice), 200);
+    assert!(client.try_transfer(&alice, &bob, &201).is_err());
+    client.approve(&alice, &bob, &1_000);
+    assert!(client.try_transfer_from(&bob, &alice, &bob, &201).is_err());
+    assert!(client
+        .try_transfer_batch(&alice, &vec![&env, (bob.clone(), 201i128)])
+        .is_err());
+    client.transfer(&alice, &bob, &200);
+
+    set_ledger_time(&env, 2_000);
+    assert_eq!(client.get_unlocked_balance(&alice), 1_000);
+    client.transfer_from(&bob, &alice, &bob, &1_000);
+    assert_eq!(client.balance(&bob), 1_200);
+    assert!(client.try_transfer(&alice, &bob, &1).is_err());
+
+    // Unlocked entries are pruned by the next lock
+    client.transfer_locked(&vesting, &alice, &100, &4_000);
+    assert_eq!(
+        client.get_token_locks(&alice),
+        vec![&env, (3_000u64, 500i128), (4_000u64, 100i128)]
+    );
+    assert!(client
+        .try_transfer_locked(&vesting, &alice, &100, &2_000)
+        .is_err());
+}
@@ -0,0 +1,163 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{symbol_short, token, Address, Env, TryFromVal};
+use stellar_multisig_contract::event::{DeFiEvent, EventEnvelope, VestingEvent};
+use stellar_multisig_contract::testutils::{advance_ledger, create_token, fund};
+use stellar_multisig_contract::token::{TokenContract, TokenContractClient};
+use stellar_multisig_contract::vesting::{VestingContract, VestingContractClient, VestingTerms};
+
+fn setup_vesting(
+    env: &Env,
+) -> (
+    VestingContractClient<'_>,
+    Address,
+    Address,
+    token::Client<'_>,
+) {
+    let admin = Address::generate(env);
+    let beneficiary = Address::generate(env);
+
+    let token = create_token(env);
+    fund(env, &token.address, &admin, 1_000_000);
+
+    let client = VestingContractClient::new(env, &env.register(VestingContract, ()));
+    client.initialize_vesting(&admin);
+
+    (client, admin, beneficiary, token)
+}
+
+// One year with a three month cliff, starting now
+fn yearly_terms(env: &Env, revocable: bool) -> VestingTerms {
+    VestingTerms {
+        start_time: env.ledger().timestamp(),
+        cliff_duration: 90 * 86_400,
+        duration: 360 * 86_400,
+        revocable,
+        unlock_time: 0,
+    }
+}
+
+#[test]
+fn test_vesting_cliff_then_linear() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, beneficiary, token) = setup_vesting(&env);
+
+    let id = client.create_vesting_schedule(
+        &admin,
+        &beneficiary,
+        &token.address,
+        &360_000,
+        &yearly_terms(&env, false),
+    );
+    assert_eq!(token.balance(&client.address), 360_000);
+
+    advance_ledger(&env, 89 * 86_400);
+    assert_eq!(client.get_claimable_amount(&id), 0);
+    assert!(client.try_claim(&id).is_err());
+
+    advance_ledger(&env, 86_400);
+    assert_eq!(client.claim(&id), 90_000);
+    let (_, _, data) = env.events().all().last().unwrap();
+    match EventEnvelope::try_from_val(&env, &data).unwrap().event {
+        DeFiEvent::Vesting(VestingEvent::Claimed(data)) => {
+            assert_eq!(data.amount, 90_000);
+            assert_eq!(data.total_claimed, 90_000);
+        }
+        _ => panic!("expected VestingEvent::Claimed"),
+    }
+
+    advance_ledger(&env, 400 * 86_400);
+    assert_eq!(client.claim(&id), 270_000);
+    assert_eq!(token.balance(&beneficiary), 360_000);
+    assert_eq!(client.get_beneficiary_schedules(&beneficiary).len(), 1);
+    assert_eq!(client.get_vesting_schedules(&0, &10).len(), 1);
+}
+
+#[test]
+fn test_revoked_vesting_refunds_unvested() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, beneficiary, token) = setup_vesting(&env);
+
+    let id = client.create_vesting_schedule(
+        &admin,
+        &beneficiary,
+        &token.address,
+        &360_000,
+        &yearly_terms(&env, true),
+    );
+
+    advance_ledger(&env, 180 * 86_400);
+    assert_eq!(client.revoke_vesting(&admin, &id), 180_000);
+    assert_eq!(token.balance(&admin), 1_000_000 - 180_000);
+
+    // What vested before revocation stays claimable, nothing more accrues
+    advance_ledger(&env, 180 * 86_400);
+    assert_eq!(client.claim(&id), 180_000);
+    assert_eq!(client.get_vested_amount(&id), 180_000);
+}
+
+#[test]
+#[should_panic(expected = "Schedule is not revocable")]
+fn test_irrevocable_vesting_cannot_be_revoked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, beneficiary, token) = setup_vesting(&env);
+
+    let id = client.create_vesting_schedule(
+        &admin,
+        &beneficiary,
+        &token.address,
+        &360_000,
+        &yearly_terms(&env, false),
+    );
+    client.revoke_vesting(&admin, &id);
+}
+
+#[test]
+fn test_claimed_tokens_stay_locked_until_unlock_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+    client.initialize_vesting(&admin);
+
+    let token = TokenContractClient::new(&env, &env.register(TokenContract, ()));
+    token.initialize_token(&admin, &symbol_short!("NGNX"), &symbol_short!("NGNX"), &7);
+    token.mint(&admin, &admin, &360_000);
+    token.set_token_lock_source(&admin, &client.address, &true);
+
+    let unlock_time = env.ledger().timestamp() + 360 * 86_400;
+    let id = client.create_vesting_schedule(
+        &admin,
+        &beneficiary,
+        &token.address,
+        &360_000,
+        &VestingTerms {
+            unlock_time,
+            ..yearly_terms(&env, false)
+        },
+    );
+    assert_eq!(client.get_vesting_unlock_time(&id), unlock_time);
+
+    // Vested and claimed, but not transferable before the unlock time
+    advance_ledger(&env, 180 * 86_400);
+    assert_eq!(client.claim(&id), 180_000);
+    assert_eq!(token.balance(&beneficiary), 180_000);
+    assert_eq!(token.get_unlocked_balance(&beneficiary), 0);
+    assert!(token.try_transfer(&beneficiary, &buyer, &1).is_err());
+    token.approve(&beneficiary, &buyer, &180_000);
+    assert!(token
+        .try_transfer_from(&buyer, &beneficiary, &buyer, &1)
+        .is_err());
+
+    advance_ledger(&env, 180 * 86_400);
+    assert_eq!(client.claim(&id), 180_000);
+    token.transfer(&beneficiary, &buyer, &300_000);
+    token.transfer_from(&buyer, &beneficiary, &buyer, &60_000);
+    assert_eq!(token.balance(&buyer), 360_000);
+}